@@ -0,0 +1,241 @@
+//! Predicates for narrowing a subscription to one agent, session or trace.
+
+use clawhive_schema::{BusMessage, SessionKey};
+use uuid::Uuid;
+
+/// Every field that is set must match. A message that does not carry the
+/// attribute at all (e.g. `ConsolidationCompleted` has no trace id) is
+/// filtered out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BusFilter {
+    pub agent_id: Option<String>,
+    /// `channel_type:connector_id:conversation_scope[:user_scope]`. Matches
+    /// when either key is a `:`-delimited prefix of the other, so
+    /// `telegram:tg_main` covers every conversation on that connector and a
+    /// full session key still matches replies, which carry no user scope.
+    pub session_key: Option<String>,
+    /// Also matches tool calls made by a delegated run with this id.
+    pub trace_id: Option<Uuid>,
+}
+
+impl BusFilter {
+    pub fn is_empty(&self) -> bool {
+        self.agent_id.is_none() && self.session_key.is_none() && self.trace_id.is_none()
+    }
+
+    pub fn matches(&self, msg: &BusMessage) -> bool {
+        if let Some(agent_id) = &self.agent_id {
+            if message_agent_id(msg) != Some(agent_id.as_str()) {
+                return false;
+            }
+        }
+        if let Some(session_key) = &self.session_key {
+            if !message_session_key(msg).is_some_and(|key| keys_overlap(&key, session_key)) {
+                return false;
+            }
+        }
+        if let Some(trace_id) = self.trace_id {
+            if !message_trace_ids(msg).contains(&trace_id) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn keys_overlap(a: &str, b: &str) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    long == short
+        || long
+            .strip_prefix(short)
+            .is_some_and(|rest| rest.starts_with(':'))
+}
+
+fn message_agent_id(msg: &BusMessage) -> Option<&str> {
+    match msg {
+        BusMessage::HandleIncomingMessage {
+            resolved_agent_id, ..
+        } => Some(resolved_agent_id),
+        BusMessage::NeedHumanApproval { agent_id, .. }
+        | BusMessage::ScheduledTaskTriggered { agent_id, .. }
+        | BusMessage::DeliverApprovalRequest { agent_id, .. }
+        | BusMessage::DelegateTaskCompleted { agent_id, .. } => Some(agent_id),
+        BusMessage::ReplyReady { agent_id, .. }
+        | BusMessage::TaskFailed { agent_id, .. }
+        | BusMessage::StreamDelta { agent_id, .. } => agent_id.as_deref(),
+        _ => None,
+    }
+}
+
+fn message_session_key(msg: &BusMessage) -> Option<String> {
+    let conversation = |channel_type: &str, connector_id: &str, scope: &str| {
+        Some(format!("{channel_type}:{connector_id}:{scope}"))
+    };
+    match msg {
+        BusMessage::HandleIncomingMessage { inbound, .. } => {
+            Some(SessionKey::from_inbound(inbound).0)
+        }
+        BusMessage::ReplyReady { outbound, .. } => conversation(
+            &outbound.channel_type,
+            &outbound.connector_id,
+            &outbound.conversation_scope,
+        ),
+        BusMessage::ActionReady { action } => conversation(
+            &action.channel_type,
+            &action.connector_id,
+            &action.conversation_scope,
+        ),
+        BusMessage::MemoryWriteRequested { session_key, .. }
+        | BusMessage::MemoryReadRequested { session_key, .. }
+        | BusMessage::WaitTaskCompleted { session_key, .. } => Some(session_key.clone()),
+        BusMessage::ScheduledTaskCompleted { session_key, .. } => session_key.clone(),
+        BusMessage::NeedHumanApproval {
+            source_channel_type: Some(channel_type),
+            source_connector_id: Some(connector_id),
+            source_conversation_scope: Some(scope),
+            ..
+        } => conversation(channel_type, connector_id, scope),
+        BusMessage::DeliverAnnounce {
+            channel_type,
+            connector_id,
+            conversation_scope,
+            ..
+        }
+        | BusMessage::DeliverApprovalRequest {
+            channel_type,
+            connector_id,
+            conversation_scope,
+            ..
+        }
+        | BusMessage::DeliverSkillConfirm {
+            channel_type,
+            connector_id,
+            conversation_scope,
+            ..
+        }
+        | BusMessage::DelegateTaskCompleted {
+            channel_type,
+            connector_id,
+            conversation_scope,
+            ..
+        } => conversation(channel_type, connector_id, conversation_scope),
+        _ => None,
+    }
+}
+
+fn message_trace_ids(msg: &BusMessage) -> Vec<Uuid> {
+    match msg {
+        BusMessage::HandleIncomingMessage { inbound, .. } => vec![inbound.trace_id],
+        BusMessage::ReplyReady { outbound, .. } => vec![outbound.trace_id],
+        BusMessage::ActionReady { action } => vec![action.trace_id],
+        BusMessage::CancelTask { trace_id }
+        | BusMessage::MessageAccepted { trace_id }
+        | BusMessage::TaskFailed { trace_id, .. }
+        | BusMessage::NeedHumanApproval { trace_id, .. }
+        | BusMessage::StreamDelta { trace_id, .. } => vec![*trace_id],
+        BusMessage::ToolCallStarted {
+            trace_id,
+            parent_run_id,
+            ..
+        }
+        | BusMessage::ToolCallCompleted {
+            trace_id,
+            parent_run_id,
+            ..
+        } => std::iter::once(*trace_id).chain(*parent_run_id).collect(),
+        BusMessage::DelegateTaskCompleted { run_id, .. } => vec![*run_id],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(trace_id: Uuid) -> BusMessage {
+        BusMessage::StreamDelta {
+            trace_id,
+            delta: "hi".into(),
+            is_final: false,
+            agent_id: Some("agent-a".into()),
+        }
+    }
+
+    #[test]
+    fn session_keys_match_on_colon_boundaries() {
+        let filter = BusFilter {
+            session_key: Some("telegram:tg_main:chat:1".into()),
+            ..Default::default()
+        };
+        let announce = |scope: &str| BusMessage::DeliverAnnounce {
+            channel_type: "telegram".into(),
+            connector_id: "tg_main".into(),
+            conversation_scope: scope.into(),
+            text: "done".into(),
+        };
+        assert!(filter.matches(&announce("chat:1")));
+        assert!(!filter.matches(&announce("chat:12")));
+
+        let connector = BusFilter {
+            session_key: Some("telegram:tg_main".into()),
+            ..Default::default()
+        };
+        assert!(connector.matches(&announce("chat:12")));
+        assert!(!connector.matches(&BusMessage::RunScheduledConsolidation));
+    }
+
+    #[test]
+    fn trace_filter_includes_delegated_tool_calls() {
+        let run = Uuid::new_v4();
+        let filter = BusFilter {
+            trace_id: Some(run),
+            ..Default::default()
+        };
+        assert!(filter.matches(&delta(run)));
+        assert!(!filter.matches(&delta(Uuid::new_v4())));
+        assert!(filter.matches(&BusMessage::ToolCallStarted {
+            trace_id: Uuid::new_v4(),
+            tool_name: "read".into(),
+            arguments: "{}".into(),
+            parent_run_id: Some(run),
+        }));
+    }
+
+    #[test]
+    fn agent_filter_covers_replies_deltas_and_failures() {
+        let filter = BusFilter {
+            agent_id: Some("agent-a".into()),
+            ..Default::default()
+        };
+        let failed = |agent_id: Option<&str>| BusMessage::TaskFailed {
+            trace_id: Uuid::new_v4(),
+            error: "boom".into(),
+            agent_id: agent_id.map(str::to_owned),
+        };
+        assert!(filter.matches(&delta(Uuid::new_v4())));
+        assert!(filter.matches(&failed(Some("agent-a"))));
+        assert!(!filter.matches(&failed(Some("agent-b"))));
+        assert!(!filter.matches(&failed(None)));
+    }
+
+    #[test]
+    fn all_fields_must_match() {
+        let filter = BusFilter {
+            agent_id: Some("agent-a".into()),
+            trace_id: Some(Uuid::new_v4()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&BusMessage::NeedHumanApproval {
+            trace_id: Uuid::new_v4(),
+            reason: "r".into(),
+            agent_id: "agent-a".into(),
+            command: "ls".into(),
+            network_target: None,
+            summary: None,
+            source_channel_type: None,
+            source_connector_id: None,
+            source_conversation_scope: None,
+        }));
+        assert!(BusFilter::default().matches(&BusMessage::RunScheduledConsolidation));
+    }
+}
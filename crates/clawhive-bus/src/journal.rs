@@ -0,0 +1,217 @@
+//! SQLite journal backing the durable bus mode.
+//!
+//! Every published message (except token-level `StreamDelta`s) is appended
+//! with a monotonically increasing sequence number. Named subscribers keep an
+//! acknowledged cursor so they resume after a restart, and readers such as
+//! the SSE endpoint can replay from any sequence still retained.
+//!
+//! A cursor is refreshed whenever its subscriber starts or acks. One left
+//! untouched for [`STALE_CURSOR_DAYS`], say by a subscriber that was renamed
+//! or removed, is dropped so it no longer holds back pruning.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use clawhive_schema::BusMessage;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use tokio::sync::Notify;
+
+use crate::Topic;
+
+/// Prune old entries once every this many appends.
+const PRUNE_EVERY: u64 = 256;
+/// Cursors not refreshed for this many days are pruned.
+pub const STALE_CURSOR_DAYS: i64 = 30;
+
+/// A journaled message and its sequence number.
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    pub seq: i64,
+    pub message: BusMessage,
+}
+
+pub struct BusJournal {
+    conn: Arc<Mutex<Connection>>,
+    pub(crate) appended: Notify,
+    appends: AtomicU64,
+    retain_events: usize,
+}
+
+impl BusJournal {
+    /// Open or create the journal, keeping roughly the newest
+    /// `retain_events` messages, and anything older that a reliable
+    /// subscriber has not acknowledged.
+    pub fn open(db_path: &Path, retain_events: usize) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)?;
+        conn.execute_batch(
+            r#"PRAGMA journal_mode=WAL;
+               PRAGMA synchronous=NORMAL;
+               CREATE TABLE IF NOT EXISTS bus_events (
+                   seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                   topic TEXT NOT NULL,
+                   payload TEXT NOT NULL,
+                   created_at TEXT NOT NULL
+               );
+               CREATE INDEX IF NOT EXISTS idx_bus_events_topic ON bus_events(topic, seq);
+               CREATE TABLE IF NOT EXISTS bus_cursors (
+                   subscriber TEXT PRIMARY KEY,
+                   seq INTEGER NOT NULL,
+                   updated_at TEXT NOT NULL
+               );"#,
+        )?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            appended: Notify::new(),
+            appends: AtomicU64::new(0),
+            retain_events: retain_events.max(1),
+        })
+    }
+
+    /// Whether messages on `topic` are written to the journal.
+    pub fn records(topic: &Topic) -> bool {
+        *topic != Topic::StreamDelta
+    }
+
+    /// Run `f` on the connection off the async runtime.
+    async fn with_conn<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|_| anyhow!("failed to lock bus journal"))?;
+            f(&conn)
+        })
+        .await?
+    }
+
+    pub(crate) async fn append(&self, message: &BusMessage) -> Result<i64> {
+        let payload = serde_json::to_string(message)?;
+        let topic = Topic::from_message(message).as_str();
+        let prune = self.appends.fetch_add(1, Ordering::Relaxed) % PRUNE_EVERY == PRUNE_EVERY - 1;
+        let retain_events = self.retain_events as i64;
+        let seq = self
+            .with_conn(move |conn| {
+                let now = chrono::Utc::now();
+                conn.execute(
+                    "INSERT INTO bus_events (topic, payload, created_at) VALUES (?1, ?2, ?3)",
+                    params![topic, payload, now.to_rfc3339()],
+                )?;
+                let seq = conn.last_insert_rowid();
+                if prune {
+                    let stale_before = now - chrono::Duration::days(STALE_CURSOR_DAYS);
+                    let dropped = conn.execute(
+                        "DELETE FROM bus_cursors WHERE updated_at < ?1",
+                        params![stale_before.to_rfc3339()],
+                    )?;
+                    if dropped > 0 {
+                        tracing::warn!(
+                            dropped,
+                            "dropped bus journal cursors not acknowledged in {STALE_CURSOR_DAYS} days"
+                        );
+                    }
+                    // Never drop what a reliable subscriber has not acked yet.
+                    conn.execute(
+                        "DELETE FROM bus_events
+                         WHERE seq <= MIN(?1, COALESCE((SELECT MIN(seq) FROM bus_cursors), ?1))",
+                        params![seq - retain_events],
+                    )?;
+                }
+                Ok(seq)
+            })
+            .await?;
+        self.appended.notify_waiters();
+        Ok(seq)
+    }
+
+    /// Sequence number of the newest entry, 0 when empty.
+    pub async fn head(&self) -> Result<i64> {
+        self.with_conn(|conn| {
+            Ok(
+                conn.query_row("SELECT COALESCE(MAX(seq), 0) FROM bus_events", [], |row| {
+                    row.get(0)
+                })?,
+            )
+        })
+        .await
+    }
+
+    /// Up to `limit` entries after `after`, oldest first. An empty `topics`
+    /// slice matches every topic.
+    pub async fn read_after(
+        &self,
+        after: i64,
+        topics: &[Topic],
+        limit: usize,
+    ) -> Result<Vec<JournalEntry>> {
+        let mut sql = String::from("SELECT seq, payload FROM bus_events WHERE seq > ?");
+        if !topics.is_empty() {
+            sql.push_str(" AND topic IN (");
+            sql.push_str(&vec!["?"; topics.len()].join(", "));
+            sql.push(')');
+        }
+        sql.push_str(" ORDER BY seq LIMIT ?");
+
+        let mut values: Vec<rusqlite::types::Value> = vec![after.into()];
+        values.extend(topics.iter().map(|topic| topic.as_str().to_string().into()));
+        values.push((limit as i64).into());
+
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values), |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?;
+            let mut entries = Vec::new();
+            for row in rows {
+                let (seq, payload) = row?;
+                match serde_json::from_str(&payload) {
+                    Ok(message) => entries.push(JournalEntry { seq, message }),
+                    Err(e) => {
+                        tracing::warn!(seq, error = %e, "skipping unreadable bus journal entry")
+                    }
+                }
+            }
+            Ok(entries)
+        })
+        .await
+    }
+
+    /// Last sequence `subscriber` acknowledged.
+    pub async fn cursor(&self, subscriber: &str) -> Result<Option<i64>> {
+        let subscriber = subscriber.to_owned();
+        self.with_conn(move |conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT seq FROM bus_cursors WHERE subscriber = ?1",
+                    params![subscriber],
+                    |row| row.get(0),
+                )
+                .optional()?)
+        })
+        .await
+    }
+
+    /// Move `subscriber`'s cursor forward to `seq`; never backwards. Also
+    /// refreshes the cursor so it is not pruned as stale.
+    pub async fn ack(&self, subscriber: &str, seq: i64) -> Result<()> {
+        let subscriber = subscriber.to_owned();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO bus_cursors (subscriber, seq, updated_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(subscriber) DO UPDATE
+                 SET seq = MAX(seq, excluded.seq), updated_at = excluded.updated_at",
+                params![subscriber, seq, chrono::Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+}
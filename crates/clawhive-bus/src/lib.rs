@@ -1,9 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 
 use clawhive_schema::BusMessage;
 use tokio::sync::{mpsc, RwLock};
 
+pub mod filter;
+pub mod journal;
+
+pub use filter::BusFilter;
+pub use journal::{BusJournal, JournalEntry};
+
 #[derive(Debug, thiserror::Error)]
 pub enum BusError {
     #[error("channel closed")]
@@ -38,11 +44,70 @@ pub enum Topic {
     DeliverApprovalRequest,
     DeliverSkillConfirm,
     WaitTaskCompleted,
+    DelegateTaskCompleted,
     ToolCallStarted,
     ToolCallCompleted,
 }
 
 impl Topic {
+    pub const ALL: &'static [Topic] = &[
+        Topic::HandleIncomingMessage,
+        Topic::CancelTask,
+        Topic::RunScheduledConsolidation,
+        Topic::MessageAccepted,
+        Topic::ReplyReady,
+        Topic::ActionReady,
+        Topic::TaskFailed,
+        Topic::MemoryWriteRequested,
+        Topic::NeedHumanApproval,
+        Topic::MemoryReadRequested,
+        Topic::ConsolidationCompleted,
+        Topic::StreamDelta,
+        Topic::ScheduledTaskTriggered,
+        Topic::ScheduledTaskCompleted,
+        Topic::DeliverAnnounce,
+        Topic::DeliverApprovalRequest,
+        Topic::DeliverSkillConfirm,
+        Topic::WaitTaskCompleted,
+        Topic::DelegateTaskCompleted,
+        Topic::ToolCallStarted,
+        Topic::ToolCallCompleted,
+    ];
+
+    /// The `BusMessage` variant name, used in configs and APIs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Topic::HandleIncomingMessage => "HandleIncomingMessage",
+            Topic::CancelTask => "CancelTask",
+            Topic::RunScheduledConsolidation => "RunScheduledConsolidation",
+            Topic::MessageAccepted => "MessageAccepted",
+            Topic::ReplyReady => "ReplyReady",
+            Topic::ActionReady => "ActionReady",
+            Topic::TaskFailed => "TaskFailed",
+            Topic::MemoryWriteRequested => "MemoryWriteRequested",
+            Topic::NeedHumanApproval => "NeedHumanApproval",
+            Topic::MemoryReadRequested => "MemoryReadRequested",
+            Topic::ConsolidationCompleted => "ConsolidationCompleted",
+            Topic::StreamDelta => "StreamDelta",
+            Topic::ScheduledTaskTriggered => "ScheduledTaskTriggered",
+            Topic::ScheduledTaskCompleted => "ScheduledTaskCompleted",
+            Topic::DeliverAnnounce => "DeliverAnnounce",
+            Topic::DeliverApprovalRequest => "DeliverApprovalRequest",
+            Topic::DeliverSkillConfirm => "DeliverSkillConfirm",
+            Topic::WaitTaskCompleted => "WaitTaskCompleted",
+            Topic::DelegateTaskCompleted => "DelegateTaskCompleted",
+            Topic::ToolCallStarted => "ToolCallStarted",
+            Topic::ToolCallCompleted => "ToolCallCompleted",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|topic| topic.as_str() == name)
+            .cloned()
+    }
+
     pub fn from_message(msg: &BusMessage) -> Self {
         match msg {
             BusMessage::HandleIncomingMessage { .. } => Topic::HandleIncomingMessage,
@@ -63,17 +128,32 @@ impl Topic {
             BusMessage::DeliverApprovalRequest { .. } => Topic::DeliverApprovalRequest,
             BusMessage::DeliverSkillConfirm { .. } => Topic::DeliverSkillConfirm,
             BusMessage::WaitTaskCompleted { .. } => Topic::WaitTaskCompleted,
+            BusMessage::DelegateTaskCompleted { .. } => Topic::DelegateTaskCompleted,
             BusMessage::ToolCallStarted { .. } => Topic::ToolCallStarted,
             BusMessage::ToolCallCompleted { .. } => Topic::ToolCallCompleted,
         }
     }
 }
 
-type Subscriber = mpsc::Sender<BusMessage>;
+struct Subscriber {
+    tx: mpsc::Sender<BusMessage>,
+    filter: Option<Arc<BusFilter>>,
+    /// A reliable subscriber that reads from the journal; it only gets
+    /// messages here when journaling them failed.
+    journaled: bool,
+}
+
+type Subscribers = Arc<RwLock<HashMap<Topic, Vec<Subscriber>>>>;
+type DroppedCounts = Arc<std::sync::Mutex<HashMap<Topic, u64>>>;
+
+/// How many journal entries a receiver fetches per query.
+const JOURNAL_BATCH: usize = 128;
 
 pub struct EventBus {
-    subscribers: Arc<RwLock<HashMap<Topic, Vec<Subscriber>>>>,
+    subscribers: Subscribers,
     capacity: usize,
+    journal: Option<Arc<BusJournal>>,
+    dropped: DroppedCounts,
 }
 
 impl EventBus {
@@ -81,47 +161,391 @@ impl EventBus {
         Self {
             subscribers: Arc::new(RwLock::new(HashMap::new())),
             capacity,
+            journal: None,
+            dropped: Arc::default(),
         }
     }
 
+    /// Enable the durable mode: every message is journaled before fan-out.
+    /// Call before handing out publishers.
+    pub fn with_journal(mut self, journal: BusJournal) -> Self {
+        self.journal = Some(Arc::new(journal));
+        self
+    }
+
+    pub fn journal(&self) -> Option<&Arc<BusJournal>> {
+        self.journal.as_ref()
+    }
+
     pub async fn subscribe(&self, topic: Topic) -> mpsc::Receiver<BusMessage> {
+        self.subscribe_filtered(&[topic], BusFilter::default())
+            .await
+    }
+
+    /// One receiver for several topics, in publish order.
+    pub async fn subscribe_many(&self, topics: &[Topic]) -> mpsc::Receiver<BusMessage> {
+        self.subscribe_filtered(topics, BusFilter::default()).await
+    }
+
+    pub async fn subscribe_all(&self) -> mpsc::Receiver<BusMessage> {
+        self.subscribe_many(Topic::ALL).await
+    }
+
+    /// Like [`subscribe_many`](Self::subscribe_many), but messages that do not
+    /// match `filter` are skipped at publish time and never queue up.
+    pub async fn subscribe_filtered(
+        &self,
+        topics: &[Topic],
+        filter: BusFilter,
+    ) -> mpsc::Receiver<BusMessage> {
+        let filter = (!filter.is_empty()).then(|| Arc::new(filter));
+        self.register(topics, filter, false).await
+    }
+
+    async fn register(
+        &self,
+        topics: &[Topic],
+        filter: Option<Arc<BusFilter>>,
+        journaled: bool,
+    ) -> mpsc::Receiver<BusMessage> {
         let (tx, rx) = mpsc::channel(self.capacity);
         let mut subs = self.subscribers.write().await;
-        subs.entry(topic).or_default().push(tx);
+        for topic in topics {
+            let subscribers = subs.entry(topic.clone()).or_default();
+            subscribers.retain(|sub| !sub.tx.is_closed());
+            subscribers.push(Subscriber {
+                tx: tx.clone(),
+                filter: filter.clone(),
+                journaled,
+            });
+        }
         rx
     }
 
-    pub async fn publish(&self, msg: BusMessage) -> Result<()> {
-        let topic = Topic::from_message(&msg);
-        let subs = self.subscribers.read().await;
-        if let Some(subscribers) = subs.get(&topic) {
-            for tx in subscribers {
-                let _ = tx.try_send(msg.clone());
+    /// Subscribe with at-least-once delivery, for topics whose loss would
+    /// strand a user-visible action (approvals, scheduled deliveries, wait
+    /// and delegate completions). In durable mode the receiver reads from the
+    /// journal and resumes after the last sequence `subscriber` acknowledged,
+    /// so the name must be stable across restarts; messages that could not
+    /// be journaled are delivered live instead. Without a journal this is a
+    /// plain subscription and `ack` is a no-op.
+    pub async fn subscribe_reliable(&self, subscriber: &str, topic: Topic) -> BusReceiver {
+        if let Some(journal) = &self.journal {
+            match start_cursor(journal, subscriber).await {
+                Ok(cursor) => {
+                    let fallback = self
+                        .register(std::slice::from_ref(&topic), None, true)
+                        .await;
+                    let mut receiver = BusReceiver::journal(
+                        journal.clone(),
+                        Some(subscriber.to_string()),
+                        vec![topic],
+                        cursor,
+                    );
+                    if let ReceiverSource::Journal { fallback: slot, .. } = &mut receiver.source {
+                        *slot = Some(fallback);
+                    }
+                    return receiver;
+                }
+                Err(e) => tracing::warn!(
+                    subscriber,
+                    error = %e,
+                    "bus journal unavailable, falling back to live subscription"
+                ),
             }
         }
-        Ok(())
+        BusReceiver::live(self.subscribe(topic).await)
+    }
+
+    /// Read journaled messages on `topics` after sequence `after`, or from
+    /// the current head when `after` is `None`. `None` without a journal.
+    pub async fn tail(&self, topics: &[Topic], after: Option<i64>) -> Option<BusReceiver> {
+        let journal = self.journal.as_ref()?;
+        let cursor = match after {
+            Some(seq) => seq,
+            None => journal.head().await.ok()?,
+        };
+        Some(BusReceiver::journal(
+            journal.clone(),
+            None,
+            topics.to_vec(),
+            cursor,
+        ))
+    }
+
+    pub async fn publish(&self, msg: BusMessage) -> Result<()> {
+        publish(
+            &self.subscribers,
+            self.journal.as_deref(),
+            &self.dropped,
+            msg,
+        )
+        .await
+    }
+
+    /// Messages discarded because a subscriber's channel was full, by topic.
+    pub fn dropped_messages(&self) -> BTreeMap<&'static str, u64> {
+        let dropped = self.dropped.lock().unwrap_or_else(|e| e.into_inner());
+        dropped
+            .iter()
+            .map(|(topic, count)| (topic.as_str(), *count))
+            .collect()
+    }
+
+    /// Messages queued but not yet received, summed over each topic's
+    /// subscribers.
+    pub async fn queue_depths(&self) -> BTreeMap<&'static str, usize> {
+        let subs = self.subscribers.read().await;
+        subs.iter()
+            .map(|(topic, subscribers)| {
+                let depth = subscribers
+                    .iter()
+                    .filter(|sub| !sub.tx.is_closed())
+                    .map(|sub| sub.tx.max_capacity() - sub.tx.capacity())
+                    .sum();
+                (topic.as_str(), depth)
+            })
+            .collect()
     }
 
     pub fn publisher(&self) -> BusPublisher {
         BusPublisher {
             subscribers: self.subscribers.clone(),
+            journal: self.journal.clone(),
+            dropped: self.dropped.clone(),
         }
     }
 }
 
 #[derive(Clone)]
 pub struct BusPublisher {
-    subscribers: Arc<RwLock<HashMap<Topic, Vec<Subscriber>>>>,
+    subscribers: Subscribers,
+    journal: Option<Arc<BusJournal>>,
+    dropped: DroppedCounts,
 }
 
 impl BusPublisher {
     pub async fn publish(&self, msg: BusMessage) -> Result<()> {
-        let topic = Topic::from_message(&msg);
-        let subs = self.subscribers.read().await;
-        if let Some(subscribers) = subs.get(&topic) {
-            for tx in subscribers {
-                let _ = tx.try_send(msg.clone());
+        publish(
+            &self.subscribers,
+            self.journal.as_deref(),
+            &self.dropped,
+            msg,
+        )
+        .await
+    }
+}
+
+async fn publish(
+    subscribers: &Subscribers,
+    journal: Option<&BusJournal>,
+    dropped: &DroppedCounts,
+    msg: BusMessage,
+) -> Result<()> {
+    let topic = Topic::from_message(&msg);
+    let mut journaled = false;
+    if let Some(journal) = journal.filter(|_| BusJournal::records(&topic)) {
+        match journal.append(&msg).await {
+            Ok(_) => journaled = true,
+            Err(e) => tracing::error!(
+                topic = topic.as_str(),
+                error = %e,
+                "failed to journal bus message; delivering it to reliable subscribers live"
+            ),
+        }
+    }
+    let subs = subscribers.read().await;
+    if let Some(subscribers) = subs.get(&topic) {
+        for sub in subscribers {
+            if sub.journaled && journaled {
+                continue;
             }
+            if sub
+                .filter
+                .as_ref()
+                .is_some_and(|filter| !filter.matches(&msg))
+            {
+                continue;
+            }
+            if let Err(mpsc::error::TrySendError::Full(_)) = sub.tx.try_send(msg.clone()) {
+                let mut dropped = dropped.lock().unwrap_or_else(|e| e.into_inner());
+                *dropped.entry(topic.clone()).or_default() += 1;
+                tracing::debug!(
+                    topic = topic.as_str(),
+                    "bus subscriber full, message dropped"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A new reliable subscriber starts at the journal head; persist that right
+/// away so messages published before its first ack survive a restart. An
+/// existing cursor is re-acked so a subscriber that is still in use never
+/// looks stale.
+async fn start_cursor(journal: &BusJournal, subscriber: &str) -> anyhow::Result<i64> {
+    let seq = match journal.cursor(subscriber).await? {
+        Some(seq) => seq,
+        None => journal.head().await?,
+    };
+    journal.ack(subscriber, seq).await?;
+    Ok(seq)
+}
+
+/// A received message. `seq` is the journal sequence in durable mode.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub seq: Option<i64>,
+    pub message: BusMessage,
+}
+
+pub struct BusReceiver {
+    source: ReceiverSource,
+}
+
+enum ReceiverSource {
+    Live(mpsc::Receiver<BusMessage>),
+    Journal {
+        journal: Arc<BusJournal>,
+        subscriber: Option<String>,
+        topics: Vec<Topic>,
+        cursor: i64,
+        pending: VecDeque<JournalEntry>,
+        /// Messages that could not be journaled (reliable subscribers only).
+        fallback: Option<mpsc::Receiver<BusMessage>>,
+    },
+}
+
+impl BusReceiver {
+    fn live(rx: mpsc::Receiver<BusMessage>) -> Self {
+        Self {
+            source: ReceiverSource::Live(rx),
+        }
+    }
+
+    fn journal(
+        journal: Arc<BusJournal>,
+        subscriber: Option<String>,
+        topics: Vec<Topic>,
+        cursor: i64,
+    ) -> Self {
+        Self {
+            source: ReceiverSource::Journal {
+                journal,
+                subscriber,
+                topics,
+                cursor,
+                pending: VecDeque::new(),
+                fallback: None,
+            },
+        }
+    }
+
+    pub async fn recv(&mut self) -> Option<Delivery> {
+        match &mut self.source {
+            ReceiverSource::Live(rx) => rx
+                .recv()
+                .await
+                .map(|message| Delivery { seq: None, message }),
+            ReceiverSource::Journal {
+                journal,
+                topics,
+                cursor,
+                pending,
+                fallback,
+                ..
+            } => loop {
+                if let Some(entry) = pending.pop_front() {
+                    *cursor = entry.seq;
+                    return Some(Delivery {
+                        seq: Some(entry.seq),
+                        message: entry.message,
+                    });
+                }
+                if let Some(message) = fallback.as_mut().and_then(|rx| rx.try_recv().ok()) {
+                    return Some(Delivery { seq: None, message });
+                }
+                // Register for the wakeup before querying so an append that
+                // lands in between is not missed.
+                let notified = journal.appended.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                match journal.read_after(*cursor, topics, JOURNAL_BATCH).await {
+                    Ok(entries) if !entries.is_empty() => pending.extend(entries),
+                    Ok(_) => match fallback.as_mut() {
+                        Some(rx) => tokio::select! {
+                            _ = notified => {}
+                            Some(message) = rx.recv() => {
+                                return Some(Delivery { seq: None, message });
+                            }
+                        },
+                        None => notified.await,
+                    },
+                    Err(e) => {
+                        tracing::warn!(error = %e, "failed to read bus journal");
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }
+            },
+        }
+    }
+
+    /// Everything available right now, without waiting.
+    pub async fn drain(&mut self) -> Vec<Delivery> {
+        match &mut self.source {
+            ReceiverSource::Live(rx) => {
+                let mut deliveries = Vec::new();
+                while let Ok(message) = rx.try_recv() {
+                    deliveries.push(Delivery { seq: None, message });
+                }
+                deliveries
+            }
+            ReceiverSource::Journal {
+                journal,
+                topics,
+                cursor,
+                pending,
+                fallback,
+                ..
+            } => {
+                let after = pending.back().map_or(*cursor, |entry| entry.seq);
+                match journal.read_after(after, topics, JOURNAL_BATCH).await {
+                    Ok(entries) => pending.extend(entries),
+                    Err(e) => tracing::warn!(error = %e, "failed to read bus journal"),
+                }
+                if let Some(last) = pending.back() {
+                    *cursor = last.seq;
+                }
+                let mut deliveries: Vec<Delivery> = pending
+                    .drain(..)
+                    .map(|entry| Delivery {
+                        seq: Some(entry.seq),
+                        message: entry.message,
+                    })
+                    .collect();
+                if let Some(rx) = fallback.as_mut() {
+                    while let Ok(message) = rx.try_recv() {
+                        deliveries.push(Delivery { seq: None, message });
+                    }
+                }
+                deliveries
+            }
+        }
+    }
+
+    /// Mark `delivery` handled so it is not redelivered after a restart.
+    pub async fn ack(&self, delivery: &Delivery) -> Result<()> {
+        if let (
+            ReceiverSource::Journal {
+                journal,
+                subscriber: Some(subscriber),
+                ..
+            },
+            Some(seq),
+        ) = (&self.source, delivery.seq)
+        {
+            journal.ack(subscriber, seq).await?;
         }
         Ok(())
     }
@@ -137,6 +561,7 @@ mod tests {
 
     fn reply_ready_message() -> BusMessage {
         BusMessage::ReplyReady {
+            agent_id: None,
             outbound: OutboundMessage {
                 trace_id: Uuid::new_v4(),
                 channel_type: "telegram".to_string(),
@@ -150,6 +575,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn topic_names_round_trip() {
+        for topic in Topic::ALL {
+            assert_eq!(Topic::from_name(topic.as_str()).as_ref(), Some(topic));
+        }
+        assert_eq!(
+            Topic::from_message(&reply_ready_message()).as_str(),
+            "ReplyReady"
+        );
+        assert_eq!(Topic::from_name("replyready"), None);
+    }
+
     #[tokio::test]
     async fn publish_to_no_subscribers_succeeds() {
         let bus = EventBus::new(8);
@@ -203,6 +640,7 @@ mod tests {
         let mut reply_rx = bus.subscribe(Topic::ReplyReady).await;
 
         let msg = BusMessage::TaskFailed {
+            agent_id: None,
             trace_id: Uuid::new_v4(),
             error: "test".into(),
         };
@@ -246,6 +684,283 @@ mod tests {
         assert!(second.is_err());
     }
 
+    #[tokio::test]
+    async fn subscribe_many_receives_topics_in_publish_order() {
+        let bus = EventBus::new(8);
+        let mut rx = bus
+            .subscribe_many(&[Topic::ReplyReady, Topic::MessageAccepted])
+            .await;
+
+        bus.publish(BusMessage::MessageAccepted {
+            trace_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+        bus.publish(BusMessage::RunScheduledConsolidation)
+            .await
+            .unwrap();
+        bus.publish(reply_ready_message()).await.unwrap();
+
+        assert!(matches!(
+            rx.recv().await,
+            Some(BusMessage::MessageAccepted { .. })
+        ));
+        assert!(matches!(
+            rx.recv().await,
+            Some(BusMessage::ReplyReady { .. })
+        ));
+        assert!(rx.try_recv().is_err());
+
+        let mut all = bus.subscribe_all().await;
+        bus.publish(BusMessage::RunScheduledConsolidation)
+            .await
+            .unwrap();
+        assert!(matches!(
+            all.try_recv(),
+            Ok(BusMessage::RunScheduledConsolidation)
+        ));
+    }
+
+    #[tokio::test]
+    async fn filtered_subscription_skips_non_matching_messages() {
+        let bus = EventBus::new(1);
+        let wanted = Uuid::new_v4();
+        let mut rx = bus
+            .subscribe_filtered(
+                &[Topic::MessageAccepted],
+                BusFilter {
+                    trace_id: Some(wanted),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        bus.publish(BusMessage::MessageAccepted {
+            trace_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+        bus.publish(BusMessage::MessageAccepted { trace_id: wanted })
+            .await
+            .unwrap();
+
+        match rx.try_recv() {
+            Ok(BusMessage::MessageAccepted { trace_id }) => assert_eq!(trace_id, wanted),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(bus.dropped_messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn full_subscriber_counts_dropped_messages() {
+        let bus = EventBus::new(1);
+        let _rx = bus.subscribe(Topic::ReplyReady).await;
+
+        for _ in 0..3 {
+            bus.publisher()
+                .publish(reply_ready_message())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(bus.dropped_messages().get("ReplyReady"), Some(&2));
+    }
+
+    #[tokio::test]
+    async fn queue_depths_count_unreceived_messages() {
+        let bus = EventBus::new(8);
+        let mut rx = bus.subscribe(Topic::ReplyReady).await;
+        bus.publish(reply_ready_message()).await.unwrap();
+        bus.publish(reply_ready_message()).await.unwrap();
+        assert_eq!(bus.queue_depths().await.get("ReplyReady"), Some(&2));
+
+        rx.recv().await.unwrap();
+        assert_eq!(bus.queue_depths().await.get("ReplyReady"), Some(&1));
+    }
+
+    fn durable_bus(dir: &tempfile::TempDir) -> EventBus {
+        let journal = BusJournal::open(&dir.path().join("bus.db"), 100).unwrap();
+        EventBus::new(8).with_journal(journal)
+    }
+
+    fn accepted() -> BusMessage {
+        BusMessage::MessageAccepted {
+            trace_id: Uuid::new_v4(),
+        }
+    }
+
+    #[tokio::test]
+    async fn reliable_subscriber_redelivers_unacked_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let bus = durable_bus(&dir);
+            let mut rx = bus
+                .subscribe_reliable("listener", Topic::MessageAccepted)
+                .await;
+            bus.publish(accepted()).await.unwrap();
+            bus.publish(accepted()).await.unwrap();
+
+            let first = timeout(Duration::from_millis(500), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(first.seq, Some(1));
+            rx.ack(&first).await.unwrap();
+            let second = timeout(Duration::from_millis(500), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(second.seq, Some(2));
+        }
+
+        let bus = durable_bus(&dir);
+        let mut rx = bus
+            .subscribe_reliable("listener", Topic::MessageAccepted)
+            .await;
+        let redelivered = timeout(Duration::from_millis(500), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(redelivered.seq, Some(2));
+        assert!(matches!(
+            redelivered.message,
+            BusMessage::MessageAccepted { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn reliable_subscriber_wakes_on_publish() {
+        let dir = tempfile::tempdir().unwrap();
+        let bus = durable_bus(&dir);
+        bus.publish(accepted()).await.unwrap();
+        let mut rx = bus.subscribe_reliable("late", Topic::MessageAccepted).await;
+
+        let publisher = bus.publisher();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            publisher.publish(accepted()).await.unwrap();
+        });
+
+        let delivery = timeout(Duration::from_millis(500), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(delivery.seq, Some(2), "new subscribers start at the head");
+    }
+
+    #[tokio::test]
+    async fn tail_replays_after_sequence_and_skips_stream_deltas() {
+        let dir = tempfile::tempdir().unwrap();
+        let bus = durable_bus(&dir);
+        bus.publish(accepted()).await.unwrap();
+        bus.publish(BusMessage::StreamDelta {
+            agent_id: None,
+            trace_id: Uuid::new_v4(),
+            delta: "tok".into(),
+            is_final: false,
+        })
+        .await
+        .unwrap();
+        bus.publish(reply_ready_message()).await.unwrap();
+        bus.publish(accepted()).await.unwrap();
+
+        let mut tail = bus
+            .tail(&[Topic::MessageAccepted, Topic::ReplyReady], Some(1))
+            .await
+            .unwrap();
+        let seqs: Vec<_> = tail.drain().await.iter().map(|d| d.seq).collect();
+        assert_eq!(seqs, vec![Some(2), Some(3)]);
+        assert!(tail.drain().await.is_empty());
+        assert!(EventBus::new(8).tail(&[], None).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn pruning_keeps_events_a_subscriber_has_not_acked() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = BusJournal::open(&dir.path().join("bus.db"), 10).unwrap();
+        let bus = EventBus::new(8).with_journal(journal);
+        let _offline = bus
+            .subscribe_reliable("offline", Topic::MessageAccepted)
+            .await;
+        for _ in 0..600 {
+            bus.publish(accepted()).await.unwrap();
+        }
+
+        let journal = bus.journal().unwrap();
+        let kept = journal
+            .read_after(0, &[Topic::MessageAccepted], 1000)
+            .await
+            .unwrap();
+        assert_eq!(kept.len(), 600);
+
+        journal.ack("offline", 590).await.unwrap();
+        for _ in 0..256 {
+            bus.publish(accepted()).await.unwrap();
+        }
+        let kept = journal
+            .read_after(0, &[Topic::MessageAccepted], 1000)
+            .await
+            .unwrap();
+        assert!(kept.len() < 856);
+        assert!(kept.first().unwrap().seq > 1);
+        assert!(kept.first().unwrap().seq <= 591);
+    }
+
+    #[tokio::test]
+    async fn pruning_drops_cursors_of_subscribers_that_went_away() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("bus.db");
+        let bus = EventBus::new(8).with_journal(BusJournal::open(&db_path, 10).unwrap());
+        let journal = bus.journal().unwrap();
+        journal.ack("renamed", 0).await.unwrap();
+        journal.ack("current", 0).await.unwrap();
+        rusqlite::Connection::open(&db_path)
+            .unwrap()
+            .execute(
+                "UPDATE bus_cursors SET updated_at = '2000-01-01T00:00:00+00:00'",
+                [],
+            )
+            .unwrap();
+        // Subscribing again refreshes a cursor that is still in use.
+        let _current = bus.subscribe_reliable("current", Topic::ReplyReady).await;
+
+        for _ in 0..256 {
+            bus.publish(accepted()).await.unwrap();
+        }
+        assert_eq!(journal.cursor("renamed").await.unwrap(), None);
+        assert_eq!(journal.cursor("current").await.unwrap(), Some(0));
+        let kept = journal.read_after(0, &[], 1000).await.unwrap();
+        assert_eq!(kept.len(), 256, "the live cursor still holds events back");
+
+        journal.ack("current", 256).await.unwrap();
+        for _ in 0..256 {
+            bus.publish(accepted()).await.unwrap();
+        }
+        let kept = journal.read_after(0, &[], 1000).await.unwrap();
+        assert!(kept.first().unwrap().seq > 256);
+    }
+
+    #[tokio::test]
+    async fn reliable_subscriber_gets_messages_that_could_not_be_journaled() {
+        let dir = tempfile::tempdir().unwrap();
+        let bus = durable_bus(&dir);
+        let mut rx = bus
+            .subscribe_reliable("listener", Topic::MessageAccepted)
+            .await;
+        rusqlite::Connection::open(dir.path().join("bus.db"))
+            .unwrap()
+            .execute_batch("DROP TABLE bus_events;")
+            .unwrap();
+
+        bus.publish(accepted()).await.unwrap();
+        let delivery = timeout(Duration::from_millis(500), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(delivery.seq, None);
+        rx.ack(&delivery).await.unwrap();
+    }
+
     #[tokio::test]
     async fn topic_from_message_covers_all_variants() {
         let trace_id = Uuid::new_v4();
@@ -284,6 +999,7 @@ mod tests {
             ),
             (
                 BusMessage::ReplyReady {
+                    agent_id: None,
                     outbound: OutboundMessage {
                         trace_id,
                         channel_type: "t".into(),
@@ -299,6 +1015,7 @@ mod tests {
             ),
             (
                 BusMessage::TaskFailed {
+                    agent_id: None,
                     trace_id,
                     error: "e".into(),
                 },
@@ -344,6 +1061,7 @@ mod tests {
             ),
             (
                 BusMessage::StreamDelta {
+                    agent_id: None,
                     trace_id,
                     delta: "hello".into(),
                     is_final: false,
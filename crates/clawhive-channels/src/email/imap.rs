@@ -0,0 +1,151 @@
+//! The mailbox side of the channel, on top of `async-imap`: login, select,
+//! search, fetch, flag and IDLE.
+
+use std::future::Future;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use async_imap::{Authenticator, Client, Session};
+use futures_util::TryStreamExt;
+use tokio::time::Duration;
+
+use super::net::{MailStream, Security};
+
+/// `AUTHENTICATE PLAIN`, used when the credentials are not ASCII and so
+/// cannot be sent as an IMAP quoted string.
+struct PlainAuth<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+impl Authenticator for PlainAuth<'_> {
+    type Response = Vec<u8>;
+
+    fn process(&mut self, _challenge: &[u8]) -> Self::Response {
+        format!("\0{}\0{}", self.username, self.password).into_bytes()
+    }
+}
+
+pub struct ImapSession {
+    /// Taken while the session is in IDLE.
+    session: Option<Session<MailStream>>,
+    supports_idle: bool,
+}
+
+impl ImapSession {
+    /// Connect, upgrade with `STARTTLS` when asked to, and log in.
+    pub async fn login(
+        host: &str,
+        port: u16,
+        security: Security,
+        username: &str,
+        password: &str,
+    ) -> Result<Self> {
+        let stream = MailStream::connect(host, port, security)
+            .await
+            .with_context(|| format!("failed to connect to IMAP server {host}:{port}"))?;
+        let mut client = Client::new(stream);
+        client
+            .read_response()
+            .await
+            .ok_or_else(|| anyhow!("IMAP server closed the connection before its greeting"))??;
+        if security == Security::StartTls {
+            client
+                .run_command_and_check_ok("STARTTLS", None)
+                .await
+                .context("IMAP STARTTLS failed")?;
+            client = Client::new(client.into_inner().upgrade(host).await?);
+        }
+
+        let login = if username.is_ascii() && password.is_ascii() {
+            client.login(username, password).await
+        } else {
+            client
+                .authenticate("PLAIN", PlainAuth { username, password })
+                .await
+        };
+        let mut session = login.map_err(|(e, _)| e).context("IMAP login failed")?;
+        let supports_idle = session.capabilities().await?.has_str("IDLE");
+        Ok(Self {
+            session: Some(session),
+            supports_idle,
+        })
+    }
+
+    pub fn supports_idle(&self) -> bool {
+        self.supports_idle
+    }
+
+    fn session(&mut self) -> Result<&mut Session<MailStream>> {
+        self.session
+            .as_mut()
+            .ok_or_else(|| anyhow!("IMAP session was lost during IDLE"))
+    }
+
+    pub async fn select(&mut self, mailbox: &str) -> Result<()> {
+        self.session()?
+            .select(mailbox)
+            .await
+            .with_context(|| format!("failed to select mailbox {mailbox}"))?;
+        Ok(())
+    }
+
+    /// UIDs of messages without the `\Seen` flag, oldest first.
+    pub async fn search_unseen(&mut self) -> Result<Vec<u32>> {
+        let mut uids: Vec<u32> = self
+            .session()?
+            .uid_search("UNSEEN")
+            .await?
+            .into_iter()
+            .collect();
+        uids.sort_unstable();
+        Ok(uids)
+    }
+
+    /// Fetch the full RFC 822 message without marking it as read.
+    pub async fn fetch(&mut self, uid: u32) -> Result<Option<Vec<u8>>> {
+        let fetches: Vec<_> = self
+            .session()?
+            .uid_fetch(uid.to_string(), "BODY.PEEK[]")
+            .await?
+            .try_collect()
+            .await?;
+        Ok(fetches
+            .iter()
+            .find_map(|fetch| fetch.body().map(<[u8]>::to_vec)))
+    }
+
+    pub async fn mark_seen(&mut self, uid: u32) -> Result<()> {
+        let _: Vec<_> = self
+            .session()?
+            .uid_store(uid.to_string(), "+FLAGS.SILENT (\\Seen)")
+            .await?
+            .try_collect()
+            .await?;
+        Ok(())
+    }
+
+    /// Wait in IDLE until the server reports a change, `max_wait` passes or
+    /// `interrupt` completes. Returns whether the server reported something.
+    pub async fn idle(&mut self, max_wait: Duration, interrupt: impl Future) -> Result<bool> {
+        let Some(session) = self.session.take() else {
+            bail!("IMAP session was lost during IDLE");
+        };
+        let mut handle = session.idle();
+        handle.init().await.context("server refused IDLE")?;
+        let new_data = {
+            let (wait, _stop) = handle.wait_with_timeout(max_wait);
+            tokio::select! {
+                response = wait => matches!(response?, async_imap::extensions::idle::IdleResponse::NewData(_)),
+                _ = interrupt => false,
+            }
+        };
+        self.session = Some(handle.done().await?);
+        Ok(new_data)
+    }
+
+    pub async fn logout(&mut self) {
+        if let Some(mut session) = self.session.take() {
+            let _ = session.logout().await;
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Turning received RFC 5322 / MIME messages into what the channel needs
+//! (parsed with `mail-parser`), and building plain-text replies (`lettre`).
+
+use anyhow::{Context as _, Result};
+use lettre::message::header::{ContentType, Header, HeaderName, HeaderValue};
+use lettre::message::Mailbox;
+use mail_parser::{HeaderValue as ParsedHeader, MessageParser, MimeHeaders};
+
+/// A file carried in a MIME part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MailAttachment {
+    pub file_name: Option<String>,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// The parts of a received message the channel cares about.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedEmail {
+    pub message_id: Option<String>,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+    /// Lower-cased sender address.
+    pub from: Option<String>,
+    /// Lower-cased `Reply-To` address, when set.
+    pub reply_to: Option<String>,
+    pub subject: String,
+    pub text: String,
+    pub attachments: Vec<MailAttachment>,
+    /// `Auto-Submitted` / `Precedence: bulk` mail that must not be answered.
+    pub auto_generated: bool,
+    /// `Authentication-Results` headers, topmost (most recently added) first.
+    pub authentication_results: Vec<String>,
+}
+
+impl ParsedEmail {
+    /// Root of the conversation: the first `References` entry, else the
+    /// message being replied to, else this message.
+    pub fn thread_root(&self) -> Option<&str> {
+        self.references
+            .first()
+            .or(self.in_reply_to.as_ref())
+            .or(self.message_id.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Where replies should go.
+    pub fn reply_address(&self) -> Option<&str> {
+        self.reply_to.as_deref().or(self.from.as_deref())
+    }
+
+    /// Only what [`OutgoingEmail::reply_to`] needs, without body or files.
+    pub fn thread_headers(&self) -> Self {
+        Self {
+            message_id: self.message_id.clone(),
+            in_reply_to: self.in_reply_to.clone(),
+            references: self.references.clone(),
+            from: self.from.clone(),
+            reply_to: self.reply_to.clone(),
+            subject: self.subject.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn parse_email(raw: &[u8]) -> ParsedEmail {
+    let Some(message) = MessageParser::default().parse(raw) else {
+        return ParsedEmail::default();
+    };
+    let header = |name: &'static str| message.header_raw(name).map(str::trim);
+    let auto_generated = header("Auto-Submitted").is_some_and(|v| !v.eq_ignore_ascii_case("no"))
+        || header("Precedence").is_some_and(|v| {
+            matches!(
+                v.to_ascii_lowercase().as_str(),
+                "bulk" | "junk" | "auto_reply"
+            )
+        });
+    let address = |list: Option<&mail_parser::Address>| {
+        list.and_then(|list| list.first())
+            .and_then(|addr| addr.address())
+            .map(|addr| addr.trim().to_ascii_lowercase())
+            .filter(|addr| addr.contains('@'))
+    };
+
+    let attachments = message
+        .attachments()
+        .map(|part| MailAttachment {
+            file_name: part.attachment_name().map(str::to_owned),
+            mime_type: part
+                .content_type()
+                .map(|ct| match ct.subtype() {
+                    Some(subtype) => format!("{}/{subtype}", ct.ctype()),
+                    None => ct.ctype().to_string(),
+                })
+                .unwrap_or_else(|| "application/octet-stream".to_string())
+                .to_ascii_lowercase(),
+            data: part.contents().to_vec(),
+        })
+        .collect();
+
+    ParsedEmail {
+        message_id: message.message_id().map(angle_id),
+        in_reply_to: message_ids(message.in_reply_to()).into_iter().next(),
+        references: message_ids(message.references()),
+        from: address(message.from()),
+        reply_to: address(message.reply_to()),
+        subject: message.subject().unwrap_or_default().trim().to_string(),
+        text: message
+            .body_text(0)
+            .map(|text| text.replace("\r\n", "\n").trim().to_string())
+            .unwrap_or_default(),
+        attachments,
+        auto_generated,
+        authentication_results: message
+            .headers_raw()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("Authentication-Results"))
+            .map(|(_, value)| value.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect(),
+    }
+}
+
+fn angle_id(id: &str) -> String {
+    format!(
+        "<{}>",
+        id.trim().trim_start_matches('<').trim_end_matches('>')
+    )
+}
+
+fn message_ids(value: &ParsedHeader) -> Vec<String> {
+    match value {
+        ParsedHeader::Text(id) => vec![angle_id(id)],
+        ParsedHeader::TextList(ids) => ids.iter().map(|id| angle_id(id)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Drop the quoted history mail clients append below a reply.
+pub fn strip_quoted_reply(text: &str) -> String {
+    let mut kept = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let is_attribution = trimmed.starts_with("On ") && trimmed.ends_with("wrote:");
+        if trimmed.starts_with('>') || is_attribution || trimmed == "-----Original Message-----" {
+            break;
+        }
+        kept.push(line);
+    }
+    kept.join("\n").trim().to_string()
+}
+
+/// `Auto-Submitted: auto-replied` (RFC 3834), so other bots do not answer.
+#[derive(Debug, Clone)]
+struct AutoSubmitted(String);
+
+impl Header for AutoSubmitted {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("Auto-Submitted")
+    }
+
+    fn parse(s: &str) -> std::result::Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self(s.to_string()))
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), self.0.clone())
+    }
+}
+
+/// A plain-text message to send.
+#[derive(Debug, Clone, Default)]
+pub struct OutgoingEmail {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+    pub body: String,
+}
+
+impl OutgoingEmail {
+    /// Build a reply that stays in the sender's thread.
+    pub fn reply_to(from: &str, original: &ParsedEmail, body: &str) -> Option<Self> {
+        let to = original.reply_address()?.to_string();
+        let subject = if original.subject.to_ascii_lowercase().starts_with("re:") {
+            original.subject.clone()
+        } else {
+            format!("Re: {}", original.subject)
+        };
+        let mut references = original.references.clone();
+        if references.is_empty() {
+            references.extend(original.in_reply_to.clone());
+        }
+        references.extend(original.message_id.clone());
+        Some(Self {
+            from: from.to_string(),
+            to,
+            subject,
+            in_reply_to: original.message_id.clone(),
+            references,
+            body: body.to_string(),
+        })
+    }
+
+    pub fn to_message(&self) -> Result<lettre::Message> {
+        let domain = self.from.rsplit('@').next().unwrap_or("localhost");
+        let from: Mailbox = self
+            .from
+            .parse()
+            .with_context(|| format!("invalid sender address: {}", self.from))?;
+        let to: Mailbox = self
+            .to
+            .parse()
+            .with_context(|| format!("invalid recipient address: {}", self.to))?;
+        let mut builder = lettre::Message::builder()
+            .from(from)
+            .to(to)
+            .subject(self.subject.as_str())
+            .message_id(Some(format!("<{}@{domain}>", uuid::Uuid::new_v4())))
+            .header(AutoSubmitted("auto-replied".to_string()))
+            .header(ContentType::TEXT_PLAIN);
+        if let Some(in_reply_to) = &self.in_reply_to {
+            builder = builder.in_reply_to(in_reply_to.clone());
+        }
+        if !self.references.is_empty() {
+            builder = builder.references(self.references.join(" "));
+        }
+        Ok(builder.body(self.body.clone())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTIPART: &str = "From: \"Alice Example\" <Alice@Example.com>\r\n\
+Reply-To: alice+replies@example.com\r\n\
+Subject: =?utf-8?Q?Caf=C3=A9_report?= =?utf-8?B?IOKckw==?=\r\n\
+Message-ID: <m2@example.com>\r\n\
+In-Reply-To: <m1@clawhive.local>\r\n\
+References: <root@example.com>\r\n <m1@clawhive.local>\r\n\
+Authentication-Results: mx.example.net;\r\n dkim=pass header.d=example.com\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+\r\n\
+preamble\r\n\
+--outer\r\n\
+Content-Type: multipart/alternative; boundary=inner\r\n\
+\r\n\
+--inner\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Numbers are =\r\n\
+in. Caf=C3=A9 open.\r\n\
+\r\n\
+On Mon, Jan 1, 2024 at 10:00 Bot wrote:\r\n\
+> earlier text\r\n\
+--inner\r\n\
+Content-Type: text/html\r\n\
+\r\n\
+<p>Numbers are in.</p>\r\n\
+--inner--\r\n\
+--outer\r\n\
+Content-Type: application/pdf; name=\"report.pdf\"\r\n\
+Content-Disposition: attachment; filename*=utf-8''Q1%20report.pdf\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+JVBERi0x\r\n\
+LjQ=\r\n\
+--outer--\r\n";
+
+    #[test]
+    fn parses_headers_body_and_attachments() {
+        let parsed = parse_email(MULTIPART.as_bytes());
+        assert_eq!(parsed.from.as_deref(), Some("alice@example.com"));
+        assert_eq!(parsed.reply_address(), Some("alice+replies@example.com"));
+        assert_eq!(parsed.subject, "Café report ✓");
+        assert_eq!(parsed.message_id.as_deref(), Some("<m2@example.com>"));
+        assert_eq!(parsed.in_reply_to.as_deref(), Some("<m1@clawhive.local>"));
+        assert_eq!(
+            parsed.references,
+            ["<root@example.com>", "<m1@clawhive.local>"]
+        );
+        assert_eq!(parsed.thread_root(), Some("<root@example.com>"));
+        assert_eq!(
+            parsed.authentication_results,
+            ["mx.example.net; dkim=pass header.d=example.com"]
+        );
+        assert!(parsed.text.starts_with("Numbers are in. Café open."));
+        assert_eq!(
+            strip_quoted_reply(&parsed.text),
+            "Numbers are in. Café open."
+        );
+        assert!(!parsed.auto_generated);
+
+        assert_eq!(
+            parsed.attachments,
+            [MailAttachment {
+                file_name: Some("Q1 report.pdf".into()),
+                mime_type: "application/pdf".into(),
+                data: b"%PDF-1.4".to_vec(),
+            }]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_html_and_flags_auto_replies() {
+        let raw = "From: bot@example.com\nAuto-Submitted: auto-replied\nContent-Type: text/html\n\n<div>Out of office &amp; away</div>";
+        let parsed = parse_email(raw.as_bytes());
+        assert_eq!(parsed.text, "Out of office & away");
+        assert!(parsed.auto_generated);
+        assert_eq!(parsed.thread_root(), None);
+    }
+
+    #[test]
+    fn reply_keeps_threading_headers() {
+        let original = parse_email(MULTIPART.as_bytes());
+        let reply = OutgoingEmail::reply_to("bot@clawhive.local", &original, "Thanks! ✓").unwrap();
+        assert_eq!(reply.to, "alice+replies@example.com");
+        assert_eq!(reply.subject, "Re: Café report ✓");
+        assert_eq!(reply.in_reply_to.as_deref(), Some("<m2@example.com>"));
+        assert_eq!(
+            reply.references,
+            [
+                "<root@example.com>",
+                "<m1@clawhive.local>",
+                "<m2@example.com>"
+            ]
+        );
+
+        let rendered = reply.to_message().unwrap().formatted();
+        let echoed = parse_email(&rendered);
+        assert_eq!(echoed.text, "Thanks! ✓");
+        assert_eq!(echoed.subject, "Re: Café report ✓");
+        assert_eq!(echoed.in_reply_to.as_deref(), Some("<m2@example.com>"));
+        assert_eq!(echoed.references, reply.references);
+        assert!(echoed
+            .message_id
+            .is_some_and(|id| id.ends_with("@clawhive.local>")));
+        assert!(echoed.auto_generated);
+    }
+}
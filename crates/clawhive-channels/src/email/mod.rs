@@ -0,0 +1,742 @@
+//! Email channel: IMAP (IDLE, or polling when the server lacks it) for inbound
+//! mail and SMTP for replies.
+//!
+//! Every sender is a DM conversation (`dm:{address}`). Replies keep the
+//! `In-Reply-To`/`References` chain so they land in the sender's thread.
+
+pub mod imap;
+pub mod mime;
+pub mod net;
+pub mod smtp;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use anyhow::Result;
+use base64::Engine;
+use chrono::Utc;
+use clawhive_bus::{EventBus, Topic};
+use clawhive_gateway::Gateway;
+use clawhive_schema::{ApprovalDisplay, Attachment, AttachmentKind, BusMessage, InboundMessage};
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::common::AbortOnDrop;
+
+pub use imap::ImapSession;
+pub use mime::{parse_email, strip_quoted_reply, MailAttachment, OutgoingEmail, ParsedEmail};
+pub use net::Security;
+pub use smtp::SmtpClient;
+
+/// Servers drop IDLE after 30 minutes; re-issue it a little earlier.
+const IDLE_REFRESH: Duration = Duration::from_secs(25 * 60);
+/// Turns tried for one message before it is left unread for a human.
+const MAX_ATTEMPTS: u32 = 3;
+/// Senders whose latest message is remembered for threading replies.
+const MAX_THREADS: usize = 1024;
+const THREAD_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Which senders may talk to the bot, mirroring WhatsApp's `dm_policy`:
+/// `open`, `disabled` or `allowlist` (the default).
+///
+/// The `From:` header is only trusted when the receiving server vouches for
+/// it in `Authentication-Results`.
+#[derive(Debug, Clone)]
+pub struct SenderPolicy {
+    pub dm_policy: String,
+    pub allow_from: Vec<String>,
+    /// `authserv-id` of the receiving mail server. Only its
+    /// `Authentication-Results` count; without it no sender is authenticated,
+    /// since any header may have been written by the sender.
+    pub authserv_id: Option<String>,
+}
+
+impl SenderPolicy {
+    pub fn from_config(dm_policy: &str, allow_from: &[String], authserv_id: Option<&str>) -> Self {
+        Self {
+            dm_policy: dm_policy.to_string(),
+            allow_from: allow_from
+                .iter()
+                .map(|entry| entry.trim().to_ascii_lowercase())
+                .filter(|entry| !entry.is_empty())
+                .collect(),
+            authserv_id: authserv_id
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_owned),
+        }
+    }
+
+    /// Entries are full addresses or `@domain` suffixes. Under `allowlist`
+    /// the sender must also be authenticated.
+    pub fn is_allowed(&self, sender: &str, authenticated: bool) -> bool {
+        match self.dm_policy.as_str() {
+            "open" => true,
+            "disabled" => false,
+            _ => {
+                let sender = sender.to_ascii_lowercase();
+                authenticated
+                    && self.allow_from.iter().any(|entry| {
+                        if entry.starts_with('@') {
+                            sender.ends_with(entry.as_str())
+                        } else {
+                            entry == &sender
+                        }
+                    })
+            }
+        }
+    }
+
+    /// Whether a trusted `Authentication-Results` header reports a DMARC,
+    /// DKIM or SPF pass for the sender's domain.
+    pub fn is_authenticated(&self, email: &ParsedEmail) -> bool {
+        let Some((_, domain)) = email.from.as_deref().and_then(|from| from.rsplit_once('@')) else {
+            return false;
+        };
+        let Some(id) = &self.authserv_id else {
+            return false;
+        };
+        email
+            .authentication_results
+            .iter()
+            .filter(|results| results_authserv_id(results).eq_ignore_ascii_case(id))
+            .any(|results| vouches_for(results, domain))
+    }
+}
+
+fn results_authserv_id(results: &str) -> &str {
+    results
+        .split(';')
+        .next()
+        .and_then(|id| id.split_whitespace().next())
+        .unwrap_or_default()
+}
+
+/// RFC 8601 results for `domain`: a DMARC pass for it, or a DKIM signature
+/// or SPF check that passed for it or a parent domain.
+fn vouches_for(results: &str, domain: &str) -> bool {
+    let mut without_comments = String::with_capacity(results.len());
+    let mut depth = 0usize;
+    for c in results.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => without_comments.push(c),
+            _ => {}
+        }
+    }
+    let aligned = |value: &str| {
+        let value = value.trim_matches('"');
+        let checked = value
+            .rsplit_once('@')
+            .map_or(value, |(_, d)| d)
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        !checked.is_empty() && (checked == domain || domain.ends_with(&format!(".{checked}")))
+    };
+
+    without_comments.split(';').skip(1).any(|clause| {
+        let mut tokens = clause.split_whitespace();
+        let Some((method, verdict)) = tokens.next().and_then(|t| t.split_once('=')) else {
+            return false;
+        };
+        if !verdict.eq_ignore_ascii_case("pass") {
+            return false;
+        }
+        let props: Vec<(String, &str)> = tokens
+            .filter_map(|t| t.split_once('='))
+            .map(|(k, v)| (k.to_ascii_lowercase(), v))
+            .collect();
+        let prop = |name: &str| props.iter().find(|(k, _)| k == name).map(|(_, v)| *v);
+        match method.to_ascii_lowercase().as_str() {
+            "dmarc" => prop("header.from")
+                .is_some_and(|d| d.trim_matches('"').eq_ignore_ascii_case(domain)),
+            "dkim" => prop("header.d").or(prop("header.i")).is_some_and(aligned),
+            "spf" => prop("smtp.mailfrom").is_some_and(aligned),
+            _ => false,
+        }
+    })
+}
+
+/// Text the gateway reads as an answer to an approval prompt.
+fn is_approval_answer(text: &str) -> bool {
+    let lower = text.trim_start().to_ascii_lowercase();
+    ["/approve", "yes ", "no ", "always "]
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+}
+
+/// Adapter for converting parsed mail into internal messages.
+pub struct EmailAdapter {
+    connector_id: String,
+}
+
+impl EmailAdapter {
+    pub fn new(connector_id: impl Into<String>) -> Self {
+        Self {
+            connector_id: connector_id.into(),
+        }
+    }
+
+    pub fn to_inbound(&self, sender: &str, text: &str) -> InboundMessage {
+        InboundMessage {
+            trace_id: Uuid::new_v4(),
+            channel_type: "email".to_string(),
+            connector_id: self.connector_id.clone(),
+            conversation_scope: format!("dm:{sender}"),
+            user_scope: format!("user:{sender}"),
+            text: text.to_string(),
+            at: Utc::now(),
+            thread_id: None,
+            is_mention: false,
+            mention_target: None,
+            message_id: None,
+            attachments: vec![],
+            message_source: None,
+        }
+    }
+
+    /// Convert a received message. The subject is kept on the first message
+    /// of a thread; quoted history is dropped from replies.
+    pub fn email_to_inbound(&self, email: &ParsedEmail) -> Option<InboundMessage> {
+        let sender = email.from.as_deref()?;
+        let body = strip_quoted_reply(&email.text);
+        let text = if email.in_reply_to.is_none() && !email.subject.trim().is_empty() {
+            format!("{}\n\n{body}", email.subject.trim())
+        } else {
+            body
+        };
+        if text.trim().is_empty() && email.attachments.is_empty() {
+            return None;
+        }
+
+        let mut inbound = self.to_inbound(sender, text.trim());
+        inbound.thread_id = email.thread_root().map(str::to_owned);
+        inbound.message_id = email.message_id.clone();
+        inbound.attachments = email.attachments.iter().map(mail_attachment).collect();
+        Some(inbound)
+    }
+}
+
+fn mail_attachment(attachment: &MailAttachment) -> Attachment {
+    let mime = attachment.mime_type.as_str();
+    let kind = if mime.starts_with("image/") {
+        AttachmentKind::Image
+    } else if mime.starts_with("audio/") {
+        AttachmentKind::Audio
+    } else if mime.starts_with("video/") {
+        AttachmentKind::Video
+    } else if mime.starts_with("text/") || mime == "application/pdf" || mime == "application/json" {
+        AttachmentKind::Document
+    } else {
+        AttachmentKind::Other
+    };
+    Attachment {
+        kind,
+        url: base64::engine::general_purpose::STANDARD.encode(&attachment.data),
+        mime_type: Some(attachment.mime_type.clone()),
+        file_name: attachment.file_name.clone(),
+        size: Some(attachment.data.len() as u64),
+    }
+}
+
+/// Connection settings for the bot's mailbox.
+#[derive(Debug, Clone)]
+pub struct EmailBotConfig {
+    pub connector_id: String,
+    pub address: String,
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub imap_security: Security,
+    pub smtp: SmtpClient,
+    pub username: String,
+    pub password: String,
+    pub mailbox: String,
+    pub idle: bool,
+    pub poll_interval: Duration,
+    pub policy: SenderPolicy,
+}
+
+/// Email bot watching one IMAP mailbox.
+pub struct EmailBot {
+    config: EmailBotConfig,
+    gateway: Arc<Gateway>,
+    bus: Arc<EventBus>,
+}
+
+impl EmailBot {
+    pub fn new(config: EmailBotConfig, gateway: Arc<Gateway>, bus: Arc<EventBus>) -> Self {
+        Self {
+            config,
+            gateway,
+            bus,
+        }
+    }
+
+    pub async fn run_impl(self) -> Result<()> {
+        let (done_tx, done_rx) = mpsc::unbounded_channel();
+        let handler = Arc::new(EmailHandler {
+            adapter: EmailAdapter::new(&self.config.connector_id),
+            config: self.config,
+            gateway: self.gateway,
+            threads: Mutex::new(HashMap::new()),
+            done_tx,
+        });
+        let _listeners = [
+            AbortOnDrop(tokio::spawn(spawn_delivery_listener(
+                self.bus.subscribe(Topic::DeliverAnnounce).await,
+                handler.clone(),
+            ))),
+            AbortOnDrop(tokio::spawn(spawn_approval_listener(
+                self.bus.subscribe(Topic::DeliverApprovalRequest).await,
+                handler.clone(),
+            ))),
+        ];
+
+        let mut mail = MailboxState {
+            done_rx,
+            in_flight: HashSet::new(),
+            failures: HashMap::new(),
+        };
+        loop {
+            if let Err(e) = handler.clone().watch_mailbox(&mut mail).await {
+                tracing::warn!(
+                    target: "clawhive::channel::email",
+                    connector_id = %handler.config.connector_id,
+                    error = %e,
+                    "IMAP session ended, reconnecting in 5s..."
+                );
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    }
+}
+
+/// A turn started for a message has finished; `ok` when it succeeded.
+struct TurnDone {
+    uid: u32,
+    ok: bool,
+}
+
+/// Messages are only flagged `\Seen` once their turn succeeded, so this
+/// outlives IMAP reconnects.
+struct MailboxState {
+    done_rx: mpsc::UnboundedReceiver<TurnDone>,
+    /// UIDs whose turn is still running.
+    in_flight: HashSet<u32>,
+    /// Failed turns per UID; at [`MAX_ATTEMPTS`] the message is left unread.
+    failures: HashMap<u32, u32>,
+}
+
+impl MailboxState {
+    async fn settle(&mut self, session: &mut ImapSession, done: TurnDone) -> Result<()> {
+        self.in_flight.remove(&done.uid);
+        if done.ok {
+            self.failures.remove(&done.uid);
+            return session.mark_seen(done.uid).await;
+        }
+        let failures = self.failures.entry(done.uid).or_default();
+        *failures += 1;
+        if *failures >= MAX_ATTEMPTS {
+            tracing::error!(
+                target: "clawhive::channel::email",
+                uid = done.uid,
+                "giving up on email after {MAX_ATTEMPTS} failed turns; leaving it unread"
+            );
+        }
+        Ok(())
+    }
+
+    fn should_process(&self, uid: u32) -> bool {
+        !self.in_flight.contains(&uid)
+            && self.failures.get(&uid).copied().unwrap_or(0) < MAX_ATTEMPTS
+    }
+}
+
+/// What happened to a fetched message.
+enum Disposition {
+    /// Not for the bot (own mail, auto-replies, senders not allowed).
+    Ignored,
+    /// A turn is running; its result arrives as a [`TurnDone`].
+    Dispatched,
+}
+
+struct EmailHandler {
+    adapter: EmailAdapter,
+    config: EmailBotConfig,
+    gateway: Arc<Gateway>,
+    /// Threading headers of the latest message per sender, so announcements
+    /// and approval prompts reply into the conversation the user last wrote
+    /// in. Bounded by [`MAX_THREADS`] and [`THREAD_TTL`].
+    threads: Mutex<HashMap<String, (Instant, ParsedEmail)>>,
+    done_tx: mpsc::UnboundedSender<TurnDone>,
+}
+
+impl EmailHandler {
+    async fn watch_mailbox(self: Arc<Self>, mail: &mut MailboxState) -> Result<()> {
+        let config = &self.config;
+        let mut session = ImapSession::login(
+            &config.imap_host,
+            config.imap_port,
+            config.imap_security,
+            &config.username,
+            &config.password,
+        )
+        .await?;
+        session.select(&config.mailbox).await?;
+        let use_idle = config.idle && session.supports_idle();
+        tracing::info!(
+            target: "clawhive::channel::email",
+            connector_id = %config.connector_id,
+            mailbox = %config.mailbox,
+            idle = use_idle,
+            "watching mailbox"
+        );
+
+        loop {
+            while let Ok(done) = mail.done_rx.try_recv() {
+                mail.settle(&mut session, done).await?;
+            }
+            for uid in session.search_unseen().await? {
+                if !mail.should_process(uid) {
+                    continue;
+                }
+                let Some(raw) = session.fetch(uid).await? else {
+                    continue;
+                };
+                match self.clone().handle_email(uid, parse_email(&raw)).await {
+                    Disposition::Ignored => session.mark_seen(uid).await?,
+                    Disposition::Dispatched => {
+                        mail.in_flight.insert(uid);
+                    }
+                }
+            }
+
+            // Wake up early when a turn finishes so its message is flagged.
+            let finished = if use_idle {
+                let mut finished = None;
+                session
+                    .idle(IDLE_REFRESH, async {
+                        finished = mail.done_rx.recv().await;
+                    })
+                    .await?;
+                finished
+            } else {
+                tokio::time::timeout(config.poll_interval, mail.done_rx.recv())
+                    .await
+                    .ok()
+                    .flatten()
+            };
+            if let Some(done) = finished {
+                mail.settle(&mut session, done).await?;
+            }
+        }
+    }
+
+    async fn handle_email(self: Arc<Self>, uid: u32, email: ParsedEmail) -> Disposition {
+        let Some(sender) = email.from.clone() else {
+            return Disposition::Ignored;
+        };
+        if sender.eq_ignore_ascii_case(&self.config.address) || email.auto_generated {
+            return Disposition::Ignored;
+        }
+        let policy = &self.config.policy;
+        let authenticated = policy.is_authenticated(&email);
+        if !policy.is_allowed(&sender, authenticated) {
+            tracing::debug!(
+                target: "clawhive::channel::email",
+                sender = %sender,
+                authenticated,
+                dm_policy = %policy.dm_policy,
+                "ignoring email from sender not allowed by dm_policy"
+            );
+            return Disposition::Ignored;
+        }
+        let Some(inbound) = self.adapter.email_to_inbound(&email) else {
+            return Disposition::Ignored;
+        };
+        if !authenticated && is_approval_answer(&inbound.text) {
+            tracing::warn!(
+                target: "clawhive::channel::email",
+                sender = %sender,
+                "ignoring approval answer from unauthenticated sender"
+            );
+            return Disposition::Ignored;
+        }
+        self.remember_thread(&sender, &email).await;
+
+        tokio::spawn(async move {
+            let ok = match self.gateway.handle_inbound(inbound).await {
+                Ok(Some(outbound)) => {
+                    if !outbound.text.trim().is_empty() {
+                        self.reply(&email, &outbound.text).await;
+                    }
+                    true
+                }
+                Ok(None) => true,
+                Err(e) => {
+                    tracing::error!("Failed to handle email from {sender}: {e}");
+                    false
+                }
+            };
+            let _ = self.done_tx.send(TurnDone { uid, ok });
+        });
+        Disposition::Dispatched
+    }
+
+    async fn remember_thread(&self, sender: &str, email: &ParsedEmail) {
+        let now = Instant::now();
+        let mut threads = self.threads.lock().await;
+        threads.retain(|_, (seen, _)| now.duration_since(*seen) < THREAD_TTL);
+        if threads.len() >= MAX_THREADS && !threads.contains_key(sender) {
+            let oldest = threads
+                .iter()
+                .min_by_key(|(_, (seen, _))| *seen)
+                .map(|(address, _)| address.clone());
+            if let Some(oldest) = oldest {
+                threads.remove(&oldest);
+            }
+        }
+        threads.insert(sender.to_string(), (now, email.thread_headers()));
+    }
+
+    async fn reply(&self, original: &ParsedEmail, text: &str) {
+        let Some(reply) = OutgoingEmail::reply_to(&self.config.address, original, text) else {
+            return;
+        };
+        self.send(reply).await;
+    }
+
+    /// Send to `address`, threading onto its latest message when there is one.
+    async fn send_to(&self, address: &str, subject: &str, text: &str) {
+        let latest = self
+            .threads
+            .lock()
+            .await
+            .get(address)
+            .filter(|(seen, _)| seen.elapsed() < THREAD_TTL)
+            .map(|(_, email)| email.clone());
+        match latest {
+            Some(original) => self.reply(&original, text).await,
+            None => {
+                self.send(OutgoingEmail {
+                    from: self.config.address.clone(),
+                    to: address.to_string(),
+                    subject: subject.to_string(),
+                    body: text.to_string(),
+                    ..Default::default()
+                })
+                .await
+            }
+        }
+    }
+
+    async fn send(&self, email: OutgoingEmail) {
+        if let Err(e) = self.config.smtp.send(&email).await {
+            tracing::error!("Failed to send email to {}: {e:#}", email.to);
+        }
+    }
+}
+
+async fn spawn_delivery_listener(mut rx: mpsc::Receiver<BusMessage>, handler: Arc<EmailHandler>) {
+    while let Some(msg) = rx.recv().await {
+        let BusMessage::DeliverAnnounce {
+            channel_type,
+            connector_id,
+            conversation_scope,
+            text,
+        } = msg
+        else {
+            continue;
+        };
+        if channel_type != "email" || connector_id != handler.config.connector_id {
+            continue;
+        }
+        let Some(address) = conversation_scope.strip_prefix("dm:") else {
+            tracing::warn!(
+                "Could not parse email address from conversation_scope: {}",
+                conversation_scope
+            );
+            continue;
+        };
+        handler
+            .send_to(address, &format!("Message from {connector_id}"), &text)
+            .await;
+    }
+}
+
+/// Approval prompts go out as mail; answering with the `/approve` line in the
+/// reply body resolves them through the normal inbound path.
+async fn spawn_approval_listener(mut rx: mpsc::Receiver<BusMessage>, handler: Arc<EmailHandler>) {
+    while let Some(msg) = rx.recv().await {
+        let BusMessage::DeliverApprovalRequest {
+            channel_type,
+            connector_id,
+            conversation_scope,
+            short_id,
+            agent_id,
+            command,
+            network_target,
+            summary,
+        } = msg
+        else {
+            continue;
+        };
+        if channel_type != "email" || connector_id != handler.config.connector_id {
+            continue;
+        }
+        let Some(address) = conversation_scope.strip_prefix("dm:") else {
+            continue;
+        };
+        let display = ApprovalDisplay::new(&agent_id, &command, network_target.as_deref(), summary);
+        let text = format!(
+            "{}\n\nReply with one of these lines:\n\n/approve {short_id} allow\n/approve {short_id} always\n/approve {short_id} deny",
+            display.to_markdown()
+        );
+        handler
+            .send_to(address, &format!("Approval needed: {agent_id}"), &text)
+            .await;
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ChannelBot for EmailBot {
+    fn channel_type(&self) -> &str {
+        "email"
+    }
+
+    fn connector_id(&self) -> &str {
+        &self.config.connector_id
+    }
+
+    async fn run(self: Box<Self>) -> Result<()> {
+        self.run_impl().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sender_policy_matches_addresses_and_domains() {
+        let policy = SenderPolicy::from_config(
+            "allowlist",
+            &[" Alice@Example.com ".into(), "@corp.example".into()],
+            None,
+        );
+        assert!(policy.is_allowed("alice@example.com", true));
+        assert!(policy.is_allowed("BOB@corp.example", true));
+        assert!(!policy.is_allowed("alice@example.com", false));
+        assert!(!policy.is_allowed("mallory@example.com", true));
+        assert!(!policy.is_allowed("bob@evilcorp.example.net", true));
+
+        assert!(SenderPolicy::from_config("open", &[], None).is_allowed("anyone@x.org", false));
+        assert!(
+            !SenderPolicy::from_config("disabled", &["a@b.c".into()], None)
+                .is_allowed("a@b.c", true)
+        );
+    }
+
+    fn with_results(from: &str, results: &[&str]) -> ParsedEmail {
+        ParsedEmail {
+            from: Some(from.to_string()),
+            authentication_results: results.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn authentication_requires_a_trusted_aligned_pass() {
+        let policy = SenderPolicy::from_config("allowlist", &[], Some("mx.local"));
+        assert!(policy.is_authenticated(&with_results(
+            "alice@example.com",
+            &["mx.local; dkim=pass (good signature) header.d=example.com; spf=fail"]
+        )));
+        assert!(policy.is_authenticated(&with_results(
+            "alice@mail.example.com",
+            &["mx.local; spf=pass smtp.mailfrom=bounce@example.com"]
+        )));
+        assert!(policy.is_authenticated(&with_results(
+            "alice@example.com",
+            &["mx.local; dmarc=pass header.from=example.com"]
+        )));
+        // Forged From: signed by someone else's domain.
+        assert!(!policy.is_authenticated(&with_results(
+            "alice@example.com",
+            &["mx.local; dkim=pass header.d=attacker.example"]
+        )));
+        assert!(!policy.is_authenticated(&with_results(
+            "alice@example.com",
+            &["mx.local; dkim=fail header.d=example.com"]
+        )));
+        assert!(!policy.is_authenticated(&with_results("alice@example.com", &[])));
+        assert!(policy.is_authenticated(&with_results(
+            "alice@example.com",
+            &[
+                "relay.example; dkim=none",
+                "mx.local; dkim=pass header.d=example.com"
+            ]
+        )));
+        assert!(!policy.is_authenticated(&with_results(
+            "alice@example.com",
+            &["forged.example; dkim=pass header.d=example.com"]
+        )));
+    }
+
+    #[test]
+    fn sender_supplied_results_do_not_authenticate_without_authserv_id() {
+        // The receiving server added no header of its own, so the only one
+        // present came with the message.
+        let email = with_results(
+            "alice@example.com",
+            &["mx.example.com; dkim=pass header.d=example.com"],
+        );
+        let policy = SenderPolicy::from_config("allowlist", &["@example.com".into()], None);
+        assert!(!policy.is_authenticated(&email));
+        assert!(!policy.is_allowed("alice@example.com", policy.is_authenticated(&email)));
+    }
+
+    #[test]
+    fn recognises_approval_answers() {
+        assert!(is_approval_answer("/approve ab12 allow"));
+        assert!(is_approval_answer("  YES ab12"));
+        assert!(!is_approval_answer("Status?\n\n/approve ab12 allow"));
+        assert!(!is_approval_answer("yesterday went fine"));
+    }
+
+    #[test]
+    fn email_to_inbound_threads_and_keeps_subject_on_new_threads() {
+        let adapter = EmailAdapter::new("mail");
+        let first = parse_email(
+            b"From: Alice <alice@example.com>\r\nSubject: Quarterly numbers\r\nMessage-ID: <a1@example.com>\r\n\r\nCan you summarise?\r\n",
+        );
+        let inbound = adapter.email_to_inbound(&first).unwrap();
+        assert_eq!(inbound.channel_type, "email");
+        assert_eq!(inbound.conversation_scope, "dm:alice@example.com");
+        assert_eq!(inbound.user_scope, "user:alice@example.com");
+        assert_eq!(inbound.text, "Quarterly numbers\n\nCan you summarise?");
+        assert_eq!(inbound.thread_id.as_deref(), Some("<a1@example.com>"));
+        assert_eq!(inbound.message_id.as_deref(), Some("<a1@example.com>"));
+
+        let reply = parse_email(
+            b"From: alice@example.com\r\nSubject: Re: Quarterly numbers\r\nMessage-ID: <a3@example.com>\r\nIn-Reply-To: <b2@bot.example>\r\nReferences: <a1@example.com> <b2@bot.example>\r\n\r\nThanks, more detail please.\r\n\r\n> Revenue was up.\r\n",
+        );
+        let inbound = adapter.email_to_inbound(&reply).unwrap();
+        assert_eq!(inbound.text, "Thanks, more detail please.");
+        assert_eq!(inbound.thread_id.as_deref(), Some("<a1@example.com>"));
+    }
+
+    #[test]
+    fn mail_attachments_map_to_attachment_kinds() {
+        let attachment = mail_attachment(&MailAttachment {
+            file_name: Some("photo.jpg".into()),
+            mime_type: "image/jpeg".into(),
+            data: vec![1, 2, 3],
+        });
+        assert_eq!(attachment.kind, AttachmentKind::Image);
+        assert_eq!(attachment.url, "AQID");
+        assert_eq!(attachment.size, Some(3));
+    }
+}
@@ -0,0 +1,65 @@
+//! SMTP submission through `lettre`.
+
+use anyhow::{Context as _, Result};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::transport::smtp::extension::ClientId;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use tokio::time::Duration;
+
+use super::mime::OutgoingEmail;
+use super::net::Security;
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+pub struct SmtpClient {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl std::fmt::Debug for SmtpClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtpClient").finish_non_exhaustive()
+    }
+}
+
+impl SmtpClient {
+    pub fn new(
+        host: impl Into<String>,
+        port: u16,
+        security: Security,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Result<Self> {
+        let host = host.into();
+        let username = username.into();
+        let tls = match security {
+            Security::None => Tls::None,
+            Security::Tls => Tls::Wrapper(TlsParameters::new_rustls(host.clone())?),
+            Security::StartTls => Tls::Required(TlsParameters::new_rustls(host.clone())?),
+        };
+        let hello = username
+            .rsplit_once('@')
+            .map(|(_, domain)| ClientId::Domain(domain.to_string()))
+            .unwrap_or_default();
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+            .port(port)
+            .tls(tls)
+            .hello_name(hello)
+            .timeout(Some(SMTP_TIMEOUT));
+        if !username.is_empty() {
+            builder = builder.credentials(Credentials::new(username, password.into()));
+        }
+        Ok(Self {
+            transport: builder.build(),
+        })
+    }
+
+    pub async fn send(&self, email: &OutgoingEmail) -> Result<()> {
+        self.transport
+            .send(email.to_message()?)
+            .await
+            .with_context(|| format!("failed to send email to {}", email.to))?;
+        Ok(())
+    }
+}
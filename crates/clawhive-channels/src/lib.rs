@@ -19,6 +19,12 @@ pub mod slack;
 #[cfg(feature = "imessage")]
 pub mod imessage;
 
+#[cfg(feature = "matrix")]
+pub mod matrix;
+
+#[cfg(feature = "email")]
+pub mod email;
+
 #[cfg(feature = "whatsapp")]
 pub mod whatsapp;
 
@@ -76,6 +82,7 @@ mod tests {
         let discord = include_str!("discord.rs");
         let telegram = include_str!("telegram.rs");
         let slack = include_str!("slack.rs");
+        let matrix = include_str!("matrix.rs");
         let whatsapp = include_str!("whatsapp.rs");
         let dingtalk = include_str!("dingtalk.rs");
         let wecom = include_str!("wecom.rs");
@@ -84,7 +91,7 @@ mod tests {
         let feishu = include_str!("feishu/bot.rs");
 
         for source in [
-            discord, telegram, slack, whatsapp, dingtalk, wecom, imessage, weixin, feishu,
+            discord, telegram, slack, matrix, whatsapp, dingtalk, wecom, imessage, weixin, feishu,
         ] {
             assert!(source.contains("resolve_turn_lifecycle(&inbound)"));
         }
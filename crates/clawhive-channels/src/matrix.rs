@@ -0,0 +1,1444 @@
+//! Matrix channel integration using the client-server API.
+//!
+//! The bot logs in as a regular user with an access token and long-polls
+//! `/sync`, resuming from the last `next_batch` token across restarts. Rooms
+//! listed in the account's `m.direct` data (or joined from an `is_direct`
+//! invite) are routed as DMs, everything else as groups. End-to-end encrypted
+//! rooms are not supported; encrypted events are skipped.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use chrono::Utc;
+use clawhive_bus::{EventBus, Topic};
+use clawhive_gateway::Gateway;
+use clawhive_schema::{
+    ActionKind, ApprovalDisplay, Attachment, AttachmentKind, BusMessage, InboundMessage,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, Mutex, Notify};
+use tokio::time::Duration;
+use uuid::Uuid;
+
+use crate::common::{infer_mime_from_filename, AbortOnDrop, PROGRESS_MESSAGE};
+
+/// How long a single `/sync` request waits for new events.
+const SYNC_TIMEOUT_MS: u64 = 30_000;
+
+/// Room event types the bot reads from sync timelines.
+const TIMELINE_EVENT_TYPES: [&str; 3] = ["m.room.message", "m.reaction", "m.room.encrypted"];
+
+/// Reactions offered on approval prompts, in button order.
+const APPROVAL_REACTIONS: [(&str, &str); 3] = [("✅", "allow"), ("🔓", "always"), ("❌", "deny")];
+
+/// Adapter for converting between Matrix and internal message formats.
+pub struct MatrixAdapter {
+    connector_id: String,
+}
+
+impl MatrixAdapter {
+    pub fn new(connector_id: impl Into<String>) -> Self {
+        Self {
+            connector_id: connector_id.into(),
+        }
+    }
+
+    pub fn to_inbound(
+        &self,
+        room_id: &str,
+        sender: &str,
+        text: &str,
+        is_dm: bool,
+        event_id: Option<&str>,
+    ) -> InboundMessage {
+        let conversation_scope = if is_dm {
+            format!("dm:{room_id}")
+        } else {
+            format!("group:{room_id}")
+        };
+        InboundMessage {
+            trace_id: Uuid::new_v4(),
+            channel_type: "matrix".to_string(),
+            connector_id: self.connector_id.clone(),
+            conversation_scope,
+            user_scope: format!("user:{sender}"),
+            text: text.to_string(),
+            at: Utc::now(),
+            thread_id: None,
+            is_mention: false,
+            mention_target: None,
+            message_id: event_id.map(str::to_owned),
+            attachments: vec![],
+            message_source: None,
+        }
+    }
+
+    /// Convert an `m.room.message` timeline event. Returns `None` for events
+    /// the bot should not answer: its own messages, notices (the bot
+    /// convention) and edits.
+    pub fn event_to_inbound(
+        &self,
+        room_id: &str,
+        event: &MatrixEvent,
+        bot_user_id: &str,
+        is_dm: bool,
+    ) -> Option<InboundMessage> {
+        if event.kind != "m.room.message" || event.sender == bot_user_id {
+            return None;
+        }
+        let content = &event.content;
+        let relates_to = &content["m.relates_to"];
+        if relates_to["rel_type"] == "m.replace" {
+            return None;
+        }
+
+        let msgtype = content["msgtype"].as_str()?;
+        let body = content["body"].as_str().unwrap_or_default();
+        let (text, attachment) = match msgtype {
+            "m.text" | "m.emote" => (body.to_string(), None),
+            "m.image" | "m.file" | "m.audio" | "m.video" => {
+                // `body` is a caption only when a separate filename is given.
+                let file_name = content["filename"].as_str().unwrap_or(body);
+                let caption = if content["filename"].is_string() && file_name != body {
+                    body.to_string()
+                } else {
+                    String::new()
+                };
+                (caption, media_attachment(msgtype, content, file_name))
+            }
+            _ => return None,
+        };
+
+        let mentioned_by_id = content["m.mentions"]["user_ids"]
+            .as_array()
+            .is_some_and(|ids| ids.iter().any(|id| id == bot_user_id));
+        let is_mention = mentioned_by_id
+            || text.contains(bot_user_id)
+            || content["formatted_body"]
+                .as_str()
+                .is_some_and(|html| html.contains(&format!("matrix.to/#/{bot_user_id}")));
+        let text = text.replace(bot_user_id, "");
+        let text = text.trim().trim_start_matches(':').trim();
+        if text.is_empty() && attachment.is_none() {
+            return None;
+        }
+
+        let mut inbound =
+            self.to_inbound(room_id, &event.sender, text, is_dm, Some(&event.event_id));
+        if relates_to["rel_type"] == "m.thread" {
+            inbound.thread_id = relates_to["event_id"].as_str().map(str::to_owned);
+        }
+        inbound.is_mention = is_mention;
+        inbound.mention_target = is_mention.then(|| bot_user_id.to_string());
+        inbound.attachments = attachment.into_iter().collect();
+        Some(inbound)
+    }
+}
+
+fn media_attachment(msgtype: &str, content: &Value, file_name: &str) -> Option<Attachment> {
+    let url = content["url"].as_str()?.to_string();
+    let info = &content["info"];
+    let mime_type = info["mimetype"]
+        .as_str()
+        .map(str::to_owned)
+        .or_else(|| infer_mime_from_filename(Some(file_name)));
+    let kind = match msgtype {
+        "m.image" => AttachmentKind::Image,
+        "m.video" => AttachmentKind::Video,
+        "m.audio" => AttachmentKind::Audio,
+        _ => match mime_type.as_deref() {
+            Some(mime)
+                if mime.starts_with("text/")
+                    || mime == "application/pdf"
+                    || mime == "application/json" =>
+            {
+                AttachmentKind::Document
+            }
+            _ => AttachmentKind::Other,
+        },
+    };
+    Some(Attachment {
+        kind,
+        url,
+        mime_type,
+        file_name: Some(file_name.to_string()),
+        size: info["size"].as_u64(),
+    })
+}
+
+fn parse_room_id(conversation_scope: &str) -> Option<&str> {
+    conversation_scope
+        .strip_prefix("dm:")
+        .or_else(|| conversation_scope.strip_prefix("group:"))
+        .filter(|id| !id.is_empty())
+}
+
+/// Inline `/sync` filter: only the timeline events the bot handles, no
+/// presence or receipts, and with `initial` no timeline at all.
+fn sync_filter(initial: bool) -> Value {
+    let mut timeline = json!({ "types": TIMELINE_EVENT_TYPES });
+    if initial {
+        timeline["limit"] = json!(0);
+    }
+    json!({
+        "presence": { "types": [] },
+        "account_data": { "types": ["m.direct"] },
+        "room": {
+            "timeline": timeline,
+            "state": { "lazy_load_members": true },
+            "ephemeral": { "types": [] },
+            "account_data": { "types": [] },
+        },
+    })
+}
+
+/// The `next_batch` token saved by the previous run, if any.
+fn load_sync_token(path: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str::<Value>(&contents).ok()?["next_batch"]
+        .as_str()
+        .filter(|token| !token.is_empty())
+        .map(str::to_owned)
+}
+
+fn save_sync_token(path: &Path, token: &str) {
+    let json = json!({ "next_batch": token });
+    if let Err(e) = std::fs::write(path, json.to_string()) {
+        tracing::warn!(
+            target: "clawhive::channel::matrix",
+            error = %e,
+            "failed to save matrix sync token"
+        );
+    }
+}
+
+/// Percent-encode a room or event ID for use as a path segment.
+fn encode_path(segment: &str) -> String {
+    url::form_urlencoded::byte_serialize(segment.as_bytes()).collect()
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MatrixEvent {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub event_id: String,
+    #[serde(default)]
+    pub sender: String,
+    #[serde(default)]
+    pub state_key: Option<String>,
+    #[serde(default)]
+    pub content: Value,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventList {
+    #[serde(default)]
+    pub events: Vec<MatrixEvent>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct JoinedRoom {
+    #[serde(default)]
+    pub timeline: EventList,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct InvitedRoom {
+    #[serde(default)]
+    pub invite_state: EventList,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SyncRooms {
+    #[serde(default)]
+    pub join: HashMap<String, JoinedRoom>,
+    #[serde(default)]
+    pub invite: HashMap<String, InvitedRoom>,
+}
+
+/// The parts of a `/sync` response the bot uses.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SyncResponse {
+    pub next_batch: String,
+    #[serde(default)]
+    pub rooms: SyncRooms,
+    #[serde(default)]
+    pub account_data: EventList,
+}
+
+/// Something in a sync batch the bot has to act on.
+#[derive(Debug, Clone)]
+pub enum MatrixIncoming {
+    Message {
+        room_id: String,
+        inbound: InboundMessage,
+    },
+    Reaction {
+        room_id: String,
+        sender: String,
+        target_event_id: String,
+        key: String,
+    },
+    Invite {
+        room_id: String,
+        inviter: String,
+        /// The inviter marked the room as a direct chat.
+        is_direct: bool,
+    },
+}
+
+/// Tracks which rooms are DMs across syncs. `m.direct` only arrives in a sync
+/// when it changes, so the bot loads it once at start-up as well.
+#[derive(Debug, Default)]
+pub struct RoomDirectory {
+    direct: HashSet<String>,
+}
+
+impl RoomDirectory {
+    pub fn is_dm(&self, room_id: &str) -> bool {
+        self.direct.contains(room_id)
+    }
+
+    /// Add the rooms of an `m.direct` map (user ID → room IDs).
+    pub fn add_direct(&mut self, content: &Value) {
+        if let Some(map) = content.as_object() {
+            self.direct.extend(
+                map.values()
+                    .filter_map(Value::as_array)
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(str::to_owned),
+            );
+        }
+    }
+
+    /// Fold a sync batch into the directory and pull out what needs handling.
+    /// The first sync after start-up (`initial`) only updates the directory so
+    /// history is not answered again.
+    pub fn absorb(
+        &mut self,
+        sync: &SyncResponse,
+        adapter: &MatrixAdapter,
+        bot_user_id: &str,
+        initial: bool,
+    ) -> Vec<MatrixIncoming> {
+        for event in &sync.account_data.events {
+            if event.kind == "m.direct" {
+                self.add_direct(&event.content);
+            }
+        }
+
+        let mut incoming = Vec::new();
+        for (room_id, room) in &sync.rooms.invite {
+            let Some(invite) =
+                room.invite_state.events.iter().find(|e| {
+                    e.kind == "m.room.member" && e.state_key.as_deref() == Some(bot_user_id)
+                })
+            else {
+                continue;
+            };
+            let is_direct = invite.content["is_direct"] == true;
+            if is_direct {
+                self.direct.insert(room_id.clone());
+            }
+            incoming.push(MatrixIncoming::Invite {
+                room_id: room_id.clone(),
+                inviter: invite.sender.clone(),
+                is_direct,
+            });
+        }
+
+        for (room_id, room) in &sync.rooms.join {
+            if initial {
+                continue;
+            }
+            let is_dm = self.is_dm(room_id);
+            for event in &room.timeline.events {
+                match event.kind.as_str() {
+                    "m.reaction" if event.sender != bot_user_id => {
+                        let relates_to = &event.content["m.relates_to"];
+                        if let (Some(target), Some(key)) =
+                            (relates_to["event_id"].as_str(), relates_to["key"].as_str())
+                        {
+                            incoming.push(MatrixIncoming::Reaction {
+                                room_id: room_id.clone(),
+                                sender: event.sender.clone(),
+                                target_event_id: target.to_string(),
+                                key: key.to_string(),
+                            });
+                        }
+                    }
+                    "m.room.encrypted" => {
+                        tracing::debug!(
+                            target: "clawhive::channel::matrix",
+                            room_id = %room_id,
+                            "skipping encrypted event"
+                        );
+                    }
+                    _ => {
+                        if let Some(inbound) =
+                            adapter.event_to_inbound(room_id, event, bot_user_id, is_dm)
+                        {
+                            incoming.push(MatrixIncoming::Message {
+                                room_id: room_id.clone(),
+                                inbound,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        incoming
+    }
+}
+
+/// Minimal Matrix client-server API client.
+#[derive(Clone)]
+pub struct MatrixClient {
+    http: reqwest::Client,
+    homeserver_url: String,
+    access_token: String,
+}
+
+impl MatrixClient {
+    pub fn new(homeserver_url: impl Into<String>, access_token: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            homeserver_url: homeserver_url.into().trim_end_matches('/').to_string(),
+            access_token: access_token.into(),
+        }
+    }
+
+    async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+        timeout: Duration,
+    ) -> Result<Value> {
+        let mut request = self
+            .http
+            .request(method, format!("{}{path}", self.homeserver_url))
+            .bearer_auth(&self.access_token)
+            .timeout(timeout);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("matrix request {path} failed"))?;
+        let status = response.status();
+        let value: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            bail!(
+                "matrix {path} returned {status}: {} {}",
+                value["errcode"].as_str().unwrap_or_default(),
+                value["error"].as_str().unwrap_or_default()
+            );
+        }
+        Ok(value)
+    }
+
+    /// Returns the user ID the access token belongs to.
+    pub async fn whoami(&self) -> Result<String> {
+        let value = self
+            .request(
+                reqwest::Method::GET,
+                "/_matrix/client/v3/account/whoami",
+                None,
+                Duration::from_secs(30),
+            )
+            .await?;
+        value["user_id"]
+            .as_str()
+            .map(str::to_owned)
+            .ok_or_else(|| anyhow!("matrix whoami returned no user_id"))
+    }
+
+    /// Long-poll `/sync`. Every request carries [`sync_filter`]; without
+    /// `since` the timelines are left out, as that history is not answered.
+    pub async fn sync(&self, since: Option<&str>, timeout_ms: u64) -> Result<SyncResponse> {
+        let filter = sync_filter(since.is_none()).to_string();
+        let mut path = format!(
+            "/_matrix/client/v3/sync?timeout={timeout_ms}&filter={}",
+            encode_path(&filter)
+        );
+        if let Some(since) = since {
+            path.push_str(&format!("&since={}", encode_path(since)));
+        }
+        let value = self
+            .request(
+                reqwest::Method::GET,
+                &path,
+                None,
+                Duration::from_millis(timeout_ms) + Duration::from_secs(30),
+            )
+            .await?;
+        serde_json::from_value(value).context("invalid matrix sync response")
+    }
+
+    /// Read a global account data event, or `None` if it was never set.
+    pub async fn account_data(&self, user_id: &str, kind: &str) -> Result<Option<Value>> {
+        let path = format!(
+            "/_matrix/client/v3/user/{}/account_data/{kind}",
+            encode_path(user_id)
+        );
+        let response = self
+            .http
+            .get(format!("{}{path}", self.homeserver_url))
+            .bearer_auth(&self.access_token)
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .with_context(|| format!("matrix request {path} failed"))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let status = response.status();
+        let value: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            bail!(
+                "matrix {path} returned {status}: {} {}",
+                value["errcode"].as_str().unwrap_or_default(),
+                value["error"].as_str().unwrap_or_default()
+            );
+        }
+        Ok(Some(value))
+    }
+
+    pub async fn set_account_data(&self, user_id: &str, kind: &str, content: Value) -> Result<()> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!(
+                "/_matrix/client/v3/user/{}/account_data/{kind}",
+                encode_path(user_id)
+            ),
+            Some(content),
+            Duration::from_secs(30),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Record `room_id` as the direct chat with `peer` in `m.direct`, the way
+    /// clients do when accepting a DM invite, so it stays a DM after restarts.
+    pub async fn mark_direct(&self, user_id: &str, peer: &str, room_id: &str) -> Result<()> {
+        let mut direct = self
+            .account_data(user_id, "m.direct")
+            .await?
+            .filter(Value::is_object)
+            .unwrap_or_else(|| json!({}));
+        if let Some(rooms) = direct[peer].as_array_mut() {
+            if rooms.iter().any(|r| r == room_id) {
+                return Ok(());
+            }
+            rooms.push(json!(room_id));
+        } else {
+            direct[peer] = json!([room_id]);
+        }
+        self.set_account_data(user_id, "m.direct", direct).await
+    }
+
+    pub async fn join(&self, room_id: &str) -> Result<()> {
+        self.request(
+            reqwest::Method::POST,
+            &format!("/_matrix/client/v3/join/{}", encode_path(room_id)),
+            Some(json!({})),
+            Duration::from_secs(30),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Send a room event and return its event ID.
+    pub async fn send_event(
+        &self,
+        room_id: &str,
+        event_type: &str,
+        content: Value,
+    ) -> Result<String> {
+        let value = self
+            .request(
+                reqwest::Method::PUT,
+                &format!(
+                    "/_matrix/client/v3/rooms/{}/send/{event_type}/{}",
+                    encode_path(room_id),
+                    Uuid::new_v4()
+                ),
+                Some(content),
+                Duration::from_secs(30),
+            )
+            .await?;
+        Ok(value["event_id"].as_str().unwrap_or_default().to_string())
+    }
+
+    /// Send a text message, inside a thread when `thread_root` is set.
+    pub async fn send_text(
+        &self,
+        room_id: &str,
+        text: &str,
+        thread_root: Option<&str>,
+        reply_to: Option<&str>,
+    ) -> Result<String> {
+        let mut content = json!({ "msgtype": "m.text", "body": text });
+        if let Some(root) = thread_root {
+            content["m.relates_to"] = json!({
+                "rel_type": "m.thread",
+                "event_id": root,
+                "is_falling_back": true,
+                "m.in_reply_to": { "event_id": reply_to.unwrap_or(root) },
+            });
+        }
+        self.send_event(room_id, "m.room.message", content).await
+    }
+
+    pub async fn send_html(&self, room_id: &str, body: &str, html: &str) -> Result<String> {
+        self.send_event(
+            room_id,
+            "m.room.message",
+            json!({
+                "msgtype": "m.text",
+                "body": body,
+                "format": "org.matrix.custom.html",
+                "formatted_body": html,
+            }),
+        )
+        .await
+    }
+
+    /// Replace the text of an earlier message.
+    pub async fn edit(&self, room_id: &str, event_id: &str, new_text: &str) -> Result<String> {
+        self.send_event(
+            room_id,
+            "m.room.message",
+            json!({
+                "msgtype": "m.text",
+                "body": format!("* {new_text}"),
+                "m.new_content": { "msgtype": "m.text", "body": new_text },
+                "m.relates_to": { "rel_type": "m.replace", "event_id": event_id },
+            }),
+        )
+        .await
+    }
+
+    pub async fn react(&self, room_id: &str, event_id: &str, key: &str) -> Result<String> {
+        self.send_event(
+            room_id,
+            "m.reaction",
+            json!({
+                "m.relates_to": { "rel_type": "m.annotation", "event_id": event_id, "key": key },
+            }),
+        )
+        .await
+    }
+
+    pub async fn redact(&self, room_id: &str, event_id: &str) -> Result<()> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!(
+                "/_matrix/client/v3/rooms/{}/redact/{}/{}",
+                encode_path(room_id),
+                encode_path(event_id),
+                Uuid::new_v4()
+            ),
+            Some(json!({})),
+            Duration::from_secs(30),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_typing(&self, room_id: &str, user_id: &str, typing: bool) -> Result<()> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!(
+                "/_matrix/client/v3/rooms/{}/typing/{}",
+                encode_path(room_id),
+                encode_path(user_id)
+            ),
+            Some(json!({ "typing": typing, "timeout": 30_000 })),
+            Duration::from_secs(30),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Download an `mxc://server/media-id` URI through the authenticated
+    /// media API.
+    pub async fn download(&self, mxc: &str) -> Result<Vec<u8>> {
+        let Some((server, media_id)) = mxc
+            .strip_prefix("mxc://")
+            .and_then(|rest| rest.split_once('/'))
+        else {
+            bail!("not an mxc URI: {mxc}");
+        };
+        let response = self
+            .http
+            .get(format!(
+                "{}/_matrix/client/v1/media/download/{}/{}",
+                self.homeserver_url,
+                encode_path(server),
+                encode_path(media_id)
+            ))
+            .bearer_auth(&self.access_token)
+            .timeout(Duration::from_secs(60))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// Matrix bot driven by the `/sync` long-poll.
+pub struct MatrixBot {
+    connector_id: String,
+    client: MatrixClient,
+    gateway: Arc<Gateway>,
+    bus: Arc<EventBus>,
+    require_mention: bool,
+    auto_join: bool,
+    allow_from: Vec<String>,
+    data_dir: Option<PathBuf>,
+}
+
+impl MatrixBot {
+    pub fn new(
+        homeserver_url: impl Into<String>,
+        access_token: impl Into<String>,
+        connector_id: impl Into<String>,
+        gateway: Arc<Gateway>,
+        bus: Arc<EventBus>,
+    ) -> Self {
+        Self {
+            connector_id: connector_id.into(),
+            client: MatrixClient::new(homeserver_url, access_token),
+            gateway,
+            bus,
+            require_mention: true,
+            auto_join: true,
+            allow_from: Vec::new(),
+            data_dir: None,
+        }
+    }
+
+    pub fn with_require_mention(mut self, require: bool) -> Self {
+        self.require_mention = require;
+        self
+    }
+
+    pub fn with_auto_join(mut self, auto_join: bool) -> Self {
+        self.auto_join = auto_join;
+        self
+    }
+
+    /// Only answer (and accept invites from) these Matrix user IDs. Empty
+    /// means everyone.
+    pub fn with_allow_from(mut self, allow_from: Vec<String>) -> Self {
+        self.allow_from = allow_from;
+        self
+    }
+
+    /// Keep the sync token in `data_dir/sync.json` so a restart picks up the
+    /// messages that arrived while the bot was down.
+    pub fn with_data_dir(mut self, data_dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(data_dir.into());
+        self
+    }
+
+    pub async fn run_impl(self) -> Result<()> {
+        let bot_user_id = self.client.whoami().await?;
+        tracing::info!(
+            target: "clawhive::channel::matrix",
+            connector_id = %self.connector_id,
+            user_id = %bot_user_id,
+            "matrix bot authenticated"
+        );
+
+        let handler = Arc::new(MatrixHandler {
+            adapter: MatrixAdapter::new(&self.connector_id),
+            connector_id: self.connector_id.clone(),
+            client: self.client.clone(),
+            gateway: self.gateway.clone(),
+            bot_user_id,
+            require_mention: self.require_mention,
+            auto_join: self.auto_join,
+            allow_from: self.allow_from,
+            pending_approvals: Mutex::new(HashMap::new()),
+            sent_reactions: Mutex::new(HashMap::new()),
+        });
+
+        let _listeners = [
+            AbortOnDrop(tokio::spawn(spawn_delivery_listener(
+                self.bus.subscribe(Topic::DeliverAnnounce).await,
+                handler.clone(),
+            ))),
+            AbortOnDrop(tokio::spawn(spawn_approval_listener(
+                self.bus.subscribe(Topic::DeliverApprovalRequest).await,
+                handler.clone(),
+            ))),
+            AbortOnDrop(tokio::spawn(spawn_action_listener(
+                self.bus.subscribe(Topic::ActionReady).await,
+                handler.clone(),
+            ))),
+        ];
+
+        let mut directory = RoomDirectory::default();
+        match self
+            .client
+            .account_data(&handler.bot_user_id, "m.direct")
+            .await
+        {
+            Ok(Some(direct)) => directory.add_direct(&direct),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(
+                target: "clawhive::channel::matrix",
+                error = %e,
+                "failed to load matrix m.direct rooms"
+            ),
+        }
+
+        let sync_path = self.data_dir.as_ref().map(|dir| dir.join("sync.json"));
+        let mut since = sync_path.as_deref().and_then(load_sync_token);
+        let mut initial = since.is_none();
+        loop {
+            let sync = match self.client.sync(since.as_deref(), SYNC_TIMEOUT_MS).await {
+                Ok(sync) => sync,
+                Err(e) => {
+                    tracing::warn!(
+                        target: "clawhive::channel::matrix",
+                        error = %e,
+                        "matrix sync failed, retrying in 5s..."
+                    );
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+            for incoming in directory.absorb(&sync, &handler.adapter, &handler.bot_user_id, initial)
+            {
+                handler.clone().dispatch(incoming).await;
+            }
+            initial = false;
+            if let Some(path) = &sync_path {
+                save_sync_token(path, &sync.next_batch);
+            }
+            since = Some(sync.next_batch);
+        }
+    }
+}
+
+struct MatrixHandler {
+    adapter: MatrixAdapter,
+    connector_id: String,
+    client: MatrixClient,
+    gateway: Arc<Gateway>,
+    bot_user_id: String,
+    require_mention: bool,
+    auto_join: bool,
+    allow_from: Vec<String>,
+    /// Approval prompt event ID → (approval short ID, conversation scope).
+    pending_approvals: Mutex<HashMap<String, (String, String)>>,
+    /// `(room, target event, key)` → the reaction event the bot sent.
+    sent_reactions: Mutex<HashMap<(String, String, String), String>>,
+}
+
+impl MatrixHandler {
+    fn is_allowed(&self, user_id: &str) -> bool {
+        self.allow_from.is_empty() || self.allow_from.iter().any(|u| u == user_id)
+    }
+
+    async fn dispatch(self: Arc<Self>, incoming: MatrixIncoming) {
+        match incoming {
+            MatrixIncoming::Invite {
+                room_id,
+                inviter,
+                is_direct,
+            } => {
+                if !self.auto_join || !self.is_allowed(&inviter) {
+                    return;
+                }
+                if let Err(e) = self.client.join(&room_id).await {
+                    tracing::warn!("Failed to join Matrix room {room_id}: {e}");
+                    return;
+                }
+                tracing::info!(
+                    target: "clawhive::channel::matrix",
+                    room_id = %room_id,
+                    inviter = %inviter,
+                    "joined matrix room"
+                );
+                if is_direct {
+                    if let Err(e) = self
+                        .client
+                        .mark_direct(&self.bot_user_id, &inviter, &room_id)
+                        .await
+                    {
+                        tracing::warn!("Failed to record Matrix DM room {room_id}: {e}");
+                    }
+                }
+            }
+            MatrixIncoming::Reaction {
+                room_id,
+                sender,
+                target_event_id,
+                key,
+            } => {
+                self.handle_reaction(room_id, sender, target_event_id, key)
+                    .await
+            }
+            MatrixIncoming::Message { room_id, inbound } => {
+                self.handle_message(room_id, inbound).await
+            }
+        }
+    }
+
+    async fn handle_message(self: Arc<Self>, room_id: String, mut inbound: InboundMessage) {
+        let sender = inbound.user_scope.trim_start_matches("user:").to_string();
+        if !self.is_allowed(&sender) {
+            tracing::debug!(
+                target: "clawhive::channel::matrix",
+                sender = %sender,
+                "ignoring matrix message from user not in allow_from"
+            );
+            return;
+        }
+        let is_dm = inbound.conversation_scope.starts_with("dm:");
+        if !is_dm && self.require_mention && !inbound.is_mention {
+            return;
+        }
+
+        // Images and inline-able documents are passed to the model as bytes.
+        let mut attachments = Vec::new();
+        for mut attachment in std::mem::take(&mut inbound.attachments) {
+            if matches!(
+                attachment.kind,
+                AttachmentKind::Image | AttachmentKind::Document
+            ) {
+                match self.client.download(&attachment.url).await {
+                    Ok(bytes) => {
+                        use base64::Engine;
+                        attachment.url = base64::engine::general_purpose::STANDARD.encode(bytes);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to download Matrix media: {e}");
+                        continue;
+                    }
+                }
+            }
+            attachments.push(attachment);
+        }
+        inbound.attachments = attachments;
+
+        let progress_delay = self
+            .gateway
+            .resolve_turn_lifecycle(&inbound)
+            .progress_delay_secs;
+        tokio::spawn(self.run_turn(room_id, inbound, progress_delay));
+    }
+
+    async fn run_turn(
+        self: Arc<Self>,
+        room_id: String,
+        inbound: InboundMessage,
+        progress_delay: u64,
+    ) {
+        let thread_root = inbound.thread_id.clone();
+        let reply_to = inbound.message_id.clone();
+        let _ = self
+            .client
+            .set_typing(&room_id, &self.bot_user_id, true)
+            .await;
+
+        let turn_complete = Arc::new(Notify::new());
+        let progress_complete = turn_complete.clone();
+        let progress_client = self.client.clone();
+        let progress_room = room_id.clone();
+        let progress_thread = thread_root.clone();
+        let _progress_guard = (progress_delay > 0).then(|| {
+            AbortOnDrop(tokio::spawn(async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(progress_delay)) => {
+                        if let Err(e) = progress_client
+                            .send_text(&progress_room, PROGRESS_MESSAGE, progress_thread.as_deref(), None)
+                            .await
+                        {
+                            tracing::warn!("Failed to send Matrix progress message: {e}");
+                        }
+                    }
+                    _ = progress_complete.notified() => {}
+                }
+            }))
+        });
+
+        let result = self.gateway.handle_inbound(inbound).await;
+        turn_complete.notify_waiters();
+        let _ = self
+            .client
+            .set_typing(&room_id, &self.bot_user_id, false)
+            .await;
+
+        match result {
+            Ok(Some(outbound)) if !outbound.text.trim().is_empty() => {
+                if let Err(e) = self
+                    .client
+                    .send_text(
+                        &room_id,
+                        &outbound.text,
+                        thread_root.as_deref(),
+                        reply_to.as_deref(),
+                    )
+                    .await
+                {
+                    tracing::error!("Failed to send Matrix reply: {e}");
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Failed to handle Matrix message: {e}"),
+        }
+    }
+
+    /// Reactions on an approval prompt answer it.
+    async fn handle_reaction(
+        self: Arc<Self>,
+        room_id: String,
+        sender: String,
+        target_event_id: String,
+        key: String,
+    ) {
+        let Some(decision) = APPROVAL_REACTIONS
+            .iter()
+            .find(|(emoji, _)| key.trim_end_matches('\u{fe0f}') == *emoji)
+            .map(|(_, decision)| *decision)
+        else {
+            return;
+        };
+        if !self.is_allowed(&sender) {
+            return;
+        }
+        let Some((short_id, conversation_scope)) =
+            self.pending_approvals.lock().await.remove(&target_event_id)
+        else {
+            return;
+        };
+
+        let text = format!("/approve {short_id} {decision}");
+        let is_dm = conversation_scope.starts_with("dm:");
+        let inbound = self
+            .adapter
+            .to_inbound(&room_id, &sender, &text, is_dm, None);
+        tokio::spawn(async move {
+            let reply_text = match self.gateway.handle_inbound(inbound).await {
+                Ok(Some(outbound)) => outbound.text,
+                Ok(None) => String::new(),
+                Err(e) => format!("❌ Error: {e}"),
+            };
+            if !reply_text.is_empty() {
+                if let Err(e) = self
+                    .client
+                    .send_text(&room_id, &reply_text, None, None)
+                    .await
+                {
+                    tracing::error!("Failed to send Matrix approval reply: {e}");
+                }
+            }
+        });
+    }
+}
+
+async fn spawn_delivery_listener(mut rx: mpsc::Receiver<BusMessage>, handler: Arc<MatrixHandler>) {
+    while let Some(msg) = rx.recv().await {
+        let BusMessage::DeliverAnnounce {
+            channel_type,
+            connector_id,
+            conversation_scope,
+            text,
+        } = msg
+        else {
+            continue;
+        };
+        if channel_type != "matrix" || connector_id != handler.connector_id {
+            continue;
+        }
+        let Some(room_id) = parse_room_id(&conversation_scope) else {
+            tracing::warn!(
+                "Could not parse Matrix room from conversation_scope: {}",
+                conversation_scope
+            );
+            continue;
+        };
+        if let Err(e) = handler.client.send_text(room_id, &text, None, None).await {
+            tracing::error!("Failed to deliver announce message to Matrix: {e}");
+        }
+    }
+}
+
+/// Matrix has no buttons, so approval prompts are answered by reacting to
+/// them. The bot pre-seeds the three reactions so they are one tap away.
+async fn spawn_approval_listener(mut rx: mpsc::Receiver<BusMessage>, handler: Arc<MatrixHandler>) {
+    while let Some(msg) = rx.recv().await {
+        let BusMessage::DeliverApprovalRequest {
+            channel_type,
+            connector_id,
+            conversation_scope,
+            short_id,
+            agent_id,
+            command,
+            network_target,
+            summary,
+        } = msg
+        else {
+            continue;
+        };
+        if channel_type != "matrix" || connector_id != handler.connector_id {
+            continue;
+        }
+        let Some(room_id) = parse_room_id(&conversation_scope) else {
+            tracing::warn!(
+                "Could not parse Matrix room from conversation_scope: {}",
+                conversation_scope
+            );
+            continue;
+        };
+
+        let display = ApprovalDisplay::new(&agent_id, &command, network_target.as_deref(), summary);
+        let body = format!(
+            "{}\n\nReact ✅ to allow once, 🔓 to always allow, ❌ to deny (or reply /approve {short_id} allow|always|deny).",
+            display.to_markdown()
+        );
+        let html = format!(
+            "{}<br><br>React ✅ to allow once, 🔓 to always allow, ❌ to deny (or reply <code>/approve {short_id} allow|always|deny</code>).",
+            display.to_html().replace('\n', "<br>")
+        );
+        let event_id = match handler.client.send_html(room_id, &body, &html).await {
+            Ok(event_id) => event_id,
+            Err(e) => {
+                tracing::error!("Failed to send approval prompt to Matrix: {e}");
+                continue;
+            }
+        };
+        handler
+            .pending_approvals
+            .lock()
+            .await
+            .insert(event_id.clone(), (short_id, conversation_scope.clone()));
+        for (emoji, _) in APPROVAL_REACTIONS {
+            if let Err(e) = handler.client.react(room_id, &event_id, emoji).await {
+                tracing::warn!("Failed to seed Matrix approval reaction: {e}");
+            }
+        }
+    }
+}
+
+/// Spawn a listener for ActionReady messages (reactions, edits, deletes)
+async fn spawn_action_listener(mut rx: mpsc::Receiver<BusMessage>, handler: Arc<MatrixHandler>) {
+    while let Some(msg) = rx.recv().await {
+        let BusMessage::ActionReady { action } = msg else {
+            continue;
+        };
+        if action.channel_type != "matrix" || action.connector_id != handler.connector_id {
+            continue;
+        }
+        let Some(room_id) = parse_room_id(&action.conversation_scope) else {
+            tracing::warn!("Could not parse Matrix room: {}", action.conversation_scope);
+            continue;
+        };
+        let Some(event_id) = action.message_id.as_deref() else {
+            tracing::warn!("Missing message_id for Matrix action");
+            continue;
+        };
+        let client = &handler.client;
+
+        let result = match &action.action {
+            ActionKind::React { emoji } => match client.react(room_id, event_id, emoji).await {
+                Ok(reaction_id) => {
+                    handler.sent_reactions.lock().await.insert(
+                        (room_id.to_string(), event_id.to_string(), emoji.clone()),
+                        reaction_id,
+                    );
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            },
+            // Removing a reaction means redacting the reaction event we sent.
+            ActionKind::Unreact { emoji } => {
+                let reaction_ids: Vec<String> = {
+                    let mut sent = handler.sent_reactions.lock().await;
+                    let keys: Vec<_> = sent
+                        .keys()
+                        .filter(|(room, target, key)| {
+                            room == room_id
+                                && target == event_id
+                                && emoji.as_ref().is_none_or(|e| e == key)
+                        })
+                        .cloned()
+                        .collect();
+                    keys.iter().filter_map(|key| sent.remove(key)).collect()
+                };
+                let mut result = Ok(());
+                for reaction_id in reaction_ids {
+                    if let Err(e) = client.redact(room_id, &reaction_id).await {
+                        result = Err(e);
+                    }
+                }
+                result
+            }
+            ActionKind::Edit { new_text } => {
+                client.edit(room_id, event_id, new_text).await.map(|_| ())
+            }
+            ActionKind::Delete => client.redact(room_id, event_id).await,
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to apply Matrix action: {e}");
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ChannelBot for MatrixBot {
+    fn channel_type(&self) -> &str {
+        "matrix"
+    }
+
+    fn connector_id(&self) -> &str {
+        &self.connector_id
+    }
+
+    async fn run(self: Box<Self>) -> Result<()> {
+        self.run_impl().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOT: &str = "@clawhive:example.org";
+
+    fn event(value: Value) -> MatrixEvent {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn sync(value: Value) -> SyncResponse {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn to_inbound_uses_dm_and_group_scopes() {
+        let adapter = MatrixAdapter::new("mx");
+        let dm = adapter.to_inbound("!a:example.org", "@alice:example.org", "hi", true, None);
+        assert_eq!(dm.channel_type, "matrix");
+        assert_eq!(dm.conversation_scope, "dm:!a:example.org");
+        assert_eq!(dm.user_scope, "user:@alice:example.org");
+
+        let group = adapter.to_inbound("!b:example.org", "@alice:example.org", "hi", false, None);
+        assert_eq!(group.conversation_scope, "group:!b:example.org");
+        assert_eq!(
+            parse_room_id(&group.conversation_scope),
+            Some("!b:example.org")
+        );
+    }
+
+    #[test]
+    fn event_to_inbound_detects_and_strips_mentions() {
+        let adapter = MatrixAdapter::new("mx");
+        let inbound = adapter
+            .event_to_inbound(
+                "!room:example.org",
+                &event(json!({
+                    "type": "m.room.message",
+                    "event_id": "$e1",
+                    "sender": "@alice:example.org",
+                    "content": {
+                        "msgtype": "m.text",
+                        "body": "@clawhive:example.org: what's up?",
+                        "m.mentions": { "user_ids": [BOT] },
+                        "m.relates_to": { "rel_type": "m.thread", "event_id": "$root" },
+                    },
+                })),
+                BOT,
+                false,
+            )
+            .unwrap();
+        assert_eq!(inbound.text, "what's up?");
+        assert!(inbound.is_mention);
+        assert_eq!(inbound.mention_target.as_deref(), Some(BOT));
+        assert_eq!(inbound.message_id.as_deref(), Some("$e1"));
+        assert_eq!(inbound.thread_id.as_deref(), Some("$root"));
+
+        let pill = adapter
+            .event_to_inbound(
+                "!room:example.org",
+                &event(json!({
+                    "type": "m.room.message",
+                    "event_id": "$e2",
+                    "sender": "@alice:example.org",
+                    "content": {
+                        "msgtype": "m.text",
+                        "body": "Clawhive: ping",
+                        "formatted_body": "<a href=\"https://matrix.to/#/@clawhive:example.org\">Clawhive</a>: ping",
+                    },
+                })),
+                BOT,
+                false,
+            )
+            .unwrap();
+        assert!(pill.is_mention);
+    }
+
+    #[test]
+    fn event_to_inbound_skips_own_notices_and_edits() {
+        let adapter = MatrixAdapter::new("mx");
+        let own = event(json!({
+            "type": "m.room.message",
+            "sender": BOT,
+            "content": { "msgtype": "m.text", "body": "reply" },
+        }));
+        let notice = event(json!({
+            "type": "m.room.message",
+            "sender": "@other-bot:example.org",
+            "content": { "msgtype": "m.notice", "body": "beep" },
+        }));
+        let edit = event(json!({
+            "type": "m.room.message",
+            "sender": "@alice:example.org",
+            "content": {
+                "msgtype": "m.text",
+                "body": "* fixed",
+                "m.relates_to": { "rel_type": "m.replace", "event_id": "$e1" },
+            },
+        }));
+        for ev in [own, notice, edit] {
+            assert!(adapter.event_to_inbound("!r:x", &ev, BOT, true).is_none());
+        }
+    }
+
+    #[test]
+    fn media_messages_become_attachments() {
+        let adapter = MatrixAdapter::new("mx");
+        let inbound = adapter
+            .event_to_inbound(
+                "!r:example.org",
+                &event(json!({
+                    "type": "m.room.message",
+                    "event_id": "$img",
+                    "sender": "@alice:example.org",
+                    "content": {
+                        "msgtype": "m.image",
+                        "body": "look at this",
+                        "filename": "cat.png",
+                        "url": "mxc://example.org/abc",
+                        "info": { "mimetype": "image/png", "size": 42 },
+                    },
+                })),
+                BOT,
+                true,
+            )
+            .unwrap();
+        assert_eq!(inbound.text, "look at this");
+        let attachment = &inbound.attachments[0];
+        assert_eq!(attachment.kind, AttachmentKind::Image);
+        assert_eq!(attachment.url, "mxc://example.org/abc");
+        assert_eq!(attachment.file_name.as_deref(), Some("cat.png"));
+        assert_eq!(attachment.size, Some(42));
+
+        let file = adapter
+            .event_to_inbound(
+                "!r:example.org",
+                &event(json!({
+                    "type": "m.room.message",
+                    "sender": "@alice:example.org",
+                    "content": { "msgtype": "m.file", "body": "notes.md", "url": "mxc://example.org/n" },
+                })),
+                BOT,
+                true,
+            )
+            .unwrap();
+        assert_eq!(file.text, "");
+        assert_eq!(file.attachments[0].kind, AttachmentKind::Document);
+    }
+
+    #[test]
+    fn directory_tracks_dms_and_skips_initial_history() {
+        let adapter = MatrixAdapter::new("mx");
+        let mut directory = RoomDirectory::default();
+        let message = json!({
+            "type": "m.room.message",
+            "event_id": "$old",
+            "sender": "@alice:example.org",
+            "content": { "msgtype": "m.text", "body": "hello" },
+        });
+
+        let first = sync(json!({
+            "next_batch": "s1",
+            "account_data": { "events": [
+                { "type": "m.direct", "content": { "@alice:example.org": ["!dm:example.org"] } },
+            ]},
+            "rooms": { "join": {
+                "!dm:example.org": { "timeline": { "events": [message] } },
+                "!pair:example.org": { "summary": { "m.joined_member_count": 2 } },
+                "!team:example.org": { "summary": { "m.joined_member_count": 5 } },
+            }},
+        }));
+        assert!(directory.absorb(&first, &adapter, BOT, true).is_empty());
+        assert!(directory.is_dm("!dm:example.org"));
+        // Two members alone do not make a DM; only m.direct does.
+        assert!(!directory.is_dm("!pair:example.org"));
+        assert!(!directory.is_dm("!team:example.org"));
+
+        let next = sync(json!({
+            "next_batch": "s2",
+            "rooms": {
+                "join": { "!team:example.org": { "timeline": { "events": [
+                    { "type": "m.room.message", "event_id": "$m", "sender": "@bob:example.org",
+                      "content": { "msgtype": "m.text", "body": "hi team" } },
+                    { "type": "m.reaction", "event_id": "$r", "sender": "@bob:example.org",
+                      "content": { "m.relates_to": { "rel_type": "m.annotation", "event_id": "$prompt", "key": "✅" } } },
+                ]}}},
+                "invite": { "!new:example.org": { "invite_state": { "events": [
+                    { "type": "m.room.member", "sender": "@carol:example.org", "state_key": BOT,
+                      "content": { "membership": "invite", "is_direct": true } },
+                ]}}},
+            },
+        }));
+        let incoming = directory.absorb(&next, &adapter, BOT, false);
+        assert_eq!(incoming.len(), 3);
+        assert!(directory.is_dm("!new:example.org"));
+        assert!(incoming.iter().any(|i| matches!(i,
+            MatrixIncoming::Invite { room_id, inviter, is_direct: true }
+                if room_id == "!new:example.org" && inviter == "@carol:example.org")));
+        assert!(incoming.iter().any(|i| matches!(i,
+            MatrixIncoming::Message { inbound, .. }
+                if inbound.conversation_scope == "group:!team:example.org")));
+        assert!(incoming.iter().any(|i| matches!(i,
+            MatrixIncoming::Reaction { target_event_id, key, .. }
+                if target_event_id == "$prompt" && key == "✅")));
+    }
+
+    #[test]
+    fn sync_filter_drops_history_only_on_the_first_sync() {
+        let initial = sync_filter(true);
+        assert_eq!(initial["room"]["timeline"]["limit"], 0);
+        assert_eq!(initial["presence"]["types"], json!([]));
+
+        let resumed = sync_filter(false);
+        assert!(resumed["room"]["timeline"].get("limit").is_none());
+        assert_eq!(
+            resumed["room"]["timeline"]["types"],
+            json!(TIMELINE_EVENT_TYPES)
+        );
+    }
+
+    #[test]
+    fn sync_token_survives_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sync.json");
+        assert_eq!(load_sync_token(&path), None);
+
+        save_sync_token(&path, "s42_1");
+        assert_eq!(load_sync_token(&path).as_deref(), Some("s42_1"));
+    }
+}
@@ -1,24 +1,45 @@
-//! Slack channel integration using Web API.
+//! Slack channel integration built on `slack-morphism`.
 //!
-//! This module provides Slack bot functionality via the Web API.
-//! For real-time events, use Slack's Events API with a webhook endpoint,
-//! or poll conversations.history.
+//! Events arrive over Socket Mode (a WebSocket opened with an app-level
+//! `xapp-` token) and/or the Events API (signed HTTP callbacks). Both
+//! transports feed the same dispatcher; replies, streamed edits, reactions and
+//! approval prompts go out through the Web API. A connector with only a bot
+//! token receives nothing, but still delivers announcements, approval prompts
+//! and actions.
 
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::Instant;
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context as _, Result};
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
 use chrono::Utc;
+use clawhive_bus::{EventBus, Topic};
 use clawhive_gateway::Gateway;
-use clawhive_schema::InboundMessage;
+use clawhive_schema::{
+    ActionKind, ApprovalDisplay, Attachment, AttachmentKind, BusMessage, InboundMessage,
+};
+use serde_json::json;
 use slack_morphism::prelude::*;
-use tokio::time::{interval, Duration};
+use slack_morphism::signature_verifier::SlackEventSignatureVerifier;
+use tokio::sync::{mpsc, Mutex, Notify};
+use tokio::time::Duration;
 use uuid::Uuid;
 
-use crate::common::AbortOnDrop;
+use crate::common::{infer_mime_from_filename, AbortOnDrop, PROGRESS_MESSAGE};
 
-// Type alias for the Slack client
-type HyperClient = SlackClient<SlackClientHyperHttpsConnector>;
-const PROGRESS_MESSAGE: &str = "⏳ Still working on it... (send /stop to cancel)";
+pub const SLACK_API_BASE: &str = SlackClientHttpApiUri::SLACK_API_URI_STR;
+/// Deadline for a single Web API call.
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+/// `chat.update` is rate limited, so streamed text is flushed at most this often.
+const STREAM_EDIT_INTERVAL: Duration = Duration::from_millis(1500);
+/// How many recent `channel:ts` keys are remembered for de-duplication.
+const RECENT_MESSAGE_CAPACITY: usize = 512;
 
 /// Adapter for converting between Slack and internal message formats.
 pub struct SlackAdapter {
@@ -45,7 +66,7 @@ impl SlackAdapter {
             trace_id: Uuid::new_v4(),
             channel_type: "slack".to_string(),
             connector_id: self.connector_id.clone(),
-            conversation_scope: format!("channel:{channel}"),
+            conversation_scope: conversation_scope(channel, thread_ts),
             user_scope: format!("user:{user}"),
             text: text.to_string(),
             at: Utc::now(),
@@ -57,6 +78,497 @@ impl SlackAdapter {
             message_source: None,
         }
     }
+
+    /// Convert a `message` / `app_mention` event. Returns `None` for the bot's
+    /// own posts and for messages with neither text nor files.
+    pub fn message_to_inbound(
+        &self,
+        message: &SlackIncomingMessage,
+        bot_user_id: &str,
+    ) -> Option<InboundMessage> {
+        if message.user == bot_user_id {
+            return None;
+        }
+
+        let mention_tag = format!("<@{bot_user_id}>");
+        let is_mention = message.is_app_mention || message.text.contains(&mention_tag);
+        let text = message.text.replace(&mention_tag, "");
+        let text = text.trim();
+        if text.is_empty() && message.files.is_empty() {
+            return None;
+        }
+
+        let mut inbound = self.to_inbound(
+            &message.channel,
+            &message.user,
+            text,
+            message.reply_thread(),
+            is_mention,
+        );
+        inbound.message_id = Some(message.ts.clone());
+        inbound.mention_target = is_mention.then_some(mention_tag);
+        inbound.attachments = message.files.iter().filter_map(file_attachment).collect();
+        Some(inbound)
+    }
+}
+
+/// `channel:<id>`, or `channel:<id>:thread:<ts>` inside a thread, so every
+/// thread is a conversation of its own.
+pub fn conversation_scope(channel: &str, thread_ts: Option<&str>) -> String {
+    match thread_ts {
+        Some(ts) => format!("channel:{channel}:thread:{ts}"),
+        None => format!("channel:{channel}"),
+    }
+}
+
+/// Inverse of [`conversation_scope`]: the channel and, if any, the thread.
+fn parse_conversation_scope(scope: &str) -> Option<(&str, Option<&str>)> {
+    let rest = scope.strip_prefix("channel:")?;
+    let (channel, thread_ts) = match rest.split_once(":thread:") {
+        Some((channel, ts)) => (channel, Some(ts).filter(|ts| !ts.is_empty())),
+        None => (rest, None),
+    };
+    (!channel.is_empty()).then_some((channel, thread_ts))
+}
+
+/// A `message` or `app_mention` event from a person.
+#[derive(Debug, Clone)]
+pub struct SlackIncomingMessage {
+    pub channel: String,
+    /// Sent in a direct message with the bot.
+    pub is_direct: bool,
+    pub user: String,
+    /// Delivered as `app_mention` rather than `message`.
+    pub is_app_mention: bool,
+    pub text: String,
+    pub ts: String,
+    pub thread_ts: Option<String>,
+    pub files: Vec<SlackFile>,
+}
+
+impl SlackIncomingMessage {
+    /// Where the reply goes: the thread the user wrote in, or in channels a
+    /// new thread under their message. Direct messages stay flat otherwise.
+    pub fn reply_thread(&self) -> Option<&str> {
+        self.thread_ts
+            .as_deref()
+            .or_else(|| (!self.is_direct).then_some(self.ts.as_str()))
+    }
+}
+
+/// A Block Kit button press.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlackBlockAction {
+    pub user_id: String,
+    pub channel_id: String,
+    pub message_ts: String,
+    /// Thread of the message holding the button.
+    pub thread_ts: Option<String>,
+    pub value: String,
+}
+
+/// Anything the bot reacts to, whichever transport delivered it.
+#[derive(Debug, Clone)]
+pub enum SlackIncoming {
+    Message(SlackIncomingMessage),
+    BlockAction(SlackBlockAction),
+}
+
+impl SlackIncoming {
+    /// Messages and mentions from people. Edits, deletions, joins and bot
+    /// posts are not addressed to the bot and yield `None`.
+    pub fn from_push_event(event: SlackPushEventCallback) -> Option<Self> {
+        let message = match event.event {
+            SlackEventCallbackBody::Message(message) => {
+                if message.sender.bot_id.is_some()
+                    || !matches!(
+                        message.subtype,
+                        None | Some(SlackMessageEventType::FileShare)
+                            | Some(SlackMessageEventType::ThreadBroadcast)
+                    )
+                {
+                    return None;
+                }
+                let channel = message.origin.channel?.0;
+                let is_direct = match &message.origin.channel_type {
+                    Some(kind) => kind.0 == "im",
+                    None => channel.starts_with('D'),
+                };
+                let content = message.content.unwrap_or_else(SlackMessageContent::new);
+                SlackIncomingMessage {
+                    channel,
+                    is_direct,
+                    user: message.sender.user?.0,
+                    is_app_mention: false,
+                    text: content.text.unwrap_or_default(),
+                    ts: message.origin.ts.0,
+                    thread_ts: message.origin.thread_ts.map(|ts| ts.0),
+                    files: content.files.unwrap_or_default(),
+                }
+            }
+            SlackEventCallbackBody::AppMention(mention) => SlackIncomingMessage {
+                is_direct: mention.channel.0.starts_with('D'),
+                channel: mention.channel.0,
+                user: mention.user.0,
+                is_app_mention: true,
+                text: mention.content.text.unwrap_or_default(),
+                ts: mention.origin.ts.0,
+                thread_ts: mention.origin.thread_ts.map(|ts| ts.0),
+                files: mention.content.files.unwrap_or_default(),
+            },
+            _ => return None,
+        };
+        Some(Self::Message(message))
+    }
+
+    /// Button presses on messages; other interactions yield `None`.
+    pub fn from_interaction(event: SlackInteractionEvent) -> Option<Self> {
+        let SlackInteractionEvent::BlockActions(event) = event else {
+            return None;
+        };
+        let SlackInteractionActionContainer::Message(container) = &event.container else {
+            return None;
+        };
+        let channel_id = event
+            .channel
+            .as_ref()
+            .map(|channel| channel.id.clone())
+            .or_else(|| container.channel_id.clone())?;
+        let value = event.actions.as_ref()?.first()?.value.clone()?;
+        Some(Self::BlockAction(SlackBlockAction {
+            user_id: event.user.as_ref()?.id.0.clone(),
+            channel_id: channel_id.0,
+            message_ts: container.message_ts.0.clone(),
+            thread_ts: event
+                .message
+                .as_ref()
+                .and_then(|message| message.origin.thread_ts.as_ref())
+                .map(|ts| ts.0.clone()),
+            value,
+        }))
+    }
+}
+
+fn file_attachment(file: &SlackFile) -> Option<Attachment> {
+    let url = file
+        .url_private_download
+        .as_ref()
+        .or(file.url_private.as_ref())?
+        .to_string();
+    let mime_type = file
+        .mimetype
+        .as_ref()
+        .map(|mime| mime.0.clone())
+        .or_else(|| infer_mime_from_filename(file.name.as_deref()));
+    let kind = match mime_type.as_deref() {
+        Some(mime) if mime.starts_with("image/") => AttachmentKind::Image,
+        Some(mime) if mime.starts_with("video/") => AttachmentKind::Video,
+        Some(mime) if mime.starts_with("audio/") => AttachmentKind::Audio,
+        Some(mime)
+            if mime.starts_with("text/")
+                || mime == "application/pdf"
+                || mime == "application/json" =>
+        {
+            AttachmentKind::Document
+        }
+        _ => AttachmentKind::Other,
+    };
+    Some(Attachment {
+        kind,
+        url,
+        mime_type,
+        file_name: file.name.clone(),
+        size: None,
+    })
+}
+
+/// Slack reactions are addressed by name; accept `:name:`, `name` or a few
+/// common emoji characters.
+fn slack_emoji_name(emoji: &str) -> String {
+    let name = match emoji {
+        "👍" => "thumbsup",
+        "👎" => "thumbsdown",
+        "👀" => "eyes",
+        "✅" => "white_check_mark",
+        "❌" => "x",
+        "❤️" | "❤" => "heart",
+        "🎉" => "tada",
+        "🔥" => "fire",
+        "🙏" => "pray",
+        "😂" => "joy",
+        "🤔" => "thinking_face",
+        other => other.trim_matches(':'),
+    };
+    name.to_string()
+}
+
+/// The Web API calls the bot makes, with the bot token bound.
+#[derive(Clone)]
+pub struct SlackApi {
+    client: Arc<SlackHyperClient>,
+    token: SlackApiToken,
+    http: reqwest::Client,
+}
+
+impl SlackApi {
+    /// Client for the Web API at `api_base`, normally [`SLACK_API_BASE`].
+    pub fn new(bot_token: impl Into<String>, api_base: &str) -> Result<Self> {
+        let https = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .context("failed to load root certificates for Slack")?
+            .https_or_http()
+            .enable_http1()
+            .enable_http2()
+            .build();
+        let connector = SlackClientHyperHttpsConnector::from(https)
+            .with_slack_api_url(api_base.trim_end_matches('/'));
+        Ok(Self {
+            client: Arc::new(SlackClient::new(connector)),
+            token: SlackApiToken::new(bot_token.into().into()),
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Returns the bot's own user ID.
+    pub async fn auth_test(&self) -> Result<String> {
+        let session = self.client.open_session(&self.token);
+        let response = timed("auth.test", session.auth_test()).await?;
+        Ok(response.user_id.0)
+    }
+
+    /// Post a message and return its `ts`.
+    pub async fn post_message(
+        &self,
+        channel: &str,
+        text: &str,
+        thread_ts: Option<&str>,
+        blocks: Option<Vec<SlackBlock>>,
+    ) -> Result<String> {
+        let mut request = SlackApiChatPostMessageRequest::new(
+            SlackChannelId(channel.to_string()),
+            message_content(text, blocks),
+        );
+        if let Some(ts) = thread_ts {
+            request = request.with_thread_ts(SlackTs(ts.to_string()));
+        }
+        let session = self.client.open_session(&self.token);
+        let response = timed("chat.postMessage", session.chat_post_message(&request)).await?;
+        Ok(response.ts.0)
+    }
+
+    pub async fn update_message(
+        &self,
+        channel: &str,
+        ts: &str,
+        text: &str,
+        blocks: Option<Vec<SlackBlock>>,
+    ) -> Result<()> {
+        let request = SlackApiChatUpdateRequest::new(
+            SlackChannelId(channel.to_string()),
+            message_content(text, blocks),
+            SlackTs(ts.to_string()),
+        );
+        let session = self.client.open_session(&self.token);
+        timed("chat.update", session.chat_update(&request)).await?;
+        Ok(())
+    }
+
+    pub async fn delete_message(&self, channel: &str, ts: &str) -> Result<()> {
+        let request = SlackApiChatDeleteRequest::new(
+            SlackChannelId(channel.to_string()),
+            SlackTs(ts.to_string()),
+        );
+        let session = self.client.open_session(&self.token);
+        timed("chat.delete", session.chat_delete(&request)).await?;
+        Ok(())
+    }
+
+    pub async fn add_reaction(&self, channel: &str, ts: &str, name: &str) -> Result<()> {
+        let request = SlackApiReactionsAddRequest::new(
+            SlackChannelId(channel.to_string()),
+            SlackReactionName(name.to_string()),
+            SlackTs(ts.to_string()),
+        );
+        let session = self.client.open_session(&self.token);
+        timed("reactions.add", session.reactions_add(&request)).await?;
+        Ok(())
+    }
+
+    pub async fn remove_reaction(&self, channel: &str, ts: &str, name: &str) -> Result<()> {
+        let request = SlackApiReactionsRemoveRequest::new(SlackReactionName(name.to_string()))
+            .with_channel(SlackChannelId(channel.to_string()))
+            .with_timestamp(SlackTs(ts.to_string()));
+        let session = self.client.open_session(&self.token);
+        timed("reactions.remove", session.reactions_remove(&request)).await?;
+        Ok(())
+    }
+
+    /// Download a private file URL (requires the `files:read` scope).
+    pub async fn download_file(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .http
+            .get(url)
+            .bearer_auth(self.token.token_value.0.as_str())
+            .timeout(Duration::from_secs(60))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+fn message_content(text: &str, blocks: Option<Vec<SlackBlock>>) -> SlackMessageContent {
+    let content = SlackMessageContent::new().with_text(text.to_string());
+    match blocks {
+        Some(blocks) => content.with_blocks(blocks),
+        None => content,
+    }
+}
+
+async fn timed<T>(
+    method: &str,
+    call: impl std::future::Future<Output = ClientResult<T>>,
+) -> Result<T> {
+    tokio::time::timeout(API_TIMEOUT, call)
+        .await
+        .map_err(|_| anyhow!("slack {method} timed out"))?
+        .map_err(|e| anyhow!("slack {method} failed: {e}"))
+}
+
+/// Hold Socket Mode connections open until the event receiver goes away.
+/// slack-morphism acknowledges every envelope and reconnects on its own.
+pub async fn run_socket_mode(
+    api: SlackApi,
+    app_token: String,
+    events: mpsc::Sender<SlackIncoming>,
+) -> Result<()> {
+    let environment = Arc::new(
+        SlackClientEventsListenerEnvironment::new(api.client.clone())
+            .with_user_state(events.clone()),
+    );
+    let callbacks = SlackSocketModeListenerCallbacks::new()
+        .with_push_events(forward_push_event)
+        .with_interaction_events(forward_interaction);
+    let listener = SlackClientSocketModeListener::new(
+        &SlackClientSocketModeConfig::new(),
+        environment,
+        callbacks,
+    );
+    listener
+        .listen_for(&SlackApiToken::new(app_token.into()))
+        .await
+        .map_err(|e| anyhow!("failed to register slack Socket Mode token: {e}"))?;
+    listener.start().await;
+    events.closed().await;
+    listener.shutdown().await;
+    Ok(())
+}
+
+async fn forward_push_event(
+    event: SlackPushEventCallback,
+    _client: Arc<SlackHyperClient>,
+    state: SlackClientEventsUserState,
+) -> UserCallbackResult<()> {
+    if let Some(incoming) = SlackIncoming::from_push_event(event) {
+        forward(&state, incoming).await;
+    }
+    Ok(())
+}
+
+async fn forward_interaction(
+    event: SlackInteractionEvent,
+    _client: Arc<SlackHyperClient>,
+    state: SlackClientEventsUserState,
+) -> UserCallbackResult<()> {
+    if let Some(incoming) = SlackIncoming::from_interaction(event) {
+        forward(&state, incoming).await;
+    }
+    Ok(())
+}
+
+async fn forward(state: &SlackClientEventsUserState, incoming: SlackIncoming) {
+    let events = state
+        .read()
+        .await
+        .get_user_state::<mpsc::Sender<SlackIncoming>>()
+        .cloned();
+    if let Some(events) = events {
+        let _ = events.send(incoming).await;
+    }
+}
+
+#[derive(Clone)]
+struct EventsApiState {
+    verifier: Arc<SlackEventSignatureVerifier>,
+    events: mpsc::Sender<SlackIncoming>,
+}
+
+/// Router for the Events API request URL. Event subscriptions and
+/// interactivity can both point at `/slack/events`.
+pub fn events_router(
+    signing_secret: impl Into<String>,
+    events: mpsc::Sender<SlackIncoming>,
+) -> Router {
+    let secret = SlackSigningSecret::new(signing_secret.into());
+    let state = EventsApiState {
+        verifier: Arc::new(SlackEventSignatureVerifier::new(&secret)),
+        events,
+    };
+    Router::new()
+        .route("/slack/events", post(receive_event))
+        .with_state(state)
+}
+
+async fn receive_event(
+    State(state): State<EventsApiState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+    };
+    let Ok(body) = std::str::from_utf8(&body) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    // Also rejects requests signed more than five minutes ago.
+    if state
+        .verifier
+        .verify(
+            header(SlackEventSignatureVerifier::SLACK_SIGNED_HASH_HEADER),
+            body,
+            header(SlackEventSignatureVerifier::SLACK_SIGNED_TIMESTAMP),
+        )
+        .is_err()
+    {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    // Interactivity posts a form with a single JSON `payload` field.
+    let incoming = if header("content-type").starts_with("application/x-www-form-urlencoded") {
+        let interaction = url::form_urlencoded::parse(body.as_bytes())
+            .find(|(key, _)| key == "payload")
+            .and_then(|(_, value)| serde_json::from_str::<SlackInteractionEvent>(&value).ok());
+        let Some(interaction) = interaction else {
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+        SlackIncoming::from_interaction(interaction)
+    } else {
+        match serde_json::from_str::<SlackPushEvent>(body) {
+            Ok(SlackPushEvent::UrlVerification(verification)) => {
+                return Json(json!({ "challenge": verification.challenge })).into_response();
+            }
+            Ok(SlackPushEvent::EventCallback(event)) => SlackIncoming::from_push_event(event),
+            Ok(_) => None,
+            Err(_) => return StatusCode::BAD_REQUEST.into_response(),
+        }
+    };
+    if let Some(incoming) = incoming {
+        let _ = state.events.send(incoming).await;
+    }
+    StatusCode::OK.into_response()
 }
 
 /// Slack bot configuration.
@@ -66,10 +578,16 @@ pub struct SlackBotConfig {
     pub bot_token: String,
     /// Connector ID for this bot instance
     pub connector_id: String,
-    /// Channels to monitor (channel IDs)
-    pub channels: Vec<String>,
-    /// Poll interval in seconds
-    pub poll_interval_secs: u64,
+    /// App-level token (xapp-...); enables Socket Mode
+    pub app_token: Option<String>,
+    /// Signing secret for Events API requests
+    pub signing_secret: Option<String>,
+    /// Address the Events API receiver binds; enables the Events API
+    pub events_listen: Option<String>,
+    /// Only answer channel messages that mention the bot (DMs always pass)
+    pub require_mention: bool,
+    /// Web API base URL
+    pub api_base: String,
 }
 
 impl SlackBotConfig {
@@ -77,221 +595,635 @@ impl SlackBotConfig {
         Self {
             bot_token: bot_token.into(),
             connector_id: connector_id.into(),
-            channels: vec![],
-            poll_interval_secs: 5,
+            app_token: None,
+            signing_secret: None,
+            events_listen: None,
+            require_mention: true,
+            api_base: SLACK_API_BASE.to_string(),
         }
     }
 
-    pub fn with_channels(mut self, channels: Vec<String>) -> Self {
-        self.channels = channels;
+    pub fn with_app_token(mut self, app_token: impl Into<String>) -> Self {
+        self.app_token = Some(app_token.into());
         self
     }
 
-    pub fn with_poll_interval(mut self, secs: u64) -> Self {
-        self.poll_interval_secs = secs;
+    pub fn with_events_api(
+        mut self,
+        signing_secret: impl Into<String>,
+        listen: impl Into<String>,
+    ) -> Self {
+        self.signing_secret = Some(signing_secret.into());
+        self.events_listen = Some(listen.into());
+        self
+    }
+
+    pub fn with_require_mention(mut self, require: bool) -> Self {
+        self.require_mention = require;
+        self
+    }
+
+    pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = api_base.into();
         self
     }
 }
 
-/// Slack bot using polling (for environments without webhook support).
+/// Slack bot driven by Socket Mode and/or the Events API.
 pub struct SlackBot {
     config: SlackBotConfig,
     gateway: Arc<Gateway>,
+    bus: Option<Arc<EventBus>>,
 }
 
 impl SlackBot {
     pub fn new(config: SlackBotConfig, gateway: Arc<Gateway>) -> Self {
-        Self { config, gateway }
+        Self {
+            config,
+            gateway,
+            bus: None,
+        }
     }
 
-    /// Run the Slack bot with polling.
-    pub async fn run_impl(self) -> Result<()> {
-        let client = SlackClient::new(SlackClientHyperHttpsConnector::new()?);
-        let token = SlackApiToken::new(self.config.bot_token.clone().into());
+    pub fn with_bus(mut self, bus: Arc<EventBus>) -> Self {
+        self.bus = Some(bus);
+        self
+    }
 
-        // Get bot user ID
-        let session = client.open_session(&token);
-        let auth_test = session.auth_test().await?;
-        let bot_user_id = auth_test.user_id.0.clone();
+    pub async fn run_impl(self) -> Result<()> {
+        let api = SlackApi::new(&self.config.bot_token, &self.config.api_base)?;
+        let bot_user_id = api.auth_test().await?;
 
         tracing::info!(
-            "Slack bot connected as {} ({})",
-            auth_test.user.unwrap_or_default(),
-            bot_user_id
+            target: "clawhive::channel::slack",
+            connector_id = %self.config.connector_id,
+            bot_user_id = %bot_user_id,
+            "slack bot authenticated"
         );
 
-        let adapter = Arc::new(SlackAdapter::new(&self.config.connector_id));
-
-        // Track last message timestamp per channel
-        let mut last_ts: std::collections::HashMap<String, String> =
-            std::collections::HashMap::new();
-
-        let mut poll_timer = interval(Duration::from_secs(self.config.poll_interval_secs));
+        let (events_tx, mut events_rx) = mpsc::channel(256);
+        let mut tasks = Vec::new();
 
-        loop {
-            poll_timer.tick().await;
-
-            for channel_id in &self.config.channels {
-                let oldest = last_ts.get(channel_id).map(|s| s.as_str());
-
-                match self
-                    .poll_channel(&client, &token, channel_id, oldest, &bot_user_id, &adapter)
-                    .await
-                {
-                    Ok(Some(newest_ts)) => {
-                        last_ts.insert(channel_id.clone(), newest_ts);
-                    }
-                    Ok(None) => {}
-                    Err(e) => {
-                        tracing::warn!("Failed to poll Slack channel {channel_id}: {e}");
-                    }
+        if let Some(app_token) = self.config.app_token.clone() {
+            let api = api.clone();
+            let events = events_tx.clone();
+            tasks.push(AbortOnDrop(tokio::spawn(async move {
+                if let Err(e) = run_socket_mode(api, app_token, events).await {
+                    tracing::error!(
+                        target: "clawhive::channel::slack",
+                        error = %e,
+                        "slack Socket Mode stopped"
+                    );
                 }
-            }
+            })));
         }
-    }
-
-    async fn poll_channel(
-        &self,
-        client: &HyperClient,
-        token: &SlackApiToken,
-        channel_id: &str,
-        oldest: Option<&str>,
-        bot_user_id: &str,
-        adapter: &SlackAdapter,
-    ) -> Result<Option<String>> {
-        let session = client.open_session(token);
-
-        let mut request = SlackApiConversationsHistoryRequest::new()
-            .with_channel(SlackChannelId(channel_id.to_string()))
-            .with_limit(20);
 
-        if let Some(ts) = oldest {
-            request = request.with_oldest(SlackTs(ts.to_string()));
+        if let Some(listen) = &self.config.events_listen {
+            let Some(signing_secret) = self
+                .config
+                .signing_secret
+                .clone()
+                .filter(|secret| !secret.is_empty())
+            else {
+                bail!(
+                    "slack connector {} sets events_listen without signing_secret",
+                    self.config.connector_id
+                );
+            };
+            let listener = tokio::net::TcpListener::bind(listen)
+                .await
+                .with_context(|| format!("failed to bind slack Events API receiver on {listen}"))?;
+            tracing::info!(
+                target: "clawhive::channel::slack",
+                listen = %listen,
+                "slack Events API receiver listening on /slack/events"
+            );
+            let router = events_router(signing_secret, events_tx.clone());
+            tasks.push(AbortOnDrop(tokio::spawn(async move {
+                if let Err(e) = axum::serve(listener, router).await {
+                    tracing::error!(
+                        target: "clawhive::channel::slack",
+                        error = %e,
+                        "slack Events API receiver stopped"
+                    );
+                }
+            })));
         }
 
-        let response = session.conversations_history(&request).await?;
+        let receives_events = !tasks.is_empty();
+        if !receives_events {
+            tracing::warn!(
+                target: "clawhive::channel::slack",
+                connector_id = %self.config.connector_id,
+                "slack connector has no app_token or events_listen; it only delivers announcements, approvals and actions"
+            );
+        }
+        drop(events_tx);
 
-        let mut newest_ts: Option<String> = None;
+        let handler = Arc::new(SlackHandler {
+            adapter: SlackAdapter::new(&self.config.connector_id),
+            connector_id: self.config.connector_id.clone(),
+            api: api.clone(),
+            gateway: self.gateway.clone(),
+            bot_user_id,
+            require_mention: self.config.require_mention,
+            recent: Mutex::new(RecentMessages::default()),
+            streams: Arc::new(Mutex::new(HashMap::new())),
+        });
 
-        for msg in response.messages.into_iter().rev() {
-            // Extract timestamp
-            let ts = msg.origin.ts.0.clone();
+        if let Some(bus) = &self.bus {
+            let connector_id = self.config.connector_id.clone();
+            tasks.push(AbortOnDrop(tokio::spawn(spawn_delivery_listener(
+                bus.subscribe(Topic::DeliverAnnounce).await,
+                api.clone(),
+                connector_id.clone(),
+            ))));
+            tasks.push(AbortOnDrop(tokio::spawn(spawn_approval_listener(
+                bus.subscribe(Topic::DeliverApprovalRequest).await,
+                api.clone(),
+                connector_id.clone(),
+            ))));
+            tasks.push(AbortOnDrop(tokio::spawn(spawn_action_listener(
+                bus.subscribe(Topic::ActionReady).await,
+                api.clone(),
+                connector_id,
+            ))));
+            tasks.push(AbortOnDrop(tokio::spawn(spawn_stream_listener(
+                bus.subscribe(Topic::StreamDelta).await,
+                api.clone(),
+                handler.streams.clone(),
+            ))));
+        }
 
-            // Track newest
-            if newest_ts.as_ref().is_none_or(|cur| *ts > **cur) {
-                newest_ts = Some(ts.clone());
+        if !receives_events {
+            if self.bus.is_none() {
+                bail!(
+                    "slack connector {} needs app_token (Socket Mode) or events_listen and signing_secret (Events API)",
+                    self.config.connector_id
+                );
             }
+            // Send-only: the bus listeners above do all the work.
+            std::future::pending::<()>().await;
+        }
 
-            // Skip if older than our cursor
-            if let Some(oldest) = oldest {
-                if ts.as_str() <= oldest {
-                    continue;
-                }
+        while let Some(incoming) = events_rx.recv().await {
+            match incoming {
+                SlackIncoming::Message(message) => handler.clone().handle_message(message),
+                SlackIncoming::BlockAction(action) => handler.clone().handle_block_action(action),
             }
+        }
 
-            // Extract user
-            let user = match &msg.sender.user {
-                Some(u) => u.0.clone(),
-                None => continue,
-            };
+        bail!("slack event transports stopped")
+    }
+}
 
-            // Skip bot messages
-            if user == bot_user_id {
-                continue;
+/// Bounded set of recently seen `channel:ts` keys. A channel mention arrives
+/// as both `message` and `app_mention`, and Slack retries slow deliveries.
+#[derive(Default)]
+struct RecentMessages {
+    seen: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl RecentMessages {
+    fn insert(&mut self, key: String) -> bool {
+        if !self.seen.insert(key.clone()) {
+            return false;
+        }
+        self.order.push_back(key);
+        if self.order.len() > RECENT_MESSAGE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
             }
+        }
+        true
+    }
+}
 
-            // Extract text
-            let text = msg.content.text.as_deref().unwrap_or("");
+/// The in-progress reply of one turn. The first progress notice or streamed
+/// chunk creates the message; later chunks and the final reply edit it.
+struct StreamedReply {
+    channel: String,
+    thread_ts: Option<String>,
+    ts: Option<String>,
+    text: String,
+    last_edit: Option<Instant>,
+}
 
-            if text.is_empty() {
-                continue;
+impl StreamedReply {
+    async fn show(&mut self, api: &SlackApi, text: &str) -> Result<()> {
+        match &self.ts {
+            Some(ts) => api.update_message(&self.channel, ts, text, None).await,
+            None => {
+                let ts = api
+                    .post_message(&self.channel, text, self.thread_ts.as_deref(), None)
+                    .await?;
+                self.ts = Some(ts);
+                Ok(())
             }
+        }
+    }
+}
 
-            // Check for mention
-            let is_mention = text.contains(&format!("<@{bot_user_id}>"));
-            let thread_ts = msg.origin.thread_ts.as_ref().map(|t| t.0.as_str());
+type ActiveStreams = Arc<Mutex<HashMap<Uuid, Arc<Mutex<StreamedReply>>>>>;
 
-            let inbound = adapter.to_inbound(channel_id, &user, text, thread_ts, is_mention);
+struct SlackHandler {
+    adapter: SlackAdapter,
+    connector_id: String,
+    api: SlackApi,
+    gateway: Arc<Gateway>,
+    bot_user_id: String,
+    require_mention: bool,
+    recent: Mutex<RecentMessages>,
+    streams: ActiveStreams,
+}
 
-            tracing::debug!("Slack message from {user} in {channel_id}: {text}");
+impl SlackHandler {
+    /// Each event is handled on its own task so a slow file download does
+    /// not hold up the events behind it.
+    fn handle_message(self: Arc<Self>, message: SlackIncomingMessage) {
+        tokio::spawn(async move {
+            if !self
+                .recent
+                .lock()
+                .await
+                .insert(format!("{}:{}", message.channel, message.ts))
+            {
+                return;
+            }
+            let Some(mut inbound) = self.adapter.message_to_inbound(&message, &self.bot_user_id)
+            else {
+                return;
+            };
+            if !message.is_direct && self.require_mention && !inbound.is_mention {
+                return;
+            }
+            inbound.attachments = self
+                .download_attachments(std::mem::take(&mut inbound.attachments))
+                .await;
+
+            tracing::debug!(
+                target: "clawhive::channel::slack",
+                channel = %message.channel,
+                ts = %message.ts,
+                "slack message received"
+            );
 
-            let gateway = self.gateway.clone();
-            let client = client.clone();
-            let token = token.clone();
-            let channel = channel_id.to_string();
-            let progress_thread_ts = thread_ts.map(|ts| ts.to_string());
+            let thread_ts = inbound.thread_id.clone();
             let progress_delay = self
                 .gateway
                 .resolve_turn_lifecycle(&inbound)
                 .progress_delay_secs;
-            tokio::spawn(async move {
-                let turn_complete = Arc::new(tokio::sync::Notify::new());
-                let progress_complete = turn_complete.clone();
-                let progress_client = client.clone();
-                let progress_token = token.clone();
-                let progress_channel = channel.clone();
-                let _progress_guard = (progress_delay > 0).then(|| {
-                    AbortOnDrop(tokio::spawn(async move {
-                        tokio::select! {
-                            _ = tokio::time::sleep(Duration::from_secs(progress_delay)) => {
-                                if let Err(e) = send_slack_message(
-                                    &progress_client,
-                                    &progress_token,
-                                    &progress_channel,
-                                    PROGRESS_MESSAGE,
-                                    progress_thread_ts.as_deref(),
-                                )
-                                .await
-                                {
-                                    tracing::warn!("Failed to send Slack progress message: {e}");
-                                }
+            self.run_turn(inbound, message.channel, thread_ts, progress_delay)
+                .await;
+        });
+    }
+
+    /// Images and inline-able documents are passed to the model as bytes.
+    async fn download_attachments(&self, attachments: Vec<Attachment>) -> Vec<Attachment> {
+        let mut downloaded = Vec::new();
+        for mut attachment in attachments {
+            if matches!(
+                attachment.kind,
+                AttachmentKind::Image | AttachmentKind::Document
+            ) {
+                match self.api.download_file(&attachment.url).await {
+                    Ok(bytes) => {
+                        use base64::Engine;
+                        attachment.url = base64::engine::general_purpose::STANDARD.encode(bytes);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to download Slack file: {e}");
+                        continue;
+                    }
+                }
+            }
+            downloaded.push(attachment);
+        }
+        downloaded
+    }
+
+    async fn run_turn(
+        self: Arc<Self>,
+        inbound: InboundMessage,
+        channel: String,
+        thread_ts: Option<String>,
+        progress_delay: u64,
+    ) {
+        let trace_id = inbound.trace_id;
+        let reply = Arc::new(Mutex::new(StreamedReply {
+            channel,
+            thread_ts,
+            ts: None,
+            text: String::new(),
+            last_edit: None,
+        }));
+        self.streams.lock().await.insert(trace_id, reply.clone());
+
+        let turn_complete = Arc::new(Notify::new());
+        let progress_complete = turn_complete.clone();
+        let progress_reply = reply.clone();
+        let progress_api = self.api.clone();
+        let progress_guard = (progress_delay > 0).then(|| {
+            AbortOnDrop(tokio::spawn(async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(progress_delay)) => {
+                        let mut reply = progress_reply.lock().await;
+                        if reply.ts.is_none() {
+                            if let Err(e) = reply.show(&progress_api, PROGRESS_MESSAGE).await {
+                                tracing::warn!("Failed to send Slack progress message: {e}");
                             }
-                            _ = progress_complete.notified() => {}
                         }
-                    }))
-                });
+                    }
+                    _ = progress_complete.notified() => {}
+                }
+            }))
+        });
 
-                let result = gateway.handle_inbound(inbound).await;
-                turn_complete.notify_waiters();
+        let result = self.gateway.handle_inbound(inbound).await;
+        turn_complete.notify_waiters();
+        drop(progress_guard);
+        self.streams.lock().await.remove(&trace_id);
 
-                if let Err(e) = result {
-                    tracing::error!("Failed to handle Slack message: {e}");
+        let mut reply = reply.lock().await;
+        match result {
+            Ok(Some(outbound)) if !outbound.text.trim().is_empty() => {
+                if let Err(e) = reply.show(&self.api, &outbound.text).await {
+                    tracing::error!("Failed to send Slack reply: {e}");
+                }
+            }
+            Ok(_) => {
+                if let Some(ts) = &reply.ts {
+                    let _ = self.api.delete_message(&reply.channel, ts).await;
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to handle Slack message: {e}");
+                if let Err(e) = reply.show(&self.api, &format!("❌ Error: {e}")).await {
+                    tracing::warn!("Failed to send Slack error reply: {e}");
                 }
-            });
+            }
         }
+    }
+
+    fn handle_block_action(self: Arc<Self>, action: SlackBlockAction) {
+        let Some((short_id, decision)) = action
+            .value
+            .strip_prefix("approve:")
+            .and_then(|rest| rest.split_once(':'))
+        else {
+            return;
+        };
+        let label = match decision {
+            "allow" => "✅ Allowed once",
+            "always" => "🔓 Always allowed",
+            _ => "❌ Denied",
+        };
+        let text = format!("/approve {short_id} {decision}");
+        // The prompt was posted into the conversation that asked for it.
+        let inbound = self.adapter.to_inbound(
+            &action.channel_id,
+            &action.user_id,
+            &text,
+            action.thread_ts.as_deref(),
+            false,
+        );
+
+        tokio::spawn(async move {
+            // Replace the buttons so the prompt cannot be answered twice.
+            let resolved = format!("{label} by <@{}>", action.user_id);
+            let blocks = vec![SlackSectionBlock::new()
+                .with_text(md!(resolved.clone()))
+                .into()];
+            if let Err(e) = self
+                .api
+                .update_message(
+                    &action.channel_id,
+                    &action.message_ts,
+                    &resolved,
+                    Some(blocks),
+                )
+                .await
+            {
+                tracing::warn!(
+                    connector_id = %self.connector_id,
+                    "Failed to update Slack approval message: {e}"
+                );
+            }
 
-        Ok(newest_ts)
+            let reply_text = match self.gateway.handle_inbound(inbound).await {
+                Ok(Some(outbound)) => outbound.text,
+                Ok(None) => String::new(),
+                Err(e) => format!("❌ Error: {e}"),
+            };
+            if !reply_text.is_empty() {
+                if let Err(e) = self
+                    .api
+                    .post_message(
+                        &action.channel_id,
+                        &reply_text,
+                        action.thread_ts.as_deref(),
+                        None,
+                    )
+                    .await
+                {
+                    tracing::error!("Failed to send Slack approval reply: {e}");
+                }
+            }
+        });
     }
 }
 
-/// Send a message to Slack.
-pub async fn send_slack_message(
-    client: &HyperClient,
-    token: &SlackApiToken,
-    channel: &str,
-    text: &str,
-    thread_ts: Option<&str>,
-) -> Result<()> {
-    let session = client.open_session(token);
+/// Edit in-progress replies as stream deltas arrive for their trace.
+async fn spawn_stream_listener(
+    mut rx: mpsc::Receiver<BusMessage>,
+    api: SlackApi,
+    streams: ActiveStreams,
+) {
+    while let Some(msg) = rx.recv().await {
+        let BusMessage::StreamDelta {
+            trace_id, delta, ..
+        } = msg
+        else {
+            continue;
+        };
+        let Some(reply) = streams.lock().await.get(&trace_id).cloned() else {
+            continue;
+        };
 
-    let mut request = SlackApiChatPostMessageRequest::new(
-        SlackChannelId(channel.to_string()),
-        SlackMessageContent::new().with_text(text.to_string()),
-    );
+        let mut reply = reply.lock().await;
+        reply.text.push_str(&delta);
+        if reply.text.trim().is_empty()
+            || reply
+                .last_edit
+                .is_some_and(|at| at.elapsed() < STREAM_EDIT_INTERVAL)
+        {
+            continue;
+        }
+        let text = reply.text.clone();
+        if let Err(e) = reply.show(&api, &text).await {
+            tracing::warn!("Failed to stream Slack reply: {e}");
+        }
+        reply.last_edit = Some(Instant::now());
+    }
+}
 
-    if let Some(ts) = thread_ts {
-        request = request.with_thread_ts(SlackTs(ts.to_string()));
+async fn spawn_delivery_listener(
+    mut rx: mpsc::Receiver<BusMessage>,
+    api: SlackApi,
+    connector_id: String,
+) {
+    while let Some(msg) = rx.recv().await {
+        let BusMessage::DeliverAnnounce {
+            channel_type,
+            connector_id: msg_connector_id,
+            conversation_scope,
+            text,
+        } = msg
+        else {
+            continue;
+        };
+
+        if channel_type != "slack" || msg_connector_id != connector_id {
+            continue;
+        }
+
+        let Some((channel, thread_ts)) = parse_conversation_scope(&conversation_scope) else {
+            tracing::warn!(
+                "Could not parse Slack channel from conversation_scope: {}",
+                conversation_scope
+            );
+            continue;
+        };
+
+        if let Err(e) = api.post_message(channel, &text, thread_ts, None).await {
+            tracing::error!("Failed to deliver announce message to Slack: {e}");
+        }
     }
+}
 
-    match tokio::time::timeout(Duration::from_secs(30), session.chat_post_message(&request)).await {
-        Ok(result) => {
-            result?;
+/// Post approval requests with Block Kit buttons. Presses come back as
+/// `block_actions` and are turned into `/approve` commands.
+async fn spawn_approval_listener(
+    mut rx: mpsc::Receiver<BusMessage>,
+    api: SlackApi,
+    connector_id: String,
+) {
+    while let Some(msg) = rx.recv().await {
+        let BusMessage::DeliverApprovalRequest {
+            channel_type,
+            connector_id: msg_connector_id,
+            conversation_scope,
+            short_id,
+            agent_id,
+            command,
+            network_target,
+            summary,
+        } = msg
+        else {
+            continue;
+        };
+
+        if channel_type != "slack" || msg_connector_id != connector_id {
+            continue;
         }
-        Err(_) => {
-            tracing::warn!(channel = %channel, "slack message delivery timed out after 30s");
+
+        let Some((channel, thread_ts)) = parse_conversation_scope(&conversation_scope) else {
+            tracing::warn!(
+                "Could not parse Slack channel from conversation_scope: {}",
+                conversation_scope
+            );
+            continue;
+        };
+
+        let display = ApprovalDisplay::new(&agent_id, &command, network_target.as_deref(), summary);
+        let text = display.to_markdown().replace("**", "*");
+        let blocks = approval_blocks(&text, &short_id);
+
+        if let Err(e) = api
+            .post_message(channel, &text, thread_ts, Some(blocks))
+            .await
+        {
+            tracing::error!("Failed to send approval buttons to Slack: {e}");
+        }
+    }
+}
+
+fn approval_blocks(text: &str, short_id: &str) -> Vec<SlackBlock> {
+    let button = |label: &str, decision: &str, style: Option<SlackBlockButtonStyle>| {
+        let button = SlackBlockButtonElement::new(pt!(label))
+            .with_action_id(SlackActionId(format!("approve_{decision}")))
+            .with_value(format!("approve:{short_id}:{decision}"));
+        match style {
+            Some(style) => button.with_style(style),
+            None => button,
+        }
+        .into()
+    };
+    vec![
+        SlackSectionBlock::new().with_text(md!(text)).into(),
+        SlackActionsBlock::new(vec![
+            button(
+                "✅ Allow Once",
+                "allow",
+                Some(SlackBlockButtonStyle::Primary),
+            ),
+            button("🔓 Always Allow", "always", None),
+            button("❌ Deny", "deny", Some(SlackBlockButtonStyle::Danger)),
+        ])
+        .with_block_id(SlackBlockId(format!("approval:{short_id}")))
+        .into(),
+    ]
+}
+
+/// Spawn a listener for ActionReady messages (reactions, edits, deletes)
+async fn spawn_action_listener(
+    mut rx: mpsc::Receiver<BusMessage>,
+    api: SlackApi,
+    connector_id: String,
+) {
+    while let Some(msg) = rx.recv().await {
+        let BusMessage::ActionReady { action } = msg else {
+            continue;
+        };
+
+        if action.channel_type != "slack" || action.connector_id != connector_id {
+            continue;
+        }
+
+        let Some((channel, _)) = parse_conversation_scope(&action.conversation_scope) else {
+            tracing::warn!(
+                "Could not parse Slack channel: {}",
+                action.conversation_scope
+            );
+            continue;
+        };
+        let Some(ts) = action.message_id.as_deref() else {
+            tracing::warn!("Missing message_id for Slack action");
+            continue;
+        };
+
+        let result = match &action.action {
+            ActionKind::React { emoji } => {
+                api.add_reaction(channel, ts, &slack_emoji_name(emoji))
+                    .await
+            }
+            // Slack removes reactions by name only; there is no "clear all".
+            ActionKind::Unreact { emoji: Some(emoji) } => {
+                api.remove_reaction(channel, ts, &slack_emoji_name(emoji))
+                    .await
+            }
+            ActionKind::Unreact { emoji: None } => {
+                tracing::warn!("Slack unreact needs an emoji name");
+                continue;
+            }
+            ActionKind::Edit { new_text } => api.update_message(channel, ts, new_text, None).await,
+            ActionKind::Delete => api.delete_message(channel, ts).await,
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to apply Slack action: {e}");
         }
     }
-    Ok(())
 }
 
 #[async_trait::async_trait]
@@ -312,6 +1244,26 @@ impl super::ChannelBot for SlackBot {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::Value;
+
+    fn push_event(event: Value) -> Option<SlackIncoming> {
+        let callback = serde_json::from_value(json!({
+            "team_id": "T1",
+            "api_app_id": "A1",
+            "event_id": "Ev1",
+            "event_time": 1_700_000_000,
+            "event": event,
+        }))
+        .unwrap();
+        SlackIncoming::from_push_event(callback)
+    }
+
+    fn message(event: Value) -> SlackIncomingMessage {
+        match push_event(event) {
+            Some(SlackIncoming::Message(message)) => message,
+            other => panic!("expected a message, got {other:?}"),
+        }
+    }
 
     #[test]
     fn adapter_to_inbound_basic() {
@@ -335,15 +1287,206 @@ mod tests {
 
         assert!(inbound.is_mention);
         assert_eq!(inbound.thread_id, Some("1234567890.123456".to_string()));
+        assert_eq!(
+            inbound.conversation_scope,
+            "channel:C123456:thread:1234567890.123456"
+        );
+    }
+
+    #[test]
+    fn conversation_scopes_round_trip() {
+        assert_eq!(
+            parse_conversation_scope(&conversation_scope("C1", Some("1700000000.000100"))),
+            Some(("C1", Some("1700000000.000100")))
+        );
+        assert_eq!(parse_conversation_scope("channel:D1"), Some(("D1", None)));
+        assert_eq!(parse_conversation_scope("channel:"), None);
+        assert_eq!(parse_conversation_scope("chat:1"), None);
     }
 
     #[test]
     fn config_builder() {
         let config = SlackBotConfig::new("xoxb-xxx", "slack-main")
-            .with_channels(vec!["C123".to_string(), "C456".to_string()])
-            .with_poll_interval(10);
+            .with_app_token("xapp-xxx")
+            .with_events_api("secret", "127.0.0.1:3100")
+            .with_require_mention(false);
+
+        assert_eq!(config.app_token.as_deref(), Some("xapp-xxx"));
+        assert_eq!(config.signing_secret.as_deref(), Some("secret"));
+        assert_eq!(config.events_listen.as_deref(), Some("127.0.0.1:3100"));
+        assert!(!config.require_mention);
+        assert_eq!(config.api_base, SLACK_API_BASE);
+    }
+
+    #[test]
+    fn message_event_strips_mention_and_keeps_thread() {
+        let adapter = SlackAdapter::new("slack-main");
+        let message = message(json!({
+            "type": "message",
+            "channel": "C1",
+            "channel_type": "channel",
+            "user": "U1",
+            "text": "<@UBOT> summarize this",
+            "ts": "1700000000.000200",
+            "thread_ts": "1700000000.000100",
+        }));
+
+        let inbound = adapter.message_to_inbound(&message, "UBOT").unwrap();
+        assert_eq!(inbound.text, "summarize this");
+        assert!(inbound.is_mention);
+        assert_eq!(inbound.mention_target.as_deref(), Some("<@UBOT>"));
+        assert_eq!(inbound.thread_id.as_deref(), Some("1700000000.000100"));
+        assert_eq!(
+            inbound.conversation_scope,
+            "channel:C1:thread:1700000000.000100"
+        );
+        assert_eq!(inbound.message_id.as_deref(), Some("1700000000.000200"));
+        assert!(!message.is_direct);
+    }
 
-        assert_eq!(config.channels.len(), 2);
-        assert_eq!(config.poll_interval_secs, 10);
+    #[test]
+    fn channel_messages_start_a_thread_and_direct_messages_stay_flat() {
+        let adapter = SlackAdapter::new("slack-main");
+        let in_channel = message(json!({
+            "type": "app_mention", "channel": "C1", "user": "U1",
+            "text": "<@UBOT> hi", "ts": "1700000000.000300"
+        }));
+        let direct = message(json!({
+            "type": "message", "channel": "D1", "channel_type": "im",
+            "user": "U1", "text": "hi", "ts": "1700000000.000400"
+        }));
+
+        let inbound = adapter.message_to_inbound(&in_channel, "UBOT").unwrap();
+        assert!(in_channel.is_app_mention);
+        assert_eq!(
+            inbound.conversation_scope,
+            "channel:C1:thread:1700000000.000300"
+        );
+        let inbound = adapter.message_to_inbound(&direct, "UBOT").unwrap();
+        assert!(direct.is_direct);
+        assert_eq!(inbound.conversation_scope, "channel:D1");
+        assert!(inbound.thread_id.is_none());
+    }
+
+    #[test]
+    fn message_event_skips_bots_edits_and_own_messages() {
+        let adapter = SlackAdapter::new("slack-main");
+        let from_bot = push_event(json!({
+            "type": "message", "channel": "D1", "bot_id": "B1", "user": "U2", "text": "hi", "ts": "1"
+        }));
+        let edited = push_event(json!({
+            "type": "message", "subtype": "message_changed", "channel": "D1", "ts": "2"
+        }));
+        let own = message(json!({
+            "type": "message", "channel": "D1", "user": "UBOT", "text": "hi", "ts": "3"
+        }));
+
+        assert!(from_bot.is_none());
+        assert!(edited.is_none());
+        assert!(adapter.message_to_inbound(&own, "UBOT").is_none());
+    }
+
+    #[test]
+    fn message_event_maps_files_to_attachments() {
+        let adapter = SlackAdapter::new("slack-main");
+        let message = message(json!({
+            "type": "message",
+            "subtype": "file_share",
+            "channel": "D1",
+            "user": "U1",
+            "text": "",
+            "ts": "1",
+            "files": [
+                { "id": "F1", "name": "chart.png", "mimetype": "image/png",
+                  "url_private_download": "https://files.slack.com/chart.png" },
+                { "id": "F2", "name": "notes.md", "url_private": "https://files.slack.com/notes.md" },
+                { "id": "F3", "name": "no-url.bin" },
+            ],
+        }));
+
+        let inbound = adapter.message_to_inbound(&message, "UBOT").unwrap();
+        assert!(message.is_direct);
+        assert_eq!(inbound.attachments.len(), 2);
+        assert_eq!(inbound.attachments[0].kind, AttachmentKind::Image);
+        assert_eq!(
+            inbound.attachments[0].url,
+            "https://files.slack.com/chart.png"
+        );
+        assert_eq!(inbound.attachments[1].kind, AttachmentKind::Document);
+        assert_eq!(
+            inbound.attachments[1].mime_type.as_deref(),
+            Some("text/markdown")
+        );
+    }
+
+    #[test]
+    fn interactions_read_block_actions() {
+        let interaction = serde_json::from_value(json!({
+            "type": "block_actions",
+            "team": { "id": "T1" },
+            "user": { "id": "U1" },
+            "api_app_id": "A1",
+            "trigger_id": "tr1",
+            "channel": { "id": "C1" },
+            "container": { "type": "message", "message_ts": "1700000000.000300" },
+            "message": { "ts": "1700000000.000300", "thread_ts": "1700000000.000100" },
+            "actions": [{ "type": "button", "action_id": "approve_always", "value": "approve:ab12:always" }],
+        }))
+        .unwrap();
+
+        let Some(SlackIncoming::BlockAction(action)) = SlackIncoming::from_interaction(interaction)
+        else {
+            panic!("expected a block action");
+        };
+        assert_eq!(
+            action,
+            SlackBlockAction {
+                user_id: "U1".into(),
+                channel_id: "C1".into(),
+                message_ts: "1700000000.000300".into(),
+                thread_ts: Some("1700000000.000100".into()),
+                value: "approve:ab12:always".into(),
+            }
+        );
+        assert!(push_event(json!({ "type": "reaction_added" })).is_none());
+    }
+
+    #[test]
+    fn approval_blocks_carry_decisions() {
+        let blocks = serde_json::to_value(approval_blocks("Run `ls`?", "ab12")).unwrap();
+        let values: Vec<&str> = blocks[1]["elements"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["value"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            values,
+            [
+                "approve:ab12:allow",
+                "approve:ab12:always",
+                "approve:ab12:deny"
+            ]
+        );
+        assert_eq!(blocks[1]["elements"][0]["style"], "primary");
+        assert_eq!(blocks[1]["block_id"], "approval:ab12");
+    }
+
+    #[test]
+    fn recent_messages_forget_oldest_keys() {
+        let mut recent = RecentMessages::default();
+        assert!(recent.insert("C1:1".into()));
+        assert!(!recent.insert("C1:1".into()));
+        for i in 0..RECENT_MESSAGE_CAPACITY {
+            recent.insert(format!("C2:{i}"));
+        }
+        assert!(recent.insert("C1:1".into()));
+    }
+
+    #[test]
+    fn emoji_names_accept_colons_and_characters() {
+        assert_eq!(slack_emoji_name(":rocket:"), "rocket");
+        assert_eq!(slack_emoji_name("👀"), "eyes");
+        assert_eq!(slack_emoji_name("tada"), "tada");
     }
 }
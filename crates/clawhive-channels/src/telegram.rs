@@ -1586,6 +1586,12 @@ fn default_file_name(kind: &AttachmentKind, mime_type: &Option<String>) -> Strin
     }
 }
 
+/// Telegram only renders OGG/Opus as a voice note; other audio is sent as a
+/// music file.
+fn is_voice_note(mime_type: Option<&str>) -> bool {
+    matches!(mime_type, Some("audio/ogg" | "audio/opus"))
+}
+
 async fn send_attachments(
     bot: &Bot,
     chat_id: ChatId,
@@ -1646,6 +1652,36 @@ async fn send_attachments(
                     }
                 }
             }
+            AttachmentKind::Audio => {
+                let input = InputFile::memory(bytes).file_name(file_name);
+                let sent = if is_voice_note(att.mime_type.as_deref()) {
+                    let mut req = bot.send_voice(chat_id, input);
+                    if let Some(c) = cap {
+                        req = req.caption(c);
+                    }
+                    tokio::time::timeout(Duration::from_secs(30), req.send())
+                        .await
+                        .map(|r| r.map(|_| ()))
+                } else {
+                    let mut req = bot.send_audio(chat_id, input);
+                    if let Some(c) = cap {
+                        req = req.caption(c);
+                    }
+                    tokio::time::timeout(Duration::from_secs(30), req.send())
+                        .await
+                        .map(|r| r.map(|_| ()))
+                };
+                match sent {
+                    Ok(r) => r,
+                    Err(_) => {
+                        tracing::warn!(
+                            chat_id = chat_id.0,
+                            "telegram audio delivery timed out after 30s"
+                        );
+                        Ok(())
+                    }
+                }
+            }
             _ => {
                 let input = InputFile::memory(bytes).file_name(file_name);
                 let mut req = bot.send_document(chat_id, input);
@@ -1910,6 +1946,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ogg_audio_is_sent_as_voice_note() {
+        assert!(is_voice_note(Some("audio/ogg")));
+        assert!(!is_voice_note(Some("audio/mpeg")));
+        assert!(!is_voice_note(None));
+    }
+
     #[test]
     fn attachment_text_mode_prefers_caption_for_short_text_with_attachments() {
         assert_eq!(
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use clawhive_core::config::{WebhookFilterConfig, WebhookSourceConfig, WebhookTemplateConfig};
 use serde_json::Value;
 use uuid::Uuid;
 
@@ -16,6 +17,11 @@ pub struct NormalizedEvent {
 pub trait PayloadNormalizer: Send + Sync {
     fn normalize(&self, payload: &Value) -> NormalizedEvent;
     fn derive_scope(&self, payload: &Value, source_id: &str) -> String;
+
+    /// Whether the event should reach an agent at all.
+    fn accepts(&self, _payload: &Value) -> bool {
+        true
+    }
 }
 
 pub struct RawNormalizer;
@@ -347,6 +353,199 @@ impl PayloadNormalizer for GithubNormalizer {
     }
 }
 
+/// `format: template`: fields, filters and text come from the source's
+/// [`WebhookTemplateConfig`] instead of code.
+pub struct TemplateNormalizer {
+    template: WebhookTemplateConfig,
+    /// Substituted for `{{source_id}}` in every template.
+    source_id: Option<String>,
+}
+
+impl TemplateNormalizer {
+    pub fn new(template: WebhookTemplateConfig) -> Self {
+        Self {
+            template,
+            source_id: None,
+        }
+    }
+
+    pub fn with_source_id(mut self, source_id: impl Into<String>) -> Self {
+        self.source_id = Some(source_id.into());
+        self
+    }
+
+    /// A named field, or a payload path when no field has that name.
+    fn lookup<'a>(&self, payload: &'a Value, key: &str) -> Option<&'a Value> {
+        let path = self.template.fields.get(key).map_or(key, String::as_str);
+        select_path(payload, path).filter(|value| !value.is_null())
+    }
+
+    fn render(&self, template: &str, payload: &Value, source_id: Option<&str>) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            out.push_str(&rest[..start]);
+            let placeholder = &rest[start + 2..start + 2 + len];
+            let (key, fallback) = match placeholder.split_once('|') {
+                Some((key, fallback)) => (key.trim(), fallback.trim()),
+                None => (placeholder.trim(), ""),
+            };
+            let value = match (key, source_id) {
+                ("source_id", Some(source_id)) => source_id.to_string(),
+                _ => self
+                    .lookup(payload, key)
+                    .map(value_text)
+                    .unwrap_or_default(),
+            };
+            out.push_str(if value.is_empty() { fallback } else { &value });
+            rest = &rest[start + 2 + len + 2..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn render_optional(&self, template: Option<&String>, payload: &Value) -> Option<String> {
+        template
+            .map(|template| {
+                self.render(template, payload, self.source_id.as_deref())
+                    .trim()
+                    .to_string()
+            })
+            .filter(|rendered| !rendered.is_empty())
+    }
+
+    fn filter_matches(&self, filter: &WebhookFilterConfig, payload: &Value) -> bool {
+        let value = self.lookup(payload, &filter.path);
+        if filter
+            .exists
+            .is_some_and(|exists| exists != value.is_some())
+        {
+            return false;
+        }
+        let text = value.map(value_text).unwrap_or_default();
+        if filter
+            .equals
+            .as_ref()
+            .is_some_and(|expected| text != value_text(expected))
+        {
+            return false;
+        }
+        if filter
+            .not_equals
+            .as_ref()
+            .is_some_and(|unexpected| text == value_text(unexpected))
+        {
+            return false;
+        }
+        if !filter.one_of.is_empty()
+            && !filter
+                .one_of
+                .iter()
+                .any(|candidate| text == value_text(candidate))
+        {
+            return false;
+        }
+        if filter
+            .contains
+            .as_ref()
+            .is_some_and(|needle| !text.contains(needle.as_str()))
+        {
+            return false;
+        }
+        true
+    }
+}
+
+impl PayloadNormalizer for TemplateNormalizer {
+    fn normalize(&self, payload: &Value) -> NormalizedEvent {
+        let rendered = self.render(&self.template.text, payload, self.source_id.as_deref());
+        let text = if rendered.trim().is_empty() {
+            pretty_json(payload)
+        } else {
+            rendered.trim().to_string()
+        };
+        let labels = self
+            .template
+            .fields
+            .keys()
+            .filter_map(|name| {
+                let value = value_text(self.lookup(payload, name)?);
+                (!value.is_empty()).then(|| (name.clone(), value))
+            })
+            .collect();
+
+        NormalizedEvent {
+            text: truncate_text(text, RAW_MAX_BYTES),
+            title: self.render_optional(self.template.title.as_ref(), payload),
+            severity: self.render_optional(self.template.severity.as_ref(), payload),
+            labels,
+        }
+    }
+
+    fn derive_scope(&self, payload: &Value, source_id: &str) -> String {
+        self.template
+            .conversation_scope
+            .as_ref()
+            .map(|template| {
+                self.render(template, payload, Some(source_id))
+                    .trim()
+                    .to_string()
+            })
+            .filter(|scope| !scope.is_empty())
+            .unwrap_or_else(|| format!("source:{source_id}:event:{}", Uuid::new_v4()))
+    }
+
+    fn accepts(&self, payload: &Value) -> bool {
+        self.template
+            .filters
+            .iter()
+            .all(|filter| self.filter_matches(filter, payload))
+    }
+}
+
+/// Resolve a JSONPath-style path: `$.a.b`, `a.b[0]`, `a.b.0`, `items[-1]`.
+fn select_path<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let mut current = payload;
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        let (key, indexes) = segment.split_once('[').unwrap_or((segment, ""));
+        if !key.is_empty() {
+            current = match current {
+                Value::Array(items) => index_array(items, key.parse().ok()?)?,
+                _ => current.get(key)?,
+            };
+        }
+        if !indexes.is_empty() {
+            for index in indexes.trim_end_matches(']').split("][") {
+                current = index_array(current.as_array()?, index.trim().parse().ok()?)?;
+            }
+        }
+    }
+    Some(current)
+}
+
+fn index_array(items: &[Value], index: i64) -> Option<&Value> {
+    let index = if index < 0 {
+        items.len().checked_sub(index.unsigned_abs() as usize)?
+    } else {
+        index as usize
+    };
+    items.get(index)
+}
+
+/// Strings render bare, `null` as nothing, everything else as compact JSON.
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
 fn pretty_json(payload: &Value) -> String {
     serde_json::to_string_pretty(payload).unwrap_or_else(|_| payload.to_string())
 }
@@ -384,6 +583,20 @@ pub fn get_normalizer(format: &str) -> Box<dyn PayloadNormalizer> {
     }
 }
 
+/// Like [`get_normalizer`], but builds the `template` format from the
+/// source's own template. A template source without one is an error.
+pub fn normalizer_for_source(
+    source: &WebhookSourceConfig,
+) -> anyhow::Result<Box<dyn PayloadNormalizer>> {
+    source.validate()?;
+    Ok(match (source.format.as_str(), &source.template) {
+        ("template", Some(template)) => {
+            Box::new(TemplateNormalizer::new(template.clone()).with_source_id(&source.source_id))
+        }
+        _ => get_normalizer(&source.format),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -614,6 +827,118 @@ mod tests {
         assert!(fallback.starts_with("source:unknown:event:"));
     }
 
+    fn sentry_template() -> WebhookTemplateConfig {
+        serde_json::from_value(json!({
+            "fields": {
+                "title": "$.data.issue.title",
+                "level": "data.issue.level",
+                "project": "data.issue.project.slug"
+            },
+            "text": "[{{level}}] {{title}} in {{project}}\nCulprit: {{data.issue.culprit|unknown}}",
+            "title": "{{title}}",
+            "severity": "{{level}}",
+            "filters": [
+                {"path": "level", "one_of": ["error", "fatal"]},
+                {"path": "$.action", "not_equals": "resolved"}
+            ],
+            "conversation_scope": "{{source_id}}:{{project}}:{{data.issue.id}}"
+        }))
+        .unwrap()
+    }
+
+    fn sentry_payload(level: &str, action: &str) -> Value {
+        json!({
+            "action": action,
+            "data": {"issue": {
+                "id": 4211,
+                "title": "ZeroDivisionError",
+                "level": level,
+                "project": {"slug": "api"}
+            }}
+        })
+    }
+
+    #[test]
+    fn template_normalizer_renders_fields_and_scope() {
+        let normalizer = TemplateNormalizer::new(sentry_template());
+        let payload = sentry_payload("error", "created");
+
+        assert!(normalizer.accepts(&payload));
+        let event = normalizer.normalize(&payload);
+        assert_eq!(
+            event.text,
+            "[error] ZeroDivisionError in api\nCulprit: unknown"
+        );
+        assert_eq!(event.title.as_deref(), Some("ZeroDivisionError"));
+        assert_eq!(event.severity.as_deref(), Some("error"));
+        assert_eq!(event.labels["project"], "api");
+        assert_eq!(
+            normalizer.derive_scope(&payload, "sentry"),
+            "sentry:api:4211"
+        );
+    }
+
+    #[test]
+    fn template_normalizer_filters_drop_events() {
+        let normalizer = TemplateNormalizer::new(sentry_template());
+        assert!(!normalizer.accepts(&sentry_payload("warning", "created")));
+        assert!(!normalizer.accepts(&sentry_payload("fatal", "resolved")));
+        assert!(normalizer.accepts(&sentry_payload("fatal", "created")));
+
+        let exists: WebhookFilterConfig =
+            serde_json::from_value(json!({"path": "merge_request", "exists": false})).unwrap();
+        let normalizer = TemplateNormalizer::new(WebhookTemplateConfig {
+            filters: vec![exists],
+            ..WebhookTemplateConfig::default()
+        });
+        assert!(normalizer.accepts(&json!({"object_kind": "push"})));
+        assert!(!normalizer.accepts(&json!({"merge_request": {"iid": 1}})));
+    }
+
+    #[test]
+    fn select_path_supports_indexes() {
+        let payload = json!({"commits": [{"id": "a"}, {"id": "b"}], "n": [[1, 2]]});
+        assert_eq!(select_path(&payload, "$.commits[0].id"), Some(&json!("a")));
+        assert_eq!(select_path(&payload, "commits.1.id"), Some(&json!("b")));
+        assert_eq!(select_path(&payload, "commits[-1].id"), Some(&json!("b")));
+        assert_eq!(select_path(&payload, "$.n[0][1]"), Some(&json!(2)));
+        assert_eq!(select_path(&payload, "$"), Some(&payload));
+        assert_eq!(select_path(&payload, "commits[5].id"), None);
+        assert_eq!(select_path(&payload, "commits[-3]"), None);
+    }
+
+    #[test]
+    fn template_normalizer_falls_back_to_payload_and_fresh_scope() {
+        let normalizer = TemplateNormalizer::new(WebhookTemplateConfig {
+            text: "{{missing}}".into(),
+            conversation_scope: Some("{{missing}}".into()),
+            ..WebhookTemplateConfig::default()
+        });
+        let payload = json!({"k": "v"});
+        assert!(normalizer.normalize(&payload).text.contains("\"k\": \"v\""));
+        assert!(normalizer
+            .derive_scope(&payload, "ci")
+            .starts_with("source:ci:event:"));
+    }
+
+    #[test]
+    fn template_source_without_template_is_rejected() {
+        let mut source: WebhookSourceConfig = serde_json::from_value(json!({
+            "source_id": "ci",
+            "format": "template",
+            "auth": {"method": "api_key"}
+        }))
+        .unwrap();
+        let err = normalizer_for_source(&source).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "webhook source ci uses format template but has no template"
+        );
+
+        source.template = Some(sentry_template());
+        assert!(normalizer_for_source(&source).is_ok());
+    }
+
     #[test]
     fn truncate_text_safe_on_multibyte_boundary() {
         // "你好世界" is 4 CJK chars = 12 bytes (3 bytes each)
@@ -13,7 +13,7 @@ use uuid::Uuid;
 use wacore::download::MediaType;
 use wacore::types::events::Event;
 use waproto::whatsapp as wa;
-use waproto::whatsapp::message::{DocumentMessage, ImageMessage};
+use waproto::whatsapp::message::{AudioMessage, DocumentMessage, ImageMessage};
 use whatsapp_rust::bot::Bot;
 use whatsapp_rust::client::Client;
 use whatsapp_rust::upload::{UploadOptions, UploadResponse};
@@ -490,9 +490,15 @@ pub async fn start_whatsapp(
 
                                     let prefixed_text = format!("{prefix}{}", outbound.text);
 
+                                    let caption_index = if has_text {
+                                        caption_attachment_index(&outbound.attachments)
+                                    } else {
+                                        None
+                                    };
+
                                     if has_attachments {
                                         for (i, att) in outbound.attachments.iter().enumerate() {
-                                            let caption = if i == 0 && has_text {
+                                            let caption = if caption_index == Some(i) {
                                                 Some(prefixed_text.as_str())
                                             } else {
                                                 None
@@ -512,7 +518,7 @@ pub async fn start_whatsapp(
                                             }
                                         }
 
-                                        if has_text {
+                                        if caption_index.is_some() {
                                             tracing::info!(
                                                 sender = %sender_jid,
                                                 chat = %chat_jid,
@@ -523,7 +529,7 @@ pub async fn start_whatsapp(
                                         }
                                     }
 
-                                    if has_text && !has_attachments {
+                                    if has_text {
                                         let reply = wa::Message {
                                             conversation: Some(prefixed_text),
                                             ..Default::default()
@@ -886,6 +892,7 @@ async fn resolve_attachment_bytes(att: &Attachment) -> anyhow::Result<Vec<u8>> {
 fn attachment_media_type(kind: AttachmentKind) -> MediaType {
     match kind {
         AttachmentKind::Image => MediaType::Image,
+        AttachmentKind::Audio => MediaType::Audio,
         _ => MediaType::Document,
     }
 }
@@ -910,6 +917,21 @@ fn build_attachment_message(
             })),
             ..Default::default()
         },
+        // Audio messages carry no caption; the reply text is sent separately.
+        AttachmentKind::Audio => wa::Message {
+            audio_message: Some(Box::new(AudioMessage {
+                url: Some(upload.url),
+                direct_path: Some(upload.direct_path),
+                media_key: Some(upload.media_key.to_vec()),
+                file_enc_sha256: Some(upload.file_enc_sha256.to_vec()),
+                file_sha256: Some(upload.file_sha256.to_vec()),
+                file_length: Some(upload.file_length),
+                mimetype: att.mime_type.clone(),
+                ptt: Some(is_voice_note(att.mime_type.as_deref())),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
         _ => {
             let file_name = att.file_name.clone().unwrap_or_else(|| {
                 let ext = att
@@ -939,6 +961,18 @@ fn build_attachment_message(
     }
 }
 
+/// WhatsApp plays OGG/Opus audio as a push-to-talk voice note.
+fn is_voice_note(mime_type: Option<&str>) -> bool {
+    matches!(mime_type, Some("audio/ogg" | "audio/opus"))
+}
+
+/// Attachment that carries the reply text as its caption, if any can.
+fn caption_attachment_index(attachments: &[Attachment]) -> Option<usize> {
+    attachments
+        .iter()
+        .position(|att| !matches!(att.kind, AttachmentKind::Audio))
+}
+
 async fn send_attachment(
     client: &Client,
     chat_jid: &Jid,
@@ -1096,6 +1130,42 @@ mod tests {
         assert_eq!(image.file_length, Some(42));
     }
 
+    #[test]
+    fn build_attachment_message_sends_ogg_audio_as_voice_note() {
+        let attachment = Attachment {
+            kind: AttachmentKind::Audio,
+            url: "aGVsbG8=".to_string(),
+            mime_type: Some("audio/ogg".to_string()),
+            file_name: Some("reply.ogg".to_string()),
+            size: Some(42),
+        };
+
+        let message = build_attachment_message(&attachment, test_upload_response(), Some("hi"));
+
+        assert!(message.document_message.is_none());
+        let audio = message.audio_message.expect("expected audio message");
+        assert_eq!(audio.ptt, Some(true));
+        assert_eq!(audio.mimetype.as_deref(), Some("audio/ogg"));
+        assert_eq!(audio.file_length, Some(42));
+    }
+
+    #[test]
+    fn reply_text_is_captioned_on_first_non_audio_attachment() {
+        let audio = Attachment {
+            kind: AttachmentKind::Audio,
+            url: String::new(),
+            mime_type: Some("audio/ogg".to_string()),
+            file_name: None,
+            size: None,
+        };
+        let image = Attachment {
+            kind: AttachmentKind::Image,
+            ..audio.clone()
+        };
+        assert_eq!(caption_attachment_index(std::slice::from_ref(&audio)), None);
+        assert_eq!(caption_attachment_index(&[audio, image]), Some(1));
+    }
+
     #[test]
     fn build_attachment_message_generates_document_filename_from_mime() {
         let attachment = Attachment {
@@ -1132,7 +1202,7 @@ mod tests {
     }
 
     #[test]
-    fn attachment_media_type_uses_document_for_non_images() {
+    fn attachment_media_type_uses_document_for_other_kinds() {
         assert!(matches!(
             attachment_media_type(AttachmentKind::Image),
             MediaType::Image
@@ -1143,7 +1213,7 @@ mod tests {
         ));
         assert!(matches!(
             attachment_media_type(AttachmentKind::Audio),
-            MediaType::Document
+            MediaType::Audio
         ));
         assert!(matches!(
             attachment_media_type(AttachmentKind::Document),
@@ -0,0 +1,265 @@
+#![cfg(feature = "email")]
+
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::time::Duration;
+
+use clawhive_channels::email::{
+    parse_email, EmailAdapter, ImapSession, OutgoingEmail, Security, SmtpClient,
+};
+
+const MESSAGE: &str = "From: Alice <alice@example.com>\r\n\
+Subject: Status?\r\n\
+Message-ID: <q1@example.com>\r\n\
+Content-Type: multipart/mixed; boundary=b\r\n\
+\r\n\
+--b\r\n\
+Content-Type: text/plain\r\n\
+\r\n\
+How is the deploy going?\r\n\
+--b\r\n\
+Content-Type: image/png\r\n\
+Content-Disposition: attachment; filename=graph.png\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+iVBORw0K\r\n\
+--b--\r\n";
+
+/// Scripted IMAP server. Records every command it sees.
+async fn imap_stand_in() -> (u16, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let seen = log.clone();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(imap_connection(stream, seen.clone()));
+        }
+    });
+    (port, log)
+}
+
+async fn imap_connection(stream: tokio::net::TcpStream, seen: Arc<Mutex<Vec<String>>>) {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    write.write_all(b"* OK stand-in ready\r\n").await.unwrap();
+    let mut marked_seen = false;
+    while let Some(line) = lines.next_line().await.unwrap() {
+        seen.lock().unwrap().push(line.clone());
+        let (tag, command) = line.split_once(' ').unwrap();
+        let reply = match command {
+            c if c.starts_with("LOGIN \"bot@example.com\"") => {
+                if c.ends_with("\"secret\"") {
+                    format!("{tag} OK logged in\r\n")
+                } else {
+                    format!("{tag} NO [AUTHENTICATIONFAILED] bad password\r\n")
+                }
+            }
+            "AUTHENTICATE PLAIN" => {
+                write.write_all(b"+ \r\n").await.unwrap();
+                let response = lines.next_line().await.unwrap().unwrap();
+                seen.lock().unwrap().push(response.clone());
+                let expected = base64::engine::general_purpose::STANDARD
+                    .encode("\0bot@example.com\0s\u{e9}cret");
+                if response == expected {
+                    format!("{tag} OK authenticated\r\n")
+                } else {
+                    format!("{tag} NO [AUTHENTICATIONFAILED] bad password\r\n")
+                }
+            }
+            "CAPABILITY" => format!("* CAPABILITY IMAP4rev1 IDLE\r\n{tag} OK\r\n"),
+            "SELECT \"INBOX\"" => format!("* 1 EXISTS\r\n{tag} OK [READ-WRITE]\r\n"),
+            "UID SEARCH UNSEEN" if marked_seen => format!("* SEARCH\r\n{tag} OK\r\n"),
+            "UID SEARCH UNSEEN" => format!("* SEARCH 7\r\n{tag} OK\r\n"),
+            "UID FETCH 7 BODY.PEEK[]" => format!(
+                "* 1 FETCH (UID 7 BODY[] {{{}}}\r\n{MESSAGE})\r\n{tag} OK\r\n",
+                MESSAGE.len()
+            ),
+            "UID STORE 7 +FLAGS.SILENT (\\Seen)" => {
+                marked_seen = true;
+                format!("{tag} OK\r\n")
+            }
+            "IDLE" => {
+                write.write_all(b"+ idling\r\n").await.unwrap();
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                write.write_all(b"* 2 EXISTS\r\n").await.unwrap();
+                let done = lines.next_line().await.unwrap().unwrap();
+                seen.lock().unwrap().push(done);
+                format!("{tag} OK IDLE terminated\r\n")
+            }
+            "LOGOUT" => format!("* BYE\r\n{tag} OK\r\n"),
+            _ => format!("{tag} BAD unexpected\r\n"),
+        };
+        write.write_all(reply.as_bytes()).await.unwrap();
+    }
+}
+
+/// Minimal SMTP server that accepts one message and returns the transcript.
+async fn smtp_stand_in() -> (u16, tokio::task::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (read, mut write) = stream.into_split();
+        let mut reader = BufReader::new(read);
+        let mut transcript = String::new();
+        write.write_all(b"220 stand-in ESMTP\r\n").await.unwrap();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+            transcript.push_str(&line);
+            let reply: &[u8] = match line.trim_end() {
+                l if l.starts_with("EHLO") => b"250-stand-in\r\n250 AUTH PLAIN\r\n",
+                l if l.starts_with("AUTH PLAIN") => b"235 ok\r\n",
+                l if l.starts_with("MAIL FROM") || l.starts_with("RCPT TO") => b"250 ok\r\n",
+                "DATA" => {
+                    write.write_all(b"354 go ahead\r\n").await.unwrap();
+                    loop {
+                        let mut data = String::new();
+                        reader.read_line(&mut data).await.unwrap();
+                        transcript.push_str(&data);
+                        if data == ".\r\n" {
+                            break;
+                        }
+                    }
+                    b"250 queued\r\n"
+                }
+                "QUIT" => {
+                    write.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                }
+                _ => b"500 what\r\n",
+            };
+            write.write_all(reply).await.unwrap();
+        }
+        transcript
+    });
+    (port, handle)
+}
+
+// ---------------------------------------------------------------------------
+// IMAP: fetch unseen mail, flag it, then wait in IDLE for the next one
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn imap_session_fetches_unseen_mail_and_idles() {
+    let (port, log) = imap_stand_in().await;
+    let err = ImapSession::login(
+        "127.0.0.1",
+        port,
+        Security::None,
+        "bot@example.com",
+        "wrong",
+    )
+    .await
+    .err()
+    .unwrap();
+    assert!(format!("{err:#}").contains("bad password"));
+    // Non-ASCII credentials cannot be quoted, so they go through AUTHENTICATE.
+    ImapSession::login(
+        "127.0.0.1",
+        port,
+        Security::None,
+        "bot@example.com",
+        "s\u{e9}cret",
+    )
+    .await
+    .unwrap();
+
+    let mut session = ImapSession::login(
+        "127.0.0.1",
+        port,
+        Security::None,
+        "bot@example.com",
+        "secret",
+    )
+    .await
+    .unwrap();
+    assert!(session.supports_idle());
+    session.select("INBOX").await.unwrap();
+    assert_eq!(session.search_unseen().await.unwrap(), [7]);
+    let raw = session.fetch(7).await.unwrap().unwrap();
+    assert_eq!(raw, MESSAGE.as_bytes());
+    session.mark_seen(7).await.unwrap();
+    assert!(session.search_unseen().await.unwrap().is_empty());
+    assert!(session
+        .idle(Duration::from_secs(5), std::future::pending::<()>())
+        .await
+        .unwrap());
+    session.logout().await;
+
+    assert!(log.lock().unwrap().iter().any(|line| line == "DONE"));
+
+    let email = parse_email(&raw);
+    let inbound = EmailAdapter::new("mail").email_to_inbound(&email).unwrap();
+    assert_eq!(inbound.conversation_scope, "dm:alice@example.com");
+    assert_eq!(inbound.text, "Status?\n\nHow is the deploy going?");
+    assert_eq!(inbound.thread_id.as_deref(), Some("<q1@example.com>"));
+    assert_eq!(inbound.attachments.len(), 1);
+    assert_eq!(
+        inbound.attachments[0].file_name.as_deref(),
+        Some("graph.png")
+    );
+    assert_eq!(
+        inbound.attachments[0].mime_type.as_deref(),
+        Some("image/png")
+    );
+}
+
+// ---------------------------------------------------------------------------
+// SMTP: authenticated reply that keeps the thread headers
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn smtp_reply_preserves_threading_headers() {
+    let (port, server) = smtp_stand_in().await;
+    let original = parse_email(MESSAGE.as_bytes());
+    let reply = OutgoingEmail::reply_to(
+        "bot@example.com",
+        &original,
+        "Deploy finished.\n.hidden line",
+    )
+    .unwrap();
+
+    SmtpClient::new(
+        "127.0.0.1",
+        port,
+        Security::None,
+        "bot@example.com",
+        "secret",
+    )
+    .unwrap()
+    .send(&reply)
+    .await
+    .unwrap();
+
+    let transcript = server.await.unwrap();
+    assert!(transcript.contains("AUTH PLAIN AGJvdEBleGFtcGxlLmNvbQBzZWNyZXQ=\r\n"));
+    assert!(transcript.contains("MAIL FROM:<bot@example.com>\r\n"));
+    assert!(transcript.contains("RCPT TO:<alice@example.com>\r\n"));
+    assert!(transcript.contains("Subject: Re: Status?\r\n"));
+    assert!(transcript.contains("In-Reply-To: <q1@example.com>\r\n"));
+    assert!(transcript.contains("References: <q1@example.com>\r\n"));
+    assert!(transcript.ends_with("QUIT\r\n"));
+
+    let data = transcript
+        .split_once("DATA\r\n")
+        .unwrap()
+        .1
+        .split_once("\r\n.\r\n")
+        .unwrap()
+        .0;
+    // Leading dots are stuffed on the wire; undo that as a server would.
+    assert!(data.contains("\r\n..hidden line"));
+    let data = data.replace("\r\n..", "\r\n.");
+    assert_eq!(
+        parse_email(data.as_bytes()).text,
+        "Deploy finished.\n.hidden line"
+    );
+}
@@ -0,0 +1,260 @@
+#![cfg(feature = "matrix")]
+
+use wiremock::matchers::{body_partial_json, header, method, path, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use clawhive_channels::matrix::{MatrixAdapter, MatrixClient, MatrixIncoming, RoomDirectory};
+
+const BOT: &str = "@clawhive:example.org";
+
+// ---------------------------------------------------------------------------
+// Sync: whoami, initial sync, then a batch with a DM message and an invite
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn sync_loop_routes_dm_messages_and_invites() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/_matrix/client/v3/account/whoami"))
+        .and(header("authorization", "Bearer syt_test"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "user_id": BOT })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/_matrix/client/v3/sync"))
+        .and(query_param("since", "s1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next_batch": "s2",
+            "rooms": {
+                "join": { "!dm:example.org": { "timeline": { "events": [{
+                    "type": "m.room.message",
+                    "event_id": "$m1",
+                    "sender": "@alice:example.org",
+                    "content": { "msgtype": "m.text", "body": "hello bot" },
+                }]}}},
+                "invite": { "!team:example.org": { "invite_state": { "events": [{
+                    "type": "m.room.member",
+                    "sender": "@alice:example.org",
+                    "state_key": BOT,
+                    "content": { "membership": "invite" },
+                }]}}},
+            },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/_matrix/client/v3/sync"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next_batch": "s1",
+            "account_data": { "events": [{
+                "type": "m.direct",
+                "content": { "@alice:example.org": ["!dm:example.org"] },
+            }]},
+            "rooms": { "join": { "!dm:example.org": { "timeline": { "events": [{
+                "type": "m.room.message",
+                "event_id": "$old",
+                "sender": "@alice:example.org",
+                "content": { "msgtype": "m.text", "body": "already answered" },
+            }]}}}},
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path_regex(
+            r"^/_matrix/client/v3/join/%21team%3Aexample\.org$",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "room_id": "!team:example.org" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = MatrixClient::new(server.uri(), "syt_test");
+    let adapter = MatrixAdapter::new("matrix-main");
+    let mut directory = RoomDirectory::default();
+    assert_eq!(client.whoami().await.unwrap(), BOT);
+
+    let first = client.sync(None, 0).await.unwrap();
+    assert!(directory.absorb(&first, &adapter, BOT, true).is_empty());
+
+    let next = client.sync(Some(&first.next_batch), 0).await.unwrap();
+    assert_eq!(next.next_batch, "s2");
+    let mut incoming = directory.absorb(&next, &adapter, BOT, false);
+    incoming.sort_by_key(|i| matches!(i, MatrixIncoming::Invite { .. }));
+
+    let MatrixIncoming::Message { room_id, inbound } = &incoming[0] else {
+        panic!("expected a message, got {incoming:?}");
+    };
+    assert_eq!(room_id, "!dm:example.org");
+    assert_eq!(inbound.conversation_scope, "dm:!dm:example.org");
+    assert_eq!(inbound.user_scope, "user:@alice:example.org");
+    assert_eq!(inbound.text, "hello bot");
+
+    let MatrixIncoming::Invite { room_id, .. } = &incoming[1] else {
+        panic!("expected an invite");
+    };
+    client.join(room_id).await.unwrap();
+}
+
+// ---------------------------------------------------------------------------
+// DMs: accepted direct invites are recorded in m.direct
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn mark_direct_adds_room_to_existing_direct_map() {
+    let server = MockServer::start().await;
+    let direct_path =
+        r"^/_matrix/client/v3/user/%40clawhive%3Aexample\.org/account_data/m\.direct$";
+    Mock::given(method("GET"))
+        .and(path_regex(direct_path))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "@bob:example.org": ["!bob:example.org"],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path_regex(direct_path))
+        .and(body_partial_json(serde_json::json!({
+            "@bob:example.org": ["!bob:example.org"],
+            "@alice:example.org": ["!alice:example.org"],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = MatrixClient::new(server.uri(), "syt_test");
+    client
+        .mark_direct(BOT, "@alice:example.org", "!alice:example.org")
+        .await
+        .unwrap();
+    // Already recorded: nothing is written.
+    client
+        .mark_direct(BOT, "@bob:example.org", "!bob:example.org")
+        .await
+        .unwrap();
+
+    let missing = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "errcode": "M_NOT_FOUND",
+        })))
+        .mount(&missing)
+        .await;
+    let client = MatrixClient::new(missing.uri(), "syt_test");
+    assert_eq!(client.account_data(BOT, "m.direct").await.unwrap(), None);
+}
+
+// ---------------------------------------------------------------------------
+// Sending: threaded replies, reactions, edits, redactions and media
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn client_sends_threaded_replies_reactions_and_edits() {
+    let server = MockServer::start().await;
+    let room = r"/rooms/%21room%3Aexample\.org";
+    Mock::given(method("PUT"))
+        .and(path_regex(format!(
+            r"^/_matrix/client/v3{room}/send/m\.room\.message/[0-9a-f-]+$"
+        )))
+        .and(body_partial_json(serde_json::json!({
+            "body": "done",
+            "m.relates_to": { "rel_type": "m.thread", "event_id": "$root" },
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "event_id": "$reply" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path_regex(format!(
+            r"^/_matrix/client/v3{room}/send/m\.room\.message/"
+        )))
+        .and(body_partial_json(serde_json::json!({
+            "m.new_content": { "body": "done!" },
+            "m.relates_to": { "rel_type": "m.replace", "event_id": "$reply" },
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "event_id": "$edit" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path_regex(format!(
+            r"^/_matrix/client/v3{room}/send/m\.reaction/"
+        )))
+        .and(body_partial_json(serde_json::json!({
+            "m.relates_to": { "rel_type": "m.annotation", "event_id": "$reply", "key": "👀" },
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "event_id": "$react" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path_regex(format!(
+            r"^/_matrix/client/v3{room}/redact/%24react/"
+        )))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "event_id": "$redaction" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/_matrix/client/v1/media/download/example.org/abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"png-bytes".to_vec()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path_regex(r"/send/m\.room\.message/"))
+        .and(body_partial_json(
+            serde_json::json!({ "body": "forbidden" }),
+        ))
+        .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+            "errcode": "M_FORBIDDEN",
+            "error": "not in room",
+        })))
+        .mount(&server)
+        .await;
+
+    let client = MatrixClient::new(format!("{}/", server.uri()), "syt_test");
+    let reply = client
+        .send_text("!room:example.org", "done", Some("$root"), Some("$m1"))
+        .await
+        .unwrap();
+    assert_eq!(reply, "$reply");
+    assert_eq!(
+        client
+            .edit("!room:example.org", &reply, "done!")
+            .await
+            .unwrap(),
+        "$edit"
+    );
+    let reaction = client
+        .react("!room:example.org", &reply, "👀")
+        .await
+        .unwrap();
+    client.redact("!room:example.org", &reaction).await.unwrap();
+    assert_eq!(
+        client.download("mxc://example.org/abc").await.unwrap(),
+        b"png-bytes"
+    );
+
+    let err = client
+        .send_text("!other:example.org", "forbidden", None, None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("M_FORBIDDEN"));
+}
@@ -0,0 +1,310 @@
+#![cfg(feature = "slack")]
+
+use futures_util::{SinkExt, StreamExt};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use clawhive_channels::slack::{
+    events_router, run_socket_mode, SlackApi, SlackBlockAction, SlackIncoming,
+};
+
+/// Accept Socket Mode connections, greeting each one. The first connection
+/// also gets every envelope; the acks it sends back are collected.
+async fn fake_socket_server(envelopes: Vec<serde_json::Value>) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/", listener.local_addr().unwrap());
+    let (acks_tx, acks_rx) = mpsc::channel(8);
+    tokio::spawn(async move {
+        let hello = serde_json::json!({
+            "type": "hello",
+            "num_connections": 1,
+            "connection_info": { "app_id": "A1" },
+            "debug_info": { "host": "fake" },
+        })
+        .to_string();
+        let mut envelopes = Some(envelopes);
+        while let Ok((stream, _)) = listener.accept().await {
+            let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+                continue;
+            };
+            let envelopes = envelopes.take().unwrap_or_default();
+            let acks_tx = acks_tx.clone();
+            let hello = hello.clone();
+            tokio::spawn(async move {
+                ws.send(WsMessage::Text(hello.into())).await.unwrap();
+                for envelope in envelopes {
+                    ws.send(WsMessage::Text(envelope.to_string().into()))
+                        .await
+                        .unwrap();
+                }
+                while let Some(Ok(frame)) = ws.next().await {
+                    if let WsMessage::Text(text) = frame {
+                        let ack: serde_json::Value = serde_json::from_str(&text).unwrap();
+                        if let Some(id) = ack["envelope_id"].as_str() {
+                            let _ = acks_tx.send(id.to_string()).await;
+                        }
+                    }
+                }
+            });
+        }
+    });
+    (url, acks_rx)
+}
+
+fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("v0:{timestamp}:{body}").as_bytes());
+    format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+// ---------------------------------------------------------------------------
+// Socket Mode: envelopes are acked and forwarded until Slack says disconnect
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn socket_mode_acks_envelopes_and_forwards_events() {
+    let (ws_url, mut acks) = fake_socket_server(vec![
+        serde_json::json!({
+            "envelope_id": "env-1",
+            "type": "events_api",
+            "accepts_response_payload": false,
+            "payload": {
+                "type": "event_callback",
+                "team_id": "T1",
+                "api_app_id": "A1",
+                "event_id": "Ev1",
+                "event_time": 1_700_000_000,
+                "event": {
+                    "type": "app_mention",
+                    "channel": "C1",
+                    "user": "U1",
+                    "text": "<@UBOT> hi",
+                    "ts": "1700000000.000100",
+                    "thread_ts": "1699999999.000100",
+                },
+            },
+        }),
+        serde_json::json!({
+            "envelope_id": "env-2",
+            "type": "interactive",
+            "accepts_response_payload": false,
+            "payload": {
+                "type": "block_actions",
+                "team": { "id": "T1" },
+                "user": { "id": "U1" },
+                "api_app_id": "A1",
+                "trigger_id": "tr1",
+                "channel": { "id": "C1" },
+                "container": { "type": "message", "message_ts": "1700000000.000200" },
+                "actions": [{ "type": "button", "action_id": "approve_allow", "value": "approve:ab12:allow" }],
+            },
+        }),
+    ])
+    .await;
+
+    let api_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/apps.connections.open"))
+        .and(header("authorization", "Bearer xapp-test"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "ok": true, "url": ws_url })),
+        )
+        .mount(&api_server)
+        .await;
+
+    let api = SlackApi::new("xoxb-test", &api_server.uri()).unwrap();
+    let (tx, mut rx) = mpsc::channel(8);
+    let session = tokio::spawn(run_socket_mode(api, "xapp-test".into(), tx));
+
+    let timeout = std::time::Duration::from_secs(10);
+    let Some(SlackIncoming::Message(event)) =
+        tokio::time::timeout(timeout, rx.recv()).await.unwrap()
+    else {
+        panic!("expected a message event");
+    };
+    assert!(event.is_app_mention);
+    assert_eq!(event.thread_ts.as_deref(), Some("1699999999.000100"));
+
+    let Some(SlackIncoming::BlockAction(action)) =
+        tokio::time::timeout(timeout, rx.recv()).await.unwrap()
+    else {
+        panic!("expected a block action");
+    };
+    assert_eq!(
+        action,
+        SlackBlockAction {
+            user_id: "U1".into(),
+            channel_id: "C1".into(),
+            message_ts: "1700000000.000200".into(),
+            thread_ts: None,
+            value: "approve:ab12:allow".into(),
+        }
+    );
+
+    let mut acked = Vec::new();
+    for _ in 0..2 {
+        acked.push(
+            tokio::time::timeout(timeout, acks.recv())
+                .await
+                .unwrap()
+                .unwrap(),
+        );
+    }
+    acked.sort();
+    assert_eq!(acked, ["env-1", "env-2"]);
+
+    // Dropping the receiver shuts the listener down.
+    drop(rx);
+    tokio::time::timeout(timeout, session)
+        .await
+        .expect("listener should stop once events are no longer read")
+        .unwrap()
+        .unwrap();
+}
+
+// ---------------------------------------------------------------------------
+// Events API: signed requests only, url_verification answered inline
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn events_api_verifies_signatures_and_forwards_events() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/slack/events", listener.local_addr().unwrap());
+    let (tx, mut rx) = mpsc::channel(8);
+    tokio::spawn(async move {
+        axum::serve(listener, events_router("shh", tx))
+            .await
+            .unwrap();
+    });
+
+    let client = reqwest::Client::new();
+    let post = |body: String, content_type: &'static str, secret: &'static str| {
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        client
+            .post(&url)
+            .header("content-type", content_type)
+            .header("x-slack-request-timestamp", &timestamp)
+            .header("x-slack-signature", sign(secret, &timestamp, &body))
+            .body(body)
+            .send()
+    };
+
+    let challenge = r#"{"type":"url_verification","challenge":"abc123"}"#.to_string();
+    let resp = post(challenge.clone(), "application/json", "wrong")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+
+    let resp = post(challenge, "application/json", "shh").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["challenge"], "abc123");
+
+    let event = serde_json::json!({
+        "type": "event_callback",
+        "team_id": "T1",
+        "api_app_id": "A1",
+        "event_id": "Ev1",
+        "event_time": 1_700_000_000,
+        "event": {
+            "type": "message",
+            "channel": "D1",
+            "channel_type": "im",
+            "user": "U1",
+            "text": "hello",
+            "ts": "1700000000.000100",
+        },
+    });
+    let resp = post(event.to_string(), "application/json", "shh")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let Some(SlackIncoming::Message(message)) = rx.recv().await else {
+        panic!("expected a message event");
+    };
+    assert_eq!(message.text, "hello");
+    assert!(message.is_direct);
+
+    let interaction = serde_json::json!({
+        "type": "block_actions",
+        "team": { "id": "T1" },
+        "user": { "id": "U1" },
+        "api_app_id": "A1",
+        "trigger_id": "tr1",
+        "channel": { "id": "D1" },
+        "container": { "type": "message", "message_ts": "1700000000.000200" },
+        "message": { "ts": "1700000000.000200" },
+        "actions": [{ "type": "button", "action_id": "approve_deny", "value": "approve:ab12:deny" }],
+    });
+    let form: String = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("payload", &interaction.to_string())
+        .finish();
+    let resp = post(form, "application/x-www-form-urlencoded", "shh")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let Some(SlackIncoming::BlockAction(action)) = rx.recv().await else {
+        panic!("expected a block action");
+    };
+    assert_eq!(action.value, "approve:ab12:deny");
+    assert_eq!(action.message_ts, "1700000000.000200");
+}
+
+// ---------------------------------------------------------------------------
+// Web API: threaded replies, edits and reactions
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn web_api_posts_in_thread_and_reports_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat.postMessage"))
+        .and(header("authorization", "Bearer xoxb-test"))
+        .and(body_partial_json(serde_json::json!({
+            "channel": "C1",
+            "text": "done",
+            "thread_ts": "1700000000.000100",
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ok": true,
+            "channel": "C1",
+            "ts": "1700000000.000900",
+            "message": { "ts": "1700000000.000900", "text": "done" },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/reactions.add"))
+        .and(body_partial_json(serde_json::json!({
+            "channel": "C1",
+            "timestamp": "1700000000.000100",
+            "name": "eyes",
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "ok": false, "error": "already_reacted" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let api = SlackApi::new("xoxb-test", &server.uri()).unwrap();
+    let ts = api
+        .post_message("C1", "done", Some("1700000000.000100"), None)
+        .await
+        .unwrap();
+    assert_eq!(ts, "1700000000.000900");
+
+    let err = api
+        .add_reaction("C1", "1700000000.000100", "eyes")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("already_reacted"));
+}
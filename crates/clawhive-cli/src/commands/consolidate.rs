@@ -45,11 +45,20 @@ pub(crate) async fn run(root: &Path, agent_id_override: Option<&str>) -> Result<
             embedding_cache_ttl_days: config.main.memory_search.embedding_cache_ttl_days,
         },
     );
-    let consolidation_embedding_provider = build_embedding_provider(&config).await;
+    let consolidation_embedding_provider = clawhive_core::runtime_config::with_embedding_cache(
+        build_embedding_provider(&config).await?,
+        &memory,
+        &config,
+    );
     let mut consolidator_builder = HippocampusConsolidator::new(
         consolidation_agent_id,
         file_store.clone(),
-        Arc::new(build_router_from_config(&config).await),
+        Arc::new(
+            build_router_from_config(&config)
+                .await
+                .with_usage_ledger(clawhive_memory::usage::UsageLedger::new(memory.db()))
+                .with_budgets(clawhive_core::runtime_config::build_budget_guard(&config)),
+        ),
         agent_config.model_policy.primary.clone(),
         agent_config.model_policy.fallbacks.clone(),
     )
@@ -0,0 +1,43 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Subcommand;
+use clawhive_core::SecurityMode;
+use clawhive_gateway::mcp_server::{serve_stdio, McpServer};
+
+use crate::runtime::bootstrap::{bootstrap, resolve_security_override};
+
+#[derive(Subcommand)]
+pub(crate) enum McpCommands {
+    #[command(about = "Serve agents and their memory as MCP tools over stdio")]
+    Serve {
+        /// Override security mode (overrides agent config)
+        #[arg(long, value_name = "MODE")]
+        security: Option<SecurityMode>,
+        /// Shorthand for --security off
+        #[arg(long)]
+        no_security: bool,
+    },
+}
+
+pub(crate) async fn run(cmd: McpCommands, root: &Path) -> Result<()> {
+    match cmd {
+        McpCommands::Serve {
+            security,
+            no_security,
+        } => {
+            let security_override = resolve_security_override(security, no_security);
+            let (_bus, _memory, gateway, _config, _schedule_manager, _wait_manager, _approvals) =
+                bootstrap(root, security_override).await?;
+            let server = Arc::new(McpServer::new(gateway, "stdio"));
+            tracing::info!("serving MCP over stdio");
+            serve_stdio(
+                server,
+                tokio::io::BufReader::new(tokio::io::stdin()),
+                tokio::io::stdout(),
+            )
+            .await
+        }
+    }
+}
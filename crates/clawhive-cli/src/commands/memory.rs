@@ -1,10 +1,12 @@
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use chrono::NaiveDate;
 use clap::Subcommand;
-use clawhive_core::{MemoryDocument, MEMORY_SECTION_ORDER};
+use clawhive_core::{
+    ClawhiveConfig, ImportFormat, ImportPlan, MemoryDocument, MemoryImporter, MEMORY_SECTION_ORDER,
+};
 use clawhive_memory::dirty_sources::{
     DirtySourceStore, DIRTY_KIND_DAILY_FILE, DIRTY_KIND_FACT, DIRTY_KIND_MEMORY_FILE,
     DIRTY_KIND_SESSION,
@@ -14,7 +16,7 @@ use clawhive_memory::file_audit::{audit_memory_file, cleanup_memory_file, Memory
 use clawhive_memory::file_store::MemoryFileStore;
 use clawhive_memory::memory_lineage::MemoryLineageStore;
 use clawhive_memory::search_index::{SearchConfig, SearchIndex};
-use clawhive_memory::SessionReader;
+use clawhive_memory::{MemoryStore, SessionReader};
 
 use crate::runtime::bootstrap::{bootstrap, build_embedding_provider};
 
@@ -95,6 +97,29 @@ pub enum MemoryCommands {
         #[arg(long, help = "Export format: json or markdown (default: json)")]
         format: Option<String>,
     },
+    #[command(
+        about = "Import memory from an export, markdown folder, OpenClaw workspace or chat transcript"
+    )]
+    Import {
+        #[arg(help = "Agent ID to import into")]
+        agent_id: String,
+        #[arg(
+            help = "Export JSON, markdown file or folder, OpenClaw workspace, or JSONL/CSV transcript"
+        )]
+        path: PathBuf,
+        #[arg(
+            long,
+            help = "Source format: export, markdown, openclaw or transcript (default: detect)"
+        )]
+        format: Option<String>,
+        #[arg(
+            long,
+            help = "Session key (channel_type:connector_id:conversation:user:id) for transcripts that name none; without one only the shared layer can search them"
+        )]
+        session_key: Option<String>,
+        #[arg(long, help = "Report what would be imported without writing anything")]
+        dry_run: bool,
+    },
 }
 
 pub async fn run(cmd: MemoryCommands, root: &Path) -> Result<()> {
@@ -274,7 +299,11 @@ pub async fn run(cmd: MemoryCommands, root: &Path) -> Result<()> {
                                 .embedding_cache_ttl_days,
                         },
                     );
-                    let embedding_provider = build_embedding_provider(&config).await;
+                    let embedding_provider = clawhive_core::runtime_config::with_embedding_cache(
+                        build_embedding_provider(&config).await?,
+                        &memory,
+                        &config,
+                    );
                     search_index
                         .process_dirty_sources(
                             &dirty_store,
@@ -295,29 +324,12 @@ pub async fn run(cmd: MemoryCommands, root: &Path) -> Result<()> {
             let workspace_dir = root.join("workspaces").join(&agent_id);
             let file_store = MemoryFileStore::new(&workspace_dir);
             let session_reader = SessionReader::new(&workspace_dir);
-            let search_index = SearchIndex::new_with_config(
-                memory.db(),
-                &agent_id,
-                SearchConfig {
-                    vector_weight: config.main.memory_search.vector_weight,
-                    bm25_weight: config.main.memory_search.bm25_weight,
-                    decay_half_life_days: config.main.memory_search.decay_half_life_days,
-                    mmr_lambda: config.main.memory_search.mmr_lambda,
-                    access_boost_factor: config.main.memory_search.access_boost_factor,
-                    hot_days: config.main.memory_search.temperature.hot_days,
-                    warm_days: config.main.memory_search.temperature.warm_days,
-                    cold_filter: config.main.memory_search.temperature.cold_filter,
-                    access_protect_count: config
-                        .main
-                        .memory_search
-                        .temperature
-                        .access_protect_count,
-                    max_results: config.main.memory_search.max_results,
-                    min_score: config.main.memory_search.min_score,
-                    embedding_cache_ttl_days: config.main.memory_search.embedding_cache_ttl_days,
-                },
+            let search_index = build_search_index(&memory, &config, &agent_id);
+            let embedding_provider = clawhive_core::runtime_config::with_embedding_cache(
+                build_embedding_provider(&config).await?,
+                &memory,
+                &config,
             );
-            let embedding_provider = build_embedding_provider(&config).await;
 
             println!("Rebuilding session index for agent '{agent_id}'...");
             let count = search_index
@@ -459,28 +471,7 @@ pub async fn run(cmd: MemoryCommands, root: &Path) -> Result<()> {
             let workspace_dir = root.join("workspaces").join(&agent_id);
             let file_store = MemoryFileStore::new(&workspace_dir);
             let session_reader = SessionReader::new(&workspace_dir);
-            let search_index = SearchIndex::new_with_config(
-                memory.db(),
-                &agent_id,
-                SearchConfig {
-                    vector_weight: config.main.memory_search.vector_weight,
-                    bm25_weight: config.main.memory_search.bm25_weight,
-                    decay_half_life_days: config.main.memory_search.decay_half_life_days,
-                    mmr_lambda: config.main.memory_search.mmr_lambda,
-                    access_boost_factor: config.main.memory_search.access_boost_factor,
-                    hot_days: config.main.memory_search.temperature.hot_days,
-                    warm_days: config.main.memory_search.temperature.warm_days,
-                    cold_filter: config.main.memory_search.temperature.cold_filter,
-                    access_protect_count: config
-                        .main
-                        .memory_search
-                        .temperature
-                        .access_protect_count,
-                    max_results: config.main.memory_search.max_results,
-                    min_score: config.main.memory_search.min_score,
-                    embedding_cache_ttl_days: config.main.memory_search.embedding_cache_ttl_days,
-                },
-            );
+            let search_index = build_search_index(&memory, &config, &agent_id);
             let dirty_store = DirtySourceStore::new(memory.db());
             let fact_store = FactStore::new(memory.db());
 
@@ -523,7 +514,11 @@ pub async fn run(cmd: MemoryCommands, root: &Path) -> Result<()> {
                 enqueued += 1;
             }
 
-            let embedding_provider = build_embedding_provider(&config).await;
+            let embedding_provider = clawhive_core::runtime_config::with_embedding_cache(
+                build_embedding_provider(&config).await?,
+                &memory,
+                &config,
+            );
             let reindexed = search_index
                 .process_dirty_sources(
                     &dirty_store,
@@ -586,9 +581,105 @@ pub async fn run(cmd: MemoryCommands, root: &Path) -> Result<()> {
 
             Ok(())
         }
+        MemoryCommands::Import {
+            agent_id,
+            path,
+            format,
+            session_key,
+            dry_run,
+        } => {
+            let format = format
+                .as_deref()
+                .map(str::parse::<ImportFormat>)
+                .transpose()?;
+            let plan = ImportPlan::load(&path, format)?;
+            let workspace_dir = root.join("workspaces").join(&agent_id);
+            let report = MemoryImporter::new(&memory, &agent_id, &workspace_dir)
+                .with_session_key(session_key)?
+                .with_dry_run(dry_run)
+                .apply(plan)
+                .await?;
+
+            let verb = if dry_run { "Would import" } else { "Imported" };
+            println!("{verb} into '{agent_id}' from {}:", path.display());
+            println!(
+                "  facts:        {} new, {} duplicates skipped",
+                report.facts_imported, report.facts_skipped
+            );
+            println!(
+                "  MEMORY.md:    {}",
+                if report.long_term_restored {
+                    "restored"
+                } else {
+                    "unchanged"
+                }
+            );
+            println!(
+                "  daily files:  {} restored, {} already present",
+                report.daily_files_restored, report.daily_files_skipped
+            );
+            println!(
+                "  sessions:     {} imported, {} already present",
+                report.sessions_imported, report.sessions_skipped
+            );
+            if report.transcript_rows_dropped > 0 {
+                println!(
+                    "  transcript rows without content dropped: {}",
+                    report.transcript_rows_dropped
+                );
+            }
+            if dry_run {
+                return Ok(());
+            }
+
+            let file_store = MemoryFileStore::new(&workspace_dir);
+            let session_reader = SessionReader::new(&workspace_dir);
+            let embedding_provider = clawhive_core::runtime_config::with_embedding_cache(
+                build_embedding_provider(&config).await?,
+                &memory,
+                &config,
+            );
+            let reindexed = build_search_index(&memory, &config, &agent_id)
+                .process_dirty_sources(
+                    &DirtySourceStore::new(memory.db()),
+                    &agent_id,
+                    &file_store,
+                    &session_reader,
+                    embedding_provider.as_ref(),
+                    usize::MAX,
+                )
+                .await?;
+            println!("  indexed_chunks: {reindexed}");
+            Ok(())
+        }
     }
 }
 
+fn build_search_index(
+    memory: &MemoryStore,
+    config: &ClawhiveConfig,
+    agent_id: &str,
+) -> SearchIndex {
+    SearchIndex::new_with_config(
+        memory.db(),
+        agent_id,
+        SearchConfig {
+            vector_weight: config.main.memory_search.vector_weight,
+            bm25_weight: config.main.memory_search.bm25_weight,
+            decay_half_life_days: config.main.memory_search.decay_half_life_days,
+            mmr_lambda: config.main.memory_search.mmr_lambda,
+            access_boost_factor: config.main.memory_search.access_boost_factor,
+            hot_days: config.main.memory_search.temperature.hot_days,
+            warm_days: config.main.memory_search.temperature.warm_days,
+            cold_filter: config.main.memory_search.temperature.cold_filter,
+            access_protect_count: config.main.memory_search.temperature.access_protect_count,
+            max_results: config.main.memory_search.max_results,
+            min_score: config.main.memory_search.min_score,
+            embedding_cache_ttl_days: config.main.memory_search.embedding_cache_ttl_days,
+        },
+    )
+}
+
 struct MemoryFileEntry {
     path: String,
     kind: MemoryFileKind,
@@ -7,6 +7,7 @@ pub mod config;
 pub mod consolidate;
 pub mod dashboard;
 pub mod logs;
+pub mod mcp;
 pub mod memory;
 pub mod reload;
 pub mod schedule;
@@ -17,5 +18,6 @@ pub mod start;
 pub mod status;
 pub mod task;
 pub mod update;
+pub mod usage;
 pub mod validate;
 pub mod wait;
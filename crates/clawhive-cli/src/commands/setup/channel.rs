@@ -7,7 +7,8 @@ use dialoguer::{theme::ColorfulTheme, Confirm, Select};
 
 use clawhive_core::config::{
     DingTalkChannelConfig, DingTalkConnectorConfig, DiscordChannelConfig, DiscordConnectorConfig,
-    FeishuChannelConfig, FeishuConnectorConfig, IMessageChannelConfig, IMessageConnectorConfig,
+    EmailChannelConfig, EmailConnectorConfig, FeishuChannelConfig, FeishuConnectorConfig,
+    IMessageChannelConfig, IMessageConnectorConfig, MatrixChannelConfig, MatrixConnectorConfig,
     SlackChannelConfig, SlackConnectorConfig, TelegramChannelConfig, TelegramConnectorConfig,
     WeComChannelConfig, WeComConnectorConfig, WeixinChannelConfig, WeixinConnectorConfig,
     WhatsAppChannelConfig, WhatsAppConnectorConfig,
@@ -33,6 +34,15 @@ pub(super) struct ChannelConfig {
     pub(super) bot_id: Option<String>,
     pub(super) secret: Option<String>,
     pub(super) bot_token: Option<String>,
+    pub(super) app_token: Option<String>,
+    pub(super) signing_secret: Option<String>,
+    pub(super) events_listen: Option<String>,
+    pub(super) homeserver_url: Option<String>,
+    pub(super) email_address: Option<String>,
+    pub(super) imap_host: Option<String>,
+    pub(super) smtp_host: Option<String>,
+    pub(super) authserv_id: Option<String>,
+    pub(super) password: Option<String>,
     pub(super) db_path: Option<String>,
     pub(super) poll_interval_secs: Option<u64>,
     pub(super) allow_from: Option<Vec<String>>,
@@ -46,8 +56,8 @@ pub(super) fn handle_add_channel(
     _force: bool,
 ) -> Result<()> {
     let channel_types = [
-        "Telegram", "Discord", "Slack", "WhatsApp", "iMessage", "Feishu", "DingTalk", "WeCom",
-        "WeChat", "← Back",
+        "Telegram", "Discord", "Slack", "Matrix", "Email", "WhatsApp", "iMessage", "Feishu",
+        "DingTalk", "WeCom", "WeChat", "← Back",
     ];
     let selected = Select::with_theme(theme)
         .with_prompt("Channel type")
@@ -58,18 +68,22 @@ pub(super) fn handle_add_channel(
         0 => "telegram",
         1 => "discord",
         2 => "slack",
-        3 => "whatsapp",
-        4 => "imessage",
-        5 => "feishu",
-        6 => "dingtalk",
-        7 => "wecom",
-        8 => "weixin",
+        3 => "matrix",
+        4 => "email",
+        5 => "whatsapp",
+        6 => "imessage",
+        7 => "feishu",
+        8 => "dingtalk",
+        9 => "wecom",
+        10 => "weixin",
         _ => return Ok(()),
     };
     let default_id = match channel_type {
         "telegram" => "my_telegram_bot",
         "discord" => "my_discord_bot",
         "slack" => "my_slack_bot",
+        "matrix" => "my_matrix_bot",
+        "email" => "my_email_bot",
         "whatsapp" => "my_whatsapp_bot",
         "imessage" => "my_imessage_bot",
         "feishu" => "my_feishu_bot",
@@ -96,6 +110,15 @@ pub(super) fn handle_add_channel(
     let mut bot_id_str = None;
     let mut secret = None;
     let mut bot_token_str = None;
+    let mut app_token = None;
+    let mut signing_secret = None;
+    let mut events_listen = None;
+    let mut homeserver_url = None;
+    let mut email_address = None;
+    let mut imap_host = None;
+    let mut smtp_host = None;
+    let mut authserv_id = None;
+    let mut password = None;
     let mut poll_interval = None;
     let mut allow_from = None;
 
@@ -108,6 +131,127 @@ pub(super) fn handle_add_channel(
             };
             println!("  {ARROW} Token saved: {}", mask_secret(&bt));
             bot_token_str = Some(bt);
+            let at = match input_or_back_with_default(
+                theme,
+                "App-level token (xapp-..., enables Socket Mode; leave empty to use the Events API)",
+                "",
+            )? {
+                Some(t) => t,
+                None => return Ok(()),
+            };
+            if at.is_empty() {
+                let sec = match input_or_back(theme, "Signing secret (from Basic Information)")? {
+                    Some(t) if !t.is_empty() => t,
+                    Some(_) => anyhow::bail!("Signing secret cannot be empty"),
+                    None => return Ok(()),
+                };
+                let listen = match input_or_back_with_default(
+                    theme,
+                    "Events API listen address (Request URL path: /slack/events)",
+                    "0.0.0.0:3100",
+                )? {
+                    Some(l) => l,
+                    None => return Ok(()),
+                };
+                println!("  {ARROW} Events API on {listen}: {}", mask_secret(&sec));
+                signing_secret = Some(sec);
+                events_listen = Some(listen);
+            } else {
+                println!("  {ARROW} Socket Mode token saved: {}", mask_secret(&at));
+                app_token = Some(at);
+            }
+            token = String::new();
+        }
+        "matrix" => {
+            let hs =
+                match input_or_back_with_default(theme, "Homeserver URL", "https://matrix.org")? {
+                    Some(u) if !u.is_empty() => u,
+                    Some(_) => anyhow::bail!("Homeserver URL cannot be empty"),
+                    None => return Ok(()),
+                };
+            token = match input_or_back(theme, "Access token of the bot account")? {
+                Some(t) if !t.is_empty() => t,
+                Some(_) => anyhow::bail!("Access token cannot be empty"),
+                None => return Ok(()),
+            };
+            println!("  {ARROW} Token saved for {hs}: {}", mask_secret(&token));
+            let allow_input = match input_or_back_with_default(
+                theme,
+                "Allowed Matrix users (comma-separated, e.g. @alice:matrix.org; leave empty for all)",
+                "",
+            )? {
+                Some(value) => value,
+                None => return Ok(()),
+            };
+            allow_from = Some(
+                allow_input
+                    .split(',')
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .collect(),
+            );
+            homeserver_url = Some(hs);
+        }
+        "email" => {
+            let address = match input_or_back(theme, "Bot email address")? {
+                Some(a) if a.contains('@') => a,
+                Some(_) => anyhow::bail!("Email address must contain '@'"),
+                None => return Ok(()),
+            };
+            let domain = address.rsplit('@').next().unwrap_or_default();
+            let imap = match input_or_back_with_default(
+                theme,
+                "IMAP server (TLS, port 993)",
+                &format!("imap.{domain}"),
+            )? {
+                Some(h) => h,
+                None => return Ok(()),
+            };
+            let smtp = match input_or_back_with_default(
+                theme,
+                "SMTP server (TLS, port 465)",
+                &format!("smtp.{domain}"),
+            )? {
+                Some(h) => h,
+                None => return Ok(()),
+            };
+            let pass = match input_or_back(theme, "Password (an app password is recommended)")? {
+                Some(p) if !p.is_empty() => p,
+                Some(_) => anyhow::bail!("Password cannot be empty"),
+                None => return Ok(()),
+            };
+            println!(
+                "  {ARROW} Credentials saved: {address}:{}",
+                mask_secret(&pass)
+            );
+            let allow_input = match input_or_back_with_default(
+                theme,
+                "Allowed senders (comma-separated addresses or @domain)",
+                "",
+            )? {
+                Some(value) => value,
+                None => return Ok(()),
+            };
+            allow_from = Some(
+                allow_input
+                    .split(',')
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .collect(),
+            );
+            let server_id = match input_or_back(
+                theme,
+                "Receiving server authserv-id (first word of its Authentication-Results header, e.g. mx.google.com)",
+            )? {
+                Some(id) if !id.trim().is_empty() => id.trim().to_string(),
+                Some(_) => anyhow::bail!("authserv-id cannot be empty"),
+                None => return Ok(()),
+            };
+            authserv_id = Some(server_id);
+            email_address = Some(address);
+            imap_host = Some(imap);
+            smtp_host = Some(smtp);
+            password = Some(pass);
             token = String::new();
         }
         "whatsapp" => {
@@ -339,6 +483,15 @@ pub(super) fn handle_add_channel(
         bot_id: bot_id_str,
         secret,
         bot_token: bot_token_str,
+        app_token,
+        signing_secret,
+        events_listen,
+        homeserver_url,
+        email_address,
+        imap_host,
+        smtp_host,
+        authserv_id,
+        password,
         db_path: None,
         poll_interval_secs: poll_interval,
         allow_from: if channel_type == "telegram" {
@@ -499,6 +652,10 @@ fn add_channel_to_config(
             let connector = SlackConnectorConfig {
                 connector_id: cfg.connector_id.clone(),
                 bot_token: cfg.bot_token.clone().unwrap_or_default(),
+                app_token: cfg.app_token.clone(),
+                signing_secret: cfg.signing_secret.clone(),
+                events_listen: cfg.events_listen.clone(),
+                require_mention: cfg.require_mention,
             };
             match main_cfg.channels.slack.as_mut() {
                 Some(sl) => {
@@ -514,6 +671,63 @@ fn add_channel_to_config(
                 }
             }
         }
+        "matrix" => {
+            let connector = MatrixConnectorConfig {
+                connector_id: cfg.connector_id.clone(),
+                homeserver_url: cfg.homeserver_url.clone().unwrap_or_default(),
+                access_token: cfg.token.clone(),
+                require_mention: cfg.require_mention,
+                auto_join: true,
+                allow_from: cfg.allow_from.clone().unwrap_or_default(),
+            };
+            match main_cfg.channels.matrix.as_mut() {
+                Some(mx) => {
+                    mx.enabled = true;
+                    mx.connectors.retain(|c| c.connector_id != cfg.connector_id);
+                    mx.connectors.push(connector);
+                }
+                None => {
+                    main_cfg.channels.matrix = Some(MatrixChannelConfig {
+                        enabled: true,
+                        connectors: vec![connector],
+                    });
+                }
+            }
+        }
+        "email" => {
+            let address = cfg.email_address.clone().unwrap_or_default();
+            let connector = EmailConnectorConfig {
+                connector_id: cfg.connector_id.clone(),
+                username: address.clone(),
+                address,
+                imap_host: cfg.imap_host.clone().unwrap_or_default(),
+                imap_port: 993,
+                imap_security: "tls".to_string(),
+                smtp_host: cfg.smtp_host.clone().unwrap_or_default(),
+                smtp_port: 465,
+                smtp_security: "tls".to_string(),
+                password: cfg.password.clone().unwrap_or_default(),
+                mailbox: "INBOX".to_string(),
+                idle: true,
+                poll_interval_secs: 60,
+                dm_policy: "allowlist".to_string(),
+                allow_from: cfg.allow_from.clone().unwrap_or_default(),
+                authserv_id: cfg.authserv_id.clone(),
+            };
+            match main_cfg.channels.email.as_mut() {
+                Some(em) => {
+                    em.enabled = true;
+                    em.connectors.retain(|c| c.connector_id != cfg.connector_id);
+                    em.connectors.push(connector);
+                }
+                None => {
+                    main_cfg.channels.email = Some(EmailChannelConfig {
+                        enabled: true,
+                        connectors: vec![connector],
+                    });
+                }
+            }
+        }
         "whatsapp" => {
             let connector = WhatsAppConnectorConfig {
                 connector_id: cfg.connector_id.clone(),
@@ -658,6 +872,12 @@ pub(super) fn remove_channel_from_config(config_root: &Path, connector_id: &str)
     if let Some(sl) = cfg.channels.slack.as_mut() {
         sl.connectors.retain(|c| c.connector_id != connector_id);
     }
+    if let Some(mx) = cfg.channels.matrix.as_mut() {
+        mx.connectors.retain(|c| c.connector_id != connector_id);
+    }
+    if let Some(em) = cfg.channels.email.as_mut() {
+        em.connectors.retain(|c| c.connector_id != connector_id);
+    }
     if let Some(wa) = cfg.channels.whatsapp.as_mut() {
         wa.connectors.retain(|c| c.connector_id != connector_id);
     }
@@ -1075,4 +1295,108 @@ mod tests {
         assert!(content.contains("wa-main"));
         assert!(content.contains("+1234567890"));
     }
+
+    #[test]
+    fn add_slack_channel_persists_socket_mode_token() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("config")).unwrap();
+        std::fs::write(
+            temp.path().join("config/main.yaml"),
+            generate_main_yaml("clawhive", None, None),
+        )
+        .unwrap();
+
+        add_channel_to_config(
+            temp.path(),
+            "slack",
+            &ChannelConfig {
+                connector_id: "slack-main".into(),
+                bot_token: Some("xoxb-bot".into()),
+                app_token: Some("xapp-app".into()),
+                require_mention: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let cfg = load_main_config(temp.path()).unwrap();
+        let connector = &cfg.channels.slack.unwrap().connectors[0];
+        assert_eq!(connector.bot_token, "xoxb-bot");
+        assert_eq!(connector.app_token.as_deref(), Some("xapp-app"));
+        assert!(connector.events_listen.is_none());
+    }
+
+    #[test]
+    fn add_and_remove_matrix_channel() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("config")).unwrap();
+        std::fs::write(
+            temp.path().join("config/main.yaml"),
+            generate_main_yaml("clawhive", None, None),
+        )
+        .unwrap();
+
+        add_channel_to_config(
+            temp.path(),
+            "matrix",
+            &ChannelConfig {
+                connector_id: "matrix-main".into(),
+                token: "syt_token".into(),
+                homeserver_url: Some("https://matrix.example.org".into()),
+                allow_from: Some(vec!["@alice:example.org".into()]),
+                require_mention: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let cfg = load_main_config(temp.path()).unwrap();
+        let matrix = cfg.channels.matrix.unwrap();
+        assert!(matrix.enabled);
+        let connector = &matrix.connectors[0];
+        assert_eq!(connector.homeserver_url, "https://matrix.example.org");
+        assert_eq!(connector.access_token, "syt_token");
+        assert_eq!(connector.allow_from, ["@alice:example.org"]);
+        assert!(connector.auto_join);
+
+        remove_channel_from_config(temp.path(), "matrix-main").unwrap();
+        let cfg = load_main_config(temp.path()).unwrap();
+        assert!(cfg.channels.matrix.unwrap().connectors.is_empty());
+    }
+
+    #[test]
+    fn add_email_channel_uses_address_as_login() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("config")).unwrap();
+        std::fs::write(
+            temp.path().join("config/main.yaml"),
+            generate_main_yaml("clawhive", None, None),
+        )
+        .unwrap();
+
+        add_channel_to_config(
+            temp.path(),
+            "email",
+            &ChannelConfig {
+                connector_id: "mail-main".into(),
+                email_address: Some("bot@example.com".into()),
+                imap_host: Some("imap.example.com".into()),
+                smtp_host: Some("smtp.example.com".into()),
+                authserv_id: Some("mx.example.com".into()),
+                password: Some("app-password".into()),
+                allow_from: Some(vec!["@example.com".into()]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let cfg = load_main_config(temp.path()).unwrap();
+        let connector = &cfg.channels.email.unwrap().connectors[0];
+        assert_eq!(connector.username, "bot@example.com");
+        assert_eq!(connector.imap_port, 993);
+        assert_eq!(connector.smtp_security, "tls");
+        assert_eq!(connector.dm_policy, "allowlist");
+        assert_eq!(connector.allow_from, ["@example.com"]);
+        assert_eq!(connector.authserv_id.as_deref(), Some("mx.example.com"));
+    }
 }
@@ -214,6 +214,24 @@ fn scan_main_and_routing(config_dir: &Path) -> (Vec<ChannelInfo>, Option<String>
                 }
             }
 
+            if let Some(matrix) = main.channels.matrix {
+                for connector in matrix.connectors {
+                    channels.push(ChannelInfo {
+                        channel_type: "matrix".to_string(),
+                        connector_id: connector.connector_id,
+                    });
+                }
+            }
+
+            if let Some(email) = main.channels.email {
+                for connector in email.connectors {
+                    channels.push(ChannelInfo {
+                        channel_type: "email".to_string(),
+                        connector_id: connector.connector_id,
+                    });
+                }
+            }
+
             if let Some(whatsapp) = main.channels.whatsapp {
                 for connector in whatsapp.connectors {
                     channels.push(ChannelInfo {
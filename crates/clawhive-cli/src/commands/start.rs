@@ -7,8 +7,10 @@ use anyhow::Result;
 
 use clawhive_channels::dingtalk::DingTalkBot;
 use clawhive_channels::discord::DiscordBot;
+use clawhive_channels::email::{EmailBot, EmailBotConfig, Security, SenderPolicy, SmtpClient};
 use clawhive_channels::feishu::FeishuBot;
 use clawhive_channels::imessage::IMessageBot;
+use clawhive_channels::matrix::MatrixBot;
 use clawhive_channels::slack::{SlackBot, SlackBotConfig};
 use clawhive_channels::telegram::TelegramBot;
 use clawhive_channels::wecom::WeComBot;
@@ -16,10 +18,13 @@ use clawhive_channels::weixin::WeixinBot;
 use clawhive_channels::ChannelBot;
 use clawhive_core::heartbeat::{is_heartbeat_ack, should_skip_heartbeat, DEFAULT_HEARTBEAT_PROMPT};
 use clawhive_core::*;
+use clawhive_gateway::outbound_webhook::{
+    spawn_outbound_webhook_dispatcher, OutboundWebhookStore, OutboundWebhooks,
+};
 use clawhive_gateway::supervisor::{BotFactory, ChannelSupervisor};
 use clawhive_gateway::{
-    spawn_approval_delivery_listener, spawn_cancel_task_listener, spawn_scheduled_task_listener,
-    spawn_wait_task_listener, ReloadCoordinator,
+    spawn_approval_delivery_listener, spawn_cancel_task_listener, spawn_delegate_task_listener,
+    spawn_scheduled_task_listener, spawn_wait_task_listener, ReloadCoordinator,
 };
 
 use crate::runtime::bootstrap::{bootstrap, build_embedding_provider, build_router_from_config};
@@ -296,8 +301,83 @@ fn build_bot_factory() -> BotFactory {
                 .as_str()
                 .unwrap_or_default()
                 .to_string();
-            let slack_config = SlackBotConfig::new(bot_token, connector_id);
-            let bot = SlackBot::new(slack_config, gateway);
+            let mut slack_config = SlackBotConfig::new(bot_token, connector_id)
+                .with_require_mention(config["require_mention"].as_bool().unwrap_or(true));
+            if let Some(app_token) = config["app_token"].as_str().filter(|t| !t.is_empty()) {
+                slack_config = slack_config.with_app_token(app_token);
+            }
+            if let Some(listen) = config["events_listen"].as_str().filter(|l| !l.is_empty()) {
+                let signing_secret = config["signing_secret"].as_str().unwrap_or_default();
+                slack_config = slack_config.with_events_api(signing_secret, listen);
+            }
+            let bot = SlackBot::new(slack_config, gateway).with_bus(bus);
+            Ok(Box::pin(async move { Box::new(bot).run().await })
+                as std::pin::Pin<
+                    Box<dyn std::future::Future<Output = Result<()>> + Send + 'static>,
+                >)
+        }
+        "matrix" => {
+            let homeserver_url = config["homeserver_url"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let access_token = config["access_token"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let connector_id = config["connector_id"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let allow_from = config["allow_from"]
+                .as_array()
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|v| v.as_str().map(str::to_owned))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let data_dir = expand_tilde(&format!("~/.clawhive/data/matrix-{connector_id}"));
+            let _ = std::fs::create_dir_all(&data_dir);
+            let bot = MatrixBot::new(homeserver_url, access_token, connector_id, gateway, bus)
+                .with_require_mention(config["require_mention"].as_bool().unwrap_or(true))
+                .with_auto_join(config["auto_join"].as_bool().unwrap_or(true))
+                .with_allow_from(allow_from)
+                .with_data_dir(data_dir);
+            Ok(Box::pin(async move { Box::new(bot).run().await })
+                as std::pin::Pin<
+                    Box<dyn std::future::Future<Output = Result<()>> + Send + 'static>,
+                >)
+        }
+        "email" => {
+            let connector: EmailConnectorConfig = serde_json::from_value(config.clone())?;
+            let smtp = SmtpClient::new(
+                &connector.smtp_host,
+                connector.smtp_port,
+                Security::parse(&connector.smtp_security)?,
+                &connector.username,
+                &connector.password,
+            )?;
+            let email_config = EmailBotConfig {
+                policy: SenderPolicy::from_config(
+                    &connector.dm_policy,
+                    &connector.allow_from,
+                    connector.authserv_id.as_deref(),
+                ),
+                imap_security: Security::parse(&connector.imap_security)?,
+                poll_interval: Duration::from_secs(connector.poll_interval_secs.max(5)),
+                connector_id: connector.connector_id,
+                address: connector.address,
+                imap_host: connector.imap_host,
+                imap_port: connector.imap_port,
+                smtp,
+                username: connector.username,
+                password: connector.password,
+                mailbox: connector.mailbox,
+                idle: connector.idle,
+            };
+            let bot = EmailBot::new(email_config, gateway, bus);
             Ok(Box::pin(async move { Box::new(bot).run().await })
                 as std::pin::Pin<
                     Box<dyn std::future::Future<Output = Result<()>> + Send + 'static>,
@@ -513,6 +593,36 @@ fn start_configured_bots(
         }
     }
 
+    if let Some(matrix) = &config.main.channels.matrix {
+        if matrix.enabled {
+            for connector in &matrix.connectors {
+                let config_json = serde_json::to_value(connector)?;
+                if let Err(error) =
+                    supervisor.start(connector.connector_id.clone(), "matrix", config_json)
+                {
+                    tracing::error!(connector = %connector.connector_id, "failed to start matrix bot: {error}");
+                } else {
+                    started += 1;
+                }
+            }
+        }
+    }
+
+    if let Some(email) = &config.main.channels.email {
+        if email.enabled {
+            for connector in &email.connectors {
+                let config_json = serde_json::to_value(connector)?;
+                if let Err(error) =
+                    supervisor.start(connector.connector_id.clone(), "email", config_json)
+                {
+                    tracing::error!(connector = %connector.connector_id, "failed to start email bot: {error}");
+                } else {
+                    started += 1;
+                }
+            }
+        }
+    }
+
     if let Some(whatsapp) = &config.main.channels.whatsapp {
         if whatsapp.enabled {
             for connector in &whatsapp.connectors {
@@ -576,8 +686,17 @@ async fn start_bot(
     let (bus, memory, gateway, config, schedule_manager, wait_task_manager, approval_registry) =
         bootstrap(root, security_override).await?;
 
-    let router_for_consolidation = Arc::new(build_router_from_config(&config).await);
-    let consolidation_embedding_provider = build_embedding_provider(&config).await;
+    let router_for_consolidation = Arc::new(
+        build_router_from_config(&config)
+            .await
+            .with_usage_ledger(clawhive_memory::usage::UsageLedger::new(memory.db()))
+            .with_budgets(clawhive_core::runtime_config::build_budget_guard(&config)),
+    );
+    let consolidation_embedding_provider = clawhive_core::runtime_config::with_embedding_cache(
+        build_embedding_provider(&config).await?,
+        &memory,
+        &config,
+    );
     let consolidation_schedule = config.main.consolidation_schedule.clone();
     let archive_retention_days = config.main.archive_retention_days;
     let embedding_cache_ttl_days = config.main.memory_search.embedding_cache_ttl_days;
@@ -589,6 +708,20 @@ async fn start_bot(
         tracing::warn!("Startup embedding cache cleanup failed: {e}");
     }
 
+    // A new embedding model or width invalidates every stored vector; the
+    // per-agent startup indexing below then embeds everything again.
+    match clawhive_memory::search_index::SearchIndex::new(memory.db(), "")
+        .reset_if_model_changed(consolidation_embedding_provider.as_ref())
+    {
+        Ok(true) => tracing::info!(
+            model = %consolidation_embedding_provider.model_id(),
+            dimensions = consolidation_embedding_provider.dimensions(),
+            "Embedding model changed, reindexing all memory"
+        ),
+        Ok(false) => {}
+        Err(e) => tracing::warn!("Embedding model change check failed: {e}"),
+    }
+
     let mut consolidators: Vec<Arc<HippocampusConsolidator>> = Vec::new();
     for agent_config in config.agents.iter().filter(|a| a.enabled) {
         let workspace = Workspace::resolve(
@@ -708,11 +841,22 @@ async fn start_bot(
     tracing::info!("Cancel task gateway listener started");
 
     let _wait_task_listener_handle = spawn_wait_task_listener(gateway.clone(), Arc::clone(&bus));
+    let _delegate_task_listener_handle = spawn_delegate_task_listener(Arc::clone(&bus));
     tracing::info!("Wait task gateway listener started");
 
     let _approval_listener_handle = spawn_approval_delivery_listener(Arc::clone(&bus));
     tracing::info!("Approval delivery listener started");
 
+    let outbound_webhooks = Arc::new(
+        OutboundWebhooks::new(OutboundWebhookStore::open(
+            &root.join("data/outbound_webhooks.db"),
+        )?)
+        .await?,
+    );
+    let _outbound_webhook_handle =
+        spawn_outbound_webhook_dispatcher(Arc::clone(&outbound_webhooks), Arc::clone(&bus));
+    tracing::info!("Outbound webhook dispatcher started");
+
     // Spawn heartbeat tasks for agents with heartbeat enabled
     for agent_config in &config.agents {
         if !agent_config.enabled {
@@ -869,6 +1013,11 @@ async fn start_bot(
         gateway: Some(gateway.clone()),
         web_password_hash: Arc::new(RwLock::new(web_password_hash)),
         session_store: Arc::new(RwLock::new(HashMap::<String, Instant>::new())),
+        webhook_deliveries: Arc::new(
+            clawhive_server::webhook_deliveries::WebhookDeliveries::open(
+                &root.join("data/webhook_deliveries.db"),
+            )?,
+        ),
         whatsapp_pairing: Arc::new(RwLock::new(HashMap::new())),
         pending_openai_oauth: Arc::new(RwLock::new(HashMap::new())),
         openai_oauth_config: clawhive_server::state::default_openai_oauth_config(),
@@ -877,6 +1026,16 @@ async fn start_bot(
         port,
         schedule_manager: Some(Arc::clone(&schedule_manager)),
         reload_coordinator: Some(Arc::clone(&reload_coordinator)),
+        outbound_webhooks: Some(outbound_webhooks),
+        memory: Some(Arc::clone(&memory)),
+        metrics_bearer_token: config
+            .main
+            .metrics
+            .bearer_token
+            .as_deref()
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(str::to_owned),
     };
     let http_addr = format!("0.0.0.0:{port}");
     tokio::spawn(async move {
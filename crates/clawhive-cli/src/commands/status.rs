@@ -205,7 +205,7 @@ fn print_status_ex(root: &Path, just_started: bool) {
                         }
                         _ => "off".to_string(),
                     };
-                    format!("{} ({})", &a.agent_id, status)
+                    format!("{} ({})", a.agent_id, status)
                 })
                 .collect();
             if !heartbeat_parts.is_empty() {
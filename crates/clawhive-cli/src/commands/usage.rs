@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use clawhive_memory::usage::{UsageFilter, UsageGroupBy, UsageLedger, UsageSummary};
+use clawhive_memory::MemoryStore;
+
+const DEFAULT_WINDOW_DAYS: i64 = 30;
+
+pub(crate) async fn run(
+    root: &Path,
+    since: Option<String>,
+    until: Option<String>,
+    agent: Option<String>,
+    by: &str,
+    recent: usize,
+    json: bool,
+) -> Result<()> {
+    let group_by: UsageGroupBy = by.parse()?;
+    let db_path = root.join("data/clawhive.db");
+    if !db_path.exists() {
+        println!("No usage recorded yet ({} not found).", db_path.display());
+        return Ok(());
+    }
+    let memory = MemoryStore::open(db_path.to_str().unwrap_or("data/clawhive.db"))?;
+    let ledger = UsageLedger::new(memory.db());
+
+    let since = since.unwrap_or_else(|| {
+        (Utc::now() - Duration::days(DEFAULT_WINDOW_DAYS))
+            .format("%Y-%m-%d")
+            .to_string()
+    });
+    let filter = UsageFilter {
+        since: Some(since),
+        until,
+        agent_id: agent,
+        session_key: None,
+        user_scope: None,
+    };
+    let rows = ledger.summarize(&filter, group_by).await?;
+    let total = ledger.total(&filter).await?;
+    let recent_calls = if recent > 0 {
+        ledger.list_recent(&filter, recent).await?
+    } else {
+        Vec::new()
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "filter": filter,
+                "group_by": group_by,
+                "rows": rows,
+                "total": total,
+                "recent": recent_calls,
+            }))?
+        );
+        return Ok(());
+    }
+
+    let window = match &filter.until {
+        Some(until) => format!("{} .. {until}", filter.since.as_deref().unwrap_or("")),
+        None => format!("since {}", filter.since.as_deref().unwrap_or("")),
+    };
+    println!("LLM usage by {} ({window})", group_by.as_str());
+    print_header(group_by.as_str());
+    for row in &rows {
+        print_row(row);
+    }
+    println!("{}", "-".repeat(112));
+    print_row(&total);
+    if total.unpriced_calls > 0 {
+        println!(
+            "\n{} call(s) used models without known pricing and are not included in COST.",
+            total.unpriced_calls
+        );
+    }
+
+    if !recent_calls.is_empty() {
+        println!("\nRecent calls:");
+        println!(
+            "{:<26} {:<16} {:<14} {:<36} {:>8} {:>8} {:>8} {:>10}",
+            "TIME", "AGENT", "PURPOSE", "MODEL", "IN", "OUT", "MS", "COST"
+        );
+        for call in &recent_calls {
+            println!(
+                "{:<26} {:<16} {:<14} {:<36} {:>8} {:>8} {:>8} {:>10}",
+                truncate(&call.recorded_at, 26),
+                truncate(or_dash(&call.agent_id), 16),
+                call.purpose,
+                truncate(&format!("{}/{}", call.provider, call.model), 36),
+                call.input_tokens,
+                call.output_tokens,
+                call.latency_ms,
+                call.cost_usd
+                    .map(format_cost)
+                    .unwrap_or_else(|| "-".to_string()),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn print_header(key: &str) {
+    println!(
+        "{:<40} {:>7} {:>12} {:>10} {:>12} {:>12} {:>12}",
+        key.to_uppercase(),
+        "CALLS",
+        "INPUT",
+        "OUTPUT",
+        "CACHE READ",
+        "CACHE WRITE",
+        "COST"
+    );
+    println!("{}", "-".repeat(112));
+}
+
+fn print_row(row: &UsageSummary) {
+    println!(
+        "{:<40} {:>7} {:>12} {:>10} {:>12} {:>12} {:>12}",
+        truncate(or_dash(&row.key), 40),
+        row.calls,
+        row.input_tokens,
+        row.output_tokens,
+        row.cache_read_tokens,
+        row.cache_write_tokens,
+        format_cost(row.cost_usd),
+    );
+}
+
+fn format_cost(cost: f64) -> String {
+    format!("${cost:.4}")
+}
+
+fn or_dash(value: &str) -> &str {
+    if value.is_empty() {
+        "-"
+    } else {
+        value
+    }
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    if value.chars().count() <= max_chars {
+        value.to_string()
+    } else {
+        let head: String = value.chars().take(max_chars.saturating_sub(1)).collect();
+        format!("{head}…")
+    }
+}
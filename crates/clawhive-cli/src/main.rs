@@ -111,10 +111,34 @@ enum Commands {
         /// Agent ID to consolidate (defaults to routing default_agent_id)
         agent_id: Option<String>,
     },
+    #[command(about = "Report LLM token usage and cost")]
+    Usage {
+        #[arg(
+            long,
+            help = "Only include calls on/after YYYY-MM-DD (default: last 30 days)"
+        )]
+        since: Option<String>,
+        #[arg(long, help = "Only include calls before YYYY-MM-DD")]
+        until: Option<String>,
+        #[arg(long, help = "Only include calls made by this agent")]
+        agent: Option<String>,
+        #[arg(
+            long,
+            default_value = "agent",
+            help = "Group by agent, session, user, channel, provider, model, purpose or day"
+        )]
+        by: String,
+        #[arg(long, default_value = "0", help = "Also list the N most recent calls")]
+        recent: usize,
+        #[arg(long, help = "Print the report as JSON")]
+        json: bool,
+    },
     #[command(subcommand, about = "Memory management")]
     Memory(commands::memory::MemoryCommands),
     #[command(subcommand, about = "Agent management")]
     Agent(commands::agent::AgentCommands),
+    #[command(subcommand, about = "Model Context Protocol server")]
+    Mcp(commands::mcp::McpCommands),
     #[command(subcommand, about = "Skill management")]
     Skill(commands::skill::SkillCommands),
     #[command(subcommand, about = "Session management")]
@@ -293,12 +317,25 @@ async fn main() -> Result<()> {
         Commands::Consolidate { agent_id } => {
             commands::consolidate::run(&cli.config_root, agent_id.as_deref()).await?;
         }
+        Commands::Usage {
+            since,
+            until,
+            agent,
+            by,
+            recent,
+            json,
+        } => {
+            commands::usage::run(&cli.config_root, since, until, agent, &by, recent, json).await?;
+        }
         Commands::Memory(cmd) => {
             commands::memory::run(cmd, &cli.config_root).await?;
         }
         Commands::Agent(cmd) => {
             commands::agent::run(cmd, &cli.config_root)?;
         }
+        Commands::Mcp(cmd) => {
+            commands::mcp::run(cmd, &cli.config_root).await?;
+        }
         Commands::Skill(cmd) => {
             commands::skill::run(cmd, &cli.config_root).await?;
         }
@@ -350,6 +387,24 @@ mod tests {
         assert!(matches!(cli.command.unwrap(), Commands::Consolidate { .. }));
     }
 
+    #[test]
+    fn parses_usage_subcommand() {
+        let cli = Cli::try_parse_from([
+            "clawhive",
+            "usage",
+            "--by",
+            "model",
+            "--since",
+            "2026-03-01",
+            "--json",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command.unwrap(),
+            Commands::Usage { by, since: Some(_), json: true, .. } if by == "model"
+        ));
+    }
+
     #[test]
     fn parses_start_tui_flag() {
         let cli = Cli::try_parse_from(["clawhive", "start", "--tui"]).unwrap();
@@ -393,6 +448,18 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn parses_mcp_serve_subcommand() {
+        let cli = Cli::try_parse_from(["clawhive", "mcp", "serve", "--no-security"]).unwrap();
+        assert!(matches!(
+            cli.command.unwrap(),
+            Commands::Mcp(commands::mcp::McpCommands::Serve {
+                no_security: true,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn parses_agent_list_subcommand() {
         let cli = Cli::try_parse_from(["clawhive", "agent", "list"]).unwrap();
@@ -4,7 +4,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context as _, Result};
 use async_trait::async_trait;
 
 use clawhive_auth::{
@@ -12,7 +12,7 @@ use clawhive_auth::{
     oauth::{OpenAiOAuthConfig, OPENAI_OAUTH_CLIENT_ID},
     AuthProfile, TokenManager,
 };
-use clawhive_bus::EventBus;
+use clawhive_bus::{BusJournal, EventBus};
 use clawhive_core::*;
 use clawhive_gateway::{Gateway, RateLimitConfig, RateLimiter};
 use clawhive_memory::embedding::{
@@ -21,6 +21,7 @@ use clawhive_memory::embedding::{
 };
 use clawhive_memory::file_store::MemoryFileStore;
 use clawhive_memory::search_index::{SearchConfig, SearchIndex};
+use clawhive_memory::usage::UsageLedger;
 use clawhive_memory::MemoryStore;
 use clawhive_provider::{
     custom, minimax, moonshot, qianfan, qwen, register_builtin_providers, volcengine, zhipu,
@@ -381,7 +382,9 @@ pub(crate) async fn bootstrap(
         db_path.to_str().unwrap_or("data/clawhive.db"),
     )?);
 
-    let router = build_router_from_config(&config).await;
+    let router = build_router_from_config(&config)
+        .await
+        .with_usage_ledger(UsageLedger::new(memory.db()));
 
     // Load personas from workspace directories (OpenClaw-style)
     let mut personas = HashMap::new();
@@ -415,7 +418,12 @@ pub(crate) async fn bootstrap(
         }
     }
 
-    let bus = Arc::new(EventBus::new(256));
+    let mut bus = EventBus::new(256);
+    if config.main.bus.durable {
+        let journal = BusJournal::open(&root.join("data/bus.db"), config.main.bus.retain_events)?;
+        bus = bus.with_journal(journal);
+    }
+    let bus = Arc::new(bus);
     let publisher = bus.publisher();
     let new_path = root.join("data/runtime_allowlist.json");
     let old_path = root.join("data/exec_allowlist.json");
@@ -427,6 +435,10 @@ pub(crate) async fn bootstrap(
         }
     }
     let approval_registry = Arc::new(ApprovalRegistry::with_persistence(new_path));
+    let router = router.with_budgets(
+        clawhive_core::runtime_config::build_budget_guard(&config)
+            .with_approvals(approval_registry.clone(), publisher.clone()),
+    );
     let scheduler_db_path = root.join("data/scheduler.db");
     let sqlite_store = Arc::new(SqliteStore::open(&scheduler_db_path)?);
     let yaml_schedules_dir = root.join("config/schedules.d");
@@ -453,18 +465,19 @@ pub(crate) async fn bootstrap(
         SkillRegistry::new()
     });
     let workspace_dir = root.to_path_buf();
-    let embedding_provider = build_embedding_provider(&config).await;
-    let file_store = MemoryFileStore::new(&workspace_dir);
-    let search_index = SearchIndex::new_with_config(
-        memory.db(),
-        "",
-        to_search_config(&config.main.memory_search),
+    let embedding_provider = clawhive_core::runtime_config::with_embedding_cache(
+        build_embedding_provider(&config).await?,
+        &memory,
+        &config,
     );
+    let file_store = MemoryFileStore::new(&workspace_dir);
+    let search_config = to_search_config(&config.main.memory_search);
+    let search_index = SearchIndex::new_with_config(memory.db(), "", search_config.clone());
 
     let search_providers =
         clawhive_core::runtime_config::build_search_providers(&config.main.tools);
 
-    let tool_registry = build_tool_registry(
+    let mut tool_registry = build_tool_registry(
         &file_store,
         &search_index,
         &memory,
@@ -479,6 +492,7 @@ pub(crate) async fn bootstrap(
         &config.agents,
         &personas,
     );
+    clawhive_core::mcp::mount_mcp_servers(&mut tool_registry, &config.agents, &workspace_dir).await;
     let config_view = ConfigView::new(
         0,
         config.agents.clone(),
@@ -487,22 +501,47 @@ pub(crate) async fn bootstrap(
         router,
         tool_registry,
         embedding_provider,
-    );
-
-    let orchestrator = Arc::new(
-        OrchestratorBuilder::new(
-            config_view,
-            publisher.clone(),
-            memory.clone(),
-            Arc::new(NativeExecutor),
-            workspace_dir.clone(),
-            Arc::clone(&schedule_manager),
-        )
-        .skill_registry(skill_registry)
-        .approval_registry(approval_registry.clone())
-        .project_root(root.to_path_buf())
-        .build(),
-    );
+    )
+    .with_search_config(search_config);
+
+    let mut orchestrator_builder = OrchestratorBuilder::new(
+        config_view,
+        publisher.clone(),
+        memory.clone(),
+        Arc::new(NativeExecutor),
+        workspace_dir.clone(),
+        Arc::clone(&schedule_manager),
+    )
+    .skill_registry(skill_registry)
+    .approval_registry(approval_registry.clone())
+    .project_root(root.to_path_buf());
+    if let Some(transcription_config) = &config.main.transcription {
+        match AudioTranscription::from_config(transcription_config) {
+            Ok(Some(transcription)) => {
+                tracing::info!(
+                    "Audio transcription enabled (provider: {:?})",
+                    transcription_config.provider
+                );
+                orchestrator_builder = orchestrator_builder.transcription(Arc::new(transcription));
+            }
+            Ok(None) => tracing::info!("Audio transcription disabled"),
+            Err(e) => tracing::warn!("Audio transcription not configured: {e}"),
+        }
+    }
+    if let Some(speech_config) = &config.main.speech {
+        match VoiceReplies::from_config(speech_config) {
+            Some(voice_replies) => {
+                tracing::info!(
+                    "Voice replies enabled (model: {}, voice: {})",
+                    speech_config.model,
+                    speech_config.voice
+                );
+                orchestrator_builder = orchestrator_builder.voice_replies(Arc::new(voice_replies));
+            }
+            None => tracing::info!("Voice replies disabled"),
+        }
+    }
+    let orchestrator = Arc::new(orchestrator_builder.build());
 
     let rate_limiter = RateLimiter::new(RateLimitConfig::default());
     let gateway = Arc::new(Gateway::new(
@@ -957,15 +996,17 @@ pub(crate) async fn build_router_from_config(config: &ClawhiveConfig) -> LlmRout
     LlmRouter::new(registry, aliases, vec![])
 }
 
+/// Fails only for `provider: local`: air-gapped setups must neither fall
+/// through to a remote service nor silently degrade to keyword search.
 pub(crate) async fn build_embedding_provider(
     config: &ClawhiveConfig,
-) -> Arc<dyn EmbeddingProvider> {
+) -> Result<Arc<dyn EmbeddingProvider>> {
     let embedding_config = &config.main.embedding;
 
     // If explicitly disabled, use stub
     if !embedding_config.enabled {
         tracing::info!("Embedding disabled, using stub provider");
-        return Arc::new(StubEmbeddingProvider::new(8));
+        return Ok(Arc::new(StubEmbeddingProvider::new(8)));
     }
 
     // Priority: ollama > openai (explicit key) > openai (reuse provider key) > stub
@@ -983,7 +1024,7 @@ pub(crate) async fn build_embedding_provider(
                     embedding_config.model,
                     embedding_config.dimensions
                 );
-                return Arc::new(provider) as Arc<dyn EmbeddingProvider>;
+                return Ok(Arc::new(provider) as Arc<dyn EmbeddingProvider>);
             }
             tracing::warn!("Ollama not available, falling back");
         }
@@ -995,10 +1036,22 @@ pub(crate) async fn build_embedding_provider(
                     "Auto-detected Ollama, using embedding model: {}",
                     ollama.model_id()
                 );
-                return Arc::new(ollama) as Arc<dyn EmbeddingProvider>;
+                return Ok(Arc::new(ollama) as Arc<dyn EmbeddingProvider>);
             }
             tracing::debug!("Ollama not available for auto-detection");
         }
+        "local" => {
+            let provider = clawhive_memory::local_embedding::load_local_embedding_provider(
+                std::path::Path::new(&embedding_config.model_path),
+            )
+            .context("embedding.provider is `local` but the model could not be loaded")?;
+            tracing::info!(
+                "Local embedding provider initialized (model: {}, dimensions: {})",
+                provider.model_id(),
+                provider.dimensions()
+            );
+            return Ok(provider);
+        }
         "openai" => {} // Fall through to OpenAI logic below
         "gemini" | "google" => {
             let api_key = embedding_config.api_key.clone();
@@ -1015,7 +1068,7 @@ pub(crate) async fn build_embedding_provider(
                     embedding_config.model,
                     embedding_config.dimensions
                 );
-                return Arc::new(provider) as Arc<dyn EmbeddingProvider>;
+                return Ok(Arc::new(provider) as Arc<dyn EmbeddingProvider>);
             }
             tracing::warn!("Gemini embedding API key not set, falling back");
         }
@@ -1039,7 +1092,7 @@ pub(crate) async fn build_embedding_provider(
             embedding_config.model,
             embedding_config.dimensions
         );
-        return Arc::new(provider) as Arc<dyn EmbeddingProvider>;
+        return Ok(Arc::new(provider) as Arc<dyn EmbeddingProvider>);
     }
 
     // Try to reuse API key from configured LLM providers
@@ -1065,7 +1118,7 @@ pub(crate) async fn build_embedding_provider(
                 .with_base_url(p.api_base.clone());
 
                 tracing::info!("Reusing OpenAI API key for embeddings (text-embedding-3-small)");
-                return Arc::new(provider) as Arc<dyn EmbeddingProvider>;
+                return Ok(Arc::new(provider) as Arc<dyn EmbeddingProvider>);
             }
 
             // Gemini / Google
@@ -1090,13 +1143,13 @@ pub(crate) async fn build_embedding_provider(
 
     if let Some(key) = gemini_key {
         tracing::info!("Using Gemini API key for embeddings (gemini-embedding-001)");
-        return Arc::new(GeminiEmbeddingProvider::new(key)) as Arc<dyn EmbeddingProvider>;
+        return Ok(Arc::new(GeminiEmbeddingProvider::new(key)) as Arc<dyn EmbeddingProvider>);
     }
 
     // No embedding provider available — stub will be used
     // BM25 keyword search will handle memory_search as fallback
     tracing::warn!("No embedding provider available, memory_search will use keyword matching only");
-    Arc::new(StubEmbeddingProvider::new(8))
+    Ok(Arc::new(StubEmbeddingProvider::new(8)))
 }
 
 #[cfg(test)]
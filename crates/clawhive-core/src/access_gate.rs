@@ -18,7 +18,7 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
 use super::policy::HardBaseline;
-use super::tool::{ToolContext, ToolExecutor, ToolOutput};
+use super::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 // ───────────────────────────── Types ─────────────────────────────
 
@@ -380,6 +380,14 @@ impl ToolExecutor for GrantAccessTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["access".into()]
+    }
+
     async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
         let path_str = input["path"]
             .as_str()
@@ -447,6 +455,10 @@ impl ToolExecutor for ListAccessTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::read_only()
+    }
+
     async fn execute(&self, _input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
         let entries = self.gate.list().await;
         if entries.is_empty() {
@@ -496,6 +508,14 @@ impl ToolExecutor for RevokeAccessTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["access".into()]
+    }
+
     async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
         let path_str = input["path"]
             .as_str()
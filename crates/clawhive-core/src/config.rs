@@ -47,6 +47,10 @@ pub struct EmbeddingConfig {
     pub dimensions: usize,
     #[serde(default = "default_embedding_base_url")]
     pub base_url: String,
+    /// Directory with `config.json`, `tokenizer.json` and `model.safetensors`
+    /// for `provider: local`, which embeds on the CPU in process.
+    #[serde(default)]
+    pub model_path: String,
 }
 
 impl Default for EmbeddingConfig {
@@ -58,10 +62,163 @@ impl Default for EmbeddingConfig {
             model: default_embedding_model(),
             dimensions: default_embedding_dimensions(),
             base_url: default_embedding_base_url(),
+            model_path: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionBackend {
+    /// OpenAI-compatible `POST {base_url}/audio/transcriptions`.
+    #[default]
+    Openai,
+    /// whisper.cpp `server`: `POST {base_url}/inference`.
+    WhisperServer,
+}
+
+fn default_transcription_model() -> String {
+    "whisper-1".to_string()
+}
+
+fn default_transcription_max_bytes() -> usize {
+    25 * 1024 * 1024
+}
+
+/// Speech-to-text for inbound audio attachments (voice notes etc.).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub provider: TranscriptionBackend,
+    /// Defaults to the OpenAI API; required for `whisper_server`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default = "default_transcription_model")]
+    pub model: String,
+    /// ISO-639-1 hint; detected per clip when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Larger clips are left as a binary attachment.
+    #[serde(default = "default_transcription_max_bytes")]
+    pub max_bytes: usize,
+}
+
+impl Default for TranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            provider: TranscriptionBackend::default(),
+            base_url: None,
+            api_key: String::new(),
+            model: default_transcription_model(),
+            language: None,
+            max_bytes: default_transcription_max_bytes(),
+        }
+    }
+}
+
+fn default_speech_model() -> String {
+    "tts-1".to_string()
+}
+
+fn default_speech_voice() -> String {
+    "alloy".to_string()
+}
+
+fn default_speech_format() -> String {
+    "opus".to_string()
+}
+
+fn default_speech_max_chars() -> usize {
+    1500
+}
+
+/// Text-to-speech for voice replies, via an OpenAI-compatible
+/// `/audio/speech` endpoint (hosted or local).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeechConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Defaults to the OpenAI API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default = "default_speech_model")]
+    pub model: String,
+    #[serde(default = "default_speech_voice")]
+    pub voice: String,
+    /// `response_format`; `opus` is sent as a voice note.
+    #[serde(default = "default_speech_format")]
+    pub format: String,
+    /// Longer replies go out as text only.
+    #[serde(default = "default_speech_max_chars")]
+    pub max_chars: usize,
+}
+
+impl Default for SpeechConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            base_url: None,
+            api_key: String::new(),
+            model: default_speech_model(),
+            voice: default_speech_voice(),
+            format: default_speech_format(),
+            max_chars: default_speech_max_chars(),
+        }
+    }
+}
+
+/// Event bus persistence. When `durable` is set, messages are journaled to
+/// `data/bus.db` so critical listeners resume after a restart and the SSE
+/// stream can replay from `Last-Event-ID`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusConfig {
+    #[serde(default)]
+    pub durable: bool,
+    /// Journal entries kept for replay; older ones are pruned.
+    #[serde(default = "default_bus_retain_events")]
+    pub retain_events: usize,
+}
+
+impl Default for BusConfig {
+    fn default() -> Self {
+        Self {
+            durable: false,
+            retain_events: default_bus_retain_events(),
         }
     }
 }
 
+fn default_bus_retain_events() -> usize {
+    10_000
+}
+
+/// Prometheus `/metrics` endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Required as `Authorization: Bearer` when set; otherwise the endpoint
+    /// is open like `/hook`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_token: Option<String>,
+}
+
+/// When to attach a spoken copy of the reply.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyVoice {
+    #[default]
+    Never,
+    /// Only when the user's message carried audio.
+    Mirror,
+    Always,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelegramConnectorConfig {
     pub connector_id: String,
@@ -168,10 +325,26 @@ pub struct WeixinChannelConfig {
     pub connectors: Vec<WeixinConnectorConfig>,
 }
 
+/// A Slack workspace connection. Incoming messages need `app_token` (Socket
+/// Mode) or `events_listen` plus `signing_secret` (Events API); with only
+/// `bot_token` the connector is send-only and just delivers announcements,
+/// approval prompts and actions.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlackConnectorConfig {
     pub connector_id: String,
+    /// Bot token (xoxb-...) used for Web API calls
     pub bot_token: String,
+    /// App-level token (xapp-...) enabling Socket Mode
+    #[serde(default)]
+    pub app_token: Option<String>,
+    /// Signing secret used to verify Events API requests
+    #[serde(default)]
+    pub signing_secret: Option<String>,
+    /// Address the Events API receiver listens on, e.g. `0.0.0.0:3100`
+    #[serde(default)]
+    pub events_listen: Option<String>,
+    #[serde(default = "default_true")]
+    pub require_mention: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -182,6 +355,97 @@ pub struct SlackChannelConfig {
     pub connectors: Vec<SlackConnectorConfig>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixConnectorConfig {
+    pub connector_id: String,
+    /// Homeserver base URL, e.g. `https://matrix.example.org`
+    pub homeserver_url: String,
+    /// Access token of the bot account
+    pub access_token: String,
+    #[serde(default = "default_true")]
+    pub require_mention: bool,
+    /// Join rooms the bot is invited to (by users in `allow_from`, if set)
+    #[serde(default = "default_true")]
+    pub auto_join: bool,
+    /// Matrix user IDs allowed to talk to the bot; empty allows everyone
+    #[serde(default)]
+    pub allow_from: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixChannelConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub connectors: Vec<MatrixConnectorConfig>,
+}
+
+fn default_mailbox() -> String {
+    "INBOX".to_string()
+}
+
+fn default_imap_port() -> u16 {
+    993
+}
+
+fn default_smtp_port() -> u16 {
+    465
+}
+
+fn default_mail_security() -> String {
+    "tls".to_string()
+}
+
+fn default_email_poll_interval_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailConnectorConfig {
+    pub connector_id: String,
+    /// Address replies are sent from
+    pub address: String,
+    pub imap_host: String,
+    #[serde(default = "default_imap_port")]
+    pub imap_port: u16,
+    /// `tls` (implicit TLS), `starttls` or `none`
+    #[serde(default = "default_mail_security")]
+    pub imap_security: String,
+    pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    /// `tls` (implicit TLS), `starttls` or `none`
+    #[serde(default = "default_mail_security")]
+    pub smtp_security: String,
+    pub username: String,
+    pub password: String,
+    #[serde(default = "default_mailbox")]
+    pub mailbox: String,
+    /// Use IMAP IDLE when the server supports it; otherwise poll
+    #[serde(default = "default_true")]
+    pub idle: bool,
+    #[serde(default = "default_email_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default = "default_dm_policy")]
+    pub dm_policy: String,
+    /// Sender addresses (or `@domain` suffixes) accepted under `allowlist`;
+    /// they must also pass DKIM, SPF or DMARC
+    #[serde(default)]
+    pub allow_from: Vec<String>,
+    /// `authserv-id` of the receiving mail server whose
+    /// `Authentication-Results` are trusted; required under `allowlist`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authserv_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailChannelConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub connectors: Vec<EmailConnectorConfig>,
+}
+
 fn default_whatsapp_db_path() -> String {
     "~/.clawhive/data/whatsapp.db".to_string()
 }
@@ -238,11 +502,37 @@ pub struct IMessageChannelConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebhookAuthConfig {
+    /// `api_key`, `github`, `hmac_sha256`, `timestamped_hmac` or `stripe`.
     pub method: String,
     #[serde(default)]
     pub key_hash: Option<String>,
     #[serde(default)]
     pub key: Option<String>,
+    /// Shared secret for the HMAC methods.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Header carrying the signature. Defaults to `X-Hub-Signature-256` for
+    /// `github`, `Stripe-Signature` for `stripe`, `X-Signature` otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_header: Option<String>,
+    /// Stripped from the header value before decoding, e.g. `sha256=` or
+    /// `v0=`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_prefix: Option<String>,
+    /// `hex` (default) or `base64`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_encoding: Option<String>,
+    /// `timestamped_hmac` only. Defaults to `X-Timestamp`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_header: Option<String>,
+    /// `timestamped_hmac` only: what gets signed, with `{timestamp}` and
+    /// `{body}` placeholders. Defaults to `{timestamp}.{body}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_payload: Option<String>,
+    /// `timestamped_hmac` and `stripe`: replay window in seconds. Defaults
+    /// to 300.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance_secs: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -256,10 +546,79 @@ pub struct WebhookSourceConfig {
     #[serde(default)]
     pub prompt: Option<String>,
     pub auth: WebhookAuthConfig,
+    /// Header with the sender's delivery id; repeats of a seen id are
+    /// acknowledged without another agent turn. `github` sources default to
+    /// `X-GitHub-Delivery`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery_id_header: Option<String>,
+    /// Extraction and rendering rules for the `template` format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<WebhookTemplateConfig>,
     #[serde(default)]
     pub created_at: Option<String>,
 }
 
+impl WebhookSourceConfig {
+    /// Reject combinations the normalizers can't serve, such as
+    /// `format: template` without a `template`.
+    pub fn validate(&self) -> Result<()> {
+        if self.format == "template" && self.template.is_none() {
+            return Err(anyhow!(
+                "webhook source {} uses format template but has no template",
+                self.source_id
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Declarative payload mapping for `format: template`.
+///
+/// Paths are JSONPath-style: `$.issue.fields.summary`, `commits[0].id`,
+/// `alerts[-1].labels.severity` (the leading `$.` is optional). Templates
+/// substitute `{{name}}` with a named field, or with a payload path when no
+/// field has that name; `{{name|fallback}}` renders `fallback` when the value
+/// is missing. `{{source_id}}` is always available.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebhookTemplateConfig {
+    /// Named values extracted from the payload; also reported as labels.
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub fields: std::collections::BTreeMap<String, String>,
+    /// Message text handed to the agent.
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// Every filter must match, otherwise the event is dropped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<WebhookFilterConfig>,
+    /// Conversation to route to. Events get a fresh scope when unset or when
+    /// it renders empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_scope: Option<String>,
+}
+
+/// A condition on one field or payload path. Values compare as text, so
+/// `equals: 500` matches both `500` and `"500"`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebhookFilterConfig {
+    /// Field name or payload path.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equals: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_equals: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub one_of: Vec<serde_json::Value>,
+    /// Substring match against the rendered value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+    /// `true` requires a non-null value, `false` requires it to be absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exists: Option<bool>,
+}
+
 fn default_raw_format() -> String {
     "raw".to_string()
 }
@@ -296,6 +655,10 @@ pub struct ChannelsConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub slack: Option<SlackChannelConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<MatrixChannelConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailChannelConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub whatsapp: Option<WhatsAppChannelConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub imessage: Option<IMessageChannelConfig>,
@@ -362,6 +725,95 @@ pub struct ToolsConfig {
     pub actionbook: Option<ActionbookConfig>,
 }
 
+/// Calls a budget counts against its limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    /// Every LLM call.
+    Global,
+    /// Calls made for one agent.
+    Agent,
+    /// Calls made for one channel user (`user_scope`).
+    User,
+}
+
+/// Window a budget resets on, in UTC.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    #[default]
+    Daily,
+    Monthly,
+}
+
+/// What the router does once a budget is used up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetExhaustAction {
+    /// Fail the call.
+    #[default]
+    Refuse,
+    /// Route the call to the agent's `model_policy.compaction_model` instead.
+    Downgrade,
+    /// Hold the call until a human approves spending past the limit.
+    Approve,
+}
+
+/// A spending cap checked by the LLM router before every call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetPolicy {
+    pub scope: BudgetScope,
+    /// Agent id or user scope this budget is for. When absent on an `agent`
+    /// or `user` budget, each agent (or user) gets a budget of this size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub period: BudgetPeriod,
+    /// Spend limit in USD. Calls to models without known pricing cost nothing here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_usd: Option<f64>,
+    /// Limit on input plus output tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    #[serde(default)]
+    pub on_exhaust: BudgetExhaustAction,
+}
+
+impl BudgetScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Agent => "agent",
+            Self::User => "user",
+        }
+    }
+}
+
+impl BudgetPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Monthly => "monthly",
+        }
+    }
+}
+
+impl BudgetPolicy {
+    /// Short name such as `global:monthly`, `agent:main:daily` or
+    /// `user:*:daily` (one budget per user).
+    pub fn label(&self) -> String {
+        match self.scope {
+            BudgetScope::Global => format!("global:{}", self.period.as_str()),
+            scope => format!(
+                "{}:{}:{}",
+                scope.as_str(),
+                self.id.as_deref().unwrap_or("*"),
+                self.period.as_str()
+            ),
+        }
+    }
+}
+
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -386,10 +838,18 @@ pub struct MainConfig {
     pub channels: ChannelsConfig,
     #[serde(default)]
     pub embedding: EmbeddingConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcription: Option<TranscriptionConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speech: Option<SpeechConfig>,
     #[serde(default)]
     pub tools: ToolsConfig,
     #[serde(default)]
     pub memory_search: MemorySearchConfig,
+    #[serde(default)]
+    pub bus: BusConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
     #[serde(default = "default_consolidation_interval_hours")]
     pub consolidation_interval_hours: u64,
     #[serde(default = "default_consolidation_schedule")]
@@ -400,6 +860,9 @@ pub struct MainConfig {
     pub log_level: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub web_password_hash: Option<String>,
+    /// Spending caps enforced on every LLM call.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<BudgetPolicy>,
 }
 
 impl Default for MainConfig {
@@ -422,19 +885,26 @@ impl Default for MainConfig {
                 dingtalk: None,
                 wecom: None,
                 slack: None,
+                matrix: None,
+                email: None,
                 whatsapp: None,
                 imessage: None,
                 webhook: None,
                 weixin: None,
             },
             embedding: EmbeddingConfig::default(),
+            transcription: None,
+            speech: None,
             tools: ToolsConfig::default(),
             memory_search: MemorySearchConfig::default(),
+            bus: BusConfig::default(),
+            metrics: MetricsConfig::default(),
             consolidation_interval_hours: default_consolidation_interval_hours(),
             consolidation_schedule: default_consolidation_schedule(),
             archive_retention_days: default_archive_retention_days(),
             log_level: default_log_level(),
             web_password_hash: None,
+            budgets: Vec::new(),
         }
     }
 }
@@ -454,6 +924,29 @@ pub struct RoutingBinding {
     pub agent_id: String,
     #[serde(default)]
     pub delivery: Option<DeliveryRoutingConfig>,
+    /// Overrides the agent's `reply_voice` for this binding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_voice: Option<ReplyVoice>,
+}
+
+impl RoutingBinding {
+    pub fn matches(&self, inbound: &clawhive_schema::InboundMessage) -> bool {
+        if self.channel_type != inbound.channel_type || self.connector_id != inbound.connector_id {
+            return false;
+        }
+        let is_group = inbound.conversation_scope.contains("group");
+        match self.match_rule.kind.as_str() {
+            "dm" => !is_group,
+            "mention" => {
+                inbound.is_mention
+                    && self.match_rule.pattern.is_some()
+                    && inbound.mention_target == self.match_rule.pattern
+            }
+            "group" => is_group,
+            "all" => true,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -463,6 +956,18 @@ pub struct RoutingConfig {
     pub bindings: Vec<RoutingBinding>,
 }
 
+impl RoutingConfig {
+    /// First binding matching the inbound message, in config order.
+    pub fn binding_for(
+        &self,
+        inbound: &clawhive_schema::InboundMessage,
+    ) -> Option<&RoutingBinding> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.matches(inbound))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderConfig {
     pub provider_id: String,
@@ -502,6 +1007,51 @@ pub struct ToolPolicyConfig {
     pub allow: Vec<String>,
 }
 
+fn default_mcp_timeout_secs() -> u64 {
+    60
+}
+
+/// How clawhive reaches an MCP server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "transport", rename_all = "snake_case")]
+pub enum McpTransportConfig {
+    /// Spawn a local process and speak newline-delimited JSON-RPC over stdin/stdout.
+    Stdio {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        /// Extra environment variables passed to the server process.
+        #[serde(default)]
+        env: std::collections::BTreeMap<String, String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cwd: Option<String>,
+    },
+    /// Streamable HTTP transport (single MCP endpoint, JSON or SSE responses).
+    Http {
+        url: String,
+        #[serde(default)]
+        headers: std::collections::BTreeMap<String, String>,
+    },
+}
+
+/// An MCP server whose tools are mounted into the agent's tool registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpServerConfig {
+    /// Server name, used as the tool name prefix (`mcp__<name>__<tool>`).
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub transport: McpTransportConfig,
+    /// Per-request timeout for initialize / tools/list / tools/call.
+    #[serde(default = "default_mcp_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Declared permissions, checked like SKILL.md permissions.
+    /// Defaults to the command (stdio) or host:port (http) of the transport.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<crate::skill::SkillPermissions>,
+}
+
 /// Master security mode for an agent
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default, clap::ValueEnum)]
 #[serde(rename_all = "lowercase")]
@@ -728,6 +1278,35 @@ pub struct MemoryPolicyConfig {
     pub daily_summary_interval: u64,
 }
 
+/// Where an agent's memory writes land, parsed from `memory_policy.write_scope`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryWriteScope {
+    /// The agent-wide layer every caller reads (`all`, the default).
+    Agent,
+    /// A private partition per channel user (`user`).
+    User,
+    /// A partition per conversation, shared by its participants (`conversation`).
+    Conversation,
+    /// Memory tools and flushes write nothing (`none`).
+    Disabled,
+}
+
+impl MemoryPolicyConfig {
+    /// `None` for an unrecognized `write_scope`, which [`validate_config`]
+    /// rejects.
+    pub fn write_partition(&self) -> Option<MemoryWriteScope> {
+        match self.write_scope.trim().to_ascii_lowercase().as_str() {
+            "user" => Some(MemoryWriteScope::User),
+            "conversation" => Some(MemoryWriteScope::Conversation),
+            "none" => Some(MemoryWriteScope::Disabled),
+            // "all" plus the older "workspace" / "session" spellings all
+            // wrote to the single agent-wide store.
+            "all" | "workspace" | "session" => Some(MemoryWriteScope::Agent),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemorySearchConfig {
     #[serde(default = "default_vector_weight")]
@@ -899,6 +1478,9 @@ pub struct FullAgentConfig {
     pub identity: Option<IdentityConfig>,
     pub model_policy: ModelPolicy,
     pub tool_policy: Option<ToolPolicyConfig>,
+    /// MCP servers whose tools are exposed to this agent.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_servers: Vec<McpServerConfig>,
     pub memory_policy: Option<MemoryPolicyConfig>,
     pub sub_agent: Option<SubAgentPolicyConfig>,
     #[serde(default)]
@@ -922,6 +1504,10 @@ pub struct FullAgentConfig {
     /// Seconds of silence before sending a progress message. Default 60. Set to 0 to disable.
     #[serde(default)]
     pub progress_delay_secs: Option<u64>,
+    /// Spoken replies on channels that support them; needs `speech` in
+    /// main.yaml.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_voice: Option<ReplyVoice>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -942,6 +1528,17 @@ impl FullAgentConfig {
             progress_delay_secs: progress_delay,
         }
     }
+
+    /// An unrecognized `write_scope` writes nothing rather than risk putting
+    /// private memory in the shared layer.
+    pub fn memory_write_scope(&self) -> MemoryWriteScope {
+        match &self.memory_policy {
+            Some(policy) => policy
+                .write_partition()
+                .unwrap_or(MemoryWriteScope::Disabled),
+            None => MemoryWriteScope::Agent,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1005,6 +1602,34 @@ pub fn validate_config(config: &ClawhiveConfig) -> Result<()> {
         if !seen.insert(agent.agent_id.as_str()) {
             return Err(anyhow!("duplicate agent_id: {}", agent.agent_id));
         }
+
+        if let Some(policy) = &agent.memory_policy {
+            if policy.write_partition().is_none() {
+                return Err(anyhow!(
+                    "agent {} has unknown memory_policy.write_scope: {} \
+                     (expected all, user, conversation or none)",
+                    agent.agent_id,
+                    policy.write_scope
+                ));
+            }
+        }
+
+        let mut mcp_names = HashSet::new();
+        for server in &agent.mcp_servers {
+            if server.name.trim().is_empty() {
+                return Err(anyhow!(
+                    "agent {} has an MCP server with an empty name",
+                    agent.agent_id
+                ));
+            }
+            if !mcp_names.insert(server.name.as_str()) {
+                return Err(anyhow!(
+                    "duplicate MCP server name in agent {}: {}",
+                    agent.agent_id,
+                    server.name
+                ));
+            }
+        }
     }
 
     if !seen.contains(config.routing.default_agent_id.as_str()) {
@@ -1020,6 +1645,44 @@ pub fn validate_config(config: &ClawhiveConfig) -> Result<()> {
         }
     }
 
+    if let Some(webhook) = &config.main.channels.webhook {
+        for source in &webhook.sources {
+            source.validate()?;
+        }
+    }
+
+    if let Some(email) = config
+        .main
+        .channels
+        .email
+        .as_ref()
+        .filter(|email| email.enabled)
+    {
+        for connector in &email.connectors {
+            let authenticates = !matches!(connector.dm_policy.as_str(), "open" | "disabled");
+            let has_authserv_id = connector
+                .authserv_id
+                .as_deref()
+                .is_some_and(|id| !id.trim().is_empty());
+            if authenticates && !has_authserv_id {
+                return Err(anyhow!(
+                    "email connector {} uses dm_policy {} but has no authserv_id",
+                    connector.connector_id,
+                    connector.dm_policy
+                ));
+            }
+        }
+    }
+
+    for budget in &config.main.budgets {
+        if budget.max_usd.is_none() && budget.max_tokens.is_none() {
+            return Err(anyhow!(
+                "budget {} sets neither max_usd nor max_tokens",
+                budget.label()
+            ));
+        }
+    }
+
     Ok(())
 }
 
@@ -1106,6 +1769,37 @@ fn resolve_main_env(main: &mut MainConfig) {
         for connector in &mut slack.connectors {
             connector.connector_id = resolve_env_var(&connector.connector_id);
             connector.bot_token = resolve_env_var(&connector.bot_token);
+            if let Some(token) = &mut connector.app_token {
+                *token = resolve_env_var(token);
+            }
+            if let Some(secret) = &mut connector.signing_secret {
+                *secret = resolve_env_var(secret);
+            }
+        }
+    }
+
+    if let Some(matrix) = &mut main.channels.matrix {
+        for connector in &mut matrix.connectors {
+            connector.connector_id = resolve_env_var(&connector.connector_id);
+            connector.homeserver_url = resolve_env_var(&connector.homeserver_url);
+            connector.access_token = resolve_env_var(&connector.access_token);
+            for allow_from in &mut connector.allow_from {
+                *allow_from = resolve_env_var(allow_from);
+            }
+        }
+    }
+
+    if let Some(email) = &mut main.channels.email {
+        for connector in &mut email.connectors {
+            connector.connector_id = resolve_env_var(&connector.connector_id);
+            connector.address = resolve_env_var(&connector.address);
+            connector.imap_host = resolve_env_var(&connector.imap_host);
+            connector.smtp_host = resolve_env_var(&connector.smtp_host);
+            connector.username = resolve_env_var(&connector.username);
+            connector.password = resolve_env_var(&connector.password);
+            for allow_from in &mut connector.allow_from {
+                *allow_from = resolve_env_var(allow_from);
+            }
         }
     }
 
@@ -1139,6 +1833,9 @@ fn resolve_main_env(main: &mut MainConfig) {
             if let Some(key_hash) = &mut source.auth.key_hash {
                 *key_hash = resolve_env_var(key_hash);
             }
+            if let Some(secret) = &mut source.auth.secret {
+                *secret = resolve_env_var(secret);
+            }
         }
     }
 
@@ -1155,6 +1852,18 @@ fn resolve_main_env(main: &mut MainConfig) {
     main.embedding.base_url = resolve_env_var(&main.embedding.base_url);
     main.embedding.model = resolve_env_var(&main.embedding.model);
     main.embedding.provider = resolve_env_var(&main.embedding.provider);
+    if let Some(speech) = &mut main.speech {
+        speech.api_key = resolve_env_var(&speech.api_key);
+        if let Some(base_url) = &mut speech.base_url {
+            *base_url = resolve_env_var(base_url);
+        }
+    }
+    if let Some(transcription) = &mut main.transcription {
+        transcription.api_key = resolve_env_var(&transcription.api_key);
+        if let Some(base_url) = &mut transcription.base_url {
+            *base_url = resolve_env_var(base_url);
+        }
+    }
     main.log_level = resolve_env_var(&main.log_level);
 }
 
@@ -1226,6 +1935,34 @@ fn resolve_agents_env(agents: &mut [FullAgentConfig]) {
             }
         }
 
+        for server in &mut agent.mcp_servers {
+            match &mut server.transport {
+                McpTransportConfig::Stdio {
+                    command,
+                    args,
+                    env,
+                    cwd,
+                } => {
+                    *command = resolve_env_var(command);
+                    for arg in args {
+                        *arg = resolve_env_var(arg);
+                    }
+                    for value in env.values_mut() {
+                        *value = resolve_env_var(value);
+                    }
+                    if let Some(cwd) = cwd {
+                        *cwd = resolve_env_var(cwd);
+                    }
+                }
+                McpTransportConfig::Http { url, headers } => {
+                    *url = resolve_env_var(url);
+                    for value in headers.values_mut() {
+                        *value = resolve_env_var(value);
+                    }
+                }
+            }
+        }
+
         if let Some(memory_policy) = &mut agent.memory_policy {
             memory_policy.mode = resolve_env_var(&memory_policy.mode);
             memory_policy.write_scope = resolve_env_var(&memory_policy.write_scope);
@@ -1360,6 +2097,63 @@ write_scope: session
         assert_eq!(config.daily_at_hour, None);
         assert_eq!(config.max_injected_chars, 6000);
         assert_eq!(config.daily_summary_interval, 0);
+        assert_eq!(config.write_partition(), Some(MemoryWriteScope::Agent));
+    }
+
+    #[test]
+    fn memory_policy_write_scope_selects_partition() {
+        let parse = |scope: &str| {
+            let yaml = format!("mode: standard\nwrite_scope: {scope}\n");
+            serde_yaml::from_str::<MemoryPolicyConfig>(&yaml)
+                .unwrap()
+                .write_partition()
+        };
+
+        assert_eq!(parse("all"), Some(MemoryWriteScope::Agent));
+        assert_eq!(parse("workspace"), Some(MemoryWriteScope::Agent));
+        assert_eq!(parse("user"), Some(MemoryWriteScope::User));
+        assert_eq!(parse("Conversation"), Some(MemoryWriteScope::Conversation));
+        assert_eq!(parse("none"), Some(MemoryWriteScope::Disabled));
+        assert_eq!(parse("usr"), None);
+        assert_eq!(parse("conversaton"), None);
+    }
+
+    #[test]
+    fn validate_config_rejects_unknown_memory_write_scope() {
+        let (_tmp, root) = make_temp_config();
+        let mut config = load_config(&root).unwrap();
+        config.agents[0].memory_policy =
+            Some(serde_yaml::from_str("mode: standard\nwrite_scope: usr\n").unwrap());
+        assert_eq!(
+            config.agents[0].memory_write_scope(),
+            MemoryWriteScope::Disabled
+        );
+
+        let err = validate_config(&config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("agent main-agent has unknown memory_policy.write_scope: usr"));
+    }
+
+    #[test]
+    fn model_policy_reads_inline_sampling_defaults() {
+        let yaml = r#"
+primary: sonnet
+temperature: 0.3
+top_p: 0.9
+stop: ["</reply>"]
+seed: 11
+"#;
+        let policy: crate::ModelPolicy = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(policy.primary, "sonnet");
+        assert_eq!(policy.sampling.temperature, Some(0.3));
+        assert_eq!(policy.sampling.top_p, Some(0.9));
+        assert_eq!(policy.sampling.stop, vec!["</reply>".to_string()]);
+        assert_eq!(policy.sampling.seed, Some(11));
+
+        let bare: crate::ModelPolicy = serde_yaml::from_str("primary: sonnet").unwrap();
+        assert!(bare.sampling.is_empty());
     }
 
     #[test]
@@ -1383,6 +2177,74 @@ write_scope: session
         assert!(err.to_string().contains("duplicate agent_id"));
     }
 
+    #[test]
+    fn budgets_parse_with_defaults_and_require_a_limit() {
+        let budgets: Vec<BudgetPolicy> = serde_yaml::from_str(
+            r#"
+- scope: global
+  period: monthly
+  max_usd: 50
+- scope: user
+  max_tokens: 200000
+  on_exhaust: approve
+"#,
+        )
+        .unwrap();
+        assert_eq!(budgets[0].label(), "global:monthly");
+        assert_eq!(budgets[0].on_exhaust, BudgetExhaustAction::Refuse);
+        assert_eq!(budgets[1].period, BudgetPeriod::Daily);
+        assert_eq!(budgets[1].on_exhaust, BudgetExhaustAction::Approve);
+        assert_eq!(budgets[1].label(), "user:*:daily");
+
+        let (_tmp, root) = make_temp_config();
+        let mut config = load_config(&root).unwrap();
+        config.main.budgets = budgets.clone();
+        validate_config(&config).unwrap();
+        config.main.budgets.push(BudgetPolicy {
+            max_tokens: None,
+            ..budgets[1].clone()
+        });
+        let err = validate_config(&config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("budget user:*:daily sets neither max_usd nor max_tokens"));
+    }
+
+    #[test]
+    fn email_allowlist_requires_authserv_id() {
+        let email: EmailChannelConfig = serde_yaml::from_str(
+            r#"
+enabled: true
+connectors:
+  - connector_id: mail
+    address: bot@example.com
+    imap_host: imap.example.com
+    smtp_host: smtp.example.com
+    username: bot@example.com
+    password: secret
+    allow_from: ["@example.com"]
+"#,
+        )
+        .unwrap();
+
+        let (_tmp, root) = make_temp_config();
+        let mut config = load_config(&root).unwrap();
+        config.main.channels.email = Some(email);
+        let err = validate_config(&config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("email connector mail uses dm_policy allowlist but has no authserv_id"));
+
+        let email = config.main.channels.email.as_mut().unwrap();
+        email.connectors[0].authserv_id = Some("mx.example.com".into());
+        validate_config(&config).unwrap();
+
+        let email = config.main.channels.email.as_mut().unwrap();
+        email.connectors[0].authserv_id = None;
+        email.connectors[0].dm_policy = "open".into();
+        validate_config(&config).unwrap();
+    }
+
     #[test]
     fn resolve_env_var_replaces_env_placeholder() {
         let expected = std::env::var("PATH").unwrap();
@@ -1506,6 +2368,64 @@ auth:
         assert_eq!(config.format, "raw");
     }
 
+    #[test]
+    fn webhook_template_source_parses() {
+        let yaml = r#"
+source_id: sentry
+format: template
+auth:
+  method: api_key
+  key: "test-key"
+template:
+  fields:
+    title: $.data.issue.title
+    level: data.issue.level
+  text: "[{{level}}] {{title}}"
+  filters:
+    - path: level
+      one_of: [error, fatal]
+    - path: $.action
+      equals: created
+  conversation_scope: "sentry:{{data.issue.id}}"
+"#;
+        let config: WebhookSourceConfig = serde_yaml::from_str(yaml).unwrap();
+        let template = config.template.unwrap();
+        assert_eq!(template.fields["title"], "$.data.issue.title");
+        assert_eq!(template.filters.len(), 2);
+        assert_eq!(template.filters[0].one_of.len(), 2);
+        assert_eq!(
+            template.filters[1].equals,
+            Some(serde_json::Value::String("created".into()))
+        );
+        assert_eq!(
+            template.conversation_scope.as_deref(),
+            Some("sentry:{{data.issue.id}}")
+        );
+    }
+
+    #[test]
+    fn validate_config_rejects_template_source_without_template() {
+        let webhook: WebhookChannelConfig = serde_yaml::from_str(
+            r#"
+enabled: true
+sources:
+  - source_id: ci
+    format: template
+    auth:
+      method: api_key
+"#,
+        )
+        .unwrap();
+
+        let (_tmp, root) = make_temp_config();
+        let mut config = load_config(&root).unwrap();
+        config.main.channels.webhook = Some(webhook);
+        assert_eq!(
+            validate_config(&config).unwrap_err().to_string(),
+            "webhook source ci uses format template but has no template"
+        );
+    }
+
     #[test]
     fn validate_config_missing_default_agent() {
         let config = ClawhiveConfig {
@@ -1527,19 +2447,26 @@ auth:
                     dingtalk: None,
                     wecom: None,
                     slack: None,
+                    matrix: None,
+                    email: None,
                     whatsapp: None,
                     imessage: None,
                     webhook: None,
                     weixin: None,
                 },
                 embedding: EmbeddingConfig::default(),
+                transcription: None,
+                speech: None,
                 tools: ToolsConfig::default(),
                 memory_search: MemorySearchConfig::default(),
+                bus: BusConfig::default(),
+                metrics: MetricsConfig::default(),
                 consolidation_interval_hours: 24,
                 consolidation_schedule: default_consolidation_schedule(),
                 archive_retention_days: 30,
                 log_level: default_log_level(),
                 web_password_hash: None,
+                budgets: Vec::new(),
             },
             routing: RoutingConfig {
                 default_agent_id: "nonexistent".into(),
@@ -1557,8 +2484,10 @@ auth:
                     thinking_level: None,
                     context_window: None,
                     compaction_model: None,
+                    sampling: Default::default(),
                 },
                 tool_policy: None,
+                mcp_servers: vec![],
                 memory_policy: None,
                 sub_agent: None,
                 workspace: None,
@@ -1570,6 +2499,7 @@ auth:
                 turn_timeout_secs: None,
                 typing_ttl_secs: None,
                 progress_delay_secs: None,
+                reply_voice: None,
             }],
         };
         let err = validate_config(&config).unwrap_err();
@@ -70,6 +70,10 @@ impl ConfigDiff {
         if old.main.log_level != new.main.log_level {
             diff.requires_restart.push("log_level".to_string());
         }
+        if old.main.metrics.bearer_token != new.main.metrics.bearer_token {
+            diff.requires_restart
+                .push("metrics.bearer_token".to_string());
+        }
 
         diff
     }
@@ -135,8 +139,10 @@ mod tests {
                     thinking_level: None,
                     context_window: None,
                     compaction_model: None,
+                    sampling: Default::default(),
                 },
                 tool_policy: None,
+                mcp_servers: vec![],
                 memory_policy: None,
                 sub_agent: None,
                 heartbeat: None,
@@ -147,6 +153,7 @@ mod tests {
                 turn_timeout_secs: None,
                 typing_ttl_secs: None,
                 progress_delay_secs: None,
+                reply_voice: None,
             }],
         }
     }
@@ -205,10 +212,14 @@ mod tests {
         let old = base_config();
         let mut new = base_config();
         new.main.log_level = "debug".to_string();
+        new.main.metrics.bearer_token = Some("scrape-secret".to_string());
 
         let diff = ConfigDiff::between(&old, &new);
 
-        assert_eq!(diff.requires_restart, vec!["log_level".to_string()]);
+        assert_eq!(
+            diff.requires_restart,
+            vec!["log_level".to_string(), "metrics.bearer_token".to_string()]
+        );
         assert!(!diff.has_config_changes());
         assert!(!diff.is_empty());
     }
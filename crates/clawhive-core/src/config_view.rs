@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use clawhive_memory::embedding::EmbeddingProvider;
+use clawhive_memory::search_index::SearchConfig;
 
 use crate::config::{FullAgentConfig, RoutingConfig};
 use crate::persona::Persona;
@@ -17,6 +18,8 @@ pub struct ConfigView {
     pub router: LlmRouter,
     pub tool_registry: ToolRegistry,
     pub embedding_provider: Arc<dyn EmbeddingProvider>,
+    /// Ranking weights from `memory_search`.
+    pub search_config: SearchConfig,
 }
 
 impl ConfigView {
@@ -47,9 +50,15 @@ impl ConfigView {
             router,
             tool_registry,
             embedding_provider,
+            search_config: SearchConfig::default(),
         }
     }
 
+    pub fn with_search_config(mut self, search_config: SearchConfig) -> Self {
+        self.search_config = search_config;
+        self
+    }
+
     pub fn agent(&self, agent_id: &str) -> Option<&Arc<FullAgentConfig>> {
         self.agents.get(agent_id)
     }
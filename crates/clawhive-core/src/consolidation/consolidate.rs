@@ -1,12 +1,13 @@
 use anyhow::Result;
 
-use clawhive_provider::LlmMessage;
+use clawhive_provider::{LlmMessage, LlmRequest};
 
 use super::matching::dedup_memory_candidates;
 use super::patch::{apply_patch, parse_patch, strip_markdown_fence};
 use super::prompts::{
-    CONSOLIDATION_FULL_OVERWRITE_SYSTEM_PROMPT, CONSOLIDATION_INCREMENTAL_SYSTEM_PROMPT,
-    PROMOTION_CANDIDATE_SYSTEM_PROMPT, SECTION_MERGE_SYSTEM_PROMPT,
+    promotion_candidates_schema, CONSOLIDATION_FULL_OVERWRITE_SYSTEM_PROMPT,
+    CONSOLIDATION_INCREMENTAL_SYSTEM_PROMPT, PROMOTION_CANDIDATE_SYSTEM_PROMPT,
+    SECTION_MERGE_SYSTEM_PROMPT,
 };
 use super::text_utils::compute_line_diff;
 use super::{ConsolidationReport, HippocampusConsolidator, PromotionCandidate};
@@ -367,14 +368,15 @@ impl HippocampusConsolidator {
         daily_sections: &str,
     ) -> Result<Vec<PromotionCandidate>> {
         let response = self
-            .request_consolidation(
+            .request_structured(
                 PROMOTION_CANDIDATE_SYSTEM_PROMPT,
                 build_promotion_candidate_prompt(daily_sections),
+                4096,
+                "promotion_candidates",
+                promotion_candidates_schema(),
             )
             .await?;
-        let parsed = strip_markdown_fence(&response.text);
-        let candidates: Vec<PromotionCandidate> = serde_json::from_str(parsed.trim())?;
-        Ok(candidates)
+        parse_promotion_candidates(&response.text)
     }
 
     async fn merge_memory_section(
@@ -407,6 +409,45 @@ impl HippocampusConsolidator {
             )
             .await
     }
+
+    /// Deterministic, schema-constrained variant of [`Self::request_consolidation`].
+    pub(super) async fn request_structured(
+        &self,
+        system_prompt: &str,
+        user_prompt: String,
+        max_tokens: u32,
+        schema_name: &str,
+        schema: serde_json::Value,
+    ) -> Result<clawhive_provider::LlmResponse> {
+        let request = LlmRequest {
+            max_tokens,
+            ..LlmRequest::simple(
+                self.model_primary.clone(),
+                Some(system_prompt.to_string()),
+                user_prompt,
+            )
+        }
+        .with_json_schema(schema_name, schema);
+        self.router
+            .chat_structured(&self.model_primary, &self.model_fallbacks, request)
+            .await
+    }
+}
+
+/// Accept `{"candidates": [...]}` as well as a bare array, for providers
+/// that ignore the response format.
+fn parse_promotion_candidates(text: &str) -> Result<Vec<PromotionCandidate>> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Wrapped {
+        Object { candidates: Vec<PromotionCandidate> },
+        Array(Vec<PromotionCandidate>),
+    }
+
+    let parsed = strip_markdown_fence(text);
+    Ok(match serde_json::from_str(parsed.trim())? {
+        Wrapped::Object { candidates } | Wrapped::Array(candidates) => candidates,
+    })
 }
 
 // Re-export prompt builder functions used in this module
@@ -568,8 +609,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: old_created.clone(),
             updated_at: old_created,
         };
@@ -593,8 +637,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: recent_created.clone(),
             updated_at: recent_created,
         };
@@ -664,8 +711,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: old_created.clone(),
             updated_at: old_created,
         };
@@ -689,8 +739,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: recent_created.clone(),
             updated_at: recent_created,
         };
@@ -749,8 +802,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: old_created.clone(),
             updated_at: old_created,
         };
@@ -774,8 +830,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: recent_created.clone(),
             updated_at: recent_created,
         };
@@ -837,8 +896,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: old_created.clone(),
             updated_at: old_created,
         };
@@ -862,8 +924,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: recent_created.clone(),
             updated_at: recent_created,
         };
@@ -1335,8 +1400,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: now.clone(),
             updated_at: now,
         };
@@ -1504,4 +1572,18 @@ mod tests {
         assert!(updated.contains("Another durable fact"));
         Ok(())
     }
+
+    #[test]
+    fn parse_promotion_candidates_accepts_object_and_bare_array() -> Result<()> {
+        let item = r#"{"content":"Prefers Rust","target_kind":"fact","target_section":null,"source_date":null,"importance":0.8,"duplicate_key":null}"#;
+
+        let wrapped = super::parse_promotion_candidates(&format!(r#"{{"candidates":[{item}]}}"#))?;
+        assert_eq!(wrapped.len(), 1);
+        assert_eq!(wrapped[0].content, "Prefers Rust");
+
+        let bare = super::parse_promotion_candidates(&format!("```json\n[{item}]\n```"))?;
+        assert_eq!(bare.len(), 1);
+        assert_eq!(bare[0].target_kind, "fact");
+        Ok(())
+    }
 }
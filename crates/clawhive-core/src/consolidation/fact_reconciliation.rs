@@ -1,9 +1,11 @@
 use anyhow::{anyhow, Result};
 use chrono::{Duration, Utc};
 use clawhive_memory::fact_store::{Fact, FactStore};
-use clawhive_provider::LlmMessage;
+use clawhive_provider::LlmRequest;
 
 use super::matching::fact_conflict_step_b_passes;
+use super::patch::strip_markdown_fence;
+use super::prompts::fact_conflict_schema;
 use super::text_utils::cosine_similarity;
 use super::HippocampusConsolidator;
 
@@ -38,9 +40,11 @@ impl HippocampusConsolidator {
                 continue;
             }
 
+            // Facts only conflict within one partition: two users may
+            // legitimately hold opposite preferences.
             let others = active_facts
                 .iter()
-                .filter(|fact| fact.id != recent.id)
+                .filter(|fact| fact.id != recent.id && fact.scope() == recent.scope())
                 .cloned()
                 .collect::<Vec<_>>();
             if others.is_empty() {
@@ -139,8 +143,8 @@ impl HippocampusConsolidator {
             Fact B (newer): \"{}\"\nType: {}\n\n\
             Question: Is Fact B a direct update or correction of Fact A? \
             (e.g. preference change, updated decision, corrected information)\n\n\
-            Reply with exactly \"yes\" or \"no\". \
-            Answer \"yes\" only if they are clearly about the same specific subject \
+            Reply with a JSON object {{\"supersedes\": true|false}}. \
+            Answer true only if they are clearly about the same specific subject \
             and Fact B supersedes Fact A.",
             candidate.content, candidate.fact_type, recent.content, recent.fact_type
         );
@@ -149,19 +153,17 @@ impl HippocampusConsolidator {
             .model_compaction
             .as_deref()
             .unwrap_or(&self.model_primary);
+        let request = LlmRequest {
+            max_tokens: 64,
+            ..LlmRequest::simple(model.to_string(), None, prompt)
+        }
+        .with_json_schema("fact_conflict", fact_conflict_schema());
         let response = self
             .router
-            .chat(
-                model,
-                &self.model_fallbacks,
-                None,
-                vec![LlmMessage::user(prompt)],
-                16,
-            )
+            .chat_structured(model, &self.model_fallbacks, request)
             .await?;
 
-        let answer = response.text.trim().to_lowercase();
-        Ok(answer.starts_with("yes"))
+        Ok(parse_fact_conflict_answer(&response.text))
     }
 
     async fn supersede_with_existing_fact(
@@ -254,15 +256,42 @@ impl HippocampusConsolidator {
     }
 }
 
+/// Read `{"supersedes": bool}`, falling back to a leading yes/no for
+/// providers that ignore the response format.
+fn parse_fact_conflict_answer(text: &str) -> bool {
+    #[derive(serde::Deserialize)]
+    struct Answer {
+        supersedes: bool,
+    }
+
+    let stripped = strip_markdown_fence(text);
+    match serde_json::from_str::<Answer>(stripped.trim()) {
+        Ok(answer) => answer.supersedes,
+        Err(_) => stripped.trim().to_lowercase().starts_with("yes"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
     use chrono::Utc;
     use clawhive_memory::fact_store::Fact;
 
+    use super::parse_fact_conflict_answer;
     use crate::consolidation::test_helpers::*;
     use crate::consolidation::HippocampusConsolidator;
 
+    #[test]
+    fn parse_fact_conflict_answer_reads_json_and_plain_text() {
+        assert!(parse_fact_conflict_answer(r#"{"supersedes": true}"#));
+        assert!(!parse_fact_conflict_answer(r#"{"supersedes":false}"#));
+        assert!(parse_fact_conflict_answer(
+            "```json\n{\"supersedes\": true}\n```"
+        ));
+        assert!(parse_fact_conflict_answer("Yes."));
+        assert!(!parse_fact_conflict_answer("no"));
+    }
+
     #[tokio::test]
     async fn confirm_fact_conflict_with_llm_parses_yes_and_no() -> Result<()> {
         let (_dir, file_store) = build_file_store()?;
@@ -283,8 +312,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
         };
@@ -305,8 +337,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
         };
@@ -372,7 +372,8 @@ impl HippocampusConsolidator {
             }
         };
 
-        for fact in facts {
+        // MEMORY.md is the shared layer; partitioned facts never align to it.
+        for fact in facts.into_iter().filter(|fact| fact.scope().is_shared()) {
             let Some(item) = best_matching_memory_item_for_fact(&fact, &memory_items) else {
                 continue;
             };
@@ -4,9 +4,10 @@ use clawhive_memory::embedding::EmbeddingProvider;
 use clawhive_memory::file_store::MemoryFileStore;
 use clawhive_memory::search_index::SearchIndex;
 use clawhive_memory::session::SessionReader;
+use clawhive_memory::usage::UsagePurpose;
 use clawhive_memory::MemoryStore;
 
-use super::router::LlmRouter;
+use super::router::{LlmRouter, UsageScope};
 
 mod prompts;
 
@@ -108,6 +109,10 @@ impl HippocampusConsolidator {
         model_primary: String,
         model_fallbacks: Vec<String>,
     ) -> Self {
+        let router = Arc::new(router.with_usage_scope(UsageScope::new(
+            agent_id.clone(),
+            UsagePurpose::Consolidation,
+        )));
         Self {
             agent_id,
             file_store,
@@ -32,13 +32,13 @@ Rules:
 
 pub(super) const PROMOTION_CANDIDATE_SYSTEM_PROMPT: &str = r#"You classify daily observations for memory promotion.
 
-Return a JSON array only. Each item must contain:
+Return a JSON object {"candidates": [...]} only. Each item must contain:
 - "content": concise normalized statement
 - "target_kind": one of "discard", "fact", "memory"
 - "target_section": one of "长期项目主线", "持续性背景脉络", "关键历史决策" when target_kind is "memory", otherwise null
 - "source_date": one of the `### YYYY-MM-DD` dates from the daily observations when known, otherwise null
 - "importance": 0.0 to 1.0
-- "duplicate_key": optional short key for deduplication
+- "duplicate_key": short key for deduplication, or null
 
 Rules:
 - discard greetings, identity chatter, small talk, raw command output, receipts, and bilingual restatements
@@ -59,6 +59,64 @@ Rules:
 
 pub(super) const STALE_SECTION_CONFIRM_SYSTEM_PROMPT: &str = r#"You evaluate whether a MEMORY.md section is stale.
 
-Return exactly one token:
-- STALE: safe to archive
-- KEEP: should remain in MEMORY.md"#;
+Return a JSON object {"decision": ...} where decision is one of:
+- "STALE": safe to archive
+- "KEEP": should remain in MEMORY.md"#;
+
+/// Response schema for [`PROMOTION_CANDIDATE_SYSTEM_PROMPT`].
+pub(super) fn promotion_candidates_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "candidates": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "content": {"type": "string"},
+                        "target_kind": {"type": "string", "enum": ["discard", "fact", "memory"]},
+                        "target_section": {"type": ["string", "null"]},
+                        "source_date": {"type": ["string", "null"]},
+                        "importance": {"type": "number"},
+                        "duplicate_key": {"type": ["string", "null"]}
+                    },
+                    "required": [
+                        "content",
+                        "target_kind",
+                        "target_section",
+                        "source_date",
+                        "importance",
+                        "duplicate_key"
+                    ],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["candidates"],
+        "additionalProperties": false
+    })
+}
+
+/// Response schema for [`STALE_SECTION_CONFIRM_SYSTEM_PROMPT`].
+pub(super) fn stale_section_decision_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "decision": {"type": "string", "enum": ["STALE", "KEEP"]}
+        },
+        "required": ["decision"],
+        "additionalProperties": false
+    })
+}
+
+/// Response schema for the fact conflict confirmation prompt.
+pub(super) fn fact_conflict_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "supersedes": {"type": "boolean"}
+        },
+        "required": ["supersedes"],
+        "additionalProperties": false
+    })
+}
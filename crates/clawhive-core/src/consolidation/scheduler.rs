@@ -5,6 +5,7 @@ use anyhow::{anyhow, Result};
 use chrono::{DateTime, Datelike, Duration, Utc};
 use clawhive_memory::fact_store::FactStore;
 use clawhive_memory::memory_lineage::MemoryLineageStore;
+use clawhive_memory::search_index::fact_chunk_path;
 use clawhive_memory::{EpisodeStatusRecord, FlushPhase, MemoryStore, SessionMemoryStateRecord};
 use tokio::task;
 
@@ -332,143 +333,151 @@ impl ConsolidationScheduler {
     ) -> Result<()> {
         let today = now.date_naive();
 
-        for (date, _) in consolidator.file_store.list_daily_files().await? {
-            let age_days = today.signed_duration_since(date).num_days();
-            if age_days <= archive_retention_days as i64 {
-                continue;
-            }
+        // Partition daily files age out on the same schedule as shared ones.
+        let mut stores = vec![consolidator.file_store.clone()];
+        stores.extend(consolidator.file_store.list_partitions().await?);
+        for store in &stores {
+            for (date, _) in store.list_daily_files().await? {
+                let age_days = today.signed_duration_since(date).num_days();
+                if age_days <= archive_retention_days as i64 {
+                    continue;
+                }
 
-            match consolidator.file_store.archive_daily(date).await {
-                Ok(_) => {
-                    let old_path = format!("memory/{}.md", date.format("%Y-%m-%d"));
-                    let new_path = format!("memory/archive/{}.md", date.format("%Y-%m-%d"));
-                    let chunks_updated = if let Some(search_index) = consolidator.search_index() {
-                        match search_index.update_chunk_path(&old_path, &new_path).await {
-                            Ok(count) => count,
-                            Err(error) => {
-                                tracing::warn!(
-                                    agent_id = %consolidator.agent_id(),
-                                    date = %date,
-                                    %error,
-                                    "chunk path update failed after archive, orphan detection will catch inconsistency"
-                                );
-                                0
+                match store.archive_daily(date).await {
+                    Ok(_) => {
+                        let old_path = store.relative_daily_path(date);
+                        let new_path = store.relative_archive_path(date);
+                        let chunks_updated = if let Some(search_index) = consolidator.search_index()
+                        {
+                            match search_index.update_chunk_path(&old_path, &new_path).await {
+                                Ok(count) => count,
+                                Err(error) => {
+                                    tracing::warn!(
+                                        agent_id = %consolidator.agent_id(),
+                                        date = %date,
+                                        %error,
+                                        "chunk path update failed after archive, orphan detection will catch inconsistency"
+                                    );
+                                    0
+                                }
                             }
-                        }
-                    } else {
-                        0
-                    };
+                        } else {
+                            0
+                        };
 
-                    tracing::info!(
+                        tracing::info!(
+                            agent_id = %consolidator.agent_id(),
+                            date = %date,
+                            old_path = %old_path,
+                            new_path = %new_path,
+                            chunks_updated,
+                            "archived daily file and updated chunk paths"
+                        );
+                    }
+                    Err(error) => tracing::warn!(
                         agent_id = %consolidator.agent_id(),
                         date = %date,
-                        old_path = %old_path,
-                        new_path = %new_path,
-                        chunks_updated,
-                        "archived daily file and updated chunk paths"
-                    );
+                        action = "archive",
+                        %error,
+                        "Daily file archive failed"
+                    ),
                 }
-                Err(error) => tracing::warn!(
-                    agent_id = %consolidator.agent_id(),
-                    date = %date,
-                    action = "archive",
-                    %error,
-                    "Daily file archive failed"
-                ),
             }
-        }
 
-        for (date, _) in consolidator.file_store.list_archived_files().await? {
-            let age_days = today.signed_duration_since(date).num_days();
-            if age_days <= 90 {
-                continue;
-            }
-
-            let archived_rel_path = format!("memory/archive/{}.md", date.format("%Y-%m-%d"));
-            let total_access = match &consolidator.memory_store {
-                Some(store) => {
-                    Self::query_archived_chunk_access_count(
-                        store,
-                        consolidator.agent_id(),
-                        &archived_rel_path,
-                    )
-                    .await?
-                }
-                None => {
-                    tracing::info!(
-                        agent_id = %consolidator.agent_id(),
-                        date = %date,
-                        action = "retain",
-                        reason = "memory_store_unavailable",
-                        "Daily file lifecycle action"
-                    );
+            for (date, _) in store.list_archived_files().await? {
+                let age_days = today.signed_duration_since(date).num_days();
+                if age_days <= 90 {
                     continue;
                 }
-            };
 
-            if total_access == 0 {
-                let has_lineage = match &consolidator.memory_store {
+                let archived_rel_path = store.relative_archive_path(date);
+                let total_access = match &consolidator.memory_store {
                     Some(store) => {
-                        match Self::has_archived_chunk_lineage_refs(
+                        Self::query_archived_chunk_access_count(
                             store,
                             consolidator.agent_id(),
                             &archived_rel_path,
                         )
-                        .await
-                        {
-                            Ok(has) => has,
-                            Err(error) => {
-                                tracing::warn!(
-                                    agent_id = %consolidator.agent_id(),
-                                    date = %date,
-                                    %error,
-                                    "lineage check failed, retaining file"
-                                );
-                                true
+                        .await?
+                    }
+                    None => {
+                        tracing::info!(
+                            agent_id = %consolidator.agent_id(),
+                            date = %date,
+                            action = "retain",
+                            reason = "memory_store_unavailable",
+                            "Daily file lifecycle action"
+                        );
+                        continue;
+                    }
+                };
+
+                if total_access == 0 {
+                    let has_lineage = match &consolidator.memory_store {
+                        Some(store) => {
+                            match Self::has_archived_chunk_lineage_refs(
+                                store,
+                                consolidator.agent_id(),
+                                &archived_rel_path,
+                            )
+                            .await
+                            {
+                                Ok(has) => has,
+                                Err(error) => {
+                                    tracing::warn!(
+                                        agent_id = %consolidator.agent_id(),
+                                        date = %date,
+                                        %error,
+                                        "lineage check failed, retaining file"
+                                    );
+                                    true
+                                }
                             }
                         }
+                        None => false,
+                    };
+
+                    if has_lineage {
+                        tracing::info!(
+                            agent_id = %consolidator.agent_id(),
+                            date = %date,
+                            action = "retain",
+                            reason = "lineage_refs_exist",
+                            "Daily file lifecycle action"
+                        );
+                        continue;
                     }
-                    None => false,
-                };
 
-                if has_lineage {
+                    store.delete_archived_daily(date).await?;
+                    if let Some(search_index) = consolidator.search_index() {
+                        if let Err(error) =
+                            search_index.delete_indexed_path(&archived_rel_path).await
+                        {
+                            tracing::warn!(
+                                agent_id = %consolidator.agent_id(),
+                                date = %date,
+                                %error,
+                                "chunk deletion failed after archive file removal"
+                            );
+                        }
+                    }
                     tracing::info!(
                         agent_id = %consolidator.agent_id(),
                         date = %date,
-                        action = "retain",
-                        reason = "lineage_refs_exist",
-                        "Daily file lifecycle action"
+                        path = %archived_rel_path,
+                        "deleted archived daily file and associated chunks"
                     );
                     continue;
                 }
 
-                consolidator.file_store.delete_archived_daily(date).await?;
-                if let Some(search_index) = consolidator.search_index() {
-                    if let Err(error) = search_index.delete_indexed_path(&archived_rel_path).await {
-                        tracing::warn!(
-                            agent_id = %consolidator.agent_id(),
-                            date = %date,
-                            %error,
-                            "chunk deletion failed after archive file removal"
-                        );
-                    }
-                }
                 tracing::info!(
                     agent_id = %consolidator.agent_id(),
                     date = %date,
-                    path = %archived_rel_path,
-                    "deleted archived daily file and associated chunks"
+                    action = "retain",
+                    total_access_count = total_access,
+                    "Daily file lifecycle action"
                 );
-                continue;
             }
-
-            tracing::info!(
-                agent_id = %consolidator.agent_id(),
-                date = %date,
-                action = "retain",
-                total_access_count = total_access,
-                "Daily file lifecycle action"
-            );
         }
 
         if let Some(search_index) = consolidator.search_index() {
@@ -642,6 +651,16 @@ impl ConsolidationScheduler {
             paths.insert(format!("memory/archive/{}.md", date.format("%Y-%m-%d")));
         }
 
+        for partition in consolidator.file_store.list_partitions().await? {
+            paths.insert(partition.relative_long_term_path());
+            for (date, _) in partition.list_daily_files().await? {
+                paths.insert(partition.relative_daily_path(date));
+            }
+            for (date, _) in partition.list_archived_files().await? {
+                paths.insert(partition.relative_archive_path(date));
+            }
+        }
+
         if let Some(reader) = &consolidator.reindex_session_reader {
             for session_id in reader.list_sessions().await? {
                 paths.insert(format!("sessions/{session_id}"));
@@ -652,6 +671,10 @@ impl ConsolidationScheduler {
             for path in Self::list_indexed_session_paths(store, consolidator.agent_id()).await? {
                 paths.insert(path);
             }
+            let facts = FactStore::new(store.db())
+                .get_active_facts(consolidator.agent_id())
+                .await?;
+            paths.extend(facts.iter().map(|fact| fact_chunk_path(&fact.id)));
         }
 
         let mut known_paths = paths.into_iter().collect::<Vec<_>>();
@@ -962,6 +985,42 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn daily_file_lifecycle_archives_partition_daily_files() -> Result<()> {
+        let (_dir, file_store) = build_file_store()?;
+        let now = Utc::now();
+        let old_date = (now - chrono::Duration::days(35)).date_naive();
+        let alice = clawhive_memory::MemoryScope::from_session_key("telegram:tg:dm:1:user:alice")
+            .unwrap()
+            .user_partition();
+        let partition = file_store.partition(&alice);
+        partition.write_daily(old_date, "private").await?;
+
+        let consolidator = Arc::new(
+            HippocampusConsolidator::new(
+                "agent-1".to_string(),
+                file_store.clone(),
+                build_router(),
+                "sonnet".to_string(),
+                vec![],
+            )
+            .with_memory_store(Arc::new(MemoryStore::open_in_memory()?)),
+        );
+
+        ConsolidationScheduler::run_daily_file_lifecycle(&consolidator, 30, now).await?;
+
+        assert!(partition.read_daily(old_date).await?.is_none());
+        assert!(fs::metadata(
+            file_store
+                .workspace_dir()
+                .join(partition.relative_archive_path(old_date))
+        )
+        .await
+        .is_ok());
+        assert!(file_store.read_daily(old_date).await?.is_none());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn daily_file_lifecycle_deletes_old_archived_file_when_chunks_are_unaccessed(
     ) -> Result<()> {
@@ -1371,8 +1430,11 @@ mod tests {
             last_accessed: None,
             superseded_by: None,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
         };
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 
-use super::prompts::STALE_SECTION_CONFIRM_SYSTEM_PROMPT;
+use super::patch::strip_markdown_fence;
+use super::prompts::{stale_section_decision_schema, STALE_SECTION_CONFIRM_SYSTEM_PROMPT};
 use super::text_utils::reference_half_life_days;
 use super::{HippocampusConsolidator, StaleSectionCandidate};
 use crate::memory_document::{MemoryDocument, MEMORY_SECTION_ORDER};
@@ -79,15 +80,18 @@ impl HippocampusConsolidator {
 
         for candidate in candidates {
             let response = match self
-                .request_consolidation(
+                .request_structured(
                     STALE_SECTION_CONFIRM_SYSTEM_PROMPT,
                     format!(
-                        "Section: {}\nStaleness score: {:.2}\nDays since last accessed: {:.1}\n\nContent:\n{}\n\nIs this section stale and safe to archive? Answer STALE if yes, KEEP if no.",
+                        "Section: {}\nStaleness score: {:.2}\nDays since last accessed: {:.1}\n\nContent:\n{}\n\nIs this section stale and safe to archive? Decide STALE if yes, KEEP if no.",
                         candidate.section,
                         candidate.staleness_score,
                         candidate.days_since_accessed,
                         candidate.content,
                     ),
+                    64,
+                    "stale_section_decision",
+                    stale_section_decision_schema(),
                 )
                 .await
             {
@@ -102,8 +106,7 @@ impl HippocampusConsolidator {
                 }
             };
 
-            let decision = response.text.trim().to_ascii_uppercase();
-            if !(decision.contains("STALE") || decision.contains("YES")) {
+            if !stale_decision_confirms(&response.text) {
                 tracing::info!(section = %candidate.section, decision = %response.text, "Stale candidate rejected by LLM");
                 continue;
             }
@@ -128,6 +131,22 @@ impl HippocampusConsolidator {
     }
 }
 
+/// Read `{"decision": "STALE" | "KEEP"}`, falling back to keyword matching
+/// for providers that ignore the response format.
+fn stale_decision_confirms(text: &str) -> bool {
+    #[derive(serde::Deserialize)]
+    struct Decision {
+        decision: String,
+    }
+
+    let stripped = strip_markdown_fence(text);
+    let decision = serde_json::from_str::<Decision>(stripped.trim())
+        .map(|d| d.decision)
+        .unwrap_or(stripped)
+        .to_ascii_uppercase();
+    decision.contains("STALE") || decision.contains("YES")
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
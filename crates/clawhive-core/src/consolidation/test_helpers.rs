@@ -84,6 +84,8 @@ impl LlmProvider for SequenceProvider {
             content: vec![],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".to_string()),
         })
     }
@@ -118,6 +120,8 @@ impl LlmProvider for FailAtCallProvider {
             content: vec![],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".to_string()),
         })
     }
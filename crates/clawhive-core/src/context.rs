@@ -11,7 +11,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use clawhive_provider::LlmMessage;
 
-use super::router::LlmRouter;
+use super::router::{LlmRouter, UsageScope};
 
 /// Approximate token count from text.
 /// CJK characters average ~1.5 tokens each; ASCII/Latin averages ~0.25 tokens per char.
@@ -307,6 +307,15 @@ impl ContextManager {
         }
     }
 
+    /// Attribute compaction calls to `scope` in the usage ledger.
+    pub fn with_usage_scope(&self, scope: UsageScope) -> Self {
+        Self {
+            config: self.config.clone(),
+            router: Arc::new(self.router.with_usage_scope(scope)),
+            compaction_semaphore: self.compaction_semaphore.clone(),
+        }
+    }
+
     /// Check context state and determine what action is needed.
     /// Does NOT perform compaction - caller should handle based on result.
     pub fn check_context(&self, messages: &[LlmMessage]) -> ContextCheckResult {
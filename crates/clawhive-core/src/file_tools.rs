@@ -6,7 +6,7 @@ use async_trait::async_trait;
 use clawhive_provider::ToolDef;
 
 use super::access_gate::{resolve_path, AccessGate, AccessLevel, AccessResult};
-use super::tool::{ToolContext, ToolExecutor, ToolOutput};
+use super::tool::{file_resource_key, ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 /// Check the AccessGate and return an error ToolOutput if access is not allowed.
 /// Returns `None` when the access is allowed.
@@ -87,6 +87,17 @@ impl ToolExecutor for ReadFileTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::read_only()
+    }
+
+    fn resource_keys(&self, input: &serde_json::Value) -> Vec<String> {
+        input["path"]
+            .as_str()
+            .map(|path| vec![file_resource_key(&self.workspace, path)])
+            .unwrap_or_default()
+    }
+
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let path_str = input["path"]
             .as_str()
@@ -214,6 +225,17 @@ impl ToolExecutor for WriteFileTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, input: &serde_json::Value) -> Vec<String> {
+        input["path"]
+            .as_str()
+            .map(|path| vec![file_resource_key(&self.workspace, path)])
+            .unwrap_or_default()
+    }
+
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let path_str = input["path"]
             .as_str()
@@ -313,6 +335,17 @@ impl ToolExecutor for EditFileTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, input: &serde_json::Value) -> Vec<String> {
+        input["path"]
+            .as_str()
+            .map(|path| vec![file_resource_key(&self.workspace, path)])
+            .unwrap_or_default()
+    }
+
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let path_str = input["path"]
             .as_str()
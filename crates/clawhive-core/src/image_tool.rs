@@ -8,7 +8,7 @@ use async_trait::async_trait;
 use clawhive_provider::ToolDef;
 use serde_json::json;
 
-use super::tool::{ToolContext, ToolExecutor, ToolOutput};
+use super::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 const DEFAULT_MAX_TOKENS: u32 = 1024;
 
@@ -60,6 +60,12 @@ impl ToolExecutor for ImageTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::read_only()
+            .with_max_concurrency(2)
+            .with_timeout(std::time::Duration::from_secs(120))
+    }
+
     async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
         // Collect image URLs
         let mut image_urls: Vec<String> = vec![];
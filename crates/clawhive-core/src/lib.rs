@@ -13,8 +13,11 @@ pub mod hooks;
 pub mod identity_parser;
 pub mod image_tool;
 mod language_prefs;
+pub mod mcp;
 pub mod memory_document;
+pub mod memory_import;
 pub mod memory_retrieval;
+pub mod memory_scope;
 pub mod memory_summary;
 pub mod memory_tools;
 pub mod message_tool;
@@ -33,11 +36,13 @@ pub mod skill_install;
 pub mod skill_install_state;
 pub mod skill_tool;
 pub mod slash_commands;
+pub mod speech;
 pub mod streaming;
 pub mod subagent;
 pub mod subagent_tool;
 pub mod templates;
 pub mod tool;
+pub mod transcription;
 pub mod wait_tool;
 pub mod web_fetch_tool;
 pub mod web_search;
@@ -56,7 +61,9 @@ pub use file_tools::*;
 pub use heartbeat::*;
 pub use hooks::*;
 pub use memory_document::*;
+pub use memory_import::*;
 pub use memory_retrieval::*;
+pub use memory_scope::*;
 pub use memory_summary::*;
 pub use memory_tools::*;
 pub use message_tool::*;
@@ -74,16 +81,18 @@ pub use skill_install::*;
 pub use skill_install_state::*;
 pub use skill_tool::*;
 pub use slash_commands::*;
+pub use speech::*;
 pub use streaming::*;
 pub use subagent::*;
 pub use subagent_tool::*;
 pub use templates::*;
 pub use tool::*;
+pub use transcription::*;
 pub use web_fetch_tool::*;
 pub use web_search::*;
 pub use workspace::*;
 
-use clawhive_provider::ThinkingLevel;
+use clawhive_provider::{SamplingParams, ThinkingLevel};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,6 +110,10 @@ pub struct ModelPolicy {
     /// Falls back to primary when absent.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub compaction_model: Option<String>,
+    /// Default sampling for this agent's turns (`temperature`, `top_p`,
+    /// `stop`, `seed`), written inline in `model_policy`.
+    #[serde(flatten, default)]
+    pub sampling: SamplingParams,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
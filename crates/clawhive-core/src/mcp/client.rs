@@ -0,0 +1,414 @@
+//! MCP client: handshake, tool discovery and tool calls over a transport.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::transport::{HttpTransport, McpTransport, StdioTransport};
+use crate::config::{McpServerConfig, McpTransportConfig};
+
+/// Protocol revision requested during `initialize`.
+pub const MCP_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Upper bound on `tools/list` pages, guarding against cursor loops.
+const MAX_TOOL_PAGES: usize = 50;
+
+/// A tool advertised by an MCP server.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolInfo {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "empty_object_schema")]
+    pub input_schema: Value,
+    #[serde(default)]
+    pub annotations: McpToolAnnotations,
+}
+
+/// Behaviour hints a server may attach to a tool. Only hints that affect
+/// scheduling are kept.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolAnnotations {
+    #[serde(default)]
+    pub read_only_hint: bool,
+}
+
+fn empty_object_schema() -> Value {
+    json!({"type": "object", "properties": {}})
+}
+
+/// Flattened result of a `tools/call`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpCallResult {
+    pub content: String,
+    pub is_error: bool,
+}
+
+/// A connected, initialized MCP server.
+pub struct McpClient {
+    server: String,
+    transport: Box<dyn McpTransport>,
+    timeout: Duration,
+    protocol_version: String,
+}
+
+impl McpClient {
+    /// Spawn/connect the server and perform the `initialize` handshake.
+    pub async fn connect(config: &McpServerConfig, workspace: &Path) -> Result<Self> {
+        let transport: Box<dyn McpTransport> = match &config.transport {
+            McpTransportConfig::Stdio {
+                command,
+                args,
+                env,
+                cwd,
+            } => {
+                let cwd = cwd
+                    .as_ref()
+                    .map(|dir| workspace.join(dir))
+                    .or_else(|| workspace.is_dir().then(|| workspace.to_path_buf()));
+                let inherit_env = config
+                    .permissions
+                    .as_ref()
+                    .map(|p| p.env.clone())
+                    .unwrap_or_default();
+                Box::new(StdioTransport::spawn(
+                    &config.name,
+                    command,
+                    args,
+                    env,
+                    cwd,
+                    &inherit_env,
+                )?)
+            }
+            McpTransportConfig::Http { url, headers } => Box::new(HttpTransport::new(url, headers)),
+        };
+        Self::initialize(
+            &config.name,
+            transport,
+            Duration::from_secs(config.timeout_secs.max(1)),
+        )
+        .await
+    }
+
+    /// Run the `initialize` handshake over an already-open transport.
+    pub async fn initialize(
+        server: &str,
+        transport: Box<dyn McpTransport>,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let result = transport
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": MCP_PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {
+                        "name": "clawhive",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    }
+                }),
+                timeout,
+            )
+            .await?;
+        let protocol_version = result
+            .get("protocolVersion")
+            .and_then(Value::as_str)
+            .unwrap_or(MCP_PROTOCOL_VERSION)
+            .to_string();
+        transport.set_protocol_version(&protocol_version);
+        transport
+            .notify("notifications/initialized", json!({}))
+            .await?;
+
+        let server_info = result.get("serverInfo").cloned().unwrap_or(Value::Null);
+        tracing::info!(
+            server = %server,
+            protocol_version = %protocol_version,
+            server_info = %server_info,
+            "MCP server initialized"
+        );
+
+        Ok(Self {
+            server: server.to_string(),
+            transport,
+            timeout,
+            protocol_version,
+        })
+    }
+
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    pub fn protocol_version(&self) -> &str {
+        &self.protocol_version
+    }
+
+    /// List every tool the server exposes, following pagination cursors.
+    pub async fn list_tools(&self) -> Result<Vec<McpToolInfo>> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_TOOL_PAGES {
+            let params = match &cursor {
+                Some(cursor) => json!({"cursor": cursor}),
+                None => json!({}),
+            };
+            let result = self
+                .transport
+                .request("tools/list", params, self.timeout)
+                .await?;
+            let page: Vec<McpToolInfo> =
+                serde_json::from_value(result.get("tools").cloned().unwrap_or(json!([])))
+                    .map_err(|e| anyhow!("invalid tools/list response: {e}"))?;
+            tools.extend(page);
+            cursor = result
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if cursor.is_none() {
+                return Ok(tools);
+            }
+        }
+        tracing::warn!(server = %self.server, "tools/list pagination limit reached");
+        Ok(tools)
+    }
+
+    /// Invoke a tool and flatten its content blocks into text.
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<McpCallResult> {
+        let arguments = if arguments.is_null() {
+            json!({})
+        } else {
+            arguments
+        };
+        let result = self
+            .transport
+            .request(
+                "tools/call",
+                json!({"name": name, "arguments": arguments}),
+                self.timeout,
+            )
+            .await?;
+        Ok(flatten_call_result(&result))
+    }
+}
+
+/// Render MCP content blocks (text, image, audio, resource, resource_link)
+/// as text for a `ToolOutput`.
+pub(crate) fn flatten_call_result(result: &Value) -> McpCallResult {
+    let is_error = result
+        .get("isError")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let mut parts = Vec::new();
+    for block in result
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let part = match block.get("type").and_then(Value::as_str) {
+            Some("text") => block
+                .get("text")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            Some(kind @ ("image" | "audio")) => Some(format!(
+                "[{kind}: {}]",
+                block
+                    .get("mimeType")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown")
+            )),
+            Some("resource") => block.get("resource").map(|resource| {
+                resource
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| {
+                        format!(
+                            "[resource: {}]",
+                            resource.get("uri").and_then(Value::as_str).unwrap_or("")
+                        )
+                    })
+            }),
+            Some("resource_link") => Some(format!(
+                "[resource: {}]",
+                block.get("uri").and_then(Value::as_str).unwrap_or("")
+            )),
+            _ => None,
+        };
+        parts.extend(part);
+    }
+
+    if parts.is_empty() {
+        if let Some(structured) = result.get("structuredContent") {
+            parts.push(serde_json::to_string_pretty(structured).unwrap_or_default());
+        }
+    }
+
+    McpCallResult {
+        content: parts.join("\n"),
+        is_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    /// Stub MCP server: answers initialize, tools/list and tools/call in order.
+    #[cfg(unix)]
+    const STUB_SERVER: &str = r#"
+read -r line
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-03-26","capabilities":{"tools":{}},"serverInfo":{"name":"stub","version":"0"}}}'
+read -r line
+read -r line
+echo '{"jsonrpc":"2.0","method":"notifications/message","params":{"level":"info"}}'
+echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo","description":"Echo text","inputSchema":{"type":"object","properties":{"text":{"type":"string"}}},"annotations":{"readOnlyHint":true}}]}}'
+read -r line
+echo '{"jsonrpc":"2.0","id":3,"result":{"content":[{"type":"text","text":"hello from stub"}],"isError":false}}'
+read -r line
+"#;
+
+    #[cfg(unix)]
+    fn stub_config(dir: &Path) -> McpServerConfig {
+        let script = dir.join("stub_mcp.sh");
+        std::fs::write(&script, STUB_SERVER).unwrap();
+        McpServerConfig {
+            name: "stub".into(),
+            enabled: true,
+            transport: McpTransportConfig::Stdio {
+                command: "sh".into(),
+                args: vec![script.display().to_string()],
+                env: BTreeMap::new(),
+                cwd: None,
+            },
+            timeout_secs: 5,
+            permissions: None,
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_client_lists_and_calls_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = McpClient::connect(&stub_config(dir.path()), dir.path())
+            .await
+            .unwrap();
+        assert_eq!(client.protocol_version(), "2025-03-26");
+
+        let tools = client.list_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "echo");
+        assert_eq!(tools[0].description.as_deref(), Some("Echo text"));
+        assert!(tools[0].annotations.read_only_hint);
+
+        let result = client
+            .call_tool("echo", json!({"text": "hi"}))
+            .await
+            .unwrap();
+        assert_eq!(result.content, "hello from stub");
+        assert!(!result.is_error);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_client_fails_pending_requests_when_server_exits() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("exit.sh");
+        std::fs::write(&script, "read -r line\nexit 0\n").unwrap();
+        let config = McpServerConfig {
+            transport: McpTransportConfig::Stdio {
+                command: "sh".into(),
+                args: vec![script.display().to_string()],
+                env: BTreeMap::new(),
+                cwd: None,
+            },
+            ..stub_config(dir.path())
+        };
+
+        let err = McpClient::connect(&config, dir.path()).await.err().unwrap();
+        assert!(err.to_string().contains("exited"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn http_client_handles_json_and_sse_responses() {
+        use wiremock::matchers::{body_partial_json, header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "initialize"})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Mcp-Session-Id", "sess-1")
+                    .set_body_json(json!({
+                        "jsonrpc": "2.0", "id": 1,
+                        "result": {"protocolVersion": MCP_PROTOCOL_VERSION, "capabilities": {}}
+                    })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "notifications/initialized"}),
+            ))
+            .and(header("Mcp-Session-Id", "sess-1"))
+            .respond_with(ResponseTemplate::new(202))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "tools/call"})))
+            .and(header("Mcp-Session-Id", "sess-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                "event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":2,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"sse ok\"}],\"isError\":true}}\n\n",
+                "text/event-stream",
+            ))
+            .mount(&server)
+            .await;
+
+        let config = McpServerConfig {
+            name: "remote".into(),
+            enabled: true,
+            transport: McpTransportConfig::Http {
+                url: format!("{}/mcp", server.uri()),
+                headers: BTreeMap::new(),
+            },
+            timeout_secs: 5,
+            permissions: None,
+        };
+        let client = McpClient::connect(&config, Path::new(".")).await.unwrap();
+        let result = client.call_tool("anything", Value::Null).await.unwrap();
+        assert_eq!(result.content, "sse ok");
+        assert!(result.is_error);
+    }
+
+    #[test]
+    fn flatten_call_result_renders_non_text_blocks() {
+        let result = json!({
+            "content": [
+                {"type": "text", "text": "line one"},
+                {"type": "image", "data": "AAAA", "mimeType": "image/png"},
+                {"type": "resource", "resource": {"uri": "file:///a.txt", "text": "file body"}},
+                {"type": "resource_link", "uri": "file:///b.txt", "name": "b"}
+            ]
+        });
+        let flattened = flatten_call_result(&result);
+        assert_eq!(
+            flattened.content,
+            "line one\n[image: image/png]\nfile body\n[resource: file:///b.txt]"
+        );
+        assert!(!flattened.is_error);
+
+        let structured =
+            flatten_call_result(&json!({"content": [], "structuredContent": {"n": 1}}));
+        assert!(structured.content.contains("\"n\": 1"));
+    }
+}
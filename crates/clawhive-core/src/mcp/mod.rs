@@ -0,0 +1,454 @@
+//! Model Context Protocol client.
+//!
+//! MCP servers declared under `mcp_servers` in `agents.d/*.yaml` are spawned
+//! (stdio) or connected (streamable HTTP) at startup, and each of their tools
+//! is mounted into the [`ToolRegistry`] as `mcp__<server>__<tool>`, visible
+//! only to the agents that declared the server.
+//!
+//! MCP tools run with [`ToolOrigin::External`](crate::policy::ToolOrigin)
+//! policy: the server's declared `permissions` (or, when absent, just its
+//! command / host:port) are checked against the hard baseline before the
+//! server is started and again on every call. `tool_policy.allow` prefixes
+//! apply as for any other tool (e.g. `allow: ["mcp__github"]`).
+
+pub mod client;
+pub mod tool;
+pub mod transport;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub use client::{McpCallResult, McpClient, McpToolInfo};
+pub use tool::McpTool;
+
+use crate::config::{FullAgentConfig, McpServerConfig, McpTransportConfig};
+use crate::policy::PolicyContext;
+use crate::tool::{ToolExecutor, ToolRegistry};
+use crate::workspace::Workspace;
+
+/// Tool names must fit the strictest provider limit (OpenAI: 64 chars).
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Registry name for a server tool: `mcp__<server>__<tool>`, restricted to
+/// `[A-Za-z0-9_-]` and truncated to 64 characters.
+pub fn mcp_tool_name(server: &str, tool: &str) -> String {
+    let sanitize = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    let mut name = format!("mcp__{}__{}", sanitize(server), sanitize(tool));
+    name.truncate(MAX_TOOL_NAME_LEN);
+    name
+}
+
+/// Permissions an MCP server runs under: the declared ones, or the minimum
+/// implied by its transport.
+pub fn server_permissions(config: &McpServerConfig) -> corral_core::Permissions {
+    if let Some(declared) = &config.permissions {
+        return declared.to_corral_permissions();
+    }
+    match &config.transport {
+        McpTransportConfig::Stdio { command, env, .. } => corral_core::Permissions {
+            exec: vec![command_basename(command).to_string()],
+            env: env.keys().cloned().collect(),
+            ..Default::default()
+        },
+        McpTransportConfig::Http { url, .. } => corral_core::Permissions {
+            network: corral_core::NetworkPermissions {
+                allow: http_target(url)
+                    .map(|(host, port)| vec![format!("{host}:{port}")])
+                    .unwrap_or_default(),
+            },
+            ..Default::default()
+        },
+    }
+}
+
+fn command_basename(command: &str) -> &str {
+    Path::new(command)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(command)
+}
+
+fn http_target(url: &str) -> Option<(String, u16)> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_string();
+    let port = parsed.port_or_known_default()?;
+    Some((host, port))
+}
+
+/// Check that the transport is allowed under `policy` (exec for stdio,
+/// network for HTTP). Returns the denial reason on failure.
+pub fn check_transport_policy(
+    policy: &PolicyContext,
+    transport: &McpTransportConfig,
+) -> Result<(), String> {
+    match transport {
+        McpTransportConfig::Stdio {
+            command, args, env, ..
+        } => {
+            let command_line = std::iter::once(command.as_str())
+                .chain(args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if !policy.check_exec(&command_line) {
+                return Err(format!("exec not permitted: {command}"));
+            }
+            if let Some(key) = env.keys().find(|key| !policy.check_env(key)) {
+                return Err(format!("env var not permitted: {key}"));
+            }
+            Ok(())
+        }
+        McpTransportConfig::Http { url, .. } => {
+            let Some((host, port)) = http_target(url) else {
+                return Err(format!("invalid MCP server URL: {url}"));
+            };
+            if policy.check_network(&host, port) {
+                Ok(())
+            } else {
+                Err(format!("network access not permitted: {host}:{port}"))
+            }
+        }
+    }
+}
+
+fn agent_policy(agent: &FullAgentConfig, config: &McpServerConfig) -> PolicyContext {
+    PolicyContext::external_with_security_and_private_overrides(
+        server_permissions(config),
+        agent.security.clone(),
+        agent
+            .sandbox
+            .as_ref()
+            .map(|s| s.dangerous_allow_private.clone())
+            .unwrap_or_default(),
+    )
+}
+
+struct PendingServer {
+    config: McpServerConfig,
+    workspace: PathBuf,
+    agent_ids: Vec<String>,
+}
+
+/// Connect every enabled MCP server declared by enabled agents and register
+/// their tools, scoped to the declaring agents. Servers that fail policy
+/// checks or fail to start are logged and skipped. Returns the number of
+/// tools mounted.
+pub async fn mount_mcp_servers(
+    registry: &mut ToolRegistry,
+    agents: &[FullAgentConfig],
+    project_root: &Path,
+) -> usize {
+    // Agents declaring an identical server share one connection.
+    let mut servers: BTreeMap<String, PendingServer> = BTreeMap::new();
+    for agent in agents.iter().filter(|a| a.enabled) {
+        for config in agent.mcp_servers.iter().filter(|s| s.enabled) {
+            if let Err(reason) =
+                check_transport_policy(&agent_policy(agent, config), &config.transport)
+            {
+                tracing::warn!(
+                    target: "clawhive::audit::mcp",
+                    agent_id = %agent.agent_id,
+                    server = %config.name,
+                    %reason,
+                    "MCP server denied by policy, not starting"
+                );
+                continue;
+            }
+            match servers.get_mut(&config.name) {
+                Some(existing) if existing.config.transport == config.transport => {
+                    existing.agent_ids.push(agent.agent_id.clone());
+                }
+                Some(_) => {
+                    tracing::warn!(
+                        agent_id = %agent.agent_id,
+                        server = %config.name,
+                        "MCP server name already used by another agent with a different transport, skipping"
+                    );
+                }
+                None => {
+                    let workspace = Workspace::resolve(
+                        project_root,
+                        &agent.agent_id,
+                        agent.workspace.as_deref(),
+                    )
+                    .root()
+                    .to_path_buf();
+                    servers.insert(
+                        config.name.clone(),
+                        PendingServer {
+                            config: config.clone(),
+                            workspace,
+                            agent_ids: vec![agent.agent_id.clone()],
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    let connections = futures::future::join_all(servers.into_values().map(|pending| async move {
+        let connected = async {
+            let client = McpClient::connect(&pending.config, &pending.workspace).await?;
+            let tools = client.list_tools().await?;
+            anyhow::Ok((Arc::new(client), tools))
+        }
+        .await;
+        (pending, connected)
+    }))
+    .await;
+
+    let mut mounted = 0;
+    for (pending, connected) in connections {
+        let (client, tools) = match connected {
+            Ok(connected) => connected,
+            Err(e) => {
+                tracing::warn!(
+                    server = %pending.config.name,
+                    error = %e,
+                    "failed to start MCP server, its tools will be unavailable"
+                );
+                continue;
+            }
+        };
+        let permissions = server_permissions(&pending.config);
+        for info in tools {
+            let tool = McpTool::new(
+                Arc::clone(&client),
+                info,
+                pending.config.transport.clone(),
+                permissions.clone(),
+            );
+            let name = tool.definition().name;
+            if registry.contains(&name) {
+                tracing::warn!(tool = %name, "MCP tool name collides with an existing tool, skipping");
+                continue;
+            }
+            registry.register_for_agents(Box::new(tool), pending.agent_ids.clone());
+            mounted += 1;
+        }
+        tracing::info!(
+            server = %pending.config.name,
+            agents = ?pending.agent_ids,
+            "MCP server tools mounted"
+        );
+    }
+    mounted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SecurityMode;
+    use crate::tool::ToolContext;
+
+    fn http_server(url: &str) -> McpServerConfig {
+        McpServerConfig {
+            name: "remote".into(),
+            enabled: true,
+            transport: McpTransportConfig::Http {
+                url: url.into(),
+                headers: BTreeMap::new(),
+            },
+            timeout_secs: 5,
+            permissions: None,
+        }
+    }
+
+    #[test]
+    fn tool_name_is_sanitized_and_bounded() {
+        assert_eq!(
+            mcp_tool_name("github", "create_issue"),
+            "mcp__github__create_issue"
+        );
+        assert_eq!(mcp_tool_name("my server", "a.b/c"), "mcp__my_server__a_b_c");
+        assert_eq!(
+            mcp_tool_name("s", &"x".repeat(100)).len(),
+            MAX_TOOL_NAME_LEN
+        );
+    }
+
+    #[test]
+    fn default_permissions_follow_transport() {
+        let perms = server_permissions(&http_server("https://mcp.example.com/mcp"));
+        assert_eq!(perms.network.allow, vec!["mcp.example.com:443"]);
+        assert!(perms.exec.is_empty());
+
+        let stdio = McpServerConfig {
+            transport: McpTransportConfig::Stdio {
+                command: "/usr/local/bin/mcp-github".into(),
+                args: vec![],
+                env: BTreeMap::from([("GITHUB_TOKEN".into(), "t".into())]),
+                cwd: None,
+            },
+            ..http_server("")
+        };
+        let perms = server_permissions(&stdio);
+        assert_eq!(perms.exec, vec!["mcp-github"]);
+        assert_eq!(perms.env, vec!["GITHUB_TOKEN"]);
+    }
+
+    #[test]
+    fn transport_policy_applies_hard_baseline_and_overrides() {
+        let config = http_server("http://127.0.0.1:8931/mcp");
+        let perms = server_permissions(&config);
+
+        let strict = PolicyContext::external(perms.clone());
+        assert!(check_transport_policy(&strict, &config.transport).is_err());
+
+        let overridden = PolicyContext::external_with_security_and_private_overrides(
+            perms,
+            SecurityMode::Standard,
+            vec!["127.0.0.1:8931".into()],
+        );
+        assert!(check_transport_policy(&overridden, &config.transport).is_ok());
+    }
+
+    #[test]
+    fn declared_permissions_must_cover_transport() {
+        let mut config = http_server("https://mcp.example.com/mcp");
+        config.permissions = Some(crate::skill::SkillPermissions {
+            fs: Default::default(),
+            network: crate::skill::NetworkPermissionsDef {
+                allow: vec!["other.example.com:443".into()],
+            },
+            exec: vec![],
+            env: vec![],
+            services: Default::default(),
+        });
+        let policy = PolicyContext::external(server_permissions(&config));
+        let reason = check_transport_policy(&policy, &config.transport).unwrap_err();
+        assert!(reason.contains("mcp.example.com:443"));
+    }
+
+    fn agent_from_yaml(yaml: &str) -> FullAgentConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn mount_registers_stub_server_tools_for_declaring_agent_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("stub_mcp.sh");
+        std::fs::write(
+            &script,
+            r#"
+read -r line
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{}}}'
+read -r line
+read -r line
+echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"lookup","inputSchema":{"type":"object"}}]}}'
+read -r line
+echo '{"jsonrpc":"2.0","id":3,"result":{"content":[{"type":"text","text":"found it"}]}}'
+read -r line
+"#,
+        )
+        .unwrap();
+
+        let agents = vec![
+            agent_from_yaml(&format!(
+                r#"
+agent_id: with-mcp
+enabled: true
+workspace: {workspace}
+model_policy:
+  primary: stub/test-model
+mcp_servers:
+  - name: stub
+    transport: stdio
+    command: sh
+    args: ["{script}"]
+    timeout_secs: 5
+"#,
+                workspace = dir.path().display(),
+                script = script.display(),
+            )),
+            agent_from_yaml(
+                "agent_id: without-mcp\nenabled: true\nmodel_policy:\n  primary: stub/test-model\n",
+            ),
+        ];
+
+        let mut registry = ToolRegistry::new();
+        let mounted = mount_mcp_servers(&mut registry, &agents, dir.path()).await;
+        assert_eq!(mounted, 1);
+
+        let visible: Vec<String> = registry
+            .tool_defs_for_agent("with-mcp")
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(visible, vec!["mcp__stub__lookup"]);
+        assert!(registry.tool_defs_for_agent("without-mcp").is_empty());
+
+        let ctx = ToolContext::builtin().with_agent_id("with-mcp");
+        let output = registry
+            .execute("mcp__stub__lookup", serde_json::json!({}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(output.content, "found it");
+        assert!(!output.is_error);
+    }
+
+    #[tokio::test]
+    async fn mcp_tool_rechecks_policy_with_turn_security_mode() {
+        let config = http_server("http://127.0.0.1:1/mcp");
+        let client = Arc::new(
+            McpClient::initialize(
+                "remote",
+                Box::new(NeverCalled),
+                std::time::Duration::from_secs(1),
+            )
+            .await
+            .unwrap(),
+        );
+        let tool = McpTool::new(
+            client,
+            McpToolInfo {
+                name: "t".into(),
+                description: None,
+                input_schema: serde_json::json!({"type": "object"}),
+                annotations: Default::default(),
+            },
+            config.transport.clone(),
+            server_permissions(&config),
+        );
+
+        let output = tool
+            .execute(serde_json::json!({}), &ToolContext::builtin())
+            .await
+            .unwrap();
+        assert!(output.is_error);
+        assert!(output.content.contains("denied"));
+    }
+
+    /// Transport that completes the handshake but fails any tool call.
+    struct NeverCalled;
+
+    #[async_trait::async_trait]
+    impl transport::McpTransport for NeverCalled {
+        async fn request(
+            &self,
+            method: &str,
+            _params: serde_json::Value,
+            _timeout: std::time::Duration,
+        ) -> anyhow::Result<serde_json::Value> {
+            match method {
+                "initialize" => Ok(serde_json::json!({"protocolVersion": "2025-06-18"})),
+                other => panic!("unexpected MCP request: {other}"),
+            }
+        }
+
+        async fn notify(&self, _method: &str, _params: serde_json::Value) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use clawhive_provider::ToolDef;
+
+use super::client::{McpClient, McpToolInfo};
+use super::{check_transport_policy, mcp_tool_name};
+use crate::config::McpTransportConfig;
+use crate::policy::PolicyContext;
+use crate::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
+
+/// A tool exposed by an MCP server, executed with external-origin policy.
+pub struct McpTool {
+    client: Arc<McpClient>,
+    remote_name: String,
+    definition: ToolDef,
+    read_only: bool,
+    transport: McpTransportConfig,
+    permissions: corral_core::Permissions,
+}
+
+impl McpTool {
+    pub fn new(
+        client: Arc<McpClient>,
+        info: McpToolInfo,
+        transport: McpTransportConfig,
+        permissions: corral_core::Permissions,
+    ) -> Self {
+        let description = info
+            .description
+            .filter(|d| !d.trim().is_empty())
+            .unwrap_or_else(|| format!("Tool '{}' from MCP server", info.name));
+        let definition = ToolDef {
+            name: mcp_tool_name(client.server(), &info.name),
+            description: format!("[MCP: {}] {description}", client.server()),
+            input_schema: info.input_schema,
+        };
+        Self {
+            client,
+            remote_name: info.name,
+            definition,
+            read_only: info.annotations.read_only_hint,
+            transport,
+            permissions,
+        }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for McpTool {
+    fn definition(&self) -> ToolDef {
+        self.definition.clone()
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        if self.read_only {
+            ToolMetadata::read_only()
+        } else {
+            ToolMetadata::mutating()
+        }
+    }
+
+    /// Mutating calls to one server run in order; other servers are unaffected.
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec![format!("mcp:{}", self.client.server())]
+    }
+
+    async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let turn_policy = ctx.policy_context();
+        let policy = PolicyContext::external_with_security_and_private_overrides(
+            self.permissions.clone(),
+            turn_policy.security_mode().clone(),
+            turn_policy.private_overrides().to_vec(),
+        );
+        if let Err(reason) = check_transport_policy(&policy, &self.transport) {
+            tracing::warn!(
+                target: "clawhive::audit::mcp",
+                server = %self.client.server(),
+                tool = %self.remote_name,
+                agent_id = ctx.agent_id().unwrap_or(""),
+                %reason,
+                "MCP tool call denied"
+            );
+            return Ok(ToolOutput {
+                content: format!("MCP server '{}' denied: {reason}", self.client.server()),
+                is_error: true,
+            });
+        }
+
+        match self.client.call_tool(&self.remote_name, input).await {
+            Ok(result) => Ok(ToolOutput {
+                content: result.content,
+                is_error: result.is_error,
+            }),
+            Err(e) => Ok(ToolOutput {
+                content: format!(
+                    "MCP tool '{}' on server '{}' failed: {e}",
+                    self.remote_name,
+                    self.client.server()
+                ),
+                is_error: true,
+            }),
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! JSON-RPC transports for MCP servers (stdio and streamable HTTP).
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+
+/// Environment variables every stdio server inherits from the host process.
+const INHERITED_ENV: &[&str] = &["PATH", "HOME", "USER", "LANG", "TMPDIR"];
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value>>>>>;
+
+/// A bidirectional JSON-RPC channel to one MCP server.
+#[async_trait]
+pub trait McpTransport: Send + Sync {
+    /// Send a request and wait for the matching response `result`.
+    async fn request(&self, method: &str, params: Value, timeout: Duration) -> Result<Value>;
+
+    /// Send a notification (no response expected).
+    async fn notify(&self, method: &str, params: Value) -> Result<()>;
+
+    /// Called once the `initialize` handshake has negotiated a protocol version.
+    fn set_protocol_version(&self, _version: &str) {}
+}
+
+fn request_message(id: u64, method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+}
+
+fn notification_message(method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "method": method, "params": params})
+}
+
+/// Turn a JSON-RPC response object into its `result`, or an error.
+fn response_result(message: &Value) -> Result<Value> {
+    if let Some(error) = message.get("error") {
+        let code = error.get("code").and_then(Value::as_i64).unwrap_or(0);
+        let text = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(anyhow!("MCP error {code}: {text}"));
+    }
+    Ok(message.get("result").cloned().unwrap_or(Value::Null))
+}
+
+// ============================================================
+// stdio
+// ============================================================
+
+/// Spawns the server as a child process and exchanges newline-delimited
+/// JSON-RPC messages over its stdin/stdout.
+pub struct StdioTransport {
+    stdin: Arc<Mutex<ChildStdin>>,
+    pending: PendingMap,
+    next_id: AtomicU64,
+    reader: JoinHandle<()>,
+    stderr_reader: Option<JoinHandle<()>>,
+    _child: Child,
+}
+
+impl StdioTransport {
+    pub fn spawn(
+        server: &str,
+        command: &str,
+        args: &[String],
+        env: &BTreeMap<String, String>,
+        cwd: Option<PathBuf>,
+        inherit_env: &[String],
+    ) -> Result<Self> {
+        let mut cmd = Command::new(command);
+        cmd.args(args)
+            .env_clear()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        for key in INHERITED_ENV
+            .iter()
+            .copied()
+            .chain(inherit_env.iter().map(String::as_str))
+        {
+            if let Some(value) = crate::dotenv::resolve_env(key) {
+                cmd.env(key, value);
+            }
+        }
+        cmd.envs(env);
+        if let Some(cwd) = cwd {
+            cmd.current_dir(cwd);
+        }
+
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("failed to spawn MCP server '{server}' ({command})"))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("MCP server '{server}' has no stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("MCP server '{server}' has no stdout"))?;
+
+        let stdin = Arc::new(Mutex::new(stdin));
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+
+        let reader = tokio::spawn(read_stdio_messages(
+            server.to_string(),
+            BufReader::new(stdout),
+            Arc::clone(&stdin),
+            Arc::clone(&pending),
+        ));
+
+        let stderr_reader = child.stderr.take().map(|stderr| {
+            let server = server.to_string();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::debug!(server = %server, "mcp stderr: {line}");
+                }
+            })
+        });
+
+        Ok(Self {
+            stdin,
+            pending,
+            next_id: AtomicU64::new(1),
+            reader,
+            stderr_reader,
+            _child: child,
+        })
+    }
+
+    async fn write_message(&self, message: &Value) -> Result<()> {
+        write_line(&self.stdin, message).await
+    }
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+        if let Some(handle) = &self.stderr_reader {
+            handle.abort();
+        }
+    }
+}
+
+async fn write_line(stdin: &Mutex<ChildStdin>, message: &Value) -> Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    let mut stdin = stdin.lock().await;
+    stdin.write_all(line.as_bytes()).await?;
+    stdin.flush().await?;
+    Ok(())
+}
+
+async fn read_stdio_messages(
+    server: String,
+    stdout: BufReader<tokio::process::ChildStdout>,
+    stdin: Arc<Mutex<ChildStdin>>,
+    pending: PendingMap,
+) {
+    let mut lines = stdout.lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::warn!(server = %server, error = %e, "mcp stdout read failed");
+                break;
+            }
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let message: Value = match serde_json::from_str(trimmed) {
+            Ok(message) => message,
+            Err(_) => {
+                tracing::debug!(server = %server, "ignoring non-JSON line from MCP server");
+                continue;
+            }
+        };
+
+        let id = message.get("id").and_then(Value::as_u64);
+        match (message.get("method").and_then(Value::as_str), id) {
+            // Server → client request: answer ping, reject everything else.
+            (Some(method), Some(id)) => {
+                let reply = if method == "ping" {
+                    json!({"jsonrpc": "2.0", "id": id, "result": {}})
+                } else {
+                    json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {"code": -32601, "message": format!("method not supported: {method}")}
+                    })
+                };
+                if let Err(e) = write_line(&stdin, &reply).await {
+                    tracing::debug!(server = %server, error = %e, "failed to answer MCP server request");
+                }
+            }
+            (Some(method), None) => {
+                tracing::trace!(server = %server, method, "mcp notification");
+            }
+            (None, Some(id)) => {
+                if let Some(tx) = pending.lock().await.remove(&id) {
+                    let _ = tx.send(response_result(&message));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    tracing::info!(server = %server, "MCP server closed stdout");
+    for (_, tx) in pending.lock().await.drain() {
+        let _ = tx.send(Err(anyhow!("MCP server '{server}' exited")));
+    }
+}
+
+#[async_trait]
+impl McpTransport for StdioTransport {
+    async fn request(&self, method: &str, params: Value, timeout: Duration) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+
+        if let Err(e) = self
+            .write_message(&request_message(id, method, params))
+            .await
+        {
+            self.pending.lock().await.remove(&id);
+            return Err(e.context(format!("failed to send MCP request {method}")));
+        }
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(anyhow!("MCP server dropped request {method}")),
+            Err(_) => {
+                self.pending.lock().await.remove(&id);
+                Err(anyhow!(
+                    "MCP request {method} timed out after {}s",
+                    timeout.as_secs()
+                ))
+            }
+        }
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> Result<()> {
+        self.write_message(&notification_message(method, params))
+            .await
+    }
+}
+
+// ============================================================
+// Streamable HTTP
+// ============================================================
+
+/// POSTs each JSON-RPC message to a single MCP endpoint. Responses arrive
+/// either as `application/json` or as a `text/event-stream` body.
+pub struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    headers: BTreeMap<String, String>,
+    session_id: std::sync::Mutex<Option<String>>,
+    protocol_version: std::sync::Mutex<Option<String>>,
+    next_id: AtomicU64,
+}
+
+impl HttpTransport {
+    pub fn new(url: &str, headers: &BTreeMap<String, String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+            headers: headers.clone(),
+            session_id: std::sync::Mutex::new(None),
+            protocol_version: std::sync::Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    async fn post(&self, message: &Value, timeout: Duration) -> Result<reqwest::Response> {
+        let mut req = self
+            .client
+            .post(&self.url)
+            .timeout(timeout)
+            .header("Accept", "application/json, text/event-stream")
+            .json(message);
+        for (key, value) in &self.headers {
+            req = req.header(key, value);
+        }
+        if let Some(session_id) = self.session_id.lock().ok().and_then(|s| s.clone()) {
+            req = req.header("Mcp-Session-Id", session_id);
+        }
+        if let Some(version) = self.protocol_version.lock().ok().and_then(|v| v.clone()) {
+            req = req.header("MCP-Protocol-Version", version);
+        }
+
+        let resp = req.send().await?;
+        if let Some(session_id) = resp
+            .headers()
+            .get("mcp-session-id")
+            .and_then(|v| v.to_str().ok())
+        {
+            if let Ok(mut slot) = self.session_id.lock() {
+                *slot = Some(session_id.to_string());
+            }
+        }
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("MCP HTTP {status}: {body}"));
+        }
+        Ok(resp)
+    }
+}
+
+/// Find the JSON-RPC response with `id` inside an SSE body.
+fn find_sse_response(body: &str, id: u64) -> Option<Value> {
+    body.split("\n\n").find_map(|event| {
+        let data: Vec<&str> = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(str::trim_start)
+            .collect();
+        if data.is_empty() {
+            return None;
+        }
+        let message: Value = serde_json::from_str(&data.join("\n")).ok()?;
+        (message.get("id").and_then(Value::as_u64) == Some(id)).then_some(message)
+    })
+}
+
+#[async_trait]
+impl McpTransport for HttpTransport {
+    async fn request(&self, method: &str, params: Value, timeout: Duration) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let resp = self
+            .post(&request_message(id, method, params), timeout)
+            .await
+            .with_context(|| format!("MCP request {method} failed"))?;
+
+        let is_sse = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("text/event-stream"));
+        let body = resp.text().await?;
+
+        let message = if is_sse {
+            find_sse_response(&body, id)
+                .ok_or_else(|| anyhow!("MCP SSE stream ended without a response to {method}"))?
+        } else {
+            serde_json::from_str(&body)
+                .with_context(|| format!("invalid JSON response to MCP request {method}"))?
+        };
+        response_result(&message)
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> Result<()> {
+        self.post(
+            &notification_message(method, params),
+            Duration::from_secs(30),
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn set_protocol_version(&self, version: &str) {
+        if let Ok(mut slot) = self.protocol_version.lock() {
+            *slot = Some(version.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_result_maps_errors() {
+        let ok = json!({"jsonrpc": "2.0", "id": 1, "result": {"tools": []}});
+        assert_eq!(response_result(&ok).unwrap(), json!({"tools": []}));
+
+        let err =
+            json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32602, "message": "bad params"}});
+        let msg = response_result(&err).unwrap_err().to_string();
+        assert!(msg.contains("-32602"));
+        assert!(msg.contains("bad params"));
+    }
+
+    #[test]
+    fn find_sse_response_skips_notifications() {
+        let body = "event: message\ndata: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\n\n\
+                    event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":7,\"result\":{\"ok\":true}}\n\n";
+        let message = find_sse_response(body, 7).unwrap();
+        assert_eq!(message["result"]["ok"], json!(true));
+        assert!(find_sse_response(body, 8).is_none());
+    }
+}
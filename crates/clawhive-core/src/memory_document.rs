@@ -51,6 +51,10 @@ impl MemoryDocument {
         doc
     }
 
+    pub fn sections(&self) -> &[MemorySection] {
+        &self.sections
+    }
+
     pub fn section_content(&self, heading: &str) -> String {
         self.sections
             .iter()
@@ -0,0 +1,1131 @@
+//! Bringing memory kept elsewhere into an agent: a `clawhive memory export`,
+//! a folder of markdown notes, an OpenClaw workspace or a chat transcript.
+//!
+//! Loading turns the source into an [`ImportPlan`] without touching the
+//! agent; [`MemoryImporter`] then applies it. Notes become facts, linked to
+//! where they came from through the lineage store, and everything written is
+//! queued in `dirty_sources` for the search index.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clawhive_memory::dirty_sources::{
+    DirtySourceStore, DIRTY_KIND_DAILY_FILE, DIRTY_KIND_MEMORY_FILE, DIRTY_KIND_SESSION,
+};
+use clawhive_memory::fact_store::{self, Fact, FactStore};
+use clawhive_memory::file_store::MemoryFileStore;
+use clawhive_memory::memory_lineage::MemoryLineageStore;
+use clawhive_memory::session::{SessionEntry, SessionMessage, SessionWriter};
+use clawhive_memory::{MemoryScope, MemoryStore, SessionMemoryStateRecord, SessionReader};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::memory_retrieval::is_matching_memory_content;
+
+/// `source_type` of facts created from imported notes.
+pub const IMPORT_SOURCE_TYPE: &str = "import";
+
+/// Shorter bullets are headings or stray markup rather than memories.
+const MIN_ITEM_CHARS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// JSON written by `clawhive memory export`.
+    Export,
+    /// Any folder of `.md` files.
+    Markdown,
+    /// `MEMORY.md` plus dated notes under `memory/`.
+    OpenClaw,
+    /// Chat messages as JSONL or CSV.
+    Transcript,
+}
+
+impl ImportFormat {
+    /// Guess the format from what is at `path`.
+    pub fn detect(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            if path.join("MEMORY.md").is_file() || path.join("memory").is_dir() {
+                return Ok(Self::OpenClaw);
+            }
+            return Ok(Self::Markdown);
+        }
+        match extension(path).as_deref() {
+            Some("json") => Ok(Self::Export),
+            Some("jsonl") | Some("csv") => Ok(Self::Transcript),
+            Some("md") | Some("markdown") => Ok(Self::Markdown),
+            _ => bail!(
+                "cannot tell the import format of {}; pass --format",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "export" | "json" => Ok(Self::Export),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "openclaw" => Ok(Self::OpenClaw),
+            "transcript" | "jsonl" | "csv" => Ok(Self::Transcript),
+            other => bail!(
+                "unknown import format '{other}' (expected export, markdown, openclaw or transcript)"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Export => "export",
+            Self::Markdown => "markdown",
+            Self::OpenClaw => "openclaw",
+            Self::Transcript => "transcript",
+        })
+    }
+}
+
+/// A memory to become a fact, with where it was found.
+#[derive(Debug, Clone)]
+pub struct ImportedFact {
+    pub content: String,
+    pub fact_type: String,
+    pub occurred_at: Option<String>,
+    /// `path#L<line>` for notes, `path#fact:<id>` for exported facts.
+    pub source_ref: String,
+    /// The exported fact this came from, whose metadata is kept.
+    pub original: Option<Fact>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedTranscript {
+    pub session_id: String,
+    /// Channel session key (`channel_type:connector_id:conversation:user:id`)
+    /// the transcript belongs to. Without one only the shared layer reads it.
+    pub session_key: Option<String>,
+    pub source_ref: String,
+    pub messages: Vec<SessionMessage>,
+    /// Rows without any content, which were left out.
+    pub dropped_rows: usize,
+}
+
+/// Everything found in an import source.
+#[derive(Debug, Clone, Default)]
+pub struct ImportPlan {
+    pub facts: Vec<ImportedFact>,
+    /// Restored as `MEMORY.md` when the agent has none yet.
+    pub long_term: Option<String>,
+    /// Restored for dates the agent has no daily file for.
+    pub daily_files: Vec<(NaiveDate, String)>,
+    pub transcripts: Vec<ImportedTranscript>,
+}
+
+impl ImportPlan {
+    /// Read `path` as `format`, or as whatever [`ImportFormat::detect`] finds.
+    pub fn load(path: &Path, format: Option<ImportFormat>) -> Result<Self> {
+        let format = match format {
+            Some(format) => format,
+            None => ImportFormat::detect(path)?,
+        };
+        match format {
+            ImportFormat::Export => load_export(path),
+            ImportFormat::Markdown => load_markdown(path),
+            ImportFormat::OpenClaw => load_openclaw(path),
+            ImportFormat::Transcript => load_transcript(path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub facts_imported: usize,
+    pub facts_skipped: usize,
+    pub long_term_restored: bool,
+    pub daily_files_restored: usize,
+    pub daily_files_skipped: usize,
+    pub sessions_imported: usize,
+    pub sessions_skipped: usize,
+    pub transcript_rows_dropped: usize,
+}
+
+pub struct MemoryImporter {
+    agent_id: String,
+    fact_store: FactStore,
+    lineage_store: MemoryLineageStore,
+    dirty_store: DirtySourceStore,
+    file_store: MemoryFileStore,
+    session_writer: SessionWriter,
+    session_reader: SessionReader,
+    memory: MemoryStore,
+    session_key: Option<String>,
+    dry_run: bool,
+}
+
+impl MemoryImporter {
+    pub fn new(memory: &MemoryStore, agent_id: &str, workspace_dir: &Path) -> Self {
+        Self {
+            agent_id: agent_id.to_owned(),
+            fact_store: FactStore::new(memory.db()),
+            lineage_store: MemoryLineageStore::new(memory.db()),
+            dirty_store: DirtySourceStore::new(memory.db()),
+            file_store: MemoryFileStore::new(workspace_dir),
+            session_writer: SessionWriter::new(workspace_dir),
+            session_reader: SessionReader::new(workspace_dir),
+            memory: memory.clone(),
+            session_key: None,
+            dry_run: false,
+        }
+    }
+
+    /// Session key for transcripts that do not name one, so channel readers
+    /// of that user or conversation can search them.
+    pub fn with_session_key(mut self, session_key: Option<String>) -> Result<Self> {
+        if let Some(key) = &session_key {
+            if MemoryScope::from_session_key(key).is_none() {
+                bail!(
+                    "session key '{key}' is not of the form channel_type:connector_id:conversation:user:id"
+                );
+            }
+        }
+        self.session_key = session_key;
+        Ok(self)
+    }
+
+    /// Report what would be imported without writing anything.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub async fn apply(&self, plan: ImportPlan) -> Result<ImportReport> {
+        let mut report = ImportReport::default();
+        self.import_facts(plan.facts, &mut report).await?;
+        if let Some(long_term) = plan.long_term {
+            report.long_term_restored = self.restore_long_term(&long_term).await?;
+        }
+        for (date, content) in plan.daily_files {
+            if self.restore_daily(date, &content).await? {
+                report.daily_files_restored += 1;
+            } else {
+                report.daily_files_skipped += 1;
+            }
+        }
+        for transcript in plan.transcripts {
+            report.transcript_rows_dropped += transcript.dropped_rows;
+            if self.import_transcript(&transcript).await? {
+                report.sessions_imported += 1;
+            } else {
+                report.sessions_skipped += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    async fn import_facts(
+        &self,
+        items: Vec<ImportedFact>,
+        report: &mut ImportReport,
+    ) -> Result<()> {
+        let mut known = self.fact_store.get_active_facts(&self.agent_id).await?;
+        for item in items {
+            let fact = self.build_fact(&item);
+            let scope = fact.scope();
+            let duplicate = known.iter().any(|existing| {
+                existing.scope() == scope
+                    && is_matching_memory_content(&existing.content, &fact.content)
+            });
+            // Same content under the same id may survive as a superseded row.
+            if duplicate || self.fact_store.find_by_id(&fact.id).await?.is_some() {
+                report.facts_skipped += 1;
+                continue;
+            }
+
+            if !self.dry_run {
+                self.fact_store.insert_fact(&fact).await?;
+                self.fact_store.record_add(&fact).await?;
+                let canonical_id = self.lineage_store.link_fact(&fact).await?;
+                self.lineage_store
+                    .attach_source(
+                        &self.agent_id,
+                        &canonical_id,
+                        IMPORT_SOURCE_TYPE,
+                        &item.source_ref,
+                        "imported",
+                    )
+                    .await?;
+            }
+            report.facts_imported += 1;
+            known.push(fact);
+        }
+        Ok(())
+    }
+
+    fn build_fact(&self, item: &ImportedFact) -> Fact {
+        let now = Utc::now().to_rfc3339();
+        let mut fact = item.original.clone().unwrap_or_else(|| Fact {
+            id: String::new(),
+            agent_id: String::new(),
+            content: String::new(),
+            fact_type: String::new(),
+            importance: 0.5,
+            confidence: 0.8,
+            salience: 0,
+            status: String::new(),
+            occurred_at: None,
+            recorded_at: now.clone(),
+            source_type: IMPORT_SOURCE_TYPE.to_owned(),
+            source_session: None,
+            access_count: 0,
+            last_accessed: None,
+            superseded_by: None,
+            supersede_reason: None,
+            pinned: false,
+            affect: "neutral".to_owned(),
+            affect_intensity: 0.0,
+            created_at: now.clone(),
+            updated_at: now.clone(),
+            user_scope: None,
+            conversation_scope: None,
+        });
+        fact.id = fact_store::generate_scoped_fact_id(&self.agent_id, &fact.scope(), &item.content);
+        fact.agent_id = self.agent_id.clone();
+        fact.content = item.content.clone();
+        fact.fact_type = item.fact_type.clone();
+        fact.occurred_at = item.occurred_at.clone();
+        fact.status = "active".to_owned();
+        fact.superseded_by = None;
+        fact.supersede_reason = None;
+        fact.updated_at = now;
+        fact
+    }
+
+    async fn restore_long_term(&self, content: &str) -> Result<bool> {
+        if content.trim().is_empty() || !self.file_store.read_long_term().await?.trim().is_empty() {
+            return Ok(false);
+        }
+        if !self.dry_run {
+            self.file_store.write_long_term(content).await?;
+            self.dirty_store
+                .enqueue(
+                    &self.agent_id,
+                    DIRTY_KIND_MEMORY_FILE,
+                    &self.file_store.relative_long_term_path(),
+                    "memory_import",
+                )
+                .await?;
+        }
+        Ok(true)
+    }
+
+    async fn restore_daily(&self, date: NaiveDate, content: &str) -> Result<bool> {
+        if content.trim().is_empty() || self.file_store.read_daily(date).await?.is_some() {
+            return Ok(false);
+        }
+        if !self.dry_run {
+            self.file_store.write_daily(date, content).await?;
+            self.dirty_store
+                .enqueue(
+                    &self.agent_id,
+                    DIRTY_KIND_DAILY_FILE,
+                    &self.file_store.relative_daily_path(date),
+                    "memory_import",
+                )
+                .await?;
+        }
+        Ok(true)
+    }
+
+    async fn import_transcript(&self, transcript: &ImportedTranscript) -> Result<bool> {
+        if transcript.messages.is_empty()
+            || self
+                .session_reader
+                .session_exists(&transcript.session_id)
+                .await
+        {
+            return Ok(false);
+        }
+        if !self.dry_run {
+            let session_id = &transcript.session_id;
+            self.session_writer
+                .start_session(session_id, &self.agent_id)
+                .await?;
+            for message in &transcript.messages {
+                let entry = SessionEntry::Message {
+                    id: Uuid::new_v4().to_string(),
+                    timestamp: message.timestamp.unwrap_or_else(Utc::now),
+                    message: message.clone(),
+                };
+                self.session_writer.append(session_id, entry).await?;
+            }
+            // The search index decides who may read a transcript from the
+            // session key recorded for it.
+            if let Some(session_key) = transcript
+                .session_key
+                .as_ref()
+                .or(self.session_key.as_ref())
+            {
+                self.memory
+                    .upsert_session_memory_state(SessionMemoryStateRecord {
+                        agent_id: self.agent_id.clone(),
+                        session_id: session_id.clone(),
+                        session_key: session_key.clone(),
+                        last_flushed_turn: 0,
+                        last_boundary_flush_at: None,
+                        pending_flush: false,
+                        flush_phase: "idle".to_string(),
+                        flush_phase_updated_at: None,
+                        flush_summary_cache: None,
+                        recent_explicit_writes: Vec::new(),
+                        open_episodes: Vec::new(),
+                    })
+                    .await?;
+            }
+            self.dirty_store
+                .enqueue(
+                    &self.agent_id,
+                    DIRTY_KIND_SESSION,
+                    session_id,
+                    "memory_import",
+                )
+                .await?;
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Deserialize)]
+struct ExportFile {
+    #[serde(default)]
+    facts: Vec<Fact>,
+    #[serde(default)]
+    long_term_memory: String,
+    #[serde(default)]
+    daily_files: Vec<ExportDailyFile>,
+}
+
+#[derive(Deserialize)]
+struct ExportDailyFile {
+    date: String,
+    content: String,
+}
+
+fn load_export(path: &Path) -> Result<ImportPlan> {
+    let raw = read_file(path)?;
+    let export: ExportFile = serde_json::from_str(&raw)
+        .with_context(|| format!("{} is not a memory export", path.display()))?;
+
+    let facts = export
+        .facts
+        .into_iter()
+        .filter(|fact| fact.status == "active" && !fact.content.trim().is_empty())
+        .map(|fact| ImportedFact {
+            content: fact.content.trim().to_owned(),
+            fact_type: fact.fact_type.clone(),
+            occurred_at: fact.occurred_at.clone(),
+            source_ref: format!("{}#fact:{}", path.display(), fact.id),
+            original: Some(fact),
+        })
+        .collect();
+    let daily_files = export
+        .daily_files
+        .into_iter()
+        .map(|daily| {
+            NaiveDate::parse_from_str(&daily.date, "%Y-%m-%d")
+                .map(|date| (date, daily.content))
+                .with_context(|| format!("invalid daily file date '{}'", daily.date))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(ImportPlan {
+        facts,
+        long_term: Some(export.long_term_memory).filter(|content| !content.trim().is_empty()),
+        daily_files,
+        transcripts: Vec::new(),
+    })
+}
+
+fn load_markdown(path: &Path) -> Result<ImportPlan> {
+    let files = if path.is_dir() {
+        markdown_files(path)?
+    } else {
+        vec![path.to_path_buf()]
+    };
+    let mut plan = ImportPlan::default();
+    for file in files {
+        plan.facts
+            .extend(note_facts(&file, &read_file(&file)?, file_date(&file)));
+    }
+    Ok(plan)
+}
+
+/// OpenClaw keeps long-term notes in `MEMORY.md` and a log per day in
+/// `memory/YYYY-MM-DD.md`, the same layout as a clawhive workspace. The
+/// long-term notes become facts, not a copy of `MEMORY.md`, so they are not
+/// retrieved twice; the daily logs are restored as daily files.
+fn load_openclaw(path: &Path) -> Result<ImportPlan> {
+    let mut plan = ImportPlan::default();
+    let long_term_path = path.join("MEMORY.md");
+    if long_term_path.is_file() {
+        plan.facts.extend(note_facts(
+            &long_term_path,
+            &read_file(&long_term_path)?,
+            None,
+        ));
+    }
+
+    let memory_dir = path.join("memory");
+    if memory_dir.is_dir() {
+        for file in markdown_files(&memory_dir)? {
+            let content = read_file(&file)?;
+            match file_date(&file) {
+                Some(date) if file.parent() == Some(memory_dir.as_path()) => {
+                    plan.daily_files.push((date, content));
+                }
+                date => plan.facts.extend(note_facts(&file, &content, date)),
+            }
+        }
+    }
+    Ok(plan)
+}
+
+fn load_transcript(path: &Path) -> Result<ImportPlan> {
+    let raw = read_file(path)?;
+    let parsed = match extension(path).as_deref() {
+        Some("csv") => parse_csv_transcript(&raw)?,
+        _ => parse_jsonl_transcript(&raw)?,
+    };
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(ImportPlan {
+        transcripts: vec![ImportedTranscript {
+            session_id: transcript_session_id(&stem),
+            session_key: parsed.session_key,
+            source_ref: path.display().to_string(),
+            messages: parsed.messages,
+            dropped_rows: parsed.dropped_rows,
+        }],
+        ..ImportPlan::default()
+    })
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+}
+
+/// Every `.md` file under `dir`, in a stable order.
+fn markdown_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current)
+            .with_context(|| format!("failed to read {}", current.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else if matches!(extension(&path).as_deref(), Some("md") | Some("markdown")) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn file_date(path: &Path) -> Option<NaiveDate> {
+    let stem = path.file_stem()?.to_string_lossy();
+    NaiveDate::parse_from_str(&stem, "%Y-%m-%d").ok()
+}
+
+fn note_facts(path: &Path, content: &str, date: Option<NaiveDate>) -> Vec<ImportedFact> {
+    markdown_items(content)
+        .into_iter()
+        .map(|item| ImportedFact {
+            fact_type: fact_type_for_heading(item.heading.as_deref(), date.is_some()).to_owned(),
+            occurred_at: date.map(|date| date.format("%Y-%m-%d").to_string()),
+            source_ref: format!("{}#L{}", path.display(), item.line),
+            content: item.text,
+            original: None,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MarkdownItem {
+    line: usize,
+    heading: Option<String>,
+    text: String,
+}
+
+/// The bullets of a note, with wrapped lines joined. Notes without any
+/// bullets are read paragraph by paragraph instead.
+fn markdown_items(content: &str) -> Vec<MarkdownItem> {
+    let mut bullets = Vec::new();
+    let mut paragraphs = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut current: Option<MarkdownItem> = None;
+    let mut paragraph: Option<MarkdownItem> = None;
+    let mut in_code_block = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            flush_item(&mut bullets, &mut current);
+            flush_item(&mut paragraphs, &mut paragraph);
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush_item(&mut bullets, &mut current);
+            flush_item(&mut paragraphs, &mut paragraph);
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            flush_item(&mut bullets, &mut current);
+            flush_item(&mut paragraphs, &mut paragraph);
+            heading = Some(trimmed.trim_start_matches('#').trim().to_owned());
+            continue;
+        }
+
+        if let Some(text) = bullet_text(trimmed) {
+            flush_item(&mut bullets, &mut current);
+            flush_item(&mut paragraphs, &mut paragraph);
+            current = Some(MarkdownItem {
+                line: index + 1,
+                heading: heading.clone(),
+                text: text.to_owned(),
+            });
+            continue;
+        }
+
+        let target = if current.is_some() {
+            &mut current
+        } else {
+            &mut paragraph
+        };
+        match target {
+            Some(item) => {
+                item.text.push(' ');
+                item.text.push_str(trimmed);
+            }
+            None => {
+                *target = Some(MarkdownItem {
+                    line: index + 1,
+                    heading: heading.clone(),
+                    text: trimmed.to_owned(),
+                });
+            }
+        }
+    }
+    flush_item(&mut bullets, &mut current);
+    flush_item(&mut paragraphs, &mut paragraph);
+
+    if bullets.is_empty() {
+        paragraphs
+    } else {
+        bullets
+    }
+}
+
+fn flush_item(items: &mut Vec<MarkdownItem>, current: &mut Option<MarkdownItem>) {
+    if let Some(item) = current.take() {
+        if item.text.chars().count() >= MIN_ITEM_CHARS {
+            items.push(item);
+        }
+    }
+}
+
+fn bullet_text(line: &str) -> Option<&str> {
+    let text = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .or_else(|| {
+            let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            (digits > 0)
+                .then(|| line[digits..].strip_prefix(". "))
+                .flatten()
+        })?;
+    let text = text
+        .strip_prefix("[ ] ")
+        .or_else(|| text.strip_prefix("[x] "))
+        .or_else(|| text.strip_prefix("[X] "))
+        .unwrap_or(text);
+    Some(text.trim())
+}
+
+fn fact_type_for_heading(heading: Option<&str>, dated: bool) -> &'static str {
+    let heading = heading.unwrap_or_default().to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|needle| heading.contains(needle));
+    if has(&["prefer", "like", "偏好"]) {
+        "preference"
+    } else if has(&["rule", "guideline", "convention", "规则"]) {
+        "rule"
+    } else if has(&["people", "person", "contact", "team", "人物"]) {
+        "person"
+    } else if has(&[
+        "procedure",
+        "how to",
+        "how-to",
+        "workflow",
+        "runbook",
+        "流程",
+    ]) {
+        "procedure"
+    } else if has(&["decision", "决策"]) {
+        "decision"
+    } else if dated {
+        "event"
+    } else {
+        "decision"
+    }
+}
+
+fn transcript_session_id(stem: &str) -> String {
+    let slug = stem
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    format!("import-{}", slug.trim_matches('-'))
+}
+
+#[derive(Debug, Default)]
+struct ParsedTranscript {
+    messages: Vec<SessionMessage>,
+    session_key: Option<String>,
+    dropped_rows: usize,
+}
+
+fn parse_jsonl_transcript(raw: &str) -> Result<ParsedTranscript> {
+    let mut parsed = ParsedTranscript::default();
+    for (index, line) in raw.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: serde_json::Value = serde_json::from_str(line)
+            .with_context(|| format!("invalid JSON on line {}", index + 1))?;
+        // clawhive's own session files wrap the message and its timestamp.
+        let record = value.get("message").filter(|message| message.is_object());
+        let record = record.unwrap_or(&value);
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| record.get(*name).or_else(|| value.get(*name)))
+                .and_then(|value| value.as_str())
+        };
+        if let Some(session_key) = field(&["session_key"]) {
+            parsed.session_key = Some(session_key.to_owned());
+        }
+        // clawhive's session header and tool entries carry no message.
+        let kind = value.get("type").and_then(|kind| kind.as_str());
+        if matches!(
+            kind,
+            Some("session" | "tool_call" | "tool_result" | "compaction" | "model_change")
+        ) {
+            continue;
+        }
+        match transcript_message(
+            field(&["role", "speaker", "author", "from"]),
+            field(&["content", "text", "body"]),
+            field(&["timestamp", "time", "created_at", "date"]),
+        ) {
+            Some(message) => parsed.messages.push(message),
+            None => parsed.dropped_rows += 1,
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_csv_transcript(raw: &str) -> Result<ParsedTranscript> {
+    let mut rows = parse_csv(raw).into_iter();
+    let header = rows
+        .next()
+        .ok_or_else(|| anyhow!("CSV transcript is empty"))?
+        .into_iter()
+        .map(|name| name.trim().to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|name| names.contains(&name.as_str()))
+    };
+    let role = column(&["role", "speaker", "author", "from"]);
+    let content = column(&["content", "text", "body", "message"])
+        .ok_or_else(|| anyhow!("CSV transcript needs a content or text column"))?;
+    let timestamp = column(&["timestamp", "time", "created_at", "date"]);
+    let session_key = column(&["session_key"]);
+
+    let mut parsed = ParsedTranscript::default();
+    for row in rows {
+        let cell =
+            |index: Option<usize>| index.and_then(|index| row.get(index)).map(String::as_str);
+        if let Some(key) = cell(session_key).filter(|key| !key.trim().is_empty()) {
+            parsed.session_key = Some(key.trim().to_owned());
+        }
+        match transcript_message(cell(role), cell(Some(content)), cell(timestamp)) {
+            Some(message) => parsed.messages.push(message),
+            None => parsed.dropped_rows += 1,
+        }
+    }
+    Ok(parsed)
+}
+
+fn transcript_message(
+    role: Option<&str>,
+    content: Option<&str>,
+    timestamp: Option<&str>,
+) -> Option<SessionMessage> {
+    // Anyone other than the assistant (a second person, a system notice)
+    // is kept as a user turn rather than dropped.
+    let role = match role.unwrap_or("user").trim().to_ascii_lowercase().as_str() {
+        "assistant" | "ai" | "bot" | "agent" | "model" => "assistant",
+        _ => "user",
+    };
+    let content = content?.trim();
+    if content.is_empty() {
+        return None;
+    }
+    Some(SessionMessage {
+        role: role.to_owned(),
+        content: content.to_owned(),
+        timestamp: timestamp.and_then(|value| {
+            DateTime::parse_from_rfc3339(value.trim())
+                .ok()
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+        }),
+    })
+}
+
+/// RFC 4180 rows: quoted fields may hold commas, quotes (`""`) and newlines.
+fn parse_csv(raw: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = raw.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(ch),
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.iter().any(|cell| !cell.trim().is_empty()));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn markdown_items_join_wrapped_bullets_and_track_headings() {
+        let content = "# Notes\n\n## Preferences\n\n- Prefers concise answers\n  in English\n* [x] Uses vim keybindings everywhere\n\n## Misc\n\n1. Deploys happen on Tuesdays\n- ok\n";
+        let items = markdown_items(content);
+
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| (item.line, item.heading.as_deref(), item.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (5, Some("Preferences"), "Prefers concise answers in English"),
+                (7, Some("Preferences"), "Uses vim keybindings everywhere"),
+                (11, Some("Misc"), "Deploys happen on Tuesdays"),
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_items_fall_back_to_paragraphs() {
+        let content = "# Project\n\nThe billing service is written in Go\nand owned by the payments team.\n\n```\nmake deploy\n```\n";
+        let items = markdown_items(content);
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0].text,
+            "The billing service is written in Go and owned by the payments team."
+        );
+        assert_eq!(items[0].line, 3);
+    }
+
+    #[test]
+    fn detect_recognises_openclaw_workspaces() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(
+            ImportFormat::detect(dir.path()).unwrap(),
+            ImportFormat::Markdown
+        );
+        std::fs::write(dir.path().join("MEMORY.md"), "- note").unwrap();
+        assert_eq!(
+            ImportFormat::detect(dir.path()).unwrap(),
+            ImportFormat::OpenClaw
+        );
+        assert_eq!(
+            ImportFormat::detect(Path::new("chat.csv")).unwrap(),
+            ImportFormat::Transcript
+        );
+        assert!(ImportFormat::detect(Path::new("notes.txt")).is_err());
+    }
+
+    #[test]
+    fn openclaw_notes_become_facts_and_dated_logs_daily_files() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("MEMORY.md"),
+            "## Preferences\n\n- Prefers dark mode in every editor\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("memory")).unwrap();
+        std::fs::write(
+            dir.path().join("memory/2026-03-01.md"),
+            "- Shipped the search rewrite\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("memory/people.md"),
+            "- Dana leads the infra team\n",
+        )
+        .unwrap();
+
+        let plan = ImportPlan::load(dir.path(), None).unwrap();
+
+        assert_eq!(plan.facts.len(), 2);
+        assert_eq!(plan.facts[0].fact_type, "preference");
+        assert!(plan.facts[0].source_ref.ends_with("MEMORY.md#L3"));
+        assert_eq!(plan.facts[1].content, "Dana leads the infra team");
+        assert!(plan.long_term.is_none());
+        assert_eq!(
+            plan.daily_files,
+            vec![(
+                NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+                "- Shipped the search rewrite\n".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn csv_transcripts_handle_quoted_fields_and_count_empty_rows() {
+        let raw = "timestamp,role,text\n2026-03-01T10:00:00Z,user,\"I moved to Berlin, \"\"finally\"\"\nlast week\"\n2026-03-01T10:00:05Z,Dana,Welcome!\n2026-03-01T10:00:06Z,user,\n,assistant,Congratulations!\n";
+        let parsed = parse_csv_transcript(raw).unwrap();
+        let messages = &parsed.messages;
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages[0].content,
+            "I moved to Berlin, \"finally\"\nlast week"
+        );
+        assert!(messages[0].timestamp.is_some());
+        assert_eq!(messages[1].role, "user");
+        assert_eq!(messages[1].content, "Welcome!");
+        assert_eq!(messages[2].role, "assistant");
+        assert!(messages[2].timestamp.is_none());
+        assert_eq!(parsed.dropped_rows, 1);
+        assert_eq!(parsed.session_key, None);
+    }
+
+    #[test]
+    fn jsonl_transcripts_accept_session_files() {
+        let raw = r#"{"type":"session","id":"s1","timestamp":"2026-03-01T10:00:00Z","agent_id":"a"}
+{"type":"message","id":"m1","timestamp":"2026-03-01T10:00:00Z","message":{"role":"user","content":"hello there"}}
+{"speaker":"bot","text":"hi!","session_key":"telegram:tg:chat:1:user:42"}
+"#;
+        let parsed = parse_jsonl_transcript(raw).unwrap();
+        let messages = &parsed.messages;
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(parsed.dropped_rows, 0);
+        assert_eq!(
+            parsed.session_key.as_deref(),
+            Some("telegram:tg:chat:1:user:42")
+        );
+        assert_eq!(messages[0].content, "hello there");
+        assert!(messages[0].timestamp.is_some());
+        assert_eq!(messages[1].role, "assistant");
+    }
+
+    #[tokio::test]
+    async fn transcripts_record_their_session_key() {
+        let dir = TempDir::new().unwrap();
+        let memory = MemoryStore::open_in_memory().unwrap();
+        let transcript = |session_id: &str, session_key: Option<&str>| ImportedTranscript {
+            session_id: session_id.to_string(),
+            session_key: session_key.map(str::to_owned),
+            source_ref: "chat.jsonl".to_string(),
+            messages: vec![SessionMessage {
+                role: "user".to_string(),
+                content: "I moved to Berlin".to_string(),
+                timestamp: None,
+            }],
+            dropped_rows: 2,
+        };
+        let plan = ImportPlan {
+            transcripts: vec![
+                transcript("import-own", Some("telegram:tg:chat:1:user:42")),
+                transcript("import-default", None),
+            ],
+            ..ImportPlan::default()
+        };
+
+        assert!(MemoryImporter::new(&memory, "agent-1", dir.path())
+            .with_session_key(Some("not-a-key".to_string()))
+            .is_err());
+        let report = MemoryImporter::new(&memory, "agent-1", dir.path())
+            .with_session_key(Some("discord:dc:channel:7:user:9".to_string()))
+            .unwrap()
+            .apply(plan)
+            .await
+            .unwrap();
+
+        assert_eq!(report.sessions_imported, 2);
+        assert_eq!(report.transcript_rows_dropped, 4);
+        for (session_id, session_key) in [
+            ("import-own", "telegram:tg:chat:1:user:42"),
+            ("import-default", "discord:dc:channel:7:user:9"),
+        ] {
+            let state = memory
+                .get_session_memory_state("agent-1", session_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(state.session_key, session_key);
+        }
+    }
+
+    #[tokio::test]
+    async fn apply_dedupes_records_provenance_and_queues_indexing() {
+        let dir = TempDir::new().unwrap();
+        let memory = MemoryStore::open_in_memory().unwrap();
+        let fact_store = FactStore::new(memory.db());
+        let existing = ImportedFact {
+            content: "User prefers dark mode in every editor".to_string(),
+            fact_type: "preference".to_string(),
+            occurred_at: None,
+            source_ref: "seed".to_string(),
+            original: None,
+        };
+        MemoryImporter::new(&memory, "agent-1", dir.path())
+            .apply(ImportPlan {
+                facts: vec![existing],
+                ..ImportPlan::default()
+            })
+            .await
+            .unwrap();
+
+        let plan = ImportPlan {
+            facts: vec![
+                ImportedFact {
+                    content: "User prefers dark mode in every editor!".to_string(),
+                    fact_type: "preference".to_string(),
+                    occurred_at: None,
+                    source_ref: "notes.md#L1".to_string(),
+                    original: None,
+                },
+                ImportedFact {
+                    content: "Deploys happen on Tuesdays".to_string(),
+                    fact_type: "rule".to_string(),
+                    occurred_at: None,
+                    source_ref: "notes.md#L2".to_string(),
+                    original: None,
+                },
+            ],
+            daily_files: vec![(
+                NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+                "- Shipped".to_string(),
+            )],
+            ..ImportPlan::default()
+        };
+
+        let dry = MemoryImporter::new(&memory, "agent-1", dir.path())
+            .with_dry_run(true)
+            .apply(plan.clone())
+            .await
+            .unwrap();
+        assert_eq!(dry.facts_imported, 1);
+        assert_eq!(
+            fact_store.get_active_facts("agent-1").await.unwrap().len(),
+            1
+        );
+
+        let report = MemoryImporter::new(&memory, "agent-1", dir.path())
+            .apply(plan)
+            .await
+            .unwrap();
+        assert_eq!(report.facts_imported, 1);
+        assert_eq!(report.facts_skipped, 1);
+        assert_eq!(report.daily_files_restored, 1);
+
+        let facts = fact_store.get_active_facts("agent-1").await.unwrap();
+        let imported = facts
+            .iter()
+            .find(|fact| fact.content == "Deploys happen on Tuesdays")
+            .unwrap();
+        assert_eq!(imported.source_type, IMPORT_SOURCE_TYPE);
+
+        let lineage = MemoryLineageStore::new(memory.db());
+        let links = lineage
+            .get_links_for_source("agent-1", IMPORT_SOURCE_TYPE, "notes.md#L2")
+            .await
+            .unwrap();
+        assert_eq!(links.len(), 1);
+
+        let pending = DirtySourceStore::new(memory.db())
+            .list_pending("agent-1", 10)
+            .await
+            .unwrap();
+        assert!(pending
+            .iter()
+            .any(|item| item.source_kind == DIRTY_KIND_DAILY_FILE));
+        assert!(pending.iter().any(|item| item.source_ref == imported.id));
+    }
+}
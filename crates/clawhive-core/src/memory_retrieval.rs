@@ -4,7 +4,8 @@ use anyhow::Result;
 use clawhive_memory::embedding::EmbeddingProvider;
 use clawhive_memory::fact_store::{Fact, FactStore};
 use clawhive_memory::memory_lineage::MemoryLineageStore;
-use clawhive_memory::search_index::{SearchIndex, SearchResult, TimeRange};
+use clawhive_memory::search_index::{fact_id_from_path, SearchIndex, SearchResult, TimeRange};
+use clawhive_memory::MemoryScope;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MemorySourceKind {
@@ -27,6 +28,9 @@ pub struct MemorySearchParams {
     pub max_results: usize,
     pub min_score: f64,
     pub time_range: Option<TimeRange>,
+    /// Reader identity; facts and chunks in other users' or conversations'
+    /// partitions are left out.
+    pub scope: MemoryScope,
 }
 
 #[derive(Debug, Clone)]
@@ -99,19 +103,20 @@ pub async fn search_memory(
     } else {
         params.max_results
     };
-    let facts = fact_store.get_active_facts(agent_id).await?;
+    let facts = fact_store
+        .get_active_facts_in_scope(agent_id, &params.scope)
+        .await?;
     let filtered_facts = filter_facts_by_time_range(&facts, params.time_range.as_ref());
-    let mut hits = score_facts(
+    let lexical_fact_hits = score_facts(
         &filtered_facts,
         query,
         params.min_score,
         MemoryRoutingBias::Neutral,
-    )
-    .into_iter()
-    .map(|hit| MemoryHit::Fact(Box::new(hit)))
-    .collect::<Vec<_>>();
+    );
 
-    let mut chunks = search_index
+    let (fact_chunks, mut chunks): (Vec<_>, Vec<_>) = search_index
+        .clone()
+        .with_scope(params.scope)
         .search(
             query,
             provider,
@@ -119,7 +124,13 @@ pub async fn search_memory(
             params.min_score,
             params.time_range,
         )
-        .await?;
+        .await?
+        .into_iter()
+        .partition(|chunk| fact_id_from_path(&chunk.path).is_some());
+    let mut hits = fuse_fact_hits(lexical_fact_hits, fact_chunks, &filtered_facts)
+        .into_iter()
+        .map(|hit| MemoryHit::Fact(Box::new(hit)))
+        .collect::<Vec<_>>();
     rerank_chunks_by_source(&mut chunks, MemoryRoutingBias::Neutral);
     hits.extend(
         chunks
@@ -529,6 +540,36 @@ fn score_facts(
     hits
 }
 
+/// Merge lexical fact matches with fact embeddings returned by the hybrid
+/// chunk search. A fact found both ways keeps its better score; embedded
+/// facts missing from `facts` (out of scope or time range) are dropped.
+fn fuse_fact_hits(
+    lexical: Vec<MemoryFactHit>,
+    fact_chunks: Vec<SearchResult>,
+    facts: &[Fact],
+) -> Vec<MemoryFactHit> {
+    let facts_by_id = facts
+        .iter()
+        .map(|fact| (fact.id.as_str(), fact))
+        .collect::<HashMap<_, _>>();
+    let mut hits = lexical;
+    for chunk in fact_chunks {
+        let Some(fact) = fact_id_from_path(&chunk.path).and_then(|id| facts_by_id.get(id)) else {
+            continue;
+        };
+        let score = chunk.score * source_weight(MemorySourceKind::Fact, MemoryRoutingBias::Neutral);
+        match hits.iter_mut().find(|hit| hit.fact.id == fact.id) {
+            Some(hit) => hit.score = hit.score.max(score),
+            None => hits.push(MemoryFactHit {
+                fact: (*fact).clone(),
+                score,
+            }),
+        }
+    }
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits
+}
+
 fn source_weight(kind: MemorySourceKind, bias: MemoryRoutingBias) -> f64 {
     match bias {
         MemoryRoutingBias::Neutral => match kind {
@@ -725,8 +766,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: "2026-03-29T00:00:00Z".to_string(),
             updated_at: "2026-03-29T00:00:00Z".to_string(),
         };
@@ -756,8 +800,11 @@ mod tests {
             superseded_by: None,
             salience: 100,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: "2026-03-29T00:00:00Z".to_string(),
             updated_at: "2026-03-29T00:00:00Z".to_string(),
         };
@@ -788,8 +835,11 @@ mod tests {
             superseded_by: None,
             salience: 10,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: "2026-03-29T00:00:00Z".to_string(),
             updated_at: "2026-03-29T00:00:00Z".to_string(),
         };
@@ -835,8 +885,11 @@ mod tests {
             superseded_by: None,
             salience: 10,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: "2026-03-29T00:00:00Z".to_string(),
             updated_at: "2026-03-29T00:00:00Z".to_string(),
         };
@@ -880,8 +933,11 @@ mod tests {
                     superseded_by: None,
                     salience: 50,
                     supersede_reason: None,
+                    pinned: false,
                     affect: "neutral".to_string(),
                     affect_intensity: 0.0,
+                    user_scope: None,
+                    conversation_scope: None,
                     created_at: "2026-03-29T00:00:00Z".to_string(),
                     updated_at: "2026-03-29T00:00:00Z".to_string(),
                 },
@@ -932,8 +988,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: "2026-03-29T00:00:00Z".to_string(),
             updated_at: "2026-03-29T00:00:00Z".to_string(),
         };
@@ -1066,8 +1125,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: "2026-03-29T00:00:00Z".to_string(),
             updated_at: "2026-03-29T00:00:00Z".to_string(),
         };
@@ -1135,8 +1197,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: "2026-01-01T00:00:00Z".to_string(),
             updated_at: "2026-01-01T00:00:00Z".to_string(),
         };
@@ -1171,8 +1236,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: "2026-03-15T10:00:00Z".to_string(),
             updated_at: "2026-03-15T10:00:00Z".to_string(),
         };
@@ -1193,4 +1261,55 @@ mod tests {
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].id, "fact-in");
     }
+
+    #[test]
+    fn fuse_fact_hits_adds_paraphrase_matches_and_keeps_best_score() {
+        let coffee = Fact {
+            id: "fact-coffee".to_string(),
+            agent_id: "agent-1".to_string(),
+            content: "User takes oat milk in coffee".to_string(),
+            fact_type: "preference".to_string(),
+            importance: 0.5,
+            confidence: 1.0,
+            status: "active".to_string(),
+            occurred_at: None,
+            recorded_at: "2026-03-15T10:00:00Z".to_string(),
+            source_type: "agent_write".to_string(),
+            source_session: None,
+            access_count: 0,
+            last_accessed: None,
+            superseded_by: None,
+            salience: 50,
+            supersede_reason: None,
+            pinned: false,
+            affect: "neutral".to_string(),
+            affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
+            created_at: "2026-03-15T10:00:00Z".to_string(),
+            updated_at: "2026-03-15T10:00:00Z".to_string(),
+        };
+        let tea = Fact {
+            id: "fact-tea".to_string(),
+            content: "User dislikes green tea".to_string(),
+            ..coffee.clone()
+        };
+        let lexical = vec![MemoryFactHit {
+            fact: tea.clone(),
+            score: 0.3,
+        }];
+        let fact_chunks = vec![
+            make_result("facts/fact-coffee", "fact", 0.8),
+            make_result("facts/fact-tea", "fact", 0.1),
+            make_result("facts/fact-out-of-scope", "fact", 0.9),
+        ];
+
+        let hits = fuse_fact_hits(lexical, fact_chunks, &[coffee, tea]);
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].fact.id, "fact-coffee");
+        assert!((hits[0].score - 0.8 * 1.25).abs() < 1e-9);
+        assert_eq!(hits[1].fact.id, "fact-tea");
+        assert!((hits[1].score - 0.3).abs() < 1e-9);
+    }
 }
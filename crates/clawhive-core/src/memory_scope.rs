@@ -0,0 +1,83 @@
+//! Which memory partitions a turn may read and write.
+
+use clawhive_memory::MemoryScope;
+
+use crate::config::MemoryWriteScope;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryAccess {
+    /// Reader identity: the shared layer plus the caller's own user and
+    /// conversation partitions.
+    pub read: MemoryScope,
+    /// Partition new memories go to, or `None` when writes are disabled.
+    pub write: Option<MemoryScope>,
+}
+
+impl MemoryAccess {
+    /// Resolve access for the session that issued a turn.
+    ///
+    /// Session keys without a channel user (CLI, cron, heartbeats) read and
+    /// write the shared layer only.
+    pub fn resolve(write_scope: MemoryWriteScope, session_key: &str) -> Self {
+        let Some(caller) = MemoryScope::from_session_key(session_key) else {
+            return Self {
+                read: MemoryScope::shared(),
+                write: (write_scope != MemoryWriteScope::Disabled).then(MemoryScope::shared),
+            };
+        };
+        let write = match write_scope {
+            MemoryWriteScope::Agent => Some(MemoryScope::shared()),
+            MemoryWriteScope::User => Some(caller.user_partition()),
+            MemoryWriteScope::Conversation => Some(caller.conversation_partition()),
+            MemoryWriteScope::Disabled => None,
+        };
+        Self {
+            read: caller,
+            write,
+        }
+    }
+
+    /// Access for callers outside any session, such as maintenance jobs.
+    pub fn shared() -> Self {
+        Self {
+            read: MemoryScope::shared(),
+            write: Some(MemoryScope::shared()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_scope_writes_to_the_callers_private_partition() {
+        let key = "telegram:tg:group:1:user:alice";
+        let access = MemoryAccess::resolve(MemoryWriteScope::User, key);
+
+        assert_eq!(access.read, MemoryScope::from_session_key(key).unwrap());
+        let write = access.write.unwrap();
+        assert_eq!(write.user_scope.as_deref(), Some("telegram:user:alice"));
+        assert_eq!(write.conversation_scope, None);
+
+        let conversation = MemoryAccess::resolve(MemoryWriteScope::Conversation, key);
+        assert_eq!(
+            conversation.write.unwrap().conversation_scope.as_deref(),
+            Some("telegram:tg:group:1")
+        );
+    }
+
+    #[test]
+    fn sessions_without_a_channel_user_stay_in_the_shared_layer() {
+        let access = MemoryAccess::resolve(MemoryWriteScope::User, "cron:nightly");
+        assert_eq!(access, MemoryAccess::shared());
+
+        let disabled = MemoryAccess::resolve(MemoryWriteScope::Disabled, "cron:nightly");
+        assert_eq!(disabled.write, None);
+        assert!(
+            MemoryAccess::resolve(MemoryWriteScope::Disabled, "telegram:tg:dm:1:user:a")
+                .write
+                .is_none()
+        );
+    }
+}
@@ -6,16 +6,20 @@ use clawhive_memory::embedding::EmbeddingProvider;
 use clawhive_memory::fact_store::{self, Fact, FactStore};
 use clawhive_memory::file_store::MemoryFileStore;
 use clawhive_memory::search_index::{SearchIndex, TimeRange};
-use clawhive_memory::{MemoryStore, RecentExplicitMemoryWrite, SessionMemoryStateRecord};
+use clawhive_memory::{
+    MemoryScope, MemoryStore, RecentExplicitMemoryWrite, SessionMemoryStateRecord,
+};
 use clawhive_provider::ToolDef;
 
+use crate::config::MemoryWriteScope;
 use crate::memory_document::MemoryDocument;
+use crate::memory_scope::MemoryAccess;
 
 use super::memory_retrieval::{
     classify_chunk_source, find_matching_fact, search_memory, source_label, MemoryHit,
     MemorySearchParams,
 };
-use super::tool::{ToolContext, ToolExecutor, ToolOutput};
+use super::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 pub struct MemorySearchTool {
     fact_store: FactStore,
@@ -78,7 +82,11 @@ impl ToolExecutor for MemorySearchTool {
         }
     }
 
-    async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::read_only()
+    }
+
+    async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let query = input["query"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("missing 'query' field"))?;
@@ -107,6 +115,7 @@ impl ToolExecutor for MemorySearchTool {
                 max_results,
                 min_score: 0.35,
                 time_range,
+                scope: MemoryScope::from_session_key(ctx.session_key()).unwrap_or_default(),
             },
         )
         .await
@@ -192,6 +201,10 @@ impl ToolExecutor for MemoryGetTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::read_only()
+    }
+
     async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
         let key = input["key"]
             .as_str()
@@ -237,6 +250,7 @@ pub struct MemoryWriteTool {
     file_store: MemoryFileStore,
     memory: Arc<MemoryStore>,
     agent_id: String,
+    write_scope: MemoryWriteScope,
 }
 
 impl MemoryWriteTool {
@@ -251,9 +265,16 @@ impl MemoryWriteTool {
             file_store,
             memory,
             agent_id,
+            write_scope: MemoryWriteScope::Agent,
         }
     }
 
+    /// Route writes to the partition selected by `memory_policy.write_scope`.
+    pub fn with_write_scope(mut self, write_scope: MemoryWriteScope) -> Self {
+        self.write_scope = write_scope;
+        self
+    }
+
     async fn record_explicit_write_marker(&self, ctx: &ToolContext, fact: &Fact) -> Result<()> {
         if ctx.session_key().is_empty() {
             return Ok(());
@@ -386,6 +407,14 @@ impl ToolExecutor for MemoryWriteTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["memory".into()]
+    }
+
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let content = input["content"]
             .as_str()
@@ -405,8 +434,17 @@ impl ToolExecutor for MemoryWriteTool {
             .unwrap_or(0.0)
             .clamp(0.0, 1.0);
         let has_correction_phrase = super::orchestrator::contains_correction_phrase(content);
+        let Some(scope) = MemoryAccess::resolve(self.write_scope, ctx.session_key()).write else {
+            return Ok(ToolOutput {
+                content: "Memory writes are disabled for this agent.".into(),
+                is_error: true,
+            });
+        };
 
-        let active_facts = self.fact_store.get_active_facts(&self.agent_id).await?;
+        let active_facts = self
+            .fact_store
+            .get_active_facts_in_scope(&self.agent_id, &scope)
+            .await?;
         if let Some(existing) = find_matching_fact(&active_facts, content) {
             if !has_correction_phrase {
                 return Ok(ToolOutput {
@@ -443,7 +481,7 @@ impl ToolExecutor for MemoryWriteTool {
             {
                 let now = chrono::Utc::now().to_rfc3339();
                 let new_fact = Fact {
-                    id: fact_store::generate_fact_id(&self.agent_id, content),
+                    id: fact_store::generate_scoped_fact_id(&self.agent_id, &scope, content),
                     agent_id: self.agent_id.clone(),
                     content: content.to_owned(),
                     fact_type: fact_type.to_owned(),
@@ -459,8 +497,11 @@ impl ToolExecutor for MemoryWriteTool {
                     superseded_by: None,
                     salience,
                     supersede_reason: None,
+                    pinned: false,
                     affect: affect.clone(),
                     affect_intensity,
+                    user_scope: scope.user_scope.clone(),
+                    conversation_scope: scope.conversation_scope.clone(),
                     created_at: now.clone(),
                     updated_at: now,
                 };
@@ -509,7 +550,7 @@ impl ToolExecutor for MemoryWriteTool {
 
         let now = chrono::Utc::now().to_rfc3339();
         let fact = Fact {
-            id: fact_store::generate_fact_id(&self.agent_id, content),
+            id: fact_store::generate_scoped_fact_id(&self.agent_id, &scope, content),
             agent_id: self.agent_id.clone(),
             content: content.to_owned(),
             fact_type: fact_type.to_owned(),
@@ -525,8 +566,11 @@ impl ToolExecutor for MemoryWriteTool {
             superseded_by: None,
             salience,
             supersede_reason: None,
+            pinned: false,
             affect,
             affect_intensity,
+            user_scope: scope.user_scope,
+            conversation_scope: scope.conversation_scope,
             created_at: now.clone(),
             updated_at: now,
         };
@@ -558,6 +602,7 @@ impl ToolExecutor for MemoryWriteTool {
 pub struct MemoryForgetTool {
     fact_store: FactStore,
     agent_id: String,
+    write_scope: MemoryWriteScope,
 }
 
 impl MemoryForgetTool {
@@ -565,8 +610,15 @@ impl MemoryForgetTool {
         Self {
             fact_store,
             agent_id,
+            write_scope: MemoryWriteScope::Agent,
         }
     }
+
+    /// Only retract facts in the partition selected by `memory_policy.write_scope`.
+    pub fn with_write_scope(mut self, write_scope: MemoryWriteScope) -> Self {
+        self.write_scope = write_scope;
+        self
+    }
 }
 
 #[async_trait]
@@ -592,17 +644,31 @@ impl ToolExecutor for MemoryForgetTool {
         }
     }
 
-    async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["memory".into()]
+    }
+
+    async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let content = input["content"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("missing 'content' field"))?;
         let reason = input["reason"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("missing 'reason' field"))?;
+        let Some(scope) = MemoryAccess::resolve(self.write_scope, ctx.session_key()).write else {
+            return Ok(ToolOutput {
+                content: "Memory writes are disabled for this agent.".into(),
+                is_error: true,
+            });
+        };
 
         match self
             .fact_store
-            .find_by_content(&self.agent_id, content)
+            .find_by_content_in_scope(&self.agent_id, &scope, content)
             .await
         {
             Ok(Some(fact)) if fact.status == "active" => {
@@ -636,6 +702,7 @@ impl ToolExecutor for MemoryForgetTool {
 pub struct MemorySupersedeToolDef {
     fact_store: FactStore,
     agent_id: String,
+    write_scope: MemoryWriteScope,
 }
 
 impl MemorySupersedeToolDef {
@@ -643,8 +710,15 @@ impl MemorySupersedeToolDef {
         Self {
             fact_store,
             agent_id,
+            write_scope: MemoryWriteScope::Agent,
         }
     }
+
+    /// Only replace facts in the partition selected by `memory_policy.write_scope`.
+    pub fn with_write_scope(mut self, write_scope: MemoryWriteScope) -> Self {
+        self.write_scope = write_scope;
+        self
+    }
 }
 
 #[async_trait]
@@ -680,7 +754,15 @@ impl ToolExecutor for MemorySupersedeToolDef {
         }
     }
 
-    async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["memory".into()]
+    }
+
+    async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let old_fact_content = input["old_fact_content"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("missing 'old_fact_content' field"))?;
@@ -693,10 +775,16 @@ impl ToolExecutor for MemorySupersedeToolDef {
         let reason = input["reason"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("missing 'reason' field"))?;
+        let Some(scope) = MemoryAccess::resolve(self.write_scope, ctx.session_key()).write else {
+            return Ok(ToolOutput {
+                content: "Memory writes are disabled for this agent.".into(),
+                is_error: true,
+            });
+        };
 
         let old_fact = match self
             .fact_store
-            .find_by_content(&self.agent_id, old_fact_content)
+            .find_by_content_in_scope(&self.agent_id, &scope, old_fact_content)
             .await
         {
             Ok(Some(fact)) => fact,
@@ -726,7 +814,7 @@ impl ToolExecutor for MemorySupersedeToolDef {
 
         let now = chrono::Utc::now().to_rfc3339();
         let new_fact = Fact {
-            id: fact_store::generate_fact_id(&self.agent_id, new_fact_content),
+            id: fact_store::generate_scoped_fact_id(&self.agent_id, &scope, new_fact_content),
             agent_id: self.agent_id.clone(),
             content: new_fact_content.to_owned(),
             fact_type: fact_type.to_owned(),
@@ -742,8 +830,11 @@ impl ToolExecutor for MemorySupersedeToolDef {
             superseded_by: None,
             salience: fact_store::default_salience_for_type(fact_type),
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_owned(),
             affect_intensity: 0.0,
+            user_scope: scope.user_scope,
+            conversation_scope: scope.conversation_scope,
             created_at: now.clone(),
             updated_at: now,
         };
@@ -937,6 +1028,88 @@ mod tests {
         assert!(result.content.contains("Chinese replies"));
     }
 
+    #[tokio::test]
+    async fn user_scoped_memory_is_private_to_its_author() {
+        let (tmp, memory, search_tool, _) = setup();
+        let alice = ToolContext::builtin().with_session_key("telegram:tg:group:1:user:alice");
+        let bob = ToolContext::builtin().with_session_key("telegram:tg:group:1:user:bob");
+        let fact_store = FactStore::new(memory.db());
+        let write_tool = MemoryWriteTool::new(
+            fact_store.clone(),
+            MemoryFileStore::new(tmp.path()),
+            memory.clone(),
+            "test-agent".to_string(),
+        )
+        .with_write_scope(MemoryWriteScope::User);
+
+        let result = write_tool
+            .execute(
+                serde_json::json!({
+                    "content": "User is allergic to peanuts",
+                    "fact_type": "person"
+                }),
+                &alice,
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error);
+
+        let for_alice = search_tool
+            .execute(serde_json::json!({"query": "allergic peanuts"}), &alice)
+            .await
+            .unwrap();
+        assert!(for_alice.content.contains("allergic to peanuts"));
+        let for_bob = search_tool
+            .execute(serde_json::json!({"query": "allergic peanuts"}), &bob)
+            .await
+            .unwrap();
+        assert!(!for_bob.content.contains("allergic to peanuts"));
+
+        let forget = MemoryForgetTool::new(fact_store.clone(), "test-agent".to_string())
+            .with_write_scope(MemoryWriteScope::User);
+        let bob_forget = forget
+            .execute(
+                serde_json::json!({"content": "User is allergic to peanuts", "reason": "test"}),
+                &bob,
+            )
+            .await
+            .unwrap();
+        assert!(bob_forget.content.starts_with("No active fact found"));
+        let alice_forget = forget
+            .execute(
+                serde_json::json!({"content": "User is allergic to peanuts", "reason": "test"}),
+                &alice,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            alice_forget.content,
+            "Forgotten: User is allergic to peanuts"
+        );
+    }
+
+    #[tokio::test]
+    async fn memory_write_is_refused_when_write_scope_is_none() {
+        let (tmp, memory, _, _) = setup();
+        let tool = MemoryWriteTool::new(
+            FactStore::new(memory.db()),
+            MemoryFileStore::new(tmp.path()),
+            memory.clone(),
+            "test-agent".to_string(),
+        )
+        .with_write_scope(MemoryWriteScope::Disabled);
+
+        let result = tool
+            .execute(
+                serde_json::json!({"content": "User likes tea", "fact_type": "preference"}),
+                &ToolContext::builtin(),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error);
+    }
+
     #[tokio::test]
     async fn memory_search_honors_time_range_for_daily_chunks() {
         let (_tmp, _memory, tool, _) = setup();
@@ -6,7 +6,7 @@ use clawhive_schema::BusMessage;
 use serde::Deserialize;
 
 use crate::policy::ToolOrigin;
-use crate::tool::{ToolContext, ToolExecutor, ToolOutput};
+use crate::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 pub const MESSAGE_TOOL_NAME: &str = "message";
 
@@ -71,6 +71,14 @@ impl ToolExecutor for MessageTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["outbound".into()]
+    }
+
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let parsed: MessageInput = serde_json::from_value(input)
             .map_err(|e| anyhow!("invalid message tool input: {e}"))?;
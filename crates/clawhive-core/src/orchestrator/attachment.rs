@@ -3,6 +3,8 @@ use base64::Engine as _;
 use clawhive_provider::ContentBlock;
 use clawhive_schema::{Attachment, AttachmentKind};
 
+use crate::transcription::Transcripts;
+
 pub(super) const MAX_ATTACHMENT_TEXT_CHARS: usize = 12_000;
 pub(super) const MAX_PDF_IMAGE_PAGES: usize = 8;
 
@@ -204,9 +206,26 @@ pub(super) fn attachment_prompt_fragment(attachment: &Attachment) -> Option<Stri
     ))
 }
 
-pub(super) fn build_attachment_blocks(attachments: &[Attachment]) -> Vec<ContentBlock> {
+/// Audio that was transcribed goes in as its transcript.
+fn transcript_fragment(attachment: &Attachment, transcript: &str) -> String {
+    let mime = attachment.mime_type.as_deref().unwrap_or("audio/ogg");
+    let label = attachment.file_name.as_deref().unwrap_or("voice message");
+    let body = truncate_attachment_text(transcript);
+    format!("<attachment name=\"{label}\" type=\"{mime}\">\nTranscript:\n{body}\n</attachment>")
+}
+
+pub(super) fn build_attachment_blocks(
+    attachments: &[Attachment],
+    transcripts: &Transcripts,
+) -> Vec<ContentBlock> {
     let mut blocks = Vec::new();
-    for a in attachments {
+    for (index, a) in attachments.iter().enumerate() {
+        if let Some(transcript) = transcripts.get(&index) {
+            blocks.push(ContentBlock::Text {
+                text: transcript_fragment(a, transcript),
+            });
+            continue;
+        }
         match a.kind {
             AttachmentKind::Image => {
                 let media_type = a
@@ -329,9 +348,17 @@ pub(super) fn build_pdf_content_blocks(attachment: &Attachment) -> Option<Vec<Co
     }
 }
 
-pub(super) fn build_session_text(user_text: &str, attachments: &[Attachment]) -> String {
+pub(super) fn build_session_text(
+    user_text: &str,
+    attachments: &[Attachment],
+    transcripts: &Transcripts,
+) -> String {
     let mut parts = vec![user_text.to_string()];
-    for a in attachments {
+    for (index, a) in attachments.iter().enumerate() {
+        if let Some(transcript) = transcripts.get(&index) {
+            parts.push(transcript_fragment(a, transcript));
+            continue;
+        }
         if matches!(a.kind, AttachmentKind::Image) {
             continue;
         }
@@ -434,7 +461,7 @@ mod tests {
             size: None,
         };
 
-        let blocks = build_attachment_blocks(&[attachment]);
+        let blocks = build_attachment_blocks(&[attachment], &Transcripts::new());
         assert_eq!(blocks.len(), 1);
         match &blocks[0] {
             ContentBlock::Text { text } => {
@@ -459,9 +486,39 @@ mod tests {
                 file_name: Some("lease.docx".to_string()),
                 size: None,
             }],
+            &Transcripts::new(),
         );
 
         assert!(session_text.contains("lease.docx"));
         assert!(session_text.contains("binary attachment uploaded"));
     }
+
+    #[test]
+    fn transcribed_audio_replaces_binary_placeholder() {
+        let voice = Attachment {
+            kind: AttachmentKind::Audio,
+            url: "T2dnUw==".to_string(),
+            mime_type: Some("audio/ogg".to_string()),
+            file_name: Some("voice.ogg".to_string()),
+            size: None,
+        };
+        let transcripts = Transcripts::from([(0, "Book a table for two.".to_string())]);
+
+        let blocks = build_attachment_blocks(std::slice::from_ref(&voice), &transcripts);
+        assert_eq!(blocks.len(), 1);
+        match &blocks[0] {
+            ContentBlock::Text { text } => {
+                assert!(text.contains("voice.ogg"));
+                assert!(text.contains("Transcript:\nBook a table for two."));
+            }
+            other => panic!("expected text block, got {other:?}"),
+        }
+
+        let session_text = build_session_text("", std::slice::from_ref(&voice), &transcripts);
+        assert!(session_text.contains("Book a table for two."));
+        assert!(!session_text.contains("binary attachment uploaded"));
+
+        let untranscribed = build_session_text("", &[voice], &Transcripts::new());
+        assert!(untranscribed.contains("binary attachment uploaded"));
+    }
 }
@@ -11,6 +11,8 @@ use crate::approval::ApprovalRegistry;
 use crate::config_view::ConfigView;
 use crate::session::SessionManager;
 use crate::skill::SkillRegistry;
+use crate::speech::VoiceReplies;
+use crate::transcription::AudioTranscription;
 
 use super::Orchestrator;
 
@@ -27,6 +29,8 @@ pub struct OrchestratorBuilder {
     skill_registry: Option<SkillRegistry>,
     approval_registry: Option<Arc<ApprovalRegistry>>,
     project_root: Option<std::path::PathBuf>,
+    transcription: Option<Arc<AudioTranscription>>,
+    voice_replies: Option<Arc<VoiceReplies>>,
     // Allow overriding auto-derived workspace I/O (e.g. in tests with pre-populated stores)
     file_store: Option<MemoryFileStore>,
     session_writer: Option<SessionWriter>,
@@ -53,6 +57,8 @@ impl OrchestratorBuilder {
             skill_registry: None,
             approval_registry: None,
             project_root: None,
+            transcription: None,
+            voice_replies: None,
             file_store: None,
             session_writer: None,
             session_reader: None,
@@ -80,6 +86,18 @@ impl OrchestratorBuilder {
         self
     }
 
+    /// Transcribe inbound audio attachments with this provider.
+    pub fn transcription(mut self, transcription: Arc<AudioTranscription>) -> Self {
+        self.transcription = Some(transcription);
+        self
+    }
+
+    /// Speak replies where the agent's `reply_voice` policy asks for it.
+    pub fn voice_replies(mut self, voice_replies: Arc<VoiceReplies>) -> Self {
+        self.voice_replies = Some(voice_replies);
+        self
+    }
+
     pub fn file_store(mut self, file_store: MemoryFileStore) -> Self {
         self.file_store = Some(file_store);
         self
@@ -134,6 +152,8 @@ impl OrchestratorBuilder {
             search_index,
             self.workspace_root,
             self.project_root,
+            self.transcription,
+            self.voice_replies,
         )
     }
 }
@@ -0,0 +1,300 @@
+//! The `delegate_*` tools for orchestrated turns.
+//!
+//! The sub-agent runs the same tool loop as a normal turn, as the target
+//! agent: its own persona, workspace, tool policy and security mode. Tool
+//! calls it makes are published with the delegating run as their parent.
+//! Async runs keep going after the delegating turn ends; their results are
+//! gathered with `delegate_collect`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+
+use anyhow::{anyhow, Result};
+use clawhive_provider::LlmMessage;
+use clawhive_schema::BusMessage;
+
+use crate::config::FullAgentConfig;
+use crate::config_view::ConfigView;
+use crate::subagent::{
+    DelegatedRun, SubAgentReport, SubAgentRequest, SubAgentUsage, DEFAULT_DELEGATE_TIMEOUT_SECS,
+};
+use crate::subagent_tool::{
+    started_run_content, DelegateCollectTool, DelegateMode, DelegateStatusTool, SubAgentTool,
+};
+use crate::tool::{ToolContext, ToolExecutor, ToolOutput};
+
+use super::Orchestrator;
+
+const DELEGATION_NOTE: &str = "## Delegated Task\nAnother agent delegated the task below to you. Work through it with your tools, then reply with the result. Your reply goes back to the delegating agent, not to a user, so report what you did and found instead of asking follow-up questions.";
+
+type ReportFuture<'a> = Pin<Box<dyn Future<Output = Result<SubAgentReport>> + Send + 'a>>;
+
+/// What a delegated run inherits from the turn that started it. Owned, so
+/// async runs can outlive the parent's `ToolContext`.
+#[derive(Debug, Clone, Default)]
+struct DelegationOrigin {
+    /// (channel_type, connector_id, conversation_scope, user_scope)
+    source: Option<(String, String, String, String)>,
+    is_scheduled_task: bool,
+}
+
+impl DelegationOrigin {
+    fn from_context(ctx: &ToolContext) -> Self {
+        let source = match (
+            ctx.source_channel_type(),
+            ctx.source_connector_id(),
+            ctx.source_conversation_scope(),
+        ) {
+            (Some(channel), Some(connector), Some(scope)) => Some((
+                channel.to_string(),
+                connector.to_string(),
+                scope.to_string(),
+                ctx.source_user_scope().unwrap_or_default().to_string(),
+            )),
+            _ => None,
+        };
+        Self {
+            source,
+            is_scheduled_task: ctx.is_scheduled_task(),
+        }
+    }
+}
+
+impl Orchestrator {
+    /// Let async `delegate_task` runs hold on to this orchestrator. Called
+    /// once the orchestrator is shared, e.g. by the gateway; without it only
+    /// sync delegation is available.
+    pub fn bind_shared(self: &Arc<Self>) {
+        let _ = self.shared.set(Arc::downgrade(self));
+    }
+
+    pub(super) async fn delegate_task(
+        &self,
+        view: &ConfigView,
+        input: serde_json::Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolOutput> {
+        let mode = DelegateMode::from_input(&input)?;
+        let req = SubAgentTool::request_from_input(&input, ctx, DEFAULT_DELEGATE_TIMEOUT_SECS)?;
+        if view.agent(&req.target_agent_id).is_none() {
+            return Ok(ToolOutput {
+                content: format!(
+                    "Failed to spawn sub-agent: sub-agent not found: {}",
+                    req.target_agent_id
+                ),
+                is_error: true,
+            });
+        }
+        let origin = DelegationOrigin::from_context(ctx);
+        let task = req.task.clone();
+
+        if mode == DelegateMode::Async {
+            let notify = input["notify"].as_bool().unwrap_or(false);
+            return self.delegate_task_async(req, task, origin, notify).await;
+        }
+
+        let result = match self
+            .subagents
+            .run(req, |run| self.run_delegated(view, run, task, &origin))
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                return Ok(ToolOutput {
+                    content: format!("Failed to spawn sub-agent: {e}"),
+                    is_error: true,
+                });
+            }
+        };
+
+        if !result.artifacts.is_empty() {
+            if let Some(collector) = ctx.attachment_collector() {
+                collector
+                    .lock()
+                    .await
+                    .extend(result.artifacts.iter().cloned());
+            }
+        }
+
+        Ok(ToolOutput {
+            content: result.to_tool_content(),
+            is_error: !result.success,
+        })
+    }
+
+    async fn delegate_task_async(
+        &self,
+        req: SubAgentRequest,
+        task: String,
+        origin: DelegationOrigin,
+        notify: bool,
+    ) -> Result<ToolOutput> {
+        let Some(orchestrator) = self.shared.get().and_then(Weak::upgrade) else {
+            return Ok(ToolOutput {
+                content: "Async delegation is not available here; use mode \"sync\"".into(),
+                is_error: true,
+            });
+        };
+
+        let target_agent_id = req.target_agent_id.clone();
+        let announce_to = origin.source.clone().filter(|_| notify).map(
+            |(channel_type, connector_id, conversation_scope, _)| {
+                (channel_type, connector_id, conversation_scope)
+            },
+        );
+        let bus = self.bus.clone();
+        let agent_id = target_agent_id.clone();
+
+        let spawned = self
+            .subagents
+            .spawn_with_completion(
+                req,
+                move |run| async move {
+                    let view = orchestrator.config_view();
+                    orchestrator.run_delegated(&view, run, task, &origin).await
+                },
+                move |result| async move {
+                    let Some((channel_type, connector_id, conversation_scope)) = announce_to else {
+                        return;
+                    };
+                    let _ = bus
+                        .publish(BusMessage::DelegateTaskCompleted {
+                            run_id: result.run_id,
+                            agent_id,
+                            channel_type,
+                            connector_id,
+                            conversation_scope,
+                            status: result.status.as_str().to_string(),
+                            output: result.output,
+                        })
+                        .await;
+                },
+            )
+            .await;
+
+        Ok(match spawned {
+            Ok(run_id) => ToolOutput {
+                content: started_run_content(run_id, &target_agent_id),
+                is_error: false,
+            },
+            Err(e) => ToolOutput {
+                content: format!("Failed to spawn sub-agent: {e}"),
+                is_error: true,
+            },
+        })
+    }
+
+    pub(super) async fn delegate_status(
+        &self,
+        input: serde_json::Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolOutput> {
+        DelegateStatusTool::new(self.subagents.clone())
+            .execute(input, ctx)
+            .await
+    }
+
+    pub(super) async fn delegate_collect(
+        &self,
+        input: serde_json::Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolOutput> {
+        DelegateCollectTool::new(self.subagents.clone(), DEFAULT_DELEGATE_TIMEOUT_SECS)
+            .execute(input, ctx)
+            .await
+    }
+
+    /// Drive one delegated run through the tool loop. Boxed because the loop
+    /// can reach `delegate_task` again.
+    fn run_delegated<'a>(
+        &'a self,
+        view: &'a ConfigView,
+        run: DelegatedRun,
+        task: String,
+        origin: &'a DelegationOrigin,
+    ) -> ReportFuture<'a> {
+        Box::pin(async move {
+            let agent = view
+                .agent(&run.target_agent_id)
+                .cloned()
+                .ok_or_else(|| anyhow!("sub-agent not found: {}", run.target_agent_id))?;
+            let target = agent.agent_id.as_str();
+            let model = agent.model_policy.primary.as_str();
+
+            let base = view
+                .personas
+                .get(target)
+                .map(|persona| persona.assembled_system_prompt_minimal())
+                .unwrap_or_default();
+            let system = self.build_runtime_system_prompt(
+                target,
+                model,
+                format!("{base}\n\n{DELEGATION_NOTE}"),
+            );
+            let allowed = self.delegated_tool_names(view, &agent);
+
+            let private_overrides = agent
+                .sandbox
+                .as_ref()
+                .map(|s| s.dangerous_allow_private.clone())
+                .unwrap_or_default();
+            let session_key = format!("subagent:{}", run.run_id);
+
+            let (resp, _messages, artifacts, meta) = self
+                .tool_use_loop(
+                    view,
+                    target,
+                    &session_key,
+                    model,
+                    &agent.model_policy.fallbacks,
+                    Some(system),
+                    vec![LlmMessage::user(task)],
+                    agent.max_response_tokens.unwrap_or(4096),
+                    Some(&allowed),
+                    None,
+                    agent.security.clone(),
+                    private_overrides,
+                    origin.source.clone(),
+                    false,
+                    origin.is_scheduled_task,
+                    agent.model_policy.thinking_level,
+                    run.trace_id,
+                    run.cancel.clone(),
+                    Some(&run),
+                )
+                .await?;
+
+            Ok(SubAgentReport {
+                output: resp.text,
+                artifacts,
+                tool_calls: meta.successful_tool_calls,
+                usage: SubAgentUsage {
+                    input_tokens: meta.input_tokens,
+                    output_tokens: meta.output_tokens,
+                },
+                cancelled: meta.cancelled,
+            })
+        })
+    }
+
+    /// The target agent's own tools, narrowed by its tool policy and by the
+    /// runner's allow-list. Both match by prefix; an empty list allows all.
+    fn delegated_tool_names(&self, view: &ConfigView, agent: &FullAgentConfig) -> Vec<String> {
+        let policy = agent
+            .tool_policy
+            .as_ref()
+            .map(|p| p.allow.as_slice())
+            .unwrap_or_default();
+        let runner = self.subagents.allowed_tools();
+        let permits = |list: &[String], name: &str| {
+            list.is_empty() || list.iter().any(|prefix| name.starts_with(prefix.as_str()))
+        };
+        view.tool_registry
+            .tool_defs_for_agent(&agent.agent_id)
+            .into_iter()
+            .map(|def| def.name)
+            .filter(|name| permits(policy, name) && permits(runner, name))
+            .collect()
+    }
+}
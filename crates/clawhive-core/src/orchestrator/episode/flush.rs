@@ -99,7 +99,9 @@ impl Orchestrator {
                 false, // must_use_web_search
                 false, // is_scheduled_task
                 agent.model_policy.thinking_level,
+                inbound.trace_id,
                 CancellationToken::new(),
+                None,
             )
             .await?;
 
@@ -169,6 +171,7 @@ impl Orchestrator {
             .bus
             .publish(BusMessage::ReplyReady {
                 outbound: outbound.clone(),
+                agent_id: Some(agent_id.to_string()),
             })
             .await;
 
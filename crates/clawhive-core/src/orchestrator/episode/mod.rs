@@ -411,8 +411,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
         };
@@ -466,8 +469,11 @@ mod tests {
             superseded_by: None,
             salience: 50,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_string(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
         };
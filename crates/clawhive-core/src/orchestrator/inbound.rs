@@ -3,17 +3,22 @@ use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
 use clawhive_memory::dirty_sources::DIRTY_KIND_SESSION;
+use clawhive_memory::usage::UsagePurpose;
 use clawhive_provider::{ContentBlock, LlmMessage, StreamChunk};
 use clawhive_schema::*;
 use futures_core::Stream;
 use tokio_util::sync::CancellationToken;
 
+use crate::config::FullAgentConfig;
 use crate::config_view::ConfigView;
 use crate::language_prefs::{
     apply_language_policy_prompt, detect_response_language, is_language_guard_exempt,
     log_language_guard,
 };
+use crate::router::UsageScope;
 use crate::session::SessionResetReason;
+use crate::speech::{resolve_reply_voice, wants_voice_reply};
+use crate::transcription::Transcripts;
 
 use super::attachment::{build_attachment_blocks, build_session_text, build_user_content};
 use super::episode::EpisodeTurnInput;
@@ -263,7 +268,8 @@ impl Orchestrator {
             .await;
         }
 
-        let session_text = build_session_text(&inbound.text, &inbound.attachments);
+        let transcripts = self.transcribe_audio(&inbound.attachments).await;
+        let session_text = build_session_text(&inbound.text, &inbound.attachments, &transcripts);
 
         let system_prompt = view
             .persona(agent_id)
@@ -367,7 +373,7 @@ impl Orchestrator {
         let mut messages = build_messages_from_history(&history_messages);
         {
             let preprocessed = self.runtime.preprocess_input(&inbound.text).await?;
-            let attachment_blocks = build_attachment_blocks(&inbound.attachments);
+            let attachment_blocks = build_attachment_blocks(&inbound.attachments, &transcripts);
 
             if attachment_blocks.is_empty() {
                 messages.push(LlmMessage::user(preprocessed));
@@ -440,7 +446,9 @@ impl Orchestrator {
                 must_use_web_search,
                 is_scheduled_task,
                 agent.model_policy.thinking_level,
+                inbound.trace_id,
                 cancel_token,
+                None,
             )
             .await?;
         if tool_meta.cancelled {
@@ -487,6 +495,13 @@ impl Orchestrator {
 
         outbound_attachments.extend(tool_attachments);
 
+        if let Some(voice) = self
+            .voice_reply(view.as_ref(), agent, &inbound, &reply_text)
+            .await
+        {
+            outbound_attachments.push(voice);
+        }
+
         if !outbound_attachments.is_empty() {
             tracing::info!(
                 agent_id = %agent_id,
@@ -592,6 +607,7 @@ impl Orchestrator {
             .bus
             .publish(BusMessage::ReplyReady {
                 outbound: outbound.clone(),
+                agent_id: Some(agent_id.to_string()),
             })
             .await;
 
@@ -776,10 +792,11 @@ impl Orchestrator {
             .resolve_target_language(&inbound, &history_messages);
         apply_language_policy_prompt(&mut system_prompt, target_language);
 
+        let transcripts = self.transcribe_audio(&inbound.attachments).await;
         let mut messages = build_messages_from_history(&history_messages);
         {
             let preprocessed = self.runtime.preprocess_input(&inbound.text).await?;
-            let attachment_blocks = build_attachment_blocks(&inbound.attachments);
+            let attachment_blocks = build_attachment_blocks(&inbound.attachments, &transcripts);
 
             if attachment_blocks.is_empty() {
                 messages.push(LlmMessage::user(preprocessed));
@@ -837,7 +854,9 @@ impl Orchestrator {
                 must_use_web_search,
                 false, // is_scheduled_task
                 agent.model_policy.thinking_level,
+                inbound.trace_id,
                 cancel_token,
+                None,
             )
             .await?;
 
@@ -846,7 +865,8 @@ impl Orchestrator {
             let abort_text = filter_no_reply(&abort_text);
 
             let workspace = self.workspace_state_for(agent_id);
-            let session_text = build_session_text(&inbound.text, &inbound.attachments);
+            let session_text =
+                build_session_text(&inbound.text, &inbound.attachments, &transcripts);
             let _ = workspace
                 .session_writer
                 .append_message(&session_result.session.session_id, "user", &session_text)
@@ -874,6 +894,8 @@ impl Orchestrator {
                     is_final: true,
                     input_tokens: resp.input_tokens,
                     output_tokens: resp.output_tokens,
+                    cache_read_tokens: resp.cache_read_tokens,
+                    cache_write_tokens: resp.cache_write_tokens,
                     stop_reason: resp.stop_reason.clone(),
                     content_blocks: resp.content.clone(),
                 })));
@@ -899,6 +921,16 @@ impl Orchestrator {
 
         let stream = view
             .router
+            .with_usage_scope(
+                UsageScope::new(agent_id, UsagePurpose::Turn)
+                    .with_session(&session.session_key.0)
+                    .with_source(
+                        &inbound.channel_type,
+                        &inbound.connector_id,
+                        &inbound.conversation_scope,
+                        &inbound.user_scope,
+                    ),
+            )
             .stream(
                 &agent.model_policy.primary,
                 &agent.model_policy.fallbacks,
@@ -906,6 +938,7 @@ impl Orchestrator {
                 final_messages,
                 2048,
                 agent.model_policy.thinking_level,
+                agent.model_policy.sampling.clone(),
             )
             .await?;
 
@@ -941,6 +974,7 @@ impl Orchestrator {
                     trace_id,
                     delta: chunk.delta.clone(),
                     is_final: chunk.is_final,
+                    agent_id: Some(agent_id_owned.clone()),
                 };
                 tokio::spawn(async move {
                     let _ = bus.publish(msg).await;
@@ -951,6 +985,28 @@ impl Orchestrator {
 
         Ok(Box::pin(mapped))
     }
+
+    async fn voice_reply(
+        &self,
+        view: &ConfigView,
+        agent: &FullAgentConfig,
+        inbound: &InboundMessage,
+        reply_text: &str,
+    ) -> Option<Attachment> {
+        let voice_replies = self.voice_replies.as_ref()?;
+        let policy = resolve_reply_voice(&view.routing, agent, inbound);
+        if !wants_voice_reply(policy, inbound) {
+            return None;
+        }
+        voice_replies.synthesize_reply(reply_text).await
+    }
+
+    async fn transcribe_audio(&self, attachments: &[Attachment]) -> Transcripts {
+        match &self.transcription {
+            Some(transcription) => transcription.transcribe_attachments(attachments).await,
+            None => Transcripts::new(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1026,6 +1082,81 @@ mod tests {
         assert_eq!(messages[1].content, "[Task stopped by user]");
     }
 
+    #[tokio::test]
+    async fn inbound_voice_note_is_transcribed_into_session() {
+        use base64::Engine as _;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/inference"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "text": "Move my dentist appointment to Friday."
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = Arc::new(SequenceProvider::new(vec![]));
+        let (mut orchestrator, tmp, memory) =
+            make_tool_loop_test_orchestrator(provider.clone(), Some(2)).await;
+        let config = crate::config::TranscriptionConfig {
+            provider: crate::config::TranscriptionBackend::WhisperServer,
+            base_url: Some(server.uri()),
+            ..Default::default()
+        };
+        orchestrator.transcription = crate::transcription::AudioTranscription::from_config(&config)
+            .unwrap()
+            .map(Arc::new);
+        let view = orchestrator.config_view();
+        let inbound = InboundMessage {
+            trace_id: uuid::Uuid::new_v4(),
+            channel_type: "telegram".into(),
+            connector_id: "tg_main".into(),
+            conversation_scope: "chat:voice".into(),
+            user_scope: "user:1".into(),
+            text: String::new(),
+            at: Utc::now(),
+            thread_id: None,
+            is_mention: false,
+            mention_target: None,
+            message_id: None,
+            attachments: vec![Attachment {
+                kind: AttachmentKind::Audio,
+                url: base64::engine::general_purpose::STANDARD.encode(b"OggS-voice"),
+                mime_type: Some("audio/ogg".into()),
+                file_name: Some("voice.ogg".into()),
+                size: None,
+            }],
+            message_source: None,
+        };
+        let session_key = SessionKey::from_inbound(&inbound);
+        let cancel_token = CancellationToken::new();
+        cancel_token.cancel();
+
+        orchestrator
+            .handle_with_view(view, inbound, "agent-a", cancel_token)
+            .await
+            .unwrap();
+        assert_eq!(provider.call_count(), 0);
+
+        let session = memory
+            .get_session(&session_key.0)
+            .await
+            .unwrap()
+            .expect("session record");
+        let messages = SessionReader::new(tmp.path())
+            .load_recent_messages(&session.session_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(messages[0].role, "user");
+        assert!(messages[0]
+            .content
+            .contains("Transcript:\nMove my dentist appointment to Friday."));
+        assert!(!messages[0].content.contains("binary attachment uploaded"));
+    }
+
     #[tokio::test]
     async fn compaction_does_not_write_persistent_memory_layers() {
         let tmp = tempfile::tempdir().unwrap();
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use chrono::{Datelike, Duration, Local, NaiveDate};
 use clawhive_memory::search_index::TimeRange;
+use clawhive_memory::MemoryScope;
 use clawhive_schema::SessionKey;
 
 use crate::config_view::ConfigView;
@@ -16,7 +17,7 @@ impl Orchestrator {
         &self,
         view: &ConfigView,
         agent_id: &str,
-        _session_key: &SessionKey,
+        session_key: &SessionKey,
         query: &str,
     ) -> Result<String> {
         let budget = view
@@ -24,10 +25,11 @@ impl Orchestrator {
             .and_then(|agent| agent.memory_policy.as_ref())
             .map(|policy| policy.max_injected_chars)
             .unwrap_or(6000);
+        let scope = MemoryScope::from_session_key(&session_key.0).unwrap_or_default();
 
         let fact_store = clawhive_memory::fact_store::FactStore::new(self.memory.db());
         let facts = fact_store
-            .get_injected_facts(agent_id)
+            .get_injected_facts_in_scope(agent_id, &scope)
             .await
             .unwrap_or_default();
 
@@ -42,6 +44,7 @@ impl Orchestrator {
                 max_results: 6,
                 min_score: 0.25,
                 time_range: detect_time_range_from_query(query),
+                scope,
             },
         )
         .await;
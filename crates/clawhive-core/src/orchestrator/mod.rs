@@ -1,5 +1,5 @@
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock, Weak};
 
 use arc_swap::ArcSwap;
 use clawhive_bus::BusPublisher;
@@ -18,6 +18,9 @@ use super::approval::ApprovalRegistry;
 use super::session::SessionManager;
 use super::skill::SkillRegistry;
 use super::skill_install_state::SkillInstallState;
+use super::speech::VoiceReplies;
+use super::subagent::{SubAgentRunner, DEFAULT_MAX_CONCURRENT, DEFAULT_MAX_DEPTH};
+use super::transcription::AudioTranscription;
 use super::workspace::Workspace;
 use super::workspace_manager::{AgentWorkspaceManager, AgentWorkspaceState};
 
@@ -39,7 +42,10 @@ use summary::{detect_empty_promise_structural, EmptyPromiseVerdict};
 
 mod skill_commands;
 
+mod delegation;
+
 mod tool_loop;
+mod tool_schedule;
 
 mod builder;
 pub use builder::OrchestratorBuilder;
@@ -67,6 +73,12 @@ pub struct Orchestrator {
     language_prefs: LanguagePrefs,
     pending_boundary_recoveries: Arc<tokio::sync::Mutex<HashSet<String>>>,
     compaction_locks: Arc<tokio::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+    subagents: Arc<SubAgentRunner>,
+    /// Set by [`Orchestrator::bind_shared`]; async delegations need an owned
+    /// handle to keep running after the turn that started them.
+    shared: OnceLock<Weak<Orchestrator>>,
+    transcription: Option<Arc<AudioTranscription>>,
+    voice_replies: Option<Arc<VoiceReplies>>,
 }
 
 impl Orchestrator {
@@ -85,6 +97,8 @@ impl Orchestrator {
         search_index: SearchIndex,
         workspace_root: std::path::PathBuf,
         project_root: Option<std::path::PathBuf>,
+        transcription: Option<Arc<AudioTranscription>>,
+        voice_replies: Option<Arc<VoiceReplies>>,
     ) -> Self {
         let router = Arc::new(config_view.router.clone());
         let search_config = search_index.config().clone();
@@ -130,6 +144,23 @@ impl Orchestrator {
         };
         let workspaces = AgentWorkspaceManager::new(agent_workspace_map, default_state);
 
+        let subagents = Arc::new(SubAgentRunner::new(
+            router.clone(),
+            config_view
+                .agents
+                .iter()
+                .map(|(id, agent)| (id.clone(), agent.as_ref().clone()))
+                .collect(),
+            config_view
+                .personas
+                .iter()
+                .map(|(id, persona)| (id.clone(), persona.as_ref().clone()))
+                .collect(),
+            DEFAULT_MAX_DEPTH,
+            Vec::new(),
+            DEFAULT_MAX_CONCURRENT,
+        ));
+
         let skills_root = workspace_root.join("skills");
         let skill_registry = ArcSwap::from_pointee(skill_registry);
         let config_view = ArcSwap::from_pointee(config_view);
@@ -155,6 +186,10 @@ impl Orchestrator {
             language_prefs: LanguagePrefs::new(),
             pending_boundary_recoveries: Arc::new(tokio::sync::Mutex::new(HashSet::new())),
             compaction_locks: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            subagents,
+            shared: OnceLock::new(),
+            transcription,
+            voice_replies,
         }
     }
 }
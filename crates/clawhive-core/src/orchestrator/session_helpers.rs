@@ -99,6 +99,7 @@ impl Orchestrator {
             router: view.router.clone(),
             tool_registry: view.tool_registry.clone(),
             embedding_provider: Arc::clone(&view.embedding_provider),
+            search_config: view.search_config.clone(),
         };
         self.config_view.store(Arc::new(new_view));
 
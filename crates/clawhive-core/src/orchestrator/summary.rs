@@ -5,17 +5,19 @@ use clawhive_memory::fact_store::FactStore;
 use clawhive_memory::memory_lineage::generate_canonical_id_with_key;
 use clawhive_memory::memory_lineage::MemoryLineageStore;
 use clawhive_memory::search_index::SearchIndex;
+use clawhive_memory::usage::UsagePurpose;
 use clawhive_memory::{MemoryStore, RecentExplicitMemoryWrite, SessionMessage};
 use clawhive_provider::{ContentBlock, LlmMessage, LlmResponse};
 
 use crate::config::FullAgentConfig;
 use crate::memory_document::MemoryDocument;
 use crate::memory_retrieval::is_matching_memory_content;
+use crate::memory_scope::MemoryAccess;
 use crate::memory_summary::{
     build_summary_prompt, group_daily_candidates, merge_daily_blocks, parse_candidates,
     retain_summary_candidates, SummaryClass,
 };
-use crate::router::LlmRouter;
+use crate::router::{LlmRouter, UsageScope};
 use crate::session::Session;
 
 use crate::config_view::ConfigView;
@@ -114,6 +116,13 @@ impl Orchestrator {
         if messages.is_empty() {
             return false;
         }
+        let Some(write_scope) =
+            MemoryAccess::resolve(agent.memory_write_scope(), &session.session_key.0).write
+        else {
+            tracing::debug!(source, agent_id, "Memory writes disabled; skipping summary");
+            return true;
+        };
+        let daily_store = file_store.partition(&write_scope);
         let reader = clawhive_memory::session::SessionReader::new(file_store.workspace_dir());
 
         // Use the last message's timestamp to determine the daily file date,
@@ -136,6 +145,10 @@ impl Orchestrator {
         let llm_messages = vec![LlmMessage::user(conversation)];
 
         match router
+            .with_usage_scope(
+                UsageScope::new(agent_id, UsagePurpose::Summary)
+                    .with_session(&session.session_key.0),
+            )
             .chat(
                 &agent.model_policy.primary,
                 &agent.model_policy.fallbacks,
@@ -160,7 +173,10 @@ impl Orchestrator {
                 let retained = retain_summary_candidates(candidates);
                 let fact_store = FactStore::new(memory.db());
                 let lineage_store = MemoryLineageStore::new(memory.db());
-                let mut active_facts = match fact_store.get_active_facts(agent_id).await {
+                let mut active_facts = match fact_store
+                    .get_active_facts_in_scope(agent_id, &write_scope)
+                    .await
+                {
                     Ok(facts) => facts,
                     Err(error) => {
                         tracing::warn!(source, %error, "Failed to load active facts for summary precheck");
@@ -229,8 +245,9 @@ impl Orchestrator {
                     // apply_affect_salience_boost; pass raw default here.
                     let salience = 50_u8;
                     let fact = clawhive_memory::fact_store::Fact {
-                        id: clawhive_memory::fact_store::generate_fact_id(
+                        id: clawhive_memory::fact_store::generate_scoped_fact_id(
                             agent_id,
+                            &write_scope,
                             &candidate.content,
                         ),
                         agent_id: agent_id.to_string(),
@@ -248,16 +265,26 @@ impl Orchestrator {
                         superseded_by: None,
                         salience,
                         supersede_reason: None,
+                        pinned: false,
                         affect,
                         affect_intensity,
+                        user_scope: write_scope.user_scope.clone(),
+                        conversation_scope: write_scope.conversation_scope.clone(),
                         created_at: now.clone(),
                         updated_at: now,
                     };
+                    // Only supersede within the partition being written, so a
+                    // user's flush never retires a shared or another user's fact.
+                    let same_partition = active_facts
+                        .iter()
+                        .filter(|existing| existing.scope() == write_scope)
+                        .cloned()
+                        .collect::<Vec<_>>();
                     let conflict = match find_boundary_flush_conflict(
                         embedding_provider,
                         &fact.content,
                         &fact.fact_type,
-                        &active_facts,
+                        &same_partition,
                     )
                     .await
                     {
@@ -394,7 +421,7 @@ impl Orchestrator {
                 }
                 let grouped = group_daily_candidates(&retained_for_daily);
                 let grouped_for_write = grouped.clone();
-                let rendered = match file_store
+                let rendered = match daily_store
                     .update_daily(today, move |existing| {
                         Ok(merge_daily_blocks(
                             today,
@@ -418,7 +445,7 @@ impl Orchestrator {
                     return true;
                 };
                 {
-                    let relative_path = format!("memory/{}.md", today.format("%Y-%m-%d"));
+                    let relative_path = daily_store.relative_daily_path(today);
                     let dirty = DirtySourceStore::new(memory.db());
                     let mut daily_reindexed = false;
                     let session_path_prefix = format!("sessions/{}#", session.session_id);
@@ -811,6 +838,8 @@ pub(super) fn synthesize_cancelled_response(tool_summaries: &[(String, String)])
         content: vec![ContentBlock::Text { text }],
         input_tokens: None,
         output_tokens: None,
+        cache_read_tokens: None,
+        cache_write_tokens: None,
         stop_reason: Some("cancelled".into()),
     }
 }
@@ -51,6 +51,8 @@ impl LlmProvider for CompactionOnlyProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -114,8 +116,10 @@ pub(super) fn agent_with_memory_policy(
             thinking_level: None,
             context_window: None,
             compaction_model: None,
+            sampling: Default::default(),
         },
         tool_policy: None,
+        mcp_servers: vec![],
         memory_policy,
         sub_agent: None,
         heartbeat: None,
@@ -126,6 +130,7 @@ pub(super) fn agent_with_memory_policy(
         turn_timeout_secs: None,
         typing_ttl_secs: None,
         progress_delay_secs: None,
+        reply_voice: None,
     }
 }
 
@@ -407,6 +412,8 @@ pub(super) fn llm_text_response(text: &str, stop_reason: &str) -> LlmResponse {
         }],
         input_tokens: None,
         output_tokens: None,
+        cache_read_tokens: None,
+        cache_write_tokens: None,
         stop_reason: Some(stop_reason.to_string()),
     }
 }
@@ -421,6 +428,8 @@ pub(super) fn llm_tool_use_response(id: &str, name: &str, input: serde_json::Val
         }],
         input_tokens: None,
         output_tokens: None,
+        cache_read_tokens: None,
+        cache_write_tokens: None,
         stop_reason: Some("tool_use".to_string()),
     }
 }
@@ -1,20 +1,23 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use clawhive_provider::{ContentBlock, LlmMessage, LlmRequest};
+use clawhive_memory::usage::UsagePurpose;
+use clawhive_provider::{ContentBlock, LlmMessage, LlmRequest, PromptCache};
 use clawhive_schema::*;
 use tokio_util::sync::CancellationToken;
 
 use crate::access_gate::{
     AccessGate, AccessLevel, AccessResult, GrantAccessTool, ListAccessTool, RevokeAccessTool,
 };
-use crate::config::{ExecSecurityConfig, SandboxPolicyConfig, SecurityMode};
+use crate::config::{ExecSecurityConfig, MemoryWriteScope, SandboxPolicyConfig, SecurityMode};
 use crate::config_view::ConfigView;
 use crate::file_tools::{EditFileTool, ReadFileTool, WriteFileTool};
 use crate::memory_tools::{
     MemoryForgetTool, MemoryGetTool, MemorySearchTool, MemorySupersedeToolDef, MemoryWriteTool,
 };
+use crate::router::UsageScope;
 use crate::shell_tool::ExecuteCommandTool;
+use crate::subagent::DelegatedRun;
 use crate::tool::{ToolContext, ToolExecutor};
 
 use super::memory_context::truncate_tool_result_preview;
@@ -27,6 +30,7 @@ use super::summary::{
     detect_empty_promise_by_llm, detect_empty_promise_structural, synthesize_cancelled_response,
     EmptyPromiseVerdict,
 };
+use super::tool_schedule::{run_scheduled, ToolCallPlan, ToolSchedule};
 use super::Orchestrator;
 
 #[derive(Debug, Clone, Default)]
@@ -34,6 +38,8 @@ pub(super) struct ToolLoopMeta {
     pub(super) successful_tool_calls: usize,
     pub(super) final_stop_reason: Option<String>,
     pub(super) cancelled: bool,
+    pub(super) input_tokens: u64,
+    pub(super) output_tokens: u64,
 }
 
 impl Orchestrator {
@@ -57,6 +63,23 @@ impl Orchestrator {
         )
     }
 
+    /// Run `memory_search` or `memory_get` for `agent_id` outside of a turn,
+    /// e.g. on behalf of an external MCP client.
+    pub async fn execute_memory_read_tool(
+        &self,
+        agent_id: &str,
+        name: &str,
+        input: serde_json::Value,
+    ) -> Result<crate::tool::ToolOutput> {
+        if !matches!(name, "memory_search" | "memory_get") {
+            anyhow::bail!("not a memory read tool: {name}");
+        }
+        let view = self.config_view();
+        let ctx = ToolContext::builtin().with_agent_id(agent_id);
+        self.execute_tool_for_agent(view.as_ref(), agent_id, name, input, &ctx)
+            .await
+    }
+
     pub(super) async fn execute_tool_for_agent(
         &self,
         view: &ConfigView,
@@ -100,6 +123,11 @@ impl Orchestrator {
             }
         }
 
+        let memory_write_scope = view
+            .agent(agent_id)
+            .map(|agent| agent.memory_write_scope())
+            .unwrap_or(MemoryWriteScope::Agent);
+
         match name {
             "memory_search" => {
                 let fact_store = clawhive_memory::fact_store::FactStore::new(self.memory.db());
@@ -125,18 +153,21 @@ impl Orchestrator {
                     Arc::clone(&self.memory),
                     agent_id.to_string(),
                 )
+                .with_write_scope(memory_write_scope)
                 .execute(input, ctx)
                 .await
             }
             "memory_forget" => {
                 let fact_store = clawhive_memory::fact_store::FactStore::new(self.memory.db());
                 MemoryForgetTool::new(fact_store, agent_id.to_string())
+                    .with_write_scope(memory_write_scope)
                     .execute(input, ctx)
                     .await
             }
             "memory_supersede" => {
                 let fact_store = clawhive_memory::fact_store::FactStore::new(self.memory.db());
                 MemorySupersedeToolDef::new(fact_store, agent_id.to_string())
+                    .with_write_scope(memory_write_scope)
                     .execute(input, ctx)
                     .await
             }
@@ -146,7 +177,11 @@ impl Orchestrator {
             "exec" | "execute_command" => {
                 let summarizer = view.agent(agent_id).map(|agent| {
                     crate::shell_tool::ApprovalSummarizer::new(
-                        view.router.clone(),
+                        view.router.with_usage_scope(UsageScope {
+                            user_scope: ctx.source_user_scope().map(str::to_owned),
+                            ..UsageScope::new(agent_id, UsagePurpose::Tool)
+                                .with_session(ctx.session_key())
+                        }),
                         agent.model_policy.primary.clone(),
                         agent.model_policy.fallbacks.clone(),
                     )
@@ -165,6 +200,9 @@ impl Orchestrator {
                 .execute(input, ctx)
                 .await
             }
+            "delegate_task" => self.delegate_task(view, input, ctx).await,
+            "delegate_status" => self.delegate_status(input, ctx).await,
+            "delegate_collect" => self.delegate_collect(input, ctx).await,
             "grant_access" => self.approve_then_grant(agent_id, &gate, input, ctx).await,
             "list_access" => ListAccessTool::new(gate).execute(input, ctx).await,
             "revoke_access" => RevokeAccessTool::new(gate).execute(input, ctx).await,
@@ -288,7 +326,9 @@ impl Orchestrator {
         must_use_web_search: bool,
         is_scheduled_task: bool,
         thinking_level: Option<clawhive_provider::ThinkingLevel>,
+        trace_id: uuid::Uuid,
         cancel_token: CancellationToken,
+        delegation: Option<&DelegatedRun>,
     ) -> Result<(
         clawhive_provider::LlmResponse,
         Vec<LlmMessage>,
@@ -299,17 +339,32 @@ impl Orchestrator {
         let tool_defs: Vec<_> = match allowed_tools {
             Some(allow_list) => view
                 .tool_registry
-                .tool_defs()
+                .tool_defs_for_agent(agent_id)
                 .into_iter()
                 .filter(|t| allow_list.iter().any(|a| t.name.starts_with(a)))
                 .collect(),
-            None => view.tool_registry.tool_defs(),
+            None => view.tool_registry.tool_defs_for_agent(agent_id),
         };
         let max_iterations = view
             .agents
             .get(agent_id)
             .and_then(|a| a.max_iterations)
             .unwrap_or(50) as usize;
+        let sampling = view
+            .agents
+            .get(agent_id)
+            .map(|a| a.model_policy.sampling.clone())
+            .unwrap_or_default();
+        let purpose = if delegation.is_some() {
+            UsagePurpose::Subagent
+        } else {
+            UsagePurpose::Turn
+        };
+        let mut usage_scope = UsageScope::new(agent_id, purpose).with_session(session_key);
+        if let Some((ch, co, cv, us)) = &source_info {
+            usage_scope = usage_scope.with_source(ch, co, cv, us);
+        }
+        let router = view.router.with_usage_scope(usage_scope.clone());
         let mut web_search_reminder_injected = false;
         let mut web_search_called = false;
         let loop_started = std::time::Instant::now();
@@ -317,6 +372,8 @@ impl Orchestrator {
         let mut empty_promise_retries: u32 = 0;
         let mut total_tool_calls: usize = 0;
         let mut successful_tool_calls_total: usize = 0;
+        let mut input_tokens: u64 = 0;
+        let mut output_tokens: u64 = 0;
         let mut tool_summaries: Vec<(String, String)> = Vec::new();
         let mut last_intermediate_text = String::new();
         let attachment_collector: Arc<tokio::sync::Mutex<Vec<Attachment>>> =
@@ -341,6 +398,8 @@ impl Orchestrator {
                         successful_tool_calls: successful_tool_calls_total,
                         final_stop_reason: resp.stop_reason.clone(),
                         cancelled: true,
+                        input_tokens,
+                        output_tokens,
                     },
                 ));
             }
@@ -386,7 +445,12 @@ impl Orchestrator {
             let messages_before_compaction = messages.clone();
             let result = tokio::time::timeout(
                 std::time::Duration::from_secs(60),
-                ctx_mgr.ensure_within_limits(primary, messages),
+                ctx_mgr
+                    .with_usage_scope(UsageScope {
+                        purpose: UsagePurpose::Compaction,
+                        ..usage_scope.clone()
+                    })
+                    .ensure_within_limits(primary, messages),
             )
             .await;
 
@@ -445,6 +509,8 @@ impl Orchestrator {
                         successful_tool_calls: successful_tool_calls_total,
                         final_stop_reason: resp.stop_reason.clone(),
                         cancelled: true,
+                        input_tokens,
+                        output_tokens,
                     },
                 ));
             }
@@ -456,10 +522,18 @@ impl Orchestrator {
                 max_tokens,
                 tools: tool_defs.clone(),
                 thinking_level,
+                sampling: sampling.clone(),
+                tool_choice: None,
+                response_format: None,
+                // Persona prompt, tool defs and prior rounds are identical on
+                // the next iteration and the next turn of this session.
+                cache: PromptCache::stable_prefix(&messages),
             };
 
             let llm_started = std::time::Instant::now();
-            let resp = view.router.chat_with_tools(primary, fallbacks, req).await?;
+            let resp = router.chat_with_tools(primary, fallbacks, req).await?;
+            input_tokens += u64::from(resp.input_tokens.unwrap_or(0));
+            output_tokens += u64::from(resp.output_tokens.unwrap_or(0));
             let llm_round_ms = llm_started.elapsed().as_millis() as u64;
 
             if is_slow_latency_ms(llm_round_ms, SLOW_LLM_ROUND_WARN_MS) {
@@ -480,6 +554,8 @@ impl Orchestrator {
                 stop_reason = ?resp.stop_reason,
                 input_tokens = ?resp.input_tokens,
                 output_tokens = ?resp.output_tokens,
+                cache_read_tokens = ?resp.cache_read_tokens,
+                cache_write_tokens = ?resp.cache_write_tokens,
                 "tool_use_loop: LLM response"
             );
 
@@ -654,13 +730,8 @@ impl Orchestrator {
                     let is_empty_promise = match verdict {
                         EmptyPromiseVerdict::Structural => true,
                         EmptyPromiseVerdict::Inconclusive => {
-                            detect_empty_promise_by_llm(
-                                &view.router,
-                                primary,
-                                fallbacks,
-                                &resp.text,
-                            )
-                            .await
+                            detect_empty_promise_by_llm(&router, primary, fallbacks, &resp.text)
+                                .await
                         }
                         EmptyPromiseVerdict::No => false,
                     };
@@ -718,6 +789,8 @@ impl Orchestrator {
                         successful_tool_calls: successful_tool_calls_total,
                         final_stop_reason: final_resp.stop_reason,
                         cancelled: false,
+                        input_tokens,
+                        output_tokens,
                     },
                 ));
             }
@@ -764,14 +837,21 @@ impl Orchestrator {
             .with_attachment_collector(attachment_collector.clone());
             let ctx = ctx
                 .with_skill_registry(self.active_skill_registry())
-                .with_agent_id(agent_id);
+                .with_agent_id(agent_id)
+                .with_session_key(session_key);
             let ctx = if let Some((ref ch, ref co, ref cv, ref us)) = source_info {
                 ctx.with_source(ch.clone(), co.clone(), cv.clone())
                     .with_source_user_scope(us.clone())
             } else {
                 ctx
             };
-            let ctx = ctx.with_scheduled_task(is_scheduled_task);
+            let ctx = ctx
+                .with_scheduled_task(is_scheduled_task)
+                .with_turn(trace_id, cancel_token.clone());
+            let ctx = match delegation {
+                Some(run) => ctx.with_delegation(run.clone()),
+                None => ctx,
+            };
 
             if cancel_token.is_cancelled() {
                 tracing::info!(
@@ -789,14 +869,23 @@ impl Orchestrator {
                         successful_tool_calls: successful_tool_calls_total,
                         final_stop_reason: resp.stop_reason.clone(),
                         cancelled: true,
+                        input_tokens,
+                        output_tokens,
                     },
                 ));
             }
 
+            let plans: Vec<ToolCallPlan> = tool_uses
+                .iter()
+                .map(|(_, name, input)| ToolCallPlan::for_call(&view.tool_registry, name, input))
+                .collect();
+            let schedule = ToolSchedule::new(&plans);
             let tool_futures: Vec<_> = tool_uses
                 .into_iter()
-                .map(|(id, name, input)| {
+                .zip(plans.iter())
+                .map(|((id, name, input), plan)| {
                     let ctx = ctx.clone();
+                    let schedule = &schedule;
                     let agent_id = agent_id.to_string();
                     let tool_name = name.clone();
                     async move {
@@ -809,13 +898,32 @@ impl Orchestrator {
                             "tool_use_loop: tool input"
                         );
                         let input_bytes = input_str.len();
+                        if let Some(run) = delegation {
+                            let _ = self
+                                .bus
+                                .publish(BusMessage::ToolCallStarted {
+                                    trace_id: run.trace_id,
+                                    tool_name: tool_name.clone(),
+                                    arguments: input_str.clone(),
+                                    parent_run_id: Some(run.parent_run_id),
+                                })
+                                .await;
+                        }
                         let tool_started = std::time::Instant::now();
-                        match self
-                            .execute_tool_for_agent(view, &agent_id, &name, input, &ctx)
-                            .await
+                        let result = match run_scheduled(
+                            schedule,
+                            plan,
+                            self.execute_tool_for_agent(view, &agent_id, &name, input, &ctx),
+                        )
+                        .await
                         {
                             Ok(output) => {
                                 let duration_ms = tool_started.elapsed().as_millis() as u64;
+                                clawhive_metrics::record_tool_call(
+                                    &tool_name,
+                                    !output.is_error,
+                                    tool_started.elapsed(),
+                                );
                                 let output_preview_end = output.content.floor_char_boundary(200);
                                 tracing::info!(
                                     agent_id = %agent_id,
@@ -841,6 +949,11 @@ impl Orchestrator {
                             }
                             Err(e) => {
                                 let duration_ms = tool_started.elapsed().as_millis() as u64;
+                                clawhive_metrics::record_tool_call(
+                                    &tool_name,
+                                    false,
+                                    tool_started.elapsed(),
+                                );
                                 tracing::warn!(
                                     agent_id = %agent_id,
                                     tool_name = %tool_name,
@@ -855,7 +968,22 @@ impl Orchestrator {
                                     is_error: true,
                                 }
                             }
+                        };
+                        if let (Some(run), ContentBlock::ToolResult { content, .. }) =
+                            (delegation, &result)
+                        {
+                            let _ = self
+                                .bus
+                                .publish(BusMessage::ToolCallCompleted {
+                                    trace_id: run.trace_id,
+                                    tool_name: tool_name.clone(),
+                                    output: content.clone(),
+                                    duration_ms: tool_started.elapsed().as_millis() as u64,
+                                    parent_run_id: Some(run.parent_run_id),
+                                })
+                                .await;
                         }
+                        result
                     }
                 })
                 .collect();
@@ -921,6 +1049,8 @@ impl Orchestrator {
                         successful_tool_calls: successful_tool_calls_total,
                         final_stop_reason: resp.stop_reason.clone(),
                         cancelled: true,
+                        input_tokens,
+                        output_tokens,
                     },
                 ));
             }
@@ -964,11 +1094,16 @@ impl Orchestrator {
             max_tokens,
             tools: vec![],
             thinking_level,
+            sampling: sampling.clone(),
+            tool_choice: None,
+            response_format: None,
+            cache: PromptCache::stable_prefix(&messages),
         };
-        let mut resp = view
-            .router
+        let mut resp = router
             .chat_with_tools(primary, fallbacks, final_req)
             .await?;
+        input_tokens += u64::from(resp.input_tokens.unwrap_or(0));
+        output_tokens += u64::from(resp.output_tokens.unwrap_or(0));
 
         // Fallback: if the LLM still returned empty, extract the last successful
         // tool result so the user sees *something* useful.
@@ -1001,6 +1136,8 @@ impl Orchestrator {
                 successful_tool_calls: successful_tool_calls_total,
                 final_stop_reason: resp.stop_reason.clone(),
                 cancelled: false,
+                input_tokens,
+                output_tokens,
             },
         ))
     }
@@ -1053,7 +1190,9 @@ mod tests {
                 false,
                 false,
                 None,
+                uuid::Uuid::new_v4(),
                 cancel_token,
+                None,
             )
             .await
             .unwrap();
@@ -1106,7 +1245,9 @@ mod tests {
                 false,
                 false,
                 None,
+                uuid::Uuid::new_v4(),
                 cancel_token.clone(),
+                None,
             ),
             cancel_after_first_llm,
         );
@@ -1149,7 +1290,9 @@ mod tests {
                 false,
                 false,
                 None,
+                uuid::Uuid::new_v4(),
                 CancellationToken::new(),
+                None,
             )
             .await
             .unwrap();
@@ -1189,7 +1332,9 @@ mod tests {
                 false,
                 false,
                 None,
+                uuid::Uuid::new_v4(),
                 cancel_token,
+                None,
             )
             .await
             .unwrap();
@@ -1199,6 +1344,145 @@ mod tests {
         assert!(meta.cancelled);
     }
 
+    #[tokio::test]
+    async fn delegate_task_runs_sub_agent_through_tool_loop() {
+        let provider = Arc::new(SequenceProvider::new(vec![
+            llm_tool_use_response(
+                "tool-1",
+                "delegate_task",
+                json!({"target_agent_id": "agent-a", "task": "Summarize sample.txt"}),
+            ),
+            llm_tool_use_response("tool-2", "read_file", json!({"path": "sample.txt"})),
+            llm_text_response("sample.txt holds two lines of notes.", "end_turn"),
+            llm_text_response("The sub-agent summarized the file.", "end_turn"),
+        ]));
+        let (orchestrator, tmp, _memory) =
+            make_tool_loop_test_orchestrator(provider.clone(), Some(4)).await;
+        std::fs::write(tmp.path().join("sample.txt"), "first\nsecond").unwrap();
+        let view = orchestrator.config_view();
+
+        let (resp, messages, _attachments, meta) = orchestrator
+            .tool_use_loop(
+                view.as_ref(),
+                "agent-a",
+                "session-delegation",
+                "test/model",
+                &[],
+                None,
+                vec![LlmMessage::user("delegate the summary")],
+                512,
+                None,
+                None,
+                SecurityMode::default(),
+                vec![],
+                None,
+                false,
+                false,
+                None,
+                uuid::Uuid::new_v4(),
+                CancellationToken::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(provider.call_count(), 4);
+        assert_eq!(resp.text, "The sub-agent summarized the file.");
+        assert_eq!(meta.successful_tool_calls, 1);
+
+        let result = messages
+            .iter()
+            .flat_map(|m| m.content.iter())
+            .find_map(|block| match block {
+                clawhive_provider::ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                } if tool_use_id == "tool-1" => Some((content.clone(), *is_error)),
+                _ => None,
+            })
+            .expect("delegate_task result");
+        assert!(!result.1);
+        let report: serde_json::Value = serde_json::from_str(&result.0).unwrap();
+        assert_eq!(report["status"], "completed");
+        assert_eq!(report["tool_calls"], 1);
+        assert_eq!(report["output"], "sample.txt holds two lines of notes.");
+    }
+
+    #[tokio::test]
+    async fn async_delegations_fan_out_and_collect() {
+        let provider = Arc::new(SequenceProvider::new(vec![
+            llm_text_response("Found the answer.", "end_turn"),
+            llm_text_response("Found the answer.", "end_turn"),
+        ]));
+        let (orchestrator, _tmp, _memory) =
+            make_tool_loop_test_orchestrator(provider.clone(), Some(2)).await;
+        let orchestrator = Arc::new(orchestrator);
+        orchestrator.bind_shared();
+        let view = orchestrator.config_view();
+        let ctx = ToolContext::builtin();
+
+        let mut run_ids = Vec::new();
+        for task in ["look into topic one", "look into topic two"] {
+            let started = orchestrator
+                .execute_tool_for_agent(
+                    view.as_ref(),
+                    "agent-a",
+                    "delegate_task",
+                    json!({"target_agent_id": "agent-a", "task": task, "mode": "async"}),
+                    &ctx,
+                )
+                .await
+                .unwrap();
+            assert!(!started.is_error, "{}", started.content);
+            let started: serde_json::Value = serde_json::from_str(&started.content).unwrap();
+            run_ids.push(started["run_id"].as_str().unwrap().to_string());
+        }
+
+        let collected = orchestrator
+            .execute_tool_for_agent(
+                view.as_ref(),
+                "agent-a",
+                "delegate_collect",
+                json!({"run_ids": run_ids, "timeout_seconds": 5}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(!collected.is_error, "{}", collected.content);
+        let collected: serde_json::Value = serde_json::from_str(&collected.content).unwrap();
+        let results = collected["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r["status"] == "completed"));
+        assert_eq!(
+            collected["merged"],
+            "Found the answer.\n\n---\n\nFound the answer."
+        );
+        assert!(collected["pending"].as_array().unwrap().is_empty());
+        assert_eq!(provider.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn async_delegation_needs_a_shared_orchestrator() {
+        let provider = Arc::new(SequenceProvider::new(vec![]));
+        let (orchestrator, _tmp, _memory) =
+            make_tool_loop_test_orchestrator(provider, Some(1)).await;
+        let view = orchestrator.config_view();
+
+        let output = orchestrator
+            .execute_tool_for_agent(
+                view.as_ref(),
+                "agent-a",
+                "delegate_task",
+                json!({"target_agent_id": "agent-a", "task": "later", "mode": "async"}),
+                &ToolContext::builtin(),
+            )
+            .await
+            .unwrap();
+        assert!(output.is_error);
+        assert!(output.content.contains("mode \"sync\""));
+    }
+
     #[tokio::test]
     async fn execute_tool_for_agent_scopes_memory_write_to_current_agent() {
         let (orchestrator, _tmp, memory) =
@@ -78,13 +78,23 @@ pub fn build_tool_registry(
         Arc::new(router.clone()),
         agents_map,
         personas,
-        3,
+        crate::subagent::DEFAULT_MAX_DEPTH,
         vec![],
-        5,
+        crate::subagent::DEFAULT_MAX_CONCURRENT,
     ));
+    // Registered for their definitions; orchestrated turns dispatch the
+    // delegate_* tools to the orchestrator's own runner in
+    // execute_tool_for_agent().
     registry.register(Box::new(crate::subagent_tool::SubAgentTool::new(
+        sub_agent_runner.clone(),
+        crate::subagent::DEFAULT_DELEGATE_TIMEOUT_SECS,
+    )));
+    registry.register(Box::new(crate::subagent_tool::DelegateStatusTool::new(
+        sub_agent_runner.clone(),
+    )));
+    registry.register(Box::new(crate::subagent_tool::DelegateCollectTool::new(
         sub_agent_runner,
-        30,
+        crate::subagent::DEFAULT_DELEGATE_TIMEOUT_SECS,
     )));
     // Default access gate for the global tool registry
     let default_access_gate = Arc::new(AccessGate::new(
@@ -0,0 +1,275 @@
+//! Scheduling for the tool calls of one loop iteration.
+//!
+//! Read-only calls overlap freely. Mutating calls take their resource keys
+//! exclusively, so two edits of one file run in the order the model asked
+//! for them, and a mutating call without keys (e.g. `execute_command`) runs
+//! alone. Per-tool concurrency caps and timeouts come from `ToolMetadata`.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, OwnedSemaphorePermit};
+use tokio::sync::{RwLock, Semaphore};
+
+use crate::tool::{ToolMetadata, ToolOutput, ToolRegistry};
+
+/// What the scheduler needs to know about one requested call.
+#[derive(Debug, Clone)]
+pub(super) struct ToolCallPlan {
+    pub(super) tool: String,
+    pub(super) metadata: ToolMetadata,
+    pub(super) resources: Vec<String>,
+}
+
+impl ToolCallPlan {
+    pub(super) fn for_call(registry: &ToolRegistry, name: &str, input: &serde_json::Value) -> Self {
+        let tool = canonical_tool_name(name);
+        let mut resources = registry.resource_keys(tool, input);
+        resources.sort();
+        resources.dedup();
+        Self {
+            tool: tool.to_string(),
+            metadata: registry.metadata(tool),
+            resources,
+        }
+    }
+
+    fn exclusive(&self) -> bool {
+        !self.metadata.read_only && self.resources.is_empty()
+    }
+}
+
+/// The orchestrator dispatches a few short aliases to the same builtins.
+fn canonical_tool_name(name: &str) -> &str {
+    match name {
+        "read" => "read_file",
+        "write" => "write_file",
+        "edit" => "edit_file",
+        "exec" => "execute_command",
+        other => other,
+    }
+}
+
+/// Held for the duration of one call; dropping it releases the call's slots.
+pub(super) struct ToolSlot {
+    _shared: Vec<OwnedRwLockReadGuard<()>>,
+    _exclusive: Vec<OwnedRwLockWriteGuard<()>>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+pub(super) struct ToolSchedule {
+    global: Arc<RwLock<()>>,
+    resources: HashMap<String, Arc<RwLock<()>>>,
+    limits: HashMap<String, Arc<Semaphore>>,
+}
+
+impl ToolSchedule {
+    pub(super) fn new(plans: &[ToolCallPlan]) -> Self {
+        let mut resources = HashMap::new();
+        let mut limits = HashMap::new();
+        for plan in plans {
+            for key in &plan.resources {
+                resources
+                    .entry(key.clone())
+                    .or_insert_with(|| Arc::new(RwLock::new(())));
+            }
+            if let Some(limit) = plan.metadata.max_concurrency {
+                limits
+                    .entry(plan.tool.clone())
+                    .or_insert_with(|| Arc::new(Semaphore::new(limit)));
+            }
+        }
+        Self {
+            global: Arc::new(RwLock::new(())),
+            resources,
+            limits,
+        }
+    }
+
+    /// Wait until `plan` may run. Locks are always taken in the same order
+    /// (global, then sorted resource keys, then the tool's semaphore), and
+    /// tokio's locks are fair, so calls queued on the same resource keep the
+    /// order in which they were first polled.
+    pub(super) async fn acquire(&self, plan: &ToolCallPlan) -> ToolSlot {
+        let mut shared = Vec::new();
+        let mut exclusive = Vec::new();
+        if plan.exclusive() {
+            exclusive.push(self.global.clone().write_owned().await);
+        } else {
+            shared.push(self.global.clone().read_owned().await);
+        }
+        for key in &plan.resources {
+            let lock = self.resources[key].clone();
+            if plan.metadata.read_only {
+                shared.push(lock.read_owned().await);
+            } else {
+                exclusive.push(lock.write_owned().await);
+            }
+        }
+        let permit = match self.limits.get(&plan.tool) {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        ToolSlot {
+            _shared: shared,
+            _exclusive: exclusive,
+            _permit: permit,
+        }
+    }
+}
+
+/// Run one tool call under its scheduling slot, turning an elapsed timeout
+/// into an error result the model can see.
+pub(super) async fn run_scheduled<F>(
+    schedule: &ToolSchedule,
+    plan: &ToolCallPlan,
+    call: F,
+) -> Result<ToolOutput>
+where
+    F: Future<Output = Result<ToolOutput>>,
+{
+    let _slot = schedule.acquire(plan).await;
+    let Some(limit) = plan.metadata.timeout else {
+        return call.await;
+    };
+    match tokio::time::timeout(limit, call).await {
+        Ok(result) => result,
+        Err(_) => Ok(ToolOutput {
+            content: format!(
+                "Tool '{}' timed out after {}s",
+                plan.tool,
+                limit.as_secs_f64()
+            ),
+            is_error: true,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use super::*;
+
+    fn plan(tool: &str, metadata: ToolMetadata, resources: &[&str]) -> ToolCallPlan {
+        ToolCallPlan {
+            tool: tool.into(),
+            metadata,
+            resources: resources.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    /// Run every plan concurrently; each call logs its start and end around a
+    /// short sleep so overlaps show up in the log.
+    async fn run_all(plans: Vec<ToolCallPlan>) -> Vec<String> {
+        let schedule = ToolSchedule::new(&plans);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let calls = plans.iter().enumerate().map(|(i, plan)| {
+            let log = log.clone();
+            run_scheduled(&schedule, plan, async move {
+                log.lock().unwrap().push(format!("start {i}"));
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                log.lock().unwrap().push(format!("end {i}"));
+                Ok(ToolOutput {
+                    content: String::new(),
+                    is_error: false,
+                })
+            })
+        });
+        futures::future::join_all(calls).await;
+        let log = log.lock().unwrap().clone();
+        log
+    }
+
+    #[tokio::test]
+    async fn read_only_calls_overlap() {
+        let log = run_all(vec![
+            plan("read_file", ToolMetadata::read_only(), &["file:a"]),
+            plan("read_file", ToolMetadata::read_only(), &["file:a"]),
+            plan("memory_search", ToolMetadata::read_only(), &[]),
+        ])
+        .await;
+        assert_eq!(&log[..3], ["start 0", "start 1", "start 2"]);
+    }
+
+    #[tokio::test]
+    async fn mutating_calls_on_one_resource_run_in_order() {
+        let log = run_all(vec![
+            plan("edit_file", ToolMetadata::mutating(), &["file:a"]),
+            plan("edit_file", ToolMetadata::mutating(), &["file:a"]),
+            plan("write_file", ToolMetadata::mutating(), &["file:b"]),
+        ])
+        .await;
+        assert_eq!(&log[..2], ["start 0", "start 2"]);
+        let end_0 = log.iter().position(|l| l == "end 0").unwrap();
+        let start_1 = log.iter().position(|l| l == "start 1").unwrap();
+        assert!(end_0 < start_1);
+    }
+
+    #[tokio::test]
+    async fn keyless_mutating_call_runs_alone() {
+        let log = run_all(vec![
+            plan("read_file", ToolMetadata::read_only(), &["file:a"]),
+            plan("execute_command", ToolMetadata::mutating(), &[]),
+            plan("read_file", ToolMetadata::read_only(), &["file:b"]),
+        ])
+        .await;
+        assert_eq!(
+            log,
+            ["start 0", "end 0", "start 1", "end 1", "start 2", "end 2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn max_concurrency_caps_parallel_calls() {
+        let capped = ToolMetadata::read_only().with_max_concurrency(1);
+        let log = run_all(vec![
+            plan("web_fetch", capped.clone(), &[]),
+            plan("web_fetch", capped, &[]),
+        ])
+        .await;
+        assert_eq!(log, ["start 0", "end 0", "start 1", "end 1"]);
+    }
+
+    #[tokio::test]
+    async fn timeout_is_reported_as_tool_error() {
+        let slow = plan(
+            "web_fetch",
+            ToolMetadata::read_only().with_timeout(Duration::from_millis(10)),
+            &[],
+        );
+        let schedule = ToolSchedule::new(std::slice::from_ref(&slow));
+        let output = run_scheduled(&schedule, &slow, async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(ToolOutput {
+                content: "late".into(),
+                is_error: false,
+            })
+        })
+        .await
+        .unwrap();
+        assert!(output.is_error);
+        assert!(output.content.contains("web_fetch"));
+        assert!(output.content.contains("timed out"));
+    }
+
+    #[test]
+    fn plans_resolve_aliases_and_sort_keys() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(crate::file_tools::EditFileTool::new(
+            std::path::PathBuf::from("/tmp"),
+            Arc::new(crate::access_gate::AccessGate::new(
+                std::path::PathBuf::from("/tmp"),
+                std::path::PathBuf::from("/tmp/grants.json"),
+            )),
+        )));
+        let plan =
+            ToolCallPlan::for_call(&registry, "edit", &serde_json::json!({"path": "./a.md"}));
+        assert_eq!(plan.tool, "edit_file");
+        assert!(!plan.metadata.read_only);
+        assert_eq!(plan.resources, ["file:/tmp/a.md"]);
+    }
+}
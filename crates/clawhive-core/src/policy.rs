@@ -290,6 +290,11 @@ impl PolicyContext {
         &self.security_mode
     }
 
+    /// Private network targets (`host:port`) allowed past the hard baseline.
+    pub fn private_overrides(&self) -> &[String] {
+        &self.private_overrides
+    }
+
     /// Check if network access is allowed.
     pub fn check_network(&self, host: &str, port: u16) -> bool {
         // 0. Security off bypasses everything
@@ -0,0 +1,568 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use clawhive_bus::BusPublisher;
+use clawhive_memory::usage::{UsageFilter, UsageGroupBy, UsageLedger, UsageSummary};
+use clawhive_provider::LlmMessage;
+use clawhive_schema::{ApprovalDecision, BusMessage};
+use serde::Serialize;
+
+use super::{parse_provider_model, LlmRouter, UsageScope};
+use crate::config::{BudgetExhaustAction, BudgetPeriod, BudgetPolicy, BudgetScope};
+use crate::context::{estimate_messages_tokens, estimate_tokens};
+use crate::ApprovalRegistry;
+
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Estimated `(usd, tokens)` of calls that passed the budget check but are
+/// not in the ledger yet, by budget key.
+type InFlight = Arc<Mutex<HashMap<String, (f64, u64)>>>;
+
+/// Budget policies together with what the router needs to act on them.
+#[derive(Clone, Default)]
+pub struct BudgetGuard {
+    policies: Arc<Vec<BudgetPolicy>>,
+    /// Agent id → `model_policy.compaction_model`, used by `downgrade`.
+    downgrade_models: Arc<HashMap<String, String>>,
+    approvals: Option<Arc<ApprovalRegistry>>,
+    bus: Option<BusPublisher>,
+    in_flight: InFlight,
+    /// Serializes check-and-reserve so concurrent calls see each other.
+    check_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl BudgetGuard {
+    pub fn new(policies: Vec<BudgetPolicy>, downgrade_models: HashMap<String, String>) -> Self {
+        Self {
+            policies: Arc::new(policies),
+            downgrade_models: Arc::new(downgrade_models),
+            ..Default::default()
+        }
+    }
+
+    pub fn policies(&self) -> &[BudgetPolicy] {
+        &self.policies
+    }
+
+    /// Let `approve` budgets ask for a human decision. Without this they
+    /// behave like `refuse`.
+    pub fn with_approvals(mut self, registry: Arc<ApprovalRegistry>, bus: BusPublisher) -> Self {
+        self.approvals = Some(registry);
+        self.bus = Some(bus);
+        self
+    }
+}
+
+/// What a call may still cost, counted against its budgets until its usage
+/// is in the ledger.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(super) struct CallEstimate {
+    pub usd: f64,
+    pub tokens: u64,
+}
+
+impl CallEstimate {
+    /// Prompt size plus the full `max_tokens` of output, priced as `model`.
+    pub(super) fn of_request(
+        model: &str,
+        system: Option<&str>,
+        messages: &[LlmMessage],
+        max_tokens: u32,
+    ) -> Self {
+        let input = estimate_messages_tokens(messages) + system.map_or(0, estimate_tokens);
+        let input = u32::try_from(input).unwrap_or(u32::MAX);
+        let usd = parse_provider_model(model)
+            .ok()
+            .and_then(|(provider, model)| {
+                clawhive_schema::provider_presets::model_pricing(&provider, &model)
+            })
+            .map_or(0.0, |pricing| pricing.cost_usd(input, max_tokens, 0, 0));
+        Self {
+            usd,
+            tokens: u64::from(input) + u64::from(max_tokens),
+        }
+    }
+}
+
+/// A call's estimate held against its budgets. Dropping it releases the
+/// hold; the router keeps it until the call's ledger entry is written.
+pub(super) struct BudgetReservation {
+    in_flight: InFlight,
+    budgets: Vec<String>,
+    estimate: CallEstimate,
+}
+
+impl Drop for BudgetReservation {
+    fn drop(&mut self) {
+        let Ok(mut in_flight) = self.in_flight.lock() else {
+            return;
+        };
+        for budget in &self.budgets {
+            if let Some((usd, tokens)) = in_flight.get_mut(budget) {
+                *usd -= self.estimate.usd;
+                *tokens = tokens.saturating_sub(self.estimate.tokens);
+                if *tokens == 0 {
+                    in_flight.remove(budget);
+                }
+            }
+        }
+    }
+}
+
+/// Outcome of a passed budget check.
+#[derive(Default)]
+pub(super) struct BudgetClearance {
+    /// Model to use instead of the requested one.
+    pub downgrade: Option<String>,
+    pub reservation: Option<BudgetReservation>,
+}
+
+/// Where one budget stands in its current period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetStatus {
+    /// Budget name with the agent or user filled in, e.g. `agent:main:daily`.
+    pub budget: String,
+    pub scope: BudgetScope,
+    /// Agent id or user scope; `None` for global budgets.
+    pub subject: Option<String>,
+    pub period: BudgetPeriod,
+    /// First day of the current period, `YYYY-MM-DD` (UTC).
+    pub period_start: String,
+    pub max_usd: Option<f64>,
+    pub max_tokens: Option<u64>,
+    pub spent_usd: f64,
+    pub spent_tokens: u64,
+    pub on_exhaust: BudgetExhaustAction,
+    pub exhausted: bool,
+    /// Spending past the limit was approved for the rest of the period.
+    pub overridden: bool,
+}
+
+impl BudgetStatus {
+    fn new(
+        policy: &BudgetPolicy,
+        subject: Option<String>,
+        period_start: String,
+        spent: &UsageSummary,
+        overridden: bool,
+    ) -> Self {
+        let spent_tokens = spent.input_tokens + spent.output_tokens;
+        let exhausted = is_exhausted(policy, spent.cost_usd, spent_tokens);
+        Self {
+            budget: budget_key(policy, subject.as_deref()),
+            scope: policy.scope,
+            subject,
+            period: policy.period,
+            period_start,
+            max_usd: policy.max_usd,
+            max_tokens: policy.max_tokens,
+            spent_usd: spent.cost_usd,
+            spent_tokens,
+            on_exhaust: policy.on_exhaust,
+            exhausted,
+            overridden,
+        }
+    }
+
+    /// Count calls still in flight as spent.
+    fn add_in_flight(&mut self, policy: &BudgetPolicy, usd: f64, tokens: u64) {
+        self.spent_usd += usd;
+        self.spent_tokens += tokens;
+        self.exhausted = is_exhausted(policy, self.spent_usd, self.spent_tokens);
+    }
+
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(max) = self.max_usd {
+            parts.push(format!("${:.2} of ${max:.2}", self.spent_usd));
+        }
+        if let Some(max) = self.max_tokens {
+            parts.push(format!("{} of {max} tokens", self.spent_tokens));
+        }
+        format!("{} used since {}", parts.join(", "), self.period_start)
+    }
+}
+
+fn is_exhausted(policy: &BudgetPolicy, spent_usd: f64, spent_tokens: u64) -> bool {
+    policy.max_usd.is_some_and(|max| spent_usd >= max)
+        || policy.max_tokens.is_some_and(|max| spent_tokens >= max)
+}
+
+/// Name a budget is stored under, with `subject` in place of a missing id.
+fn budget_key(policy: &BudgetPolicy, subject: Option<&str>) -> String {
+    match (policy.scope, subject) {
+        (BudgetScope::Global, _) | (_, None) => policy.label(),
+        (scope, Some(subject)) => {
+            format!("{}:{subject}:{}", scope.as_str(), policy.period.as_str())
+        }
+    }
+}
+
+fn period_start(period: BudgetPeriod, now: DateTime<Utc>) -> String {
+    match period {
+        BudgetPeriod::Daily => now.format("%Y-%m-%d").to_string(),
+        BudgetPeriod::Monthly => now.format("%Y-%m-01").to_string(),
+    }
+}
+
+/// The agent or user `policy` tracks for a call made under `scope`:
+/// `Some(None)` for global budgets, `None` when the budget does not apply.
+fn subject_for(policy: &BudgetPolicy, scope: &UsageScope) -> Option<Option<String>> {
+    let subject = match policy.scope {
+        BudgetScope::Global => return Some(None),
+        BudgetScope::Agent => Some(scope.agent_id.as_str()).filter(|id| !id.is_empty()),
+        BudgetScope::User => scope.user_scope.as_deref(),
+    }?;
+    match &policy.id {
+        Some(id) if id != subject => None,
+        _ => Some(Some(subject.to_string())),
+    }
+}
+
+fn spend_filter(policy: &BudgetPolicy, subject: Option<&str>, since: &str) -> UsageFilter {
+    let mut filter = UsageFilter {
+        since: Some(since.to_string()),
+        ..Default::default()
+    };
+    match policy.scope {
+        BudgetScope::Global => {}
+        BudgetScope::Agent => filter.agent_id = subject.map(str::to_owned),
+        BudgetScope::User => filter.user_scope = subject.map(str::to_owned),
+    }
+    filter
+}
+
+async fn budget_status(
+    ledger: &UsageLedger,
+    policy: &BudgetPolicy,
+    subject: Option<String>,
+    now: DateTime<Utc>,
+) -> Result<BudgetStatus> {
+    let since = period_start(policy.period, now);
+    let spent = ledger
+        .total(&spend_filter(policy, subject.as_deref(), &since))
+        .await?;
+    let overridden = ledger
+        .has_budget_override(&budget_key(policy, subject.as_deref()), &since)
+        .await?;
+    Ok(BudgetStatus::new(
+        policy, subject, since, &spent, overridden,
+    ))
+}
+
+/// Current standing of every budget. Budgets without an `id` get one entry
+/// per agent or user that has spent anything this period.
+pub async fn budget_report(
+    ledger: &UsageLedger,
+    policies: &[BudgetPolicy],
+) -> Result<Vec<BudgetStatus>> {
+    let now = Utc::now();
+    let mut report = Vec::new();
+    for policy in policies {
+        let group_by = match (policy.scope, &policy.id) {
+            (BudgetScope::Global, _) => {
+                report.push(budget_status(ledger, policy, None, now).await?);
+                continue;
+            }
+            (_, Some(id)) => {
+                report.push(budget_status(ledger, policy, Some(id.clone()), now).await?);
+                continue;
+            }
+            (BudgetScope::Agent, None) => UsageGroupBy::Agent,
+            (BudgetScope::User, None) => UsageGroupBy::User,
+        };
+        let since = period_start(policy.period, now);
+        let filter = spend_filter(policy, None, &since);
+        for row in ledger.summarize(&filter, group_by).await? {
+            if row.key.is_empty() {
+                continue;
+            }
+            let key = budget_key(policy, Some(&row.key));
+            let overridden = ledger.has_budget_override(&key, &since).await?;
+            report.push(BudgetStatus::new(
+                policy,
+                Some(row.key.clone()),
+                since.clone(),
+                &row,
+                overridden,
+            ));
+        }
+    }
+    Ok(report)
+}
+
+impl LlmRouter {
+    /// Check the call about to be made against every budget that covers it
+    /// and hold its estimated cost against them.
+    ///
+    /// Calls still in flight count as spent, so concurrent calls cannot all
+    /// slip under a limit; at most the last call admitted goes past it.
+    /// Returns the model to use instead when a `downgrade` budget is used
+    /// up, and an error when a budget refuses the call.
+    pub(super) async fn check_budgets(
+        &self,
+        primary: &str,
+        system: Option<&str>,
+        messages: &[LlmMessage],
+        max_tokens: u32,
+    ) -> Result<BudgetClearance> {
+        let (Some(ledger), Some(guard)) = (&self.usage_ledger, &self.budgets) else {
+            return Ok(BudgetClearance::default());
+        };
+        if guard.policies.is_empty() {
+            return Ok(BudgetClearance::default());
+        }
+        let scope = self.usage_scope.clone().unwrap_or_default();
+        let now = Utc::now();
+        let mut downgrade = None;
+        let mut budgets = Vec::new();
+        let mut serial = Some(guard.check_lock.lock().await);
+
+        for policy in guard.policies.iter() {
+            let Some(subject) = subject_for(policy, &scope) else {
+                continue;
+            };
+            let mut status = match budget_status(ledger, policy, subject, now).await {
+                Ok(status) => status,
+                Err(e) => {
+                    tracing::warn!(budget = %policy.label(), "failed to read budget spend: {e}");
+                    continue;
+                }
+            };
+            let in_flight = guard
+                .in_flight
+                .lock()
+                .map(|in_flight| in_flight.get(&status.budget).copied())
+                .unwrap_or_default();
+            if let Some((usd, tokens)) = in_flight {
+                status.add_in_flight(policy, usd, tokens);
+            }
+            budgets.push(status.budget.clone());
+            if !status.exhausted || status.overridden {
+                continue;
+            }
+
+            match policy.on_exhaust {
+                BudgetExhaustAction::Refuse => {
+                    return Err(anyhow!(
+                        "budget {} exhausted ({})",
+                        status.budget,
+                        status.describe()
+                    ));
+                }
+                BudgetExhaustAction::Downgrade => {
+                    let Some(model) = guard.downgrade_models.get(&scope.agent_id) else {
+                        return Err(anyhow!(
+                            "budget {} exhausted ({}) and agent '{}' has no compaction_model to downgrade to",
+                            status.budget,
+                            status.describe(),
+                            scope.agent_id
+                        ));
+                    };
+                    tracing::info!(
+                        budget = %status.budget,
+                        model = %model,
+                        "budget exhausted, downgrading model"
+                    );
+                    downgrade = Some(model.clone());
+                }
+                BudgetExhaustAction::Approve => {
+                    // Other calls need not queue behind a human decision.
+                    serial = None;
+                    self.await_budget_approval(guard, ledger, &scope, &status)
+                        .await?;
+                }
+            }
+        }
+
+        let model = downgrade.as_deref().unwrap_or(primary);
+        let model = self
+            .resolve_model(model)
+            .unwrap_or_else(|_| model.to_string());
+        let estimate = CallEstimate::of_request(&model, system, messages, max_tokens);
+        if let Ok(mut in_flight) = guard.in_flight.lock() {
+            for budget in &budgets {
+                let held = in_flight.entry(budget.clone()).or_default();
+                held.0 += estimate.usd;
+                held.1 += estimate.tokens;
+            }
+        }
+        drop(serial);
+
+        Ok(BudgetClearance {
+            downgrade,
+            reservation: Some(BudgetReservation {
+                in_flight: guard.in_flight.clone(),
+                budgets,
+                estimate,
+            }),
+        })
+    }
+
+    /// Hold the call until a human decides. "Always allow" lifts the budget
+    /// for the rest of its period; "allow once" lets only this call through.
+    async fn await_budget_approval(
+        &self,
+        guard: &BudgetGuard,
+        ledger: &UsageLedger,
+        scope: &UsageScope,
+        status: &BudgetStatus,
+    ) -> Result<()> {
+        let (Some(registry), Some(bus), Some((ch_type, conn_id, conv_scope))) = (
+            guard.approvals.as_ref(),
+            guard.bus.as_ref(),
+            scope.conversation.as_ref(),
+        ) else {
+            // Background calls (consolidation, sub-agents) have no one to ask.
+            return Err(anyhow!(
+                "budget {} exhausted ({}) and no approval channel is available",
+                status.budget,
+                status.describe()
+            ));
+        };
+
+        let trace_id = uuid::Uuid::new_v4();
+        let description = format!("exceed budget {}", status.budget);
+        tracing::info!(%trace_id, budget = %status.budget, "requesting budget approval");
+        let rx = registry
+            .request(trace_id, description.clone(), scope.agent_id.clone())
+            .await;
+
+        let _ = bus
+            .publish(BusMessage::NeedHumanApproval {
+                trace_id,
+                reason: format!("Budget {} exhausted ({})", status.budget, status.describe()),
+                agent_id: scope.agent_id.clone(),
+                command: description,
+                network_target: None,
+                summary: None,
+                source_channel_type: Some(ch_type.clone()),
+                source_connector_id: Some(conn_id.clone()),
+                source_conversation_scope: Some(conv_scope.clone()),
+            })
+            .await;
+
+        match tokio::time::timeout(APPROVAL_TIMEOUT, rx).await {
+            Ok(Ok(ApprovalDecision::AllowOnce)) => Ok(()),
+            Ok(Ok(ApprovalDecision::AlwaysAllow)) => {
+                ledger
+                    .grant_budget_override(&status.budget, &status.period_start)
+                    .await
+            }
+            Ok(Ok(ApprovalDecision::Deny)) | Ok(Err(_)) => {
+                Err(anyhow!("spending past budget {} was denied", status.budget))
+            }
+            Err(_) => Err(anyhow!(
+                "budget {} approval timed out after 10 minutes",
+                status.budget
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn policy(scope: BudgetScope, id: Option<&str>) -> BudgetPolicy {
+        BudgetPolicy {
+            scope,
+            id: id.map(str::to_owned),
+            period: BudgetPeriod::Daily,
+            max_usd: Some(1.0),
+            max_tokens: None,
+            on_exhaust: BudgetExhaustAction::Refuse,
+        }
+    }
+
+    #[test]
+    fn period_start_truncates_to_day_or_month() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 17, 22, 5, 0).unwrap();
+        assert_eq!(period_start(BudgetPeriod::Daily, now), "2026-03-17");
+        assert_eq!(period_start(BudgetPeriod::Monthly, now), "2026-03-01");
+    }
+
+    #[test]
+    fn subject_matches_agent_and_user_budgets() {
+        let scope = UsageScope {
+            agent_id: "main".into(),
+            user_scope: Some("user:7".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            subject_for(&policy(BudgetScope::Global, None), &scope),
+            Some(None)
+        );
+        assert_eq!(
+            subject_for(&policy(BudgetScope::Agent, None), &scope),
+            Some(Some("main".into()))
+        );
+        assert_eq!(
+            subject_for(&policy(BudgetScope::Agent, Some("helper")), &scope),
+            None
+        );
+        assert_eq!(
+            subject_for(&policy(BudgetScope::User, Some("user:7")), &scope),
+            Some(Some("user:7".into()))
+        );
+        assert_eq!(
+            subject_for(&policy(BudgetScope::User, None), &UsageScope::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn budget_key_fills_in_subject() {
+        assert_eq!(
+            budget_key(&policy(BudgetScope::Global, None), None),
+            "global:daily"
+        );
+        assert_eq!(
+            budget_key(&policy(BudgetScope::User, None), Some("user:7")),
+            "user:user:7:daily"
+        );
+        assert_eq!(
+            budget_key(&policy(BudgetScope::Agent, Some("main")), Some("main")),
+            "agent:main:daily"
+        );
+    }
+
+    #[tokio::test]
+    async fn report_lists_spend_against_each_budget() {
+        use clawhive_memory::usage::UsageRecord;
+
+        let memory = clawhive_memory::MemoryStore::open_in_memory().unwrap();
+        let ledger = UsageLedger::new(memory.db());
+        ledger
+            .record(UsageRecord {
+                agent_id: "main".into(),
+                input_tokens: 90,
+                output_tokens: 20,
+                cost_usd: Some(0.4),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let mut global = policy(BudgetScope::Global, None);
+        global.period = BudgetPeriod::Monthly;
+        let per_agent = BudgetPolicy {
+            max_usd: None,
+            max_tokens: Some(100),
+            on_exhaust: BudgetExhaustAction::Downgrade,
+            ..policy(BudgetScope::Agent, None)
+        };
+
+        let report = budget_report(&ledger, &[global, per_agent]).await.unwrap();
+        assert_eq!(report[0].budget, "global:monthly");
+        assert_eq!(report[0].spent_usd, 0.4);
+        assert!(!report[0].exhausted);
+        assert_eq!(report[1].budget, "agent:main:daily");
+        assert_eq!(report[1].spent_tokens, 110);
+        assert!(report[1].exhausted);
+    }
+}
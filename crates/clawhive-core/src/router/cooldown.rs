@@ -58,6 +58,13 @@ impl CooldownStore {
         self.stats.get(provider_id).cloned().unwrap_or_default()
     }
 
+    pub fn snapshot(&self) -> Vec<(String, ProviderCooldownStats)> {
+        self.stats
+            .iter()
+            .map(|(id, stats)| (id.clone(), stats.clone()))
+            .collect()
+    }
+
     pub fn set_cooldown(&mut self, provider_id: &str, duration: Duration, reason: &str) {
         let stats = self.stats.entry(provider_id.to_string()).or_default();
         stats.set_cooldown(duration, reason);
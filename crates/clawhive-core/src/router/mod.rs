@@ -1,19 +1,28 @@
+mod budget;
 mod cooldown;
 mod failover;
+mod usage;
 
+pub use budget::{budget_report, BudgetGuard, BudgetStatus};
 pub use cooldown::{CooldownStore, ProviderCooldownStats};
 pub use failover::{
     classify_failover_reason, get_cooldown_duration, is_failover_error, FailoverReason,
 };
+pub use usage::UsageScope;
 
 use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
 use anyhow::{anyhow, Result};
-use clawhive_provider::{LlmMessage, LlmRequest, LlmResponse, ProviderRegistry, StreamChunk};
+use clawhive_memory::usage::UsageLedger;
+use clawhive_provider::{
+    LlmMessage, LlmRequest, LlmResponse, PromptCache, ProviderRegistry, SamplingParams, StreamChunk,
+};
 use futures_core::Stream;
 use tokio::time;
+use usage::CallUsage;
 
 const MAX_RETRIES: usize = 2;
 const BASE_BACKOFF_MS: u64 = 1000;
@@ -24,6 +33,9 @@ pub struct LlmRouter {
     aliases: HashMap<String, String>,
     global_fallbacks: Vec<String>,
     cooldowns: Arc<RwLock<CooldownStore>>,
+    usage_ledger: Option<UsageLedger>,
+    usage_scope: Option<UsageScope>,
+    budgets: Option<BudgetGuard>,
 }
 
 impl LlmRouter {
@@ -37,6 +49,40 @@ impl LlmRouter {
             aliases,
             global_fallbacks,
             cooldowns: Arc::new(RwLock::new(CooldownStore::new())),
+            usage_ledger: None,
+            usage_scope: None,
+            budgets: None,
+        }
+    }
+
+    /// Record every successful call in `ledger`.
+    pub fn with_usage_ledger(mut self, ledger: UsageLedger) -> Self {
+        self.usage_ledger = Some(ledger);
+        self
+    }
+
+    /// Check `guard`'s budgets before every call. Spend is read from the
+    /// usage ledger, so budgets are only enforced together with
+    /// [`Self::with_usage_ledger`].
+    pub fn with_budgets(mut self, guard: BudgetGuard) -> Self {
+        self.budgets = Some(guard);
+        self
+    }
+
+    pub fn usage_ledger(&self) -> Option<&UsageLedger> {
+        self.usage_ledger.as_ref()
+    }
+
+    pub fn budgets(&self) -> Option<&BudgetGuard> {
+        self.budgets.as_ref()
+    }
+
+    /// A router sharing this one's providers and cooldowns whose calls are
+    /// attributed to `scope` in the usage ledger.
+    pub fn with_usage_scope(&self, scope: UsageScope) -> Self {
+        Self {
+            usage_scope: Some(scope),
+            ..self.clone()
         }
     }
 
@@ -50,6 +96,14 @@ impl LlmRouter {
         self.registry.list().into_iter().map(String::from).collect()
     }
 
+    /// Cooldown state of every provider that has failed at least once.
+    pub fn cooldowns(&self) -> Vec<(String, ProviderCooldownStats)> {
+        self.cooldowns
+            .read()
+            .map(|store| store.snapshot())
+            .unwrap_or_default()
+    }
+
     /// Check if a provider is currently in cooldown
     fn is_provider_in_cooldown(&self, provider_id: &str) -> bool {
         self.cooldowns
@@ -62,6 +116,7 @@ impl LlmRouter {
     /// Record a provider failure and set cooldown
     fn record_provider_failure(&self, provider_id: &str, reason: FailoverReason) {
         let duration = get_cooldown_duration(reason);
+        clawhive_metrics::record_provider_failover(provider_id, reason.as_str());
         if let Ok(mut store) = self.cooldowns.write() {
             store.set_cooldown(provider_id, duration, reason.as_str());
         }
@@ -82,9 +137,16 @@ impl LlmRouter {
         messages: Vec<LlmMessage>,
         max_tokens: u32,
     ) -> Result<LlmResponse> {
-        let mut candidates = vec![primary.to_string()];
-        candidates.extend(fallbacks.iter().cloned());
-        candidates.extend(self.global_fallbacks.clone());
+        let clearance = self
+            .check_budgets(primary, system.as_deref(), &messages, max_tokens)
+            .await?;
+        let mut reservation = clearance.reservation;
+        let downgrade = clearance.downgrade;
+        let mut candidates = vec![downgrade.clone().unwrap_or_else(|| primary.to_string())];
+        if downgrade.is_none() {
+            candidates.extend(fallbacks.iter().cloned());
+            candidates.extend(self.global_fallbacks.clone());
+        }
 
         // Deduplicate candidates while preserving order
         let mut seen = std::collections::HashSet::new();
@@ -144,12 +206,24 @@ impl LlmRouter {
                     max_tokens,
                     tools: vec![],
                     thinking_level: None,
+                    sampling: Default::default(),
+                    tool_choice: None,
+                    response_format: None,
+                    cache: Default::default(),
                 };
 
+                let started = Instant::now();
                 match provider.chat(req).await {
                     Ok(resp) => {
                         // Success! Clear any cooldown for this provider
                         self.clear_provider_cooldown(&provider_id);
+                        self.record_usage(
+                            &provider_id,
+                            &model_id,
+                            CallUsage::of_response(&resp),
+                            started.elapsed(),
+                            reservation.take(),
+                        );
 
                         if idx > 0 {
                             tracing::info!(
@@ -163,6 +237,12 @@ impl LlmRouter {
                         return Ok(resp);
                     }
                     Err(err) => {
+                        clawhive_metrics::record_provider_request(
+                            &provider_id,
+                            &model_id,
+                            false,
+                            started.elapsed(),
+                        );
                         let is_retryable = err.is_retryable();
                         let err_str = err.to_string();
                         let failover_reason = classify_failover_reason(&err_str);
@@ -229,9 +309,21 @@ impl LlmRouter {
         fallbacks: &[String],
         request: LlmRequest,
     ) -> Result<LlmResponse> {
-        let mut candidates = vec![primary.to_string()];
-        candidates.extend(fallbacks.iter().cloned());
-        candidates.extend(self.global_fallbacks.clone());
+        let clearance = self
+            .check_budgets(
+                primary,
+                request.system.as_deref(),
+                &request.messages,
+                request.max_tokens,
+            )
+            .await?;
+        let mut reservation = clearance.reservation;
+        let downgrade = clearance.downgrade;
+        let mut candidates = vec![downgrade.clone().unwrap_or_else(|| primary.to_string())];
+        if downgrade.is_none() {
+            candidates.extend(fallbacks.iter().cloned());
+            candidates.extend(self.global_fallbacks.clone());
+        }
 
         // Deduplicate candidates while preserving order
         let mut seen = std::collections::HashSet::new();
@@ -286,16 +378,20 @@ impl LlmRouter {
             loop {
                 let req = LlmRequest {
                     model: model_id.clone(),
-                    system: request.system.clone(),
-                    messages: request.messages.clone(),
-                    max_tokens: request.max_tokens,
-                    tools: request.tools.clone(),
-                    thinking_level: request.thinking_level,
+                    ..request.clone()
                 };
 
+                let started = Instant::now();
                 match provider.chat(req).await {
                     Ok(resp) => {
                         self.clear_provider_cooldown(&provider_id);
+                        self.record_usage(
+                            &provider_id,
+                            &model_id,
+                            CallUsage::of_response(&resp),
+                            started.elapsed(),
+                            reservation.take(),
+                        );
 
                         if idx > 0 {
                             tracing::info!(
@@ -309,6 +405,12 @@ impl LlmRouter {
                         return Ok(resp);
                     }
                     Err(err) => {
+                        clawhive_metrics::record_provider_request(
+                            &provider_id,
+                            &model_id,
+                            false,
+                            started.elapsed(),
+                        );
                         let is_retryable = err.is_retryable();
                         let err_str = err.to_string();
                         let failover_reason = classify_failover_reason(&err_str);
@@ -352,6 +454,23 @@ impl LlmRouter {
         }))
     }
 
+    /// Deterministic structured call for classification-style tasks.
+    ///
+    /// Set `request.response_format` (see [`LlmRequest::with_json_schema`]);
+    /// temperature defaults to 0 unless the request overrides it. Callers
+    /// should still tolerate non-JSON text from providers that ignore the
+    /// format.
+    pub async fn chat_structured(
+        &self,
+        primary: &str,
+        fallbacks: &[String],
+        mut request: LlmRequest,
+    ) -> Result<LlmResponse> {
+        request.sampling.temperature.get_or_insert(0.0);
+        self.chat_with_tools(primary, fallbacks, request).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn stream(
         &self,
         primary: &str,
@@ -360,10 +479,18 @@ impl LlmRouter {
         messages: Vec<LlmMessage>,
         max_tokens: u32,
         thinking_level: Option<clawhive_provider::ThinkingLevel>,
+        sampling: SamplingParams,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
-        let mut candidates = vec![primary.to_string()];
-        candidates.extend(fallbacks.iter().cloned());
-        candidates.extend(self.global_fallbacks.clone());
+        let clearance = self
+            .check_budgets(primary, system.as_deref(), &messages, max_tokens)
+            .await?;
+        let mut reservation = clearance.reservation;
+        let downgrade = clearance.downgrade;
+        let mut candidates = vec![downgrade.clone().unwrap_or_else(|| primary.to_string())];
+        if downgrade.is_none() {
+            candidates.extend(fallbacks.iter().cloned());
+            candidates.extend(self.global_fallbacks.clone());
+        }
 
         // Deduplicate
         let mut seen = std::collections::HashSet::new();
@@ -400,8 +527,13 @@ impl LlmRouter {
                 max_tokens,
                 tools: vec![],
                 thinking_level,
+                sampling: sampling.clone(),
+                tool_choice: None,
+                response_format: None,
+                cache: PromptCache::stable_prefix(&messages),
             };
 
+            let started = Instant::now();
             match provider.stream(req).await {
                 Ok(stream) => {
                     self.clear_provider_cooldown(&provider_id);
@@ -413,9 +545,21 @@ impl LlmRouter {
                             model_id
                         );
                     }
-                    return Ok(stream);
+                    return Ok(self.record_stream_usage(
+                        stream,
+                        provider_id,
+                        model_id,
+                        started,
+                        reservation.take(),
+                    ));
                 }
                 Err(err) => {
+                    clawhive_metrics::record_provider_request(
+                        &provider_id,
+                        &model_id,
+                        false,
+                        started.elapsed(),
+                    );
                     let err_str = err.to_string();
                     if let Some(reason) = classify_failover_reason(&err_str) {
                         self.record_provider_failure(&provider_id, reason);
@@ -483,6 +627,8 @@ mod tests {
                 content: vec![],
                 input_tokens: None,
                 output_tokens: None,
+                cache_read_tokens: None,
+                cache_write_tokens: None,
                 stop_reason: Some("end_turn".into()),
             })
         }
@@ -509,6 +655,8 @@ mod tests {
                 content: vec![],
                 input_tokens: None,
                 output_tokens: None,
+                cache_read_tokens: None,
+                cache_write_tokens: None,
                 stop_reason: Some("end_turn".into()),
             })
         }
@@ -526,6 +674,8 @@ mod tests {
                     is_final: false,
                     input_tokens: None,
                     output_tokens: None,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
                     stop_reason: None,
                     content_blocks: vec![],
                 }),
@@ -534,6 +684,8 @@ mod tests {
                     is_final: false,
                     input_tokens: None,
                     output_tokens: None,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
                     stop_reason: None,
                     content_blocks: vec![],
                 }),
@@ -542,6 +694,8 @@ mod tests {
                     is_final: true,
                     input_tokens: Some(5),
                     output_tokens: Some(10),
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
                     stop_reason: Some("end_turn".into()),
                     content_blocks: vec![],
                 }),
@@ -560,11 +714,59 @@ mod tests {
                 content: vec![],
                 input_tokens: None,
                 output_tokens: None,
+                cache_read_tokens: None,
+                cache_write_tokens: None,
+                stop_reason: Some("end_turn".into()),
+            })
+        }
+    }
+
+    struct CapturingProvider {
+        requests: std::sync::Mutex<Vec<LlmRequest>>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for CapturingProvider {
+        async fn chat(&self, request: LlmRequest) -> Result<LlmResponse, ProviderError> {
+            self.requests.lock().unwrap().push(request);
+            Ok(LlmResponse {
+                text: r#"{"ok":true}"#.into(),
+                content: vec![],
+                input_tokens: None,
+                output_tokens: None,
+                cache_read_tokens: None,
+                cache_write_tokens: None,
                 stop_reason: Some("end_turn".into()),
             })
         }
     }
 
+    #[tokio::test]
+    async fn chat_structured_forwards_format_and_pins_temperature() {
+        let provider = Arc::new(CapturingProvider {
+            requests: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut registry = ProviderRegistry::new();
+        registry.register("test", provider.clone());
+        let aliases = HashMap::from([("model".to_string(), "test/model".to_string())]);
+        let router = LlmRouter::new(registry, aliases, vec![]);
+
+        let mut request = LlmRequest::simple("model".into(), None, "hi".into())
+            .with_json_schema("verdict", serde_json::json!({"type": "object"}));
+        request.sampling.seed = Some(7);
+        router.chat_structured("model", &[], request).await.unwrap();
+
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].model, "model");
+        assert_eq!(requests[0].sampling.temperature, Some(0.0));
+        assert_eq!(requests[0].sampling.seed, Some(7));
+        assert!(matches!(
+            requests[0].response_format,
+            Some(clawhive_provider::ResponseFormat::JsonSchema { ref name, .. }) if name == "verdict"
+        ));
+    }
+
     #[tokio::test]
     async fn retries_on_retryable_error() {
         let provider = Arc::new(RetryableFailProvider {
@@ -648,7 +850,15 @@ mod tests {
         let router = LlmRouter::new(registry, aliases, vec![]);
 
         let mut stream = router
-            .stream("model", &[], None, vec![LlmMessage::user("hi")], 100, None)
+            .stream(
+                "model",
+                &[],
+                None,
+                vec![LlmMessage::user("hi")],
+                100,
+                None,
+                Default::default(),
+            )
             .await
             .unwrap();
 
@@ -685,8 +895,241 @@ mod tests {
                 vec![LlmMessage::user("hi")],
                 100,
                 None,
+                Default::default(),
             )
             .await;
         assert!(stream.is_ok());
     }
+
+    #[tokio::test]
+    async fn streamed_success_is_counted_without_usage_ledger() {
+        let mut registry = ProviderRegistry::new();
+        registry.register("streammetrics", Arc::new(StubStreamProvider));
+        let aliases = HashMap::from([("model".to_string(), "streammetrics/no-ledger".to_string())]);
+        let router = LlmRouter::new(registry, aliases, vec![]);
+
+        let mut stream = router
+            .stream(
+                "model",
+                &[],
+                None,
+                vec![LlmMessage::user("hi")],
+                100,
+                None,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        while let Some(chunk) = stream.next().await {
+            chunk.unwrap();
+        }
+
+        let text = clawhive_metrics::global().render();
+        assert!(text.contains(
+            "clawhive_provider_request_duration_seconds_count{model=\"no-ledger\",outcome=\"ok\",provider=\"streammetrics\"} 1\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn scoped_calls_are_recorded_in_usage_ledger() {
+        use clawhive_memory::usage::{UsageFilter, UsageGroupBy, UsageLedger, UsagePurpose};
+
+        let memory = clawhive_memory::MemoryStore::open_in_memory().unwrap();
+        let ledger = UsageLedger::new(memory.db());
+        let mut registry = ProviderRegistry::new();
+        registry.register("test", Arc::new(StubStreamProvider));
+        let aliases = HashMap::from([("model".to_string(), "test/model".to_string())]);
+        let router = LlmRouter::new(registry, aliases, vec![])
+            .with_usage_ledger(ledger.clone())
+            .with_usage_scope(
+                super::UsageScope::new("main", UsagePurpose::Turn).with_session("telegram:tg:1"),
+            );
+
+        router
+            .chat("model", &[], None, vec![LlmMessage::user("hi")], 100)
+            .await
+            .unwrap();
+        let mut stream = router
+            .stream(
+                "model",
+                &[],
+                None,
+                vec![LlmMessage::user("hi")],
+                100,
+                None,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        while stream.next().await.is_some() {}
+
+        // Ledger writes are spawned in the background.
+        let filter = UsageFilter::default();
+        let mut total = ledger.total(&filter).await.unwrap();
+        for _ in 0..50 {
+            if total.calls == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            total = ledger.total(&filter).await.unwrap();
+        }
+        assert_eq!(total.calls, 2);
+        assert_eq!(total.input_tokens, 5);
+        assert_eq!(total.output_tokens, 10);
+        // No preset prices the `test` provider's model.
+        assert_eq!(total.unpriced_calls, 2);
+
+        let by_channel = ledger
+            .summarize(&filter, UsageGroupBy::Channel)
+            .await
+            .unwrap();
+        assert_eq!(by_channel[0].key, "telegram");
+        let recent = ledger.list_recent(&filter, 1).await.unwrap();
+        assert_eq!(recent[0].agent_id, "main");
+        assert_eq!(recent[0].purpose, "turn");
+    }
+
+    #[tokio::test]
+    async fn exhausted_budget_refuses_downgrades_or_waits_for_approval() {
+        use clawhive_memory::usage::{UsageLedger, UsagePurpose, UsageRecord};
+        use clawhive_schema::ApprovalDecision;
+
+        use crate::config::{BudgetExhaustAction, BudgetPeriod, BudgetPolicy, BudgetScope};
+
+        let memory = clawhive_memory::MemoryStore::open_in_memory().unwrap();
+        let ledger = UsageLedger::new(memory.db());
+        ledger
+            .record(UsageRecord {
+                agent_id: "main".into(),
+                cost_usd: Some(2.0),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let provider = Arc::new(CapturingProvider {
+            requests: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut registry = ProviderRegistry::new();
+        registry.register("test", provider.clone());
+        let router = LlmRouter::new(registry, HashMap::new(), vec![])
+            .with_usage_ledger(ledger.clone())
+            .with_usage_scope(super::UsageScope::new("main", UsagePurpose::Turn));
+        let budget = |on_exhaust| BudgetPolicy {
+            scope: BudgetScope::Agent,
+            id: Some("main".into()),
+            period: BudgetPeriod::Daily,
+            max_usd: Some(1.0),
+            max_tokens: None,
+            on_exhaust,
+        };
+        let downgrades = HashMap::from([("main".to_string(), "test/cheap".to_string())]);
+        let chat = |router: LlmRouter| async move {
+            router
+                .chat("test/big", &[], None, vec![LlmMessage::user("hi")], 100)
+                .await
+        };
+
+        let refusing = router.clone().with_budgets(super::BudgetGuard::new(
+            vec![budget(BudgetExhaustAction::Refuse)],
+            downgrades.clone(),
+        ));
+        let err = chat(refusing).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("budget agent:main:daily exhausted"));
+        assert!(provider.requests.lock().unwrap().is_empty());
+
+        let downgrading = router.clone().with_budgets(super::BudgetGuard::new(
+            vec![budget(BudgetExhaustAction::Downgrade)],
+            downgrades.clone(),
+        ));
+        chat(downgrading).await.unwrap();
+        assert_eq!(provider.requests.lock().unwrap()[0].model, "cheap");
+
+        let approvals = Arc::new(crate::ApprovalRegistry::new());
+        let bus = clawhive_bus::EventBus::new(16);
+        let guard = super::BudgetGuard::new(vec![budget(BudgetExhaustAction::Approve)], downgrades)
+            .with_approvals(approvals.clone(), bus.publisher());
+
+        // Background calls have no conversation to ask in and fail at once.
+        let err = chat(router.clone().with_budgets(guard.clone()))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no approval channel"));
+        assert!(approvals.pending_list().await.is_empty());
+
+        let approving = router
+            .with_usage_scope(
+                super::UsageScope::new("main", UsagePurpose::Turn)
+                    .with_source("telegram", "tg", "chat:1", "user:1"),
+            )
+            .with_budgets(guard);
+        let approver = tokio::spawn(async move {
+            loop {
+                if let Some((trace_id, command, _)) = approvals.pending_list().await.pop() {
+                    assert_eq!(command, "exceed budget agent:main:daily");
+                    approvals
+                        .resolve(trace_id, ApprovalDecision::AlwaysAllow)
+                        .await
+                        .unwrap();
+                    return;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        });
+        chat(approving.clone()).await.unwrap();
+        approver.await.unwrap();
+        assert_eq!(provider.requests.lock().unwrap()[1].model, "big");
+
+        // "Always allow" lifts the budget for the rest of the day.
+        chat(approving).await.unwrap();
+        assert_eq!(provider.requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn calls_in_flight_count_against_budgets() {
+        use clawhive_memory::usage::{UsageLedger, UsagePurpose};
+
+        use crate::config::{BudgetExhaustAction, BudgetPeriod, BudgetPolicy, BudgetScope};
+
+        let memory = clawhive_memory::MemoryStore::open_in_memory().unwrap();
+        let router = LlmRouter::new(ProviderRegistry::new(), HashMap::new(), vec![])
+            .with_usage_ledger(UsageLedger::new(memory.db()))
+            .with_usage_scope(super::UsageScope::new("main", UsagePurpose::Turn))
+            .with_budgets(super::BudgetGuard::new(
+                vec![BudgetPolicy {
+                    scope: BudgetScope::Global,
+                    id: None,
+                    period: BudgetPeriod::Daily,
+                    max_usd: None,
+                    max_tokens: Some(150),
+                    on_exhaust: BudgetExhaustAction::Refuse,
+                }],
+                HashMap::new(),
+            ));
+        let messages = [LlmMessage::user("hi")];
+
+        let first = router
+            .check_budgets("test/model", None, &messages, 100)
+            .await
+            .unwrap();
+        let second = router
+            .check_budgets("test/model", None, &messages, 100)
+            .await
+            .unwrap();
+        // Two calls of up to ~100 tokens each are now held against 150.
+        let err = router
+            .check_budgets("test/model", None, &messages, 100)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("budget global:daily exhausted"));
+
+        drop((first, second));
+        router
+            .check_budgets("test/model", None, &messages, 100)
+            .await
+            .unwrap();
+    }
 }
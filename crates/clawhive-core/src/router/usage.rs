@@ -0,0 +1,174 @@
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use clawhive_memory::usage::{UsagePurpose, UsageRecord};
+use clawhive_provider::{LlmResponse, StreamChunk};
+use futures::StreamExt;
+use futures_core::Stream;
+
+use super::budget::BudgetReservation;
+use super::LlmRouter;
+
+/// Who an LLM call is made for, as recorded in the usage ledger.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageScope {
+    pub agent_id: String,
+    pub session_key: Option<String>,
+    /// Channel user the call answers, for per-user budgets.
+    pub user_scope: Option<String>,
+    /// `(channel_type, connector_id, conversation_scope)` of the message being
+    /// answered. Budget approval prompts are delivered there.
+    pub conversation: Option<(String, String, String)>,
+    pub purpose: UsagePurpose,
+}
+
+impl UsageScope {
+    pub fn new(agent_id: impl Into<String>, purpose: UsagePurpose) -> Self {
+        Self {
+            agent_id: agent_id.into(),
+            session_key: None,
+            user_scope: None,
+            conversation: None,
+            purpose,
+        }
+    }
+
+    pub fn with_session(mut self, session_key: impl Into<String>) -> Self {
+        self.session_key = Some(session_key.into());
+        self
+    }
+
+    pub fn with_source(
+        mut self,
+        channel_type: impl Into<String>,
+        connector_id: impl Into<String>,
+        conversation_scope: impl Into<String>,
+        user_scope: impl Into<String>,
+    ) -> Self {
+        self.conversation = Some((
+            channel_type.into(),
+            connector_id.into(),
+            conversation_scope.into(),
+        ));
+        self.user_scope = Some(user_scope.into());
+        self
+    }
+}
+
+/// Token counts of a single provider call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct CallUsage {
+    input_tokens: u32,
+    output_tokens: u32,
+    cache_read_tokens: u32,
+    cache_write_tokens: u32,
+}
+
+impl CallUsage {
+    pub(super) fn of_response(resp: &LlmResponse) -> Self {
+        Self {
+            input_tokens: resp.input_tokens.unwrap_or(0),
+            output_tokens: resp.output_tokens.unwrap_or(0),
+            cache_read_tokens: resp.cache_read_tokens.unwrap_or(0),
+            cache_write_tokens: resp.cache_write_tokens.unwrap_or(0),
+        }
+    }
+
+    /// Streams report usage piecemeal (prompt tokens up front, output tokens
+    /// on the final chunk), so keep the latest value seen for each counter.
+    fn absorb(&mut self, chunk: &StreamChunk) {
+        if let Some(tokens) = chunk.input_tokens {
+            self.input_tokens = tokens;
+        }
+        if let Some(tokens) = chunk.output_tokens {
+            self.output_tokens = tokens;
+        }
+        if let Some(tokens) = chunk.cache_read_tokens {
+            self.cache_read_tokens = tokens;
+        }
+        if let Some(tokens) = chunk.cache_write_tokens {
+            self.cache_write_tokens = tokens;
+        }
+    }
+}
+
+impl LlmRouter {
+    /// Append a ledger entry for a successful call. The write happens in the
+    /// background so a slow database never delays a reply; `reservation`
+    /// keeps the call counted against its budgets until the write is done.
+    pub(super) fn record_usage(
+        &self,
+        provider_id: &str,
+        model_id: &str,
+        usage: CallUsage,
+        latency: Duration,
+        reservation: Option<BudgetReservation>,
+    ) {
+        clawhive_metrics::record_provider_request(provider_id, model_id, true, latency);
+        let Some(ledger) = self.usage_ledger.clone() else {
+            return;
+        };
+        let scope = self.usage_scope.clone().unwrap_or_default();
+        let cost_usd = clawhive_schema::provider_presets::model_pricing(provider_id, model_id).map(
+            |pricing| {
+                pricing.cost_usd(
+                    usage.input_tokens,
+                    usage.output_tokens,
+                    usage.cache_read_tokens,
+                    usage.cache_write_tokens,
+                )
+            },
+        );
+        let record = UsageRecord {
+            recorded_at: String::new(),
+            agent_id: scope.agent_id,
+            session_key: scope.session_key,
+            user_scope: scope.user_scope,
+            provider: provider_id.to_string(),
+            model: model_id.to_string(),
+            purpose: scope.purpose.as_str().to_string(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_read_tokens: usage.cache_read_tokens,
+            cache_write_tokens: usage.cache_write_tokens,
+            latency_ms: latency.as_millis() as u64,
+            cost_usd,
+        };
+        tokio::spawn(async move {
+            if let Err(e) = ledger.record(record).await {
+                tracing::warn!("failed to record LLM usage: {e}");
+            }
+            drop(reservation);
+        });
+    }
+
+    /// Pass a provider stream through, recording the provider metric and
+    /// usage once its final chunk arrives. Latency covers the whole stream.
+    pub(super) fn record_stream_usage(
+        &self,
+        stream: Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>,
+        provider_id: String,
+        model_id: String,
+        started: Instant,
+        mut reservation: Option<BudgetReservation>,
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>> {
+        let router = self.clone();
+        let mut usage = CallUsage::default();
+        Box::pin(stream.map(move |item| {
+            if let Ok(chunk) = &item {
+                usage.absorb(chunk);
+                if chunk.is_final {
+                    router.record_usage(
+                        &provider_id,
+                        &model_id,
+                        usage,
+                        started.elapsed(),
+                        reservation.take(),
+                    );
+                }
+            }
+            item
+        }))
+    }
+}
@@ -4,7 +4,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context as _, Result};
 use async_trait::async_trait;
 use clawhive_auth::{
     manager::OpenAiRefreshConfig,
@@ -13,11 +13,13 @@ use clawhive_auth::{
 };
 use clawhive_bus::BusPublisher;
 use clawhive_memory::embedding::{
-    EmbeddingProvider, GeminiEmbeddingProvider, OllamaEmbeddingProvider, OpenAiEmbeddingProvider,
-    StubEmbeddingProvider,
+    CachedEmbeddingProvider, EmbeddingProvider, GeminiEmbeddingProvider, OllamaEmbeddingProvider,
+    OpenAiEmbeddingProvider, StubEmbeddingProvider,
 };
 use clawhive_memory::file_store::MemoryFileStore;
+use clawhive_memory::local_embedding::load_local_embedding_provider;
 use clawhive_memory::search_index::{SearchConfig, SearchIndex};
+use clawhive_memory::usage::UsageLedger;
 use clawhive_memory::MemoryStore;
 use clawhive_provider::{
     custom, minimax, moonshot, qianfan, qwen, register_builtin_providers, volcengine, zhipu,
@@ -30,7 +32,7 @@ use crate::config::{ClawhiveConfig, MemorySearchConfig, ProviderConfig};
 use crate::config_view::ConfigView;
 use crate::orchestrator::build_tool_registry;
 use crate::persona::{load_persona_from_workspace, Persona};
-use crate::router::LlmRouter;
+use crate::router::{BudgetGuard, LlmRouter};
 use crate::workspace::Workspace;
 use crate::ApprovalRegistry;
 
@@ -347,6 +349,22 @@ async fn refresh_required_openai_oauth_profiles(
     }
 }
 
+/// Budgets from `main.yaml`, downgrading to each agent's compaction model.
+pub fn build_budget_guard(config: &ClawhiveConfig) -> BudgetGuard {
+    let downgrade_models = config
+        .agents
+        .iter()
+        .filter_map(|agent| {
+            agent
+                .model_policy
+                .compaction_model
+                .clone()
+                .map(|model| (agent.agent_id.clone(), model))
+        })
+        .collect();
+    BudgetGuard::new(config.main.budgets.clone(), downgrade_models)
+}
+
 pub async fn build_router_from_config(config: &ClawhiveConfig) -> LlmRouter {
     let token_manager = match TokenManager::new() {
         Ok(manager) => {
@@ -587,10 +605,31 @@ pub async fn build_router_from_config(config: &ClawhiveConfig) -> LlmRouter {
     LlmRouter::new(registry, aliases, vec![])
 }
 
-pub async fn build_embedding_provider(config: &ClawhiveConfig) -> Arc<dyn EmbeddingProvider> {
+/// Serve repeated texts (unchanged facts and chunks) from the SQLite
+/// embedding cache. Stub providers are returned as they are.
+pub fn with_embedding_cache(
+    provider: Arc<dyn EmbeddingProvider>,
+    memory: &Arc<MemoryStore>,
+    config: &ClawhiveConfig,
+) -> Arc<dyn EmbeddingProvider> {
+    if !provider.is_semantic() {
+        return provider;
+    }
+    Arc::new(CachedEmbeddingProvider::new(
+        provider,
+        Arc::clone(memory),
+        config.main.embedding.provider.clone(),
+    ))
+}
+
+/// Fails only for `provider: local`, which must never fall back: a stub
+/// would silently turn memory search into keyword matching.
+pub async fn build_embedding_provider(
+    config: &ClawhiveConfig,
+) -> Result<Arc<dyn EmbeddingProvider>> {
     let embedding_config = &config.main.embedding;
     if !embedding_config.enabled {
-        return Arc::new(StubEmbeddingProvider::new(8));
+        return Ok(Arc::new(StubEmbeddingProvider::new(8)));
     }
     match embedding_config.provider.as_str() {
         "ollama" => {
@@ -600,38 +639,42 @@ pub async fn build_embedding_provider(config: &ClawhiveConfig) -> Arc<dyn Embedd
             )
             .with_base_url(embedding_config.base_url.clone());
             if provider.is_available().await {
-                return Arc::new(provider);
+                return Ok(Arc::new(provider));
             }
         }
         "auto" | "" => {
             let ollama = OllamaEmbeddingProvider::new();
             if ollama.is_available().await {
-                return Arc::new(ollama);
+                return Ok(Arc::new(ollama));
             }
         }
+        "local" => {
+            return load_local_embedding_provider(Path::new(&embedding_config.model_path))
+                .context("embedding.provider is `local` but the model could not be loaded");
+        }
         "gemini" | "google" if !embedding_config.api_key.is_empty() => {
-            return Arc::new(
+            return Ok(Arc::new(
                 GeminiEmbeddingProvider::with_model(
                     embedding_config.api_key.clone(),
                     embedding_config.model.clone(),
                     embedding_config.dimensions,
                 )
                 .with_base_url(embedding_config.base_url.clone()),
-            );
+            ));
         }
         _ => {}
     }
     if !embedding_config.api_key.is_empty() {
-        return Arc::new(
+        return Ok(Arc::new(
             OpenAiEmbeddingProvider::with_model(
                 embedding_config.api_key.clone(),
                 embedding_config.model.clone(),
                 embedding_config.dimensions,
             )
             .with_base_url(embedding_config.base_url.clone()),
-        );
+        ));
     }
-    Arc::new(StubEmbeddingProvider::new(8))
+    Ok(Arc::new(StubEmbeddingProvider::new(8)))
 }
 
 pub async fn build_personas_from_config(
@@ -716,19 +759,24 @@ pub async fn build_config_view(
     approval_registry: &Option<Arc<ApprovalRegistry>>,
     publisher: &BusPublisher,
     schedule_manager: Arc<clawhive_scheduler::ScheduleManager>,
-) -> ConfigView {
-    let router = build_router_from_config(config).await;
+) -> Result<ConfigView> {
+    let mut budgets = build_budget_guard(config);
+    if let Some(registry) = approval_registry {
+        budgets = budgets.with_approvals(Arc::clone(registry), publisher.clone());
+    }
+    let router = build_router_from_config(config)
+        .await
+        .with_usage_ledger(UsageLedger::new(memory.db()))
+        .with_budgets(budgets);
     let personas = build_personas_from_config(root, config).await;
-    let embedding_provider = build_embedding_provider(config).await;
+    let embedding_provider =
+        with_embedding_cache(build_embedding_provider(config).await?, memory, config);
     let file_store = MemoryFileStore::new(root);
-    let search_index = SearchIndex::new_with_config(
-        memory.db(),
-        "",
-        to_search_config(&config.main.memory_search),
-    );
+    let search_config = to_search_config(&config.main.memory_search);
+    let search_index = SearchIndex::new_with_config(memory.db(), "", search_config.clone());
     let search_providers = build_search_providers(&config.main.tools);
     let router_arc = Arc::new(router.clone());
-    let tool_registry = build_tool_registry(
+    let mut tool_registry = build_tool_registry(
         &file_store,
         &search_index,
         memory,
@@ -743,7 +791,8 @@ pub async fn build_config_view(
         &config.agents,
         &personas,
     );
-    ConfigView::new(
+    crate::mcp::mount_mcp_servers(&mut tool_registry, &config.agents, root).await;
+    Ok(ConfigView::new(
         generation,
         config.agents.clone(),
         personas,
@@ -752,4 +801,5 @@ pub async fn build_config_view(
         tool_registry,
         embedding_provider,
     )
+    .with_search_config(search_config))
 }
@@ -10,7 +10,7 @@ use clawhive_scheduler::{
 };
 use serde::Deserialize;
 
-use crate::tool::{ToolContext, ToolExecutor, ToolOutput};
+use crate::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 pub const SCHEDULE_TOOL_NAME: &str = "schedule";
 
@@ -437,6 +437,14 @@ impl ToolExecutor for ScheduleTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["schedule".into()]
+    }
+
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let parsed: ScheduleInput = serde_json::from_value(input)
             .map_err(|e| anyhow!("invalid schedule tool input: {e}"))?;
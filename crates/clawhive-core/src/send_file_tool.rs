@@ -6,7 +6,7 @@ use clawhive_provider::ToolDef;
 use clawhive_schema::{Attachment, AttachmentKind};
 use serde_json::json;
 
-use super::tool::{ToolContext, ToolExecutor, ToolOutput};
+use super::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 pub struct SendFileTool;
 
@@ -51,6 +51,14 @@ impl ToolExecutor for SendFileTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["outbound".into()]
+    }
+
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let path_str = input
             .get("path")
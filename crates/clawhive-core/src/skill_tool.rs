@@ -2,7 +2,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use clawhive_provider::ToolDef;
 
-use crate::tool::{ToolContext, ToolExecutor, ToolOutput};
+use crate::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 pub struct SkillTool;
 
@@ -41,6 +41,10 @@ impl ToolExecutor for SkillTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::read_only()
+    }
+
     async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
         let name = input["name"].as_str().unwrap_or("").trim();
         let file = input["file"]
@@ -0,0 +1,253 @@
+//! Spoken copies of agent replies for voice-capable channels.
+
+use std::sync::Arc;
+
+use base64::Engine as _;
+use clawhive_provider::{OpenAiSpeech, SpeechProvider};
+use clawhive_schema::{Attachment, AttachmentKind, InboundMessage};
+
+use crate::config::{FullAgentConfig, ReplyVoice, RoutingConfig, SpeechConfig};
+
+/// Channels whose adapters send audio attachments as voice notes.
+pub const VOICE_REPLY_CHANNELS: &[&str] = &["telegram", "whatsapp"];
+
+/// The binding that routed the message wins over the agent's own setting.
+pub fn resolve_reply_voice(
+    routing: &RoutingConfig,
+    agent: &FullAgentConfig,
+    inbound: &InboundMessage,
+) -> ReplyVoice {
+    routing
+        .binding_for(inbound)
+        .filter(|binding| binding.agent_id == agent.agent_id)
+        .and_then(|binding| binding.reply_voice)
+        .or(agent.reply_voice)
+        .unwrap_or_default()
+}
+
+pub fn wants_voice_reply(policy: ReplyVoice, inbound: &InboundMessage) -> bool {
+    if !VOICE_REPLY_CHANNELS.contains(&inbound.channel_type.as_str()) {
+        return false;
+    }
+    match policy {
+        ReplyVoice::Never => false,
+        ReplyVoice::Mirror => inbound
+            .attachments
+            .iter()
+            .any(|a| matches!(a.kind, AttachmentKind::Audio)),
+        ReplyVoice::Always => true,
+    }
+}
+
+pub struct VoiceReplies {
+    provider: Arc<dyn SpeechProvider>,
+    max_chars: usize,
+}
+
+impl VoiceReplies {
+    pub fn new(provider: Arc<dyn SpeechProvider>, max_chars: usize) -> Self {
+        Self {
+            provider,
+            max_chars,
+        }
+    }
+
+    /// `None` when speech is disabled.
+    pub fn from_config(config: &SpeechConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let mut speech = OpenAiSpeech::new(config.api_key.clone())
+            .with_model(config.model.clone())
+            .with_voice(config.voice.clone())
+            .with_format(config.format.clone());
+        if let Some(base_url) = &config.base_url {
+            speech = speech.with_base_url(base_url.clone());
+        }
+        Some(Self::new(Arc::new(speech), config.max_chars))
+    }
+
+    /// Speak `text` as an audio attachment. Failures are logged and the reply
+    /// goes out as text only.
+    pub async fn synthesize_reply(&self, text: &str) -> Option<Attachment> {
+        let spoken = spoken_text(text);
+        if spoken.is_empty() {
+            return None;
+        }
+        if spoken.chars().count() > self.max_chars {
+            tracing::debug!(
+                chars = spoken.chars().count(),
+                max_chars = self.max_chars,
+                "reply too long for a voice note, sending text only"
+            );
+            return None;
+        }
+        match self.provider.synthesize(&spoken).await {
+            Ok(audio) => {
+                let extension = match audio.mime_type.as_str() {
+                    "audio/mpeg" => "mp3",
+                    other => other.split('/').nth(1).unwrap_or("ogg"),
+                };
+                Some(Attachment {
+                    kind: AttachmentKind::Audio,
+                    size: Some(audio.data.len() as u64),
+                    url: base64::engine::general_purpose::STANDARD.encode(&audio.data),
+                    file_name: Some(format!("reply.{extension}")),
+                    mime_type: Some(audio.mime_type),
+                })
+            }
+            Err(e) => {
+                tracing::warn!(
+                    provider = self.provider.name(),
+                    error = %e,
+                    "failed to synthesize voice reply"
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Drop Markdown markers that TTS engines would read aloud.
+fn spoken_text(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .map(|line| line.trim_start_matches(['#', '>', ' ']))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace(['*', '`'], "")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use clawhive_provider::SpeechAudio;
+
+    use super::*;
+    use crate::config::{MatchRule, RoutingBinding};
+
+    struct FakeSpeech;
+
+    #[async_trait]
+    impl SpeechProvider for FakeSpeech {
+        async fn synthesize(&self, text: &str) -> Result<SpeechAudio> {
+            Ok(SpeechAudio {
+                data: text.as_bytes().to_vec(),
+                mime_type: "audio/ogg".into(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "fake"
+        }
+    }
+
+    fn inbound(channel_type: &str, attachments: Vec<Attachment>) -> InboundMessage {
+        InboundMessage {
+            trace_id: uuid::Uuid::new_v4(),
+            channel_type: channel_type.into(),
+            connector_id: "main".into(),
+            conversation_scope: "chat:1".into(),
+            user_scope: "user:1".into(),
+            text: String::new(),
+            at: Utc::now(),
+            thread_id: None,
+            is_mention: false,
+            mention_target: None,
+            message_id: None,
+            attachments,
+            message_source: None,
+        }
+    }
+
+    fn voice_note() -> Attachment {
+        Attachment {
+            kind: AttachmentKind::Audio,
+            url: String::new(),
+            mime_type: Some("audio/ogg".into()),
+            file_name: None,
+            size: None,
+        }
+    }
+
+    #[test]
+    fn policy_only_speaks_on_voice_channels() {
+        let voice = inbound("telegram", vec![voice_note()]);
+        let typed = inbound("whatsapp", vec![]);
+        assert!(wants_voice_reply(ReplyVoice::Mirror, &voice));
+        assert!(!wants_voice_reply(ReplyVoice::Mirror, &typed));
+        assert!(wants_voice_reply(ReplyVoice::Always, &typed));
+        assert!(!wants_voice_reply(ReplyVoice::Never, &voice));
+        assert!(!wants_voice_reply(
+            ReplyVoice::Always,
+            &inbound("discord", vec![voice_note()])
+        ));
+    }
+
+    #[test]
+    fn binding_overrides_agent_reply_voice() {
+        let mut agent = crate::config::FullAgentConfig {
+            reply_voice: Some(ReplyVoice::Mirror),
+            ..serde_yaml::from_str(
+                "agent_id: agent-a\nenabled: true\nmodel_policy:\n  primary: test/model\n",
+            )
+            .unwrap()
+        };
+        let mut routing = RoutingConfig {
+            default_agent_id: "agent-a".into(),
+            bindings: vec![RoutingBinding {
+                channel_type: "telegram".into(),
+                connector_id: "main".into(),
+                match_rule: MatchRule {
+                    kind: "dm".into(),
+                    pattern: None,
+                },
+                agent_id: "agent-a".into(),
+                delivery: None,
+                reply_voice: Some(ReplyVoice::Always),
+            }],
+        };
+        let msg = inbound("telegram", vec![]);
+        assert_eq!(
+            resolve_reply_voice(&routing, &agent, &msg),
+            ReplyVoice::Always
+        );
+
+        routing.bindings[0].reply_voice = None;
+        assert_eq!(
+            resolve_reply_voice(&routing, &agent, &msg),
+            ReplyVoice::Mirror
+        );
+
+        agent.reply_voice = None;
+        assert_eq!(
+            resolve_reply_voice(&routing, &agent, &msg),
+            ReplyVoice::Never
+        );
+    }
+
+    #[tokio::test]
+    async fn synthesize_reply_strips_markdown_and_respects_max_chars() {
+        let replies = VoiceReplies::new(Arc::new(FakeSpeech), 40);
+        let attachment = replies
+            .synthesize_reply("## Plan\n**Leave** at `noon`.")
+            .await
+            .expect("voice attachment");
+        assert!(matches!(attachment.kind, AttachmentKind::Audio));
+        assert_eq!(attachment.mime_type.as_deref(), Some("audio/ogg"));
+        assert_eq!(attachment.file_name.as_deref(), Some("reply.ogg"));
+        let spoken = base64::engine::general_purpose::STANDARD
+            .decode(&attachment.url)
+            .unwrap();
+        assert_eq!(spoken, b"Plan\nLeave at noon.");
+
+        assert!(replies
+            .synthesize_reply(&"word ".repeat(20))
+            .await
+            .is_none());
+    }
+}
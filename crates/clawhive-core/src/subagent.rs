@@ -1,16 +1,32 @@
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 
 use anyhow::Result;
+use clawhive_memory::usage::UsagePurpose;
 use clawhive_provider::LlmMessage;
-use tokio::sync::{Mutex, Semaphore};
+use clawhive_schema::Attachment;
+use serde::Serialize;
+use tokio::sync::{watch, Mutex, OwnedSemaphorePermit, Semaphore};
 use tokio::task::JoinHandle;
-use tokio::time::{timeout, Duration};
+use tokio::time::{timeout, Duration, Instant};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use super::config::FullAgentConfig;
 use super::persona::Persona;
-use super::router::LlmRouter;
+use super::router::{LlmRouter, UsageScope};
+use super::tool::ToolContext;
+
+/// How deep delegated runs may nest.
+pub const DEFAULT_MAX_DEPTH: u32 = 3;
+/// How many delegated runs may be in flight at once.
+pub const DEFAULT_MAX_CONCURRENT: usize = 5;
+/// Timeout for a delegated run when the caller does not give one.
+pub const DEFAULT_DELEGATE_TIMEOUT_SECS: u64 = 300;
+/// How long a detached run's result is kept, past its timeout, for the
+/// owner to collect.
+pub const UNCOLLECTED_RUN_TTL: Duration = Duration::from_secs(60 * 60);
 
 #[derive(Debug, Clone)]
 pub struct SubAgentRequest {
@@ -20,6 +36,75 @@ pub struct SubAgentRequest {
     pub task: String,
     pub timeout_seconds: u64,
     pub depth: u32,
+    /// Cancellation of the delegating turn; the run is cancelled with it.
+    pub parent_cancel: Option<CancellationToken>,
+    pub owner: RunOwner,
+}
+
+/// Who started a run. Detached runs can only be checked on and collected
+/// by the same agent in the same session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOwner {
+    pub agent_id: String,
+    pub session_key: String,
+}
+
+impl RunOwner {
+    pub fn from_context(ctx: &ToolContext) -> Self {
+        Self {
+            agent_id: ctx.agent_id().unwrap_or_default().to_string(),
+            session_key: ctx.session_key().to_string(),
+        }
+    }
+}
+
+/// An admitted run. Carried through the sub-agent's tool loop so its events
+/// and nested delegations can be traced back to the parent.
+#[derive(Debug, Clone)]
+pub struct DelegatedRun {
+    pub run_id: Uuid,
+    pub parent_run_id: Uuid,
+    pub trace_id: Uuid,
+    pub target_agent_id: String,
+    pub depth: u32,
+    pub cancel: CancellationToken,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubAgentStatus {
+    Completed,
+    Failed,
+    TimedOut,
+    Cancelled,
+}
+
+impl SubAgentStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::TimedOut => "timed_out",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SubAgentUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// What a sub-agent produced, before the runner stamps it with a run id
+/// and status.
+#[derive(Debug, Clone, Default)]
+pub struct SubAgentReport {
+    pub output: String,
+    pub artifacts: Vec<Attachment>,
+    pub tool_calls: usize,
+    pub usage: SubAgentUsage,
+    pub cancelled: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -27,14 +112,84 @@ pub struct SubAgentResult {
     pub run_id: Uuid,
     pub output: String,
     pub success: bool,
+    pub status: SubAgentStatus,
+    pub artifacts: Vec<Attachment>,
+    pub tool_calls: usize,
+    pub usage: SubAgentUsage,
+}
+
+impl SubAgentResult {
+    pub fn failed(run_id: Uuid, status: SubAgentStatus, output: impl Into<String>) -> Self {
+        Self {
+            run_id,
+            output: output.into(),
+            success: false,
+            status,
+            artifacts: Vec::new(),
+            tool_calls: 0,
+            usage: SubAgentUsage::default(),
+        }
+    }
+
+    fn from_report(run_id: Uuid, report: SubAgentReport) -> Self {
+        let status = if report.cancelled {
+            SubAgentStatus::Cancelled
+        } else {
+            SubAgentStatus::Completed
+        };
+        Self {
+            run_id,
+            output: report.output,
+            success: status == SubAgentStatus::Completed,
+            status,
+            artifacts: report.artifacts,
+            tool_calls: report.tool_calls,
+            usage: report.usage,
+        }
+    }
+
+    /// JSON summary handed back to the delegating agent as the tool result.
+    /// Artifacts are listed by name only; the files themselves travel with
+    /// the parent's reply.
+    pub fn to_tool_content(&self) -> String {
+        self.to_json().to_string()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let artifacts: Vec<_> = self
+            .artifacts
+            .iter()
+            .map(|a| {
+                serde_json::json!({
+                    "kind": a.kind,
+                    "file_name": a.file_name,
+                    "mime_type": a.mime_type,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "run_id": self.run_id,
+            "status": self.status,
+            "output": self.output,
+            "artifacts": artifacts,
+            "tool_calls": self.tool_calls,
+            "usage": self.usage,
+        })
+    }
 }
 
 struct RunHandle {
     handle: JoinHandle<SubAgentResult>,
+    cancel: CancellationToken,
+    /// Flips to `true` once the result is ready, before the task returns.
+    done: watch::Receiver<bool>,
     #[allow(dead_code)]
     parent_run_id: Uuid,
     #[allow(dead_code)]
     trace_id: Uuid,
+    owner: RunOwner,
+    /// When the result is dropped if nobody collected it.
+    expires_at: Instant,
 }
 
 pub struct SubAgentRunner {
@@ -46,6 +201,7 @@ pub struct SubAgentRunner {
     allowed_tools: Vec<String>,
     concurrency_limit: Arc<Semaphore>,
     max_concurrent: usize,
+    result_ttl: Duration,
 }
 
 impl SubAgentRunner {
@@ -66,10 +222,17 @@ impl SubAgentRunner {
             allowed_tools,
             concurrency_limit: Arc::new(Semaphore::new(max_concurrent)),
             max_concurrent,
+            result_ttl: UNCOLLECTED_RUN_TTL,
         }
     }
 
-    pub async fn spawn(&self, req: SubAgentRequest) -> Result<Uuid> {
+    pub fn with_result_ttl(mut self, ttl: Duration) -> Self {
+        self.result_ttl = ttl;
+        self
+    }
+
+    /// Check the depth and concurrency limits and reserve a slot for `req`.
+    fn admit(&self, req: &SubAgentRequest) -> Result<(DelegatedRun, OwnedSemaphorePermit)> {
         if req.depth >= self.max_depth {
             return Err(anyhow::anyhow!(
                 "sub-agent recursion depth {} exceeds maximum {}",
@@ -89,6 +252,104 @@ impl SubAgentRunner {
                 )
             })?;
 
+        let run = DelegatedRun {
+            run_id: Uuid::new_v4(),
+            parent_run_id: req.parent_run_id,
+            trace_id: req.trace_id,
+            target_agent_id: req.target_agent_id.clone(),
+            depth: req.depth,
+            cancel: req
+                .parent_cancel
+                .as_ref()
+                .map(CancellationToken::child_token)
+                .unwrap_or_default(),
+        };
+        Ok((run, permit))
+    }
+
+    /// Run a delegated task on the caller's task, under the runner's limits
+    /// and the request's timeout. `execute` drives the sub-agent itself.
+    pub async fn run<F, Fut>(&self, req: SubAgentRequest, execute: F) -> Result<SubAgentResult>
+    where
+        F: FnOnce(DelegatedRun) -> Fut,
+        Fut: Future<Output = Result<SubAgentReport>>,
+    {
+        let (run, _permit) = self.admit(&req)?;
+        let run_id = run.run_id;
+        let cancel = run.cancel.clone();
+        Ok(finish(run_id, cancel, req.timeout_seconds, execute(run)).await)
+    }
+
+    /// Like [`run`](Self::run), but detached: the run id comes back at once
+    /// and the result is collected with [`wait_result`](Self::wait_result).
+    pub async fn spawn_with<F, Fut>(&self, req: SubAgentRequest, execute: F) -> Result<Uuid>
+    where
+        F: FnOnce(DelegatedRun) -> Fut,
+        Fut: Future<Output = Result<SubAgentReport>> + Send + 'static,
+    {
+        self.spawn_with_completion(req, execute, |_| std::future::ready(()))
+            .await
+    }
+
+    /// Like [`spawn_with`](Self::spawn_with), and hands a copy of the result
+    /// to `on_complete` as soon as the run finishes, e.g. to notify the
+    /// session that delegated it.
+    pub async fn spawn_with_completion<F, Fut, C, CFut>(
+        &self,
+        req: SubAgentRequest,
+        execute: F,
+        on_complete: C,
+    ) -> Result<Uuid>
+    where
+        F: FnOnce(DelegatedRun) -> Fut,
+        Fut: Future<Output = Result<SubAgentReport>> + Send + 'static,
+        C: FnOnce(SubAgentResult) -> CFut + Send + 'static,
+        CFut: Future<Output = ()> + Send + 'static,
+    {
+        let (run, permit) = self.admit(&req)?;
+        let run_id = run.run_id;
+        let cancel = run.cancel.clone();
+        let work = execute(run);
+        let timeout_secs = req.timeout_seconds;
+        let finish_cancel = cancel.clone();
+        let (done_tx, done) = watch::channel(false);
+        let handle = tokio::spawn(async move {
+            let _permit = permit; // held until task completes
+            let result = finish(run_id, finish_cancel, timeout_secs, work).await;
+            let _ = done_tx.send(true);
+            on_complete(result.clone()).await;
+            result
+        });
+
+        let now = Instant::now();
+        let mut runs = self.active_runs.lock().await;
+        runs.retain(|_, run| {
+            let expired = run.expires_at <= now;
+            if expired {
+                run.cancel.cancel();
+                run.handle.abort();
+            }
+            !expired
+        });
+        runs.insert(
+            run_id,
+            RunHandle {
+                handle,
+                cancel,
+                done,
+                parent_run_id: req.parent_run_id,
+                trace_id: req.trace_id,
+                owner: req.owner,
+                expires_at: now + Duration::from_secs(timeout_secs) + self.result_ttl,
+            },
+        );
+
+        Ok(run_id)
+    }
+
+    /// Answer with a single chat call using the target persona's prompt.
+    /// For callers that have no orchestrator to run a full tool loop.
+    pub async fn spawn(&self, req: SubAgentRequest) -> Result<Uuid> {
         let agent = self
             .agents
             .get(&req.target_agent_id)
@@ -101,63 +362,38 @@ impl SubAgentRunner {
             .map(|p| p.assembled_system_prompt_minimal())
             .unwrap_or_default();
 
-        let run_id = Uuid::new_v4();
-        let router = self.router.clone();
+        let router = self.router.with_usage_scope(UsageScope::new(
+            req.target_agent_id.clone(),
+            UsagePurpose::Subagent,
+        ));
         let task_text = req.task.clone();
-        let timeout_secs = req.timeout_seconds;
-        let parent_run_id = req.parent_run_id;
-        let trace_id = req.trace_id;
-
-        let handle = tokio::spawn(async move {
-            let _permit = permit; // held until task completes
 
+        self.spawn_with(req, move |_run| async move {
             let messages = vec![LlmMessage::user(task_text)];
-
-            let result = timeout(
-                Duration::from_secs(timeout_secs),
-                router.chat(
+            let resp = router
+                .chat(
                     &agent.model_policy.primary,
                     &agent.model_policy.fallbacks,
                     Some(system),
                     messages,
                     2048,
-                ),
-            )
-            .await;
-
-            match result {
-                Ok(Ok(resp)) => SubAgentResult {
-                    run_id,
-                    output: resp.text,
-                    success: true,
-                },
-                Ok(Err(err)) => SubAgentResult {
-                    run_id,
-                    output: err.to_string(),
-                    success: false,
-                },
-                Err(_) => SubAgentResult {
-                    run_id,
-                    output: "sub-agent timeout".into(),
-                    success: false,
+                )
+                .await?;
+            Ok(SubAgentReport {
+                output: resp.text,
+                usage: SubAgentUsage {
+                    input_tokens: resp.input_tokens.unwrap_or(0).into(),
+                    output_tokens: resp.output_tokens.unwrap_or(0).into(),
                 },
-            }
-        });
-
-        self.active_runs.lock().await.insert(
-            run_id,
-            RunHandle {
-                handle,
-                parent_run_id,
-                trace_id,
-            },
-        );
-
-        Ok(run_id)
+                ..SubAgentReport::default()
+            })
+        })
+        .await
     }
 
     pub async fn cancel(&self, run_id: &Uuid) -> bool {
         if let Some(run) = self.active_runs.lock().await.remove(run_id) {
+            run.cancel.cancel();
             run.handle.abort();
             true
         } else {
@@ -175,19 +411,51 @@ impl SubAgentRunner {
 
         match run.handle.await {
             Ok(result) => Ok(result),
-            Err(e) if e.is_cancelled() => Ok(SubAgentResult {
-                run_id: *run_id,
-                output: "task cancelled".into(),
-                success: false,
-            }),
-            Err(e) => Ok(SubAgentResult {
-                run_id: *run_id,
-                output: format!("task panicked: {e}"),
-                success: false,
-            }),
+            Err(e) if e.is_cancelled() => Ok(SubAgentResult::failed(
+                *run_id,
+                SubAgentStatus::Cancelled,
+                "task cancelled",
+            )),
+            Err(e) => Ok(SubAgentResult::failed(
+                *run_id,
+                SubAgentStatus::Failed,
+                format!("task panicked: {e}"),
+            )),
         }
     }
 
+    /// Whether a detached run of `owner`'s has finished. `None` if the run
+    /// is unknown, belongs to someone else, or its result was already
+    /// collected.
+    pub async fn is_finished(&self, run_id: &Uuid, owner: &RunOwner) -> Option<bool> {
+        self.active_runs
+            .lock()
+            .await
+            .get(run_id)
+            .filter(|run| run.owner == *owner)
+            .map(|run| *run.done.borrow() || run.handle.is_finished())
+    }
+
+    /// Wait until every run of `owner`'s in `run_ids` has finished, or
+    /// `max_wait` passes. Results stay in place for
+    /// [`wait_result`](Self::wait_result).
+    pub async fn wait_finished(&self, run_ids: &[Uuid], owner: &RunOwner, max_wait: Duration) {
+        let receivers: Vec<_> = {
+            let runs = self.active_runs.lock().await;
+            run_ids
+                .iter()
+                .filter_map(|id| runs.get(id).filter(|run| run.owner == *owner))
+                .map(|run| run.done.clone())
+                .collect()
+        };
+        let all_done =
+            futures::future::join_all(receivers.into_iter().map(|mut done| async move {
+                // An error means the task ended without reporting, e.g. aborted.
+                let _ = done.wait_for(|finished| *finished).await;
+            }));
+        let _ = timeout(max_wait, all_done).await;
+    }
+
     pub fn result_merge(results: &[SubAgentResult]) -> String {
         results
             .iter()
@@ -206,6 +474,23 @@ impl SubAgentRunner {
     }
 }
 
+/// Apply the timeout to a run and turn its outcome into a result.
+async fn finish(
+    run_id: Uuid,
+    cancel: CancellationToken,
+    timeout_secs: u64,
+    work: impl Future<Output = Result<SubAgentReport>>,
+) -> SubAgentResult {
+    match timeout(Duration::from_secs(timeout_secs), work).await {
+        Ok(Ok(report)) => SubAgentResult::from_report(run_id, report),
+        Ok(Err(err)) => SubAgentResult::failed(run_id, SubAgentStatus::Failed, err.to_string()),
+        Err(_) => {
+            cancel.cancel();
+            SubAgentResult::failed(run_id, SubAgentStatus::TimedOut, "sub-agent timeout")
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::{ModelPolicy, SecurityMode};
@@ -229,8 +514,10 @@ mod tests {
                 thinking_level: None,
                 context_window: None,
                 compaction_model: None,
+                sampling: Default::default(),
             },
             tool_policy: None,
+            mcp_servers: vec![],
             memory_policy: None,
             sub_agent: None,
             workspace: None,
@@ -242,6 +529,7 @@ mod tests {
             turn_timeout_secs: None,
             typing_ttl_secs: None,
             progress_delay_secs: None,
+            reply_voice: None,
         };
 
         let mut agents = HashMap::new();
@@ -267,6 +555,8 @@ mod tests {
             task: "Do something".into(),
             timeout_seconds: 30,
             depth: 0,
+            parent_cancel: None,
+            owner: RunOwner::default(),
         };
         let run_id = runner.spawn(req).await.unwrap();
         let result = runner.wait_result(&run_id).await.unwrap();
@@ -284,11 +574,34 @@ mod tests {
             task: "Do something".into(),
             timeout_seconds: 30,
             depth: 0,
+            parent_cancel: None,
+            owner: RunOwner::default(),
         };
         let result = runner.spawn(req).await;
         assert!(result.is_err());
     }
 
+    #[test]
+    fn cancelling_the_parent_cancels_the_run() {
+        let runner = make_runner_with_stub();
+        let parent = CancellationToken::new();
+        let req = SubAgentRequest {
+            parent_run_id: Uuid::new_v4(),
+            trace_id: Uuid::new_v4(),
+            target_agent_id: "test-agent".into(),
+            task: "Do something".into(),
+            timeout_seconds: 30,
+            depth: 0,
+            parent_cancel: Some(parent.clone()),
+            owner: RunOwner::default(),
+        };
+        let (run, _permit) = runner.admit(&req).unwrap();
+        assert!(!run.cancel.is_cancelled());
+
+        parent.cancel();
+        assert!(run.cancel.is_cancelled());
+    }
+
     #[tokio::test]
     async fn cancel_running_task() {
         let runner = make_runner_with_stub();
@@ -299,6 +612,8 @@ mod tests {
             task: "Quick task".into(),
             timeout_seconds: 60,
             depth: 0,
+            parent_cancel: None,
+            owner: RunOwner::default(),
         };
         let run_id = runner.spawn(req).await.unwrap();
         tokio::time::sleep(Duration::from_millis(10)).await;
@@ -309,21 +624,21 @@ mod tests {
     #[tokio::test]
     async fn result_merge_concatenates_successful() {
         let results = vec![
-            SubAgentResult {
-                run_id: Uuid::new_v4(),
-                output: "Result A".into(),
-                success: true,
-            },
-            SubAgentResult {
-                run_id: Uuid::new_v4(),
-                output: "Failed".into(),
-                success: false,
-            },
-            SubAgentResult {
-                run_id: Uuid::new_v4(),
-                output: "Result B".into(),
-                success: true,
-            },
+            SubAgentResult::from_report(
+                Uuid::new_v4(),
+                SubAgentReport {
+                    output: "Result A".into(),
+                    ..SubAgentReport::default()
+                },
+            ),
+            SubAgentResult::failed(Uuid::new_v4(), SubAgentStatus::Failed, "Failed"),
+            SubAgentResult::from_report(
+                Uuid::new_v4(),
+                SubAgentReport {
+                    output: "Result B".into(),
+                    ..SubAgentReport::default()
+                },
+            ),
         ];
         let merged = SubAgentRunner::result_merge(&results);
         assert!(merged.contains("Result A"));
@@ -343,6 +658,8 @@ mod tests {
             task: "task".into(),
             timeout_seconds: 60,
             depth: 0,
+            parent_cancel: None,
+            owner: RunOwner::default(),
         };
         let _run_id = runner.spawn(req).await.unwrap();
         let _count = runner.active_count().await;
@@ -358,6 +675,8 @@ mod tests {
             task: "deep task".into(),
             timeout_seconds: 30,
             depth: 5,
+            parent_cancel: None,
+            owner: RunOwner::default(),
         };
         let result = runner.spawn(req).await;
         assert!(result.is_err());
@@ -381,8 +700,10 @@ mod tests {
                 thinking_level: None,
                 context_window: None,
                 compaction_model: None,
+                sampling: Default::default(),
             },
             tool_policy: None,
+            mcp_servers: vec![],
             memory_policy: None,
             sub_agent: None,
             workspace: None,
@@ -394,6 +715,7 @@ mod tests {
             turn_timeout_secs: None,
             typing_ttl_secs: None,
             progress_delay_secs: None,
+            reply_voice: None,
         };
 
         let mut agents = HashMap::new();
@@ -422,6 +744,8 @@ mod tests {
                 task: format!("task {i}"),
                 timeout_seconds: 60,
                 depth: 0,
+                parent_cancel: None,
+                owner: RunOwner::default(),
             };
             runner.spawn(req).await.unwrap();
         }
@@ -434,6 +758,8 @@ mod tests {
             task: "task overflow".into(),
             timeout_seconds: 60,
             depth: 0,
+            parent_cancel: None,
+            owner: RunOwner::default(),
         };
         let result = runner.spawn(req).await;
         assert!(result.is_err());
@@ -456,6 +782,8 @@ mod tests {
             task: "first task".into(),
             timeout_seconds: 30,
             depth: 0,
+            parent_cancel: None,
+            owner: RunOwner::default(),
         };
         let run_id = runner.spawn(req).await.unwrap();
 
@@ -475,9 +803,150 @@ mod tests {
             task: "second task".into(),
             timeout_seconds: 30,
             depth: 0,
+            parent_cancel: None,
+            owner: RunOwner::default(),
         };
         let run_id2 = runner.spawn(req).await.unwrap();
         let result2 = runner.wait_result(&run_id2).await.unwrap();
         assert!(result2.success);
     }
+
+    #[tokio::test]
+    async fn run_times_out_and_reports_json_status() {
+        let runner = make_runner_with_stub();
+        let req = SubAgentRequest {
+            parent_run_id: Uuid::new_v4(),
+            trace_id: Uuid::new_v4(),
+            target_agent_id: "test-agent".into(),
+            task: "slow task".into(),
+            timeout_seconds: 0,
+            depth: 1,
+            parent_cancel: None,
+            owner: RunOwner::default(),
+        };
+        let result = runner
+            .run(req, |run| async move {
+                assert_eq!(run.depth, 1);
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(SubAgentReport::default())
+            })
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.status, SubAgentStatus::TimedOut);
+
+        let content: serde_json::Value = serde_json::from_str(&result.to_tool_content()).unwrap();
+        assert_eq!(content["status"], "timed_out");
+        assert_eq!(content["run_id"], result.run_id.to_string());
+        assert_eq!(content["tool_calls"], 0);
+        assert!(content["artifacts"].as_array().unwrap().is_empty());
+        assert_eq!(runner.active_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn wait_finished_reports_completion_and_runs_hook() {
+        let runner = make_runner_with_stub();
+        let (hook_tx, hook_rx) = tokio::sync::oneshot::channel();
+        let req = SubAgentRequest {
+            parent_run_id: Uuid::new_v4(),
+            trace_id: Uuid::new_v4(),
+            target_agent_id: "test-agent".into(),
+            task: "background task".into(),
+            timeout_seconds: 30,
+            depth: 0,
+            parent_cancel: None,
+            owner: RunOwner::default(),
+        };
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let run_id = runner
+            .spawn_with_completion(
+                req,
+                |_run| async move {
+                    let _ = release_rx.await;
+                    Ok(SubAgentReport {
+                        output: "done".into(),
+                        ..SubAgentReport::default()
+                    })
+                },
+                move |result| async move {
+                    let _ = hook_tx.send(result.status);
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            runner.is_finished(&run_id, &RunOwner::default()).await,
+            Some(false)
+        );
+        runner
+            .wait_finished(&[run_id], &RunOwner::default(), Duration::from_millis(20))
+            .await;
+        assert_eq!(
+            runner.is_finished(&run_id, &RunOwner::default()).await,
+            Some(false)
+        );
+
+        release_tx.send(()).unwrap();
+        runner
+            .wait_finished(&[run_id], &RunOwner::default(), Duration::from_secs(5))
+            .await;
+        assert_eq!(
+            runner.is_finished(&run_id, &RunOwner::default()).await,
+            Some(true)
+        );
+        assert_eq!(hook_rx.await.unwrap(), SubAgentStatus::Completed);
+
+        let result = runner.wait_result(&run_id).await.unwrap();
+        assert_eq!(result.output, "done");
+        assert_eq!(
+            runner.is_finished(&run_id, &RunOwner::default()).await,
+            None
+        );
+    }
+
+    fn owned_request(owner: &RunOwner, timeout_seconds: u64) -> SubAgentRequest {
+        SubAgentRequest {
+            parent_run_id: Uuid::new_v4(),
+            trace_id: Uuid::new_v4(),
+            target_agent_id: "test-agent".into(),
+            task: "background task".into(),
+            timeout_seconds,
+            depth: 0,
+            parent_cancel: None,
+            owner: owner.clone(),
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_are_only_visible_to_their_owner() {
+        let runner = make_runner_with_stub();
+        let alice = RunOwner {
+            agent_id: "test-agent".into(),
+            session_key: "chat:alice".into(),
+        };
+        let bob = RunOwner {
+            session_key: "chat:bob".into(),
+            ..alice.clone()
+        };
+        let run_id = runner.spawn(owned_request(&alice, 30)).await.unwrap();
+
+        runner
+            .wait_finished(&[run_id], &alice, Duration::from_secs(5))
+            .await;
+        assert_eq!(runner.is_finished(&run_id, &bob).await, None);
+        assert_eq!(runner.is_finished(&run_id, &alice).await, Some(true));
+    }
+
+    #[tokio::test]
+    async fn uncollected_runs_expire() {
+        let runner = make_runner_with_stub().with_result_ttl(Duration::ZERO);
+        let owner = RunOwner::default();
+        let stale = runner.spawn(owned_request(&owner, 0)).await.unwrap();
+        assert!(runner.is_finished(&stale, &owner).await.is_some());
+
+        let fresh = runner.spawn(owned_request(&owner, 30)).await.unwrap();
+        assert_eq!(runner.is_finished(&stale, &owner).await, None);
+        assert!(runner.is_finished(&fresh, &owner).await.is_some());
+    }
 }
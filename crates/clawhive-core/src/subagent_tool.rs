@@ -1,12 +1,64 @@
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
 use clawhive_provider::ToolDef;
+use tokio::time::Duration;
 use uuid::Uuid;
 
-use super::subagent::{SubAgentRequest, SubAgentRunner};
-use super::tool::{ToolContext, ToolExecutor, ToolOutput};
+use super::subagent::{RunOwner, SubAgentRequest, SubAgentRunner};
+use super::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
+
+/// How `delegate_task` hands back its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelegateMode {
+    /// Wait for the sub-agent and return its result.
+    Sync,
+    /// Return the run id at once; collect later with `delegate_collect`.
+    Async,
+}
+
+impl DelegateMode {
+    pub fn from_input(input: &serde_json::Value) -> Result<Self> {
+        match input["mode"].as_str() {
+            None | Some("sync") => Ok(Self::Sync),
+            Some("async") => Ok(Self::Async),
+            Some(other) => bail!("invalid 'mode' {other:?}: expected \"sync\" or \"async\""),
+        }
+    }
+}
+
+/// Tool result for an async delegation that has just started.
+pub fn started_run_content(run_id: Uuid, target_agent_id: &str) -> String {
+    serde_json::json!({
+        "run_id": run_id,
+        "status": "running",
+        "target_agent_id": target_agent_id,
+    })
+    .to_string()
+}
+
+fn parse_run_ids(input: &serde_json::Value) -> Result<Vec<Uuid>> {
+    let Some(ids) = input["run_ids"].as_array() else {
+        bail!("missing 'run_ids' field");
+    };
+    ids.iter()
+        .map(|id| {
+            let id = id
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'run_ids' must be strings"))?;
+            Uuid::parse_str(id).map_err(|e| anyhow::anyhow!("invalid run id {id:?}: {e}"))
+        })
+        .collect()
+}
+
+fn run_ids_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "array",
+        "items": { "type": "string" },
+        "description": "Run ids returned by delegate_task in async mode"
+    })
+}
 
 pub struct SubAgentTool {
     runner: Arc<SubAgentRunner>,
@@ -20,6 +72,42 @@ impl SubAgentTool {
             default_timeout,
         }
     }
+
+    /// Build the request for a `delegate_task` call. Calls made from inside
+    /// a delegated run nest one level below it.
+    pub fn request_from_input(
+        input: &serde_json::Value,
+        ctx: &ToolContext,
+        default_timeout: u64,
+    ) -> Result<SubAgentRequest> {
+        let target_agent_id = input["target_agent_id"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("missing 'target_agent_id' field"))?
+            .to_string();
+
+        let task = input["task"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("missing 'task' field"))?
+            .to_string();
+
+        let timeout_seconds = input["timeout_seconds"].as_u64().unwrap_or(default_timeout);
+
+        let (parent_run_id, depth) = match ctx.delegation() {
+            Some(run) => (run.run_id, run.depth + 1),
+            None => (Uuid::new_v4(), 0),
+        };
+
+        Ok(SubAgentRequest {
+            parent_run_id,
+            trace_id: ctx.trace_id().unwrap_or_else(Uuid::new_v4),
+            target_agent_id,
+            task,
+            timeout_seconds,
+            depth,
+            parent_cancel: ctx.cancel_token().cloned(),
+            owner: RunOwner::from_context(ctx),
+        })
+    }
 }
 
 #[async_trait]
@@ -27,7 +115,7 @@ impl ToolExecutor for SubAgentTool {
     fn definition(&self) -> ToolDef {
         ToolDef {
             name: "delegate_task".into(),
-            description: "Delegate a task to a sub-agent. The sub-agent runs independently with its own persona and returns a result.".into(),
+            description: "Delegate a task to a sub-agent. The sub-agent works through the task with its own persona, workspace and tools, and returns a JSON result with its status, output, artifacts and token usage.".into(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -41,8 +129,19 @@ impl ToolExecutor for SubAgentTool {
                     },
                     "timeout_seconds": {
                         "type": "integer",
-                        "description": "Timeout in seconds (default: 30)",
-                        "default": 30
+                        "description": format!("Timeout in seconds (default: {})", self.default_timeout),
+                        "default": self.default_timeout
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["sync", "async"],
+                        "description": "sync waits for the result. async returns a run_id at once so several tasks can run in parallel; check them with delegate_status and gather them with delegate_collect.",
+                        "default": "sync"
+                    },
+                    "notify": {
+                        "type": "boolean",
+                        "description": "async only: announce the result in this conversation when the run finishes",
+                        "default": false
                     }
                 },
                 "required": ["target_agent_id", "task"]
@@ -50,29 +149,23 @@ impl ToolExecutor for SubAgentTool {
         }
     }
 
-    async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
-        let target_agent_id = input["target_agent_id"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("missing 'target_agent_id' field"))?
-            .to_string();
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
 
-        let task = input["task"]
+    /// Delegations to different agents run side by side; two tasks for the
+    /// same agent share its workspace and so run in order.
+    fn resource_keys(&self, input: &serde_json::Value) -> Vec<String> {
+        input["target_agent_id"]
             .as_str()
-            .ok_or_else(|| anyhow::anyhow!("missing 'task' field"))?
-            .to_string();
-
-        let timeout_seconds = input["timeout_seconds"]
-            .as_u64()
-            .unwrap_or(self.default_timeout);
+            .map(|agent| vec![format!("agent:{agent}")])
+            .unwrap_or_default()
+    }
 
-        let req = SubAgentRequest {
-            parent_run_id: Uuid::new_v4(),
-            trace_id: Uuid::new_v4(),
-            target_agent_id,
-            task,
-            timeout_seconds,
-            depth: 0,
-        };
+    async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let mode = DelegateMode::from_input(&input)?;
+        let req = Self::request_from_input(&input, ctx, self.default_timeout)?;
+        let target_agent_id = req.target_agent_id.clone();
 
         let run_id = match self.runner.spawn(req).await {
             Ok(id) => id,
@@ -84,9 +177,16 @@ impl ToolExecutor for SubAgentTool {
             }
         };
 
+        if mode == DelegateMode::Async {
+            return Ok(ToolOutput {
+                content: started_run_content(run_id, &target_agent_id),
+                is_error: false,
+            });
+        }
+
         match self.runner.wait_result(&run_id).await {
             Ok(result) => Ok(ToolOutput {
-                content: result.output,
+                content: result.to_tool_content(),
                 is_error: !result.success,
             }),
             Err(e) => Ok(ToolOutput {
@@ -97,6 +197,155 @@ impl ToolExecutor for SubAgentTool {
     }
 }
 
+/// `delegate_status`: where async runs stand, without consuming results.
+pub struct DelegateStatusTool {
+    runner: Arc<SubAgentRunner>,
+}
+
+impl DelegateStatusTool {
+    pub fn new(runner: Arc<SubAgentRunner>) -> Self {
+        Self { runner }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for DelegateStatusTool {
+    fn definition(&self) -> ToolDef {
+        ToolDef {
+            name: "delegate_status".into(),
+            description: "Check whether sub-agent runs started with delegate_task in async mode have finished. Can wait a while for them. Results stay available for delegate_collect.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "run_ids": run_ids_schema(),
+                    "wait_seconds": {
+                        "type": "integer",
+                        "description": "Wait up to this long for the runs to finish (default: 0)",
+                        "default": 0
+                    }
+                },
+                "required": ["run_ids"]
+            }),
+        }
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::read_only()
+    }
+
+    async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let run_ids = parse_run_ids(&input)?;
+        let owner = RunOwner::from_context(ctx);
+        let wait = input["wait_seconds"].as_u64().unwrap_or(0);
+        if wait > 0 {
+            self.runner
+                .wait_finished(&run_ids, &owner, Duration::from_secs(wait))
+                .await;
+        }
+
+        let mut runs = Vec::with_capacity(run_ids.len());
+        for run_id in run_ids {
+            let state = match self.runner.is_finished(&run_id, &owner).await {
+                Some(true) => "finished",
+                Some(false) => "running",
+                None => "unknown",
+            };
+            runs.push(serde_json::json!({ "run_id": run_id, "state": state }));
+        }
+        Ok(ToolOutput {
+            content: serde_json::json!({ "runs": runs }).to_string(),
+            is_error: false,
+        })
+    }
+}
+
+/// `delegate_collect`: wait for async runs and hand back their results,
+/// each once, with the successful outputs merged.
+pub struct DelegateCollectTool {
+    runner: Arc<SubAgentRunner>,
+    default_timeout: u64,
+}
+
+impl DelegateCollectTool {
+    pub fn new(runner: Arc<SubAgentRunner>, default_timeout: u64) -> Self {
+        Self {
+            runner,
+            default_timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for DelegateCollectTool {
+    fn definition(&self) -> ToolDef {
+        ToolDef {
+            name: "delegate_collect".into(),
+            description: "Wait for sub-agent runs started with delegate_task in async mode and return their results, plus the successful outputs merged into one text. Each result can be collected once; runs still going when the wait ends are listed as pending.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "run_ids": run_ids_schema(),
+                    "timeout_seconds": {
+                        "type": "integer",
+                        "description": format!("Wait up to this long for unfinished runs (default: {})", self.default_timeout),
+                        "default": self.default_timeout
+                    }
+                },
+                "required": ["run_ids"]
+            }),
+        }
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    /// Collecting consumes results, so two collects never race for a run.
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["delegate_runs".to_string()]
+    }
+
+    async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let run_ids = parse_run_ids(&input)?;
+        let owner = RunOwner::from_context(ctx);
+        let wait = input["timeout_seconds"]
+            .as_u64()
+            .unwrap_or(self.default_timeout);
+        self.runner
+            .wait_finished(&run_ids, &owner, Duration::from_secs(wait))
+            .await;
+
+        let mut results = Vec::new();
+        let mut pending = Vec::new();
+        let mut unknown = Vec::new();
+        for run_id in run_ids {
+            match self.runner.is_finished(&run_id, &owner).await {
+                Some(true) => results.push(self.runner.wait_result(&run_id).await?),
+                Some(false) => pending.push(run_id),
+                None => unknown.push(run_id),
+            }
+        }
+
+        if let Some(collector) = ctx.attachment_collector() {
+            let mut attachments = collector.lock().await;
+            for result in &results {
+                attachments.extend(result.artifacts.iter().cloned());
+            }
+        }
+
+        Ok(ToolOutput {
+            content: serde_json::json!({
+                "results": results.iter().map(|r| r.to_json()).collect::<Vec<_>>(),
+                "merged": SubAgentRunner::result_merge(&results),
+                "pending": pending,
+                "unknown": unknown,
+            })
+            .to_string(),
+            is_error: results.is_empty() && pending.is_empty(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,8 +370,10 @@ mod tests {
                 thinking_level: None,
                 context_window: None,
                 compaction_model: None,
+                sampling: Default::default(),
             },
             tool_policy: None,
+            mcp_servers: vec![],
             memory_policy: None,
             sub_agent: None,
             workspace: None,
@@ -134,6 +385,7 @@ mod tests {
             turn_timeout_secs: None,
             typing_ttl_secs: None,
             progress_delay_secs: None,
+            reply_voice: None,
         };
 
         let mut agents = HashMap::new();
@@ -210,4 +462,96 @@ mod tests {
             .await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn async_delegations_are_collected_and_merged() {
+        let tool = make_sub_agent_tool();
+        let status = DelegateStatusTool::new(tool.runner.clone());
+        let collect = DelegateCollectTool::new(tool.runner.clone(), 30);
+        let ctx = ToolContext::builtin();
+
+        let mut run_ids = Vec::new();
+        for task in ["first", "second"] {
+            let started = tool
+                .execute(
+                    serde_json::json!({
+                        "target_agent_id": "helper",
+                        "task": task,
+                        "mode": "async"
+                    }),
+                    &ctx,
+                )
+                .await
+                .unwrap();
+            assert!(!started.is_error);
+            let started: serde_json::Value = serde_json::from_str(&started.content).unwrap();
+            assert_eq!(started["status"], "running");
+            run_ids.push(started["run_id"].as_str().unwrap().to_string());
+        }
+
+        let polled = status
+            .execute(
+                serde_json::json!({ "run_ids": run_ids, "wait_seconds": 5 }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        let polled: serde_json::Value = serde_json::from_str(&polled.content).unwrap();
+        assert!(polled["runs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|run| run["state"] == "finished"));
+
+        let collected = collect
+            .execute(serde_json::json!({ "run_ids": run_ids }), &ctx)
+            .await
+            .unwrap();
+        assert!(!collected.is_error);
+        let collected: serde_json::Value = serde_json::from_str(&collected.content).unwrap();
+        assert_eq!(collected["results"].as_array().unwrap().len(), 2);
+        assert!(collected["merged"].as_str().unwrap().contains("---"));
+
+        // Results are handed out once.
+        let again = collect
+            .execute(serde_json::json!({ "run_ids": run_ids }), &ctx)
+            .await
+            .unwrap();
+        assert!(again.is_error);
+        let again: serde_json::Value = serde_json::from_str(&again.content).unwrap();
+        assert_eq!(again["unknown"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn invalid_mode_is_rejected() {
+        let tool = make_sub_agent_tool();
+        let result = tool
+            .execute(
+                serde_json::json!({
+                    "target_agent_id": "helper",
+                    "task": "Say hello",
+                    "mode": "later"
+                }),
+                &ToolContext::builtin(),
+            )
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn requests_inherit_the_turn_trace_and_cancellation() {
+        let trace_id = Uuid::new_v4();
+        let cancel = tokio_util::sync::CancellationToken::new();
+        let ctx = ToolContext::builtin().with_turn(trace_id, cancel.clone());
+        let req = SubAgentTool::request_from_input(
+            &serde_json::json!({"target_agent_id": "helper", "task": "Say hello"}),
+            &ctx,
+            30,
+        )
+        .unwrap();
+
+        assert_eq!(req.trace_id, trace_id);
+        cancel.cancel();
+        assert!(req.parent_cancel.unwrap().is_cancelled());
+    }
 }
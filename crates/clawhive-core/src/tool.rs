@@ -8,16 +8,21 @@
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use clawhive_provider::ToolDef;
 use clawhive_schema::Attachment;
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
 
+use super::access_gate::{normalize_path, resolve_path};
 use super::config::SecurityMode;
 use super::policy::{PolicyContext, ToolOrigin};
 use super::skill::SkillRegistry;
+use super::subagent::DelegatedRun;
 
 /// Output from a tool execution.
 pub struct ToolOutput {
@@ -25,6 +30,52 @@ pub struct ToolOutput {
     pub is_error: bool,
 }
 
+/// How the orchestrator may schedule calls to a tool within one round.
+///
+/// The default is conservative: a tool that declares nothing is treated as
+/// mutating and, without resource keys, runs alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolMetadata {
+    /// The tool only observes state, so its calls may overlap freely.
+    pub read_only: bool,
+    /// Upper bound on calls of this tool in flight at once.
+    pub max_concurrency: Option<usize>,
+    /// Deadline for a single call. Exceeding it yields an error result.
+    pub timeout: Option<Duration>,
+}
+
+impl ToolMetadata {
+    pub fn read_only() -> Self {
+        Self {
+            read_only: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn mutating() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_concurrency(mut self, limit: usize) -> Self {
+        self.max_concurrency = Some(limit.max(1));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Resource key for a file path, resolved the way the file tools resolve it:
+/// against `workspace`, with `.` and `..` folded, so `./a.txt`, `sub/../a.txt`
+/// and the absolute path of `a.txt` all collide.
+pub fn file_resource_key(workspace: &Path, path: &str) -> String {
+    let resolved =
+        resolve_path(workspace, path).unwrap_or_else(|_| normalize_path(Path::new(path)));
+    format!("file:{}", resolved.display())
+}
+
 /// A message from the conversation history.
 #[derive(Debug, Clone)]
 pub struct ConversationMessage {
@@ -60,6 +111,12 @@ pub struct ToolContext {
     agent_id: Option<String>,
     /// Whether this execution is from a scheduled task (auto-approve non-HardBaseline approvals)
     is_scheduled_task: bool,
+    /// Set when the tool runs inside a delegated sub-agent run
+    delegation: Option<DelegatedRun>,
+    /// Trace of the turn this tool call belongs to
+    trace_id: Option<Uuid>,
+    /// Cancellation of the turn this tool call belongs to
+    cancel: Option<CancellationToken>,
 }
 
 impl ToolContext {
@@ -84,6 +141,9 @@ impl ToolContext {
             attachment_collector: None,
             agent_id: None,
             is_scheduled_task: false,
+            delegation: None,
+            trace_id: None,
+            cancel: None,
         }
     }
 
@@ -101,6 +161,9 @@ impl ToolContext {
             attachment_collector: None,
             agent_id: None,
             is_scheduled_task: false,
+            delegation: None,
+            trace_id: None,
+            cancel: None,
         }
     }
 
@@ -120,6 +183,9 @@ impl ToolContext {
             attachment_collector: None,
             agent_id: None,
             is_scheduled_task: false,
+            delegation: None,
+            trace_id: None,
+            cancel: None,
         }
     }
 
@@ -140,6 +206,9 @@ impl ToolContext {
             attachment_collector: None,
             agent_id: None,
             is_scheduled_task: false,
+            delegation: None,
+            trace_id: None,
+            cancel: None,
         }
     }
 
@@ -160,6 +229,9 @@ impl ToolContext {
             attachment_collector: None,
             agent_id: None,
             is_scheduled_task: false,
+            delegation: None,
+            trace_id: None,
+            cancel: None,
         }
     }
 
@@ -184,6 +256,9 @@ impl ToolContext {
             attachment_collector: None,
             agent_id: None,
             is_scheduled_task: false,
+            delegation: None,
+            trace_id: None,
+            cancel: None,
         }
     }
 
@@ -205,6 +280,9 @@ impl ToolContext {
             attachment_collector: None,
             agent_id: None,
             is_scheduled_task: false,
+            delegation: None,
+            trace_id: None,
+            cancel: None,
         }
     }
 
@@ -277,6 +355,17 @@ impl ToolContext {
         self
     }
 
+    pub fn with_delegation(mut self, run: DelegatedRun) -> Self {
+        self.delegation = Some(run);
+        self
+    }
+
+    pub fn with_turn(mut self, trace_id: Uuid, cancel: CancellationToken) -> Self {
+        self.trace_id = Some(trace_id);
+        self.cancel = Some(cancel);
+        self
+    }
+
     // ============================================================
     // Accessors
     // ============================================================
@@ -323,6 +412,21 @@ impl ToolContext {
         self.agent_id.as_deref()
     }
 
+    /// The delegated run this tool call belongs to, if any.
+    pub fn delegation(&self) -> Option<&DelegatedRun> {
+        self.delegation.as_ref()
+    }
+
+    /// Trace id of the turn this tool call belongs to.
+    pub fn trace_id(&self) -> Option<Uuid> {
+        self.trace_id
+    }
+
+    /// Cancellation token of the turn this tool call belongs to.
+    pub fn cancel_token(&self) -> Option<&CancellationToken> {
+        self.cancel.as_ref()
+    }
+
     pub fn is_scheduled_task(&self) -> bool {
         self.is_scheduled_task
     }
@@ -392,12 +496,26 @@ pub trait ToolExecutor: Send + Sync {
 
     /// Execute the tool with the given input and context.
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput>;
+
+    /// Scheduling metadata: read-only vs mutating, concurrency cap, timeout.
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::default()
+    }
+
+    /// Resources a call with `input` touches. Mutating calls sharing a key
+    /// run one after another; a mutating call with no keys runs alone.
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 /// Registry of available tools.
 #[derive(Clone)]
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn ToolExecutor>>,
+    /// Tools visible only to specific agents (e.g. MCP tools from agents.d).
+    /// Tools absent from this map are visible to every agent.
+    agent_scopes: HashMap<String, Vec<String>>,
 }
 
 impl ToolRegistry {
@@ -405,12 +523,21 @@ impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            agent_scopes: HashMap::new(),
         }
     }
 
     /// Register a tool.
     pub fn register(&mut self, tool: Box<dyn ToolExecutor>) {
         let name = tool.definition().name.clone();
+        self.agent_scopes.remove(&name);
+        self.tools.insert(name, Arc::from(tool));
+    }
+
+    /// Register a tool that only the given agents may see and execute.
+    pub fn register_for_agents(&mut self, tool: Box<dyn ToolExecutor>, agent_ids: Vec<String>) {
+        let name = tool.definition().name.clone();
+        self.agent_scopes.insert(name.clone(), agent_ids);
         self.tools.insert(name, Arc::from(tool));
     }
 
@@ -419,6 +546,22 @@ impl ToolRegistry {
         self.tools.values().map(|t| t.definition()).collect()
     }
 
+    /// Get the tool definitions visible to one agent.
+    pub fn tool_defs_for_agent(&self, agent_id: &str) -> Vec<ToolDef> {
+        self.tools
+            .iter()
+            .filter(|(name, _)| self.is_visible_to(name, Some(agent_id)))
+            .map(|(_, t)| t.definition())
+            .collect()
+    }
+
+    fn is_visible_to(&self, name: &str, agent_id: Option<&str>) -> bool {
+        match self.agent_scopes.get(name) {
+            None => true,
+            Some(agents) => agent_id.is_some_and(|id| agents.iter().any(|a| a == id)),
+        }
+    }
+
     /// Execute a tool by name.
     pub async fn execute(
         &self,
@@ -429,10 +572,27 @@ impl ToolRegistry {
         let tool = self
             .tools
             .get(name)
+            .filter(|_| self.is_visible_to(name, ctx.agent_id()))
             .ok_or_else(|| anyhow!("tool not found: {name}"))?;
         tool.execute(input, ctx).await
     }
 
+    /// Scheduling metadata for a tool; unknown tools get the conservative default.
+    pub fn metadata(&self, name: &str) -> ToolMetadata {
+        self.tools
+            .get(name)
+            .map(|t| t.metadata())
+            .unwrap_or_default()
+    }
+
+    /// Resource keys a call to `name` with `input` would touch.
+    pub fn resource_keys(&self, name: &str, input: &serde_json::Value) -> Vec<String> {
+        self.tools
+            .get(name)
+            .map(|t| t.resource_keys(input))
+            .unwrap_or_default()
+    }
+
     /// Check if the registry is empty.
     pub fn is_empty(&self) -> bool {
         self.tools.is_empty()
@@ -512,6 +672,54 @@ mod tests {
         assert!(!result.is_error);
     }
 
+    #[tokio::test]
+    async fn registry_scopes_agent_only_tools() {
+        let mut registry = ToolRegistry::new();
+        registry.register_for_agents(Box::new(EchoTool), vec!["agent-a".into()]);
+
+        assert_eq!(registry.tool_defs_for_agent("agent-a").len(), 1);
+        assert!(registry.tool_defs_for_agent("agent-b").is_empty());
+
+        let allowed = ToolContext::builtin().with_agent_id("agent-a");
+        let result = registry
+            .execute("echo", serde_json::json!({"text": "hi"}), &allowed)
+            .await
+            .unwrap();
+        assert_eq!(result.content, "hi");
+
+        let denied = ToolContext::builtin().with_agent_id("agent-b");
+        assert!(registry
+            .execute("echo", serde_json::json!({"text": "hi"}), &denied)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn registry_metadata_defaults_to_mutating() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(EchoTool));
+
+        assert_eq!(registry.metadata("echo"), ToolMetadata::mutating());
+        assert_eq!(registry.metadata("missing"), ToolMetadata::default());
+        assert!(registry
+            .resource_keys("echo", &serde_json::json!({"text": "x"}))
+            .is_empty());
+    }
+
+    #[test]
+    fn file_resource_key_resolves_against_workspace() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ws = tmp.path();
+        let key = file_resource_key(ws, "notes/a.md");
+        let absolute = ws.join("notes/a.md");
+
+        assert_eq!(file_resource_key(ws, "./notes/a.md"), key);
+        assert_eq!(file_resource_key(ws, "notes/./a.md"), key);
+        assert_eq!(file_resource_key(ws, "other/../notes/a.md"), key);
+        assert_eq!(file_resource_key(ws, absolute.to_str().unwrap()), key);
+        assert_ne!(file_resource_key(ws, "a.md"), file_resource_key(ws, "b.md"));
+    }
+
     #[tokio::test]
     async fn registry_execute_unknown_tool() {
         let registry = ToolRegistry::new();
@@ -0,0 +1,220 @@
+//! Transcribes inbound audio attachments before the turn is assembled, so
+//! voice notes reach the agent (and the session log) as text.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use base64::Engine as _;
+use clawhive_provider::{
+    AudioClip, OpenAiTranscriber, TranscriptionProvider, WhisperServerTranscriber,
+};
+use clawhive_schema::{Attachment, AttachmentKind};
+
+use crate::config::{TranscriptionBackend, TranscriptionConfig};
+
+/// Transcript text keyed by the attachment's index in the inbound message.
+pub type Transcripts = HashMap<usize, String>;
+
+pub struct AudioTranscription {
+    provider: Arc<dyn TranscriptionProvider>,
+    max_bytes: usize,
+}
+
+impl AudioTranscription {
+    pub fn new(provider: Arc<dyn TranscriptionProvider>, max_bytes: usize) -> Self {
+        Self {
+            provider,
+            max_bytes,
+        }
+    }
+
+    /// `None` when transcription is disabled.
+    pub fn from_config(config: &TranscriptionConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let language = config.language.clone().filter(|l| !l.trim().is_empty());
+        let provider: Arc<dyn TranscriptionProvider> = match config.provider {
+            TranscriptionBackend::Openai => {
+                let mut transcriber = OpenAiTranscriber::new(config.api_key.clone())
+                    .with_model(config.model.clone())
+                    .with_language(language);
+                if let Some(base_url) = &config.base_url {
+                    transcriber = transcriber.with_base_url(base_url.clone());
+                }
+                Arc::new(transcriber)
+            }
+            TranscriptionBackend::WhisperServer => {
+                let base_url = config.base_url.clone().ok_or_else(|| {
+                    anyhow!("transcription.base_url is required for whisper_server")
+                })?;
+                Arc::new(WhisperServerTranscriber::new(base_url).with_language(language))
+            }
+        };
+        Ok(Some(Self::new(provider, config.max_bytes)))
+    }
+
+    /// Transcribe every audio attachment. Clips that fail to decode, exceed
+    /// `max_bytes` or fail upstream are skipped and stay binary attachments.
+    pub async fn transcribe_attachments(&self, attachments: &[Attachment]) -> Transcripts {
+        let mut transcripts = Transcripts::new();
+        for (index, attachment) in attachments.iter().enumerate() {
+            if !matches!(attachment.kind, AttachmentKind::Audio) {
+                continue;
+            }
+            let Some(clip) = self.audio_clip(attachment) else {
+                continue;
+            };
+            match self.provider.transcribe(&clip).await {
+                Ok(transcript) if !transcript.text.is_empty() => {
+                    tracing::debug!(
+                        provider = self.provider.name(),
+                        file_name = %clip.file_name,
+                        language = ?transcript.language,
+                        chars = transcript.text.chars().count(),
+                        "transcribed audio attachment"
+                    );
+                    transcripts.insert(index, transcript.text);
+                }
+                Ok(_) => {
+                    tracing::debug!(file_name = %clip.file_name, "audio transcript is empty");
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        provider = self.provider.name(),
+                        file_name = %clip.file_name,
+                        error = %e,
+                        "failed to transcribe audio attachment"
+                    );
+                }
+            }
+        }
+        transcripts
+    }
+
+    fn audio_clip(&self, attachment: &Attachment) -> Option<AudioClip> {
+        let data = match base64::engine::general_purpose::STANDARD.decode(&attachment.url) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!(
+                    file_name = ?attachment.file_name,
+                    error = %e,
+                    "failed to base64-decode audio attachment"
+                );
+                return None;
+            }
+        };
+        if data.len() > self.max_bytes {
+            tracing::warn!(
+                file_name = ?attachment.file_name,
+                byte_len = data.len(),
+                max_bytes = self.max_bytes,
+                "audio attachment too large to transcribe"
+            );
+            return None;
+        }
+        let mime_type = attachment
+            .mime_type
+            .clone()
+            .unwrap_or_else(|| "audio/ogg".to_string());
+        let file_name = attachment
+            .file_name
+            .clone()
+            .unwrap_or_else(|| format!("audio.{}", audio_extension(&mime_type)));
+        Some(AudioClip {
+            data,
+            file_name,
+            mime_type,
+        })
+    }
+}
+
+/// Hosted Whisper endpoints pick the decoder from the file extension.
+fn audio_extension(mime_type: &str) -> &'static str {
+    match mime_type.split(';').next().unwrap_or_default().trim() {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" | "audio/aac" => "m4a",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/webm" => "webm",
+        "audio/flac" | "audio/x-flac" => "flac",
+        _ => "ogg",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use clawhive_provider::Transcript;
+
+    use super::*;
+
+    struct EchoTranscriber;
+
+    #[async_trait]
+    impl TranscriptionProvider for EchoTranscriber {
+        async fn transcribe(&self, clip: &AudioClip) -> Result<Transcript> {
+            if clip.data == b"broken" {
+                return Err(anyhow!("decoder error"));
+            }
+            Ok(Transcript {
+                text: format!(
+                    "{} says {}",
+                    clip.file_name,
+                    String::from_utf8_lossy(&clip.data)
+                ),
+                language: None,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+    }
+
+    fn attachment(kind: AttachmentKind, data: &[u8], mime: &str) -> Attachment {
+        Attachment {
+            kind,
+            url: base64::engine::general_purpose::STANDARD.encode(data),
+            mime_type: Some(mime.to_string()),
+            file_name: None,
+            size: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn transcribes_only_usable_audio_attachments() {
+        let transcription = AudioTranscription::new(Arc::new(EchoTranscriber), 16);
+        let attachments = vec![
+            attachment(AttachmentKind::Image, b"png", "image/png"),
+            attachment(AttachmentKind::Audio, b"hello", "audio/mpeg"),
+            attachment(AttachmentKind::Audio, b"broken", "audio/ogg"),
+            attachment(
+                AttachmentKind::Audio,
+                b"far too long for the limit",
+                "audio/ogg",
+            ),
+        ];
+
+        let transcripts = transcription.transcribe_attachments(&attachments).await;
+        assert_eq!(transcripts.len(), 1);
+        assert_eq!(transcripts[&1], "audio.mp3 says hello");
+    }
+
+    #[test]
+    fn whisper_server_requires_base_url() {
+        let config = TranscriptionConfig {
+            provider: TranscriptionBackend::WhisperServer,
+            ..TranscriptionConfig::default()
+        };
+        assert!(AudioTranscription::from_config(&config).is_err());
+
+        let disabled = TranscriptionConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(AudioTranscription::from_config(&disabled)
+            .unwrap()
+            .is_none());
+    }
+}
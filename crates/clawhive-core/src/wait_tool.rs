@@ -8,7 +8,7 @@ use clawhive_provider::ToolDef;
 use clawhive_scheduler::{WaitTask, WaitTaskManager};
 use serde::Deserialize;
 
-use crate::tool::{ToolContext, ToolExecutor, ToolOutput};
+use crate::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 pub const WAIT_TOOL_NAME: &str = "wait_task";
 
@@ -137,6 +137,14 @@ impl ToolExecutor for WaitTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::mutating()
+    }
+
+    fn resource_keys(&self, _input: &serde_json::Value) -> Vec<String> {
+        vec!["schedule".into()]
+    }
+
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let parsed: WaitInput =
             serde_json::from_value(input).map_err(|e| anyhow!("invalid wait_task input: {e}"))?;
@@ -2,7 +2,7 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use clawhive_provider::ToolDef;
 
-use super::tool::{ToolContext, ToolExecutor, ToolOutput};
+use super::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 const DEFAULT_MAX_CHARS: usize = 20_000;
@@ -60,6 +60,12 @@ impl ToolExecutor for WebFetchTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::read_only()
+            .with_max_concurrency(4)
+            .with_timeout(std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS * 2))
+    }
+
     async fn execute(&self, input: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         use super::policy::HardBaseline;
 
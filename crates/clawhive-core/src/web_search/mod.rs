@@ -11,7 +11,7 @@ use async_trait::async_trait;
 use clawhive_provider::ToolDef;
 use tokio::sync::Mutex;
 
-use crate::tool::{ToolContext, ToolExecutor, ToolOutput};
+use crate::tool::{ToolContext, ToolExecutor, ToolMetadata, ToolOutput};
 use circuit_breaker::CircuitBreaker;
 use provider::{SearchError, SearchProvider, SearchResult};
 
@@ -83,6 +83,12 @@ impl ToolExecutor for WebSearchTool {
         }
     }
 
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::read_only()
+            .with_max_concurrency(2)
+            .with_timeout(std::time::Duration::from_secs(60))
+    }
+
     async fn execute(&self, input: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
         let query = input["query"]
             .as_str()
@@ -59,6 +59,8 @@ impl LlmProvider for EchoProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -73,6 +75,8 @@ impl LlmProvider for ThinkingEchoProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -99,6 +103,8 @@ impl LlmProvider for TranscriptProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -127,6 +133,8 @@ impl LlmProvider for MultiSessionSummaryProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -155,6 +163,8 @@ impl LlmProvider for StructuredDailySummaryProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -184,6 +194,8 @@ impl LlmProvider for SlowStructuredDailySummaryProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -212,6 +224,8 @@ impl LlmProvider for MemoryOnlySummaryProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -247,6 +261,8 @@ impl LlmProvider for DuplicateKeyRewriteSummaryProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -275,6 +291,8 @@ impl LlmProvider for StructuredPromotionSummaryProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -299,6 +317,8 @@ impl LlmProvider for PromiseThenSummaryProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -351,6 +371,7 @@ fn test_agent(primary: &str, fallbacks: Vec<&str>) -> AgentConfig {
             thinking_level: None,
             context_window: None,
             compaction_model: None,
+            sampling: Default::default(),
         },
     }
 }
@@ -367,8 +388,10 @@ fn test_full_agent(agent_id: &str, primary: &str, fallbacks: Vec<&str>) -> FullA
             thinking_level: None,
             context_window: None,
             compaction_model: None,
+            sampling: Default::default(),
         },
         tool_policy: None,
+        mcp_servers: vec![],
         memory_policy: None,
         sub_agent: None,
         workspace: None,
@@ -380,6 +403,7 @@ fn test_full_agent(agent_id: &str, primary: &str, fallbacks: Vec<&str>) -> FullA
         turn_timeout_secs: None,
         typing_ttl_secs: None,
         progress_delay_secs: None,
+        reply_voice: None,
     }
 }
 
@@ -602,8 +626,11 @@ async fn orchestrator_injects_active_facts_into_memory_context() {
         superseded_by: None,
         salience: 70,
         supersede_reason: None,
+        pinned: false,
         affect: "neutral".to_string(),
         affect_intensity: 0.0,
+        user_scope: None,
+        conversation_scope: None,
         created_at: now.clone(),
         updated_at: now,
     };
@@ -784,8 +811,11 @@ async fn orchestrator_dedupes_matching_fact_and_memory_chunk_in_prompt_context()
         superseded_by: None,
         salience: 70,
         supersede_reason: None,
+        pinned: false,
         affect: "neutral".to_string(),
         affect_intensity: 0.0,
+        user_scope: None,
+        conversation_scope: None,
         created_at: now.clone(),
         updated_at: now,
     };
@@ -885,8 +915,11 @@ async fn orchestrator_does_not_inject_irrelevant_facts_when_memory_hits_exist()
         superseded_by: None,
         salience: 70,
         supersede_reason: None,
+        pinned: false,
         affect: "neutral".to_string(),
         affect_intensity: 0.0,
+        user_scope: None,
+        conversation_scope: None,
         created_at: now.clone(),
         updated_at: now,
     };
@@ -989,8 +1022,11 @@ async fn orchestrator_keeps_file_fallback_when_facts_exist_but_search_misses() {
         superseded_by: None,
         salience: 50,
         supersede_reason: None,
+        pinned: false,
         affect: "neutral".to_string(),
         affect_intensity: 0.0,
+        user_scope: None,
+        conversation_scope: None,
         created_at: now.clone(),
         updated_at: now,
     };
@@ -1473,8 +1509,11 @@ async fn fallback_summary_suppresses_content_already_explicitly_remembered() {
         superseded_by: None,
         salience: 50,
         supersede_reason: None,
+        pinned: false,
         affect: "neutral".to_string(),
         affect_intensity: 0.0,
+        user_scope: None,
+        conversation_scope: None,
         created_at: now.clone(),
         updated_at: now,
     };
@@ -2393,8 +2432,11 @@ async fn episode_closure_respects_explicit_memory_precheck_for_fact_candidates()
         superseded_by: None,
         salience: 50,
         supersede_reason: None,
+        pinned: false,
         affect: "neutral".to_string(),
         affect_intensity: 0.0,
+        user_scope: None,
+        conversation_scope: None,
         created_at: now.clone(),
         updated_at: now,
     };
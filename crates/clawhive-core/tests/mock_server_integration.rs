@@ -87,8 +87,10 @@ async fn make_orchestrator_with_provider(
             thinking_level: None,
             context_window: None,
             compaction_model: None,
+            sampling: Default::default(),
         },
         tool_policy: None,
+        mcp_servers: vec![],
         memory_policy: None,
         sub_agent: None,
         workspace: None,
@@ -100,6 +102,7 @@ async fn make_orchestrator_with_provider(
         turn_timeout_secs: None,
         typing_ttl_secs: None,
         progress_delay_secs: None,
+        reply_voice: None,
     }];
     let schedule_manager = Arc::new(
         ScheduleManager::new(
@@ -289,7 +292,7 @@ async fn mock_server_publishes_bus_events() {
         .unwrap()
         .unwrap();
     match event {
-        BusMessage::ReplyReady { outbound } => {
+        BusMessage::ReplyReady { outbound, .. } => {
             assert!(outbound.text.contains("bus reply"));
         }
         _ => panic!("unexpected event"),
@@ -428,6 +431,7 @@ async fn mock_server_fallback_on_failure() {
             thinking_level: None,
             context_window: None,
             compaction_model: None,
+            sampling: Default::default(),
         },
     };
 
@@ -462,6 +466,10 @@ async fn mock_server_validates_request_headers() {
             max_tokens: 128,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         })
         .await
         .unwrap();
@@ -481,6 +489,10 @@ async fn mock_server_handles_connection_error() {
             max_tokens: 64,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         })
         .await
         .unwrap_err();
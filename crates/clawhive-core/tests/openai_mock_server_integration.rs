@@ -80,6 +80,10 @@ async fn openai_basic_chat_with_header_verification() {
             max_tokens: 128,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         })
         .await
         .unwrap();
@@ -122,6 +126,10 @@ async fn openai_tool_calling_with_stop_reason_normalization() {
                 }),
             }],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         })
         .await
         .unwrap();
@@ -163,6 +171,10 @@ async fn openai_streaming_text_with_deltas() {
             max_tokens: 64,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         })
         .await
         .unwrap();
@@ -214,6 +226,10 @@ async fn openai_streaming_tool_calls_with_accumulated_blocks() {
                 input_schema: serde_json::json!({"type": "object"}),
             }],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         })
         .await
         .unwrap();
@@ -256,6 +272,10 @@ async fn openai_error_handling_401_not_retryable() {
             max_tokens: 64,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         })
         .await
         .unwrap_err();
@@ -329,6 +349,10 @@ async fn openai_connection_error_retryable() {
             max_tokens: 64,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         })
         .await
         .unwrap_err();
@@ -48,8 +48,10 @@ fn test_full_agent() -> FullAgentConfig {
             thinking_level: None,
             context_window: None,
             compaction_model: None,
+            sampling: Default::default(),
         },
         tool_policy: None,
+        mcp_servers: vec![],
         memory_policy: None,
         sub_agent: None,
         workspace: None,
@@ -61,6 +63,7 @@ fn test_full_agent() -> FullAgentConfig {
         turn_timeout_secs: None,
         typing_ttl_secs: None,
         progress_delay_secs: None,
+        reply_voice: None,
     }
 }
 
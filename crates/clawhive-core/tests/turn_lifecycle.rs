@@ -46,6 +46,8 @@ impl LlmProvider for RecordingProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -73,6 +75,8 @@ impl LlmProvider for TranscriptProvider {
             content: vec![ContentBlock::Text { text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -183,8 +187,10 @@ fn test_full_agent(agent_id: &str, primary: &str) -> FullAgentConfig {
             thinking_level: None,
             context_window: None,
             compaction_model: None,
+            sampling: Default::default(),
         },
         tool_policy: None,
+        mcp_servers: vec![],
         memory_policy: None,
         sub_agent: None,
         workspace: Some(".".to_string()),
@@ -196,6 +202,7 @@ fn test_full_agent(agent_id: &str, primary: &str) -> FullAgentConfig {
         turn_timeout_secs: None,
         typing_ttl_secs: None,
         progress_delay_secs: None,
+        reply_voice: None,
     }
 }
 
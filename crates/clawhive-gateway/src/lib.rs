@@ -11,6 +11,8 @@ use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+pub mod mcp_server;
+pub mod outbound_webhook;
 pub mod reload;
 pub mod supervisor;
 pub mod webhook;
@@ -82,7 +84,11 @@ impl RateLimiter {
         let bucket = buckets
             .entry(key.to_string())
             .or_insert_with(|| TokenBucket::new(&self.config));
-        bucket.try_consume()
+        let allowed = bucket.try_consume();
+        if !allowed {
+            clawhive_metrics::record_rate_limit_rejection();
+        }
+        allowed
     }
 }
 
@@ -202,6 +208,7 @@ impl Gateway {
         rate_limiter: RateLimiter,
         approval_registry: Option<Arc<ApprovalRegistry>>,
     ) -> Self {
+        orchestrator.bind_shared();
         Self {
             orchestrator,
             bus,
@@ -371,32 +378,9 @@ impl Gateway {
         routing: &RoutingConfig,
         inbound: &InboundMessage,
     ) -> Option<String> {
-        for binding in &routing.bindings {
-            if binding.channel_type == inbound.channel_type
-                && binding.connector_id == inbound.connector_id
-            {
-                match binding.match_rule.kind.as_str() {
-                    "dm" if !inbound.conversation_scope.contains("group") => {
-                        return Some(binding.agent_id.clone());
-                    }
-                    "mention" if inbound.is_mention => {
-                        if let Some(pattern) = &binding.match_rule.pattern {
-                            if inbound.mention_target.as_deref() == Some(pattern.as_str()) {
-                                return Some(binding.agent_id.clone());
-                            }
-                        }
-                    }
-                    "group" if inbound.conversation_scope.contains("group") => {
-                        return Some(binding.agent_id.clone());
-                    }
-                    "all" => {
-                        return Some(binding.agent_id.clone());
-                    }
-                    _ => {}
-                }
-            }
-        }
-        None
+        routing
+            .binding_for(inbound)
+            .map(|binding| binding.agent_id.clone())
     }
 
     pub async fn handle_inbound_for_agent(
@@ -470,6 +454,7 @@ impl Gateway {
                     .publish(BusMessage::TaskFailed {
                         trace_id,
                         error: err.to_string(),
+                        agent_id: Some(agent_id.to_string()),
                     })
                     .await;
                 Err(err)
@@ -513,14 +498,22 @@ impl Gateway {
             spawn_turn_timeout(cancel_token.clone(), turn_timeout_secs),
         );
 
+        let started = std::time::Instant::now();
         let result = self
             .handle_inbound_for_agent_with_view(view, inbound, &agent_id, cancel_token)
             .await
             .map(Some);
+        clawhive_metrics::record_turn(&agent_id, result.is_ok(), started.elapsed());
         guard.cleanup(self).await;
         result
     }
 
+    /// Consume a rate-limit token for `user_scope`. Callers that bypass
+    /// [`Gateway::handle_inbound`] use this to stay under the same limits.
+    pub async fn check_rate_limit(&self, user_scope: &str) -> bool {
+        self.rate_limiter.check(user_scope).await
+    }
+
     pub fn orchestrator(&self) -> &Arc<Orchestrator> {
         &self.orchestrator
     }
@@ -556,8 +549,10 @@ pub fn spawn_scheduled_task_listener(
     bus: Arc<EventBus>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        let mut rx = bus.subscribe(Topic::ScheduledTaskTriggered).await;
-        while let Some(msg) = rx.recv().await {
+        let mut rx = bus
+            .subscribe_reliable("gateway.scheduled_task", Topic::ScheduledTaskTriggered)
+            .await;
+        while let Some(event) = rx.recv().await {
             let BusMessage::ScheduledTaskTriggered {
                 schedule_id,
                 agent_id,
@@ -565,7 +560,7 @@ pub fn spawn_scheduled_task_listener(
                 delivery,
                 session_mode,
                 triggered_at,
-            } = msg
+            } = event.message.clone()
             else {
                 continue;
             };
@@ -610,6 +605,9 @@ pub fn spawn_scheduled_task_listener(
                     })
                     .await;
             }
+            if let Err(e) = rx.ack(&event).await {
+                tracing::warn!(error = %e, "failed to ack scheduled task trigger");
+            }
         }
     })
 }
@@ -1234,9 +1232,11 @@ async fn deliver_if_needed(
 pub fn spawn_approval_delivery_listener(bus: Arc<EventBus>) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         let publisher = bus.publisher();
-        let mut rx = bus.subscribe(Topic::NeedHumanApproval).await;
+        let mut rx = bus
+            .subscribe_reliable("gateway.approval_delivery", Topic::NeedHumanApproval)
+            .await;
         tracing::debug!("approval_delivery_listener: subscribed, waiting for events");
-        while let Some(msg) = rx.recv().await {
+        while let Some(delivery) = rx.recv().await {
             tracing::info!("approval_delivery_listener: received NeedHumanApproval event");
             let BusMessage::NeedHumanApproval {
                 trace_id,
@@ -1248,7 +1248,7 @@ pub fn spawn_approval_delivery_listener(bus: Arc<EventBus>) -> tokio::task::Join
                 source_channel_type,
                 source_connector_id,
                 source_conversation_scope,
-            } = msg
+            } = delivery.message.clone()
             else {
                 tracing::warn!(
                     "approval_delivery_listener: message did not match NeedHumanApproval"
@@ -1284,6 +1284,9 @@ pub fn spawn_approval_delivery_listener(bus: Arc<EventBus>) -> tokio::task::Join
                     summary,
                 })
                 .await;
+            if let Err(e) = rx.ack(&delivery).await {
+                tracing::warn!(error = %e, "approval_delivery_listener: failed to ack");
+            }
         }
         tracing::warn!("approval_delivery_listener: loop exited");
     })
@@ -1296,15 +1299,17 @@ pub fn spawn_wait_task_listener(
     bus: Arc<EventBus>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        let mut rx = bus.subscribe(Topic::WaitTaskCompleted).await;
-        while let Some(msg) = rx.recv().await {
+        let mut rx = bus
+            .subscribe_reliable("gateway.wait_task", Topic::WaitTaskCompleted)
+            .await;
+        while let Some(delivery) = rx.recv().await {
             let BusMessage::WaitTaskCompleted {
                 task_id,
                 session_key,
                 status,
                 message,
                 output,
-            } = msg
+            } = delivery.message.clone()
             else {
                 continue;
             };
@@ -1349,6 +1354,55 @@ pub fn spawn_wait_task_listener(
                     text: delivery_text,
                 })
                 .await;
+            if let Err(e) = rx.ack(&delivery).await {
+                tracing::warn!(error = %e, "failed to ack wait task completion");
+            }
+        }
+    })
+}
+
+/// Spawns a listener that announces finished async `delegate_task` runs in
+/// the conversation that started them, when the run asked to notify.
+pub fn spawn_delegate_task_listener(bus: Arc<EventBus>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut rx = bus
+            .subscribe_reliable("gateway.delegate_task", Topic::DelegateTaskCompleted)
+            .await;
+        while let Some(delivery) = rx.recv().await {
+            let BusMessage::DelegateTaskCompleted {
+                run_id,
+                agent_id,
+                channel_type,
+                connector_id,
+                conversation_scope,
+                status,
+                output,
+            } = delivery.message.clone()
+            else {
+                continue;
+            };
+
+            tracing::info!(
+                run_id = %run_id,
+                agent_id = %agent_id,
+                status = %status,
+                "Delegated task completed"
+            );
+
+            let output_preview: String = output.chars().take(1500).collect();
+            let _ = bus
+                .publish(BusMessage::DeliverAnnounce {
+                    channel_type,
+                    connector_id,
+                    conversation_scope,
+                    text: format!(
+                        "Delegated task for {agent_id} {status} (run {run_id}):\n\n{output_preview}"
+                    ),
+                })
+                .await;
+            if let Err(e) = rx.ack(&delivery).await {
+                tracing::warn!(error = %e, "failed to ack delegate task completion");
+            }
         }
     })
 }
@@ -1446,7 +1500,7 @@ mod tests {
         }
     }
 
-    async fn make_gateway() -> (Gateway, tempfile::TempDir) {
+    pub(crate) async fn make_gateway() -> (Gateway, tempfile::TempDir) {
         let tmp = tempfile::TempDir::new().unwrap();
         let mut registry = ProviderRegistry::new();
         register_builtin_providers(&mut registry);
@@ -1480,8 +1534,10 @@ mod tests {
                 thinking_level: None,
                 context_window: None,
                 compaction_model: None,
+                sampling: Default::default(),
             },
             tool_policy: None,
+            mcp_servers: vec![],
             memory_policy: None,
             sub_agent: None,
             workspace: None,
@@ -1493,6 +1549,7 @@ mod tests {
             turn_timeout_secs: None,
             typing_ttl_secs: None,
             progress_delay_secs: None,
+            reply_voice: None,
         }];
         let personas = HashMap::new();
         let tool_registry = build_tool_registry(
@@ -1537,7 +1594,7 @@ mod tests {
         (Gateway::new(orch, publisher, rate_limiter, None), tmp)
     }
 
-    async fn make_gateway_with_receivers() -> (
+    pub(crate) async fn make_gateway_with_receivers() -> (
         Gateway,
         tokio::sync::mpsc::Receiver<BusMessage>,
         tokio::sync::mpsc::Receiver<BusMessage>,
@@ -1580,8 +1637,10 @@ mod tests {
                 thinking_level: None,
                 context_window: None,
                 compaction_model: None,
+                sampling: Default::default(),
             },
             tool_policy: None,
+            mcp_servers: vec![],
             memory_policy: None,
             sub_agent: None,
             workspace: None,
@@ -1593,6 +1652,7 @@ mod tests {
             turn_timeout_secs: None,
             typing_ttl_secs: None,
             progress_delay_secs: None,
+            reply_voice: None,
         }];
         let personas = HashMap::new();
         let tool_registry = build_tool_registry(
@@ -1642,7 +1702,7 @@ mod tests {
         )
     }
 
-    fn add_catch_all_binding(gw: &Gateway) {
+    pub(crate) fn add_catch_all_binding(gw: &Gateway) {
         apply_test_routing(gw, |routing| {
             routing.bindings.push(RoutingBinding {
                 channel_type: "telegram".into(),
@@ -1653,6 +1713,7 @@ mod tests {
                 },
                 agent_id: "clawhive-main".into(),
                 delivery: None,
+                reply_voice: None,
             });
         });
     }
@@ -1723,6 +1784,7 @@ mod tests {
                     },
                     agent_id: "clawhive-builder".into(),
                     delivery: None,
+                    reply_voice: None,
                 }],
             }),
         );
@@ -1836,6 +1898,7 @@ mod tests {
                 },
                 agent_id: "clawhive-builder".into(),
                 delivery: None,
+                reply_voice: None,
             });
         });
         let inbound = InboundMessage {
@@ -1902,6 +1965,7 @@ mod tests {
                 },
                 agent_id: "clawhive-dm".into(),
                 delivery: None,
+                reply_voice: None,
             });
         });
         let inbound = InboundMessage {
@@ -1935,6 +1999,7 @@ mod tests {
                 },
                 agent_id: "clawhive-dm".into(),
                 delivery: None,
+                reply_voice: None,
             });
         });
         let inbound = InboundMessage {
@@ -1968,6 +2033,7 @@ mod tests {
                 },
                 agent_id: "clawhive-group".into(),
                 delivery: None,
+                reply_voice: None,
             });
         });
         let inbound = InboundMessage {
@@ -2310,6 +2376,46 @@ mod tests {
             .expect("expected listener to cancel matching token");
     }
 
+    #[tokio::test]
+    async fn delegate_task_listener_announces_to_originating_conversation() {
+        let bus = Arc::new(EventBus::new(16));
+        let mut announcements = bus.subscribe(Topic::DeliverAnnounce).await;
+        let _handle = spawn_delegate_task_listener(Arc::clone(&bus));
+        tokio::task::yield_now().await;
+
+        let run_id = uuid::Uuid::new_v4();
+        bus.publish(BusMessage::DelegateTaskCompleted {
+            run_id,
+            agent_id: "researcher".into(),
+            channel_type: "telegram".into(),
+            connector_id: "tg_main".into(),
+            conversation_scope: "chat:1".into(),
+            status: "completed".into(),
+            output: "Three sources agree.".into(),
+        })
+        .await
+        .unwrap();
+
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(1), announcements.recv())
+            .await
+            .expect("expected an announcement")
+            .unwrap();
+        let BusMessage::DeliverAnnounce {
+            channel_type,
+            conversation_scope,
+            text,
+            ..
+        } = msg
+        else {
+            panic!("expected DeliverAnnounce");
+        };
+        assert_eq!(channel_type, "telegram");
+        assert_eq!(conversation_scope, "chat:1");
+        assert!(text.contains("researcher completed"));
+        assert!(text.contains(&run_id.to_string()));
+        assert!(text.ends_with("Three sources agree."));
+    }
+
     #[test]
     fn rate_limit_config_default_values() {
         let config = RateLimitConfig::default();
@@ -2330,6 +2436,7 @@ mod tests {
                 },
                 agent_id: "clawhive-other".into(),
                 delivery: None,
+                reply_voice: None,
             });
         });
         let inbound = InboundMessage {
@@ -0,0 +1,569 @@
+//! MCP server exposing clawhive agents to external MCP clients.
+//!
+//! Transport-agnostic JSON-RPC handling lives in [`McpServer::handle_message`];
+//! `clawhive mcp serve` drives it over stdio via [`serve_stdio`] and
+//! clawhive-server mounts it at `POST /api/mcp`. Agent turns go through
+//! [`Gateway::handle_inbound_for_agent`] on the `mcp` channel, so sessions,
+//! rate limiting and approvals behave as for any other channel.
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use clawhive_schema::InboundMessage;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::Gateway;
+
+/// Protocol revisions this server can speak, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+pub const MCP_CHANNEL_TYPE: &str = "mcp";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Conversation used by `send_message` when the caller does not pick one.
+const DEFAULT_CONVERSATION: &str = "default";
+
+pub struct McpServer {
+    gateway: Arc<Gateway>,
+    connector_id: String,
+    caller: Option<String>,
+}
+
+impl McpServer {
+    /// `connector_id` identifies the transport (e.g. `stdio`, `http`) and
+    /// scopes the sessions and rate-limit bucket of its callers.
+    pub fn new(gateway: Arc<Gateway>, connector_id: impl Into<String>) -> Self {
+        Self {
+            gateway,
+            connector_id: connector_id.into(),
+            caller: None,
+        }
+    }
+
+    /// Scope turns to one client of a shared transport, such as an HTTP
+    /// `Mcp-Session-Id`, so clients keep separate sessions, memory
+    /// partitions and rate-limit buckets. `caller` must not contain `:`.
+    pub fn with_caller(mut self, caller: impl Into<String>) -> Self {
+        self.caller = Some(caller.into());
+        self
+    }
+
+    /// Handle one JSON-RPC message. Returns the response to send back, or
+    /// `None` for notifications and stray responses.
+    pub async fn handle_message(&self, message: Value) -> Option<Value> {
+        let Some(obj) = message.as_object() else {
+            return Some(error_response(
+                Value::Null,
+                INVALID_REQUEST,
+                "expected a JSON-RPC object",
+            ));
+        };
+        let id = obj.get("id").cloned();
+        let Some(method) = obj.get("method").and_then(Value::as_str) else {
+            // Responses to requests we never send; nothing to do.
+            return id
+                .is_none()
+                .then(|| error_response(Value::Null, INVALID_REQUEST, "missing method"));
+        };
+        let Some(id) = id else {
+            tracing::debug!(method, "MCP notification received");
+            return None;
+        };
+        let params = obj.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.tool_definitions() })),
+            "tools/call" => self.tools_call(&params).await,
+            other => Err((METHOD_NOT_FOUND, format!("method not found: {other}"))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params
+            .get("protocolVersion")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let protocol_version = SUPPORTED_PROTOCOL_VERSIONS
+            .iter()
+            .find(|v| **v == requested)
+            .unwrap_or(&SUPPORTED_PROTOCOL_VERSIONS[0]);
+        json!({
+            "protocolVersion": protocol_version,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": {
+                "name": "clawhive",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "instructions": "Call clawhive agents and read their memory. \
+                Every tool takes the target agent_id.",
+        })
+    }
+
+    fn enabled_agent_ids(&self) -> Vec<String> {
+        let view = self.gateway.orchestrator().config_view();
+        let mut ids: Vec<String> = view
+            .agents
+            .values()
+            .filter(|agent| agent.enabled)
+            .map(|agent| agent.agent_id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn tool_definitions(&self) -> Vec<Value> {
+        let agent_id = json!({
+            "type": "string",
+            "description": "Target clawhive agent",
+            "enum": self.enabled_agent_ids(),
+        });
+        vec![
+            json!({
+                "name": "memory_search",
+                "description": "Search an agent's remembered facts and indexed memory.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "agent_id": agent_id,
+                        "query": { "type": "string", "description": "The search query" },
+                        "max_results": {
+                            "type": "integer",
+                            "description": "Maximum number of results (default: 6)"
+                        }
+                    },
+                    "required": ["agent_id", "query"]
+                },
+                "annotations": { "readOnlyHint": true },
+            }),
+            json!({
+                "name": "memory_get",
+                "description": "Read an agent's memory file: 'MEMORY.md' for long-term memory, or 'YYYY-MM-DD' for a daily file.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "agent_id": agent_id,
+                        "key": { "type": "string", "description": "'MEMORY.md' or 'YYYY-MM-DD'" }
+                    },
+                    "required": ["agent_id", "key"]
+                },
+                "annotations": { "readOnlyHint": true },
+            }),
+            json!({
+                "name": "delegate_task",
+                "description": "Hand a self-contained task to an agent in a fresh session and return its final answer.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "agent_id": agent_id,
+                        "task": { "type": "string", "description": "What the agent should do" }
+                    },
+                    "required": ["agent_id", "task"]
+                },
+            }),
+            json!({
+                "name": "send_message",
+                "description": "Send a message to an agent and return its reply. Messages with the same conversation_id share a session.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "agent_id": agent_id,
+                        "message": { "type": "string" },
+                        "conversation_id": {
+                            "type": "string",
+                            "description": "Conversation to continue (default: \"default\")"
+                        }
+                    },
+                    "required": ["agent_id", "message"]
+                },
+            }),
+        ]
+    }
+
+    async fn tools_call(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "missing tool name".to_string()))?;
+        let mut args = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        let agent_id = take_string(&mut args, "agent_id")
+            .ok_or((INVALID_PARAMS, "missing 'agent_id' argument".to_string()))?;
+
+        let outcome = match name {
+            "memory_search" | "memory_get" => self.read_memory(&agent_id, name, args).await,
+            "delegate_task" => {
+                let task = take_string(&mut args, "task")
+                    .ok_or((INVALID_PARAMS, "missing 'task' argument".to_string()))?;
+                let scope = format!("task:{}", Uuid::new_v4());
+                self.run_turn(&agent_id, &scope, task).await
+            }
+            "send_message" => {
+                let message = take_string(&mut args, "message")
+                    .ok_or((INVALID_PARAMS, "missing 'message' argument".to_string()))?;
+                let conversation = take_string(&mut args, "conversation_id")
+                    .filter(|c| !c.trim().is_empty())
+                    .unwrap_or_else(|| DEFAULT_CONVERSATION.to_string());
+                self.run_turn(&agent_id, &format!("chat:{conversation}"), message)
+                    .await
+                    .map(|(text, is_error)| {
+                        (
+                            format!("{text}\n\n[conversation_id: {conversation}]"),
+                            is_error,
+                        )
+                    })
+            }
+            other => return Err((INVALID_PARAMS, format!("unknown tool: {other}"))),
+        };
+
+        let (text, is_error) = outcome.unwrap_or_else(|e| (e.to_string(), true));
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+
+    fn ensure_agent(&self, agent_id: &str) -> Result<()> {
+        let view = self.gateway.orchestrator().config_view();
+        match view.agent(agent_id) {
+            Some(agent) if agent.enabled => Ok(()),
+            _ => Err(anyhow!("unknown or disabled agent: {agent_id}")),
+        }
+    }
+
+    async fn read_memory(&self, agent_id: &str, tool: &str, args: Value) -> Result<(String, bool)> {
+        self.ensure_agent(agent_id)?;
+        let output = self
+            .gateway
+            .orchestrator()
+            .execute_memory_read_tool(agent_id, tool, args)
+            .await?;
+        Ok((output.content, output.is_error))
+    }
+
+    async fn run_turn(&self, agent_id: &str, scope: &str, text: String) -> Result<(String, bool)> {
+        self.ensure_agent(agent_id)?;
+        let (user_scope, conversation_scope) = match &self.caller {
+            Some(caller) => (
+                format!("user:mcp_{}_{caller}", self.connector_id),
+                format!("{scope}:{caller}"),
+            ),
+            None => (format!("user:mcp_{}", self.connector_id), scope.to_string()),
+        };
+        if !self.gateway.check_rate_limit(&user_scope).await {
+            return Err(anyhow!("rate limited: too many requests"));
+        }
+        let inbound = InboundMessage {
+            trace_id: Uuid::new_v4(),
+            channel_type: MCP_CHANNEL_TYPE.to_string(),
+            connector_id: self.connector_id.clone(),
+            conversation_scope,
+            user_scope,
+            text,
+            at: chrono::Utc::now(),
+            thread_id: None,
+            is_mention: false,
+            mention_target: None,
+            message_id: None,
+            attachments: vec![],
+            message_source: None,
+        };
+        let outbound = self
+            .gateway
+            .handle_inbound_for_agent(inbound, agent_id)
+            .await?;
+        Ok((outbound.text, false))
+    }
+}
+
+fn take_string(args: &mut Value, key: &str) -> Option<String> {
+    args.as_object_mut()?
+        .remove(key)
+        .and_then(|v| v.as_str().map(str::to_string))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Parse a raw JSON-RPC line and handle it, mapping malformed JSON to a
+/// parse error response.
+pub async fn handle_raw_message(server: &McpServer, raw: &str) -> Option<Value> {
+    match serde_json::from_str::<Value>(raw) {
+        Ok(message) => server.handle_message(message).await,
+        Err(e) => Some(error_response(
+            Value::Null,
+            PARSE_ERROR,
+            &format!("parse error: {e}"),
+        )),
+    }
+}
+
+/// Serve newline-delimited JSON-RPC until `reader` reaches EOF. Requests are
+/// handled concurrently so a long agent turn does not block `ping`.
+pub async fn serve_stdio<R, W>(server: Arc<McpServer>, reader: R, writer: W) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+    let writer_task = tokio::spawn(async move {
+        let mut writer = writer;
+        while let Some(response) = rx.recv().await {
+            let mut line = response.to_string();
+            line.push('\n');
+            writer.write_all(line.as_bytes()).await?;
+            writer.flush().await?;
+        }
+        anyhow::Ok(())
+    });
+
+    let mut lines = reader.lines();
+    let mut in_flight = tokio::task::JoinSet::new();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let server = Arc::clone(&server);
+        let tx = tx.clone();
+        in_flight.spawn(async move {
+            if let Some(response) = handle_raw_message(&server, &line).await {
+                let _ = tx.send(response);
+            }
+        });
+    }
+    while in_flight.join_next().await.is_some() {}
+    drop(tx);
+    writer_task.await??;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clawhive_memory::MemoryScope;
+    use clawhive_schema::{BusMessage, SessionKey};
+
+    use super::*;
+    use crate::tests::{add_catch_all_binding, make_gateway, make_gateway_with_receivers};
+
+    async fn make_server() -> (Arc<McpServer>, tempfile::TempDir) {
+        let (gateway, tmp) = make_gateway().await;
+        add_catch_all_binding(&gateway);
+        (Arc::new(McpServer::new(Arc::new(gateway), "test")), tmp)
+    }
+
+    async fn call(server: &McpServer, id: u64, method: &str, params: Value) -> Value {
+        server
+            .handle_message(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+            .await
+            .expect("request must get a response")
+    }
+
+    #[tokio::test]
+    async fn initialize_negotiates_protocol_version() {
+        let (server, _tmp) = make_server().await;
+        let response = call(
+            &server,
+            1,
+            "initialize",
+            json!({"protocolVersion": "2025-03-26"}),
+        )
+        .await;
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(response["result"]["serverInfo"]["name"], "clawhive");
+
+        let response = call(
+            &server,
+            2,
+            "initialize",
+            json!({"protocolVersion": "1999-01-01"}),
+        )
+        .await;
+        assert_eq!(
+            response["result"]["protocolVersion"],
+            SUPPORTED_PROTOCOL_VERSIONS[0]
+        );
+
+        let notification = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        assert!(server.handle_message(notification).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn tools_list_offers_enabled_agents() {
+        let (server, _tmp) = make_server().await;
+        let response = call(&server, 1, "tools/list", json!({})).await;
+        let tools = response["result"]["tools"].as_array().unwrap();
+        let names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "memory_search",
+                "memory_get",
+                "delegate_task",
+                "send_message"
+            ]
+        );
+        assert_eq!(
+            tools[0]["inputSchema"]["properties"]["agent_id"]["enum"],
+            json!(["clawhive-main"])
+        );
+    }
+
+    #[tokio::test]
+    async fn send_message_runs_agent_turn_through_gateway() {
+        let (server, _tmp) = make_server().await;
+        let response = call(
+            &server,
+            1,
+            "tools/call",
+            json!({
+                "name": "send_message",
+                "arguments": {"agent_id": "clawhive-main", "message": "ping", "conversation_id": "c1"}
+            }),
+        )
+        .await;
+        let result = &response["result"];
+        assert_eq!(result["isError"], false);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("stub:anthropic:claude-sonnet-4-5"));
+        assert!(text.contains("[conversation_id: c1]"));
+    }
+
+    #[tokio::test]
+    async fn callers_sharing_a_transport_stay_isolated() {
+        let (gateway, mut incoming, _accepted, _tmp) = make_gateway_with_receivers().await;
+        add_catch_all_binding(&gateway);
+        let gateway = Arc::new(gateway);
+
+        let mut sessions = Vec::new();
+        let mut user_scopes = Vec::new();
+        for caller in ["client-a", "client-b"] {
+            let server = McpServer::new(Arc::clone(&gateway), "http").with_caller(caller);
+            let response = call(
+                &server,
+                1,
+                "tools/call",
+                json!({
+                    "name": "send_message",
+                    "arguments": {"agent_id": "clawhive-main", "message": "hi"}
+                }),
+            )
+            .await;
+            assert_eq!(response["result"]["isError"], false);
+            let Some(BusMessage::HandleIncomingMessage { inbound, .. }) = incoming.recv().await
+            else {
+                panic!("expected the turn's inbound message");
+            };
+            user_scopes.push(inbound.user_scope.clone());
+            sessions.push(SessionKey::from_inbound(&inbound).0);
+        }
+
+        assert_ne!(user_scopes[0], user_scopes[1]);
+        assert_ne!(sessions[0], sessions[1]);
+        let a = MemoryScope::from_session_key(&sessions[0]).unwrap();
+        let b = MemoryScope::from_session_key(&sessions[1]).unwrap();
+        assert_ne!(a.user_partition(), b.user_partition());
+        assert_ne!(a.conversation_partition(), b.conversation_partition());
+    }
+
+    #[tokio::test]
+    async fn memory_get_reads_agent_memory() {
+        let (server, _tmp) = make_server().await;
+        let response = call(
+            &server,
+            1,
+            "tools/call",
+            json!({
+                "name": "memory_get",
+                "arguments": {"agent_id": "clawhive-main", "key": "MEMORY.md"}
+            }),
+        )
+        .await;
+        assert_eq!(response["result"]["isError"], false);
+    }
+
+    #[tokio::test]
+    async fn unknown_agent_is_a_tool_error() {
+        let (server, _tmp) = make_server().await;
+        let response = call(
+            &server,
+            1,
+            "tools/call",
+            json!({
+                "name": "delegate_task",
+                "arguments": {"agent_id": "nobody", "task": "do it"}
+            }),
+        )
+        .await;
+        assert_eq!(response["result"]["isError"], true);
+        assert!(response["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("unknown or disabled agent"));
+    }
+
+    #[tokio::test]
+    async fn protocol_errors_use_jsonrpc_codes() {
+        let (server, _tmp) = make_server().await;
+        let response = call(&server, 1, "resources/list", json!({})).await;
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = call(&server, 2, "tools/call", json!({"name": "memory_get"})).await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let response = handle_raw_message(&server, "{not json").await.unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn serve_stdio_answers_each_request_line() {
+        let (server, _tmp) = make_server().await;
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#,
+            "\n",
+        );
+        let (mut client, server_io) = tokio::io::duplex(64 * 1024);
+        serve_stdio(
+            server,
+            tokio::io::BufReader::new(input.as_bytes()),
+            server_io,
+        )
+        .await
+        .unwrap();
+
+        let mut output = String::new();
+        tokio::io::AsyncReadExt::read_to_string(&mut client, &mut output)
+            .await
+            .unwrap();
+        let ids: std::collections::BTreeSet<u64> = output
+            .lines()
+            .map(|l| {
+                serde_json::from_str::<Value>(l).unwrap()["id"]
+                    .as_u64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(ids, [1, 2].into());
+    }
+}
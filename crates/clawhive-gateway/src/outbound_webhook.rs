@@ -0,0 +1,861 @@
+//! Outbound webhook subscriptions.
+//!
+//! Forwards selected bus topics to HTTP endpoints. Each request carries the
+//! topic, a delivery id and an HMAC-SHA256 signature over
+//! `{timestamp}.{body}`, so a clawhive `timestamped_hmac` webhook source can
+//! verify it as is. Failed deliveries are retried on the scheduler's error
+//! backoff; once attempts run out the event lands in a dead-letter table
+//! where it can be inspected and retried.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use clawhive_bus::{EventBus, Topic};
+use clawhive_schema::BusMessage;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::{watch, RwLock};
+use uuid::Uuid;
+
+pub const EVENT_HEADER: &str = "X-Clawhive-Event";
+pub const DELIVERY_HEADER: &str = "X-Clawhive-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-Clawhive-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Clawhive-Signature";
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+/// How much of an endpoint's error response is kept in `last_error`.
+const MAX_ERROR_BODY_CHARS: usize = 512;
+/// Token-level deltas are far too chatty to forward one request each.
+const UNFORWARDABLE_TOPICS: &[Topic] = &[Topic::StreamDelta];
+
+/// Whether `topic` names a bus topic subscriptions may select.
+pub fn is_forwardable_topic(topic: &str) -> bool {
+    Topic::from_name(topic).is_some_and(|topic| !UNFORWARDABLE_TOPICS.contains(&topic))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboundWebhook {
+    pub id: String,
+    pub url: String,
+    /// Topic names (`ReplyReady`, `TaskFailed`, ...).
+    pub topics: Vec<String>,
+    pub secret: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl OutboundWebhook {
+    fn wants(&self, topic: &str) -> bool {
+        self.enabled && self.topics.iter().any(|wanted| wanted == topic)
+    }
+}
+
+/// An event that exhausted its delivery attempts.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub id: i64,
+    pub webhook_id: String,
+    pub topic: String,
+    pub delivery_id: String,
+    /// The exact request body that was sent.
+    pub payload: String,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Body of every outbound request.
+#[derive(Debug, Serialize)]
+struct OutboundEvent<'a> {
+    id: &'a str,
+    topic: &'a str,
+    created_at: DateTime<Utc>,
+    event: &'a BusMessage,
+}
+
+/// SQLite persistence for subscriptions and dead letters.
+pub struct OutboundWebhookStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl OutboundWebhookStore {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)?;
+        conn.execute_batch(
+            r#"PRAGMA journal_mode=WAL;
+               PRAGMA synchronous=NORMAL;
+               CREATE TABLE IF NOT EXISTS outbound_webhooks (
+                   id TEXT PRIMARY KEY,
+                   url TEXT NOT NULL,
+                   topics TEXT NOT NULL,
+                   secret TEXT NOT NULL,
+                   description TEXT,
+                   enabled INTEGER NOT NULL DEFAULT 1,
+                   created_at TEXT NOT NULL
+               );
+               CREATE TABLE IF NOT EXISTS outbound_webhook_dead_letters (
+                   id INTEGER PRIMARY KEY AUTOINCREMENT,
+                   webhook_id TEXT NOT NULL,
+                   topic TEXT NOT NULL,
+                   delivery_id TEXT NOT NULL,
+                   payload TEXT NOT NULL,
+                   attempts INTEGER NOT NULL,
+                   last_error TEXT NOT NULL,
+                   failed_at TEXT NOT NULL
+               );
+               CREATE INDEX IF NOT EXISTS idx_outbound_dead_letters_webhook
+                   ON outbound_webhook_dead_letters(webhook_id);"#,
+        )?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Run `f` on the connection off the async runtime.
+    async fn with_conn<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|_| anyhow!("failed to lock outbound webhook store"))?;
+            f(&conn)
+        })
+        .await?
+    }
+
+    pub async fn list(&self) -> Result<Vec<OutboundWebhook>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, url, topics, secret, description, enabled, created_at
+                 FROM outbound_webhooks ORDER BY created_at",
+            )?;
+            let rows = stmt.query_map([], row_to_webhook)?;
+            Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+        })
+        .await
+    }
+
+    /// Insert or replace by id.
+    pub async fn save(&self, webhook: &OutboundWebhook) -> Result<()> {
+        let webhook = webhook.clone();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO outbound_webhooks
+                     (id, url, topics, secret, description, enabled, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    webhook.id,
+                    webhook.url,
+                    serde_json::to_string(&webhook.topics)?,
+                    webhook.secret,
+                    webhook.description,
+                    webhook.enabled,
+                    webhook.created_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let id = id.to_owned();
+        self.with_conn(move |conn| {
+            let removed =
+                conn.execute("DELETE FROM outbound_webhooks WHERE id = ?1", params![id])?;
+            Ok(removed > 0)
+        })
+        .await
+    }
+
+    pub async fn record_dead_letter(
+        &self,
+        webhook_id: &str,
+        topic: &str,
+        delivery_id: &str,
+        payload: &str,
+        attempts: u32,
+        last_error: &str,
+    ) -> Result<i64> {
+        let (webhook_id, topic, delivery_id, payload, last_error) = (
+            webhook_id.to_owned(),
+            topic.to_owned(),
+            delivery_id.to_owned(),
+            payload.to_owned(),
+            last_error.to_owned(),
+        );
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO outbound_webhook_dead_letters
+                     (webhook_id, topic, delivery_id, payload, attempts, last_error, failed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    webhook_id,
+                    topic,
+                    delivery_id,
+                    payload,
+                    attempts,
+                    last_error,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    /// Newest first, optionally for one subscription.
+    pub async fn dead_letters(&self, webhook_id: Option<&str>) -> Result<Vec<DeadLetter>> {
+        let webhook_id = webhook_id.map(str::to_owned);
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, webhook_id, topic, delivery_id, payload, attempts, last_error, failed_at
+                 FROM outbound_webhook_dead_letters
+                 WHERE ?1 IS NULL OR webhook_id = ?1
+                 ORDER BY id DESC",
+            )?;
+            let rows = stmt.query_map(params![webhook_id], row_to_dead_letter)?;
+            Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+        })
+        .await
+    }
+
+    pub async fn dead_letter(&self, id: i64) -> Result<Option<DeadLetter>> {
+        self.with_conn(move |conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT id, webhook_id, topic, delivery_id, payload, attempts, last_error, failed_at
+                     FROM outbound_webhook_dead_letters WHERE id = ?1",
+                    params![id],
+                    row_to_dead_letter,
+                )
+                .optional()?)
+        })
+        .await
+    }
+
+    pub async fn delete_dead_letter(&self, id: i64) -> Result<bool> {
+        self.with_conn(move |conn| {
+            let removed = conn.execute(
+                "DELETE FROM outbound_webhook_dead_letters WHERE id = ?1",
+                params![id],
+            )?;
+            Ok(removed > 0)
+        })
+        .await
+    }
+
+    async fn update_dead_letter(&self, id: i64, attempts: u32, last_error: &str) -> Result<()> {
+        let last_error = last_error.to_owned();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "UPDATE outbound_webhook_dead_letters
+                 SET attempts = ?2, last_error = ?3, failed_at = ?4 WHERE id = ?1",
+                params![id, attempts, last_error, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+fn parse_timestamp(index: usize, value: String) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&value)
+        .map(|at| at.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, e.into())
+        })
+}
+
+fn row_to_webhook(row: &rusqlite::Row) -> rusqlite::Result<OutboundWebhook> {
+    let topics: String = row.get(2)?;
+    Ok(OutboundWebhook {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        topics: serde_json::from_str(&topics).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, e.into())
+        })?,
+        secret: row.get(3)?,
+        description: row.get(4)?,
+        enabled: row.get(5)?,
+        created_at: parse_timestamp(6, row.get(6)?)?,
+    })
+}
+
+fn row_to_dead_letter(row: &rusqlite::Row) -> rusqlite::Result<DeadLetter> {
+    Ok(DeadLetter {
+        id: row.get(0)?,
+        webhook_id: row.get(1)?,
+        topic: row.get(2)?,
+        delivery_id: row.get(3)?,
+        payload: row.get(4)?,
+        attempts: row.get(5)?,
+        last_error: row.get(6)?,
+        failed_at: parse_timestamp(7, row.get(7)?)?,
+    })
+}
+
+/// Why a single delivery attempt failed.
+enum DeliveryError {
+    /// Worth retrying: network errors, timeouts, 408, 429 and 5xx.
+    Transient(String),
+    /// The endpoint rejected the event; retrying will not help.
+    Permanent(String),
+}
+
+impl DeliveryError {
+    fn message(&self) -> &str {
+        match self {
+            Self::Transient(message) | Self::Permanent(message) => message,
+        }
+    }
+}
+
+/// Subscription registry and delivery engine. Routes mutate subscriptions
+/// through it so the in-memory copy the dispatcher reads stays current.
+pub struct OutboundWebhooks {
+    store: OutboundWebhookStore,
+    webhooks: RwLock<Vec<OutboundWebhook>>,
+    client: Client,
+    max_attempts: u32,
+    backoff: fn(u32) -> Duration,
+    /// Bumped on every change so the dispatcher can resubscribe.
+    changed: watch::Sender<()>,
+}
+
+impl OutboundWebhooks {
+    pub async fn new(store: OutboundWebhookStore) -> Result<Self> {
+        let webhooks = store.list().await?;
+        Ok(Self {
+            store,
+            webhooks: RwLock::new(webhooks),
+            client: Client::builder().timeout(DELIVERY_TIMEOUT).build()?,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            backoff: |attempt| Duration::from_millis(clawhive_scheduler::error_backoff_ms(attempt)),
+            changed: watch::channel(()).0,
+        })
+    }
+
+    /// Attempts per event before it is dead-lettered (at least one).
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay after the n-th consecutive failure.
+    pub fn with_backoff(mut self, backoff: fn(u32) -> Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn store(&self) -> &OutboundWebhookStore {
+        &self.store
+    }
+
+    pub async fn list(&self) -> Vec<OutboundWebhook> {
+        self.webhooks.read().await.clone()
+    }
+
+    pub async fn get(&self, id: &str) -> Option<OutboundWebhook> {
+        self.webhooks
+            .read()
+            .await
+            .iter()
+            .find(|webhook| webhook.id == id)
+            .cloned()
+    }
+
+    /// Create or replace a subscription.
+    pub async fn save(&self, webhook: OutboundWebhook) -> Result<()> {
+        if let Some(topic) = webhook
+            .topics
+            .iter()
+            .find(|topic| !is_forwardable_topic(topic))
+        {
+            return Err(anyhow!("unknown or unforwardable topic: {topic}"));
+        }
+        self.store.save(&webhook).await?;
+        let mut webhooks = self.webhooks.write().await;
+        match webhooks
+            .iter_mut()
+            .find(|existing| existing.id == webhook.id)
+        {
+            Some(existing) => *existing = webhook,
+            None => webhooks.push(webhook),
+        }
+        self.changed.send_replace(());
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let removed = self.store.delete(id).await?;
+        self.webhooks
+            .write()
+            .await
+            .retain(|webhook| webhook.id != id);
+        self.changed.send_replace(());
+        Ok(removed)
+    }
+
+    /// Topics at least one enabled subscription selects.
+    async fn wanted_topics(&self) -> Vec<Topic> {
+        let topics: HashSet<Topic> = self
+            .webhooks
+            .read()
+            .await
+            .iter()
+            .filter(|webhook| webhook.enabled)
+            .flat_map(|webhook| &webhook.topics)
+            .filter_map(|topic| Topic::from_name(topic))
+            .filter(|topic| !UNFORWARDABLE_TOPICS.contains(topic))
+            .collect();
+        topics.into_iter().collect()
+    }
+
+    /// Fan `msg` out to every enabled subscription on its topic. Deliveries
+    /// run in the background.
+    pub async fn dispatch(self: &Arc<Self>, msg: &BusMessage) {
+        let topic = Topic::from_message(msg).as_str();
+        let targets: Vec<OutboundWebhook> = self
+            .webhooks
+            .read()
+            .await
+            .iter()
+            .filter(|webhook| webhook.wants(topic))
+            .cloned()
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+
+        let delivery_id = Uuid::new_v4().to_string();
+        let body = match serde_json::to_string(&OutboundEvent {
+            id: &delivery_id,
+            topic,
+            created_at: Utc::now(),
+            event: msg,
+        }) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!(topic, error = %e, "failed to serialize outbound webhook event");
+                return;
+            }
+        };
+        for webhook in targets {
+            let this = Arc::clone(self);
+            let delivery_id = delivery_id.clone();
+            let body = body.clone();
+            tokio::spawn(async move {
+                this.deliver_with_retry(&webhook, topic, &delivery_id, &body)
+                    .await;
+            });
+        }
+    }
+
+    async fn deliver_with_retry(
+        &self,
+        webhook: &OutboundWebhook,
+        topic: &str,
+        delivery_id: &str,
+        body: &str,
+    ) {
+        let mut attempt = 0;
+        let error = loop {
+            attempt += 1;
+            match self.send(webhook, topic, delivery_id, body).await {
+                Ok(()) => return,
+                Err(DeliveryError::Transient(message)) if attempt < self.max_attempts => {
+                    tracing::debug!(
+                        webhook_id = %webhook.id,
+                        delivery_id,
+                        attempt,
+                        error = %message,
+                        "outbound webhook delivery failed, retrying"
+                    );
+                    tokio::time::sleep((self.backoff)(attempt)).await;
+                }
+                Err(error) => break error,
+            }
+        };
+
+        tracing::warn!(
+            webhook_id = %webhook.id,
+            topic,
+            delivery_id,
+            attempts = attempt,
+            error = %error.message(),
+            "outbound webhook delivery dead-lettered"
+        );
+        if let Err(e) = self
+            .store
+            .record_dead_letter(
+                &webhook.id,
+                topic,
+                delivery_id,
+                body,
+                attempt,
+                error.message(),
+            )
+            .await
+        {
+            tracing::error!(webhook_id = %webhook.id, error = %e, "failed to record dead letter");
+        }
+    }
+
+    async fn send(
+        &self,
+        webhook: &OutboundWebhook,
+        topic: &str,
+        delivery_id: &str,
+        body: &str,
+    ) -> std::result::Result<(), DeliveryError> {
+        let timestamp = Utc::now().timestamp().to_string();
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("User-Agent", "Clawhive-Webhooks/1.0")
+            .header(EVENT_HEADER, topic)
+            .header(DELIVERY_HEADER, delivery_id)
+            .header(TIMESTAMP_HEADER, &timestamp)
+            .header(SIGNATURE_HEADER, sign(&webhook.secret, &timestamp, body))
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| DeliveryError::Transient(format!("request failed: {e}")))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let text = response.text().await.unwrap_or_default();
+        let message = format!("endpoint returned {status}: {}", truncate_error_body(&text));
+        if status.is_server_error()
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        {
+            Err(DeliveryError::Transient(message))
+        } else {
+            Err(DeliveryError::Permanent(message))
+        }
+    }
+
+    /// Send a dead letter once more. It is removed on success; on failure the
+    /// attempt count and error are updated and the error returned.
+    pub async fn retry_dead_letter(&self, letter: &DeadLetter) -> Result<()> {
+        let webhook = self
+            .get(&letter.webhook_id)
+            .await
+            .ok_or_else(|| anyhow!("outbound webhook not found: {}", letter.webhook_id))?;
+        match self
+            .send(
+                &webhook,
+                &letter.topic,
+                &letter.delivery_id,
+                &letter.payload,
+            )
+            .await
+        {
+            Ok(()) => {
+                self.store.delete_dead_letter(letter.id).await?;
+                Ok(())
+            }
+            Err(error) => {
+                self.store
+                    .update_dead_letter(letter.id, letter.attempts + 1, error.message())
+                    .await?;
+                Err(anyhow!("{}", error.message()))
+            }
+        }
+    }
+}
+
+/// Error pages can be large; keep the start of the body for the dead letter.
+fn truncate_error_body(text: &str) -> String {
+    match text.char_indices().nth(MAX_ERROR_BODY_CHARS) {
+        Some((end, _)) => format!("{}… (truncated)", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// `sha256=<hex>` over `{timestamp}.{body}`.
+pub fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Forward bus messages to the matching subscriptions. Only the topics some
+/// enabled subscription selects are subscribed to, so with none configured
+/// the dispatcher stays off the bus; it resubscribes whenever they change.
+pub fn spawn_outbound_webhook_dispatcher(
+    webhooks: Arc<OutboundWebhooks>,
+    bus: Arc<EventBus>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut changed = webhooks.changed.subscribe();
+        loop {
+            changed.borrow_and_update();
+            let topics = webhooks.wanted_topics().await;
+            if topics.is_empty() {
+                if changed.changed().await.is_err() {
+                    return;
+                }
+                continue;
+            }
+
+            let mut rx = bus.subscribe_many(&topics).await;
+            loop {
+                tokio::select! {
+                    msg = rx.recv() => match msg {
+                        Some(msg) => webhooks.dispatch(&msg).await,
+                        None => return,
+                    },
+                    result = changed.changed() => {
+                        if result.is_err() {
+                            return;
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{header, header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    fn webhook(url: String, topics: &[&str]) -> OutboundWebhook {
+        OutboundWebhook {
+            id: Uuid::new_v4().to_string(),
+            url,
+            topics: topics.iter().map(|topic| topic.to_string()).collect(),
+            secret: "whsec_test".into(),
+            description: None,
+            enabled: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn task_failed() -> BusMessage {
+        BusMessage::TaskFailed {
+            agent_id: None,
+            trace_id: Uuid::new_v4(),
+            error: "provider timeout".into(),
+        }
+    }
+
+    async fn webhooks(tmp: &tempfile::TempDir) -> Arc<OutboundWebhooks> {
+        let store = OutboundWebhookStore::open(&tmp.path().join("data/outbound.db")).unwrap();
+        Arc::new(
+            OutboundWebhooks::new(store)
+                .await
+                .unwrap()
+                .with_max_attempts(3)
+                .with_backoff(|_| Duration::from_millis(1)),
+        )
+    }
+
+    async fn wait_for_dead_letters(webhooks: &OutboundWebhooks, count: usize) -> Vec<DeadLetter> {
+        for _ in 0..200 {
+            let letters = webhooks.store().dead_letters(None).await.unwrap();
+            if letters.len() >= count {
+                return letters;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("expected {count} dead letters");
+    }
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let signature = sign("secret", "1700000000", "{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_ne!(signature, sign("secret", "1700000001", "{}"));
+        assert!(is_forwardable_topic("ReplyReady"));
+        assert!(!is_forwardable_topic("StreamDelta"));
+        assert!(!is_forwardable_topic("Nope"));
+    }
+
+    #[tokio::test]
+    async fn delivers_signed_events_for_subscribed_topics() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/events"))
+            .and(header(EVENT_HEADER, "TaskFailed"))
+            .and(header_exists(DELIVERY_HEADER))
+            .and(header_exists(SIGNATURE_HEADER))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let webhooks = webhooks(&tmp).await;
+        webhooks
+            .save(webhook(format!("{}/events", server.uri()), &["TaskFailed"]))
+            .await
+            .unwrap();
+        let bus = Arc::new(EventBus::new(16));
+        let _dispatcher = spawn_outbound_webhook_dispatcher(Arc::clone(&webhooks), bus.clone());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        bus.publish(BusMessage::MessageAccepted {
+            trace_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+        bus.publish(task_failed()).await.unwrap();
+
+        for _ in 0..200 {
+            if !server.received_requests().await.unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        let body = String::from_utf8(request.body.clone()).unwrap();
+        let timestamp = request.headers[TIMESTAMP_HEADER].to_str().unwrap();
+        assert_eq!(
+            request.headers[SIGNATURE_HEADER].to_str().unwrap(),
+            sign("whsec_test", timestamp, &body)
+        );
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["topic"], "TaskFailed");
+        assert_eq!(json["event"]["TaskFailed"]["error"], "provider timeout");
+        assert_eq!(
+            json["id"],
+            request.headers[DELIVERY_HEADER].to_str().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_are_dead_lettered_and_can_be_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(3)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let webhooks = webhooks(&tmp).await;
+        webhooks
+            .save(webhook(server.uri(), &["TaskFailed"]))
+            .await
+            .unwrap();
+
+        webhooks.dispatch(&task_failed()).await;
+        let letters = wait_for_dead_letters(&webhooks, 1).await;
+        assert_eq!(letters[0].attempts, 3);
+        assert!(letters[0].last_error.contains("503"));
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+
+        webhooks.retry_dead_letter(&letters[0]).await.unwrap();
+        assert!(webhooks
+            .store()
+            .dead_letters(None)
+            .await
+            .unwrap()
+            .is_empty());
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[3].body, letters[0].payload.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn dispatcher_subscribes_only_to_selected_topics() {
+        let tmp = tempfile::tempdir().unwrap();
+        let webhooks = webhooks(&tmp).await;
+        let bus = Arc::new(EventBus::new(16));
+        let _dispatcher = spawn_outbound_webhook_dispatcher(Arc::clone(&webhooks), bus.clone());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(bus.queue_depths().await.is_empty());
+
+        let hook = webhook("https://example.com/hook".into(), &["TaskFailed"]);
+        webhooks.save(hook.clone()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let topics: Vec<_> = bus.queue_depths().await.into_keys().collect();
+        assert_eq!(topics, ["TaskFailed"]);
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(410).set_body_string(format!("gone{}", "!".repeat(10_000))),
+            )
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let webhooks = webhooks(&tmp).await;
+        let hook = webhook(server.uri(), &["TaskFailed"]);
+        webhooks.save(hook.clone()).await.unwrap();
+
+        webhooks.dispatch(&task_failed()).await;
+        let letters = wait_for_dead_letters(&webhooks, 1).await;
+        assert_eq!(letters[0].attempts, 1);
+        assert_eq!(letters[0].webhook_id, hook.id);
+        assert!(letters[0].last_error.contains("gone"));
+        assert!(letters[0].last_error.ends_with("(truncated)"));
+        assert!(letters[0].last_error.len() < 1_000);
+    }
+
+    #[tokio::test]
+    async fn subscriptions_persist_and_reject_unknown_topics() {
+        let tmp = tempfile::tempdir().unwrap();
+        let webhooks = webhooks(&tmp).await;
+        let mut hook = webhook("https://example.com/hook".into(), &["ReplyReady"]);
+        webhooks.save(hook.clone()).await.unwrap();
+
+        hook.enabled = false;
+        webhooks.save(hook.clone()).await.unwrap();
+        let reopened = OutboundWebhooks::new(
+            OutboundWebhookStore::open(&tmp.path().join("data/outbound.db")).unwrap(),
+        )
+        .await
+        .unwrap();
+        let listed = reopened.list().await;
+        assert_eq!(listed.len(), 1);
+        assert!(!listed[0].enabled);
+        assert_eq!(listed[0].topics, vec!["ReplyReady".to_string()]);
+
+        let bad = webhook("https://example.com/hook".into(), &["StreamDelta"]);
+        assert!(webhooks.save(bad).await.is_err());
+        assert!(webhooks.delete(&hook.id).await.unwrap());
+        assert!(webhooks.list().await.is_empty());
+    }
+}
@@ -185,7 +185,7 @@ impl ReloadCoordinator {
         config: &ClawhiveConfig,
         generation: u64,
     ) -> Result<ConfigView> {
-        Ok(build_config_view(
+        build_config_view(
             config,
             generation,
             &self.root,
@@ -194,7 +194,7 @@ impl ReloadCoordinator {
             &self.publisher,
             Arc::clone(&self.schedule_manager),
         )
-        .await)
+        .await
     }
 
     async fn reconcile_channels(&self, new_cfg: &ClawhiveConfig) -> Vec<ChannelChangeResult> {
@@ -309,6 +309,24 @@ fn extract_connectors(
             }
         }
     }
+    if let Some(mx) = &channels.matrix {
+        if mx.enabled {
+            for c in &mx.connectors {
+                if let Ok(v) = serde_json::to_value(c) {
+                    map.insert(c.connector_id.clone(), ("matrix".into(), v));
+                }
+            }
+        }
+    }
+    if let Some(em) = &channels.email {
+        if em.enabled {
+            for c in &em.connectors {
+                if let Ok(v) = serde_json::to_value(c) {
+                    map.insert(c.connector_id.clone(), ("email".into(), v));
+                }
+            }
+        }
+    }
     if let Some(wa) = &channels.whatsapp {
         if wa.enabled {
             for c in &wa.connectors {
@@ -433,7 +451,8 @@ mod tests {
             &publisher,
             Arc::clone(&schedule_manager),
         )
-        .await;
+        .await
+        .unwrap();
         let orchestrator = Arc::new(
             OrchestratorBuilder::new(
                 config_view,
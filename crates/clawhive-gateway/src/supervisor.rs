@@ -280,8 +280,10 @@ mod tests {
                 thinking_level: None,
                 context_window: None,
                 compaction_model: None,
+                sampling: Default::default(),
             },
             tool_policy: None,
+            mcp_servers: vec![],
             memory_policy: None,
             sub_agent: None,
             workspace: None,
@@ -293,6 +295,7 @@ mod tests {
             turn_timeout_secs: None,
             typing_ttl_secs: None,
             progress_delay_secs: None,
+            reply_voice: None,
         }];
         let personas = HashMap::new();
         let routing = RoutingConfig {
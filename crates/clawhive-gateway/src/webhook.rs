@@ -23,7 +23,7 @@ pub struct WebhookPayload {
     pub duration_ms: u64,
 }
 
-fn validate_webhook_url(url: &str) -> Result<()> {
+pub fn validate_webhook_url(url: &str) -> Result<()> {
     let parsed = reqwest::Url::parse(url).map_err(|e| anyhow!("invalid webhook_url: {e}"))?;
     match parsed.scheme() {
         "http" | "https" => {}
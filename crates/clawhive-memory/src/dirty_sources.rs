@@ -43,56 +43,72 @@ impl DirtySourceStore {
         reason: &str,
     ) -> Result<()> {
         let db = Arc::clone(&self.db);
-        let item = DirtySource {
-            id: Uuid::new_v4().to_string(),
-            agent_id: agent_id.to_owned(),
-            source_kind: source_kind.to_owned(),
-            source_ref: source_ref.to_owned(),
-            reason: reason.to_owned(),
-            created_at: Utc::now().to_rfc3339(),
-            processed_at: None,
-        };
+        let agent_id = agent_id.to_owned();
+        let source_kind = source_kind.to_owned();
+        let source_ref = source_ref.to_owned();
+        let reason = reason.to_owned();
         task::spawn_blocking(move || {
             let conn = db
                 .lock()
                 .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
-            conn.execute(
-                "INSERT INTO dirty_sources (id, agent_id, source_kind, source_ref, reason, created_at, processed_at) \
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL) \
-                 ON CONFLICT(agent_id, source_kind, source_ref) DO UPDATE SET \
-                    reason = excluded.reason, \
-                    created_at = excluded.created_at, \
-                    processed_at = NULL",
-                params![
-                    item.id,
-                    item.agent_id,
-                    item.source_kind,
-                    item.source_ref,
-                    item.reason,
-                    item.created_at,
-                ],
-            )?;
-            Ok::<(), anyhow::Error>(())
+            enqueue_on(&conn, &agent_id, &source_kind, &source_ref, &reason)
         })
         .await??;
         Ok(())
     }
 
     pub async fn list_pending(&self, agent_id: &str, limit: usize) -> Result<Vec<DirtySource>> {
+        self.query_pending(agent_id, None, limit).await
+    }
+
+    /// Pending items of one `source_kind`, oldest first.
+    pub async fn list_pending_of_kind(
+        &self,
+        agent_id: &str,
+        source_kind: &str,
+        limit: usize,
+    ) -> Result<Vec<DirtySource>> {
+        self.query_pending(agent_id, Some((source_kind, true)), limit)
+            .await
+    }
+
+    /// Pending items of every kind except `source_kind`, oldest first.
+    pub async fn list_pending_except_kind(
+        &self,
+        agent_id: &str,
+        source_kind: &str,
+        limit: usize,
+    ) -> Result<Vec<DirtySource>> {
+        self.query_pending(agent_id, Some((source_kind, false)), limit)
+            .await
+    }
+
+    async fn query_pending(
+        &self,
+        agent_id: &str,
+        kind_filter: Option<(&str, bool)>,
+        limit: usize,
+    ) -> Result<Vec<DirtySource>> {
         let db = Arc::clone(&self.db);
         let agent_id = agent_id.to_owned();
+        let (kind_clause, kind) = match kind_filter {
+            Some((kind, true)) => ("AND source_kind = ?3", Some(kind.to_owned())),
+            Some((kind, false)) => ("AND source_kind != ?3", Some(kind.to_owned())),
+            None => ("AND ?3 IS NULL", None),
+        };
         task::spawn_blocking(move || {
             let conn = db
                 .lock()
                 .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare(&format!(
                 "SELECT id, agent_id, source_kind, source_ref, reason, created_at, processed_at \
                  FROM dirty_sources \
-                 WHERE agent_id = ?1 AND processed_at IS NULL \
+                 WHERE agent_id = ?1 AND processed_at IS NULL {kind_clause} \
                  ORDER BY created_at ASC \
-                 LIMIT ?2",
-            )?;
-            let rows = stmt.query_map(params![agent_id, limit as i64], row_to_dirty_source)?;
+                 LIMIT ?2"
+            ))?;
+            let rows =
+                stmt.query_map(params![agent_id, limit as i64, kind], row_to_dirty_source)?;
             let mut items = Vec::new();
             for row in rows {
                 items.push(row?);
@@ -138,6 +154,34 @@ impl DirtySourceStore {
     }
 }
 
+/// Queue a source for reindexing on a connection the caller already holds, so
+/// stores can mark their rows dirty inside their own write.
+pub(crate) fn enqueue_on(
+    conn: &Connection,
+    agent_id: &str,
+    source_kind: &str,
+    source_ref: &str,
+    reason: &str,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO dirty_sources (id, agent_id, source_kind, source_ref, reason, created_at, processed_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL) \
+         ON CONFLICT(agent_id, source_kind, source_ref) DO UPDATE SET \
+            reason = excluded.reason, \
+            created_at = excluded.created_at, \
+            processed_at = NULL",
+        params![
+            Uuid::new_v4().to_string(),
+            agent_id,
+            source_kind,
+            source_ref,
+            reason,
+            Utc::now().to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
 fn row_to_dirty_source(row: &rusqlite::Row<'_>) -> rusqlite::Result<DirtySource> {
     Ok(DirtySource {
         id: row.get(0)?,
@@ -199,4 +243,33 @@ mod tests {
 
         assert_eq!(dirty.pending_count("agent-1").await.unwrap(), 0);
     }
+
+    #[tokio::test]
+    async fn kind_filters_split_pending_items() {
+        let store = MemoryStore::open_in_memory().unwrap();
+        let dirty = DirtySourceStore::new(store.db());
+
+        dirty
+            .enqueue("agent-1", DIRTY_KIND_FACT, "fact-1", "fact_added")
+            .await
+            .unwrap();
+        dirty
+            .enqueue("agent-1", DIRTY_KIND_SESSION, "s1", "append")
+            .await
+            .unwrap();
+
+        let facts = dirty
+            .list_pending_of_kind("agent-1", DIRTY_KIND_FACT, 10)
+            .await
+            .unwrap();
+        let others = dirty
+            .list_pending_except_kind("agent-1", DIRTY_KIND_FACT, 10)
+            .await
+            .unwrap();
+        assert_eq!(facts.len(), 1);
+        assert_eq!(facts[0].source_ref, "fact-1");
+        assert_eq!(others.len(), 1);
+        assert_eq!(others[0].source_kind, DIRTY_KIND_SESSION);
+        assert_eq!(dirty.list_pending("agent-1", 10).await.unwrap().len(), 2);
+    }
 }
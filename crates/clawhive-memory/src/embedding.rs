@@ -618,6 +618,31 @@ impl<P: EmbeddingProvider + 'static> EmbeddingProvider for CachedEmbeddingProvid
     fn dimensions(&self) -> usize {
         self.inner.dimensions()
     }
+
+    fn is_semantic(&self) -> bool {
+        self.inner.is_semantic()
+    }
+}
+
+/// Lets a shared `Arc<dyn EmbeddingProvider>` be wrapped, e.g. by
+/// [`CachedEmbeddingProvider`].
+#[async_trait]
+impl<P: EmbeddingProvider + ?Sized> EmbeddingProvider for Arc<P> {
+    async fn embed(&self, texts: &[String]) -> Result<EmbeddingResult> {
+        (**self).embed(texts).await
+    }
+
+    fn model_id(&self) -> &str {
+        (**self).model_id()
+    }
+
+    fn dimensions(&self) -> usize {
+        (**self).dimensions()
+    }
+
+    fn is_semantic(&self) -> bool {
+        (**self).is_semantic()
+    }
 }
 
 #[cfg(test)]
@@ -744,4 +769,24 @@ mod tests {
         assert_eq!(ordered[1], vec![0.5, 0.6]);
         assert_eq!(ordered[2], vec![0.9, 0.8]);
     }
+
+    #[tokio::test]
+    async fn cached_provider_wraps_shared_provider() {
+        let store = Arc::new(MemoryStore::open_in_memory().unwrap());
+        let inner: Arc<dyn EmbeddingProvider> = Arc::new(StubEmbeddingProvider::new(8));
+        let cached = CachedEmbeddingProvider::new(inner, Arc::clone(&store), "stub");
+
+        let texts = vec!["coffee preference".to_string()];
+        let first = cached.embed(&texts).await.unwrap();
+        let second = cached.embed(&texts).await.unwrap();
+
+        assert_eq!(first.embeddings, second.embeddings);
+        assert!(!cached.is_semantic());
+        let hash = compute_text_hash("coffee preference");
+        assert!(store
+            .get_embedding_cache("stub", "stub", "stub", &hash)
+            .await
+            .unwrap()
+            .is_some());
+    }
 }
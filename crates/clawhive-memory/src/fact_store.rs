@@ -2,13 +2,15 @@ use std::sync::{Arc, Mutex};
 
 use anyhow::{anyhow, Result};
 use chrono::Utc;
-use rusqlite::{params, Connection};
+use rusqlite::{params, params_from_iter, types::Value, Connection};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tokio::task;
 use uuid::Uuid;
 
+use crate::dirty_sources::{enqueue_on, DIRTY_KIND_FACT};
 use crate::memory_lineage::MemoryLineageStore;
+use crate::scope::MemoryScope;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fact {
@@ -34,6 +36,25 @@ pub struct Fact {
     pub affect_intensity: f64,
     pub created_at: String,
     pub updated_at: String,
+    /// Set when the fact is private to one channel user; see [`MemoryScope`].
+    #[serde(default)]
+    pub user_scope: Option<String>,
+    /// Set when the fact is shared only within one conversation.
+    #[serde(default)]
+    pub conversation_scope: Option<String>,
+    /// Pinned by an operator: always injected and exempt from confidence decay.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl Fact {
+    /// The partition this fact was written to.
+    pub fn scope(&self) -> MemoryScope {
+        MemoryScope {
+            user_scope: self.user_scope.clone(),
+            conversation_scope: self.conversation_scope.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +77,25 @@ pub fn generate_fact_id(agent_id: &str, content: &str) -> String {
     Uuid::from_bytes(hash_bytes).to_string()
 }
 
+/// Like [`generate_fact_id`], but the same content written to different
+/// partitions gets different ids. Shared facts keep their original ids.
+pub fn generate_scoped_fact_id(agent_id: &str, scope: &MemoryScope, content: &str) -> String {
+    if scope.is_shared() {
+        return generate_fact_id(agent_id, content);
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(agent_id.as_bytes());
+    for part in [&scope.user_scope, &scope.conversation_scope] {
+        hasher.update([0]);
+        hasher.update(part.as_deref().unwrap_or_default().as_bytes());
+    }
+    hasher.update([0]);
+    hasher.update(content.as_bytes());
+    let hash = hasher.finalize();
+    let hash_bytes: [u8; 16] = hash[..16].try_into().unwrap_or([0u8; 16]);
+    Uuid::from_bytes(hash_bytes).to_string()
+}
+
 pub fn default_salience_for_type(fact_type: &str) -> u8 {
     match fact_type {
         "preference" => 70,
@@ -116,6 +156,32 @@ fn validated_status(value: &str) -> Result<&str> {
     }
 }
 
+/// Which facts [`FactStore::list_facts`] returns. Unset fields match
+/// everything, so the default lists every fact of the agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FactFilter {
+    pub status: Option<String>,
+    pub fact_type: Option<String>,
+    pub user_scope: Option<String>,
+    pub conversation_scope: Option<String>,
+    pub pinned: Option<bool>,
+    /// Case-insensitive substring of the content.
+    pub contains: Option<String>,
+    /// 0 means no limit.
+    pub limit: usize,
+}
+
+/// Fields to change with [`FactStore::edit_fact`]; `None` keeps the value.
+/// Content is not among them: a fact's id is derived from its content, so
+/// new content is a new fact, written with [`FactStore::supersede`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FactEdit {
+    pub fact_type: Option<String>,
+    pub importance: Option<f64>,
+    pub confidence: Option<f64>,
+    pub salience: Option<u8>,
+}
+
 #[derive(Clone)]
 pub struct FactStore {
     db: Arc<Mutex<Connection>>,
@@ -161,8 +227,9 @@ impl FactStore {
                 INSERT INTO facts (
                     id, agent_id, content, fact_type, importance, confidence, salience,
                     status, occurred_at, recorded_at, source_type, source_session,
-                    access_count, last_accessed, superseded_by, supersede_reason, affect, affect_intensity, created_at, updated_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
+                    access_count, last_accessed, superseded_by, supersede_reason, affect, affect_intensity, created_at, updated_at,
+                    user_scope, conversation_scope, pinned
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)
                 "#,
                 params![
                     fact.id,
@@ -193,8 +260,12 @@ impl FactStore {
                     normalize_affect_intensity(fact.affect_intensity),
                     fact.created_at,
                     fact.updated_at,
+                    fact.user_scope,
+                    fact.conversation_scope,
+                    fact.pinned,
                 ],
             )?;
+            enqueue_on(&conn, &fact.agent_id, DIRTY_KIND_FACT, &fact.id, "fact_added")?;
             Ok::<(), anyhow::Error>(())
         })
         .await??;
@@ -250,6 +321,7 @@ impl FactStore {
         Ok(())
     }
 
+    /// Every active fact of the agent, across all partitions.
     pub async fn get_active_facts(&self, agent_id: &str) -> Result<Vec<Fact>> {
         let db = Arc::clone(&self.db);
         let agent_id = agent_id.to_owned();
@@ -260,7 +332,8 @@ impl FactStore {
             let mut stmt = conn.prepare(
                 "SELECT id, agent_id, content, fact_type, importance, confidence, COALESCE(salience, 50), status, \
                  occurred_at, recorded_at, source_type, source_session, access_count, \
-                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at \
+                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at, \
+                 user_scope, conversation_scope, pinned \
                  FROM facts WHERE agent_id = ?1 AND status = 'active' \
                  ORDER BY importance DESC, updated_at DESC",
             )?;
@@ -274,6 +347,43 @@ impl FactStore {
         .await?
     }
 
+    /// Active facts visible to `scope`: the shared layer plus the partitions
+    /// `scope` can read.
+    pub async fn get_active_facts_in_scope(
+        &self,
+        agent_id: &str,
+        scope: &MemoryScope,
+    ) -> Result<Vec<Fact>> {
+        let db = Arc::clone(&self.db);
+        let agent_id = agent_id.to_owned();
+        let scope = scope.clone();
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let mut stmt = conn.prepare(
+                "SELECT id, agent_id, content, fact_type, importance, confidence, COALESCE(salience, 50), status, \
+                 occurred_at, recorded_at, source_type, source_session, access_count, \
+                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at, \
+                 user_scope, conversation_scope, pinned \
+                 FROM facts WHERE agent_id = ?1 AND status = 'active' \
+                 AND (user_scope IS NULL OR user_scope = ?2) \
+                 AND (conversation_scope IS NULL OR conversation_scope = ?3) \
+                 ORDER BY importance DESC, updated_at DESC",
+            )?;
+            let rows = stmt.query_map(
+                params![agent_id, scope.user_scope, scope.conversation_scope],
+                row_to_fact,
+            )?;
+            let mut facts = Vec::new();
+            for row in rows {
+                facts.push(row?);
+            }
+            Ok(facts)
+        })
+        .await?
+    }
+
     pub async fn find_by_id(&self, fact_id: &str) -> Result<Option<Fact>> {
         let id = fact_id.to_owned();
         let db = Arc::clone(&self.db);
@@ -284,7 +394,8 @@ impl FactStore {
             let result = conn.query_row(
                 "SELECT id, agent_id, content, fact_type, importance, confidence, COALESCE(salience, 50), status, \
                  occurred_at, recorded_at, source_type, source_session, access_count, \
-                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at \
+                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at, \
+                 user_scope, conversation_scope, pinned \
                  FROM facts WHERE id = ?1",
                 params![id],
                 row_to_fact,
@@ -308,7 +419,8 @@ impl FactStore {
             let result = conn.query_row(
                 "SELECT id, agent_id, content, fact_type, importance, confidence, COALESCE(salience, 50), status, \
                  occurred_at, recorded_at, source_type, source_session, access_count, \
-                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at \
+                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at, \
+                 user_scope, conversation_scope, pinned \
                  FROM facts WHERE id = ?1",
                 params![id],
                 row_to_fact,
@@ -322,6 +434,17 @@ impl FactStore {
         .await?
     }
 
+    /// Look up a fact by exact content within one write partition.
+    pub async fn find_by_content_in_scope(
+        &self,
+        agent_id: &str,
+        scope: &MemoryScope,
+        content: &str,
+    ) -> Result<Option<Fact>> {
+        self.find_by_id(&generate_scoped_fact_id(agent_id, scope, content))
+            .await
+    }
+
     pub async fn supersede(&self, old_fact_id: &str, new_fact: &Fact, reason: &str) -> Result<()> {
         let db = Arc::clone(&self.db);
         let old_id = old_fact_id.to_owned();
@@ -357,8 +480,9 @@ impl FactStore {
                 INSERT INTO facts (
                     id, agent_id, content, fact_type, importance, confidence, salience,
                     status, occurred_at, recorded_at, source_type, source_session,
-                    access_count, last_accessed, superseded_by, supersede_reason, affect, affect_intensity, created_at, updated_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
+                    access_count, last_accessed, superseded_by, supersede_reason, affect, affect_intensity, created_at, updated_at,
+                    user_scope, conversation_scope, pinned
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)
                 "#,
                 params![
                     new_fact.id,
@@ -389,6 +513,9 @@ impl FactStore {
                     normalize_affect_intensity(new_fact.affect_intensity),
                     new_fact.created_at,
                     new_fact.updated_at,
+                    new_fact.user_scope,
+                    new_fact.conversation_scope,
+                    new_fact.pinned,
                 ],
             )?;
 
@@ -405,6 +532,21 @@ impl FactStore {
                 ],
             )?;
 
+            enqueue_on(
+                &tx,
+                &new_fact.agent_id,
+                DIRTY_KIND_FACT,
+                &old_id,
+                "fact_superseded",
+            )?;
+            enqueue_on(
+                &tx,
+                &new_fact.agent_id,
+                DIRTY_KIND_FACT,
+                &new_fact.id,
+                "fact_superseded",
+            )?;
+
             tx.commit()?;
             Ok(())
         })
@@ -436,10 +578,10 @@ impl FactStore {
                 .lock()
                 .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
 
-            let old_status: String = conn.query_row(
-                "SELECT status FROM facts WHERE id = ?1",
+            let (agent_id, old_status): (String, String) = conn.query_row(
+                "SELECT agent_id, status FROM facts WHERE id = ?1",
                 params![fact_id],
-                |r| r.get(0),
+                |r| Ok((r.get(0)?, r.get(1)?)),
             )?;
 
             let new_status = validated_status(&new_status)?;
@@ -469,6 +611,155 @@ impl FactStore {
                     now,
                 ],
             )?;
+            enqueue_on(&conn, &agent_id, DIRTY_KIND_FACT, &fact_id, "fact_status_changed")?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Facts of the agent matching `filter`, most recently updated first.
+    pub async fn list_facts(&self, agent_id: &str, filter: &FactFilter) -> Result<Vec<Fact>> {
+        let db = Arc::clone(&self.db);
+        let mut clauses = vec!["agent_id = ?1".to_string()];
+        let mut values = vec![Value::Text(agent_id.to_owned())];
+        for (column, value) in [
+            ("status", &filter.status),
+            ("fact_type", &filter.fact_type),
+            ("user_scope", &filter.user_scope),
+            ("conversation_scope", &filter.conversation_scope),
+        ] {
+            if let Some(value) = value {
+                values.push(Value::Text(value.clone()));
+                clauses.push(format!("{column} = ?{}", values.len()));
+            }
+        }
+        if let Some(pinned) = filter.pinned {
+            values.push(Value::Integer(i64::from(pinned)));
+            clauses.push(format!("pinned = ?{}", values.len()));
+        }
+        if let Some(contains) = filter.contains.as_deref().filter(|text| !text.is_empty()) {
+            values.push(Value::Text(format!("%{}%", contains.to_lowercase())));
+            clauses.push(format!("LOWER(content) LIKE ?{}", values.len()));
+        }
+        let limit = if filter.limit == 0 {
+            -1
+        } else {
+            filter.limit as i64
+        };
+        let sql = format!(
+            "SELECT id, agent_id, content, fact_type, importance, confidence, COALESCE(salience, 50), status, \
+             occurred_at, recorded_at, source_type, source_session, access_count, \
+             last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at, \
+             user_scope, conversation_scope, pinned \
+             FROM facts WHERE {} \
+             ORDER BY updated_at DESC, id ASC LIMIT {limit}",
+            clauses.join(" AND ")
+        );
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values), row_to_fact)?;
+            let mut facts = Vec::new();
+            for row in rows {
+                facts.push(row?);
+            }
+            Ok(facts)
+        })
+        .await?
+    }
+
+    /// Adjust a fact's metadata in place. The fact keeps its id, so lineage
+    /// and history stay attached to it.
+    pub async fn edit_fact(&self, fact_id: &str, edit: &FactEdit, reason: &str) -> Result<Fact> {
+        let mut fact = self
+            .find_by_id(fact_id)
+            .await?
+            .ok_or_else(|| anyhow!("fact not found: {fact_id}"))?;
+        if let Some(fact_type) = &edit.fact_type {
+            fact.fact_type = fact_type.clone();
+        }
+        if let Some(importance) = edit.importance {
+            fact.importance = importance.clamp(0.0, 1.0);
+        }
+        if let Some(confidence) = edit.confidence {
+            fact.confidence = confidence.clamp(0.0, 1.0);
+        }
+        if let Some(salience) = edit.salience {
+            fact.salience = salience.min(100);
+        }
+        fact.updated_at = Utc::now().to_rfc3339();
+
+        let db = Arc::clone(&self.db);
+        let updated = fact.clone();
+        let reason = reason.to_owned();
+        task::spawn_blocking(move || {
+            let mut conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let tx = conn.transaction()?;
+            tx.execute(
+                "UPDATE facts SET fact_type = ?1, importance = ?2, confidence = ?3, \
+                 salience = ?4, updated_at = ?5 WHERE id = ?6",
+                params![
+                    updated.fact_type,
+                    updated.importance,
+                    updated.confidence,
+                    updated.salience,
+                    updated.updated_at,
+                    updated.id,
+                ],
+            )?;
+            tx.execute(
+                "INSERT INTO fact_history (id, fact_id, event, old_content, new_content, reason, created_at) \
+                 VALUES (?1, ?2, 'UPDATE', ?3, ?4, ?5, ?6)",
+                params![
+                    Uuid::new_v4().to_string(),
+                    updated.id,
+                    updated.content,
+                    updated.content,
+                    reason,
+                    updated.updated_at,
+                ],
+            )?;
+            enqueue_on(&tx, &updated.agent_id, DIRTY_KIND_FACT, &updated.id, "fact_edited")?;
+            tx.commit()?;
+            Ok::<(), anyhow::Error>(())
+        })
+        .await??;
+        Ok(fact)
+    }
+
+    /// Pin or unpin a fact. Pinned facts are always injected into the
+    /// prompt and are skipped by [`FactStore::apply_confidence_decay`].
+    pub async fn set_pinned(&self, fact_id: &str, pinned: bool, reason: &str) -> Result<()> {
+        let db = Arc::clone(&self.db);
+        let fact_id = fact_id.to_owned();
+        let reason = reason.to_owned();
+        let now = Utc::now().to_rfc3339();
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let updated = conn.execute(
+                "UPDATE facts SET pinned = ?1, updated_at = ?2 WHERE id = ?3",
+                params![pinned, now, fact_id],
+            )?;
+            if updated == 0 {
+                return Err(anyhow!("fact not found: {fact_id}"));
+            }
+            conn.execute(
+                "INSERT INTO fact_history (id, fact_id, event, old_content, new_content, reason, created_at) \
+                 VALUES (?1, ?2, ?3, NULL, NULL, ?4, ?5)",
+                params![
+                    Uuid::new_v4().to_string(),
+                    fact_id,
+                    if pinned { "PIN" } else { "UNPIN" },
+                    reason,
+                    now,
+                ],
+            )?;
             Ok(())
         })
         .await?
@@ -484,10 +775,12 @@ impl FactStore {
             let mut stmt = conn.prepare(
                 "SELECT id, agent_id, content, fact_type, importance, confidence, COALESCE(salience, 50), status, \
                  occurred_at, recorded_at, source_type, source_session, access_count, \
-                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at \
+                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at, \
+                 user_scope, conversation_scope, pinned \
                  FROM facts \
-                 WHERE agent_id = ?1 AND status = 'active' AND COALESCE(salience, 50) >= 60 AND confidence >= 0.5 \
-                 ORDER BY COALESCE(salience, 50) DESC, updated_at DESC \
+                 WHERE agent_id = ?1 AND status = 'active' \
+                 AND (pinned = 1 OR (COALESCE(salience, 50) >= 60 AND confidence >= 0.5)) \
+                 ORDER BY pinned DESC, COALESCE(salience, 50) DESC, updated_at DESC \
                  LIMIT 50",
             )?;
             let rows = stmt.query_map(params![agent_id], row_to_fact)?;
@@ -500,6 +793,45 @@ impl FactStore {
         .await?
     }
 
+    /// High-salience facts for prompt injection, limited to what `scope` can read.
+    pub async fn get_injected_facts_in_scope(
+        &self,
+        agent_id: &str,
+        scope: &MemoryScope,
+    ) -> Result<Vec<Fact>> {
+        let db = Arc::clone(&self.db);
+        let agent_id = agent_id.to_owned();
+        let scope = scope.clone();
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let mut stmt = conn.prepare(
+                "SELECT id, agent_id, content, fact_type, importance, confidence, COALESCE(salience, 50), status, \
+                 occurred_at, recorded_at, source_type, source_session, access_count, \
+                 last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at, \
+                 user_scope, conversation_scope, pinned \
+                 FROM facts \
+                 WHERE agent_id = ?1 AND status = 'active' \
+                 AND (pinned = 1 OR (COALESCE(salience, 50) >= 60 AND confidence >= 0.5)) \
+                 AND (user_scope IS NULL OR user_scope = ?2) \
+                 AND (conversation_scope IS NULL OR conversation_scope = ?3) \
+                 ORDER BY pinned DESC, COALESCE(salience, 50) DESC, updated_at DESC \
+                 LIMIT 50",
+            )?;
+            let rows = stmt.query_map(
+                params![agent_id, scope.user_scope, scope.conversation_scope],
+                row_to_fact,
+            )?;
+            let mut facts = Vec::new();
+            for row in rows {
+                facts.push(row?);
+            }
+            Ok(facts)
+        })
+        .await?
+    }
+
     pub async fn record_add(&self, fact: &Fact) -> Result<()> {
         let db = Arc::clone(&self.db);
         let fact_id = fact.id.clone();
@@ -558,8 +890,9 @@ impl FactStore {
                 let mut stmt = tx.prepare(
                     "SELECT id, agent_id, content, fact_type, importance, confidence, COALESCE(salience, 50), status, \
                      occurred_at, recorded_at, source_type, source_session, access_count, \
-                     last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at \
-                     FROM facts WHERE agent_id = ?1 AND status = 'active'",
+                     last_accessed, superseded_by, supersede_reason, COALESCE(affect, 'neutral'), COALESCE(affect_intensity, 0.0), created_at, updated_at, \
+                 user_scope, conversation_scope, pinned \
+                     FROM facts WHERE agent_id = ?1 AND status = 'active' AND pinned = 0",
                 )?;
                 let rows = stmt.query_map(params![agent_id], row_to_fact)?;
                 let mut loaded = Vec::new();
@@ -593,6 +926,7 @@ impl FactStore {
                          VALUES (?1, ?2, 'ARCHIVE', ?3, NULL, 'confidence_decay_archive', ?4)",
                         params![Uuid::new_v4().to_string(), fact.id, fact.content, now],
                     )?;
+                    enqueue_on(&tx, &fact.agent_id, DIRTY_KIND_FACT, &fact.id, "fact_archived")?;
                     archived_count += 1;
                 } else {
                     tx.execute(
@@ -668,6 +1002,9 @@ fn row_to_fact(r: &rusqlite::Row) -> rusqlite::Result<Fact> {
         affect_intensity: r.get(17)?,
         created_at: r.get(18)?,
         updated_at: r.get(19)?,
+        user_scope: r.get(20)?,
+        conversation_scope: r.get(21)?,
+        pinned: r.get(22)?,
     })
 }
 
@@ -696,8 +1033,11 @@ mod tests {
             last_accessed: None,
             superseded_by: None,
             supersede_reason: None,
+            pinned: false,
             affect: default_affect(),
             affect_intensity: default_affect_intensity(),
+            user_scope: None,
+            conversation_scope: None,
             created_at: now.clone(),
             updated_at: now,
         }
@@ -1144,6 +1484,125 @@ mod tests {
         assert!((loaded.confidence - expected_decay_factor).abs() < 1e-9);
     }
 
+    #[tokio::test]
+    async fn pinned_facts_are_injected_and_skip_decay() {
+        let store = MemoryStore::open_in_memory().unwrap();
+        let fact_store = FactStore::new(store.db());
+
+        let mut fact = make_fact("agent-1", "Never deploy on Fridays", "event");
+        fact.confidence = 0.15;
+        fact.salience = 20;
+        fact_store.insert_fact(&fact).await.unwrap();
+        assert!(fact_store
+            .get_injected_facts("agent-1")
+            .await
+            .unwrap()
+            .is_empty());
+
+        fact_store
+            .set_pinned(&fact.id, true, "operator")
+            .await
+            .unwrap();
+        let summary = fact_store.apply_confidence_decay("agent-1").await.unwrap();
+        assert_eq!(summary.decayed_count, 0);
+
+        let injected = fact_store.get_injected_facts("agent-1").await.unwrap();
+        assert_eq!(injected.len(), 1);
+        assert!(injected[0].pinned);
+        assert!((injected[0].confidence - 0.15).abs() < 1e-9);
+
+        let history = fact_store.get_history(&fact.id).await.unwrap();
+        assert_eq!(history[0].event, "PIN");
+        assert!(fact_store.set_pinned("missing", true, "x").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn edit_fact_keeps_id_and_records_update() {
+        let store = MemoryStore::open_in_memory().unwrap();
+        let fact_store = FactStore::new(store.db());
+        let fact = make_fact("agent-1", "User lives in Paris", "person");
+        fact_store.insert_fact(&fact).await.unwrap();
+
+        let edited = fact_store
+            .edit_fact(
+                &fact.id,
+                &FactEdit {
+                    fact_type: Some("event".to_string()),
+                    importance: Some(1.5),
+                    ..FactEdit::default()
+                },
+                "operator correction",
+            )
+            .await
+            .unwrap();
+        assert_eq!(edited.id, fact.id);
+        assert!((edited.importance - 1.0).abs() < 1e-9);
+
+        let loaded = fact_store.find_by_id(&fact.id).await.unwrap().unwrap();
+        assert_eq!(loaded.content, "User lives in Paris");
+        assert_eq!(loaded.fact_type, "event");
+        let history = fact_store.get_history(&fact.id).await.unwrap();
+        assert_eq!(history[0].event, "UPDATE");
+        assert_eq!(history[0].reason.as_deref(), Some("operator correction"));
+
+        assert!(fact_store
+            .edit_fact("missing", &FactEdit::default(), "x")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn list_facts_applies_filters() {
+        let store = MemoryStore::open_in_memory().unwrap();
+        let fact_store = FactStore::new(store.db());
+        let rust = make_fact("agent-1", "User prefers Rust", "preference");
+        let go = make_fact("agent-1", "User used Go before", "event");
+        let mut scoped = make_fact("agent-1", "Alice prefers tea", "preference");
+        scoped.user_scope = Some("telegram:user:1".to_string());
+        for fact in [&rust, &go, &scoped] {
+            fact_store.insert_fact(fact).await.unwrap();
+        }
+        fact_store
+            .update_status(&go.id, "retracted", "wrong")
+            .await
+            .unwrap();
+
+        let all = fact_store
+            .list_facts("agent-1", &FactFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+
+        let active_prefs = fact_store
+            .list_facts(
+                "agent-1",
+                &FactFilter {
+                    status: Some("active".to_string()),
+                    fact_type: Some("preference".to_string()),
+                    contains: Some("RUST".to_string()),
+                    ..FactFilter::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(active_prefs.len(), 1);
+        assert_eq!(active_prefs[0].id, rust.id);
+
+        let user = fact_store
+            .list_facts(
+                "agent-1",
+                &FactFilter {
+                    user_scope: Some("telegram:user:1".to_string()),
+                    limit: 5,
+                    ..FactFilter::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(user.len(), 1);
+        assert_eq!(user[0].id, scoped.id);
+    }
+
     #[test]
     fn decay_factor_for_type_maps_expected_values() {
         assert!((decay_factor_for_type("rule") - 0.99).abs() < 1e-9);
@@ -7,6 +7,7 @@ use std::path::{Path, PathBuf};
 use tokio::fs;
 
 use crate::safe_io;
+use crate::scope::{MemoryScope, PARTITIONS_DIR};
 
 fn smart_truncate(content: &str, max_chars: usize) -> String {
     if content.chars().count() <= max_chars {
@@ -73,12 +74,16 @@ fn append_truncation_marker(content: &str) -> String {
 #[derive(Clone)]
 pub struct MemoryFileStore {
     workspace: PathBuf,
+    /// Directory name under `memory/partitions/` when this store holds one
+    /// user's or conversation's files instead of the agent-wide ones.
+    partition: Option<String>,
 }
 
 impl MemoryFileStore {
     pub fn new(workspace: impl AsRef<Path>) -> Self {
         Self {
             workspace: workspace.as_ref().to_path_buf(),
+            partition: None,
         }
     }
 
@@ -86,6 +91,79 @@ impl MemoryFileStore {
         &self.workspace
     }
 
+    /// The store for a write partition, keeping its MEMORY.md and daily files
+    /// under `memory/partitions/<dir>/`. The shared scope gets the
+    /// agent-wide store.
+    pub fn partition(&self, scope: &MemoryScope) -> Self {
+        Self {
+            workspace: self.workspace.clone(),
+            partition: scope.partition_dir(),
+        }
+    }
+
+    /// The partition this store writes to.
+    pub fn scope(&self) -> MemoryScope {
+        self.partition
+            .as_deref()
+            .and_then(MemoryScope::from_partition_dir)
+            .unwrap_or_default()
+    }
+
+    /// Stores for every partition that has files on disk.
+    pub async fn list_partitions(&self) -> Result<Vec<MemoryFileStore>> {
+        let mut out = Vec::new();
+        let mut entries = match fs::read_dir(self.workspace.join(PARTITIONS_DIR)).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(out),
+            Err(err) => return Err(err.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_dir() {
+                continue;
+            }
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if let Some(scope) = MemoryScope::from_partition_dir(&name) {
+                out.push(self.partition(&scope));
+            }
+        }
+        out.sort_by(|a, b| a.partition.cmp(&b.partition));
+        Ok(out)
+    }
+
+    /// MEMORY.md path relative to the workspace, as recorded in the search index.
+    pub fn relative_long_term_path(&self) -> String {
+        match &self.partition {
+            Some(dir) => format!("{PARTITIONS_DIR}/{dir}/MEMORY.md"),
+            None => "MEMORY.md".to_string(),
+        }
+    }
+
+    /// Daily file path relative to the workspace, as recorded in the search index.
+    pub fn relative_daily_path(&self, date: NaiveDate) -> String {
+        format!(
+            "{}/{}.md",
+            self.relative_daily_dir(),
+            date.format("%Y-%m-%d")
+        )
+    }
+
+    pub fn relative_archive_path(&self, date: NaiveDate) -> String {
+        format!(
+            "{}/archive/{}.md",
+            self.relative_daily_dir(),
+            date.format("%Y-%m-%d")
+        )
+    }
+
+    fn relative_daily_dir(&self) -> String {
+        match &self.partition {
+            Some(dir) => format!("{PARTITIONS_DIR}/{dir}"),
+            None => "memory".to_string(),
+        }
+    }
+
     /// Read the entire MEMORY.md content. Returns empty string if file doesn't exist.
     pub async fn read_long_term(&self) -> Result<String> {
         let path = self.long_term_path();
@@ -337,23 +415,30 @@ impl MemoryFileStore {
 
         for (date, content) in self.read_recent_daily(3).await? {
             sections.push(String::new());
-            sections.push(format!("From memory/{}.md:", date.format("%Y-%m-%d")));
+            sections.push(format!("From {}:", self.relative_daily_path(date)));
             sections.push(content);
         }
 
         Ok(sections.join("\n"))
     }
 
+    fn root_dir(&self) -> PathBuf {
+        match &self.partition {
+            Some(dir) => self.workspace.join(PARTITIONS_DIR).join(dir),
+            None => self.workspace.clone(),
+        }
+    }
+
     fn long_term_path(&self) -> PathBuf {
-        self.workspace.join("MEMORY.md")
+        self.root_dir().join("MEMORY.md")
     }
 
     fn archived_long_term_path(&self) -> PathBuf {
-        self.workspace.join("MEMORY_ARCHIVED.md")
+        self.root_dir().join("MEMORY_ARCHIVED.md")
     }
 
     fn daily_dir(&self) -> PathBuf {
-        self.workspace.join("memory")
+        self.workspace.join(self.relative_daily_dir())
     }
 
     fn daily_path(&self, date: NaiveDate) -> PathBuf {
@@ -379,6 +464,7 @@ impl MemoryFileStore {
 #[cfg(test)]
 mod tests {
     use super::{smart_truncate, MemoryFileStore};
+    use crate::scope::MemoryScope;
     use anyhow::Result;
     use chrono::NaiveDate;
     use std::sync::Arc;
@@ -521,6 +607,45 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_partition_files_stay_out_of_shared_layer() -> Result<()> {
+        let dir = TempDir::new()?;
+        let store = MemoryFileStore::new(dir.path());
+        let alice = MemoryScope::from_session_key("telegram:tg:dm:1:user:alice")
+            .unwrap()
+            .user_partition();
+        let partition = store.partition(&alice);
+
+        store.write_daily(date(2026, 2, 10), "shared").await?;
+        partition.write_daily(date(2026, 2, 11), "private").await?;
+        partition.write_long_term("# Alice\n").await?;
+
+        let shared_dates: Vec<_> = store
+            .list_daily_files()
+            .await?
+            .into_iter()
+            .map(|(d, _)| d)
+            .collect();
+        assert_eq!(shared_dates, vec![date(2026, 2, 10)]);
+        assert_eq!(store.read_long_term().await?, "");
+
+        let partitions = store.list_partitions().await?;
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[0].scope(), alice);
+        assert_eq!(
+            partitions[0]
+                .read_daily(date(2026, 2, 11))
+                .await?
+                .as_deref(),
+            Some("private")
+        );
+        assert_eq!(
+            partition.relative_daily_path(date(2026, 2, 11)),
+            "memory/partitions/user-telegram~3auser~3aalice/2026-02-11.md"
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_archive_daily_moves_file_into_archive_directory() -> Result<()> {
         let dir = TempDir::new()?;
@@ -6,15 +6,19 @@ pub mod fact_store;
 pub mod file_audit;
 pub mod file_store;
 pub mod health;
+pub mod local_embedding;
 pub mod memory_lineage;
 pub mod migrations;
 pub mod models;
 pub mod safe_io;
+pub mod scope;
 pub mod search_index;
 pub mod session;
 pub mod store;
+pub mod usage;
 
 pub use error::MemoryError;
 pub use models::*;
+pub use scope::MemoryScope;
 pub use session::*;
 pub use store::*;
@@ -0,0 +1,266 @@
+//! CPU-only sentence embeddings computed in process, for hosts that cannot
+//! reach an embedding service. The model runs on candle and is only compiled
+//! in with the `local-embeddings` feature.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::embedding::EmbeddingProvider;
+
+#[cfg(feature = "local-embeddings")]
+pub use model::LocalEmbeddingProvider;
+
+/// Load the model in `model_dir` as an embedding provider, or explain why
+/// this build cannot.
+pub fn load_local_embedding_provider(model_dir: &Path) -> Result<Arc<dyn EmbeddingProvider>> {
+    #[cfg(feature = "local-embeddings")]
+    {
+        Ok(Arc::new(LocalEmbeddingProvider::load(model_dir)?))
+    }
+    #[cfg(not(feature = "local-embeddings"))]
+    {
+        Err(anyhow::anyhow!(
+            "cannot load local embedding model from {}: built without the `local-embeddings` feature",
+            model_dir.display()
+        ))
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+mod model {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use anyhow::{anyhow, Context, Result};
+    use async_trait::async_trait;
+    use candle_core::{DType, Device, Tensor};
+    use candle_nn::VarBuilder;
+    use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+    use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+    use tokio::task;
+
+    use crate::embedding::{EmbeddingProvider, EmbeddingResult};
+
+    /// Texts embedded per forward pass; bounds peak memory on small hosts.
+    const BATCH_SIZE: usize = 16;
+    const MAX_SEQUENCE_LENGTH: usize = 256;
+
+    /// A BERT-family sentence-transformers model (e.g. `all-MiniLM-L6-v2`)
+    /// with mean pooling and L2-normalised output.
+    pub struct LocalEmbeddingProvider {
+        model: Arc<LocalModel>,
+        model_id: String,
+        dimensions: usize,
+    }
+
+    struct LocalModel {
+        bert: BertModel,
+        tokenizer: Tokenizer,
+        device: Device,
+    }
+
+    impl LocalEmbeddingProvider {
+        /// Load `config.json`, `tokenizer.json` and `model.safetensors`
+        /// from `model_dir`.
+        pub fn load(model_dir: &Path) -> Result<Self> {
+            let config_path = model_dir.join("config.json");
+            let config: Config = serde_json::from_str(
+                &std::fs::read_to_string(&config_path)
+                    .with_context(|| format!("failed to read {}", config_path.display()))?,
+            )
+            .with_context(|| format!("invalid model config {}", config_path.display()))?;
+
+            let mut tokenizer = Tokenizer::from_file(model_dir.join("tokenizer.json"))
+                .map_err(|error| anyhow!("failed to load tokenizer: {error}"))?;
+            tokenizer.with_padding(Some(PaddingParams {
+                strategy: PaddingStrategy::BatchLongest,
+                ..Default::default()
+            }));
+            tokenizer
+                .with_truncation(Some(TruncationParams {
+                    max_length: MAX_SEQUENCE_LENGTH.min(config.max_position_embeddings),
+                    ..Default::default()
+                }))
+                .map_err(|error| anyhow!("failed to configure tokenizer: {error}"))?;
+
+            let device = Device::Cpu;
+            let weights = model_dir.join("model.safetensors");
+            // SAFETY: the weights file is memory-mapped read-only and must not
+            // be modified while the provider is alive.
+            let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[&weights], DTYPE, &device)? };
+            let bert = BertModel::load(vb, &config)?;
+
+            let name = model_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "model".to_string());
+            Ok(Self {
+                model: Arc::new(LocalModel {
+                    bert,
+                    tokenizer,
+                    device,
+                }),
+                model_id: format!("local:{name}"),
+                dimensions: config.hidden_size,
+            })
+        }
+    }
+
+    impl LocalModel {
+        fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let encodings = self
+                .tokenizer
+                .encode_batch(texts.to_vec(), true)
+                .map_err(|error| anyhow!("failed to tokenize: {error}"))?;
+            let ids = encodings
+                .iter()
+                .map(|encoding| Tensor::new(encoding.get_ids(), &self.device))
+                .collect::<candle_core::Result<Vec<_>>>()?;
+            let masks = encodings
+                .iter()
+                .map(|encoding| Tensor::new(encoding.get_attention_mask(), &self.device))
+                .collect::<candle_core::Result<Vec<_>>>()?;
+            let input_ids = Tensor::stack(&ids, 0)?;
+            let attention_mask = Tensor::stack(&masks, 0)?;
+            let token_type_ids = input_ids.zeros_like()?;
+
+            let hidden = self
+                .bert
+                .forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+
+            // Mean over real tokens only, then unit length so cosine and L2
+            // distance rank the same way.
+            let mask = attention_mask.to_dtype(DType::F32)?.unsqueeze(2)?;
+            let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+            let counts = mask.sum(1)?.clamp(1e-9, f64::MAX)?;
+            let pooled = summed.broadcast_div(&counts)?;
+            let norms = pooled
+                .sqr()?
+                .sum_keepdim(1)?
+                .sqrt()?
+                .clamp(1e-12, f64::MAX)?;
+            Ok(pooled.broadcast_div(&norms)?.to_vec2::<f32>()?)
+        }
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for LocalEmbeddingProvider {
+        async fn embed(&self, texts: &[String]) -> Result<EmbeddingResult> {
+            let model = Arc::clone(&self.model);
+            let texts = texts.to_vec();
+            let embeddings = task::spawn_blocking(move || {
+                let mut out = Vec::with_capacity(texts.len());
+                for batch in texts.chunks(BATCH_SIZE) {
+                    out.extend(model.embed_batch(batch)?);
+                }
+                Ok::<_, anyhow::Error>(out)
+            })
+            .await??;
+
+            Ok(EmbeddingResult {
+                embeddings,
+                model: self.model_id.clone(),
+                dimensions: self.dimensions,
+            })
+        }
+
+        fn model_id(&self) -> &str {
+            &self.model_id
+        }
+
+        fn dimensions(&self) -> usize {
+            self.dimensions
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_fails_without_model_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let error = load_local_embedding_provider(dir.path())
+            .err()
+            .expect("empty directory is not a model");
+        assert!(error
+            .to_string()
+            .contains(&dir.path().display().to_string()));
+    }
+
+    /// A one-layer, 8-wide BERT with random weights and a word-level
+    /// tokenizer: enough to exercise pooling without downloading a model.
+    #[cfg(feature = "local-embeddings")]
+    fn write_tiny_model(dir: &Path) {
+        use candle_core::Device;
+        use candle_nn::{VarBuilder, VarMap};
+        use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+
+        let config = serde_json::json!({
+            "vocab_size": 6,
+            "hidden_size": 8,
+            "num_hidden_layers": 1,
+            "num_attention_heads": 2,
+            "intermediate_size": 16,
+            "hidden_act": "gelu",
+            "hidden_dropout_prob": 0.0,
+            "max_position_embeddings": 16,
+            "type_vocab_size": 2,
+            "initializer_range": 0.02,
+            "layer_norm_eps": 1e-12,
+            "pad_token_id": 0,
+            "classifier_dropout": null,
+            "model_type": "bert"
+        });
+        std::fs::write(dir.join("config.json"), config.to_string()).unwrap();
+        let tokenizer = serde_json::json!({
+            "version": "1.0",
+            "truncation": null,
+            "padding": null,
+            "added_tokens": [],
+            "normalizer": null,
+            "pre_tokenizer": { "type": "Whitespace" },
+            "post_processor": null,
+            "decoder": null,
+            "model": {
+                "type": "WordLevel",
+                "vocab": { "[PAD]": 0, "[UNK]": 1, "hello": 2, "world": 3, "memory": 4, "search": 5 },
+                "unk_token": "[UNK]"
+            }
+        });
+        std::fs::write(dir.join("tokenizer.json"), tokenizer.to_string()).unwrap();
+
+        let config: Config = serde_json::from_value(config).unwrap();
+        let vars = VarMap::new();
+        BertModel::load(VarBuilder::from_varmap(&vars, DTYPE, &Device::Cpu), &config).unwrap();
+        vars.save(dir.join("model.safetensors")).unwrap();
+    }
+
+    #[cfg(feature = "local-embeddings")]
+    #[tokio::test]
+    async fn forward_pass_mean_pools_real_tokens_and_normalises() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_tiny_model(dir.path());
+        let provider = load_local_embedding_provider(dir.path()).unwrap();
+        assert_eq!(provider.dimensions(), 8);
+
+        let alone = provider.embed(&["hello".to_string()]).await.unwrap();
+        let batch = provider
+            .embed(&["hello".to_string(), "hello world memory search".to_string()])
+            .await
+            .unwrap();
+        for embedding in &batch.embeddings {
+            assert_eq!(embedding.len(), 8);
+            let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-4, "norm {norm}");
+        }
+        // Padding "hello" to the longer text must not change its embedding.
+        for (a, b) in alone.embeddings[0].iter().zip(&batch.embeddings[0]) {
+            assert!((a - b).abs() < 1e-5, "{a} != {b}");
+        }
+        assert_ne!(batch.embeddings[0], batch.embeddings[1]);
+    }
+}
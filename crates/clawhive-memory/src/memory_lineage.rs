@@ -311,6 +311,35 @@ impl MemoryLineageStore {
         .await?
     }
 
+    /// Every source attached to a canonical memory, oldest first.
+    pub async fn get_links_for_canonical(
+        &self,
+        agent_id: &str,
+        canonical_id: &str,
+    ) -> Result<Vec<MemoryLineage>> {
+        let db = Arc::clone(&self.db);
+        let agent_id = agent_id.to_owned();
+        let canonical_id = canonical_id.to_owned();
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let mut stmt = conn.prepare(
+                "SELECT id, agent_id, canonical_id, source_kind, source_ref, relation, created_at \
+                 FROM memory_lineage \
+                 WHERE agent_id = ?1 AND canonical_id = ?2 \
+                 ORDER BY created_at ASC",
+            )?;
+            let rows = stmt.query_map(params![agent_id, canonical_id], row_to_lineage)?;
+            let mut links = Vec::new();
+            for row in rows {
+                links.push(row?);
+            }
+            Ok(links)
+        })
+        .await?
+    }
+
     pub async fn get_canonical_ids_for_sources(
         &self,
         agent_id: &str,
@@ -826,8 +855,11 @@ mod tests {
             last_accessed: None,
             superseded_by: None,
             supersede_reason: None,
+            pinned: false,
             affect: "neutral".to_owned(),
             affect_intensity: 0.0,
+            user_scope: None,
+            conversation_scope: None,
             created_at: now.clone(),
             updated_at: now,
         }
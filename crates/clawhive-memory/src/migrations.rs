@@ -458,6 +458,58 @@ fn migrations() -> Vec<Migration> {
             ALTER TABLE facts ADD COLUMN affect_intensity REAL NOT NULL DEFAULT 0.0;
             "#,
         ),
+        (
+            27,
+            r#"
+            CREATE TABLE IF NOT EXISTS llm_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recorded_at TEXT NOT NULL,
+                agent_id TEXT NOT NULL DEFAULT '',
+                session_key TEXT,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                purpose TEXT NOT NULL DEFAULT 'other',
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+                latency_ms INTEGER NOT NULL DEFAULT 0,
+                cost_usd REAL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_llm_usage_recorded_at ON llm_usage(recorded_at);
+            CREATE INDEX IF NOT EXISTS idx_llm_usage_agent ON llm_usage(agent_id, recorded_at);
+            "#,
+        ),
+        (
+            28,
+            r#"
+            ALTER TABLE llm_usage ADD COLUMN user_scope TEXT;
+            CREATE INDEX IF NOT EXISTS idx_llm_usage_user_scope ON llm_usage(user_scope, recorded_at);
+
+            CREATE TABLE IF NOT EXISTS budget_overrides (
+                budget_key TEXT NOT NULL,
+                period_start TEXT NOT NULL,
+                granted_at TEXT NOT NULL,
+                PRIMARY KEY (budget_key, period_start)
+            );
+            "#,
+        ),
+        (
+            29,
+            r#"
+            ALTER TABLE facts ADD COLUMN user_scope TEXT;
+            ALTER TABLE facts ADD COLUMN conversation_scope TEXT;
+            CREATE INDEX IF NOT EXISTS idx_facts_agent_scope
+            ON facts(agent_id, status, user_scope, conversation_scope);
+            "#,
+        ),
+        (
+            30,
+            r#"
+            ALTER TABLE facts ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+            "#,
+        ),
     ]
 }
 
@@ -967,4 +1019,27 @@ mod tests {
         assert!((intensity - 0.0).abs() < f64::EPSILON);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn migration_29_places_existing_facts_in_shared_layer() -> Result<()> {
+        let store = MemoryStore::open_in_memory()?;
+        {
+            let db = store.db();
+            let conn = db.lock().expect("lock");
+            conn.execute(
+                "INSERT INTO facts(id, agent_id, content, fact_type, importance, confidence, salience, status, occurred_at, recorded_at, source_type, source_session, access_count, last_accessed, superseded_by, supersede_reason, created_at, updated_at)
+                 VALUES ('fact-1', 'agent-1', 'Team standup is at 9', 'rule', 0.8, 1.0, 50, 'active', NULL, ?1, 'test', NULL, 0, NULL, NULL, NULL, ?1, ?1)",
+                ["2026-04-05T00:00:00Z"],
+            )?;
+        }
+
+        let reader = crate::scope::MemoryScope::from_session_key("telegram:tg:dm:1:user:1")
+            .expect("session key");
+        let facts = crate::fact_store::FactStore::new(store.db())
+            .get_active_facts_in_scope("agent-1", &reader)
+            .await?;
+        assert_eq!(facts.len(), 1);
+        assert!(facts[0].scope().is_shared());
+        Ok(())
+    }
 }
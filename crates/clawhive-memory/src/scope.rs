@@ -0,0 +1,238 @@
+//! Partitioning of an agent's memory by channel user or conversation.
+
+use serde::{Deserialize, Serialize};
+
+/// Directory, relative to the agent workspace, that holds one subdirectory
+/// of daily files per write partition.
+pub const PARTITIONS_DIR: &str = "memory/partitions";
+
+/// Which slice of an agent's memory a caller reads from or writes to.
+///
+/// Rows with neither scope set form the agent-wide shared layer. A reader
+/// sees the shared layer plus every row whose set scopes all equal its own,
+/// so a user-level fact follows that user into every conversation and a
+/// conversation-level fact is visible to everyone in that conversation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MemoryScope {
+    /// `channel_type:user_scope`, e.g. `telegram:user:42`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_scope: Option<String>,
+    /// `channel_type:connector_id:conversation_scope`, e.g.
+    /// `telegram:tg_main:chat:-100`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_scope: Option<String>,
+}
+
+impl MemoryScope {
+    /// The agent-wide layer.
+    pub fn shared() -> Self {
+        Self::default()
+    }
+
+    /// Identity of the sender of an inbound message.
+    pub fn from_source(
+        channel_type: &str,
+        connector_id: &str,
+        conversation_scope: &str,
+        user_scope: &str,
+    ) -> Self {
+        Self {
+            user_scope: Some(format!("{channel_type}:{user_scope}")),
+            conversation_scope: Some(format!(
+                "{channel_type}:{connector_id}:{conversation_scope}"
+            )),
+        }
+    }
+
+    /// Recover the caller identity from a session key
+    /// (`channel_type:connector_id:conversation_scope:user_scope`).
+    ///
+    /// Conversation scopes contain `:` themselves, so the split relies on
+    /// every channel formatting user scopes as `user:<id>`.
+    pub fn from_session_key(session_key: &str) -> Option<Self> {
+        let split = session_key.rfind(":user:")?;
+        let (conversation, user_scope) = (&session_key[..split], &session_key[split + 1..]);
+        let mut parts = conversation.splitn(3, ':');
+        let (Some(channel_type), Some(connector_id), Some(conversation_scope)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        Some(Self::from_source(
+            channel_type,
+            connector_id,
+            conversation_scope,
+            user_scope,
+        ))
+    }
+
+    /// The caller's private partition, visible in all of their conversations.
+    pub fn user_partition(&self) -> Self {
+        Self {
+            user_scope: self.user_scope.clone(),
+            conversation_scope: None,
+        }
+    }
+
+    /// The partition shared by everyone in the caller's conversation.
+    pub fn conversation_partition(&self) -> Self {
+        Self {
+            user_scope: None,
+            conversation_scope: self.conversation_scope.clone(),
+        }
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.user_scope.is_none() && self.conversation_scope.is_none()
+    }
+
+    /// Whether a row stored under the given scopes is visible to this reader.
+    pub fn can_read(&self, user_scope: Option<&str>, conversation_scope: Option<&str>) -> bool {
+        user_scope.is_none_or(|scope| self.user_scope.as_deref() == Some(scope))
+            && conversation_scope
+                .is_none_or(|scope| self.conversation_scope.as_deref() == Some(scope))
+    }
+
+    /// Whether a session transcript belongs to this reader's user or
+    /// conversation. Transcripts without a channel owner (CLI, cron,
+    /// heartbeats) belong to the shared layer and only it reads them.
+    pub fn can_read_session(&self, session_key: &str) -> bool {
+        let Some(owner) = Self::from_session_key(session_key) else {
+            return self.is_shared();
+        };
+        (self.user_scope.is_some() && owner.user_scope == self.user_scope)
+            || (self.conversation_scope.is_some()
+                && owner.conversation_scope == self.conversation_scope)
+    }
+
+    /// Directory name under [`PARTITIONS_DIR`] for a write partition, which
+    /// sets exactly one of the two scopes. `None` for the shared layer and
+    /// for reader identities.
+    pub fn partition_dir(&self) -> Option<String> {
+        match (&self.user_scope, &self.conversation_scope) {
+            (Some(user), None) => Some(format!("user-{}", escape(user))),
+            (None, Some(conversation)) => Some(format!("conversation-{}", escape(conversation))),
+            _ => None,
+        }
+    }
+
+    /// Inverse of [`partition_dir`](Self::partition_dir).
+    pub fn from_partition_dir(name: &str) -> Option<Self> {
+        if let Some(user) = name.strip_prefix("user-") {
+            return Some(Self {
+                user_scope: Some(unescape(user)?),
+                conversation_scope: None,
+            });
+        }
+        let conversation = name.strip_prefix("conversation-")?;
+        Some(Self {
+            user_scope: None,
+            conversation_scope: Some(unescape(conversation)?),
+        })
+    }
+
+    /// Whether a path relative to the agent workspace lies in the shared
+    /// layer or in a partition this reader can see.
+    pub fn can_read_path(&self, path: &str) -> bool {
+        let Some(rest) = path
+            .strip_prefix(PARTITIONS_DIR)
+            .and_then(|rest| rest.strip_prefix('/'))
+        else {
+            return true;
+        };
+        let dir = rest.split('/').next().unwrap_or_default();
+        Self::from_partition_dir(dir).is_some_and(|partition| {
+            self.can_read(
+                partition.user_scope.as_deref(),
+                partition.conversation_scope.as_deref(),
+            )
+        })
+    }
+}
+
+/// Keep `[A-Za-z0-9_-]` and write every other byte as `~xx`, so directory
+/// names stay portable and map back to exactly one scope.
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-' {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("~{byte:02x}"));
+        }
+    }
+    out
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut iter = value.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'~' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_key_round_trips_to_source_identity() {
+        let scope = MemoryScope::from_session_key("telegram:tg_main:chat:-100:user:42").unwrap();
+        assert_eq!(scope.user_scope.as_deref(), Some("telegram:user:42"));
+        assert_eq!(
+            scope.conversation_scope.as_deref(),
+            Some("telegram:tg_main:chat:-100")
+        );
+        assert_eq!(
+            scope,
+            MemoryScope::from_source("telegram", "tg_main", "chat:-100", "user:42")
+        );
+        assert!(MemoryScope::from_session_key("cli:local").is_none());
+    }
+
+    #[test]
+    fn readers_see_shared_and_their_own_partitions() {
+        let alice = MemoryScope::from_session_key("telegram:tg:group:1:user:alice").unwrap();
+        let bob = MemoryScope::from_session_key("telegram:tg:group:1:user:bob").unwrap();
+        let alice_private = alice.user_partition();
+        let group = alice.conversation_partition();
+
+        assert!(bob.can_read(None, None));
+        assert!(alice.can_read(alice_private.user_scope.as_deref(), None));
+        assert!(!bob.can_read(alice_private.user_scope.as_deref(), None));
+        assert!(bob.can_read(None, group.conversation_scope.as_deref()));
+        assert!(!MemoryScope::shared().can_read(alice_private.user_scope.as_deref(), None));
+
+        assert!(alice.can_read_session("telegram:tg:dm:alice:user:alice"));
+        assert!(bob.can_read_session("telegram:tg:group:1:user:alice"));
+        assert!(!bob.can_read_session("telegram:tg:dm:alice:user:alice"));
+        assert!(!MemoryScope::shared().can_read_session("telegram:tg:dm:alice:user:alice"));
+        assert!(MemoryScope::shared().can_read_session("cli:local"));
+        assert!(!alice.can_read_session("cli:local"));
+    }
+
+    #[test]
+    fn partition_dirs_are_reversible_and_gate_paths() {
+        let alice = MemoryScope::from_session_key("telegram:tg:dm:7:user:alice").unwrap();
+        let dir = alice.user_partition().partition_dir().unwrap();
+        assert_eq!(dir, "user-telegram~3auser~3aalice");
+        assert_eq!(
+            MemoryScope::from_partition_dir(&dir),
+            Some(alice.user_partition())
+        );
+        assert_eq!(alice.partition_dir(), None);
+
+        let bob = MemoryScope::from_session_key("telegram:tg:dm:8:user:bob").unwrap();
+        let path = format!("{PARTITIONS_DIR}/{dir}/2026-03-01.md");
+        assert!(alice.can_read_path(&path));
+        assert!(!bob.can_read_path(&path));
+        assert!(bob.can_read_path("memory/2026-03-01.md"));
+    }
+}
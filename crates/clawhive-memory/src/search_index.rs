@@ -1,6 +1,7 @@
 use std::sync::{Arc, Mutex};
 
 use anyhow::{anyhow, Result};
+use rusqlite::types::Value;
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
@@ -12,8 +13,13 @@ use crate::dirty_sources::{
     DIRTY_KIND_FACT, DIRTY_KIND_MEMORY_FILE, DIRTY_KIND_SCHEMA, DIRTY_KIND_SESSION,
 };
 use crate::embedding::EmbeddingProvider;
+use crate::fact_store::{Fact, FactStore};
+use crate::scope::{MemoryScope, PARTITIONS_DIR};
 use crate::session::{SessionEntry, SessionReader};
 
+/// Path prefix of the chunks that hold fact embeddings.
+pub const FACTS_PATH_PREFIX: &str = "facts/";
+
 #[derive(Debug, Clone)]
 struct SessionIndexUnit {
     path: String,
@@ -30,6 +36,8 @@ pub struct SearchIndex {
     db: Arc<Mutex<Connection>>,
     agent_id: String,
     search_config: SearchConfig,
+    /// `None` reads every partition and transcript.
+    scope: Option<MemoryScope>,
 }
 
 #[derive(Debug, Clone)]
@@ -117,7 +125,7 @@ fn recent_access_boost(last_accessed: Option<&str>) -> f64 {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SearchResult {
     pub chunk_id: String,
     pub path: String,
@@ -163,9 +171,26 @@ impl SearchIndex {
             db,
             agent_id: agent_id.into(),
             search_config,
+            scope: Some(MemoryScope::shared()),
         }
     }
 
+    /// Only return chunks a reader in `scope` may see: shared files, files in
+    /// the reader's partitions, and transcripts of the reader's own user or
+    /// conversation. The default shared scope sees no partition and no
+    /// channel transcript. Indexing is unaffected.
+    pub fn with_scope(mut self, scope: MemoryScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Return chunks of every partition and transcript, for operator tools
+    /// such as the memory review API.
+    pub fn with_operator_access(mut self) -> Self {
+        self.scope = None;
+        self
+    }
+
     pub fn config(&self) -> &SearchConfig {
         &self.search_config
     }
@@ -885,6 +910,53 @@ impl SearchIndex {
         Ok(())
     }
 
+    /// Embed one fact as a single chunk under [`fact_chunk_path`]. Facts
+    /// that are no longer active lose their chunk instead.
+    pub async fn index_fact(&self, fact: &Fact, provider: &dyn EmbeddingProvider) -> Result<usize> {
+        let path = fact_chunk_path(&fact.id);
+        if fact.status != "active" {
+            self.delete_indexed_path(&path).await?;
+            return Ok(0);
+        }
+        let change_hash = {
+            let mut hasher = Sha256::new();
+            hasher.update(fact.content.as_bytes());
+            format!("fact:{:x}", hasher.finalize())
+        };
+        self.index_content_with_timestamp(
+            &path,
+            &fact.content,
+            "fact",
+            &change_hash,
+            provider,
+            Some(&fact.created_at),
+        )
+        .await
+    }
+
+    /// Embed every active fact and drop chunks of facts that are gone.
+    pub async fn index_facts(&self, provider: &dyn EmbeddingProvider) -> Result<usize> {
+        let facts = FactStore::new(Arc::clone(&self.db))
+            .get_active_facts(&self.agent_id)
+            .await?;
+        let mut total = 0;
+        for fact in &facts {
+            total += self.index_fact(fact, provider).await?;
+        }
+
+        let known_paths = facts
+            .iter()
+            .map(|fact| fact_chunk_path(&fact.id))
+            .collect::<Vec<_>>();
+        for path in self.detect_orphan_chunks(&known_paths).await? {
+            if fact_id_from_path(&path).is_some() {
+                self.delete_indexed_path(&path).await?;
+            }
+        }
+
+        Ok(total)
+    }
+
     pub async fn detect_orphan_chunks(&self, known_paths: &[String]) -> Result<Vec<String>> {
         let db = Arc::clone(&self.db);
         let agent_id = self.agent_id.clone();
@@ -931,7 +1003,34 @@ impl SearchIndex {
             }
         }
 
+        for partition in file_store.list_partitions().await? {
+            let long_term = partition.read_long_term().await?;
+            if !long_term.trim().is_empty() {
+                total += self
+                    .index_file(
+                        &partition.relative_long_term_path(),
+                        &long_term,
+                        "long_term",
+                        provider,
+                    )
+                    .await?;
+            }
+            for (date, _) in partition.list_daily_files().await? {
+                if let Some(content) = partition.read_daily(date).await? {
+                    total += self
+                        .index_file(
+                            &partition.relative_daily_path(date),
+                            &content,
+                            "daily",
+                            provider,
+                        )
+                        .await?;
+                }
+            }
+        }
+
         total += self.index_sessions(reader, provider).await?;
+        total += self.index_facts(provider).await?;
 
         Ok(total)
     }
@@ -980,6 +1079,16 @@ impl SearchIndex {
         provider: &dyn EmbeddingProvider,
     ) -> Result<usize> {
         match item.source_kind.as_str() {
+            DIRTY_KIND_MEMORY_FILE if item.source_ref != "MEMORY.md" => {
+                let path = file_store.workspace_dir().join(&item.source_ref);
+                let content = match tokio::fs::read_to_string(&path).await {
+                    Ok(content) => content,
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+                    Err(error) => return Err(error.into()),
+                };
+                self.index_file(&item.source_ref, &content, "long_term", provider)
+                    .await
+            }
             DIRTY_KIND_MEMORY_FILE => {
                 let content = file_store.read_long_term().await?;
                 self.index_file("MEMORY.md", &content, "long_term", provider)
@@ -1008,7 +1117,21 @@ impl SearchIndex {
                     Ok(0)
                 }
             }
-            DIRTY_KIND_FACT => Ok(0),
+            DIRTY_KIND_FACT => {
+                let fact = FactStore::new(Arc::clone(&self.db))
+                    .find_by_id(&item.source_ref)
+                    .await?;
+                match fact {
+                    Some(fact) if fact.agent_id == self.agent_id => {
+                        self.index_fact(&fact, provider).await
+                    }
+                    _ => {
+                        self.delete_indexed_path(&fact_chunk_path(&item.source_ref))
+                            .await?;
+                        Ok(0)
+                    }
+                }
+            }
             DIRTY_KIND_SCHEMA | DIRTY_KIND_EMBEDDING_MODEL => {
                 self.index_all(file_store, reader, provider).await
             }
@@ -1025,7 +1148,16 @@ impl SearchIndex {
         provider: &dyn EmbeddingProvider,
         batch_limit: usize,
     ) -> Result<usize> {
-        let pending = dirty_store.list_pending(agent_id, batch_limit).await?;
+        // Facts are drained in their own batch so a burst of fact writes never
+        // holds back the file and session items queued behind it.
+        let mut pending = dirty_store
+            .list_pending_except_kind(agent_id, DIRTY_KIND_FACT, batch_limit)
+            .await?;
+        pending.extend(
+            dirty_store
+                .list_pending_of_kind(agent_id, DIRTY_KIND_FACT, batch_limit)
+                .await?,
+        );
         if pending.is_empty() {
             return Ok(0);
         }
@@ -1069,6 +1201,8 @@ impl SearchIndex {
         Ok(total)
     }
 
+    /// Whether stored embeddings came from a different model or have a
+    /// different width than `provider` produces.
     pub fn needs_reindex(&self, provider: &dyn EmbeddingProvider) -> Result<bool> {
         let conn = self
             .db
@@ -1081,7 +1215,46 @@ impl SearchIndex {
                 |r| r.get(0),
             )
             .optional()?;
-        Ok(current.as_deref() != Some(provider.model_id()))
+        let dimensions: Option<String> = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'vec_dimensions'",
+                [],
+                |r| r.get(0),
+            )
+            .optional()?;
+        Ok(current.as_deref() != Some(provider.model_id())
+            || dimensions.is_some_and(|d| d.parse::<usize>().ok() != Some(provider.dimensions())))
+    }
+
+    /// Drop every stored vector and file hash when [`needs_reindex`](Self::needs_reindex)
+    /// reports a model change, so the next `index_all` embeds everything
+    /// again with `provider`. The vector table is shared, so this resets the
+    /// index of every agent in the database. Returns whether it reset.
+    ///
+    /// A non-semantic provider never resets: it is what an unreachable
+    /// embedding service falls back to, and its vectors are never searched.
+    pub fn reset_if_model_changed(&self, provider: &dyn EmbeddingProvider) -> Result<bool> {
+        if !provider.is_semantic() || !self.needs_reindex(provider)? {
+            return Ok(false);
+        }
+        self.ensure_vec_table(provider.dimensions())?;
+
+        let conn = self
+            .db
+            .lock()
+            .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM chunks_vec", [])?;
+        tx.execute("DELETE FROM files", [])?;
+        tx.execute(
+            r#"
+            INSERT INTO meta(key, value) VALUES('embedding_model', ?1)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value
+            "#,
+            params![provider.model_id()],
+        )?;
+        tx.commit()?;
+        Ok(true)
     }
 
     pub async fn bump_access(&self, chunk_id: &str) -> Result<()> {
@@ -1100,6 +1273,47 @@ impl SearchIndex {
         Ok(())
     }
 
+    /// What this index's reader may see, or `None` for operator access.
+    async fn read_filter(&self) -> Result<Option<ReadFilter>> {
+        let Some(scope) = self.scope.clone() else {
+            return Ok(None);
+        };
+        let db = Arc::clone(&self.db);
+        let agent_id = self.agent_id.clone();
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT session_id, session_key FROM sessions WHERE agent_id = ?1 AND session_id != ''
+                UNION
+                SELECT session_id, session_key FROM session_memory_state WHERE agent_id = ?1
+                "#,
+            )?;
+            let rows = stmt.query_map(params![agent_id], |r| {
+                Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?))
+            })?;
+            // The shared layer reads transcripts without a channel owner,
+            // including ones with no recorded key, so it lists what to hide.
+            // Other readers list what they may read.
+            let allow = !scope.is_shared();
+            let mut session_ids = Vec::new();
+            for row in rows {
+                let (session_id, session_key) = row?;
+                if scope.can_read_session(&session_key) == allow {
+                    session_ids.push(session_id);
+                }
+            }
+            Ok(Some(ReadFilter {
+                scope,
+                session_ids,
+                allow,
+            }))
+        })
+        .await?
+    }
+
     pub async fn search(
         &self,
         query: &str,
@@ -1107,6 +1321,31 @@ impl SearchIndex {
         max_results: usize,
         min_score: f64,
         time_range: Option<TimeRange>,
+    ) -> Result<Vec<SearchResult>> {
+        let provider = Some(provider).filter(|provider| provider.is_semantic());
+        self.search_with(query, provider, max_results, min_score, time_range)
+            .await
+    }
+
+    /// Keyword search alone, for callers without an embedding provider.
+    pub async fn search_bm25(
+        &self,
+        query: &str,
+        max_results: usize,
+        min_score: f64,
+        time_range: Option<TimeRange>,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_with(query, None, max_results, min_score, time_range)
+            .await
+    }
+
+    async fn search_with(
+        &self,
+        query: &str,
+        provider: Option<&dyn EmbeddingProvider>,
+        max_results: usize,
+        min_score: f64,
+        time_range: Option<TimeRange>,
     ) -> Result<Vec<SearchResult>> {
         if query.trim().is_empty() {
             return Ok(Vec::new());
@@ -1118,14 +1357,15 @@ impl SearchIndex {
             max_results
         };
         let candidate_limit = (target_results.saturating_mul(4)).max(1);
-        let use_vectors = provider.is_semantic();
+        let use_vectors = provider.is_some();
+        let read_filter = Arc::new(self.read_filter().await?);
 
         // (chunk_id, path, source, start_line, end_line, text, score, created_at)
         type SearchCandidate = (String, String, String, i64, i64, String, f64, String);
 
         let mut vector_candidates: Vec<SearchCandidate> = Vec::new();
 
-        if use_vectors {
+        if let Some(provider) = provider {
             let embedded = provider.embed(&[query.to_owned()]).await?;
             let query_embedding = embedded
                 .embeddings
@@ -1138,6 +1378,7 @@ impl SearchIndex {
 
             let db = Arc::clone(&self.db);
             let agent_id = self.agent_id.clone();
+            let read_filter = Arc::clone(&read_filter);
             vector_candidates = task::spawn_blocking(move || {
             let conn = db
                 .lock()
@@ -1182,28 +1423,30 @@ impl SearchIndex {
                     vec_results.push(row?);
                 }
 
-                // Batch-check agent_id for returned chunk IDs
+                // Keep the chunk IDs this agent's reader may see
                 let chunk_ids: Vec<&str> = vec_results.iter().map(|r| r.0.as_str()).collect();
-                let owned_agent_ids = if chunk_ids.is_empty() {
-                    std::collections::HashMap::new()
+                let visible = if chunk_ids.is_empty() {
+                    std::collections::HashSet::new()
                 } else {
-                    let placeholders: String = chunk_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-                    let sql = format!("SELECT id, agent_id FROM chunks WHERE id IN ({placeholders})");
+                    let (condition, filter_params) = scope_condition(read_filter.as_ref().as_ref(), 3);
+                    let sql = format!(
+                        "SELECT c.id FROM chunks c WHERE c.id IN (SELECT value FROM json_each(?1)) AND c.agent_id = ?2 AND {condition}"
+                    );
                     let mut lookup_stmt = conn.prepare(&sql)?;
-                    let lookup_rows = lookup_stmt.query_map(rusqlite::params_from_iter(&chunk_ids), |r| {
-                        Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?))
+                    let mut lookup_params = vec![
+                        Value::Text(serde_json::to_string(&chunk_ids)?),
+                        Value::Text(agent_id.clone()),
+                    ];
+                    lookup_params.extend(filter_params);
+                    let lookup_rows = lookup_stmt.query_map(rusqlite::params_from_iter(lookup_params), |r| {
+                        r.get::<_, String>(0)
                     })?;
-                    let mut map = std::collections::HashMap::new();
-                    for lr in lookup_rows {
-                        let (id, aid) = lr?;
-                        map.insert(id, aid);
-                    }
-                    map
+                    lookup_rows.collect::<rusqlite::Result<std::collections::HashSet<_>>>()?
                 };
 
                 let mut out = Vec::new();
                 for (chunk_id, path, source, start_line, end_line, text, distance, created_at) in vec_results {
-                    if owned_agent_ids.get(&chunk_id) == Some(&agent_id) {
+                    if visible.contains(&chunk_id) {
                         let score = (1.0_f64 - distance).max(0.0_f64);
                         out.push((chunk_id, path, source, start_line, end_line, text, score, created_at));
                     }
@@ -1215,10 +1458,13 @@ impl SearchIndex {
                 );
             }
 
-            let mut stmt = conn.prepare(
-                "SELECT id, path, source, start_line, end_line, text, embedding, COALESCE(created_at, '') FROM chunks WHERE agent_id = ?1",
-            )?;
-            let rows = stmt.query_map(params![agent_id], |r| {
+            let (condition, filter_params) = scope_condition(read_filter.as_ref().as_ref(), 2);
+            let mut stmt = conn.prepare(&format!(
+                "SELECT c.id, c.path, c.source, c.start_line, c.end_line, c.text, c.embedding, COALESCE(c.created_at, '') FROM chunks c WHERE c.agent_id = ?1 AND {condition}",
+            ))?;
+            let mut query_params = vec![Value::Text(agent_id)];
+            query_params.extend(filter_params);
+            let rows = stmt.query_map(rusqlite::params_from_iter(query_params), |r| {
                 Ok((
                     r.get::<_, String>(0)?,
                     r.get::<_, String>(1)?,
@@ -1269,22 +1515,30 @@ impl SearchIndex {
             let db = Arc::clone(&self.db);
             let agent_id = self.agent_id.clone();
             let safe_fts_query_for_sql = safe_fts_query.clone();
+            let read_filter = Arc::clone(&read_filter);
             match task::spawn_blocking(move || {
                 let conn = db
                     .lock()
                     .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
-                let mut stmt = conn.prepare(
+                let (condition, filter_params) = scope_condition(read_filter.as_ref().as_ref(), 4);
+                let mut stmt = conn.prepare(&format!(
                     r#"
                     SELECT f.id, c.path, c.source, c.start_line, c.end_line, c.text, bm25(chunks_fts) AS rank, COALESCE(c.created_at, '') AS created_at
                     FROM chunks_fts f
                     JOIN chunks c ON c.id = f.id
-                    WHERE chunks_fts MATCH ?1 AND c.agent_id = ?2
+                    WHERE chunks_fts MATCH ?1 AND c.agent_id = ?2 AND {condition}
                     ORDER BY rank
                     LIMIT ?3
                     "#,
-                )?;
+                ))?;
+                let mut query_params = vec![
+                    Value::Text(safe_fts_query_for_sql),
+                    Value::Text(agent_id),
+                    Value::Integer(candidate_limit as i64),
+                ];
+                query_params.extend(filter_params);
                 let rows = stmt.query_map(
-                    params![safe_fts_query_for_sql, agent_id, candidate_limit as i64],
+                    rusqlite::params_from_iter(query_params),
                     |r| {
                         Ok((
                             r.get::<_, String>(0)?,
@@ -1384,6 +1638,14 @@ impl SearchIndex {
             }
         }
 
+        let fact_dates = merged
+            .values()
+            .filter(|item| fact_id_from_path(&item.path).is_some())
+            .filter_map(|item| {
+                parse_chunk_date(&item.created_at).map(|date| (item.chunk_id.clone(), date))
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+
         let mut results = merged
             .into_values()
             .filter(|item| {
@@ -1440,7 +1702,9 @@ impl SearchIndex {
 
         for result in &mut results {
             // Extract date from path like "memory/2026-02-25.md"
+            // Facts age from when they were recorded
             let age_days = extract_date_from_path(&result.path)
+                .or_else(|| fact_dates.get(&result.chunk_id).copied())
                 .map(|date| (today - date).num_days().max(0) as f64)
                 .unwrap_or(0.0); // Non-dated files (MEMORY.md etc) get no decay
 
@@ -1738,6 +2002,77 @@ fn topics_are_related(
     current.intersection(next).count() >= 2
 }
 
+/// What a scoped reader may see (see [`SearchIndex::read_filter`]).
+struct ReadFilter {
+    scope: MemoryScope,
+    /// Transcripts the reader may read when `allow`, else ones it may not.
+    session_ids: Vec<String>,
+    allow: bool,
+}
+
+/// SQL condition on `chunks c` admitting only chunks `filter` lets through,
+/// with its parameters numbered from `?{first}`. Facts must also be active.
+fn scope_condition(filter: Option<&ReadFilter>, first: usize) -> (String, Vec<Value>) {
+    let fact_prefix = FACTS_PATH_PREFIX;
+    let fact_id = FACTS_PATH_PREFIX.len() + 1;
+    let Some(filter) = filter else {
+        return (
+            format!(
+                "(c.path NOT GLOB '{fact_prefix}*' OR EXISTS (SELECT 1 FROM facts f \
+                 WHERE f.agent_id = c.agent_id AND f.id = substr(c.path, {fact_id}) AND f.status = 'active'))"
+            ),
+            Vec::new(),
+        );
+    };
+    let [sessions, allow, partitions, user, conversation] =
+        [first, first + 1, first + 2, first + 3, first + 4];
+    let partition_dirs = [
+        filter.scope.user_partition().partition_dir(),
+        filter.scope.conversation_partition().partition_dir(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    // Transcript chunks are `sessions/<id>` or `sessions/<id>#turn:<n>`.
+    let sql = format!(
+        "(c.path NOT GLOB 'sessions/*' \
+          OR (CASE WHEN instr(c.path, '#') > 0 THEN substr(c.path, 10, instr(c.path, '#') - 10) \
+                   ELSE substr(c.path, 10) END \
+              IN (SELECT value FROM json_each(?{sessions}))) = ?{allow}) \
+         AND (c.path NOT GLOB '{PARTITIONS_DIR}/*' OR EXISTS (SELECT 1 FROM json_each(?{partitions}) p \
+              WHERE c.path GLOB '{PARTITIONS_DIR}/' || p.value || '/*')) \
+         AND (c.path NOT GLOB '{fact_prefix}*' OR EXISTS (SELECT 1 FROM facts f \
+              WHERE f.agent_id = c.agent_id AND f.id = substr(c.path, {fact_id}) AND f.status = 'active' \
+              AND (f.user_scope IS NULL OR f.user_scope = ?{user}) \
+              AND (f.conversation_scope IS NULL OR f.conversation_scope = ?{conversation})))"
+    );
+    let json = |values: &[String]| {
+        Value::Text(serde_json::to_string(values).unwrap_or_else(|_| "[]".to_string()))
+    };
+    let text = |value: &Option<String>| value.clone().map_or(Value::Null, Value::Text);
+    (
+        sql,
+        vec![
+            json(&filter.session_ids),
+            Value::Integer(i64::from(filter.allow)),
+            json(&partition_dirs),
+            text(&filter.scope.user_scope),
+            text(&filter.scope.conversation_scope),
+        ],
+    )
+}
+
+/// Chunk path under which a fact's embedding is stored.
+pub fn fact_chunk_path(fact_id: &str) -> String {
+    format!("{FACTS_PATH_PREFIX}{fact_id}")
+}
+
+/// Inverse of [`fact_chunk_path`].
+pub fn fact_id_from_path(path: &str) -> Option<&str> {
+    path.strip_prefix(FACTS_PATH_PREFIX)
+        .filter(|id| !id.is_empty())
+}
+
 /// Extract a date from a path like "memory/2026-02-25.md"
 fn extract_date_from_path(path: &str) -> Option<chrono::NaiveDate> {
     // Match YYYY-MM-DD pattern in the path
@@ -1767,20 +2102,26 @@ fn path_matches_time_range(path: &str, time_range: Option<&TimeRange>, created_a
     }
 
     // For session chunks and other non-daily paths, filter by created_at timestamp
-    if !created_at.is_empty() {
-        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(created_at) {
-            return date_in_time_range(dt.date_naive(), range);
-        }
-        // Also try parsing without timezone (e.g. "2026-04-04T12:00:00Z" variants)
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(created_at, "%Y-%m-%dT%H:%M:%SZ") {
-            return date_in_time_range(dt.date(), range);
-        }
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(created_at, "%Y-%m-%dT%H:%M:%S") {
-            return date_in_time_range(dt.date(), range);
-        }
+    match parse_chunk_date(created_at) {
+        Some(date) => date_in_time_range(date, range),
+        None => true,
     }
+}
 
-    true
+fn parse_chunk_date(created_at: &str) -> Option<chrono::NaiveDate> {
+    if created_at.is_empty() {
+        return None;
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(created_at) {
+        return Some(dt.date_naive());
+    }
+    // Also try parsing without timezone (e.g. "2026-04-04T12:00:00Z" variants)
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(created_at, "%Y-%m-%dT%H:%M:%SZ") {
+        return Some(dt.date());
+    }
+    chrono::NaiveDateTime::parse_from_str(created_at, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .map(|dt| dt.date())
 }
 
 fn date_in_time_range(date: chrono::NaiveDate, range: &TimeRange) -> bool {
@@ -1980,6 +2321,7 @@ mod tests {
         dims: usize,
         model: String,
         value: f32,
+        semantic: bool,
     }
 
     #[derive(Clone)]
@@ -2021,8 +2363,15 @@ mod tests {
                 dims,
                 model: model.to_string(),
                 value,
+                semantic: false,
             }
         }
+
+        /// Stand in for a real embedding model.
+        fn semantic(mut self) -> Self {
+            self.semantic = true;
+            self
+        }
     }
 
     #[async_trait]
@@ -2044,7 +2393,7 @@ mod tests {
         }
 
         fn is_semantic(&self) -> bool {
-            false
+            self.semantic
         }
     }
 
@@ -2573,6 +2922,70 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn scoped_search_hides_other_users_partitions_and_transcripts() -> Result<()> {
+        let db = test_db()?;
+        let provider = StubEmbeddingProvider::new(8);
+        let alice = MemoryScope::from_session_key("telegram:tg:group:1:user:alice").unwrap();
+        let bob = MemoryScope::from_session_key("telegram:tg:group:1:user:bob").unwrap();
+        let alice_dir = alice.user_partition().partition_dir().unwrap();
+        {
+            let conn = db.lock().unwrap();
+            conn.execute(
+                "INSERT INTO sessions (session_key, session_id, agent_id, created_at, last_active, ttl_seconds)
+                 VALUES ('telegram:tg:dm:9:user:alice', 'alice-dm', 'test-agent', '', '', 0)",
+                [],
+            )?;
+        }
+
+        let index = SearchIndex::new(Arc::clone(&db), "test-agent");
+        for (path, source) in [
+            ("MEMORY.md".to_string(), "long_term"),
+            (
+                format!("memory/partitions/{alice_dir}/2026-03-01.md"),
+                "daily",
+            ),
+            ("sessions/alice-dm#turn:0".to_string(), "session"),
+        ] {
+            index
+                .index_file(&path, "# Notes\n\nAllergy to peanuts", source, &provider)
+                .await?;
+        }
+
+        let paths_for = |results: Vec<SearchResult>| {
+            let mut paths = results.into_iter().map(|r| r.path).collect::<Vec<_>>();
+            paths.sort();
+            paths
+        };
+        let for_alice = index
+            .clone()
+            .with_scope(alice)
+            .search("peanuts", &provider, 6, 0.0, None)
+            .await?;
+        assert_eq!(paths_for(for_alice).len(), 3);
+
+        let for_bob = index
+            .clone()
+            .with_scope(bob)
+            .search("peanuts", &provider, 6, 0.0, None)
+            .await?;
+        assert_eq!(paths_for(for_bob), vec!["MEMORY.md".to_string()]);
+
+        // Cron, CLI and other unscoped readers only get the shared layer.
+        let shared = index
+            .clone()
+            .search("peanuts", &provider, 6, 0.0, None)
+            .await?;
+        assert_eq!(paths_for(shared), vec!["MEMORY.md".to_string()]);
+
+        let operator = index
+            .with_operator_access()
+            .search("peanuts", &provider, 6, 0.0, None)
+            .await?;
+        assert_eq!(paths_for(operator).len(), 3);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn search_hybrid_returns_results() -> Result<()> {
         let db = test_db()?;
@@ -3037,6 +3450,67 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn needs_reindex_detects_dimension_change() -> Result<()> {
+        let db = test_db()?;
+        let index = SearchIndex::new(db, "test-agent");
+        let provider = NamedStubEmbeddingProvider::new(4, "same-model", 0.5);
+        index.ensure_vec_table(8)?;
+        {
+            let conn = index.db.lock().expect("lock");
+            conn.execute(
+                "INSERT INTO meta(key, value) VALUES('embedding_model', 'same-model')",
+                [],
+            )?;
+        }
+
+        assert!(index.needs_reindex(&provider)?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reset_if_model_changed_reembeds_unchanged_files() -> Result<()> {
+        let db = test_db()?;
+        let index = SearchIndex::new(Arc::clone(&db), "test-agent");
+        let old = NamedStubEmbeddingProvider::new(4, "old-model", 0.1).semantic();
+        let new = NamedStubEmbeddingProvider::new(8, "new-model", 0.2).semantic();
+        let content = "# Notes\n\nThe garden gate sticks in winter.";
+
+        assert!(
+            index
+                .index_file("MEMORY.md", content, "long_term", &old)
+                .await?
+                > 0
+        );
+        assert!(!index.reset_if_model_changed(&old)?);
+        assert_eq!(
+            index
+                .index_file("MEMORY.md", content, "long_term", &old)
+                .await?,
+            0
+        );
+
+        // Falling back to the keyword-only stub must not wipe the vectors.
+        assert!(!index.reset_if_model_changed(&StubEmbeddingProvider::new(8))?);
+
+        assert!(index.reset_if_model_changed(&new)?);
+        assert!(!index.needs_reindex(&new)?);
+        assert!(
+            index
+                .index_file("MEMORY.md", content, "long_term", &new)
+                .await?
+                > 0
+        );
+
+        let conn = db.lock().expect("lock");
+        let models: Vec<String> = conn
+            .prepare("SELECT DISTINCT model FROM chunks WHERE path = 'MEMORY.md'")?
+            .query_map([], |r| r.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        assert_eq!(models, vec!["new-model".to_string()]);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn index_all_indexes_files() -> Result<()> {
         let dir = TempDir::new()?;
@@ -3383,6 +3857,110 @@ mod tests {
         Ok(())
     }
 
+    fn test_fact(content: &str, user_scope: Option<&str>) -> Fact {
+        let now = chrono::Utc::now().to_rfc3339();
+        Fact {
+            id: crate::fact_store::generate_fact_id("test-agent", content),
+            agent_id: "test-agent".to_string(),
+            content: content.to_string(),
+            fact_type: "preference".to_string(),
+            importance: 0.5,
+            confidence: 1.0,
+            salience: 0,
+            status: "active".to_string(),
+            occurred_at: None,
+            recorded_at: now.clone(),
+            source_type: "test".to_string(),
+            source_session: None,
+            access_count: 0,
+            last_accessed: None,
+            superseded_by: None,
+            supersede_reason: None,
+            pinned: false,
+            affect: "neutral".to_string(),
+            affect_intensity: 0.0,
+            created_at: now.clone(),
+            updated_at: now,
+            user_scope: user_scope.map(str::to_owned),
+            conversation_scope: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn fact_dirty_items_embed_facts_and_follow_supersede() -> Result<()> {
+        let dir = TempDir::new()?;
+        let file_store = MemoryFileStore::new(dir.path());
+        let session_reader = SessionReader::new(dir.path());
+        let db = test_db()?;
+        let dirty = crate::dirty_sources::DirtySourceStore::new(Arc::clone(&db));
+        let facts = FactStore::new(Arc::clone(&db));
+        let index = SearchIndex::new(Arc::clone(&db), "test-agent");
+        let provider = KeywordVectorProvider;
+
+        let old = test_fact("vector-target drinks plain milk", None);
+        facts.insert_fact(&old).await?;
+        index
+            .process_dirty_sources(
+                &dirty,
+                "test-agent",
+                &file_store,
+                &session_reader,
+                &provider,
+                4,
+            )
+            .await?;
+        let results = index.search("keyword", &provider, 5, 0.0, None).await?;
+        assert_eq!(results[0].path, fact_chunk_path(&old.id));
+        assert_eq!(results[0].source, "fact");
+
+        let new = test_fact("vector-target drinks oat milk", None);
+        facts.supersede(&old.id, &new, "changed").await?;
+        index
+            .process_dirty_sources(
+                &dirty,
+                "test-agent",
+                &file_store,
+                &session_reader,
+                &provider,
+                4,
+            )
+            .await?;
+        assert_eq!(dirty.pending_count("test-agent").await?, 0);
+
+        let results = index.search("keyword", &provider, 5, 0.0, None).await?;
+        let paths = results.iter().map(|r| r.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, vec![fact_chunk_path(&new.id).as_str()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn search_drops_fact_chunks_the_reader_cannot_see() -> Result<()> {
+        let db = test_db()?;
+        let facts = FactStore::new(Arc::clone(&db));
+        let provider = StubEmbeddingProvider::new(8);
+        let private = test_fact("alice keeps bees", Some("telegram:user:alice"));
+        facts.insert_fact(&private).await?;
+        let index = SearchIndex::new(Arc::clone(&db), "test-agent");
+        assert!(index.index_facts(&provider).await? > 0);
+
+        let reader = |user: &str| {
+            index.clone().with_scope(MemoryScope {
+                user_scope: Some(format!("telegram:user:{user}")),
+                conversation_scope: None,
+            })
+        };
+        let owner = reader("alice")
+            .search("bees", &provider, 5, 0.0, None)
+            .await?;
+        let other = reader("bob")
+            .search("bees", &provider, 5, 0.0, None)
+            .await?;
+
+        assert_eq!(owner.len(), 1);
+        assert!(other.is_empty());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_index_session_creates_chunks() -> Result<()> {
         let dir = TempDir::new()?;
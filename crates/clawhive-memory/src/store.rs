@@ -150,6 +150,14 @@ pub struct MemoryStatsRecord {
     pub trace_count: i64,
 }
 
+/// Row count of one search index for one agent (`None` for shared ones).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MemoryIndexSize {
+    pub agent_id: Option<String>,
+    pub index: String,
+    pub entries: i64,
+}
+
 #[derive(Clone)]
 pub struct MemoryStore {
     db: Arc<Mutex<Connection>>,
@@ -367,6 +375,43 @@ impl MemoryStore {
         .await
     }
 
+    /// Sizes of the chunk and fact indexes per agent, plus the shared
+    /// embedding cache.
+    pub async fn index_sizes(&self) -> Result<Vec<MemoryIndexSize>, MemoryError> {
+        let db = Arc::clone(&self.db);
+        Self::blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+
+            let mut sizes = Vec::new();
+            for (index, table) in [("chunks", "chunks"), ("facts", "facts")] {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT COALESCE(agent_id, ''), COUNT(*) FROM {table} GROUP BY 1 ORDER BY 1"
+                ))?;
+                let rows = stmt.query_map([], |row| {
+                    Ok(MemoryIndexSize {
+                        agent_id: Some(row.get(0)?),
+                        index: index.to_string(),
+                        entries: row.get(1)?,
+                    })
+                })?;
+                for row in rows {
+                    sizes.push(row?);
+                }
+            }
+            let embedding_cache: i64 =
+                conn.query_row("SELECT COUNT(*) FROM embedding_cache", [], |row| row.get(0))?;
+            sizes.push(MemoryIndexSize {
+                agent_id: None,
+                index: "embedding_cache".to_string(),
+                entries: embedding_cache,
+            });
+            Ok(sizes)
+        })
+        .await
+    }
+
     pub async fn get_session(&self, key: &str) -> Result<Option<SessionRecord>, MemoryError> {
         let db = Arc::clone(&self.db);
         let key = key.to_owned();
@@ -1193,6 +1238,32 @@ mod tests {
         .expect("insert embedding_cache");
     }
 
+    #[tokio::test]
+    async fn index_sizes_group_by_agent() {
+        let store = MemoryStore::open_in_memory().expect("store");
+        {
+            let db = store.db.lock().expect("lock");
+            for (id, agent) in [("c1", "agent-a"), ("c2", "agent-a"), ("c3", "agent-b")] {
+                db.execute(
+                    "INSERT INTO chunks (id, path, source, start_line, end_line, hash, model, text, embedding, updated_at, agent_id) VALUES (?1, 'm.md', 'memory', 1, 2, 'h', 'm', 't', '', '2024-01-01T00:00:00Z', ?2)",
+                    params![id, agent],
+                )
+                .expect("insert chunk");
+            }
+        }
+
+        let sizes = store.index_sizes().await.expect("sizes");
+        let chunks: Vec<_> = sizes
+            .iter()
+            .filter(|size| size.index == "chunks")
+            .map(|size| (size.agent_id.as_deref().unwrap(), size.entries))
+            .collect();
+        assert_eq!(chunks, vec![("agent-a", 2), ("agent-b", 1)]);
+        assert!(sizes
+            .iter()
+            .any(|size| size.index == "embedding_cache" && size.entries == 0));
+    }
+
     #[tokio::test]
     async fn session_crud() {
         let store = MemoryStore::open_in_memory().expect("store");
@@ -0,0 +1,604 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rusqlite::{params_from_iter, types::Value, Connection};
+use serde::{Deserialize, Serialize};
+use tokio::task;
+
+/// Why an LLM call was made. Stored as its snake_case name.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UsagePurpose {
+    /// A conversational turn (tool loop or streamed reply).
+    Turn,
+    /// Context-window compaction of a long session.
+    Compaction,
+    /// Session summaries written on reset or boundary flush.
+    Summary,
+    /// Memory consolidation, fact reconciliation and staleness checks.
+    Consolidation,
+    /// A sub-agent run spawned by another agent.
+    Subagent,
+    /// An LLM call made by a tool on the agent's behalf.
+    Tool,
+    #[default]
+    Other,
+}
+
+impl UsagePurpose {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Turn => "turn",
+            Self::Compaction => "compaction",
+            Self::Summary => "summary",
+            Self::Consolidation => "consolidation",
+            Self::Subagent => "subagent",
+            Self::Tool => "tool",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// One LLM call as recorded in the `llm_usage` table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UsageRecord {
+    pub recorded_at: String,
+    pub agent_id: String,
+    pub session_key: Option<String>,
+    /// Sender the call was made for, when it came from a channel message.
+    #[serde(default)]
+    pub user_scope: Option<String>,
+    pub provider: String,
+    pub model: String,
+    pub purpose: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cache_read_tokens: u32,
+    pub cache_write_tokens: u32,
+    pub latency_ms: u64,
+    /// `None` when the model has no known pricing.
+    pub cost_usd: Option<f64>,
+}
+
+/// Dimension a usage report is aggregated over.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGroupBy {
+    #[default]
+    Agent,
+    Session,
+    User,
+    /// Channel type, taken from the first segment of the session key.
+    Channel,
+    Provider,
+    Model,
+    Purpose,
+    Day,
+}
+
+impl UsageGroupBy {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Agent => "agent",
+            Self::Session => "session",
+            Self::User => "user",
+            Self::Channel => "channel",
+            Self::Provider => "provider",
+            Self::Model => "model",
+            Self::Purpose => "purpose",
+            Self::Day => "day",
+        }
+    }
+
+    fn sql_key(&self) -> &'static str {
+        match self {
+            Self::Agent => "agent_id",
+            Self::Session => "COALESCE(session_key, '')",
+            Self::User => "COALESCE(user_scope, '')",
+            Self::Channel => {
+                "CASE WHEN instr(COALESCE(session_key, ''), ':') > 0 \
+                 THEN substr(session_key, 1, instr(session_key, ':') - 1) \
+                 ELSE '' END"
+            }
+            Self::Provider => "provider",
+            Self::Model => "provider || '/' || model",
+            Self::Purpose => "purpose",
+            Self::Day => "substr(recorded_at, 1, 10)",
+        }
+    }
+}
+
+impl std::str::FromStr for UsageGroupBy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "agent" => Ok(Self::Agent),
+            "session" => Ok(Self::Session),
+            "user" => Ok(Self::User),
+            "channel" => Ok(Self::Channel),
+            "provider" => Ok(Self::Provider),
+            "model" => Ok(Self::Model),
+            "purpose" => Ok(Self::Purpose),
+            "day" => Ok(Self::Day),
+            other => Err(anyhow!(
+                "unknown usage grouping: {other} (expected agent, session, user, channel, provider, model, purpose or day)"
+            )),
+        }
+    }
+}
+
+/// Row filter shared by the usage queries. Timestamps are compared as
+/// RFC 3339 strings, so a bare `YYYY-MM-DD` works for both bounds.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UsageFilter {
+    /// Inclusive lower bound on `recorded_at`.
+    pub since: Option<String>,
+    /// Exclusive upper bound on `recorded_at`.
+    pub until: Option<String>,
+    pub agent_id: Option<String>,
+    pub session_key: Option<String>,
+    #[serde(default)]
+    pub user_scope: Option<String>,
+}
+
+impl UsageFilter {
+    fn where_clause(&self) -> (String, Vec<Value>) {
+        let mut clauses = Vec::new();
+        let mut values = Vec::new();
+        if let Some(since) = &self.since {
+            values.push(Value::Text(since.clone()));
+            clauses.push(format!("recorded_at >= ?{}", values.len()));
+        }
+        if let Some(until) = &self.until {
+            values.push(Value::Text(until.clone()));
+            clauses.push(format!("recorded_at < ?{}", values.len()));
+        }
+        if let Some(agent_id) = &self.agent_id {
+            values.push(Value::Text(agent_id.clone()));
+            clauses.push(format!("agent_id = ?{}", values.len()));
+        }
+        if let Some(session_key) = &self.session_key {
+            values.push(Value::Text(session_key.clone()));
+            clauses.push(format!("session_key = ?{}", values.len()));
+        }
+        if let Some(user_scope) = &self.user_scope {
+            values.push(Value::Text(user_scope.clone()));
+            clauses.push(format!("user_scope = ?{}", values.len()));
+        }
+        if clauses.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!("WHERE {}", clauses.join(" AND ")), values)
+        }
+    }
+}
+
+/// Aggregated usage for one value of a [`UsageGroupBy`] dimension.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UsageSummary {
+    pub key: String,
+    pub calls: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub total_latency_ms: u64,
+    pub cost_usd: f64,
+    /// Calls whose model had no pricing and so are missing from `cost_usd`.
+    pub unpriced_calls: u64,
+}
+
+#[derive(Clone)]
+pub struct UsageLedger {
+    db: Arc<Mutex<Connection>>,
+}
+
+impl UsageLedger {
+    pub fn new(db: Arc<Mutex<Connection>>) -> Self {
+        Self { db }
+    }
+
+    /// Append one call to the ledger. An empty `recorded_at` is stamped with
+    /// the current time.
+    pub async fn record(&self, record: UsageRecord) -> Result<()> {
+        let db = Arc::clone(&self.db);
+        let recorded_at = if record.recorded_at.is_empty() {
+            Utc::now().to_rfc3339()
+        } else {
+            record.recorded_at.clone()
+        };
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            conn.execute(
+                "INSERT INTO llm_usage (recorded_at, agent_id, session_key, user_scope, provider, model, \
+                 purpose, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, \
+                 latency_ms, cost_usd) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                rusqlite::params![
+                    recorded_at,
+                    record.agent_id,
+                    record.session_key,
+                    record.user_scope,
+                    record.provider,
+                    record.model,
+                    record.purpose,
+                    record.input_tokens,
+                    record.output_tokens,
+                    record.cache_read_tokens,
+                    record.cache_write_tokens,
+                    record.latency_ms as i64,
+                    record.cost_usd,
+                ],
+            )?;
+            Ok::<(), anyhow::Error>(())
+        })
+        .await??;
+        Ok(())
+    }
+
+    /// Totals per `group_by` key, most expensive first.
+    pub async fn summarize(
+        &self,
+        filter: &UsageFilter,
+        group_by: UsageGroupBy,
+    ) -> Result<Vec<UsageSummary>> {
+        let db = Arc::clone(&self.db);
+        let (where_clause, values) = filter.where_clause();
+        let sql = format!(
+            "SELECT {key} AS key, {aggregates} FROM llm_usage {where_clause} \
+             GROUP BY key ORDER BY SUM(COALESCE(cost_usd, 0)) DESC, COUNT(*) DESC, key ASC",
+            key = group_by.sql_key(),
+            aggregates = SUMMARY_AGGREGATES,
+        );
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values), |row| {
+                row_to_summary(row, row.get(0)?)
+            })?;
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok::<Vec<UsageSummary>, anyhow::Error>(items)
+        })
+        .await?
+    }
+
+    /// Grand total across every row matching `filter`.
+    pub async fn total(&self, filter: &UsageFilter) -> Result<UsageSummary> {
+        let db = Arc::clone(&self.db);
+        let (where_clause, values) = filter.where_clause();
+        let sql = format!("SELECT 'total', {SUMMARY_AGGREGATES} FROM llm_usage {where_clause}");
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let summary = conn.query_row(&sql, params_from_iter(values), |row| {
+                row_to_summary(row, row.get(0)?)
+            })?;
+            Ok::<UsageSummary, anyhow::Error>(summary)
+        })
+        .await?
+    }
+
+    /// Most recent individual calls matching `filter`, newest first.
+    pub async fn list_recent(
+        &self,
+        filter: &UsageFilter,
+        limit: usize,
+    ) -> Result<Vec<UsageRecord>> {
+        let db = Arc::clone(&self.db);
+        let (where_clause, mut values) = filter.where_clause();
+        values.push(Value::Integer(limit as i64));
+        let sql = format!(
+            "SELECT recorded_at, agent_id, session_key, user_scope, provider, model, purpose, \
+             input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, latency_ms, cost_usd \
+             FROM llm_usage {where_clause} ORDER BY recorded_at DESC, id DESC LIMIT ?{}",
+            values.len()
+        );
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values), row_to_usage_record)?;
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok::<Vec<UsageRecord>, anyhow::Error>(items)
+        })
+        .await?
+    }
+
+    /// Whether spending past the budget `budget_key` was approved for the
+    /// period starting at `period_start`.
+    pub async fn has_budget_override(&self, budget_key: &str, period_start: &str) -> Result<bool> {
+        let db = Arc::clone(&self.db);
+        let budget_key = budget_key.to_owned();
+        let period_start = period_start.to_owned();
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            let found: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM budget_overrides WHERE budget_key = ?1 AND period_start = ?2)",
+                rusqlite::params![budget_key, period_start],
+                |row| row.get(0),
+            )?;
+            Ok::<bool, anyhow::Error>(found)
+        })
+        .await?
+    }
+
+    /// Allow the budget `budget_key` to be exceeded until its current period
+    /// (starting at `period_start`) ends.
+    pub async fn grant_budget_override(&self, budget_key: &str, period_start: &str) -> Result<()> {
+        let db = Arc::clone(&self.db);
+        let budget_key = budget_key.to_owned();
+        let period_start = period_start.to_owned();
+        task::spawn_blocking(move || {
+            let conn = db
+                .lock()
+                .map_err(|_| anyhow!("failed to lock sqlite connection"))?;
+            conn.execute(
+                "INSERT OR REPLACE INTO budget_overrides (budget_key, period_start, granted_at) \
+                 VALUES (?1, ?2, ?3)",
+                rusqlite::params![budget_key, period_start, Utc::now().to_rfc3339()],
+            )?;
+            Ok::<(), anyhow::Error>(())
+        })
+        .await??;
+        Ok(())
+    }
+}
+
+const SUMMARY_AGGREGATES: &str = "COUNT(*), COALESCE(SUM(input_tokens), 0), \
+     COALESCE(SUM(output_tokens), 0), COALESCE(SUM(cache_read_tokens), 0), \
+     COALESCE(SUM(cache_write_tokens), 0), COALESCE(SUM(latency_ms), 0), \
+     COALESCE(SUM(cost_usd), 0.0), COALESCE(SUM(cost_usd IS NULL), 0)";
+
+fn row_to_summary(row: &rusqlite::Row<'_>, key: String) -> rusqlite::Result<UsageSummary> {
+    Ok(UsageSummary {
+        key,
+        calls: row.get::<_, i64>(1)? as u64,
+        input_tokens: row.get::<_, i64>(2)? as u64,
+        output_tokens: row.get::<_, i64>(3)? as u64,
+        cache_read_tokens: row.get::<_, i64>(4)? as u64,
+        cache_write_tokens: row.get::<_, i64>(5)? as u64,
+        total_latency_ms: row.get::<_, i64>(6)? as u64,
+        cost_usd: row.get(7)?,
+        unpriced_calls: row.get::<_, i64>(8)? as u64,
+    })
+}
+
+fn row_to_usage_record(row: &rusqlite::Row<'_>) -> rusqlite::Result<UsageRecord> {
+    Ok(UsageRecord {
+        recorded_at: row.get(0)?,
+        agent_id: row.get(1)?,
+        session_key: row.get(2)?,
+        user_scope: row.get(3)?,
+        provider: row.get(4)?,
+        model: row.get(5)?,
+        purpose: row.get(6)?,
+        input_tokens: row.get(7)?,
+        output_tokens: row.get(8)?,
+        cache_read_tokens: row.get(9)?,
+        cache_write_tokens: row.get(10)?,
+        latency_ms: row.get::<_, i64>(11)? as u64,
+        cost_usd: row.get(12)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MemoryStore;
+
+    use super::*;
+
+    fn record(
+        recorded_at: &str,
+        agent_id: &str,
+        session_key: Option<&str>,
+        model: &str,
+        purpose: UsagePurpose,
+        cost_usd: Option<f64>,
+    ) -> UsageRecord {
+        UsageRecord {
+            recorded_at: recorded_at.to_owned(),
+            agent_id: agent_id.to_owned(),
+            session_key: session_key.map(ToOwned::to_owned),
+            user_scope: None,
+            provider: "anthropic".to_owned(),
+            model: model.to_owned(),
+            purpose: purpose.as_str().to_owned(),
+            input_tokens: 100,
+            output_tokens: 20,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            latency_ms: 250,
+            cost_usd,
+        }
+    }
+
+    async fn seeded_ledger() -> UsageLedger {
+        let store = MemoryStore::open_in_memory().unwrap();
+        let ledger = UsageLedger::new(store.db());
+        for rec in [
+            record(
+                "2026-03-01T10:00:00+00:00",
+                "main",
+                Some("telegram:tg_main:chat:1:user:1"),
+                "claude-sonnet-4-6",
+                UsagePurpose::Turn,
+                Some(0.5),
+            ),
+            record(
+                "2026-03-01T11:00:00+00:00",
+                "main",
+                Some("discord:dc_main:chan:9:user:2"),
+                "claude-sonnet-4-6",
+                UsagePurpose::Turn,
+                Some(0.25),
+            ),
+            record(
+                "2026-03-02T09:00:00+00:00",
+                "main",
+                None,
+                "claude-haiku-4-5",
+                UsagePurpose::Consolidation,
+                Some(0.01),
+            ),
+            record(
+                "2026-03-02T12:00:00+00:00",
+                "helper",
+                Some("telegram:tg_main:chat:3:user:3"),
+                "llama3.2",
+                UsagePurpose::Turn,
+                None,
+            ),
+        ] {
+            ledger.record(rec).await.unwrap();
+        }
+        ledger
+    }
+
+    #[tokio::test]
+    async fn summarize_groups_by_agent_and_sorts_by_cost() {
+        let ledger = seeded_ledger().await;
+        let rows = ledger
+            .summarize(&UsageFilter::default(), UsageGroupBy::Agent)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].key, "main");
+        assert_eq!(rows[0].calls, 3);
+        assert_eq!(rows[0].input_tokens, 300);
+        assert!((rows[0].cost_usd - 0.76).abs() < 1e-9);
+        assert_eq!(rows[1].key, "helper");
+        assert_eq!(rows[1].unpriced_calls, 1);
+        assert_eq!(rows[1].cost_usd, 0.0);
+    }
+
+    #[tokio::test]
+    async fn summarize_derives_channel_from_session_key() {
+        let ledger = seeded_ledger().await;
+        let rows = ledger
+            .summarize(&UsageFilter::default(), UsageGroupBy::Channel)
+            .await
+            .unwrap();
+        let keys: Vec<_> = rows.iter().map(|r| (r.key.as_str(), r.calls)).collect();
+        assert_eq!(keys, vec![("telegram", 2), ("discord", 1), ("", 1)]);
+    }
+
+    #[tokio::test]
+    async fn filter_applies_time_window_and_agent() {
+        let ledger = seeded_ledger().await;
+        let filter = UsageFilter {
+            since: Some("2026-03-02".into()),
+            agent_id: Some("main".into()),
+            ..Default::default()
+        };
+        let total = ledger.total(&filter).await.unwrap();
+        assert_eq!(total.calls, 1);
+        assert!((total.cost_usd - 0.01).abs() < 1e-9);
+
+        let by_day = ledger
+            .summarize(
+                &UsageFilter {
+                    until: Some("2026-03-02".into()),
+                    ..Default::default()
+                },
+                UsageGroupBy::Day,
+            )
+            .await
+            .unwrap();
+        assert_eq!(by_day.len(), 1);
+        assert_eq!(by_day[0].key, "2026-03-01");
+        assert_eq!(by_day[0].calls, 2);
+    }
+
+    #[tokio::test]
+    async fn list_recent_returns_newest_first() {
+        let ledger = seeded_ledger().await;
+        let recent = ledger
+            .list_recent(&UsageFilter::default(), 2)
+            .await
+            .unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].agent_id, "helper");
+        assert_eq!(recent[0].cost_usd, None);
+        assert_eq!(recent[1].purpose, "consolidation");
+    }
+
+    #[tokio::test]
+    async fn user_scope_is_filterable_and_groupable() {
+        let ledger = seeded_ledger().await;
+        ledger
+            .record(UsageRecord {
+                recorded_at: "2026-03-03T08:00:00+00:00".into(),
+                agent_id: "main".into(),
+                user_scope: Some("user:1".into()),
+                cost_usd: Some(0.1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let filter = UsageFilter {
+            user_scope: Some("user:1".into()),
+            ..Default::default()
+        };
+        let total = ledger.total(&filter).await.unwrap();
+        assert_eq!(total.calls, 1);
+        assert_eq!(
+            ledger.list_recent(&filter, 1).await.unwrap()[0].user_scope,
+            Some("user:1".to_string())
+        );
+
+        let rows = ledger
+            .summarize(&UsageFilter::default(), UsageGroupBy::User)
+            .await
+            .unwrap();
+        assert_eq!(rows[0].key, "");
+        assert_eq!(rows[0].calls, 4);
+        assert_eq!(rows[1].key, "user:1");
+    }
+
+    #[tokio::test]
+    async fn budget_overrides_are_scoped_to_their_period() {
+        let ledger = seeded_ledger().await;
+        assert!(!ledger
+            .has_budget_override("agent:main:daily", "2026-03-01")
+            .await
+            .unwrap());
+        ledger
+            .grant_budget_override("agent:main:daily", "2026-03-01")
+            .await
+            .unwrap();
+        assert!(ledger
+            .has_budget_override("agent:main:daily", "2026-03-01")
+            .await
+            .unwrap());
+        assert!(!ledger
+            .has_budget_override("agent:main:daily", "2026-03-02")
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    fn group_by_parses_known_dimensions() {
+        assert_eq!(
+            "purpose".parse::<UsageGroupBy>().unwrap(),
+            UsageGroupBy::Purpose
+        );
+        assert!("colour".parse::<UsageGroupBy>().is_err());
+    }
+}
@@ -0,0 +1,365 @@
+//! Process-wide runtime metrics in the Prometheus text exposition format.
+//!
+//! Hot paths (provider calls, tool calls, turns) record into the global
+//! [`Registry`] as they happen. State that already lives elsewhere — bus
+//! queues, cooldowns, memory index sizes — is copied in by the `/metrics`
+//! handler right before [`Registry::render`].
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Metric {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+}
+
+pub static PROVIDER_REQUEST_SECONDS: Metric = Metric {
+    name: "clawhive_provider_request_duration_seconds",
+    help: "LLM provider request latency, per attempt.",
+    kind: MetricKind::Histogram,
+};
+pub static PROVIDER_FAILOVERS: Metric = Metric {
+    name: "clawhive_provider_failovers_total",
+    help: "Provider failures that triggered a cooldown and failover, by reason.",
+    kind: MetricKind::Counter,
+};
+pub static PROVIDER_IN_COOLDOWN: Metric = Metric {
+    name: "clawhive_provider_in_cooldown",
+    help: "1 while a provider is skipped because of a recent failure.",
+    kind: MetricKind::Gauge,
+};
+pub static PROVIDER_COOLDOWN_REMAINING_SECONDS: Metric = Metric {
+    name: "clawhive_provider_cooldown_remaining_seconds",
+    help: "Seconds until a provider in cooldown is tried again.",
+    kind: MetricKind::Gauge,
+};
+pub static TOOL_CALL_SECONDS: Metric = Metric {
+    name: "clawhive_tool_call_duration_seconds",
+    help: "Tool execution time.",
+    kind: MetricKind::Histogram,
+};
+pub static TOOL_CALL_FAILURES: Metric = Metric {
+    name: "clawhive_tool_call_failures_total",
+    help: "Tool calls that returned an error.",
+    kind: MetricKind::Counter,
+};
+pub static TURN_SECONDS: Metric = Metric {
+    name: "clawhive_turn_duration_seconds",
+    help: "Time to handle one inbound message, from routing to reply.",
+    kind: MetricKind::Histogram,
+};
+pub static RATE_LIMIT_REJECTIONS: Metric = Metric {
+    name: "clawhive_rate_limit_rejections_total",
+    help: "Inbound messages refused by the gateway rate limiter.",
+    kind: MetricKind::Counter,
+};
+pub static BUS_QUEUE_DEPTH: Metric = Metric {
+    name: "clawhive_bus_queue_depth",
+    help: "Messages waiting in bus subscriber queues, by topic.",
+    kind: MetricKind::Gauge,
+};
+pub static BUS_DROPPED_MESSAGES: Metric = Metric {
+    name: "clawhive_bus_dropped_messages_total",
+    help: "Bus messages discarded because a subscriber queue was full.",
+    kind: MetricKind::Counter,
+};
+pub static SCHEDULER_RUNS: Metric = Metric {
+    name: "clawhive_scheduler_runs_total",
+    help: "Completed scheduled task runs, by outcome and delivery status.",
+    kind: MetricKind::Counter,
+};
+pub static MEMORY_INDEX_ENTRIES: Metric = Metric {
+    name: "clawhive_memory_index_entries",
+    help: "Rows in the memory search indexes, by agent and index.",
+    kind: MetricKind::Gauge,
+};
+
+/// Upper bounds in seconds, from fast tool calls to long agent turns.
+const DURATION_BUCKETS: &[f64] = &[
+    0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0,
+];
+
+type Labels = Vec<(&'static str, String)>;
+
+enum Series {
+    Value(f64),
+    Histogram {
+        buckets: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+struct Family {
+    metric: &'static Metric,
+    series: BTreeMap<Labels, Series>,
+}
+
+#[derive(Default)]
+pub struct Registry {
+    families: Mutex<BTreeMap<&'static str, Family>>,
+}
+
+pub fn global() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Registry::default)
+}
+
+impl Registry {
+    fn with_series(
+        &self,
+        metric: &'static Metric,
+        labels: &[(&'static str, &str)],
+        update: impl FnOnce(&mut Series),
+    ) {
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let family = families.entry(metric.name).or_insert_with(|| Family {
+            metric,
+            series: BTreeMap::new(),
+        });
+        let labels = labels
+            .iter()
+            .map(|(name, value)| (*name, (*value).to_string()))
+            .collect();
+        let series = family
+            .series
+            .entry(labels)
+            .or_insert_with(|| match metric.kind {
+                MetricKind::Histogram => Series::Histogram {
+                    buckets: vec![0; DURATION_BUCKETS.len()],
+                    sum: 0.0,
+                    count: 0,
+                },
+                _ => Series::Value(0.0),
+            });
+        update(series);
+    }
+
+    pub fn inc(&self, metric: &'static Metric, labels: &[(&'static str, &str)]) {
+        self.with_series(metric, labels, |series| {
+            if let Series::Value(value) = series {
+                *value += 1.0;
+            }
+        });
+    }
+
+    /// Set a gauge, or a counter whose total is tracked elsewhere.
+    pub fn set(&self, metric: &'static Metric, labels: &[(&'static str, &str)], value: f64) {
+        self.with_series(metric, labels, |series| {
+            if let Series::Value(current) = series {
+                *current = value;
+            }
+        });
+    }
+
+    pub fn observe(&self, metric: &'static Metric, labels: &[(&'static str, &str)], value: f64) {
+        self.with_series(metric, labels, |series| {
+            if let Series::Histogram {
+                buckets,
+                sum,
+                count,
+            } = series
+            {
+                for (bucket, bound) in buckets.iter_mut().zip(DURATION_BUCKETS) {
+                    if value <= *bound {
+                        *bucket += 1;
+                    }
+                }
+                *sum += value;
+                *count += 1;
+            }
+        });
+    }
+
+    /// Forget every series of `metric`, so a scrape-time refresh does not
+    /// keep reporting label sets that no longer exist.
+    pub fn clear(&self, metric: &'static Metric) {
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        families.remove(metric.name);
+    }
+
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        for family in families.values() {
+            let name = family.metric.name;
+            let _ = writeln!(out, "# HELP {name} {}", family.metric.help);
+            let _ = writeln!(out, "# TYPE {name} {}", family.metric.kind.as_str());
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Value(value) => {
+                        let _ = writeln!(out, "{name}{} {value}", format_labels(labels, None));
+                    }
+                    Series::Histogram {
+                        buckets,
+                        sum,
+                        count,
+                    } => {
+                        for (bucket, bound) in buckets.iter().zip(DURATION_BUCKETS) {
+                            let le = bound.to_string();
+                            let _ = writeln!(
+                                out,
+                                "{name}_bucket{} {bucket}",
+                                format_labels(labels, Some(&le))
+                            );
+                        }
+                        let _ = writeln!(
+                            out,
+                            "{name}_bucket{} {count}",
+                            format_labels(labels, Some("+Inf"))
+                        );
+                        let _ = writeln!(out, "{name}_sum{} {sum}", format_labels(labels, None));
+                        let _ =
+                            writeln!(out, "{name}_count{} {count}", format_labels(labels, None));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn outcome(ok: bool) -> &'static str {
+    if ok {
+        "ok"
+    } else {
+        "error"
+    }
+}
+
+pub fn record_provider_request(provider: &str, model: &str, ok: bool, latency: Duration) {
+    global().observe(
+        &PROVIDER_REQUEST_SECONDS,
+        &[
+            ("provider", provider),
+            ("model", model),
+            ("outcome", outcome(ok)),
+        ],
+        latency.as_secs_f64(),
+    );
+}
+
+pub fn record_provider_failover(provider: &str, reason: &str) {
+    global().inc(
+        &PROVIDER_FAILOVERS,
+        &[("provider", provider), ("reason", reason)],
+    );
+}
+
+pub fn record_tool_call(tool: &str, ok: bool, duration: Duration) {
+    global().observe(
+        &TOOL_CALL_SECONDS,
+        &[("tool", tool)],
+        duration.as_secs_f64(),
+    );
+    if !ok {
+        global().inc(&TOOL_CALL_FAILURES, &[("tool", tool)]);
+    }
+}
+
+pub fn record_turn(agent_id: &str, ok: bool, duration: Duration) {
+    global().observe(
+        &TURN_SECONDS,
+        &[("agent", agent_id), ("outcome", outcome(ok))],
+        duration.as_secs_f64(),
+    );
+}
+
+pub fn record_rate_limit_rejection() {
+    global().inc(&RATE_LIMIT_REJECTIONS, &[]);
+}
+
+pub fn record_scheduler_run(status: &str, delivery_status: &str) {
+    global().inc(
+        &SCHEDULER_RUNS,
+        &[("status", status), ("delivery", delivery_status)],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_histograms_in_text_format() {
+        let registry = Registry::default();
+        registry.inc(&TOOL_CALL_FAILURES, &[("tool", "exec")]);
+        registry.inc(&TOOL_CALL_FAILURES, &[("tool", "exec")]);
+        registry.observe(&TURN_SECONDS, &[("agent", "main"), ("outcome", "ok")], 0.3);
+        registry.observe(&TURN_SECONDS, &[("agent", "main"), ("outcome", "ok")], 7.0);
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE clawhive_tool_call_failures_total counter\n"));
+        assert!(text.contains("clawhive_tool_call_failures_total{tool=\"exec\"} 2\n"));
+        assert!(text.contains(
+            "clawhive_turn_duration_seconds_bucket{agent=\"main\",outcome=\"ok\",le=\"0.25\"} 0\n"
+        ));
+        assert!(text.contains(
+            "clawhive_turn_duration_seconds_bucket{agent=\"main\",outcome=\"ok\",le=\"0.5\"} 1\n"
+        ));
+        assert!(text.contains(
+            "clawhive_turn_duration_seconds_bucket{agent=\"main\",outcome=\"ok\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(text
+            .contains("clawhive_turn_duration_seconds_sum{agent=\"main\",outcome=\"ok\"} 7.3\n"));
+        assert!(text
+            .contains("clawhive_turn_duration_seconds_count{agent=\"main\",outcome=\"ok\"} 2\n"));
+    }
+
+    #[test]
+    fn clear_drops_stale_series_and_labels_are_escaped() {
+        let registry = Registry::default();
+        registry.set(&BUS_QUEUE_DEPTH, &[("topic", "ReplyReady")], 3.0);
+        registry.clear(&BUS_QUEUE_DEPTH);
+        registry.set(&BUS_QUEUE_DEPTH, &[("topic", "say \"hi\"\n")], 1.0);
+        registry.inc(&RATE_LIMIT_REJECTIONS, &[]);
+
+        let text = registry.render();
+        assert!(!text.contains("ReplyReady"));
+        assert!(text.contains("clawhive_bus_queue_depth{topic=\"say \\\"hi\\\"\\n\"} 1\n"));
+        assert!(text.contains("clawhive_rate_limit_rejections_total 1\n"));
+    }
+}
@@ -8,7 +8,7 @@ use std::pin::Pin;
 use tokio_stream::StreamExt;
 
 use crate::error::ProviderError;
-use crate::{LlmProvider, LlmRequest, LlmResponse, StreamChunk};
+use crate::{LlmProvider, LlmRequest, LlmResponse, ResponseFormat, StreamChunk, ToolChoice};
 
 #[derive(Debug, Clone)]
 pub struct AnthropicProvider {
@@ -84,44 +84,90 @@ impl AnthropicProvider {
     }
 
     pub(crate) fn to_api_request(request: LlmRequest) -> ApiRequest {
+        let cache = request.cache;
+        let tool_count = request.tools.len();
         let tools: Vec<ApiToolDef> = request
             .tools
             .into_iter()
-            .map(|t| ApiToolDef {
+            .enumerate()
+            .map(|(idx, t)| ApiToolDef {
                 name: t.name,
                 description: t.description,
                 input_schema: t.input_schema,
+                // A breakpoint on the last tool caches the whole tool list.
+                cache_control: (cache.tools && idx + 1 == tool_count).then(ephemeral_cache),
             })
             .collect();
 
-        let thinking = request.thinking_level.map(|level| {
+        let system = request.system.map(|text| {
+            if cache.system {
+                serde_json::json!([{
+                    "type": "text",
+                    "text": text,
+                    "cache_control": ephemeral_cache()
+                }])
+            } else {
+                serde_json::Value::String(text)
+            }
+        });
+
+        // Extended thinking only allows automatic tool choice and the default
+        // sampling temperature, so a forced tool wins over thinking and
+        // thinking wins over sampling overrides.
+        let forces_tool = matches!(
+            request.tool_choice,
+            Some(ToolChoice::Any | ToolChoice::Tool { .. })
+        );
+        let thinking_level = request.thinking_level.filter(|_| !forces_tool);
+
+        let thinking = thinking_level.map(|level| {
             serde_json::json!({
                 "type": "enabled",
                 "budget_tokens": level.anthropic_budget_tokens()
             })
         });
 
-        let max_tokens = if let Some(level) = request.thinking_level {
+        let max_tokens = if let Some(level) = thinking_level {
             request.max_tokens.max(level.anthropic_min_max_tokens())
         } else {
             request.max_tokens
         };
 
+        // Anthropic rejects temperature and top_p together on current models;
+        // temperature wins when both are set.
+        let (temperature, top_p) = if thinking_level.is_some() {
+            (None, None)
+        } else if request.sampling.temperature.is_some() {
+            (request.sampling.temperature, None)
+        } else {
+            (None, request.sampling.top_p)
+        };
+
+        let tool_choice = request.tool_choice.map(|choice| match choice {
+            ToolChoice::Auto => serde_json::json!({"type": "auto"}),
+            ToolChoice::Any => serde_json::json!({"type": "any"}),
+            ToolChoice::None => serde_json::json!({"type": "none"}),
+            ToolChoice::Tool { name } => serde_json::json!({"type": "tool", "name": name}),
+        });
+
         ApiRequest {
             model: request.model,
-            system: request.system,
+            system,
             max_tokens,
             messages: request
                 .messages
                 .into_iter()
-                .map(|m| {
+                .enumerate()
+                .map(|(idx, m)| {
+                    let cache_here = cache.history_through == Some(idx);
                     let has_non_text = m
                         .content
                         .iter()
                         .any(|b| !matches!(b, crate::ContentBlock::Text { .. }));
-                    if has_non_text {
-                        // Send as array for tool_use/tool_result/image messages
-                        let blocks: Vec<serde_json::Value> = m
+                    if has_non_text || cache_here {
+                        // Send as array for tool_use/tool_result/image messages and
+                        // for the cache breakpoint, which needs a block to sit on
+                        let mut blocks: Vec<serde_json::Value> = m
                             .content
                             .iter()
                             .map(|b| match b {
@@ -150,6 +196,11 @@ impl AnthropicProvider {
                                 }
                             })
                             .collect();
+                        if cache_here {
+                            if let Some(serde_json::Value::Object(last)) = blocks.last_mut() {
+                                last.insert("cache_control".into(), ephemeral_cache());
+                            }
+                        }
                         ApiMessage {
                             role: m.role,
                             content: serde_json::Value::Array(blocks),
@@ -164,6 +215,10 @@ impl AnthropicProvider {
                 })
                 .collect(),
             tools: if tools.is_empty() { None } else { Some(tools) },
+            tool_choice,
+            temperature,
+            top_p,
+            stop_sequences: request.sampling.stop,
             stream: false,
             thinking,
         }
@@ -172,8 +227,9 @@ impl AnthropicProvider {
 
 #[async_trait]
 impl LlmProvider for AnthropicProvider {
-    async fn chat(&self, request: LlmRequest) -> Result<LlmResponse, ProviderError> {
+    async fn chat(&self, mut request: LlmRequest) -> Result<LlmResponse, ProviderError> {
         let url = format!("{}/messages", self.api_base);
+        let structured = request.emulate_json_schema_with_tool();
         let payload = Self::to_api_request(request);
 
         let mut req = self
@@ -244,12 +300,22 @@ impl LlmProvider for AnthropicProvider {
             .collect::<Vec<_>>()
             .join("\n");
 
-        Ok(LlmResponse {
+        let response = LlmResponse {
             text,
             content: content_blocks,
             input_tokens: body.usage.as_ref().map(|u| u.input_tokens),
             output_tokens: body.usage.as_ref().map(|u| u.output_tokens),
+            cache_read_tokens: body.usage.as_ref().and_then(|u| u.cache_read_input_tokens),
+            cache_write_tokens: body
+                .usage
+                .as_ref()
+                .and_then(|u| u.cache_creation_input_tokens),
             stop_reason: body.stop_reason,
+        };
+        Ok(if structured {
+            response.unwrap_structured_output_tool()
+        } else {
+            response
         })
     }
 
@@ -257,6 +323,15 @@ impl LlmProvider for AnthropicProvider {
         &self,
         request: LlmRequest,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>, ProviderError> {
+        if matches!(
+            request.response_format,
+            Some(ResponseFormat::JsonSchema { .. })
+        ) {
+            // Structured output rides on a forced tool call; serve it whole.
+            let chunks = self.chat(request).await?.into_stream_chunks();
+            return Ok(Box::pin(tokio_stream::iter(chunks.into_iter().map(Ok))));
+        }
+
         let url = format!("{}/messages", self.api_base);
         let mut payload = Self::to_api_request(request);
         payload.stream = true;
@@ -398,6 +473,8 @@ fn parse_sse_event(event: &serde_json::Value) -> Option<StreamChunk> {
                 is_final: false,
                 input_tokens: None,
                 output_tokens: None,
+                cache_read_tokens: None,
+                cache_write_tokens: None,
                 stop_reason: None,
                 content_blocks: vec![],
             })
@@ -419,6 +496,8 @@ fn parse_sse_event(event: &serde_json::Value) -> Option<StreamChunk> {
                 is_final: true,
                 input_tokens: None,
                 output_tokens,
+                cache_read_tokens: None,
+                cache_write_tokens: None,
                 stop_reason,
                 content_blocks: vec![],
             })
@@ -426,16 +505,21 @@ fn parse_sse_event(event: &serde_json::Value) -> Option<StreamChunk> {
         "message_start" => {
             let message = event.get("message")?;
             let usage = message.get("usage")?;
-            let input_tokens = usage
-                .get("input_tokens")
-                .and_then(|value| value.as_u64())
-                .and_then(|value| u32::try_from(value).ok());
+            let token_count = |key: &str| {
+                usage
+                    .get(key)
+                    .and_then(|value| value.as_u64())
+                    .and_then(|value| u32::try_from(value).ok())
+            };
+            let input_tokens = token_count("input_tokens");
 
             Some(StreamChunk {
                 delta: String::new(),
                 is_final: false,
                 input_tokens,
                 output_tokens: None,
+                cache_read_tokens: token_count("cache_read_input_tokens"),
+                cache_write_tokens: token_count("cache_creation_input_tokens"),
                 stop_reason: None,
                 content_blocks: vec![],
             })
@@ -444,6 +528,10 @@ fn parse_sse_event(event: &serde_json::Value) -> Option<StreamChunk> {
     }
 }
 
+fn ephemeral_cache() -> serde_json::Value {
+    serde_json::json!({"type": "ephemeral"})
+}
+
 fn to_provider_error(status: StatusCode, text: &str) -> ProviderError {
     let parsed = serde_json::from_str::<ApiError>(text).ok();
     let message = if let Some(api_error) = parsed {
@@ -468,11 +556,19 @@ fn to_provider_error(status: StatusCode, text: &str) -> ProviderError {
 pub(crate) struct ApiRequest {
     pub model: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub system: Option<String>,
+    pub system: Option<serde_json::Value>,
     pub max_tokens: u32,
     pub messages: Vec<ApiMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<ApiToolDef>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -490,6 +586,8 @@ pub(crate) struct ApiToolDef {
     pub name: String,
     pub description: String,
     pub input_schema: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -517,6 +615,10 @@ pub(crate) struct ApiContentBlock {
 pub(crate) struct ApiUsage {
     pub input_tokens: u32,
     pub output_tokens: u32,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u32>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -566,6 +668,10 @@ mod tests {
             max_tokens: 1024,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let api_req = AnthropicProvider::to_api_request(req);
 
@@ -662,6 +768,10 @@ mod tests {
             max_tokens: 1024,
             messages: vec![],
             tools: None,
+            tool_choice: None,
+            temperature: None,
+            top_p: None,
+            stop_sequences: vec![],
             stream: false,
             thinking: None,
         };
@@ -674,6 +784,10 @@ mod tests {
             max_tokens: 1024,
             messages: vec![],
             tools: None,
+            tool_choice: None,
+            temperature: None,
+            top_p: None,
+            stop_sequences: vec![],
             stream: true,
             thinking: None,
         };
@@ -754,6 +868,10 @@ mod tests {
             max_tokens: 100,
             messages: vec![],
             tools: None,
+            tool_choice: None,
+            temperature: None,
+            top_p: None,
+            stop_sequences: vec![],
             stream: false,
             thinking: None,
         };
@@ -789,6 +907,10 @@ mod tests {
             max_tokens: 2048,
             tools: vec![],
             thinking_level: Some(crate::ThinkingLevel::Medium),
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let api_req = AnthropicProvider::to_api_request(req);
         let json = serde_json::to_value(&api_req).unwrap();
@@ -807,9 +929,146 @@ mod tests {
             max_tokens: 1024,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let api_req = AnthropicProvider::to_api_request(req);
         let json = serde_json::to_value(&api_req).unwrap();
         assert!(json.get("thinking").is_none());
     }
+
+    #[test]
+    fn to_api_request_maps_sampling_and_tool_choice() {
+        let req = LlmRequest {
+            sampling: crate::SamplingParams {
+                temperature: Some(0.2),
+                top_p: Some(0.9),
+                stop: vec!["</answer>".into()],
+                seed: Some(42),
+            },
+            tool_choice: Some(ToolChoice::Tool {
+                name: "lookup".into(),
+            }),
+            ..LlmRequest::simple("claude-sonnet-4-5".into(), None, "hello".into())
+        };
+        let json = serde_json::to_value(AnthropicProvider::to_api_request(req)).unwrap();
+        let temperature = json["temperature"].as_f64().unwrap();
+        assert!((temperature - 0.2).abs() < 1e-6);
+        assert!(json.get("top_p").is_none());
+        assert_eq!(json["stop_sequences"], serde_json::json!(["</answer>"]));
+        assert_eq!(
+            json["tool_choice"],
+            serde_json::json!({"type": "tool", "name": "lookup"})
+        );
+        // Anthropic has no seed parameter.
+        assert!(json.get("seed").is_none());
+    }
+
+    #[test]
+    fn to_api_request_forced_tool_disables_thinking_and_thinking_drops_sampling() {
+        let forced = LlmRequest {
+            thinking_level: Some(crate::ThinkingLevel::High),
+            tool_choice: Some(ToolChoice::Any),
+            ..LlmRequest::simple("claude-sonnet-4-5".into(), None, "hello".into())
+        };
+        let json = serde_json::to_value(AnthropicProvider::to_api_request(forced)).unwrap();
+        assert!(json.get("thinking").is_none());
+        assert_eq!(json["tool_choice"]["type"], "any");
+
+        let thinking = LlmRequest {
+            thinking_level: Some(crate::ThinkingLevel::High),
+            sampling: crate::SamplingParams {
+                temperature: Some(0.0),
+                ..Default::default()
+            },
+            ..LlmRequest::simple("claude-sonnet-4-5".into(), None, "hello".into())
+        };
+        let json = serde_json::to_value(AnthropicProvider::to_api_request(thinking)).unwrap();
+        assert_eq!(json["thinking"]["type"], "enabled");
+        assert!(json.get("temperature").is_none());
+    }
+
+    #[test]
+    fn to_api_request_emulates_json_schema_with_forced_tool() {
+        let mut req = LlmRequest::simple("claude-sonnet-4-5".into(), None, "hello".into())
+            .with_json_schema("verdict", serde_json::json!({"type": "object"}));
+        assert!(req.emulate_json_schema_with_tool());
+        let json = serde_json::to_value(AnthropicProvider::to_api_request(req)).unwrap();
+        assert_eq!(json["tools"][0]["name"], crate::STRUCTURED_OUTPUT_TOOL);
+        assert_eq!(json["tool_choice"]["name"], crate::STRUCTURED_OUTPUT_TOOL);
+    }
+
+    #[test]
+    fn to_api_request_places_cache_breakpoints() {
+        let mut req = LlmRequest {
+            tools: vec![
+                crate::ToolDef {
+                    name: "first".into(),
+                    description: "First".into(),
+                    input_schema: serde_json::json!({"type": "object"}),
+                },
+                crate::ToolDef {
+                    name: "second".into(),
+                    description: "Second".into(),
+                    input_schema: serde_json::json!({"type": "object"}),
+                },
+            ],
+            ..LlmRequest::simple(
+                "claude-sonnet-4-5".into(),
+                Some("persona".into()),
+                "hello".into(),
+            )
+        };
+        req.messages.push(LlmMessage::assistant("hi"));
+        req.messages.push(LlmMessage::user("again"));
+        req.cache = crate::PromptCache {
+            history_through: Some(1),
+            ..crate::PromptCache::stable_prefix(&req.messages)
+        };
+
+        let json = serde_json::to_value(AnthropicProvider::to_api_request(req)).unwrap();
+        assert_eq!(json["system"][0]["text"], "persona");
+        assert_eq!(json["system"][0]["cache_control"]["type"], "ephemeral");
+        assert!(json["tools"][0].get("cache_control").is_none());
+        assert_eq!(json["tools"][1]["cache_control"]["type"], "ephemeral");
+        assert_eq!(json["messages"][0]["content"], "hello");
+        assert_eq!(json["messages"][1]["content"][0]["text"], "hi");
+        assert_eq!(
+            json["messages"][1]["content"][0]["cache_control"]["type"],
+            "ephemeral"
+        );
+        assert_eq!(json["messages"][2]["content"], "again");
+    }
+
+    #[test]
+    fn cache_usage_is_parsed_from_response_and_stream() {
+        let body: ApiResponse = serde_json::from_value(serde_json::json!({
+            "content": [{"type": "text", "text": "ok"}],
+            "usage": {
+                "input_tokens": 5,
+                "output_tokens": 7,
+                "cache_creation_input_tokens": 100,
+                "cache_read_input_tokens": 2000
+            },
+            "stop_reason": "end_turn"
+        }))
+        .unwrap();
+        let usage = body.usage.unwrap();
+        assert_eq!(usage.cache_creation_input_tokens, Some(100));
+        assert_eq!(usage.cache_read_input_tokens, Some(2000));
+
+        let event = serde_json::json!({
+            "type": "message_start",
+            "message": {"usage": {
+                "input_tokens": 5,
+                "cache_creation_input_tokens": 0,
+                "cache_read_input_tokens": 2000
+            }}
+        });
+        let chunk = parse_sse_event(&event).unwrap();
+        assert_eq!(chunk.cache_read_tokens, Some(2000));
+        assert_eq!(chunk.cache_write_tokens, Some(0));
+    }
 }
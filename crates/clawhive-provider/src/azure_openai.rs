@@ -157,6 +157,8 @@ impl LlmProvider for AzureOpenAiProvider {
             stop_reason: final_stop_reason,
             input_tokens,
             output_tokens,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
         })
     }
 
@@ -259,6 +261,10 @@ mod tests {
                 input_schema: serde_json::json!({"type": "object", "properties": {"location": {"type": "string"}}}),
             }],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
 
         let payload = OpenAiChatGptProvider::to_responses_request(request, true);
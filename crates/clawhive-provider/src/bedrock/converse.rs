@@ -4,7 +4,9 @@ use anyhow::{anyhow, Result};
 use serde::Serialize;
 use serde_json::Value as JsonValue;
 
-use crate::types::{ContentBlock, LlmMessage, LlmRequest, LlmResponse, StreamChunk, ThinkingLevel};
+use crate::types::{
+    ContentBlock, LlmMessage, LlmRequest, LlmResponse, StreamChunk, ThinkingLevel, ToolChoice,
+};
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -42,6 +44,25 @@ pub enum ConverseContent {
         #[serde(rename = "toolResult")]
         tool_result: ConverseToolResult,
     },
+    CachePoint {
+        #[serde(rename = "cachePoint")]
+        cache_point: CachePoint,
+    },
+}
+
+/// Prompt-cache breakpoint: everything before it is cached as a prefix.
+#[derive(Debug, Clone, Serialize)]
+pub struct CachePoint {
+    #[serde(rename = "type")]
+    pub cache_type: String,
+}
+
+impl Default for CachePoint {
+    fn default() -> Self {
+        Self {
+            cache_type: "default".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -73,19 +94,45 @@ pub struct ConverseToolResult {
 }
 
 #[derive(Debug, Clone, Serialize)]
-pub struct ConverseSystemBlock {
-    pub text: String,
+#[serde(untagged)]
+pub enum ConverseSystemBlock {
+    Text {
+        text: String,
+    },
+    CachePoint {
+        #[serde(rename = "cachePoint")]
+        cache_point: CachePoint,
+    },
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InferenceConfig {
     pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ToolConfig {
-    pub tools: Vec<ToolSpecWrapper>,
+    pub tools: Vec<ConverseTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum ConverseTool {
+    Spec(ToolSpecWrapper),
+    CachePoint {
+        #[serde(rename = "cachePoint")]
+        cache_point: CachePoint,
+    },
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -108,36 +155,75 @@ pub struct ToolInputSchema {
 }
 
 pub fn to_converse_request(req: &LlmRequest) -> ConverseRequest {
-    let messages: Vec<ConverseMessage> = req.messages.iter().map(message_to_converse).collect();
-    let system = req
-        .system
-        .as_ref()
-        .map(|s| vec![ConverseSystemBlock { text: s.clone() }]);
+    // Cache points are rejected by models without prompt caching.
+    let cache_prompt = supports_prompt_cache(&req.model);
+    let cache_tools = cache_prompt && req.cache.tools && is_claude_on_bedrock(&req.model);
+    let mut messages: Vec<ConverseMessage> = req.messages.iter().map(message_to_converse).collect();
+    if let Some(idx) = req.cache.history_through.filter(|_| cache_prompt) {
+        if let Some(message) = messages.get_mut(idx) {
+            message.content.push(ConverseContent::CachePoint {
+                cache_point: CachePoint::default(),
+            });
+        }
+    }
+    let system = req.system.as_ref().map(|s| {
+        let mut blocks = vec![ConverseSystemBlock::Text { text: s.clone() }];
+        if cache_prompt && req.cache.system {
+            blocks.push(ConverseSystemBlock::CachePoint {
+                cache_point: CachePoint::default(),
+            });
+        }
+        blocks
+    });
+    // Converse has no "none" choice; leaving it unset keeps the model on auto.
+    let tool_choice = match &req.tool_choice {
+        Some(ToolChoice::Auto) => Some(serde_json::json!({"auto": {}})),
+        Some(ToolChoice::Any) => Some(serde_json::json!({"any": {}})),
+        Some(ToolChoice::Tool { name }) => Some(serde_json::json!({"tool": {"name": name}})),
+        Some(ToolChoice::None) | None => None,
+    };
+    // Claude's extended thinking rejects forced tool use and custom sampling.
+    let thinking_level = req.thinking_level.filter(|_| {
+        !matches!(
+            req.tool_choice,
+            Some(ToolChoice::Any | ToolChoice::Tool { .. })
+        )
+    });
+    let thinking = thinking_level.is_some() && is_claude_on_bedrock(&req.model);
     let tool_config = if req.tools.is_empty() {
         None
     } else {
         Some(ToolConfig {
+            tool_choice,
             tools: req
                 .tools
                 .iter()
-                .map(|t| ToolSpecWrapper {
-                    tool_spec: ToolSpec {
-                        name: t.name.clone(),
-                        description: t.description.clone(),
-                        input_schema: ToolInputSchema {
-                            json: t.input_schema.clone(),
+                .map(|t| {
+                    ConverseTool::Spec(ToolSpecWrapper {
+                        tool_spec: ToolSpec {
+                            name: t.name.clone(),
+                            description: t.description.clone(),
+                            input_schema: ToolInputSchema {
+                                json: t.input_schema.clone(),
+                            },
                         },
-                    },
+                    })
                 })
+                .chain(cache_tools.then(|| ConverseTool::CachePoint {
+                    cache_point: CachePoint::default(),
+                }))
                 .collect(),
         })
     };
-    let additional_model_request_fields = thinking_extra_fields(&req.model, req.thinking_level);
+    let additional_model_request_fields = thinking_extra_fields(&req.model, thinking_level);
     ConverseRequest {
         messages,
         system,
         inference_config: InferenceConfig {
             max_tokens: req.max_tokens,
+            temperature: req.sampling.temperature.filter(|_| !thinking),
+            top_p: req.sampling.top_p.filter(|_| !thinking),
+            stop_sequences: req.sampling.stop.clone(),
         },
         tool_config,
         additional_model_request_fields,
@@ -158,10 +244,20 @@ fn thinking_extra_fields(model_id: &str, level: Option<ThinkingLevel>) -> Option
 }
 
 fn is_claude_on_bedrock(model_id: &str) -> bool {
-    model_id.starts_with("anthropic.claude-")
+    bedrock_model_has_prefix(model_id, "anthropic.claude-")
+}
+
+/// Claude and Nova models accept Converse cache points.
+fn supports_prompt_cache(model_id: &str) -> bool {
+    is_claude_on_bedrock(model_id) || bedrock_model_has_prefix(model_id, "amazon.nova-")
+}
+
+/// Match a base model id, with or without an inference-profile region prefix.
+fn bedrock_model_has_prefix(model_id: &str, prefix: &str) -> bool {
+    model_id.starts_with(prefix)
         || model_id
             .split_once('.')
-            .map(|(_region, rest)| rest.starts_with("anthropic.claude-"))
+            .map(|(_region, rest)| rest.starts_with(prefix))
             .unwrap_or(false)
 }
 
@@ -269,12 +365,22 @@ pub fn from_converse_response(raw: JsonValue) -> Result<LlmResponse> {
         .and_then(|u| u.get("outputTokens"))
         .and_then(|v| v.as_u64())
         .map(|n| n as u32);
+    let cache_read_tokens = raw
+        .pointer("/usage/cacheReadInputTokens")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as u32);
+    let cache_write_tokens = raw
+        .pointer("/usage/cacheWriteInputTokens")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as u32);
 
     Ok(LlmResponse {
         text: text_parts.join(""),
         content: blocks,
         input_tokens,
         output_tokens,
+        cache_read_tokens,
+        cache_write_tokens,
         stop_reason,
     })
 }
@@ -337,6 +443,8 @@ impl ConverseStreamState {
                         is_final: false,
                         input_tokens: None,
                         output_tokens: None,
+                        cache_read_tokens: None,
+                        cache_write_tokens: None,
                         stop_reason: None,
                         content_blocks: vec![],
                     }));
@@ -366,6 +474,8 @@ impl ConverseStreamState {
                         is_final: false,
                         input_tokens: None,
                         output_tokens: None,
+                        cache_read_tokens: None,
+                        cache_write_tokens: None,
                         stop_reason: None,
                         content_blocks: vec![ContentBlock::ToolUse {
                             id: open.id,
@@ -391,11 +501,21 @@ impl ConverseStreamState {
                     .pointer("/usage/outputTokens")
                     .and_then(|v| v.as_u64())
                     .map(|n| n as u32);
+                let cache_read_tokens = payload
+                    .pointer("/usage/cacheReadInputTokens")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as u32);
+                let cache_write_tokens = payload
+                    .pointer("/usage/cacheWriteInputTokens")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as u32);
                 Ok(Some(StreamChunk {
                     delta: String::new(),
                     is_final: true,
                     input_tokens,
                     output_tokens,
+                    cache_read_tokens,
+                    cache_write_tokens,
                     stop_reason: self.pending_stop_reason.take(),
                     content_blocks: vec![],
                 }))
@@ -443,6 +563,10 @@ mod tests {
             max_tokens: 512,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let cv = to_converse_request(&req);
         let json = serde_json::to_value(&cv).unwrap();
@@ -471,6 +595,10 @@ mod tests {
             max_tokens: 1024,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let cv = to_converse_request(&req);
         let json = serde_json::to_value(&cv).unwrap();
@@ -514,6 +642,10 @@ mod tests {
                 input_schema: serde_json::json!({"type":"object","properties":{"q":{"type":"string"}}}),
             }],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let cv = to_converse_request(&req);
         let json = serde_json::to_value(&cv).unwrap();
@@ -565,6 +697,10 @@ mod tests {
             max_tokens: 100,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let cv = to_converse_request(&req);
         let json = serde_json::to_value(&cv).unwrap();
@@ -628,6 +764,124 @@ mod tests {
 
     use crate::types::ThinkingLevel;
 
+    #[test]
+    fn to_converse_request_maps_sampling_and_tool_choice() {
+        let req = LlmRequest {
+            tools: vec![crate::ToolDef {
+                name: "lookup".into(),
+                description: "Look something up".into(),
+                input_schema: serde_json::json!({"type": "object"}),
+            }],
+            sampling: crate::SamplingParams {
+                temperature: Some(0.5),
+                top_p: Some(0.8),
+                stop: vec!["STOP".into()],
+                seed: None,
+            },
+            tool_choice: Some(ToolChoice::Tool {
+                name: "lookup".into(),
+            }),
+            ..LlmRequest::simple("meta.llama3-1-70b-instruct-v1:0".into(), None, "hi".into())
+        };
+        let json = serde_json::to_value(to_converse_request(&req)).unwrap();
+        assert_eq!(json["inferenceConfig"]["temperature"], 0.5);
+        assert!(json["inferenceConfig"]["topP"].is_number());
+        assert_eq!(
+            json["inferenceConfig"]["stopSequences"],
+            serde_json::json!(["STOP"])
+        );
+        assert_eq!(
+            json["toolConfig"]["toolChoice"],
+            serde_json::json!({"tool": {"name": "lookup"}})
+        );
+    }
+
+    #[test]
+    fn forced_tool_choice_skips_claude_thinking() {
+        let req = LlmRequest {
+            tools: vec![crate::ToolDef {
+                name: "lookup".into(),
+                description: "Look something up".into(),
+                input_schema: serde_json::json!({"type": "object"}),
+            }],
+            thinking_level: Some(ThinkingLevel::High),
+            tool_choice: Some(ToolChoice::Any),
+            ..LlmRequest::simple(
+                "anthropic.claude-sonnet-4-20250514-v1:0".into(),
+                None,
+                "hi".into(),
+            )
+        };
+        let json = serde_json::to_value(to_converse_request(&req)).unwrap();
+        assert!(json["additionalModelRequestFields"].is_null());
+        assert_eq!(
+            json["toolConfig"]["toolChoice"],
+            serde_json::json!({"any": {}})
+        );
+    }
+
+    #[test]
+    fn cache_points_follow_prompt_cache_hints_for_claude() {
+        let mut req = LlmRequest {
+            tools: vec![crate::ToolDef {
+                name: "lookup".into(),
+                description: "Look something up".into(),
+                input_schema: serde_json::json!({"type": "object"}),
+            }],
+            ..LlmRequest::simple(
+                "us.anthropic.claude-sonnet-4-20250514-v1:0".into(),
+                Some("persona".into()),
+                "hi".into(),
+            )
+        };
+        req.cache = crate::PromptCache::stable_prefix(&req.messages);
+        let json = serde_json::to_value(to_converse_request(&req)).unwrap();
+
+        assert_eq!(json["system"][0]["text"], "persona");
+        assert_eq!(json["system"][1]["cachePoint"]["type"], "default");
+        assert_eq!(json["toolConfig"]["tools"][0]["toolSpec"]["name"], "lookup");
+        assert_eq!(
+            json["toolConfig"]["tools"][1]["cachePoint"]["type"],
+            "default"
+        );
+        assert_eq!(json["messages"][0]["content"][0]["text"], "hi");
+        assert_eq!(
+            json["messages"][0]["content"][1]["cachePoint"]["type"],
+            "default"
+        );
+    }
+
+    #[test]
+    fn cache_points_skipped_for_models_without_prompt_cache() {
+        let mut req = LlmRequest::simple(
+            "meta.llama3-1-70b-instruct-v1:0".into(),
+            Some("persona".into()),
+            "hi".into(),
+        );
+        req.cache = crate::PromptCache::stable_prefix(&req.messages);
+        let json = serde_json::to_value(to_converse_request(&req)).unwrap();
+
+        assert_eq!(json["system"].as_array().unwrap().len(), 1);
+        assert_eq!(json["messages"][0]["content"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn from_converse_response_reads_cache_usage() {
+        let raw = serde_json::json!({
+            "output": {"message": {"role": "assistant", "content": [{"text": "ok"}]}},
+            "stopReason": "end_turn",
+            "usage": {
+                "inputTokens": 4,
+                "outputTokens": 2,
+                "cacheReadInputTokens": 1500,
+                "cacheWriteInputTokens": 30
+            }
+        });
+        let resp = from_converse_response(raw).unwrap();
+        assert_eq!(resp.cache_read_tokens, Some(1500));
+        assert_eq!(resp.cache_write_tokens, Some(30));
+    }
+
     #[test]
     fn thinking_level_injected_for_claude() {
         let req = LlmRequest {
@@ -637,6 +891,10 @@ mod tests {
             max_tokens: 8192,
             tools: vec![],
             thinking_level: Some(ThinkingLevel::Medium),
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let cv = to_converse_request(&req);
         let json = serde_json::to_value(&cv).unwrap();
@@ -654,6 +912,10 @@ mod tests {
             max_tokens: 8192,
             tools: vec![],
             thinking_level: Some(ThinkingLevel::High),
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let cv = to_converse_request(&req);
         let json = serde_json::to_value(&cv).unwrap();
@@ -672,6 +934,10 @@ mod tests {
             max_tokens: 8192,
             tools: vec![],
             thinking_level: Some(ThinkingLevel::High),
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let cv = to_converse_request(&req);
         let json = serde_json::to_value(&cv).unwrap();
@@ -19,7 +19,7 @@ use crate::bedrock::converse::{from_converse_response, to_converse_request, Conv
 use crate::bedrock::eventstream::{EventStreamDecoder, Frame};
 use crate::bedrock::sigv4::{sign_bedrock_request, AwsCredentials};
 use crate::error::ProviderError;
-use crate::{LlmProvider, LlmRequest, LlmResponse, StreamChunk};
+use crate::{LlmProvider, LlmRequest, LlmResponse, ResponseFormat, StreamChunk};
 
 /// Authentication mode for Bedrock Runtime requests.
 ///
@@ -171,7 +171,8 @@ impl BedrockProvider {
 
 #[async_trait]
 impl LlmProvider for BedrockProvider {
-    async fn chat(&self, request: LlmRequest) -> Result<LlmResponse, ProviderError> {
+    async fn chat(&self, mut request: LlmRequest) -> Result<LlmResponse, ProviderError> {
+        let structured = request.emulate_json_schema_with_tool();
         let url = self.build_url(&request.model, false);
         let converse = to_converse_request(&request);
         let body = serde_json::to_vec(&converse)
@@ -206,7 +207,13 @@ impl LlmProvider for BedrockProvider {
         let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| {
             ProviderError::InvalidResponse(format!("parse converse response: {e}: {text}"))
         })?;
-        from_converse_response(json).map_err(|e| ProviderError::InvalidResponse(e.to_string()))
+        let response = from_converse_response(json)
+            .map_err(|e| ProviderError::InvalidResponse(e.to_string()))?;
+        Ok(if structured {
+            response.unwrap_structured_output_tool()
+        } else {
+            response
+        })
     }
 
     async fn stream(
@@ -214,6 +221,15 @@ impl LlmProvider for BedrockProvider {
         request: LlmRequest,
     ) -> Result<Pin<Box<dyn Stream<Item = anyhow::Result<StreamChunk>> + Send>>, ProviderError>
     {
+        if matches!(
+            request.response_format,
+            Some(ResponseFormat::JsonSchema { .. })
+        ) {
+            // Structured output rides on a forced tool call; serve it whole.
+            let chunks = self.chat(request).await?.into_stream_chunks();
+            return Ok(Box::pin(tokio_stream::iter(chunks.into_iter().map(Ok))));
+        }
+
         let url = self.build_url(&request.model, true);
         let converse = to_converse_request(&request);
         let body = serde_json::to_vec(&converse)
@@ -11,7 +11,9 @@ use std::pin::Pin;
 use tokio_stream::StreamExt;
 
 use crate::error::ProviderError;
-use crate::{ContentBlock, LlmProvider, LlmRequest, LlmResponse, StreamChunk};
+use crate::{
+    ContentBlock, LlmProvider, LlmRequest, LlmResponse, ResponseFormat, StreamChunk, ToolChoice,
+};
 
 const GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta";
 
@@ -107,6 +109,32 @@ impl GeminiProvider {
             }])
         };
 
+        let tool_config = tools
+            .as_ref()
+            .and(request.tool_choice.as_ref())
+            .map(|choice| {
+                let (mode, allowed) = match choice {
+                    ToolChoice::Auto => ("AUTO", vec![]),
+                    ToolChoice::Any => ("ANY", vec![]),
+                    ToolChoice::None => ("NONE", vec![]),
+                    ToolChoice::Tool { name } => ("ANY", vec![name.clone()]),
+                };
+                GeminiToolConfig {
+                    function_calling_config: GeminiFunctionCallingConfig {
+                        mode: mode.to_string(),
+                        allowed_function_names: allowed,
+                    },
+                }
+            });
+
+        let (response_mime_type, response_json_schema) = match &request.response_format {
+            Some(ResponseFormat::JsonObject) => (Some("application/json"), None),
+            Some(ResponseFormat::JsonSchema { schema, .. }) => {
+                (Some("application/json"), Some(schema.clone()))
+            }
+            None => (None, None),
+        };
+
         GeminiRequest {
             contents,
             system_instruction: request.system.as_ref().map(|s| GeminiContent {
@@ -115,10 +143,15 @@ impl GeminiProvider {
             }),
             generation_config: Some(GeminiGenerationConfig {
                 max_output_tokens: Some(request.max_tokens),
-                temperature: None,
-                top_p: None,
+                temperature: request.sampling.temperature,
+                top_p: request.sampling.top_p,
                 top_k: None,
+                stop_sequences: request.sampling.stop.clone(),
+                seed: request.sampling.seed,
+                response_mime_type: response_mime_type.map(str::to_string),
+                response_json_schema,
             }),
+            tool_config,
             tools,
         }
     }
@@ -257,6 +290,8 @@ fn to_llm_response(body: GeminiResponse) -> Result<LlmResponse, ProviderError> {
             .usage_metadata
             .as_ref()
             .map(|u| u.candidates_token_count),
+        cache_read_tokens: None,
+        cache_write_tokens: None,
         stop_reason,
     })
 }
@@ -296,6 +331,8 @@ fn parse_sse_stream(
                                                         is_final: false,
                                                         input_tokens: None,
                                                         output_tokens: None,
+                                                        cache_read_tokens: None,
+                                                        cache_write_tokens: None,
                                                         stop_reason: None,
                                                         content_blocks: vec![],
                                                     });
@@ -324,6 +361,8 @@ fn parse_sse_stream(
                                                 is_final: true,
                                                 input_tokens: response.usage_metadata.as_ref().map(|u| u.prompt_token_count),
                                                 output_tokens: response.usage_metadata.as_ref().map(|u| u.candidates_token_count),
+                                                cache_read_tokens: None,
+                                                cache_write_tokens: None,
                                                 stop_reason,
                                                 content_blocks: std::mem::take(&mut tool_calls),
                                             });
@@ -373,6 +412,8 @@ struct GeminiRequest {
     generation_config: Option<GeminiGenerationConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<GeminiTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_config: Option<GeminiToolConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -431,6 +472,28 @@ struct GeminiGenerationConfig {
     top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_json_schema: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiToolConfig {
+    function_calling_config: GeminiFunctionCallingConfig,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiFunctionCallingConfig {
+    mode: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    allowed_function_names: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -504,6 +567,10 @@ mod tests {
                 }),
             }],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let api_req = provider.build_request(&req);
 
@@ -516,6 +583,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn build_request_maps_sampling_tool_choice_and_json_schema() {
+        let provider = GeminiProvider::new("test-key");
+        let req = LlmRequest {
+            tools: vec![ToolDef {
+                name: "get_weather".into(),
+                description: "Get weather info".into(),
+                input_schema: serde_json::json!({"type": "object"}),
+            }],
+            sampling: crate::SamplingParams {
+                temperature: Some(0.0),
+                top_p: Some(0.5),
+                stop: vec!["END".into()],
+                seed: Some(3),
+            },
+            tool_choice: Some(ToolChoice::Tool {
+                name: "get_weather".into(),
+            }),
+            ..LlmRequest::simple("gemini-pro".into(), None, "Hi".into())
+        }
+        .with_json_schema("verdict", serde_json::json!({"type": "object"}));
+        let json = serde_json::to_value(provider.build_request(&req)).unwrap();
+
+        let config = &json["generationConfig"];
+        assert_eq!(config["temperature"], 0.0);
+        assert_eq!(config["topP"], 0.5);
+        assert_eq!(config["stopSequences"], serde_json::json!(["END"]));
+        assert_eq!(config["seed"], 3);
+        assert_eq!(config["responseMimeType"], "application/json");
+        assert_eq!(config["responseJsonSchema"]["type"], "object");
+        assert_eq!(
+            json["toolConfig"]["functionCallingConfig"],
+            serde_json::json!({"mode": "ANY", "allowedFunctionNames": ["get_weather"]})
+        );
+    }
+
+    #[test]
+    fn build_request_omits_unset_sampling() {
+        let provider = GeminiProvider::new("test-key");
+        let req = LlmRequest::simple("gemini-pro".into(), None, "Hi".into());
+        let json = serde_json::to_value(provider.build_request(&req)).unwrap();
+        assert!(json["generationConfig"].get("temperature").is_none());
+        assert!(json["generationConfig"].get("responseMimeType").is_none());
+        assert!(json.get("toolConfig").is_none());
+    }
+
     #[test]
     fn to_llm_response_text_only() {
         let raw = serde_json::json!({
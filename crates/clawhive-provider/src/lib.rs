@@ -6,6 +6,8 @@ pub mod gemini;
 pub mod openai;
 pub mod openai_chatgpt;
 pub mod openai_compat;
+pub mod speech;
+pub mod transcription;
 pub mod types;
 
 use std::collections::HashMap;
@@ -30,6 +32,10 @@ pub use openai_compat::{
     custom, deepseek, fireworks, groq, minimax, moonshot, ollama, ollama_with_base, openrouter,
     qianfan, qwen, together, volcengine, zhipu,
 };
+pub use speech::{speech_format_mime, OpenAiSpeech, SpeechAudio, SpeechProvider};
+pub use transcription::{
+    AudioClip, OpenAiTranscriber, Transcript, TranscriptionProvider, WhisperServerTranscriber,
+};
 pub use types::StreamChunk;
 pub use types::*;
 
@@ -350,6 +356,8 @@ impl LlmProvider for StubProvider {
             content: vec![ContentBlock::Text { text: full_text }],
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
         })
     }
@@ -377,6 +385,8 @@ impl LlmProvider for StubProvider {
                     is_final: false,
                     input_tokens: None,
                     output_tokens: None,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
                     stop_reason: None,
                     content_blocks: vec![],
                 })
@@ -388,6 +398,8 @@ impl LlmProvider for StubProvider {
             is_final: true,
             input_tokens: Some(10),
             output_tokens: Some(20),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".into()),
             content_blocks: vec![],
         }));
@@ -463,6 +475,10 @@ mod tests {
             max_tokens: 100,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let resp = provider.chat(req).await.unwrap();
         assert!(resp.text.contains("stub:anthropic:m"));
@@ -8,7 +8,10 @@ use std::pin::Pin;
 use tokio_stream::StreamExt;
 
 use crate::error::ProviderError;
-use crate::{ContentBlock, LlmMessage, LlmProvider, LlmRequest, LlmResponse, StreamChunk};
+use crate::{
+    ContentBlock, LlmMessage, LlmProvider, LlmRequest, LlmResponse, ResponseFormat, StreamChunk,
+    ToolChoice,
+};
 
 #[derive(Debug, Clone)]
 pub struct OpenAiProvider {
@@ -123,11 +126,43 @@ impl OpenAiProvider {
                 .map(|level| level.openai_reasoning_effort().to_string())
         };
 
+        // OpenAI rejects `tool_choice` on requests without tools.
+        let tool_choice =
+            request
+                .tool_choice
+                .filter(|_| tools.is_some())
+                .map(|choice| match choice {
+                    ToolChoice::Auto => serde_json::json!("auto"),
+                    ToolChoice::Any => serde_json::json!("required"),
+                    ToolChoice::None => serde_json::json!("none"),
+                    ToolChoice::Tool { name } => {
+                        serde_json::json!({"type": "function", "function": {"name": name}})
+                    }
+                });
+
+        let response_format = request.response_format.map(|format| match format {
+            ResponseFormat::JsonObject => serde_json::json!({"type": "json_object"}),
+            ResponseFormat::JsonSchema {
+                name,
+                schema,
+                strict,
+            } => serde_json::json!({
+                "type": "json_schema",
+                "json_schema": {"name": name, "schema": schema, "strict": strict}
+            }),
+        });
+
         ApiRequest {
             model: request.model,
             messages: to_api_messages(request.system, request.messages),
             max_completion_tokens: Some(request.max_tokens),
             tools,
+            tool_choice,
+            temperature: request.sampling.temperature,
+            top_p: request.sampling.top_p,
+            stop: request.sampling.stop,
+            seed: request.sampling.seed,
+            response_format,
             stream,
             stream_options: if stream {
                 Some(ApiStreamOptions {
@@ -397,6 +432,8 @@ fn to_llm_response(body: ApiResponse) -> Result<LlmResponse, ProviderError> {
         content,
         input_tokens: body.usage.as_ref().map(|u| u.prompt_tokens),
         output_tokens: body.usage.as_ref().map(|u| u.completion_tokens),
+        cache_read_tokens: None,
+        cache_write_tokens: None,
         stop_reason: normalize_finish_reason(choice.finish_reason.clone()),
     })
 }
@@ -484,6 +521,8 @@ fn parse_sse_event(
                 is_final: false,
                 input_tokens: None,
                 output_tokens: None,
+                cache_read_tokens: None,
+                cache_write_tokens: None,
                 stop_reason: None,
                 content_blocks: vec![],
             });
@@ -497,6 +536,8 @@ fn parse_sse_event(
             is_final: true,
             input_tokens: event.usage.as_ref().map(|u| u.prompt_tokens),
             output_tokens: event.usage.as_ref().map(|u| u.completion_tokens),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: normalize_finish_reason(choice.finish_reason.clone()),
             content_blocks,
         });
@@ -564,6 +605,18 @@ pub(crate) struct ApiRequest {
     pub max_completion_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<ApiTool>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -743,6 +796,10 @@ mod tests {
                 }),
             }],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
 
         let api = OpenAiProvider::to_api_request(req, false, false);
@@ -864,6 +921,10 @@ mod tests {
             max_tokens: 100,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let api = OpenAiProvider::to_api_request(req, false, false);
         assert_eq!(api.messages[0].role, "tool");
@@ -1040,6 +1101,10 @@ mod tests {
             max_tokens: 128,
             tools: vec![],
             thinking_level: Some(crate::ThinkingLevel::High),
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let api_req = OpenAiProvider::to_api_request(req, false, false);
         let json = serde_json::to_value(&api_req).unwrap();
@@ -1055,6 +1120,10 @@ mod tests {
             max_tokens: 128,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let api_req = OpenAiProvider::to_api_request(req, false, false);
         let json = serde_json::to_value(&api_req).unwrap();
@@ -1070,6 +1139,10 @@ mod tests {
             max_tokens: 128,
             tools: vec![],
             thinking_level: Some(crate::ThinkingLevel::High),
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let api_req = OpenAiProvider::to_api_request(req, false, true);
         let json = serde_json::to_value(&api_req).unwrap();
@@ -1078,4 +1151,49 @@ mod tests {
             "reasoning_effort should be stripped for custom providers"
         );
     }
+
+    #[test]
+    fn to_api_request_maps_sampling_and_response_format() {
+        let req = LlmRequest {
+            sampling: crate::SamplingParams {
+                temperature: Some(0.0),
+                top_p: Some(0.5),
+                stop: vec!["\n\n".into()],
+                seed: Some(7),
+            },
+            tool_choice: Some(ToolChoice::Any),
+            ..LlmRequest::simple("gpt-4o-mini".into(), None, "test".into())
+        }
+        .with_json_schema("verdict", serde_json::json!({"type": "object"}));
+        let json = serde_json::to_value(OpenAiProvider::to_api_request(req, false, false)).unwrap();
+        assert_eq!(json["temperature"], 0.0);
+        assert_eq!(json["top_p"], 0.5);
+        assert_eq!(json["stop"], serde_json::json!(["\n\n"]));
+        assert_eq!(json["seed"], 7);
+        assert_eq!(json["response_format"]["type"], "json_schema");
+        assert_eq!(json["response_format"]["json_schema"]["name"], "verdict");
+        assert_eq!(json["response_format"]["json_schema"]["strict"], true);
+        // No tools, so no tool_choice.
+        assert!(json.get("tool_choice").is_none());
+    }
+
+    #[test]
+    fn to_api_request_maps_tool_choice_when_tools_present() {
+        let req = LlmRequest {
+            tools: vec![crate::ToolDef {
+                name: "lookup".into(),
+                description: "Look something up".into(),
+                input_schema: serde_json::json!({"type": "object"}),
+            }],
+            tool_choice: Some(ToolChoice::Tool {
+                name: "lookup".into(),
+            }),
+            ..LlmRequest::simple("gpt-4o-mini".into(), None, "test".into())
+        };
+        let json = serde_json::to_value(OpenAiProvider::to_api_request(req, false, false)).unwrap();
+        assert_eq!(
+            json["tool_choice"],
+            serde_json::json!({"type": "function", "function": {"name": "lookup"}})
+        );
+    }
 }
@@ -8,7 +8,10 @@ use std::pin::Pin;
 use tokio_stream::StreamExt;
 
 use crate::error::ProviderError;
-use crate::{ContentBlock, LlmMessage, LlmProvider, LlmRequest, LlmResponse, StreamChunk};
+use crate::{
+    ContentBlock, LlmMessage, LlmProvider, LlmRequest, LlmResponse, ResponseFormat, StreamChunk,
+    ToolChoice,
+};
 
 #[derive(Debug, Clone)]
 pub struct OpenAiChatGptProvider {
@@ -72,21 +75,62 @@ impl OpenAiChatGptProvider {
             })
         });
 
+        let tool_choice = if request.tools.is_empty() {
+            None
+        } else {
+            Some(match request.tool_choice {
+                Some(ToolChoice::Auto) | None => serde_json::json!("auto"),
+                Some(ToolChoice::Any) => serde_json::json!("required"),
+                Some(ToolChoice::None) => serde_json::json!("none"),
+                Some(ToolChoice::Tool { name }) => {
+                    serde_json::json!({"type": "function", "name": name})
+                }
+            })
+        };
+
+        // Responses API nests the output format under `text.format`, flattened
+        // (no `json_schema` wrapper as in Chat Completions).
+        let text = request.response_format.map(|format| {
+            let format = match format {
+                ResponseFormat::JsonObject => serde_json::json!({"type": "json_object"}),
+                ResponseFormat::JsonSchema {
+                    name,
+                    schema,
+                    strict,
+                } => serde_json::json!({
+                    "type": "json_schema",
+                    "name": name,
+                    "schema": schema,
+                    "strict": strict
+                }),
+            };
+            serde_json::json!({ "format": format })
+        });
+
         ResponsesRequest {
             model: to_responses_model(&request.model),
             input: to_responses_input(request.messages),
             instructions: request.system,
             tools,
-            tool_choice: if request.tools.is_empty() {
-                None
-            } else {
-                Some("auto".to_string())
-            },
+            tool_choice,
+            temperature: request.sampling.temperature,
+            top_p: request.sampling.top_p,
+            text,
             store: false,
             stream,
             reasoning,
         }
     }
+
+    /// The ChatGPT Codex backend rejects sampling overrides; only the
+    /// structured-output and tool-choice settings survive.
+    fn to_codex_request(request: LlmRequest) -> ResponsesRequest {
+        ResponsesRequest {
+            temperature: None,
+            top_p: None,
+            ..Self::to_responses_request(request, true)
+        }
+    }
 }
 
 #[async_trait]
@@ -102,7 +146,7 @@ impl LlmProvider for OpenAiChatGptProvider {
 
         // ChatGPT Codex API requires stream=true, so we stream and collect
         let url = format!("{}/responses", self.api_base);
-        let payload = Self::to_responses_request(request, true);
+        let payload = Self::to_codex_request(request);
 
         // Debug: log the actual tools payload
         if let Some(ref tools) = payload.tools {
@@ -225,6 +269,8 @@ impl LlmProvider for OpenAiChatGptProvider {
             stop_reason: final_stop_reason,
             input_tokens,
             output_tokens,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
         })
     }
 
@@ -241,7 +287,7 @@ impl LlmProvider for OpenAiChatGptProvider {
         }
 
         let url = format!("{}/responses", self.api_base);
-        let payload = Self::to_responses_request(request, true);
+        let payload = Self::to_codex_request(request);
 
         let mut req = self
             .client
@@ -333,6 +379,8 @@ pub(crate) fn parse_sse_stream(
                     is_final: false,
                     input_tokens: None,
                     output_tokens: None,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
                     stop_reason: None,
                     content_blocks: vec![ContentBlock::ToolUse {
                         id: call_id,
@@ -362,6 +410,8 @@ fn parse_sse_event(
                 is_final: false,
                 input_tokens: None,
                 output_tokens: None,
+                cache_read_tokens: None,
+                cache_write_tokens: None,
                 stop_reason: None,
                 content_blocks: vec![],
             }))
@@ -422,6 +472,8 @@ fn parse_sse_event(
                             is_final: false,
                             input_tokens: None,
                             output_tokens: None,
+                            cache_read_tokens: None,
+                            cache_write_tokens: None,
                             stop_reason: None,
                             content_blocks: vec![ContentBlock::ToolUse {
                                 id: call_id,
@@ -452,6 +504,8 @@ fn parse_sse_event(
                         is_final: false,
                         input_tokens: None,
                         output_tokens: None,
+                        cache_read_tokens: None,
+                        cache_write_tokens: None,
                         stop_reason: None,
                         content_blocks: vec![ContentBlock::ToolUse {
                             id: call_id,
@@ -479,6 +533,8 @@ fn parse_sse_event(
                 .as_ref()
                 .and_then(|resp| resp.usage.as_ref())
                 .map(|usage| usage.output_tokens),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
             stop_reason: Some("end_turn".to_string()),
             content_blocks: vec![],
         })),
@@ -534,7 +590,13 @@ pub(crate) struct ResponsesRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<ResponsesTool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tool_choice: Option<String>,
+    pub tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<serde_json::Value>,
     pub store: bool,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub stream: bool,
@@ -831,6 +893,10 @@ mod tests {
             max_tokens: 128,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
 
         let payload = OpenAiChatGptProvider::to_responses_request(request, true);
@@ -862,6 +928,10 @@ mod tests {
                 }),
             }],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
 
         let payload = OpenAiChatGptProvider::to_responses_request(request, true);
@@ -870,7 +940,7 @@ mod tests {
         let tools = payload.tools.unwrap();
         assert_eq!(tools.len(), 1);
         assert_eq!(tools[0].name, "get_weather");
-        assert_eq!(payload.tool_choice, Some("auto".to_string()));
+        assert_eq!(payload.tool_choice, Some(serde_json::json!("auto")));
 
         // Debug: print the actual JSON
         let json = serde_json::to_string_pretty(&tools).unwrap();
@@ -1025,6 +1095,10 @@ mod tests {
             max_tokens: 128,
             tools: vec![],
             thinking_level: Some(crate::ThinkingLevel::Medium),
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let payload = OpenAiChatGptProvider::to_responses_request(request, false);
         let json = serde_json::to_value(&payload).unwrap();
@@ -1040,9 +1114,54 @@ mod tests {
             max_tokens: 128,
             tools: vec![],
             thinking_level: None,
+            sampling: Default::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: Default::default(),
         };
         let payload = OpenAiChatGptProvider::to_responses_request(request, false);
         let json = serde_json::to_value(&payload).unwrap();
         assert!(json.get("reasoning").is_none());
     }
+
+    #[test]
+    fn to_responses_request_maps_sampling_tool_choice_and_text_format() {
+        let request = LlmRequest {
+            tools: vec![crate::ToolDef {
+                name: "lookup".into(),
+                description: "Look something up".into(),
+                input_schema: serde_json::json!({"type": "object"}),
+            }],
+            sampling: crate::SamplingParams {
+                temperature: Some(0.0),
+                top_p: Some(0.5),
+                ..Default::default()
+            },
+            tool_choice: Some(ToolChoice::Tool {
+                name: "lookup".into(),
+            }),
+            ..LlmRequest::simple("gpt-4o".into(), None, "hello".into())
+        }
+        .with_json_schema("verdict", serde_json::json!({"type": "object"}));
+
+        let json = serde_json::to_value(OpenAiChatGptProvider::to_responses_request(
+            request.clone(),
+            true,
+        ))
+        .unwrap();
+        assert_eq!(json["temperature"], 0.0);
+        assert_eq!(json["top_p"], 0.5);
+        assert_eq!(
+            json["tool_choice"],
+            serde_json::json!({"type": "function", "name": "lookup"})
+        );
+        assert_eq!(json["text"]["format"]["type"], "json_schema");
+        assert_eq!(json["text"]["format"]["name"], "verdict");
+        assert_eq!(json["text"]["format"]["strict"], true);
+
+        let codex = serde_json::to_value(OpenAiChatGptProvider::to_codex_request(request)).unwrap();
+        assert!(codex.get("temperature").is_none());
+        assert!(codex.get("top_p").is_none());
+        assert_eq!(codex["text"]["format"]["type"], "json_schema");
+    }
 }
@@ -0,0 +1,167 @@
+//! Text-to-speech for voice replies.
+//!
+//! One backend: the OpenAI-compatible `/audio/speech` endpoint. Local TTS
+//! servers (Kokoro-FastAPI, openedai-speech, LocalAI) expose the same route,
+//! so pointing `base_url` at them needs no API key.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Synthesized audio, ready to send as an attachment.
+#[derive(Debug, Clone)]
+pub struct SpeechAudio {
+    pub data: Vec<u8>,
+    pub mime_type: String,
+}
+
+#[async_trait]
+pub trait SpeechProvider: Send + Sync {
+    async fn synthesize(&self, text: &str) -> Result<SpeechAudio>;
+    fn name(&self) -> &str;
+}
+
+/// MIME type for an `/audio/speech` `response_format`. `opus` comes back in
+/// an Ogg container, which is what Telegram and WhatsApp expect for voice
+/// notes.
+pub fn speech_format_mime(format: &str) -> &'static str {
+    match format {
+        "mp3" => "audio/mpeg",
+        "aac" => "audio/aac",
+        "flac" => "audio/flac",
+        "wav" => "audio/wav",
+        "pcm" => "audio/pcm",
+        _ => "audio/ogg",
+    }
+}
+
+/// `POST {base_url}/audio/speech`, bearer key optional.
+pub struct OpenAiSpeech {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+    voice: String,
+    format: String,
+}
+
+impl OpenAiSpeech {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(120))
+                .build()
+                .unwrap_or_default(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: api_key.into(),
+            model: "tts-1".to_string(),
+            voice: "alloy".to_string(),
+            format: "opus".to_string(),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    pub fn with_voice(mut self, voice: impl Into<String>) -> Self {
+        self.voice = voice.into();
+        self
+    }
+
+    pub fn with_format(mut self, format: impl Into<String>) -> Self {
+        self.format = format.into();
+        self
+    }
+}
+
+#[async_trait]
+impl SpeechProvider for OpenAiSpeech {
+    async fn synthesize(&self, text: &str) -> Result<SpeechAudio> {
+        let endpoint = format!("{}/audio/speech", self.base_url.trim_end_matches('/'));
+        let mut request = self.client.post(endpoint).json(&json!({
+            "model": self.model,
+            "input": text,
+            "voice": self.voice,
+            "response_format": self.format,
+        }));
+        if !self.api_key.is_empty() {
+            request = request.bearer_auth(&self.api_key);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("speech synthesis failed ({status}): {body}"));
+        }
+        let data = response.bytes().await?.to_vec();
+        if data.is_empty() {
+            return Err(anyhow!("speech synthesis returned no audio"));
+        }
+        Ok(SpeechAudio {
+            data,
+            mime_type: speech_format_mime(&self.format).to_string(),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "openai"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn openai_speech_posts_json_and_returns_audio() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/speech"))
+            .and(header("authorization", "Bearer sk-test"))
+            .and(body_json(json!({
+                "model": "tts-1-hd",
+                "input": "See you at noon.",
+                "voice": "nova",
+                "response_format": "opus",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"OggS-reply".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let speech = OpenAiSpeech::new("sk-test")
+            .with_base_url(format!("{}/v1", server.uri()))
+            .with_model("tts-1-hd")
+            .with_voice("nova");
+        let audio = speech.synthesize("See you at noon.").await.unwrap();
+        assert_eq!(audio.data, b"OggS-reply");
+        assert_eq!(audio.mime_type, "audio/ogg");
+    }
+
+    #[tokio::test]
+    async fn openai_speech_reports_http_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/audio/speech"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("unknown voice"))
+            .mount(&server)
+            .await;
+
+        let speech = OpenAiSpeech::new("")
+            .with_base_url(server.uri())
+            .with_format("mp3");
+        let err = speech.synthesize("hello").await.unwrap_err();
+        assert!(err.to_string().contains("unknown voice"));
+        assert_eq!(speech_format_mime("mp3"), "audio/mpeg");
+    }
+}
@@ -0,0 +1,258 @@
+//! Speech-to-text for inbound audio.
+//!
+//! Two backends: the OpenAI-compatible `/audio/transcriptions` endpoint
+//! (OpenAI, Groq, and most hosted Whisper services), and a local
+//! whisper.cpp `server` exposing `/inference`.
+
+use anyhow::{anyhow, Context as _, Result};
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+
+/// Long clips on a CPU-only whisper.cpp server can take minutes.
+const TRANSCRIPTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(180);
+
+/// One audio clip to transcribe.
+#[derive(Debug, Clone)]
+pub struct AudioClip {
+    pub data: Vec<u8>,
+    pub file_name: String,
+    pub mime_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    pub text: String,
+    /// Spoken language, when the backend reports it.
+    pub language: Option<String>,
+}
+
+#[async_trait]
+pub trait TranscriptionProvider: Send + Sync {
+    async fn transcribe(&self, clip: &AudioClip) -> Result<Transcript>;
+    fn name(&self) -> &str;
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+fn file_part(clip: &AudioClip) -> Result<Part> {
+    Part::bytes(clip.data.clone())
+        .file_name(clip.file_name.clone())
+        .mime_str(&clip.mime_type)
+        .with_context(|| format!("invalid audio MIME type {:?}", clip.mime_type))
+}
+
+async fn parse_response(response: reqwest::Response, backend: &str) -> Result<Transcript> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!("{backend} transcription failed ({status}): {body}"));
+    }
+    let parsed: TranscriptionResponse = response
+        .json()
+        .await
+        .with_context(|| format!("invalid {backend} transcription response"))?;
+    Ok(Transcript {
+        text: parsed.text.trim().to_string(),
+        language: parsed.language.filter(|l| !l.is_empty()),
+    })
+}
+
+fn transcription_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(TRANSCRIPTION_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// `POST {base_url}/audio/transcriptions` with a bearer key.
+pub struct OpenAiTranscriber {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+    language: Option<String>,
+}
+
+impl OpenAiTranscriber {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: transcription_client(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: api_key.into(),
+            model: "whisper-1".to_string(),
+            language: None,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    /// ISO-639-1 hint; without it the backend detects the language.
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for OpenAiTranscriber {
+    async fn transcribe(&self, clip: &AudioClip) -> Result<Transcript> {
+        let mut form = Form::new()
+            .part("file", file_part(clip)?)
+            .text("model", self.model.clone())
+            .text("response_format", "json");
+        if let Some(language) = &self.language {
+            form = form.text("language", language.clone());
+        }
+        let endpoint = format!(
+            "{}/audio/transcriptions",
+            self.base_url.trim_end_matches('/')
+        );
+        let mut request = self.client.post(endpoint).multipart(form);
+        if !self.api_key.is_empty() {
+            request = request.bearer_auth(&self.api_key);
+        }
+        parse_response(request.send().await?, "openai").await
+    }
+
+    fn name(&self) -> &str {
+        "openai"
+    }
+}
+
+/// whisper.cpp `server`: `POST {base_url}/inference`, no auth.
+pub struct WhisperServerTranscriber {
+    client: reqwest::Client,
+    base_url: String,
+    language: Option<String>,
+}
+
+impl WhisperServerTranscriber {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: transcription_client(),
+            base_url: base_url.into(),
+            language: None,
+        }
+    }
+
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for WhisperServerTranscriber {
+    async fn transcribe(&self, clip: &AudioClip) -> Result<Transcript> {
+        let form = Form::new()
+            .part("file", file_part(clip)?)
+            .text("response_format", "json")
+            .text(
+                "language",
+                self.language.clone().unwrap_or_else(|| "auto".to_string()),
+            );
+        let endpoint = format!("{}/inference", self.base_url.trim_end_matches('/'));
+        let response = self.client.post(endpoint).multipart(form).send().await?;
+        parse_response(response, "whisper-server").await
+    }
+
+    fn name(&self) -> &str {
+        "whisper_server"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{header, header_regex, method, path};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    use super::*;
+
+    fn clip() -> AudioClip {
+        AudioClip {
+            data: b"OggS-voice-bytes".to_vec(),
+            file_name: "voice.ogg".into(),
+            mime_type: "audio/ogg".into(),
+        }
+    }
+
+    fn body_text(request: &Request) -> String {
+        String::from_utf8_lossy(&request.body).to_string()
+    }
+
+    #[tokio::test]
+    async fn openai_transcriber_posts_multipart_form() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(header("authorization", "Bearer sk-test"))
+            .and(header_regex(
+                "content-type",
+                "^multipart/form-data; boundary=",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "text": " Remind me to call Bob. ",
+                "language": "en"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let transcriber = OpenAiTranscriber::new("sk-test")
+            .with_base_url(format!("{}/v1/", server.uri()))
+            .with_model("whisper-large-v3")
+            .with_language(Some("en".into()));
+        let transcript = transcriber.transcribe(&clip()).await.unwrap();
+        assert_eq!(transcript.text, "Remind me to call Bob.");
+        assert_eq!(transcript.language.as_deref(), Some("en"));
+
+        let requests = server.received_requests().await.unwrap();
+        let body = body_text(&requests[0]);
+        assert!(body.contains("filename=\"voice.ogg\""));
+        assert!(body.contains("Content-Type: audio/ogg"));
+        assert!(body.contains("OggS-voice-bytes"));
+        assert!(body.contains("whisper-large-v3"));
+    }
+
+    #[tokio::test]
+    async fn whisper_server_transcriber_reports_http_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/inference"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "text": "hola"
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/inference"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("model not loaded"))
+            .mount(&server)
+            .await;
+
+        let transcriber = WhisperServerTranscriber::new(server.uri());
+        let transcript = transcriber.transcribe(&clip()).await.unwrap();
+        assert_eq!(transcript.text, "hola");
+        assert_eq!(transcript.language, None);
+        let body = body_text(&server.received_requests().await.unwrap()[0]);
+        assert!(body.contains("name=\"language\"\r\n\r\nauto"));
+
+        let err = transcriber.transcribe(&clip()).await.unwrap_err();
+        assert!(err.to_string().contains("model not loaded"));
+    }
+}
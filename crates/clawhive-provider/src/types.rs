@@ -119,6 +119,97 @@ impl ThinkingLevel {
     }
 }
 
+/// Sampling controls shared by requests and per-agent model policy.
+/// Unset fields are left to the provider's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Stop sequences.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl SamplingParams {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How the model may use the request's tools.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// Model decides whether to call a tool (provider default).
+    Auto,
+    /// Model must call at least one tool.
+    Any,
+    /// Model must not call tools.
+    None,
+    /// Model must call the named tool.
+    Tool { name: String },
+}
+
+/// Requested shape of the model's text output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Any valid JSON object.
+    JsonObject,
+    /// JSON matching `schema`. The root must be an object.
+    JsonSchema {
+        name: String,
+        schema: serde_json::Value,
+        /// Ask for strict schema adherence where supported (OpenAI). The
+        /// schema must then list every property in `required` and set
+        /// `additionalProperties: false`.
+        #[serde(default)]
+        strict: bool,
+    },
+}
+
+/// Prompt-cache breakpoints for providers with explicit caching (Anthropic,
+/// Bedrock). Everything up to and including a marked part is cached as a
+/// prefix; other providers ignore this.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptCache {
+    /// Cache the system prompt.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub system: bool,
+    /// Cache the tool definitions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tools: bool,
+    /// Cache the conversation through this message index (inclusive).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_through: Option<usize>,
+}
+
+impl PromptCache {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Cache the stable prefix of a turn: system prompt, tool definitions and
+    /// the history through the latest message, so the next round trip of the
+    /// same conversation reads it back.
+    pub fn stable_prefix(messages: &[LlmMessage]) -> Self {
+        Self {
+            system: true,
+            tools: true,
+            history_through: messages.len().checked_sub(1),
+        }
+    }
+}
+
+/// Name of the synthetic tool used to emulate [`ResponseFormat::JsonSchema`]
+/// on providers that only support it through forced tool use
+/// (Anthropic, Bedrock Converse).
+pub const STRUCTURED_OUTPUT_TOOL: &str = "structured_output";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmRequest {
     pub model: String,
@@ -131,6 +222,14 @@ pub struct LlmRequest {
     /// Optional thinking/reasoning level. None = no extended thinking.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub thinking_level: Option<ThinkingLevel>,
+    #[serde(default, skip_serializing_if = "SamplingParams::is_empty")]
+    pub sampling: SamplingParams,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+    #[serde(default, skip_serializing_if = "PromptCache::is_empty")]
+    pub cache: PromptCache,
 }
 
 fn default_max_tokens() -> u32 {
@@ -146,8 +245,42 @@ impl LlmRequest {
             max_tokens: default_max_tokens(),
             tools: vec![],
             thinking_level: None,
+            sampling: SamplingParams::default(),
+            tool_choice: None,
+            response_format: None,
+            cache: PromptCache::default(),
         }
     }
+
+    /// Request strict JSON output matching `schema`.
+    pub fn with_json_schema(mut self, name: impl Into<String>, schema: serde_json::Value) -> Self {
+        self.response_format = Some(ResponseFormat::JsonSchema {
+            name: name.into(),
+            schema,
+            strict: true,
+        });
+        self
+    }
+
+    /// For providers without native JSON-schema output: swap the response
+    /// format for a forced call to [`STRUCTURED_OUTPUT_TOOL`]. Returns whether
+    /// the swap happened; if so the reply must go through
+    /// [`LlmResponse::unwrap_structured_output_tool`].
+    pub(crate) fn emulate_json_schema_with_tool(&mut self) -> bool {
+        let Some(ResponseFormat::JsonSchema { name, schema, .. }) = self.response_format.take()
+        else {
+            return false;
+        };
+        self.tools.push(ToolDef {
+            name: STRUCTURED_OUTPUT_TOOL.to_string(),
+            description: format!("Return the final answer ({name}) as structured data."),
+            input_schema: schema,
+        });
+        self.tool_choice = Some(ToolChoice::Tool {
+            name: STRUCTURED_OUTPUT_TOOL.to_string(),
+        });
+        true
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,15 +289,71 @@ pub struct LlmResponse {
     pub content: Vec<ContentBlock>,
     pub input_tokens: Option<u32>,
     pub output_tokens: Option<u32>,
+    /// Prompt tokens served from the provider's prompt cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_tokens: Option<u32>,
+    /// Prompt tokens written to the provider's prompt cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_tokens: Option<u32>,
     pub stop_reason: Option<String>,
 }
 
+impl LlmResponse {
+    /// Turn the forced [`STRUCTURED_OUTPUT_TOOL`] call back into a JSON text
+    /// reply, as if the provider supported JSON-schema output natively.
+    pub(crate) fn unwrap_structured_output_tool(mut self) -> Self {
+        let structured = self.content.iter().find_map(|block| match block {
+            ContentBlock::ToolUse { name, input, .. } if name == STRUCTURED_OUTPUT_TOOL => {
+                Some(input.to_string())
+            }
+            _ => None,
+        });
+        if let Some(json) = structured {
+            self.text = json.clone();
+            self.content = vec![ContentBlock::Text { text: json }];
+            self.stop_reason = Some("end_turn".to_string());
+        }
+        self
+    }
+
+    /// Replay a complete response as stream chunks, for adapters that have to
+    /// serve a streaming call with a non-streaming request.
+    pub(crate) fn into_stream_chunks(self) -> Vec<StreamChunk> {
+        vec![
+            StreamChunk {
+                delta: self.text,
+                is_final: false,
+                input_tokens: self.input_tokens,
+                output_tokens: None,
+                cache_read_tokens: self.cache_read_tokens,
+                cache_write_tokens: self.cache_write_tokens,
+                stop_reason: None,
+                content_blocks: vec![],
+            },
+            StreamChunk {
+                delta: String::new(),
+                is_final: true,
+                input_tokens: None,
+                output_tokens: self.output_tokens,
+                cache_read_tokens: None,
+                cache_write_tokens: None,
+                stop_reason: self.stop_reason,
+                content_blocks: vec![],
+            },
+        ]
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamChunk {
     pub delta: String,
     pub is_final: bool,
     pub input_tokens: Option<u32>,
     pub output_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_tokens: Option<u32>,
     pub stop_reason: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub content_blocks: Vec<ContentBlock>,
@@ -281,4 +470,86 @@ mod tests {
         assert_eq!(req.messages.len(), 1);
         assert_eq!(req.messages[0].text(), "hello");
     }
+
+    #[test]
+    fn llm_request_omits_unset_sampling_fields() {
+        let req = LlmRequest::simple("model".into(), None, "hello".into());
+        let json = serde_json::to_value(&req).unwrap();
+        assert!(json.get("sampling").is_none());
+        assert!(json.get("tool_choice").is_none());
+        assert!(json.get("response_format").is_none());
+
+        let roundtrip: LlmRequest = serde_json::from_value(json).unwrap();
+        assert!(roundtrip.sampling.is_empty());
+    }
+
+    #[test]
+    fn tool_choice_and_response_format_serde() {
+        let choice = ToolChoice::Tool {
+            name: "lookup".into(),
+        };
+        let json = serde_json::to_value(&choice).unwrap();
+        assert_eq!(json, serde_json::json!({"type": "tool", "name": "lookup"}));
+
+        let format: ResponseFormat = serde_json::from_value(serde_json::json!({
+            "type": "json_schema",
+            "name": "verdict",
+            "schema": {"type": "object"}
+        }))
+        .unwrap();
+        assert!(matches!(format, ResponseFormat::JsonSchema { name, .. } if name == "verdict"));
+    }
+
+    #[test]
+    fn json_schema_emulation_round_trips_through_tool_call() {
+        let mut req = LlmRequest::simple("model".into(), None, "hi".into()).with_json_schema(
+            "verdict",
+            serde_json::json!({"type": "object", "properties": {"ok": {"type": "boolean"}}}),
+        );
+        assert!(req.emulate_json_schema_with_tool());
+        assert!(req.response_format.is_none());
+        assert_eq!(req.tools.len(), 1);
+        assert_eq!(req.tools[0].name, STRUCTURED_OUTPUT_TOOL);
+        assert_eq!(
+            req.tool_choice,
+            Some(ToolChoice::Tool {
+                name: STRUCTURED_OUTPUT_TOOL.into()
+            })
+        );
+        assert!(!req.emulate_json_schema_with_tool());
+
+        let resp = LlmResponse {
+            text: String::new(),
+            content: vec![ContentBlock::ToolUse {
+                id: "t1".into(),
+                name: STRUCTURED_OUTPUT_TOOL.into(),
+                input: serde_json::json!({"ok": true}),
+            }],
+            input_tokens: None,
+            output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            stop_reason: Some("tool_use".into()),
+        }
+        .unwrap_structured_output_tool();
+        assert_eq!(resp.text, r#"{"ok":true}"#);
+        assert_eq!(resp.stop_reason.as_deref(), Some("end_turn"));
+        assert!(matches!(&resp.content[0], ContentBlock::Text { .. }));
+    }
+
+    #[test]
+    fn prompt_cache_stable_prefix_marks_latest_message() {
+        let empty = PromptCache::stable_prefix(&[]);
+        assert_eq!(empty.history_through, None);
+        assert!(empty.system && empty.tools);
+
+        let messages = vec![LlmMessage::user("a"), LlmMessage::assistant("b")];
+        assert_eq!(
+            PromptCache::stable_prefix(&messages).history_through,
+            Some(1)
+        );
+
+        let json = serde_json::to_value(LlmRequest::simple("m".into(), None, "hi".into())).unwrap();
+        assert!(json.get("cache").is_none());
+    }
 }
@@ -237,6 +237,7 @@ impl ScheduleManager {
                 }
                 maybe_msg = completion_rx.recv() => {
                     if let Some(BusMessage::ScheduledTaskCompleted { schedule_id, status, error, started_at, ended_at, delivery_status, delivery_error, response, session_key }) = maybe_msg {
+                        record_run_metrics(&status, &delivery_status);
                         let completion = CompletionEvent {
                             status,
                             error,
@@ -574,6 +575,20 @@ pub struct ScheduleStateView {
     pub state: ScheduleState,
 }
 
+fn record_run_metrics(status: &ScheduledRunStatus, delivery_status: &ScheduledDeliveryStatus) {
+    let status = match status {
+        ScheduledRunStatus::Ok => "ok",
+        ScheduledRunStatus::Error => "error",
+        ScheduledRunStatus::Skipped => "skipped",
+    };
+    let delivery_status = match delivery_status {
+        ScheduledDeliveryStatus::Delivered => "delivered",
+        ScheduledDeliveryStatus::NotDelivered => "not_delivered",
+        ScheduledDeliveryStatus::NotRequested => "not_requested",
+    };
+    clawhive_metrics::record_scheduler_run(status, delivery_status);
+}
+
 fn merge_json_value(target: &mut serde_json::Value, patch: &serde_json::Value) {
     match (target, patch) {
         (serde_json::Value::Object(target_map), serde_json::Value::Object(patch_map)) => {
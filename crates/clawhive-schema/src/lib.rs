@@ -213,6 +213,9 @@ pub enum BusMessage {
     },
     ReplyReady {
         outbound: OutboundMessage,
+        /// Agent that produced the reply, when known.
+        #[serde(default)]
+        agent_id: Option<String>,
     },
     ActionReady {
         action: ChannelAction,
@@ -220,6 +223,8 @@ pub enum BusMessage {
     TaskFailed {
         trace_id: Uuid,
         error: String,
+        #[serde(default)]
+        agent_id: Option<String>,
     },
     MemoryWriteRequested {
         session_key: String,
@@ -253,17 +258,24 @@ pub enum BusMessage {
         trace_id: Uuid,
         delta: String,
         is_final: bool,
+        #[serde(default)]
+        agent_id: Option<String>,
     },
     ToolCallStarted {
         trace_id: Uuid,
         tool_name: String,
         arguments: String,
+        /// Set when the call was made by a delegated sub-agent run.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        parent_run_id: Option<Uuid>,
     },
     ToolCallCompleted {
         trace_id: Uuid,
         tool_name: String,
         output: String,
         duration_ms: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        parent_run_id: Option<Uuid>,
     },
     ScheduledTaskTriggered {
         schedule_id: String,
@@ -320,6 +332,17 @@ pub enum BusMessage {
         message: String,
         output: Option<String>,
     },
+    /// An async `delegate_task` run finished and asked to notify the
+    /// conversation that started it.
+    DelegateTaskCompleted {
+        run_id: Uuid,
+        agent_id: String,
+        channel_type: String,
+        connector_id: String,
+        conversation_scope: String,
+        status: String,
+        output: String,
+    },
 }
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -562,12 +585,17 @@ mod tests {
         // Test ReplyReady variant
         let msg2 = BusMessage::ReplyReady {
             outbound: outbound.clone(),
+            agent_id: Some("agent-a".into()),
         };
         let json2 = serde_json::to_string(&msg2).unwrap();
         let deserialized2: BusMessage = serde_json::from_str(&json2).unwrap();
         match deserialized2 {
-            BusMessage::ReplyReady { outbound: out } => {
+            BusMessage::ReplyReady {
+                outbound: out,
+                agent_id,
+            } => {
                 assert_eq!(out.text, "reply");
+                assert_eq!(agent_id.as_deref(), Some("agent-a"));
             }
             _ => panic!("Expected ReplyReady variant"),
         }
@@ -576,6 +604,7 @@ mod tests {
         let msg3 = BusMessage::TaskFailed {
             trace_id,
             error: "test error".to_string(),
+            agent_id: None,
         };
         let json3 = serde_json::to_string(&msg3).unwrap();
         let deserialized3: BusMessage = serde_json::from_str(&json3).unwrap();
@@ -754,6 +783,7 @@ mod tests {
             trace_id,
             delta: "hello".into(),
             is_final: false,
+            agent_id: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         let de: BusMessage = serde_json::from_str(&json).unwrap();
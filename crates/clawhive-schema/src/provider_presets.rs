@@ -12,6 +12,43 @@ pub struct ModelPresetInfo {
     pub reasoning: bool,
     /// Whether the model supports image/vision input.
     pub vision: bool,
+    /// List price, when known. Used to cost entries in the usage ledger.
+    pub pricing: Option<ModelPricing>,
+}
+
+/// USD list price per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    /// Price of prompt tokens read from the provider's prompt cache.
+    pub cache_read_per_mtok: Option<f64>,
+    /// Price of prompt tokens written to the provider's prompt cache.
+    pub cache_write_per_mtok: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Cost in USD of one call. Cache tokens fall back to the input price
+    /// when the model has no separate cache rate.
+    pub fn cost_usd(
+        &self,
+        input_tokens: u32,
+        output_tokens: u32,
+        cache_read_tokens: u32,
+        cache_write_tokens: u32,
+    ) -> f64 {
+        let per_token = |tokens: u32, per_mtok: f64| f64::from(tokens) * per_mtok / 1_000_000.0;
+        per_token(input_tokens, self.input_per_mtok)
+            + per_token(output_tokens, self.output_per_mtok)
+            + per_token(
+                cache_read_tokens,
+                self.cache_read_per_mtok.unwrap_or(self.input_per_mtok),
+            )
+            + per_token(
+                cache_write_tokens,
+                self.cache_write_per_mtok.unwrap_or(self.input_per_mtok),
+            )
+    }
 }
 
 /// A known LLM provider preset.
@@ -44,6 +81,28 @@ const fn m(
         max_output_tokens,
         reasoning,
         vision,
+        pricing: None,
+    }
+}
+
+impl ModelPresetInfo {
+    const fn priced(mut self, input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        self.pricing = Some(ModelPricing {
+            input_per_mtok,
+            output_per_mtok,
+            cache_read_per_mtok: None,
+            cache_write_per_mtok: None,
+        });
+        self
+    }
+
+    /// Anthropic-style cache pricing: reads at 10%, writes at 125% of input.
+    const fn anthropic_cache(mut self) -> Self {
+        if let Some(pricing) = &mut self.pricing {
+            pricing.cache_read_per_mtok = Some(pricing.input_per_mtok * 0.1);
+            pricing.cache_write_per_mtok = Some(pricing.input_per_mtok * 1.25);
+        }
+        self
     }
 }
 
@@ -57,11 +116,21 @@ pub const PROVIDER_PRESETS: &[ProviderPreset] = &[
         needs_aws_credentials: false,
         default_model: "claude-opus-4-6",
         models: &[
-            m("claude-opus-4-6", 200_000, 32768, false, true),
-            m("claude-sonnet-4-6", 200_000, 16384, false, true),
-            m("claude-opus-4-5", 200_000, 32768, false, true),
-            m("claude-sonnet-4-5", 200_000, 16384, false, true),
-            m("claude-haiku-4-5", 200_000, 8192, false, true),
+            m("claude-opus-4-6", 200_000, 32768, false, true)
+                .priced(5.0, 25.0)
+                .anthropic_cache(),
+            m("claude-sonnet-4-6", 200_000, 16384, false, true)
+                .priced(3.0, 15.0)
+                .anthropic_cache(),
+            m("claude-opus-4-5", 200_000, 32768, false, true)
+                .priced(5.0, 25.0)
+                .anthropic_cache(),
+            m("claude-sonnet-4-5", 200_000, 16384, false, true)
+                .priced(3.0, 15.0)
+                .anthropic_cache(),
+            m("claude-haiku-4-5", 200_000, 8192, false, true)
+                .priced(1.0, 5.0)
+                .anthropic_cache(),
         ],
     },
     ProviderPreset {
@@ -78,10 +147,10 @@ pub const PROVIDER_PRESETS: &[ProviderPreset] = &[
             m("gpt-5.3", 200_000, 16384, false, true),
             m("gpt-5.2", 200_000, 16384, false, true),
             m("gpt-5.2-pro", 200_000, 32768, false, true),
-            m("gpt-5", 128_000, 16384, false, true),
-            m("gpt-5-pro", 128_000, 32768, false, true),
-            m("gpt-5-mini", 128_000, 16384, false, true),
-            m("o3-pro", 200_000, 100_000, true, true),
+            m("gpt-5", 128_000, 16384, false, true).priced(1.25, 10.0),
+            m("gpt-5-pro", 128_000, 32768, false, true).priced(15.0, 120.0),
+            m("gpt-5-mini", 128_000, 16384, false, true).priced(0.25, 2.0),
+            m("o3-pro", 200_000, 100_000, true, true).priced(20.0, 80.0),
         ],
     },
     ProviderPreset {
@@ -129,9 +198,9 @@ pub const PROVIDER_PRESETS: &[ProviderPreset] = &[
         needs_aws_credentials: false,
         default_model: "gemini-2.5-pro",
         models: &[
-            m("gemini-2.5-pro", 1_000_000, 65536, false, true),
-            m("gemini-2.5-flash", 1_000_000, 65536, false, true),
-            m("gemini-2.0-flash", 1_000_000, 8192, false, true),
+            m("gemini-2.5-pro", 1_000_000, 65536, false, true).priced(1.25, 10.0),
+            m("gemini-2.5-flash", 1_000_000, 65536, false, true).priced(0.3, 2.5),
+            m("gemini-2.0-flash", 1_000_000, 8192, false, true).priced(0.1, 0.4),
         ],
     },
     ProviderPreset {
@@ -360,28 +429,36 @@ pub const PROVIDER_PRESETS: &[ProviderPreset] = &[
                 8192,
                 false,
                 true,
-            ),
+            )
+            .priced(3.0, 15.0)
+            .anthropic_cache(),
             m(
                 "anthropic.claude-3-5-haiku-20241022-v1:0",
                 200_000,
                 8192,
                 false,
                 true,
-            ),
+            )
+            .priced(0.8, 4.0)
+            .anthropic_cache(),
             m(
                 "us.anthropic.claude-sonnet-4-20250514-v1:0",
                 200_000,
                 16384,
                 false,
                 true,
-            ),
+            )
+            .priced(3.0, 15.0)
+            .anthropic_cache(),
             m(
                 "us.anthropic.claude-opus-4-20250514-v1:0",
                 200_000,
                 32768,
                 false,
                 true,
-            ),
+            )
+            .priced(15.0, 75.0)
+            .anthropic_cache(),
             m(
                 "meta.llama3-1-70b-instruct-v1:0",
                 128_000,
@@ -430,6 +507,21 @@ pub fn model_info(provider_id: &str, model_id: &str) -> Option<&'static ModelPre
     preset_by_id(provider_id).and_then(|p| p.models.iter().find(|m| m.id == model_id))
 }
 
+/// Look up list pricing for a model. Providers configured under a custom id
+/// (or reached through an alias) fall back to the first preset that prices
+/// the same model id.
+pub fn model_pricing(provider_id: &str, model_id: &str) -> Option<ModelPricing> {
+    model_info(provider_id, model_id)
+        .and_then(|m| m.pricing)
+        .or_else(|| {
+            PROVIDER_PRESETS
+                .iter()
+                .flat_map(|p| p.models.iter())
+                .find(|m| m.id == model_id && m.pricing.is_some())
+                .and_then(|m| m.pricing)
+        })
+}
+
 /// Get model list for a provider id (with `provider_id/` prefix).
 pub fn provider_models_for_id(provider_id: &str) -> Vec<String> {
     match preset_by_id(provider_id) {
@@ -444,7 +536,7 @@ pub fn provider_models_for_id(provider_id: &str) -> Vec<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{model_info, provider_models_for_id};
+    use super::{model_info, model_pricing, provider_models_for_id};
 
     #[test]
     fn provider_models_for_id_returns_fully_qualified_ids() {
@@ -496,4 +588,22 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn model_pricing_costs_cache_tokens_at_cache_rates() {
+        let pricing = model_pricing("anthropic", "claude-sonnet-4-6").expect("priced");
+        let cost = pricing.cost_usd(1_000_000, 100_000, 1_000_000, 1_000_000);
+        // 3.00 input + 1.50 output + 0.30 cache read + 3.75 cache write
+        assert!((cost - 8.55).abs() < 1e-9, "cost was {cost}");
+    }
+
+    #[test]
+    fn model_pricing_falls_back_to_any_preset_with_same_model() {
+        assert!(model_info("my-anthropic", "claude-haiku-4-5").is_none());
+        assert_eq!(
+            model_pricing("my-anthropic", "claude-haiku-4-5"),
+            model_pricing("anthropic", "claude-haiku-4-5")
+        );
+        assert!(model_pricing("ollama", "llama3.2").is_none());
+    }
 }
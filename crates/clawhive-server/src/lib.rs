@@ -2,6 +2,7 @@ pub mod frontend;
 pub mod routes;
 pub mod state;
 pub mod webhook_auth;
+pub mod webhook_deliveries;
 
 use anyhow::Result;
 use axum::{
@@ -34,6 +35,7 @@ pub fn create_router(state: AppState) -> Router {
     Router::new()
         .nest("/api", routes::api_router())
         .nest("/hook", routes::webhook::webhook_router())
+        .merge(routes::metrics::router())
         .fallback(frontend::frontend_handler)
         .layer(middleware::from_fn_with_state(
             state.clone(),
@@ -291,6 +293,9 @@ mod tests {
                 gateway: None,
                 web_password_hash: Arc::new(std::sync::RwLock::new(web_password_hash)),
                 session_store: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+                webhook_deliveries: Arc::new(
+                    crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+                ),
                 whatsapp_pairing: Arc::new(
                     std::sync::RwLock::new(std::collections::HashMap::new()),
                 ),
@@ -303,6 +308,9 @@ mod tests {
                 port: 3000,
                 schedule_manager: None,
                 reload_coordinator: None,
+                outbound_webhooks: None,
+                memory: None,
+                metrics_bearer_token: None,
             },
             tmp,
         )
@@ -445,6 +453,97 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn event_stream_replays_after_last_event_id() {
+        use tokio_stream::StreamExt;
+
+        let (mut state, tmp) = setup_state(None);
+        let journal = clawhive_bus::BusJournal::open(&tmp.path().join("data/bus.db"), 100).unwrap();
+        state.bus = Arc::new(EventBus::new(16).with_journal(journal));
+        for _ in 0..2 {
+            state
+                .bus
+                .publish(clawhive_schema::BusMessage::MessageAccepted {
+                    trace_id: uuid::Uuid::new_v4(),
+                })
+                .await
+                .unwrap();
+        }
+        let app = create_router(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/events/stream")
+                    .header("last-event-id", "1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut body = response.into_body().into_data_stream();
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(2), body.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let text = String::from_utf8_lossy(&chunk);
+        assert!(text.contains("id: 2"), "{text}");
+        assert!(text.contains("MessageAccepted"));
+        assert!(!text.contains("id: 1\n"));
+    }
+
+    #[tokio::test]
+    async fn event_stream_applies_query_filters() {
+        use tokio_stream::StreamExt;
+
+        let (state, _tmp) = setup_state(None);
+        let bus = state.bus.clone();
+        let app = create_router(state);
+        let wanted = uuid::Uuid::new_v4();
+
+        let bad = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/events/stream?topics=NoSuchTopic")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(bad.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!(
+                        "/api/events/stream?topics=TaskFailed,MessageAccepted&trace_id={wanted}"
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for trace_id in [uuid::Uuid::new_v4(), wanted] {
+            bus.publish(clawhive_schema::BusMessage::MessageAccepted { trace_id })
+                .await
+                .unwrap();
+        }
+        let mut body = response.into_body().into_data_stream();
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(2), body.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let text = String::from_utf8_lossy(&chunk);
+        assert!(text.contains(&wanted.to_string()), "{text}");
+    }
+
     #[tokio::test]
     async fn internal_cli_token_can_call_reload_endpoint() {
         let (state, tmp) = setup_state(Some(hash_password("correct")));
@@ -613,6 +613,9 @@ mod tests {
                 gateway: None,
                 web_password_hash: Arc::new(RwLock::new(web_password_hash)),
                 session_store: Arc::new(RwLock::new(HashMap::new())),
+                webhook_deliveries: Arc::new(
+                    crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+                ),
                 whatsapp_pairing: Arc::new(RwLock::new(HashMap::new())),
                 pending_openai_oauth: Arc::new(RwLock::new(HashMap::new())),
                 openai_oauth_config: default_openai_oauth_config(),
@@ -621,6 +624,9 @@ mod tests {
                 port: 3000,
                 schedule_manager: None,
                 reload_coordinator: None,
+                outbound_webhooks: None,
+                memory: None,
+                metrics_bearer_token: None,
             },
             tmp,
         )
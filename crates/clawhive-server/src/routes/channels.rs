@@ -4,9 +4,11 @@ use axum::{
     routing::{delete, get, post},
     Json, Router,
 };
+use clawhive_core::config::{WebhookSourceConfig, WebhookTemplateConfig};
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::routes::webhook::render_event;
 use crate::state::AppState;
 #[cfg(feature = "whatsapp")]
 use crate::state::WhatsAppPairSession;
@@ -30,6 +32,10 @@ pub fn router() -> Router<AppState> {
             "/webhook/connectors/{source_id}/rotate-key",
             post(rotate_webhook_source_key),
         )
+        .route(
+            "/webhook/connectors/{source_id}/dry-run",
+            post(dry_run_webhook_source),
+        )
         .route("/{kind}/connectors", post(add_connector))
         .route("/{kind}/connectors/{id}", delete(remove_connector))
         .route("/weixin/qr-login", post(weixin_qr_login))
@@ -91,6 +97,17 @@ struct CreateWebhookSourceRequest {
     format: Option<String>,
     #[serde(default)]
     description: Option<String>,
+    /// Required for `format: template`.
+    #[serde(default)]
+    template: Option<WebhookTemplateConfig>,
+}
+
+#[derive(Deserialize)]
+struct DryRunWebhookRequest {
+    payload: serde_json::Value,
+    /// Try a template before saving it; replaces the source's own.
+    #[serde(default)]
+    template: Option<WebhookTemplateConfig>,
 }
 
 async fn get_channels(
@@ -250,6 +267,20 @@ async fn get_channels_status(
                         .unwrap_or_default();
                     !bot_token.is_empty() && !bot_token.starts_with("${")
                 }
+                "matrix" => {
+                    let access_token = connector_map
+                        .get(serde_yaml::Value::String("access_token".to_string()))
+                        .and_then(serde_yaml::Value::as_str)
+                        .unwrap_or_default();
+                    !access_token.is_empty() && !access_token.starts_with("${")
+                }
+                "email" => {
+                    let password = connector_map
+                        .get(serde_yaml::Value::String("password".to_string()))
+                        .and_then(serde_yaml::Value::as_str)
+                        .unwrap_or_default();
+                    !password.is_empty() && !password.starts_with("${")
+                }
                 _ => {
                     let token = connector_map
                         .get(serde_yaml::Value::String("token".to_string()))
@@ -430,11 +461,13 @@ async fn create_webhook_source(
         Some("raw") | Some("generic") | Some("alertmanager") | Some("github") | None => {
             body.format.unwrap_or_else(|| "raw".to_string())
         }
+        Some("template") if body.template.is_some() => "template".to_string(),
         Some(unknown) => {
             tracing::warn!(format = %unknown, "unsupported webhook format");
             return Err(StatusCode::BAD_REQUEST);
         }
     };
+    let is_template = format == "template";
     source.insert(
         serde_yaml::Value::String("format".to_string()),
         serde_yaml::Value::String(format),
@@ -445,6 +478,13 @@ async fn create_webhook_source(
             serde_yaml::Value::String(description),
         );
     }
+    if let Some(template) = body.template.filter(|_| is_template) {
+        source.insert(
+            serde_yaml::Value::String("template".to_string()),
+            serde_yaml::to_value(template)
+                .map_err(|_| axum::http::StatusCode::INTERNAL_SERVER_ERROR)?,
+        );
+    }
     source.insert(
         serde_yaml::Value::String("auth".to_string()),
         serde_yaml::Value::Mapping(auth),
@@ -509,6 +549,67 @@ async fn list_webhook_sources(
     Ok(Json(items))
 }
 
+/// Render a sample payload the way the source would deliver it, without
+/// authenticating or dispatching anything.
+async fn dry_run_webhook_source(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+    Json(body): Json<DryRunWebhookRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), StatusCode> {
+    let main = load_main_config(&state)?;
+    let source = main["channels"]["webhook"]["sources"]
+        .as_sequence()
+        .and_then(|sources| {
+            sources
+                .iter()
+                .find(|source| source["source_id"].as_str() == Some(source_id.as_str()))
+        })
+        .ok_or(axum::http::StatusCode::NOT_FOUND)?;
+    let mut source: WebhookSourceConfig = serde_yaml::from_value(source.clone())
+        .map_err(|_| axum::http::StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(template) = body.template {
+        source.format = "template".to_string();
+        source.template = Some(template);
+    }
+
+    let event = match render_event(&source, &body.payload) {
+        Ok(event) => event,
+        Err(error) => {
+            return Ok((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "source_id": source_id,
+                    "format": source.format,
+                    "error": error.to_string(),
+                })),
+            ))
+        }
+    };
+    let Some(event) = event else {
+        return Ok((
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "source_id": source_id,
+                "format": source.format,
+                "accepted": false,
+            })),
+        ));
+    };
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "source_id": source_id,
+            "format": source.format,
+            "accepted": true,
+            "text": event.text,
+            "title": event.normalized.title,
+            "severity": event.normalized.severity,
+            "labels": event.normalized.labels,
+            "conversation_scope": event.conversation_scope,
+        })),
+    ))
+}
+
 async fn delete_webhook_source(
     State(state): State<AppState>,
     Path(source_id): Path<String>,
@@ -1217,6 +1318,9 @@ mod tests {
             gateway: None,
             web_password_hash: Arc::new(std::sync::RwLock::new(None)),
             session_store: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+            webhook_deliveries: Arc::new(
+                crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+            ),
             whatsapp_pairing: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
             pending_openai_oauth: Arc::new(
                 std::sync::RwLock::new(std::collections::HashMap::new()),
@@ -1227,6 +1331,9 @@ mod tests {
             port: 3000,
             schedule_manager: None,
             reload_coordinator: None,
+            outbound_webhooks: None,
+            memory: None,
+            metrics_bearer_token: None,
         };
         (state, root)
     }
@@ -1630,4 +1737,175 @@ mod tests {
         assert_ne!(first_hash, second_hash);
         assert!(second_hash.starts_with("sha256:"));
     }
+
+    #[tokio::test]
+    async fn test_dry_run_renders_template_source() {
+        let (app, root) = setup_test_app();
+
+        let create_response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/channels/webhook/connectors")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "source_id": "gitlab",
+                            "format": "template",
+                            "template": {
+                                "fields": {"project": "$.project.path_with_namespace"},
+                                "text": "{{user_name}} pushed to {{project}}",
+                                "filters": [{"path": "object_kind", "equals": "push"}],
+                                "conversation_scope": "repo:{{project}}"
+                            }
+                        })
+                        .to_string(),
+                    ))
+                    .expect("build request"),
+            )
+            .await
+            .expect("send request");
+        assert_eq!(create_response.status(), StatusCode::CREATED);
+        let main = read_main_yaml(&root);
+        assert_eq!(
+            main["channels"]["webhook"]["sources"][0]["template"]["conversation_scope"],
+            "repo:{{project}}"
+        );
+
+        let dry_run = |payload: serde_json::Value| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(
+                        Request::builder()
+                            .method("POST")
+                            .uri("/api/channels/webhook/connectors/gitlab/dry-run")
+                            .header("content-type", "application/json")
+                            .body(Body::from(
+                                serde_json::json!({ "payload": payload }).to_string(),
+                            ))
+                            .expect("build request"),
+                    )
+                    .await
+                    .expect("send request");
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .expect("read body bytes");
+                serde_json::from_slice::<serde_json::Value>(&body).expect("parse body")
+            }
+        };
+
+        let rendered = dry_run(serde_json::json!({
+            "object_kind": "push",
+            "user_name": "ada",
+            "project": {"path_with_namespace": "infra/api"}
+        }))
+        .await;
+        assert_eq!(rendered["accepted"], true);
+        assert_eq!(rendered["text"], "ada pushed to infra/api");
+        assert_eq!(rendered["conversation_scope"], "repo:infra/api");
+        assert_eq!(rendered["labels"]["project"], "infra/api");
+
+        let filtered = dry_run(serde_json::json!({"object_kind": "note"})).await;
+        assert_eq!(filtered["accepted"], false);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_renders_source_id_in_template_text() {
+        let (app, root) = setup_test_app();
+        std::fs::write(
+            root.join("config/main.yaml"),
+            r#"channels:
+  webhook:
+    enabled: true
+    sources:
+      - source_id: ci
+        format: template
+        auth:
+          method: api_key
+        template:
+          text: "{{source_id}}: {{status}}"
+          title: "{{source_id}} build"
+"#,
+        )
+        .expect("write webhook config");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/channels/webhook/connectors/ci/dry-run")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"payload":{"status":"failed"}}"#))
+                    .expect("build request"),
+            )
+            .await
+            .expect("send request");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body bytes");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("parse body");
+        assert_eq!(json["text"], "ci: failed");
+        assert_eq!(json["title"], "ci build");
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_reports_template_source_without_template() {
+        let (app, root) = setup_test_app();
+        std::fs::write(
+            root.join("config/main.yaml"),
+            r#"channels:
+  webhook:
+    enabled: true
+    sources:
+      - source_id: ci
+        format: template
+        auth:
+          method: api_key
+"#,
+        )
+        .expect("write webhook config");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/channels/webhook/connectors/ci/dry-run")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"payload":{"k":"v"}}"#))
+                    .expect("build request"),
+            )
+            .await
+            .expect("send request");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body bytes");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("parse body");
+        assert_eq!(
+            json["error"],
+            "webhook source ci uses format template but has no template"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_template_source_requires_template() {
+        let (app, _root) = setup_test_app();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/channels/webhook/connectors")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"source_id":"ci","format":"template"}"#))
+                    .expect("build request"),
+            )
+            .await
+            .expect("send request");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
 }
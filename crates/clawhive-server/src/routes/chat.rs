@@ -231,6 +231,7 @@ async fn handle_ws_connection(socket: WebSocket, state: AppState, token: String)
                                             // published, the relay will drop the duplicate
                                             // (trace_id already removed from active set).
                                             let _ = bus.publish(BusMessage::ReplyReady {
+                                                agent_id: Some(agent_id.clone()),
                                                 outbound: outbound.clone(),
                                             }).await;
                                             // Also send directly to ensure delivery
@@ -348,6 +349,7 @@ async fn relay_bus_events(
                 trace_id,
                 delta,
                 is_final,
+                ..
             } = msg
             {
                 if !is_active_trace_id(&active_trace_ids, trace_id).await {
@@ -372,6 +374,7 @@ async fn relay_bus_events(
                 trace_id,
                 tool_name,
                 arguments,
+                ..
             } = msg
             {
                 if !is_active_trace_id(&active_trace_ids, trace_id).await {
@@ -397,6 +400,7 @@ async fn relay_bus_events(
                 tool_name,
                 output,
                 duration_ms,
+                ..
             } = msg
             {
                 if !is_active_trace_id(&active_trace_ids, trace_id).await {
@@ -418,7 +422,7 @@ async fn relay_bus_events(
         }
 
         while let Ok(msg) = rx_reply.try_recv() {
-            if let BusMessage::ReplyReady { outbound } = msg {
+            if let BusMessage::ReplyReady { outbound, .. } = msg {
                 let trace_id = outbound.trace_id;
                 let is_active = is_active_trace_id(&active_trace_ids, trace_id).await;
                 tracing::debug!(
@@ -447,7 +451,10 @@ async fn relay_bus_events(
         }
 
         while let Ok(msg) = rx_failed.try_recv() {
-            if let BusMessage::TaskFailed { trace_id, error } = msg {
+            if let BusMessage::TaskFailed {
+                trace_id, error, ..
+            } = msg
+            {
                 if !is_active_trace_id(&active_trace_ids, trace_id).await {
                     tracing::trace!(trace_id = %trace_id, "dropping task_failed for inactive trace_id");
                     continue;
@@ -676,6 +683,9 @@ mod tests {
             gateway: None,
             web_password_hash: Arc::new(RwLock::new(None)),
             session_store: Arc::new(RwLock::new(HashMap::new())),
+            webhook_deliveries: Arc::new(
+                crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+            ),
             whatsapp_pairing: Arc::new(RwLock::new(HashMap::new())),
             pending_openai_oauth: Arc::new(RwLock::new(HashMap::new())),
             openai_oauth_config: crate::state::default_openai_oauth_config(),
@@ -684,6 +694,9 @@ mod tests {
             port: 3000,
             schedule_manager: None,
             reload_coordinator: None,
+            outbound_webhooks: None,
+            memory: None,
+            metrics_bearer_token: None,
         };
 
         (state, tmp)
@@ -1,15 +1,19 @@
+use std::collections::BTreeMap;
 use std::convert::Infallible;
-use std::time::Duration;
 
 use axum::{
-    extract::State,
-    response::sse::{Event, KeepAlive, Sse},
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::get,
     Json, Router,
 };
-use clawhive_bus::Topic;
-use futures_core::Stream;
-use serde::Serialize;
+use clawhive_bus::{BusFilter, BusJournal, BusReceiver, Delivery, Topic};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::state::AppState;
 
@@ -20,6 +24,55 @@ pub struct Metrics {
     pub sessions_total: usize,
     pub providers_total: usize,
     pub channels_total: usize,
+    /// Bus messages discarded because a subscriber fell behind, by topic.
+    pub bus_dropped_messages: BTreeMap<&'static str, u64>,
+}
+
+/// Topics forwarded to the dashboard event stream when none are requested.
+const DEFAULT_STREAM_TOPICS: &[Topic] = &[
+    Topic::HandleIncomingMessage,
+    Topic::ReplyReady,
+    Topic::TaskFailed,
+    Topic::MessageAccepted,
+    Topic::StreamDelta,
+    Topic::MemoryWriteRequested,
+    Topic::MemoryReadRequested,
+    Topic::ConsolidationCompleted,
+];
+
+/// Server-side filters for `/stream`. `topics` is a comma-separated list of
+/// `BusMessage` variant names, or `*` for everything.
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamQuery {
+    #[serde(default)]
+    pub topics: Option<String>,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default)]
+    pub session_key: Option<String>,
+    #[serde(default)]
+    pub trace_id: Option<Uuid>,
+}
+
+impl StreamQuery {
+    fn topics(&self) -> Result<Vec<Topic>, StatusCode> {
+        match self.topics.as_deref().map(str::trim) {
+            None | Some("") => Ok(DEFAULT_STREAM_TOPICS.to_vec()),
+            Some("*") => Ok(Topic::ALL.to_vec()),
+            Some(list) => list
+                .split(',')
+                .map(|name| Topic::from_name(name.trim()).ok_or(StatusCode::BAD_REQUEST))
+                .collect(),
+        }
+    }
+
+    fn filter(&self) -> BusFilter {
+        BusFilter {
+            agent_id: self.agent_id.clone(),
+            session_key: self.session_key.clone(),
+            trace_id: self.trace_id,
+        }
+    }
 }
 
 pub fn router() -> Router<AppState> {
@@ -30,65 +83,63 @@ pub fn router() -> Router<AppState> {
 
 async fn event_stream(
     State(state): State<AppState>,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let mut rx = state.bus.subscribe(Topic::HandleIncomingMessage).await;
-    let mut rx_reply = state.bus.subscribe(Topic::ReplyReady).await;
-    let mut rx_failed = state.bus.subscribe(Topic::TaskFailed).await;
-    let mut rx_accepted = state.bus.subscribe(Topic::MessageAccepted).await;
-    let mut rx_stream = state.bus.subscribe(Topic::StreamDelta).await;
-    let mut rx_mem_write = state.bus.subscribe(Topic::MemoryWriteRequested).await;
-    let mut rx_mem_read = state.bus.subscribe(Topic::MemoryReadRequested).await;
-    let mut rx_consolidation = state.bus.subscribe(Topic::ConsolidationCompleted).await;
+    Query(query): Query<StreamQuery>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let topics = query.topics()?;
+    let filter = query.filter();
+
+    // With a durable bus, journaled events carry their sequence as the SSE id
+    // and a reconnecting client resumes after `Last-Event-ID`. Stream deltas
+    // are never journaled, so they always come from a live subscription.
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<i64>().ok());
+    let (journaled, live): (Vec<Topic>, Vec<Topic>) = topics
+        .into_iter()
+        .partition(|topic| state.bus.journal().is_some() && BusJournal::records(topic));
+    let mut tail = match journaled.is_empty() {
+        true => None,
+        false => state.bus.tail(&journaled, last_event_id).await,
+    };
+    let mut rx = state.bus.subscribe_filtered(&live, filter.clone()).await;
 
     let stream = async_stream::stream! {
-        let mut interval = tokio::time::interval(Duration::from_millis(100));
         loop {
-            interval.tick().await;
-
-            while let Ok(msg) = rx.try_recv() {
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    yield Ok(Event::default().data(json));
-                }
-            }
-            while let Ok(msg) = rx_reply.try_recv() {
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    yield Ok(Event::default().data(json));
-                }
-            }
-            while let Ok(msg) = rx_failed.try_recv() {
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    yield Ok(Event::default().data(json));
-                }
-            }
-            while let Ok(msg) = rx_accepted.try_recv() {
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    yield Ok(Event::default().data(json));
-                }
-            }
-            while let Ok(msg) = rx_stream.try_recv() {
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    yield Ok(Event::default().data(json));
-                }
-            }
-            while let Ok(msg) = rx_mem_write.try_recv() {
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    yield Ok(Event::default().data(json));
-                }
-            }
-            while let Ok(msg) = rx_mem_read.try_recv() {
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    yield Ok(Event::default().data(json));
+            tokio::select! {
+                Some(delivery) = next_journaled(&mut tail) => {
+                    if !filter.matches(&delivery.message) {
+                        continue;
+                    }
+                    if let Ok(json) = serde_json::to_string(&delivery.message) {
+                        let mut event = Event::default().data(json);
+                        if let Some(seq) = delivery.seq {
+                            event = event.id(seq.to_string());
+                        }
+                        yield Ok::<_, Infallible>(event);
+                    }
                 }
-            }
-            while let Ok(msg) = rx_consolidation.try_recv() {
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    yield Ok(Event::default().data(json));
+                Some(msg) = rx.recv() => {
+                    if let Ok(json) = serde_json::to_string(&msg) {
+                        yield Ok(Event::default().data(json));
+                    }
                 }
+                else => break,
             }
         }
     };
 
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    Ok(Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response())
+}
+
+async fn next_journaled(tail: &mut Option<BusReceiver>) -> Option<Delivery> {
+    match tail {
+        Some(tail) => tail.recv().await,
+        None => None,
+    }
 }
 
 async fn get_metrics(State(state): State<AppState>) -> Json<Metrics> {
@@ -160,5 +211,6 @@ async fn get_metrics(State(state): State<AppState>) -> Json<Metrics> {
         sessions_total,
         providers_total,
         channels_total,
+        bus_dropped_messages: state.bus.dropped_messages(),
     })
 }
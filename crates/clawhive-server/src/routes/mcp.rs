@@ -0,0 +1,170 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use clawhive_gateway::mcp_server::{handle_raw_message, McpServer};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+/// Connector id for MCP clients reaching clawhive over HTTP.
+const HTTP_CONNECTOR_ID: &str = "http";
+
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Streamable HTTP transport without server-initiated streams: every POST
+/// carries one JSON-RPC message and gets a plain JSON response.
+///
+/// `initialize` assigns an `Mcp-Session-Id` that the client sends on every
+/// later request. It keys the client's agent sessions, memory partitions
+/// and rate limit, so clients sharing the endpoint never see each other's
+/// conversations.
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/",
+        post(handle_mcp).get(|| async { StatusCode::METHOD_NOT_ALLOWED }),
+    )
+}
+
+async fn handle_mcp(State(state): State<AppState>, headers: HeaderMap, body: String) -> Response {
+    let Some(gateway) = state.gateway.clone() else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "Gateway unavailable" })),
+        )
+            .into_response();
+    };
+
+    let (session_id, issued) = match headers.get(SESSION_HEADER) {
+        Some(value) => match value.to_str().ok().filter(|id| is_valid_session_id(id)) {
+            Some(id) => (id.to_string(), false),
+            None => return bad_request("invalid Mcp-Session-Id"),
+        },
+        None if is_initialize(&body) => (Uuid::new_v4().to_string(), true),
+        None => return bad_request("missing Mcp-Session-Id; call initialize first"),
+    };
+
+    let server = McpServer::new(gateway, HTTP_CONNECTOR_ID).with_caller(&session_id);
+    let mut response = match handle_raw_message(&server, &body).await {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    };
+    if issued {
+        if let Ok(value) = session_id.parse() {
+            response.headers_mut().insert(SESSION_HEADER, value);
+        }
+    }
+    response
+}
+
+fn bad_request(error: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error })),
+    )
+        .into_response()
+}
+
+fn is_initialize(body: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(body)
+        .is_ok_and(|message| message["method"] == "initialize")
+}
+
+/// Session ids end up in session keys and memory partition names, so only
+/// short ids without separators are accepted.
+fn is_valid_session_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, RwLock};
+
+    use axum::body::Body;
+    use axum::http::Request;
+    use clawhive_bus::EventBus;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn test_state(dir: &std::path::Path) -> AppState {
+        AppState {
+            root: dir.to_path_buf(),
+            bus: Arc::new(EventBus::new(16)),
+            gateway: None,
+            web_password_hash: Arc::new(RwLock::new(None)),
+            session_store: Arc::new(RwLock::new(HashMap::new())),
+            webhook_deliveries: Arc::new(
+                crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+            ),
+            whatsapp_pairing: Arc::new(RwLock::new(HashMap::new())),
+            pending_openai_oauth: Arc::new(RwLock::new(HashMap::new())),
+            openai_oauth_config: crate::state::default_openai_oauth_config(),
+            enable_openai_oauth_callback_listener: false,
+            daemon_mode: false,
+            port: 8848,
+            schedule_manager: None,
+            reload_coordinator: None,
+            outbound_webhooks: None,
+            memory: None,
+            metrics_bearer_token: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn mcp_returns_503_when_no_gateway() {
+        let tmp = tempfile::tempdir().unwrap();
+        let app = router().with_state(test_state(tmp.path()));
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            ))
+            .unwrap();
+
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn mcp_session_ids_are_checked() {
+        assert!(is_valid_session_id(&Uuid::new_v4().to_string()));
+        assert!(!is_valid_session_id(""));
+        assert!(!is_valid_session_id("a:b"));
+        assert!(!is_valid_session_id(&"a".repeat(129)));
+
+        assert!(is_initialize(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize"}"#
+        ));
+        assert!(!is_initialize(
+            r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#
+        ));
+        assert!(!is_initialize("{not json"));
+    }
+
+    #[tokio::test]
+    async fn mcp_get_is_not_allowed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let app = router().with_state(test_state(tmp.path()));
+
+        let req = Request::builder()
+            .method("GET")
+            .uri("/")
+            .body(Body::empty())
+            .unwrap();
+
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -0,0 +1,988 @@
+//! Operator review of what an agent remembers: facts with their history and
+//! lineage, `MEMORY.md`, daily files and hybrid search. Mounted under
+//! `/api/agents/{id}/memory`.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use clawhive_core::{MemoryDocument, MemorySection};
+use clawhive_memory::dirty_sources::{
+    DirtySourceStore, DIRTY_KIND_DAILY_FILE, DIRTY_KIND_MEMORY_FILE,
+};
+use clawhive_memory::fact_store::{
+    generate_scoped_fact_id, Fact, FactEdit, FactFilter, FactHistory, FactStore,
+};
+use clawhive_memory::file_store::MemoryFileStore;
+use clawhive_memory::memory_lineage::{MemoryCanon, MemoryLineage, MemoryLineageStore};
+use clawhive_memory::search_index::{SearchIndex, SearchResult};
+use clawhive_memory::{MemoryScope, MemoryStore};
+use serde::{Deserialize, Serialize};
+
+use crate::state::AppState;
+
+const FACT_TYPES: [&str; 6] = [
+    "preference",
+    "decision",
+    "event",
+    "person",
+    "rule",
+    "procedure",
+];
+const DEFAULT_FACT_LIMIT: usize = 100;
+const MAX_FACT_LIMIT: usize = 1000;
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+const MAX_SEARCH_LIMIT: usize = 50;
+/// `source_type` of facts written through this API.
+const OPERATOR_SOURCE_TYPE: &str = "operator";
+
+type ApiError = (StatusCode, String);
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FactListQuery {
+    /// A fact status, or `all`. Defaults to `active`.
+    pub status: Option<String>,
+    #[serde(rename = "type")]
+    pub fact_type: Option<String>,
+    pub user: Option<String>,
+    pub conversation: Option<String>,
+    pub pinned: Option<bool>,
+    /// Case-insensitive substring of the content.
+    pub q: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct FactDetail {
+    pub fact: Fact,
+    pub history: Vec<FactHistory>,
+    pub lineage: Vec<CanonicalLineage>,
+}
+
+#[derive(Serialize)]
+pub struct CanonicalLineage {
+    pub canonical: MemoryCanon,
+    pub sources: Vec<MemoryLineage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EditFactBody {
+    pub content: Option<String>,
+    pub fact_type: Option<String>,
+    pub importance: Option<f64>,
+    pub confidence: Option<f64>,
+    pub salience: Option<u8>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SupersedeFactBody {
+    pub content: String,
+    pub fact_type: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReasonQuery {
+    pub reason: Option<String>,
+}
+
+/// Selects a memory partition, by `user` or by `conversation` but not both;
+/// without either field the shared files.
+#[derive(Debug, Default, Deserialize)]
+pub struct PartitionQuery {
+    pub user: Option<String>,
+    pub conversation: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LongTermMemory {
+    pub path: String,
+    pub content: String,
+    pub sections: Vec<LongTermSection>,
+}
+
+#[derive(Serialize)]
+pub struct LongTermSection {
+    pub heading: String,
+    pub items: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContentBody {
+    pub content: String,
+}
+
+#[derive(Serialize)]
+pub struct DailyFile {
+    pub date: String,
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MemorySearchQuery {
+    pub q: String,
+    pub limit: Option<usize>,
+    /// Search as this channel user; facts private to other users are hidden.
+    /// Without it only shared facts are returned.
+    pub user: Option<String>,
+    pub conversation: Option<String>,
+    /// Search every partition and transcript, ignoring `user` and
+    /// `conversation`.
+    #[serde(default)]
+    pub all: bool,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/facts", get(list_facts))
+        .route(
+            "/facts/{fact_id}",
+            get(get_fact).patch(edit_fact).delete(forget_fact),
+        )
+        .route("/facts/{fact_id}/pin", put(pin_fact).delete(unpin_fact))
+        .route("/facts/{fact_id}/supersede", post(supersede_fact))
+        .route("/long-term", get(get_long_term).put(put_long_term))
+        .route("/long-term/sections/{heading}", put(put_long_term_section))
+        .route("/daily", get(list_daily))
+        .route(
+            "/daily/{date}",
+            get(get_daily).put(put_daily).delete(delete_daily),
+        )
+        .route("/search", get(search_memory))
+}
+
+fn internal(error: impl std::fmt::Display) -> ApiError {
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+fn bad_request(message: impl Into<String>) -> ApiError {
+    (StatusCode::BAD_REQUEST, message.into())
+}
+
+/// Reject ids that are not a configured agent; this also keeps the id safe
+/// to use as a workspace directory name.
+fn check_agent(state: &AppState, agent_id: &str) -> Result<(), ApiError> {
+    let well_formed = !agent_id.is_empty()
+        && agent_id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    let config = state
+        .root
+        .join("config/agents.d")
+        .join(format!("{agent_id}.yaml"));
+    if well_formed && config.is_file() {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            format!("agent not found: {agent_id}"),
+        ))
+    }
+}
+
+fn memory_store(state: &AppState) -> Result<Arc<MemoryStore>, ApiError> {
+    state.memory.clone().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "memory store unavailable".to_string(),
+        )
+    })
+}
+
+/// The shared memory files, or one user's or conversation's partition.
+/// Both at once names no partition, so it is rejected rather than falling
+/// back to the shared files.
+fn file_store(
+    state: &AppState,
+    agent_id: &str,
+    partition: &PartitionQuery,
+) -> Result<MemoryFileStore, ApiError> {
+    if partition.user.is_some() && partition.conversation.is_some() {
+        return Err(bad_request("pass at most one of 'user' and 'conversation'"));
+    }
+    Ok(
+        MemoryFileStore::new(state.root.join("workspaces").join(agent_id)).partition(
+            &MemoryScope {
+                user_scope: partition.user.clone(),
+                conversation_scope: partition.conversation.clone(),
+            },
+        ),
+    )
+}
+
+fn check_fact_type(fact_type: &str) -> Result<(), ApiError> {
+    if FACT_TYPES.contains(&fact_type) {
+        Ok(())
+    } else {
+        Err(bad_request(format!(
+            "invalid fact_type '{fact_type}' (expected one of {})",
+            FACT_TYPES.join(", ")
+        )))
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| bad_request(format!("invalid date '{date}' (expected YYYY-MM-DD)")))
+}
+
+/// The agent's fact, or 404. With `active_only`, facts that were already
+/// superseded or forgotten are a 409.
+async fn load_fact(
+    facts: &FactStore,
+    agent_id: &str,
+    fact_id: &str,
+    active_only: bool,
+) -> Result<Fact, ApiError> {
+    let fact = facts
+        .find_by_id(fact_id)
+        .await
+        .map_err(internal)?
+        .filter(|fact| fact.agent_id == agent_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("fact not found: {fact_id}")))?;
+    if active_only && fact.status != "active" {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("fact {fact_id} is {}", fact.status),
+        ));
+    }
+    Ok(fact)
+}
+
+async fn enqueue(
+    memory: &MemoryStore,
+    agent_id: &str,
+    kind: &str,
+    source_ref: &str,
+) -> Result<(), ApiError> {
+    DirtySourceStore::new(memory.db())
+        .enqueue(agent_id, kind, source_ref, "memory_api")
+        .await
+        .map_err(internal)
+}
+
+async fn list_facts(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(query): Query<FactListQuery>,
+) -> Result<Json<Vec<Fact>>, ApiError> {
+    check_agent(&state, &agent_id)?;
+    let status = match query.status.as_deref() {
+        None => Some("active".to_string()),
+        Some("all") => None,
+        Some(status) => Some(status.to_string()),
+    };
+    let filter = FactFilter {
+        status,
+        fact_type: query.fact_type,
+        user_scope: query.user,
+        conversation_scope: query.conversation,
+        pinned: query.pinned,
+        contains: query.q,
+        limit: query
+            .limit
+            .unwrap_or(DEFAULT_FACT_LIMIT)
+            .clamp(1, MAX_FACT_LIMIT),
+    };
+    let memory = memory_store(&state)?;
+    let facts = FactStore::new(memory.db())
+        .list_facts(&agent_id, &filter)
+        .await
+        .map_err(internal)?;
+    Ok(Json(facts))
+}
+
+async fn get_fact(
+    State(state): State<AppState>,
+    Path((agent_id, fact_id)): Path<(String, String)>,
+) -> Result<Json<FactDetail>, ApiError> {
+    check_agent(&state, &agent_id)?;
+    let memory = memory_store(&state)?;
+    let facts = FactStore::new(memory.db());
+    let fact = load_fact(&facts, &agent_id, &fact_id, false).await?;
+    let history = facts.get_history(&fact_id).await.map_err(internal)?;
+
+    let lineage_store = MemoryLineageStore::new(memory.db());
+    let canonical_ids = lineage_store
+        .get_links_for_source(&agent_id, "fact", &fact_id)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(|link| link.canonical_id)
+        .collect::<BTreeSet<_>>();
+    let mut lineage = Vec::new();
+    for canonical_id in canonical_ids {
+        let Some(canonical) = lineage_store
+            .get_canonical(&canonical_id)
+            .await
+            .map_err(internal)?
+        else {
+            continue;
+        };
+        let sources = lineage_store
+            .get_links_for_canonical(&agent_id, &canonical_id)
+            .await
+            .map_err(internal)?;
+        lineage.push(CanonicalLineage { canonical, sources });
+    }
+
+    Ok(Json(FactDetail {
+        fact,
+        history,
+        lineage,
+    }))
+}
+
+/// Metadata is changed in place. New content supersedes the fact with a
+/// new one, since the id follows the content; the response then carries
+/// the new fact with `201 Created`.
+async fn edit_fact(
+    State(state): State<AppState>,
+    Path((agent_id, fact_id)): Path<(String, String)>,
+    Json(body): Json<EditFactBody>,
+) -> Result<(StatusCode, Json<Fact>), ApiError> {
+    check_agent(&state, &agent_id)?;
+    if let Some(fact_type) = body.fact_type.as_deref() {
+        check_fact_type(fact_type)?;
+    }
+    let content = body.content.as_deref().map(str::trim);
+    if content.is_some_and(str::is_empty) {
+        return Err(bad_request("content cannot be empty"));
+    }
+    let memory = memory_store(&state)?;
+    let facts = FactStore::new(memory.db());
+    let old = load_fact(&facts, &agent_id, &fact_id, true).await?;
+    let reason = body.reason.as_deref().unwrap_or("operator_edit");
+
+    let mut edit = FactEdit {
+        fact_type: body.fact_type,
+        importance: body.importance,
+        confidence: body.confidence,
+        salience: body.salience,
+    };
+    let (status, target) = match content.filter(|content| *content != old.content) {
+        Some(content) => {
+            let fact_type = edit.fact_type.take();
+            let new_fact =
+                supersede_with(&facts, &agent_id, old, content, fact_type, reason).await?;
+            (StatusCode::CREATED, new_fact.id)
+        }
+        None => (StatusCode::OK, fact_id),
+    };
+    if edit != FactEdit::default() {
+        facts
+            .edit_fact(&target, &edit, reason)
+            .await
+            .map_err(internal)?;
+    }
+    let fact = load_fact(&facts, &agent_id, &target, false).await?;
+    Ok((status, Json(fact)))
+}
+
+async fn forget_fact(
+    State(state): State<AppState>,
+    Path((agent_id, fact_id)): Path<(String, String)>,
+    Query(query): Query<ReasonQuery>,
+) -> Result<StatusCode, ApiError> {
+    check_agent(&state, &agent_id)?;
+    let memory = memory_store(&state)?;
+    let facts = FactStore::new(memory.db());
+    load_fact(&facts, &agent_id, &fact_id, true).await?;
+    facts
+        .update_status(
+            &fact_id,
+            "deleted",
+            query.reason.as_deref().unwrap_or("operator_forget"),
+        )
+        .await
+        .map_err(internal)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn pin_fact(
+    State(state): State<AppState>,
+    Path((agent_id, fact_id)): Path<(String, String)>,
+) -> Result<Json<Fact>, ApiError> {
+    set_pinned(&state, &agent_id, &fact_id, true).await
+}
+
+async fn unpin_fact(
+    State(state): State<AppState>,
+    Path((agent_id, fact_id)): Path<(String, String)>,
+) -> Result<Json<Fact>, ApiError> {
+    set_pinned(&state, &agent_id, &fact_id, false).await
+}
+
+async fn set_pinned(
+    state: &AppState,
+    agent_id: &str,
+    fact_id: &str,
+    pinned: bool,
+) -> Result<Json<Fact>, ApiError> {
+    check_agent(state, agent_id)?;
+    let memory = memory_store(state)?;
+    let facts = FactStore::new(memory.db());
+    load_fact(&facts, agent_id, fact_id, true).await?;
+    facts
+        .set_pinned(fact_id, pinned, "operator")
+        .await
+        .map_err(internal)?;
+    Ok(Json(load_fact(&facts, agent_id, fact_id, false).await?))
+}
+
+async fn supersede_fact(
+    State(state): State<AppState>,
+    Path((agent_id, fact_id)): Path<(String, String)>,
+    Json(body): Json<SupersedeFactBody>,
+) -> Result<(StatusCode, Json<Fact>), ApiError> {
+    check_agent(&state, &agent_id)?;
+    let content = body.content.trim();
+    if content.is_empty() {
+        return Err(bad_request("content cannot be empty"));
+    }
+    if let Some(fact_type) = body.fact_type.as_deref() {
+        check_fact_type(fact_type)?;
+    }
+    let memory = memory_store(&state)?;
+    let facts = FactStore::new(memory.db());
+    let old = load_fact(&facts, &agent_id, &fact_id, true).await?;
+    let created = supersede_with(
+        &facts,
+        &agent_id,
+        old,
+        content,
+        body.fact_type,
+        body.reason.as_deref().unwrap_or("operator_supersede"),
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Replace `old` with an active operator fact holding `content`.
+async fn supersede_with(
+    facts: &FactStore,
+    agent_id: &str,
+    old: Fact,
+    content: &str,
+    fact_type: Option<String>,
+    reason: &str,
+) -> Result<Fact, ApiError> {
+    let old_id = old.id.clone();
+    let now = Utc::now().to_rfc3339();
+    let new_fact = Fact {
+        id: generate_scoped_fact_id(agent_id, &old.scope(), content),
+        content: content.to_owned(),
+        fact_type: fact_type.unwrap_or_else(|| old.fact_type.clone()),
+        confidence: 1.0,
+        // Recomputed from the type on insert.
+        salience: 0,
+        status: "active".to_owned(),
+        recorded_at: now.clone(),
+        source_type: OPERATOR_SOURCE_TYPE.to_owned(),
+        source_session: None,
+        access_count: 0,
+        last_accessed: None,
+        superseded_by: None,
+        supersede_reason: None,
+        created_at: now.clone(),
+        updated_at: now,
+        ..old
+    };
+    if facts
+        .find_by_id(&new_fact.id)
+        .await
+        .map_err(internal)?
+        .is_some()
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("fact {} already exists", new_fact.id),
+        ));
+    }
+
+    facts
+        .supersede(&old_id, &new_fact, reason)
+        .await
+        .map_err(internal)?;
+    facts.record_add(&new_fact).await.map_err(internal)?;
+    load_fact(facts, agent_id, &new_fact.id, false).await
+}
+
+async fn get_long_term(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(partition): Query<PartitionQuery>,
+) -> Result<Json<LongTermMemory>, ApiError> {
+    check_agent(&state, &agent_id)?;
+    let files = file_store(&state, &agent_id, &partition)?;
+    let content = files.read_long_term().await.map_err(internal)?;
+    Ok(Json(long_term_view(&files, content)))
+}
+
+fn long_term_view(files: &MemoryFileStore, content: String) -> LongTermMemory {
+    let doc = MemoryDocument::parse(&content);
+    let sections = doc
+        .sections()
+        .iter()
+        .map(|MemorySection { heading, .. }| LongTermSection {
+            heading: heading.clone(),
+            items: doc.section_items(heading),
+        })
+        .collect();
+    LongTermMemory {
+        path: files.relative_long_term_path(),
+        content,
+        sections,
+    }
+}
+
+async fn put_long_term(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(partition): Query<PartitionQuery>,
+    Json(body): Json<ContentBody>,
+) -> Result<Json<LongTermMemory>, ApiError> {
+    check_agent(&state, &agent_id)?;
+    write_long_term(&state, &agent_id, &partition, body.content).await
+}
+
+async fn put_long_term_section(
+    State(state): State<AppState>,
+    Path((agent_id, heading)): Path<(String, String)>,
+    Query(partition): Query<PartitionQuery>,
+    Json(body): Json<ContentBody>,
+) -> Result<Json<LongTermMemory>, ApiError> {
+    check_agent(&state, &agent_id)?;
+    let files = file_store(&state, &agent_id, &partition)?;
+    let mut doc = MemoryDocument::parse(&files.read_long_term().await.map_err(internal)?);
+    doc.replace_section(heading.trim(), &body.content);
+    write_long_term(&state, &agent_id, &partition, doc.render()).await
+}
+
+async fn write_long_term(
+    state: &AppState,
+    agent_id: &str,
+    partition: &PartitionQuery,
+    content: String,
+) -> Result<Json<LongTermMemory>, ApiError> {
+    let files = file_store(state, agent_id, partition)?;
+    files.write_long_term(&content).await.map_err(internal)?;
+    let memory = memory_store(state)?;
+    enqueue(
+        &memory,
+        agent_id,
+        DIRTY_KIND_MEMORY_FILE,
+        &files.relative_long_term_path(),
+    )
+    .await?;
+    Ok(Json(long_term_view(&files, content)))
+}
+
+async fn list_daily(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(partition): Query<PartitionQuery>,
+) -> Result<Json<Vec<String>>, ApiError> {
+    check_agent(&state, &agent_id)?;
+    let files = file_store(&state, &agent_id, &partition)?;
+    let mut dates = files
+        .list_daily_files()
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(|(date, _)| date.format("%Y-%m-%d").to_string())
+        .collect::<Vec<_>>();
+    dates.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(Json(dates))
+}
+
+async fn get_daily(
+    State(state): State<AppState>,
+    Path((agent_id, date)): Path<(String, String)>,
+    Query(partition): Query<PartitionQuery>,
+) -> Result<Json<DailyFile>, ApiError> {
+    check_agent(&state, &agent_id)?;
+    let day = parse_date(&date)?;
+    let files = file_store(&state, &agent_id, &partition)?;
+    let content = files
+        .read_daily(day)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("no daily file for {date}")))?;
+    Ok(Json(DailyFile {
+        path: files.relative_daily_path(day),
+        date,
+        content,
+    }))
+}
+
+async fn put_daily(
+    State(state): State<AppState>,
+    Path((agent_id, date)): Path<(String, String)>,
+    Query(partition): Query<PartitionQuery>,
+    Json(body): Json<ContentBody>,
+) -> Result<Json<DailyFile>, ApiError> {
+    check_agent(&state, &agent_id)?;
+    let day = parse_date(&date)?;
+    let files = file_store(&state, &agent_id, &partition)?;
+    files
+        .write_daily(day, &body.content)
+        .await
+        .map_err(internal)?;
+    let path = files.relative_daily_path(day);
+    let memory = memory_store(&state)?;
+    enqueue(&memory, &agent_id, DIRTY_KIND_DAILY_FILE, &path).await?;
+    Ok(Json(DailyFile {
+        date,
+        path,
+        content: body.content,
+    }))
+}
+
+/// Daily files are archived rather than deleted, like consolidation does.
+async fn delete_daily(
+    State(state): State<AppState>,
+    Path((agent_id, date)): Path<(String, String)>,
+    Query(partition): Query<PartitionQuery>,
+) -> Result<StatusCode, ApiError> {
+    check_agent(&state, &agent_id)?;
+    let day = parse_date(&date)?;
+    let files = file_store(&state, &agent_id, &partition)?;
+    if files.read_daily(day).await.map_err(internal)?.is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("no daily file for {date}")));
+    }
+    files.archive_daily(day).await.map_err(internal)?;
+    let memory = memory_store(&state)?;
+    enqueue(
+        &memory,
+        &agent_id,
+        DIRTY_KIND_DAILY_FILE,
+        &files.relative_daily_path(day),
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn search_memory(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(query): Query<MemorySearchQuery>,
+) -> Result<Json<Vec<SearchResult>>, ApiError> {
+    check_agent(&state, &agent_id)?;
+    if query.q.trim().is_empty() {
+        return Err(bad_request("q is required"));
+    }
+    let view = state.config_view();
+    let memory = memory_store(&state)?;
+    let index = match &view {
+        Some(view) => {
+            SearchIndex::new_with_config(memory.db(), &agent_id, view.search_config.clone())
+        }
+        None => SearchIndex::new(memory.db(), &agent_id),
+    };
+    let index = if query.all {
+        index.with_operator_access()
+    } else {
+        index.with_scope(MemoryScope {
+            user_scope: query.user,
+            conversation_scope: query.conversation,
+        })
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let results = match &view {
+        Some(view) => {
+            index
+                .search(&query.q, view.embedding_provider.as_ref(), limit, 0.0, None)
+                .await
+        }
+        None => index.search_bm25(&query.q, limit, 0.0, None).await,
+    }
+    .map_err(internal)?;
+    Ok(Json(results))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, RwLock};
+
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use clawhive_bus::EventBus;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn setup() -> (Router, tempfile::TempDir) {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("config/agents.d")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("data")).unwrap();
+        std::fs::write(
+            tmp.path().join("config/agents.d/main.yaml"),
+            "agent_id: main\nenabled: true\n",
+        )
+        .unwrap();
+        let state = AppState {
+            root: tmp.path().to_path_buf(),
+            bus: Arc::new(EventBus::new(16)),
+            gateway: None,
+            web_password_hash: Arc::new(RwLock::new(None)),
+            session_store: Arc::new(RwLock::new(HashMap::new())),
+            webhook_deliveries: Arc::new(
+                crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+            ),
+            whatsapp_pairing: Arc::new(RwLock::new(HashMap::new())),
+            pending_openai_oauth: Arc::new(RwLock::new(HashMap::new())),
+            openai_oauth_config: crate::state::default_openai_oauth_config(),
+            enable_openai_oauth_callback_listener: false,
+            daemon_mode: false,
+            port: 3000,
+            schedule_manager: None,
+            reload_coordinator: None,
+            outbound_webhooks: None,
+            memory: Some(Arc::new(
+                MemoryStore::open(tmp.path().join("data/clawhive.db").to_str().unwrap()).unwrap(),
+            )),
+            metrics_bearer_token: None,
+        };
+        let app = Router::new()
+            .nest("/api/agents/{id}/memory", router())
+            .with_state(state);
+        (app, tmp)
+    }
+
+    async fn seed_fact(root: &std::path::Path, content: &str) -> Fact {
+        std::fs::create_dir_all(root.join("data")).unwrap();
+        let memory = MemoryStore::open(root.join("data/clawhive.db").to_str().unwrap()).unwrap();
+        let now = Utc::now().to_rfc3339();
+        let fact = Fact {
+            id: generate_scoped_fact_id("main", &MemoryScope::shared(), content),
+            agent_id: "main".to_string(),
+            content: content.to_string(),
+            fact_type: "preference".to_string(),
+            importance: 0.5,
+            confidence: 0.9,
+            salience: 0,
+            status: "active".to_string(),
+            occurred_at: None,
+            recorded_at: now.clone(),
+            source_type: "test".to_string(),
+            source_session: None,
+            access_count: 0,
+            last_accessed: None,
+            superseded_by: None,
+            supersede_reason: None,
+            pinned: false,
+            affect: "neutral".to_string(),
+            affect_intensity: 0.0,
+            created_at: now.clone(),
+            updated_at: now,
+            user_scope: None,
+            conversation_scope: None,
+        };
+        FactStore::new(memory.db())
+            .insert_fact(&fact)
+            .await
+            .unwrap();
+        fact
+    }
+
+    async fn send(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder().method(method).uri(uri);
+        let body = match body {
+            Some(body) => {
+                request = request.header("content-type", "application/json");
+                Body::from(body.to_string())
+            }
+            None => Body::empty(),
+        };
+        let response = app
+            .clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let value = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+        (status, value)
+    }
+
+    #[tokio::test]
+    async fn facts_can_be_listed_pinned_superseded_and_forgotten() {
+        let (app, tmp) = setup();
+        let fact = seed_fact(tmp.path(), "User prefers tabs").await;
+        let base = "/api/agents/main/memory/facts";
+
+        let (status, listed) = send(&app, "GET", &format!("{base}?q=TABS"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+
+        let (status, pinned) = send(&app, "PUT", &format!("{base}/{}/pin", fact.id), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(pinned["pinned"], true);
+
+        let (status, created) = send(
+            &app,
+            "POST",
+            &format!("{base}/{}/supersede", fact.id),
+            Some(serde_json::json!({ "content": "User prefers spaces" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["pinned"], true);
+        assert_eq!(created["source_type"], OPERATOR_SOURCE_TYPE);
+        let new_id = created["id"].as_str().unwrap().to_string();
+
+        let (status, detail) = send(&app, "GET", &format!("{base}/{}", fact.id), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(detail["fact"]["status"], "superseded");
+        let events = detail["history"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["event"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert!(events.contains(&"PIN") && events.contains(&"SUPERSEDE"));
+        assert_eq!(detail["lineage"].as_array().unwrap().len(), 1);
+
+        // Superseded facts can no longer be changed.
+        let (status, _) = send(&app, "DELETE", &format!("{base}/{}", fact.id), None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, _) = send(&app, "DELETE", &format!("{base}/{new_id}"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, active) = send(&app, "GET", base, None).await;
+        assert!(active.as_array().unwrap().is_empty());
+        let (_, all) = send(&app, "GET", &format!("{base}?status=all"), None).await;
+        assert_eq!(all.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn edits_supersede_new_content_and_adjust_metadata_in_place() {
+        let (app, tmp) = setup();
+        let fact = seed_fact(tmp.path(), "User lives in Paris").await;
+        let uri = format!("/api/agents/main/memory/facts/{}", fact.id);
+
+        let (status, _) = send(
+            &app,
+            "PATCH",
+            &uri,
+            Some(serde_json::json!({ "fact_type": "belief" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, edited) = send(
+            &app,
+            "PATCH",
+            &uri,
+            Some(serde_json::json!({ "content": "User lives in Lyon", "fact_type": "person" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_ne!(edited["id"], fact.id.as_str());
+        assert_eq!(edited["content"], "User lives in Lyon");
+        assert_eq!(edited["fact_type"], "person");
+        let (_, old) = send(&app, "GET", &uri, None).await;
+        assert_eq!(old["fact"]["status"], "superseded");
+
+        let new_uri = format!(
+            "/api/agents/main/memory/facts/{}",
+            edited["id"].as_str().unwrap()
+        );
+        let (status, adjusted) = send(
+            &app,
+            "PATCH",
+            &new_uri,
+            Some(serde_json::json!({ "importance": 0.9 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(adjusted["id"], edited["id"]);
+        assert_eq!(adjusted["importance"], 0.9);
+
+        let (status, _) = send(&app, "GET", "/api/agents/main/memory/facts/missing", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(&app, "GET", "/api/agents/other/memory/facts", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn long_term_and_daily_files_round_trip_and_queue_reindexing() {
+        let (app, tmp) = setup();
+        let base = "/api/agents/main/memory";
+
+        let (status, long_term) = send(
+            &app,
+            "PUT",
+            &format!("{base}/long-term/sections/Team"),
+            Some(serde_json::json!({ "content": "- Dana leads infra" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let team = long_term["sections"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|section| section["heading"] == "Team")
+            .unwrap();
+        assert_eq!(team["items"], serde_json::json!(["Dana leads infra"]));
+
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &format!("{base}/daily/2026-03-01"),
+            Some(serde_json::json!({ "content": "- Shipped search" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, dates) = send(&app, "GET", &format!("{base}/daily"), None).await;
+        assert_eq!(dates, serde_json::json!(["2026-03-01"]));
+        let (_, daily) = send(&app, "GET", &format!("{base}/daily/2026-03-01"), None).await;
+        assert_eq!(daily["content"], "- Shipped search");
+
+        let (status, _) = send(&app, "GET", &format!("{base}/daily/March"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &format!("{base}/long-term?user=alice&conversation=ops"),
+            Some(serde_json::json!({ "content": "- overwritten" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(&app, "DELETE", &format!("{base}/daily/2026-03-01"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = send(&app, "GET", &format!("{base}/daily/2026-03-01"), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let memory =
+            MemoryStore::open(tmp.path().join("data/clawhive.db").to_str().unwrap()).unwrap();
+        let pending = DirtySourceStore::new(memory.db())
+            .list_pending("main", 10)
+            .await
+            .unwrap();
+        assert!(pending
+            .iter()
+            .any(|item| item.source_kind == DIRTY_KIND_MEMORY_FILE));
+        assert!(pending
+            .iter()
+            .any(|item| item.source_kind == DIRTY_KIND_DAILY_FILE));
+    }
+}
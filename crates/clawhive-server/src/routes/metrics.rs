@@ -0,0 +1,209 @@
+//! `GET /metrics` in the Prometheus text format.
+//!
+//! Served outside `/api` so scrapers do not need a dashboard session. When
+//! `metrics.bearer_token` is set in `main.yaml`, requests must send it as
+//! `Authorization: Bearer <token>`. The token is read at startup.
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use clawhive_metrics::{
+    BUS_DROPPED_MESSAGES, BUS_QUEUE_DEPTH, MEMORY_INDEX_ENTRIES,
+    PROVIDER_COOLDOWN_REMAINING_SECONDS, PROVIDER_IN_COOLDOWN,
+};
+use subtle::ConstantTimeEq;
+
+use crate::state::AppState;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/metrics", get(prometheus_metrics))
+}
+
+async fn prometheus_metrics(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(expected) = &state.metrics_bearer_token {
+        let provided = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        if !bool::from(provided.as_bytes().ct_eq(expected.as_bytes())) {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+    }
+
+    refresh_scrape_time_metrics(&state).await;
+    (
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        clawhive_metrics::global().render(),
+    )
+        .into_response()
+}
+
+/// Copy state owned by other components into the registry.
+async fn refresh_scrape_time_metrics(state: &AppState) {
+    let registry = clawhive_metrics::global();
+
+    registry.clear(&BUS_QUEUE_DEPTH);
+    for (topic, depth) in state.bus.queue_depths().await {
+        registry.set(&BUS_QUEUE_DEPTH, &[("topic", topic)], depth as f64);
+    }
+    for (topic, dropped) in state.bus.dropped_messages() {
+        registry.set(&BUS_DROPPED_MESSAGES, &[("topic", topic)], dropped as f64);
+    }
+
+    if let Some(view) = state.config_view() {
+        registry.clear(&PROVIDER_IN_COOLDOWN);
+        registry.clear(&PROVIDER_COOLDOWN_REMAINING_SECONDS);
+        let cooldowns = view.router.cooldowns();
+        for provider in view.router.provider_ids() {
+            let remaining = cooldowns
+                .iter()
+                .find(|(id, _)| *id == provider)
+                .and_then(|(_, stats)| stats.remaining_cooldown());
+            let labels = [("provider", provider.as_str())];
+            registry.set(
+                &PROVIDER_IN_COOLDOWN,
+                &labels,
+                if remaining.is_some() { 1.0 } else { 0.0 },
+            );
+            registry.set(
+                &PROVIDER_COOLDOWN_REMAINING_SECONDS,
+                &labels,
+                remaining.map_or(0.0, |r| r.as_secs_f64()),
+            );
+        }
+    }
+
+    if let Some(memory) = &state.memory {
+        match memory.index_sizes().await {
+            Ok(sizes) => {
+                registry.clear(&MEMORY_INDEX_ENTRIES);
+                for size in sizes {
+                    registry.set(
+                        &MEMORY_INDEX_ENTRIES,
+                        &[
+                            ("agent", size.agent_id.as_deref().unwrap_or("")),
+                            ("index", &size.index),
+                        ],
+                        size.entries as f64,
+                    );
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "failed to read memory index sizes"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+    };
+    use clawhive_bus::{EventBus, Topic};
+    use clawhive_memory::MemoryStore;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn test_state(root: &std::path::Path) -> AppState {
+        AppState {
+            root: root.to_path_buf(),
+            bus: Arc::new(EventBus::new(16)),
+            gateway: None,
+            web_password_hash: Arc::new(std::sync::RwLock::new(None)),
+            session_store: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+            webhook_deliveries: Arc::new(
+                crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+            ),
+            whatsapp_pairing: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+            pending_openai_oauth: Arc::new(
+                std::sync::RwLock::new(std::collections::HashMap::new()),
+            ),
+            openai_oauth_config: crate::state::default_openai_oauth_config(),
+            enable_openai_oauth_callback_listener: false,
+            daemon_mode: false,
+            port: 3000,
+            schedule_manager: None,
+            reload_coordinator: None,
+            outbound_webhooks: None,
+            memory: None,
+            metrics_bearer_token: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn metrics_expose_bus_depth_and_memory_sizes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut state = test_state(tmp.path());
+        state.memory = Some(Arc::new(MemoryStore::open_in_memory().unwrap()));
+        let _rx = state.bus.subscribe(Topic::MessageAccepted).await;
+        state
+            .bus
+            .publish(clawhive_schema::BusMessage::MessageAccepted {
+                trace_id: uuid::Uuid::new_v4(),
+            })
+            .await
+            .unwrap();
+        clawhive_metrics::record_rate_limit_rejection();
+
+        let response = crate::create_router(state)
+            .oneshot(
+                Request::builder()
+                    .uri("/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CONTENT_TYPE);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("clawhive_bus_queue_depth{topic=\"MessageAccepted\"} 1\n"));
+        assert!(text.contains("# TYPE clawhive_rate_limit_rejections_total counter\n"));
+        assert!(text
+            .contains("clawhive_memory_index_entries{agent=\"\",index=\"embedding_cache\"} 0\n"));
+    }
+
+    #[tokio::test]
+    async fn metrics_require_configured_bearer_token() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut state = test_state(tmp.path());
+        state.metrics_bearer_token = Some("scrape-secret".into());
+        let app = crate::create_router(state);
+
+        let denied = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/metrics")
+                    .header("authorization", "Bearer wrong")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(denied.status(), StatusCode::UNAUTHORIZED);
+
+        let allowed = app
+            .oneshot(
+                Request::builder()
+                    .uri("/metrics")
+                    .header("authorization", "Bearer scrape-secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(allowed.status(), StatusCode::OK);
+    }
+}
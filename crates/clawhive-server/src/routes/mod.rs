@@ -5,12 +5,17 @@ pub mod auth;
 pub mod channels;
 pub mod chat;
 pub mod events;
+pub mod mcp;
+pub mod memory;
+pub mod metrics;
+pub mod outbound_webhooks;
 pub mod providers;
 pub mod routing;
 pub mod schedules;
 pub mod sessions;
 pub mod setup;
 pub mod skills;
+pub mod usage;
 pub mod webhook;
 
 use axum::Router;
@@ -21,10 +26,12 @@ pub fn api_router() -> Router<AppState> {
     Router::new()
         .nest("/admin", admin::router())
         .nest("/agents", agents::router())
+        .nest("/agents/{id}/memory", memory::router())
         .nest("/auth", auth::router())
         .nest("/chat", chat::router())
         .nest("/chat/attachments", attachments::router())
         .nest("/channels", channels::router())
+        .nest("/mcp", mcp::router())
         .nest("/providers", providers::router())
         .nest("/routing", routing::router())
         .nest("/schedules", schedules::router())
@@ -32,4 +39,6 @@ pub fn api_router() -> Router<AppState> {
         .nest("/events", events::router())
         .nest("/setup", setup::router())
         .nest("/skills", skills::router())
+        .nest("/usage", usage::router())
+        .nest("/webhooks/outbound", outbound_webhooks::router())
 }
@@ -0,0 +1,434 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use clawhive_gateway::outbound_webhook::{
+    is_forwardable_topic, DeadLetter, OutboundWebhook, OutboundWebhooks,
+};
+use clawhive_gateway::webhook::validate_webhook_url;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+/// A subscription as listed: the signing secret is only returned on create.
+#[derive(Serialize)]
+pub struct OutboundWebhookItem {
+    pub id: String,
+    pub url: String,
+    pub topics: Vec<String>,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub secret_masked: String,
+}
+
+impl From<OutboundWebhook> for OutboundWebhookItem {
+    fn from(webhook: OutboundWebhook) -> Self {
+        // Only the fixed prefix of generated secrets; none of the key itself.
+        let prefix = if webhook.secret.starts_with("whsec_") {
+            "whsec_"
+        } else {
+            ""
+        };
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            topics: webhook.topics,
+            description: webhook.description,
+            enabled: webhook.enabled,
+            created_at: webhook.created_at.to_rfc3339(),
+            secret_masked: format!("{prefix}..."),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateOutboundWebhookBody {
+    pub url: String,
+    pub topics: Vec<String>,
+    /// Generated when omitted.
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateOutboundWebhookBody {
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub topics: Option<Vec<String>>,
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterParams {
+    pub webhook_id: Option<String>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_webhooks).post(create_webhook))
+        .route(
+            "/{id}",
+            get(get_webhook).put(update_webhook).delete(delete_webhook),
+        )
+        .route("/dead-letters", get(list_dead_letters))
+        .route("/dead-letters/{id}", delete(delete_dead_letter))
+        .route("/dead-letters/{id}/retry", post(retry_dead_letter))
+}
+
+fn get_webhooks(state: &AppState) -> Result<&Arc<OutboundWebhooks>, StatusCode> {
+    state
+        .outbound_webhooks
+        .as_ref()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)
+}
+
+fn validate(url: &str, topics: &[String]) -> Result<(), StatusCode> {
+    if let Err(error) = validate_webhook_url(url) {
+        tracing::warn!(url, error = %error, "rejected outbound webhook url");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if topics.is_empty() || !topics.iter().all(|topic| is_forwardable_topic(topic)) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(())
+}
+
+async fn list_webhooks(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<OutboundWebhookItem>>, StatusCode> {
+    let webhooks = get_webhooks(&state)?;
+    Ok(Json(
+        webhooks
+            .list()
+            .await
+            .into_iter()
+            .map(OutboundWebhookItem::from)
+            .collect(),
+    ))
+}
+
+async fn get_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<OutboundWebhookItem>, StatusCode> {
+    let webhooks = get_webhooks(&state)?;
+    let webhook = webhooks.get(&id).await.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(webhook.into()))
+}
+
+async fn create_webhook(
+    State(state): State<AppState>,
+    Json(body): Json<CreateOutboundWebhookBody>,
+) -> Result<(StatusCode, Json<OutboundWebhook>), StatusCode> {
+    let webhooks = get_webhooks(&state)?;
+    validate(&body.url, &body.topics)?;
+
+    let webhook = OutboundWebhook {
+        id: Uuid::new_v4().to_string(),
+        url: body.url,
+        topics: body.topics,
+        secret: body
+            .secret
+            .filter(|secret| !secret.is_empty())
+            .unwrap_or_else(|| format!("whsec_{}", Uuid::new_v4().simple())),
+        description: body.description,
+        enabled: body.enabled,
+        created_at: Utc::now(),
+    };
+    webhooks
+        .save(webhook.clone())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((StatusCode::CREATED, Json(webhook)))
+}
+
+async fn update_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(body): Json<UpdateOutboundWebhookBody>,
+) -> Result<Json<OutboundWebhookItem>, StatusCode> {
+    let webhooks = get_webhooks(&state)?;
+    let mut webhook = webhooks.get(&id).await.ok_or(StatusCode::NOT_FOUND)?;
+    if let Some(url) = body.url {
+        webhook.url = url;
+    }
+    if let Some(topics) = body.topics {
+        webhook.topics = topics;
+    }
+    if let Some(secret) = body.secret.filter(|secret| !secret.is_empty()) {
+        webhook.secret = secret;
+    }
+    if body.description.is_some() {
+        webhook.description = body.description;
+    }
+    if let Some(enabled) = body.enabled {
+        webhook.enabled = enabled;
+    }
+    validate(&webhook.url, &webhook.topics)?;
+
+    webhooks
+        .save(webhook.clone())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(webhook.into()))
+}
+
+async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    let webhooks = get_webhooks(&state)?;
+    match webhooks.delete(&id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn list_dead_letters(
+    State(state): State<AppState>,
+    Query(params): Query<DeadLetterParams>,
+) -> Result<Json<Vec<DeadLetter>>, StatusCode> {
+    let webhooks = get_webhooks(&state)?;
+    webhooks
+        .store()
+        .dead_letters(params.webhook_id.as_deref())
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn delete_dead_letter(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, StatusCode> {
+    let webhooks = get_webhooks(&state)?;
+    match webhooks.store().delete_dead_letter(id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn retry_dead_letter(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let webhooks = get_webhooks(&state)?;
+    let letter = webhooks
+        .store()
+        .dead_letter(id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if webhooks.get(&letter.webhook_id).await.is_none() {
+        return Err(StatusCode::GONE);
+    }
+    match webhooks.retry_dead_letter(&letter).await {
+        Ok(()) => Ok(Json(serde_json::json!({"status": "delivered", "id": id}))),
+        Err(error) => Ok(Json(serde_json::json!({
+            "status": "failed",
+            "id": id,
+            "error": error.to_string(),
+        }))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::RwLock;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+    };
+    use clawhive_bus::EventBus;
+    use clawhive_gateway::outbound_webhook::OutboundWebhookStore;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn setup_app() -> (Router, Arc<OutboundWebhooks>, tempfile::TempDir) {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = OutboundWebhookStore::open(&tmp.path().join("data/outbound.db")).unwrap();
+        let webhooks = Arc::new(OutboundWebhooks::new(store).await.unwrap());
+        let state = AppState {
+            root: tmp.path().to_path_buf(),
+            bus: Arc::new(EventBus::new(16)),
+            gateway: None,
+            web_password_hash: Arc::new(RwLock::new(None)),
+            session_store: Arc::new(RwLock::new(HashMap::new())),
+            webhook_deliveries: Arc::new(
+                crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+            ),
+            whatsapp_pairing: Arc::new(RwLock::new(HashMap::new())),
+            pending_openai_oauth: Arc::new(RwLock::new(HashMap::new())),
+            openai_oauth_config: crate::state::default_openai_oauth_config(),
+            enable_openai_oauth_callback_listener: false,
+            daemon_mode: false,
+            port: 3000,
+            schedule_manager: None,
+            reload_coordinator: None,
+            outbound_webhooks: Some(Arc::clone(&webhooks)),
+            memory: None,
+            metrics_bearer_token: None,
+        };
+        let app = Router::new()
+            .nest("/api/webhooks/outbound", router())
+            .with_state(state);
+        (app, webhooks, tmp)
+    }
+
+    fn post_json(uri: &str, body: serde_json::Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn json_body(response: axum::response::Response) -> serde_json::Value {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_list_and_delete_outbound_webhook() {
+        let (app, webhooks, _tmp) = setup_app().await;
+
+        let response = app
+            .clone()
+            .oneshot(post_json(
+                "/api/webhooks/outbound",
+                serde_json::json!({
+                    "url": "https://example.com/clawhive",
+                    "topics": ["ReplyReady", "TaskFailed"],
+                    "description": "ops feed"
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created = json_body(response).await;
+        let id = created["id"].as_str().unwrap().to_string();
+        let secret = created["secret"].as_str().unwrap();
+        assert!(secret.starts_with("whsec_"));
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/webhooks/outbound")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let listed = json_body(response).await;
+        assert_eq!(listed[0]["id"], id.as_str());
+        assert_eq!(listed[0]["topics"][1], "TaskFailed");
+        assert!(listed[0].get("secret").is_none());
+        assert_eq!(listed[0]["secret_masked"], "whsec_...");
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri(format!("/api/webhooks/outbound/{id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(webhooks.list().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_private_urls_and_unknown_topics() {
+        let (app, _webhooks, _tmp) = setup_app().await;
+
+        for body in [
+            serde_json::json!({"url": "http://127.0.0.1:9000/hook", "topics": ["ReplyReady"]}),
+            serde_json::json!({"url": "https://example.com/hook", "topics": ["Bogus"]}),
+            serde_json::json!({"url": "https://example.com/hook", "topics": []}),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(post_json("/api/webhooks/outbound", body))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn dead_letters_list_and_retry_for_removed_webhook() {
+        let (app, webhooks, _tmp) = setup_app().await;
+        let id = webhooks
+            .store()
+            .record_dead_letter("gone-hook", "TaskFailed", "d-1", "{}", 5, "timeout")
+            .await
+            .unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/webhooks/outbound/dead-letters?webhook_id=gone-hook")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let letters = json_body(response).await;
+        assert_eq!(letters[0]["id"], id);
+        assert_eq!(letters[0]["attempts"], 5);
+
+        let response = app
+            .clone()
+            .oneshot(post_json(
+                &format!("/api/webhooks/outbound/dead-letters/{id}/retry"),
+                serde_json::json!({}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GONE);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri(format!("/api/webhooks/outbound/dead-letters/{id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+}
@@ -390,6 +390,9 @@ mod tests {
                 gateway: None,
                 web_password_hash: Arc::new(std::sync::RwLock::new(None)),
                 session_store: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+                webhook_deliveries: Arc::new(
+                    crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+                ),
                 whatsapp_pairing: Arc::new(
                     std::sync::RwLock::new(std::collections::HashMap::new()),
                 ),
@@ -402,6 +405,9 @@ mod tests {
                 port: 3000,
                 schedule_manager: None,
                 reload_coordinator: None,
+                outbound_webhooks: None,
+                memory: None,
+                metrics_bearer_token: None,
             },
             tmp,
         )
@@ -0,0 +1,224 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use clawhive_core::router::{budget_report, BudgetStatus};
+use clawhive_memory::usage::{UsageFilter, UsageGroupBy, UsageLedger, UsageRecord, UsageSummary};
+use serde::{Deserialize, Serialize};
+
+use crate::state::AppState;
+
+const MAX_RECENT: usize = 500;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UsageQuery {
+    /// Inclusive lower bound, `YYYY-MM-DD` or RFC 3339.
+    pub since: Option<String>,
+    /// Exclusive upper bound, `YYYY-MM-DD` or RFC 3339.
+    pub until: Option<String>,
+    pub agent: Option<String>,
+    pub session: Option<String>,
+    pub user: Option<String>,
+    /// agent | session | user | channel | provider | model | purpose | day
+    pub by: Option<String>,
+    /// Number of individual calls to include, newest first.
+    pub recent: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct UsageReport {
+    pub group_by: UsageGroupBy,
+    pub rows: Vec<UsageSummary>,
+    pub total: UsageSummary,
+    pub recent: Vec<UsageRecord>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_usage))
+        .route("/budgets", get(get_budgets))
+}
+
+async fn get_usage(
+    State(state): State<AppState>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<UsageReport>, (StatusCode, String)> {
+    let group_by: UsageGroupBy = match query.by.as_deref() {
+        Some(by) => by
+            .parse()
+            .map_err(|e: anyhow::Error| (StatusCode::BAD_REQUEST, e.to_string()))?,
+        None => UsageGroupBy::default(),
+    };
+    let filter = UsageFilter {
+        since: query.since,
+        until: query.until,
+        agent_id: query.agent,
+        session_key: query.session,
+        user_scope: query.user,
+    };
+
+    let Some(memory) = &state.memory else {
+        return Ok(Json(UsageReport {
+            group_by,
+            rows: Vec::new(),
+            total: UsageSummary {
+                key: "total".to_string(),
+                ..Default::default()
+            },
+            recent: Vec::new(),
+        }));
+    };
+
+    let internal = |e: &dyn std::fmt::Display| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let ledger = UsageLedger::new(memory.db());
+    let rows = ledger
+        .summarize(&filter, group_by)
+        .await
+        .map_err(|e| internal(&e))?;
+    let total = ledger.total(&filter).await.map_err(|e| internal(&e))?;
+    let recent = match query.recent.unwrap_or(0).min(MAX_RECENT) {
+        0 => Vec::new(),
+        limit => ledger
+            .list_recent(&filter, limit)
+            .await
+            .map_err(|e| internal(&e))?,
+    };
+
+    Ok(Json(UsageReport {
+        group_by,
+        rows,
+        total,
+        recent,
+    }))
+}
+
+/// Budgets the running gateway enforces, with their spend in the current
+/// period. Empty when no gateway is attached.
+async fn get_budgets(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<BudgetStatus>>, (StatusCode, String)> {
+    let Some(view) = state.config_view() else {
+        return Ok(Json(Vec::new()));
+    };
+    let (Some(ledger), Some(guard)) = (view.router.usage_ledger(), view.router.budgets()) else {
+        return Ok(Json(Vec::new()));
+    };
+    let report = budget_report(ledger, guard.policies())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, RwLock};
+
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use clawhive_bus::EventBus;
+    use clawhive_memory::usage::UsagePurpose;
+    use clawhive_memory::MemoryStore;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn test_state(dir: &std::path::Path) -> AppState {
+        AppState {
+            root: dir.to_path_buf(),
+            bus: Arc::new(EventBus::new(16)),
+            gateway: None,
+            web_password_hash: Arc::new(RwLock::new(None)),
+            session_store: Arc::new(RwLock::new(HashMap::new())),
+            webhook_deliveries: Arc::new(
+                crate::webhook_deliveries::WebhookDeliveries::in_memory().unwrap(),
+            ),
+            whatsapp_pairing: Arc::new(RwLock::new(HashMap::new())),
+            pending_openai_oauth: Arc::new(RwLock::new(HashMap::new())),
+            openai_oauth_config: crate::state::default_openai_oauth_config(),
+            enable_openai_oauth_callback_listener: false,
+            daemon_mode: false,
+            port: 8848,
+            schedule_manager: None,
+            reload_coordinator: None,
+            outbound_webhooks: None,
+            memory: None,
+            metrics_bearer_token: None,
+        }
+    }
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let resp = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = resp.status();
+        let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let value = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+        (status, value)
+    }
+
+    #[tokio::test]
+    async fn usage_groups_recorded_calls() {
+        let tmp = tempfile::tempdir().unwrap();
+        let memory = Arc::new(MemoryStore::open_in_memory().unwrap());
+        let ledger = UsageLedger::new(memory.db());
+        for (model, cost) in [("claude-sonnet-4-6", Some(0.2)), ("claude-haiku-4-5", None)] {
+            ledger
+                .record(UsageRecord {
+                    agent_id: "main".into(),
+                    session_key: Some("telegram:tg:chat:1:user:1".into()),
+                    provider: "anthropic".into(),
+                    model: model.into(),
+                    purpose: UsagePurpose::Turn.as_str().into(),
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    cost_usd: cost,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        let mut state = test_state(tmp.path());
+        state.memory = Some(memory);
+        let app = router().with_state(state);
+        let (status, body) = get_json(app, "/?by=model&recent=1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["group_by"], "model");
+        assert_eq!(body["rows"][0]["key"], "anthropic/claude-sonnet-4-6");
+        assert_eq!(body["total"]["calls"], 2);
+        assert_eq!(body["total"]["unpriced_calls"], 1);
+        assert_eq!(body["recent"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn budgets_are_empty_without_a_gateway() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("config")).unwrap();
+        std::fs::write(
+            tmp.path().join("config/main.yaml"),
+            "budgets:\n  - scope: global\n    max_usd: 1.0\n",
+        )
+        .unwrap();
+
+        let app = router().with_state(test_state(tmp.path()));
+        let (status, body) = get_json(app, "/budgets").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn usage_rejects_unknown_grouping_and_tolerates_missing_store() {
+        let tmp = tempfile::tempdir().unwrap();
+        let app = router().with_state(test_state(tmp.path()));
+        let (status, body) = get_json(app.clone(), "/").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"]["calls"], 0);
+
+        let (status, _) = get_json(app, "/?by=colour").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -5,9 +5,13 @@ use std::time::Instant;
 
 use clawhive_auth::oauth::{OpenAiOAuthConfig, OPENAI_OAUTH_CLIENT_ID};
 use clawhive_core::config_view::ConfigView;
+use clawhive_gateway::outbound_webhook::OutboundWebhooks;
 use clawhive_gateway::{Gateway, ReloadCoordinator};
+use clawhive_memory::MemoryStore;
 use clawhive_scheduler::ScheduleManager;
 
+use crate::webhook_deliveries::WebhookDeliveries;
+
 #[derive(Debug, Clone)]
 pub struct PendingOpenAiOAuth {
     pub expected_state: String,
@@ -42,6 +46,8 @@ pub struct AppState {
     pub gateway: Option<Arc<Gateway>>,
     pub web_password_hash: Arc<RwLock<Option<String>>>,
     pub session_store: Arc<RwLock<HashMap<String, Instant>>>,
+    /// Webhook delivery ids seen recently, keyed `{source_id}:{delivery_id}`.
+    pub webhook_deliveries: Arc<WebhookDeliveries>,
     pub whatsapp_pairing: Arc<RwLock<HashMap<String, WhatsAppPairSession>>>,
     pub pending_openai_oauth: Arc<RwLock<HashMap<String, PendingOpenAiOAuth>>>,
     pub openai_oauth_config: OpenAiOAuthConfig,
@@ -53,6 +59,12 @@ pub struct AppState {
     /// Shared schedule manager for schedule API routes.
     pub schedule_manager: Option<Arc<ScheduleManager>>,
     pub reload_coordinator: Option<Arc<ReloadCoordinator>>,
+    /// Outbound webhook subscriptions and their dead letters.
+    pub outbound_webhooks: Option<Arc<OutboundWebhooks>>,
+    /// Shared memory database for the memory, usage and metrics routes.
+    pub memory: Option<Arc<MemoryStore>>,
+    /// `metrics.bearer_token` from `main.yaml`, read at startup.
+    pub metrics_bearer_token: Option<String>,
 }
 
 impl AppState {
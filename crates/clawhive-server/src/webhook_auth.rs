@@ -1,4 +1,7 @@
 use axum::http::HeaderMap;
+use base64::Engine as _;
+use clawhive_core::config::{WebhookAuthConfig, WebhookSourceConfig};
+use hmac::{Hmac, Mac};
 use rand::{rngs::OsRng, Rng};
 use sha2::{Digest, Sha256};
 use subtle::ConstantTimeEq;
@@ -6,6 +9,7 @@ use subtle::ConstantTimeEq;
 const KEY_PREFIX: &str = "whk_";
 const KEY_RANDOM_BYTES: usize = 32;
 const BASE62: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const DEFAULT_TOLERANCE_SECS: u64 = 300;
 
 /// Generate a new API key: `whk_` + 32 crypto-random Base62 chars.
 pub fn generate_api_key() -> String {
@@ -64,15 +68,190 @@ pub fn extract_api_key(headers: &HeaderMap) -> Option<String> {
         .map(ToString::to_string)
 }
 
+/// Check a webhook request against its source's auth method. `now` is the
+/// current unix time, for the `timestamped_hmac` replay window.
+pub fn verify_request(
+    auth: &WebhookAuthConfig,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: i64,
+) -> bool {
+    match auth.method.as_str() {
+        "api_key" => {
+            let Some(provided_key) = extract_api_key(headers) else {
+                return false;
+            };
+            auth.key_hash
+                .as_deref()
+                .or(auth.key.as_deref())
+                .is_some_and(|stored| verify_api_key(&provided_key, stored))
+        }
+        "github" => verify_hmac(auth, headers, "x-hub-signature-256", "sha256=", &[body]),
+        "hmac_sha256" => verify_hmac(auth, headers, "x-signature", "", &[body]),
+        "timestamped_hmac" => {
+            let Some(timestamp) = header_str(
+                headers,
+                auth.timestamp_header.as_deref().unwrap_or("x-timestamp"),
+            ) else {
+                return false;
+            };
+            let Ok(sent_at) = timestamp.trim().parse::<i64>() else {
+                return false;
+            };
+            let tolerance = auth.tolerance_secs.unwrap_or(DEFAULT_TOLERANCE_SECS);
+            if now.abs_diff(sent_at) > tolerance {
+                return false;
+            }
+            let template = auth
+                .signed_payload
+                .as_deref()
+                .unwrap_or("{timestamp}.{body}");
+            let Some((before, after)) = template.split_once("{body}") else {
+                return false;
+            };
+            let before = before.replace("{timestamp}", timestamp);
+            let after = after.replace("{timestamp}", timestamp);
+            verify_hmac(
+                auth,
+                headers,
+                "x-signature",
+                "",
+                &[before.as_bytes(), body, after.as_bytes()],
+            )
+        }
+        "stripe" => verify_stripe(auth, headers, body, now),
+        _ => false,
+    }
+}
+
+/// HMAC-SHA256 over `parts` with the source secret, compared in constant time
+/// against the signature header.
+fn verify_hmac(
+    auth: &WebhookAuthConfig,
+    headers: &HeaderMap,
+    default_header: &str,
+    default_prefix: &str,
+    parts: &[&[u8]],
+) -> bool {
+    let Some(secret) = auth.secret.as_deref().filter(|s| !s.is_empty()) else {
+        return false;
+    };
+    let header = auth.signature_header.as_deref().unwrap_or(default_header);
+    let prefix = auth.signature_prefix.as_deref().unwrap_or(default_prefix);
+    let Some(signature) = header_str(headers, header).and_then(|v| v.trim().strip_prefix(prefix))
+    else {
+        return false;
+    };
+    let expected = match auth.signature_encoding.as_deref().unwrap_or("hex") {
+        "hex" => hex::decode(signature).ok(),
+        "base64" => base64::engine::general_purpose::STANDARD
+            .decode(signature)
+            .ok(),
+        _ => None,
+    };
+    expected.is_some_and(|expected| hmac_matches(secret, parts, &expected))
+}
+
+/// Stripe-style: one header `t=<unix>,v1=<hex>`, signed over `{t}.{body}`.
+/// Any of several `v1` entries may match, as while a secret is rotated.
+fn verify_stripe(auth: &WebhookAuthConfig, headers: &HeaderMap, body: &[u8], now: i64) -> bool {
+    let Some(secret) = auth.secret.as_deref().filter(|s| !s.is_empty()) else {
+        return false;
+    };
+    let header = auth
+        .signature_header
+        .as_deref()
+        .unwrap_or("stripe-signature");
+    let Some(value) = header_str(headers, header) else {
+        return false;
+    };
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for item in value.split(',') {
+        match item.trim().split_once('=') {
+            Some(("t", t)) => timestamp = Some(t),
+            Some(("v1", signature)) => signatures.extend(hex::decode(signature).ok()),
+            _ => {}
+        }
+    }
+    let Some(timestamp) = timestamp else {
+        return false;
+    };
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    let tolerance = auth.tolerance_secs.unwrap_or(DEFAULT_TOLERANCE_SECS);
+    if now.abs_diff(sent_at) > tolerance {
+        return false;
+    }
+    let parts: [&[u8]; 3] = [timestamp.as_bytes(), b".", body];
+    signatures
+        .iter()
+        .any(|signature| hmac_matches(secret, &parts, signature))
+}
+
+/// HMAC-SHA256 over `parts`, compared in constant time against `expected`.
+fn hmac_matches(secret: &str, parts: &[&[u8]], expected: &[u8]) -> bool {
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    for part in parts {
+        mac.update(part);
+    }
+    mac.verify_slice(expected).is_ok()
+}
+
+/// The sender's delivery id, used to drop retried deliveries.
+pub fn delivery_id(source: &WebhookSourceConfig, headers: &HeaderMap) -> Option<String> {
+    let header = source
+        .delivery_id_header
+        .as_deref()
+        .or(match source.auth.method.as_str() {
+            "github" => Some("x-github-delivery"),
+            _ => None,
+        })?;
+    header_str(headers, header)
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(ToString::to_string)
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
 #[cfg(test)]
 mod tests {
     use axum::http::{HeaderMap, HeaderValue};
 
+    use base64::Engine as _;
+    use clawhive_core::config::{WebhookAuthConfig, WebhookSourceConfig};
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
     use super::{
-        extract_api_key, generate_api_key, hash_api_key, verify_api_key, KEY_PREFIX,
-        KEY_RANDOM_BYTES,
+        delivery_id, extract_api_key, generate_api_key, hash_api_key, verify_api_key,
+        verify_request, KEY_PREFIX, KEY_RANDOM_BYTES,
     };
 
+    fn sign(secret: &str, payload: &[u8]) -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(payload);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn auth(yaml: &str) -> WebhookAuthConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
     #[test]
     fn generate_api_key_has_whk_prefix() {
         let key = generate_api_key();
@@ -152,4 +331,135 @@ mod tests {
 
         assert_eq!(extract_api_key(&headers), None);
     }
+
+    #[test]
+    fn github_signature_is_verified_against_raw_body() {
+        let config = auth("method: github\nsecret: gh-secret\n");
+        let body = br#"{"action":"opened"}"#;
+        let signature = format!("sha256={}", hex::encode(sign("gh-secret", body)));
+        let signed = headers(&[("x-hub-signature-256", signature)]);
+
+        assert!(verify_request(&config, &signed, body, 0));
+        assert!(!verify_request(
+            &config,
+            &signed,
+            br#"{"action":"closed"}"#,
+            0
+        ));
+        assert!(!verify_request(&config, &HeaderMap::new(), body, 0));
+
+        let wrong_secret = auth("method: github\nsecret: other\n");
+        assert!(!verify_request(&wrong_secret, &signed, body, 0));
+    }
+
+    #[test]
+    fn generic_hmac_uses_configured_header_prefix_and_encoding() {
+        let config = auth(
+            "method: hmac_sha256\nsecret: s3cret\nsignature_header: X-Shopify-Hmac-Sha256\nsignature_encoding: base64\n",
+        );
+        let body = b"payload";
+        let signature = base64::engine::general_purpose::STANDARD.encode(sign("s3cret", body));
+        assert!(verify_request(
+            &config,
+            &headers(&[("x-shopify-hmac-sha256", signature.clone())]),
+            body,
+            0
+        ));
+        assert!(!verify_request(
+            &config,
+            &headers(&[("x-signature", signature)]),
+            body,
+            0
+        ));
+
+        let prefixed = auth("method: hmac_sha256\nsecret: s3cret\nsignature_prefix: \"sha256=\"\n");
+        let hex_signature = format!("sha256={}", hex::encode(sign("s3cret", body)));
+        assert!(verify_request(
+            &prefixed,
+            &headers(&[("x-signature", hex_signature)]),
+            body,
+            0
+        ));
+    }
+
+    #[test]
+    fn stripe_signature_header_carries_timestamp_and_signatures() {
+        let config = auth("method: stripe\nsecret: whsec_test\n");
+        let body = br#"{"id":"evt_1"}"#;
+        let valid = hex::encode(sign("whsec_test", br#"1700000000.{"id":"evt_1"}"#));
+        let rotated = hex::encode(sign("whsec_old", br#"1700000000.{"id":"evt_1"}"#));
+        let signed = headers(&[(
+            "stripe-signature",
+            format!("t=1700000000,v1={rotated},v1={valid},v0=ignored"),
+        )]);
+
+        assert!(verify_request(&config, &signed, body, 1_700_000_100));
+        assert!(!verify_request(&config, &signed, body, 1_700_000_400));
+        assert!(!verify_request(&config, &signed, b"{}", 1_700_000_100));
+        let unsigned = headers(&[("stripe-signature", "t=1700000000".to_string())]);
+        assert!(!verify_request(&config, &unsigned, body, 1_700_000_100));
+    }
+
+    #[test]
+    fn timestamped_hmac_enforces_replay_window() {
+        // Slack-style: v0=hex(HMAC("v0:{ts}:{body}")).
+        let config = auth(
+            "method: timestamped_hmac\nsecret: slack\nsignature_header: X-Slack-Signature\nsignature_prefix: v0=\ntimestamp_header: X-Slack-Request-Timestamp\nsigned_payload: \"v0:{timestamp}:{body}\"\ntolerance_secs: 60\n",
+        );
+        let body = b"token=abc";
+        let signature = format!(
+            "v0={}",
+            hex::encode(sign("slack", b"v0:1700000000:token=abc"))
+        );
+        let signed = headers(&[
+            ("x-slack-signature", signature),
+            ("x-slack-request-timestamp", "1700000000".to_string()),
+        ]);
+
+        assert!(verify_request(&config, &signed, body, 1_700_000_030));
+        assert!(!verify_request(&config, &signed, body, 1_700_000_061));
+        assert!(!verify_request(
+            &config,
+            &signed,
+            b"token=xyz",
+            1_700_000_030
+        ));
+
+        let default_format = auth("method: timestamped_hmac\nsecret: k\n");
+        let signature = hex::encode(sign("k", b"42.{}"));
+        let signed = headers(&[
+            ("x-signature", signature),
+            ("x-timestamp", "42".to_string()),
+        ]);
+        assert!(verify_request(&default_format, &signed, b"{}", 300));
+        assert!(!verify_request(&default_format, &signed, b"{}", 343));
+    }
+
+    #[test]
+    fn api_key_method_and_unknown_methods() {
+        let config = auth("method: api_key\nkey: whk_abc123\n");
+        let bearer = headers(&[("authorization", "Bearer whk_abc123".to_string())]);
+        assert!(verify_request(&config, &bearer, b"{}", 0));
+
+        let unknown = auth("method: mtls\nkey: whk_abc123\n");
+        assert!(!verify_request(&unknown, &bearer, b"{}", 0));
+    }
+
+    #[test]
+    fn delivery_id_defaults_to_github_header() {
+        let github: WebhookSourceConfig =
+            serde_yaml::from_str("source_id: gh\nauth:\n  method: github\n").unwrap();
+        let custom: WebhookSourceConfig = serde_yaml::from_str(
+            "source_id: ci\ndelivery_id_header: Idempotency-Key\nauth:\n  method: api_key\n",
+        )
+        .unwrap();
+        let request = headers(&[
+            ("x-github-delivery", "d-1".to_string()),
+            ("idempotency-key", "k-9".to_string()),
+        ]);
+
+        assert_eq!(delivery_id(&github, &request).as_deref(), Some("d-1"));
+        assert_eq!(delivery_id(&custom, &request).as_deref(), Some("k-9"));
+        assert_eq!(delivery_id(&github, &HeaderMap::new()), None);
+    }
 }
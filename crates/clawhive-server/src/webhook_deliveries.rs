@@ -0,0 +1,130 @@
+//! Delivery ids of inbound webhooks, remembered so retried deliveries are
+//! dropped. Kept in SQLite so a restart does not forget them.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+const SCHEMA: &str = r#"CREATE TABLE IF NOT EXISTS webhook_deliveries (
+       key TEXT PRIMARY KEY,
+       seen_at INTEGER NOT NULL
+   );
+   CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_seen_at
+       ON webhook_deliveries(seen_at);"#;
+
+#[derive(Clone)]
+pub struct WebhookDeliveries {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl WebhookDeliveries {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;\nPRAGMA synchronous=NORMAL;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// A log that lives as long as the process, for tests.
+    pub fn in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Record `key`; false if it was already recorded within `ttl`. Expired
+    /// ids are dropped on the way, through the `seen_at` index.
+    pub async fn claim(&self, key: &str, ttl: Duration) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let key = key.to_owned();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|_| anyhow!("failed to lock webhook delivery log"))?;
+            let now = Utc::now().timestamp();
+            let cutoff = now.saturating_sub(i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX));
+            conn.execute(
+                "DELETE FROM webhook_deliveries WHERE seen_at <= ?1",
+                params![cutoff],
+            )?;
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO webhook_deliveries (key, seen_at) VALUES (?1, ?2)",
+                params![key, now],
+            )?;
+            Ok(inserted == 1)
+        })
+        .await?
+    }
+
+    /// Forget `key`, so a retry of a delivery that failed is processed.
+    pub async fn release(&self, key: &str) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let key = key.to_owned();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|_| anyhow!("failed to lock webhook delivery log"))?;
+            conn.execute(
+                "DELETE FROM webhook_deliveries WHERE key = ?1",
+                params![key],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn claims_once_until_released() {
+        let deliveries = WebhookDeliveries::in_memory().unwrap();
+        assert!(deliveries.claim("github:abc", TTL).await.unwrap());
+        assert!(!deliveries.claim("github:abc", TTL).await.unwrap());
+        assert!(deliveries.claim("github:def", TTL).await.unwrap());
+
+        deliveries.release("github:abc").await.unwrap();
+        assert!(deliveries.claim("github:abc", TTL).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn expired_claims_are_forgotten() {
+        let deliveries = WebhookDeliveries::in_memory().unwrap();
+        assert!(deliveries.claim("github:abc", TTL).await.unwrap());
+        assert!(deliveries
+            .claim("github:abc", Duration::ZERO)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn claims_survive_reopening() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("data/webhook_deliveries.db");
+        assert!(WebhookDeliveries::open(&path)
+            .unwrap()
+            .claim("github:abc", TTL)
+            .await
+            .unwrap());
+        assert!(!WebhookDeliveries::open(&path)
+            .unwrap()
+            .claim("github:abc", TTL)
+            .await
+            .unwrap());
+    }
+}
@@ -230,7 +230,7 @@ impl CodeApp {
                     self.history_scroll.ensure_bottom(self.history.len(), 20);
                 }
             }
-            BusMessage::ReplyReady { outbound }
+            BusMessage::ReplyReady { outbound, .. }
                 if outbound.channel_type == "code" && outbound.connector_id == connector_id =>
             {
                 if let Some(HistoryCell::AssistantText { is_streaming, .. }) =
@@ -247,7 +247,9 @@ impl CodeApp {
                 self.is_running = false;
                 self.history_scroll.ensure_bottom(self.history.len(), 20);
             }
-            BusMessage::TaskFailed { trace_id, error } => {
+            BusMessage::TaskFailed {
+                trace_id, error, ..
+            } => {
                 self.history.push(HistoryCell::Error {
                     trace_id,
                     message: error,
@@ -947,6 +949,7 @@ mod tests {
         let mut app = CodeApp::new("agent".to_string(), "model".to_string());
         app.handle_bus_message(
             BusMessage::StreamDelta {
+                agent_id: None,
                 trace_id: uuid::Uuid::new_v4(),
                 delta: "hello".to_string(),
                 is_final: false,
@@ -1064,6 +1067,7 @@ mod tests {
         let trace = uuid::Uuid::new_v4();
         app.handle_bus_message(
             BusMessage::StreamDelta {
+                agent_id: None,
                 trace_id: trace,
                 delta: "hello ".into(),
                 is_final: false,
@@ -1072,6 +1076,7 @@ mod tests {
         );
         app.handle_bus_message(
             BusMessage::StreamDelta {
+                agent_id: None,
                 trace_id: trace,
                 delta: "world".into(),
                 is_final: false,
@@ -1092,6 +1097,7 @@ mod tests {
 
         app.handle_bus_message(
             BusMessage::StreamDelta {
+                agent_id: None,
                 trace_id: uuid::Uuid::new_v4(),
                 delta: "done".into(),
                 is_final: false,
@@ -1100,6 +1106,7 @@ mod tests {
         );
         app.handle_bus_message(
             BusMessage::StreamDelta {
+                agent_id: None,
                 trace_id: uuid::Uuid::new_v4(),
                 delta: String::new(),
                 is_final: true,
@@ -1117,6 +1124,7 @@ mod tests {
 
         app.handle_bus_message(
             BusMessage::TaskFailed {
+                agent_id: None,
                 trace_id: uuid::Uuid::new_v4(),
                 error: "boom".into(),
             },
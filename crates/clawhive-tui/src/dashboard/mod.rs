@@ -259,7 +259,7 @@ impl App {
                     &trace_id.to_string()[..8]
                 ));
             }
-            BusMessage::ReplyReady { ref outbound } => {
+            BusMessage::ReplyReady { ref outbound, .. } => {
                 let preview: String = outbound.text.chars().take(60).collect();
                 self.push_event(format!(
                     "[{ts}] ReplyReady trace={}",
@@ -274,6 +274,7 @@ impl App {
             BusMessage::TaskFailed {
                 trace_id,
                 ref error,
+                ..
             } => {
                 self.push_event(format!(
                     "[{ts}] TaskFailed trace={}",
@@ -356,6 +357,7 @@ impl App {
                 trace_id,
                 ref delta,
                 is_final,
+                ..
             } => {
                 if is_final {
                     self.push_event(format!(
@@ -414,6 +416,15 @@ impl App {
                 self.push_event(format!("[{ts}] WaitTask {task_id}: {status}"));
                 self.push_log(format!("[{ts}] WaitTask completed: {message}"));
             }
+            BusMessage::DelegateTaskCompleted {
+                ref run_id,
+                ref agent_id,
+                ref status,
+                ..
+            } => {
+                self.push_event(format!("[{ts}] DelegateTask {agent_id}: {status}"));
+                self.push_log(format!("[{ts}] Delegated run {run_id} {status}"));
+            }
             BusMessage::ActionReady { ref action } => {
                 self.push_event(format!("[{ts}] ActionReady: {:?}", action.action));
             }
@@ -894,7 +905,10 @@ mod tests {
             attachments: vec![],
         };
 
-        app.handle_bus_message(BusMessage::ReplyReady { outbound });
+        app.handle_bus_message(BusMessage::ReplyReady {
+            agent_id: None,
+            outbound,
+        });
 
         assert_ne!(app.agent_runs, vec!["No running agents".to_string()]);
         assert!(app
@@ -0,0 +1,62 @@
+import { Badge } from "@/components/ui/badge";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Wallet } from "lucide-react";
+import { cn } from "@/lib/utils";
+import { useBudgets, type BudgetStatus } from "@/hooks/use-api";
+
+function usedFraction(b: BudgetStatus): number {
+  const fractions = [
+    b.max_usd ? b.spent_usd / b.max_usd : 0,
+    b.max_tokens ? b.spent_tokens / b.max_tokens : 0,
+  ];
+  return Math.min(1, Math.max(...fractions));
+}
+
+function describeSpend(b: BudgetStatus): string {
+  const parts: string[] = [];
+  if (b.max_usd != null) parts.push(`$${b.spent_usd.toFixed(2)} / $${b.max_usd.toFixed(2)}`);
+  if (b.max_tokens != null) parts.push(`${b.spent_tokens.toLocaleString()} / ${b.max_tokens.toLocaleString()} tokens`);
+  return parts.join(" · ");
+}
+
+function BudgetState({ budget }: { budget: BudgetStatus }) {
+  if (budget.overridden) return <Badge variant="secondary">approved</Badge>;
+  if (budget.exhausted) return <Badge variant="destructive">{budget.on_exhaust}</Badge>;
+  return <Badge variant="outline">ok</Badge>;
+}
+
+export function Budgets() {
+  const { data: budgets } = useBudgets();
+  if (!budgets || budgets.length === 0) return null;
+
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between space-y-0 pb-2">
+        <CardTitle className="text-sm font-medium">Budgets</CardTitle>
+        <Wallet className="h-4 w-4 text-muted-foreground" />
+      </CardHeader>
+      <CardContent className="grid gap-3">
+        {budgets.map((b) => {
+          const used = usedFraction(b);
+          return (
+            <div key={b.budget} className="grid gap-1">
+              <div className="flex items-center justify-between text-sm">
+                <span className="font-mono">{b.budget}</span>
+                <BudgetState budget={b} />
+              </div>
+              <div className="h-2 w-full rounded-full bg-muted">
+                <div
+                  className={cn("h-2 rounded-full", b.exhausted && !b.overridden ? "bg-destructive" : "bg-primary")}
+                  style={{ width: `${used * 100}%` }}
+                />
+              </div>
+              <p className="text-xs text-muted-foreground">
+                {describeSpend(b)} since {b.period_start}
+              </p>
+            </div>
+          );
+        })}
+      </CardContent>
+    </Card>
+  );
+}
@@ -87,6 +87,21 @@ export interface Metrics {
   channels_total: number;
 }
 
+export interface BudgetStatus {
+  budget: string;
+  scope: "global" | "agent" | "user";
+  subject: string | null;
+  period: "daily" | "monthly";
+  period_start: string;
+  max_usd: number | null;
+  max_tokens: number | null;
+  spent_usd: number;
+  spent_tokens: number;
+  on_exhaust: "refuse" | "downgrade" | "approve";
+  exhausted: boolean;
+  overridden: boolean;
+}
+
 export interface WebSearchConfig {
   enabled: boolean;
   provider: string | null;
@@ -437,6 +452,10 @@ export function useMetrics() {
   return useQuery({ queryKey: ["metrics"], queryFn: () => apiFetch<Metrics>("/api/events/metrics"), refetchInterval: 10000 });
 }
 
+export function useBudgets() {
+  return useQuery({ queryKey: ["budgets"], queryFn: () => apiFetch<BudgetStatus[]>("/api/usage/budgets"), refetchInterval: 30000 });
+}
+
 export function useSchedules() {
   return useQuery({
     queryKey: ["schedules"],
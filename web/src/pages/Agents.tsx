@@ -500,6 +500,8 @@ function AgentDetailDialog({
                       </SelectTrigger>
                       <SelectContent>
                         <SelectItem value="all">all</SelectItem>
+                        <SelectItem value="user">user</SelectItem>
+                        <SelectItem value="conversation">conversation</SelectItem>
                         <SelectItem value="none">none</SelectItem>
                       </SelectContent>
                     </Select>
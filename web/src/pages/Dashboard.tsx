@@ -4,6 +4,7 @@ import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Activity, Users, MessageSquare, Server, Radio } from "lucide-react";
 import { useMetrics, useSetupStatus, useSessions, useAgents } from "@/hooks/use-api";
 import { EventStream } from "@/components/dashboard/event-stream";
+import { Budgets } from "@/components/dashboard/budgets";
 import { Skeleton } from "@/components/ui/skeleton";
 import { ErrorState } from "@/components/ui/error-state";
 
@@ -120,6 +121,8 @@ export default function Dashboard() {
         </Card>
       </div>
 
+      <Budgets />
+
       <div className="col-span-full">
         <EventStream />
       </div>